@@ -0,0 +1,857 @@
+//! Author profiles aggregated across sources.
+//!
+//! [`AuthorResolver`] looks a name or ORCID iD up in every source that provides
+//! author profiles (OpenAlex, Semantic Scholar, DBLP, ORCID), groups the records
+//! by person and merges them into an [`AuthorProfile`]. When the records describe
+//! more than one person, the lookup returns [`AuthorLookup::Ambiguous`] with the
+//! candidates instead of merging different people.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use research_master::authors::{AuthorOptions, AuthorResolver};
+//! use research_master::models::AuthorLookup;
+//! use research_master::sources::SourceRegistry;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let registry = SourceRegistry::new();
+//! let resolver = AuthorResolver::new(registry.all().cloned().collect(), AuthorOptions::new());
+//!
+//! match resolver.resolve("0000-0002-1825-0097").await {
+//!     Ok(AuthorLookup::Found { profile }) => println!("{} ({} papers)", profile.name, profile.papers.len()),
+//!     Ok(AuthorLookup::Ambiguous { candidates, .. }) => {
+//!         for candidate in candidates {
+//!             println!("{}: {}", candidate.id, candidate.name);
+//!         }
+//!     }
+//!     Err(e) => eprintln!("{}", e),
+//! }
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures_util::future::join_all;
+
+use crate::models::{
+    normalize_author_name, normalize_orcid, Author, AuthorCandidate, AuthorLookup, AuthorProfile,
+    NameCount, Paper,
+};
+use crate::sources::{Source, SourceError};
+use crate::utils::{deduplicate_papers, DuplicateStrategy};
+
+/// Sources used for author profiles by default, in order of preference
+pub const DEFAULT_AUTHOR_SOURCES: &[&str] = &["orcid", "openalex", "semantic", "dblp"];
+
+/// Same-name records fetched per source when checking an ORCID iD
+const MAX_ORCID_CHECKS: usize = 3;
+
+/// Options for author profile lookups
+#[derive(Debug, Clone)]
+pub struct AuthorOptions {
+    /// Maximum publications fetched per source and kept in the profile
+    pub max_papers: usize,
+
+    /// Maximum candidate records requested from each source for a name
+    pub max_candidates: usize,
+
+    /// Number of top venues and co-authors reported
+    pub top: usize,
+
+    /// Author profile sources to use, in order of preference
+    pub sources: Vec<String>,
+}
+
+impl Default for AuthorOptions {
+    fn default() -> Self {
+        Self {
+            max_papers: 100,
+            max_candidates: 5,
+            top: 10,
+            sources: DEFAULT_AUTHOR_SOURCES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl AuthorOptions {
+    /// Create options with the defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum publications per source and in the profile
+    pub fn max_papers(mut self, max_papers: usize) -> Self {
+        self.max_papers = max_papers;
+        self
+    }
+
+    /// Set the maximum candidate records requested from each source
+    pub fn max_candidates(mut self, max_candidates: usize) -> Self {
+        self.max_candidates = max_candidates.max(1);
+        self
+    }
+
+    /// Set the number of top venues and co-authors reported
+    pub fn top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    /// Set the sources to use, in order of preference
+    pub fn sources(mut self, sources: Vec<String>) -> Self {
+        self.sources = sources;
+        self
+    }
+}
+
+/// Resolves names and ORCID iDs to author profiles
+#[derive(Debug, Clone)]
+pub struct AuthorResolver {
+    sources: Vec<Arc<dyn Source>>,
+    options: AuthorOptions,
+}
+
+impl AuthorResolver {
+    /// Use the author-profile `sources` named in `options.sources`, in that order
+    pub fn new(sources: Vec<Arc<dyn Source>>, options: AuthorOptions) -> Self {
+        let sources = options
+            .sources
+            .iter()
+            .filter_map(|id| {
+                sources
+                    .iter()
+                    .find(|s| s.id() == id && s.supports_author_profiles())
+                    .cloned()
+            })
+            .collect();
+
+        Self { sources, options }
+    }
+
+    /// IDs of the sources in use, in order of preference
+    pub fn source_ids(&self) -> Vec<&str> {
+        self.sources.iter().map(|s| s.id()).collect()
+    }
+
+    /// Resolve `query` to a profile
+    ///
+    /// `query` is an author name, an ORCID iD (bare, `orcid:` or orcid.org URL), or
+    /// the `id` of a candidate from an earlier ambiguous lookup: one or more
+    /// comma-separated `<source>:<author id>` records.
+    pub async fn resolve(&self, query: &str) -> Result<AuthorLookup, SourceError> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(SourceError::InvalidRequest(
+                "Author name or ORCID iD is required".to_string(),
+            ));
+        }
+        if self.sources.is_empty() {
+            return Err(SourceError::InvalidRequest(
+                "None of the selected sources provide author profiles".to_string(),
+            ));
+        }
+
+        if let Some(orcid) = normalize_orcid(query) {
+            let records = self.records_for_orcid(&orcid).await?;
+            return Ok(self.found(records));
+        }
+        if let Some(keys) = self.parse_record_keys(query) {
+            let records = self.records_for_keys(&keys).await?;
+            return Ok(self.found(records));
+        }
+        self.resolve_name(query).await
+    }
+
+    /// Look a name up in every source and merge the records if they describe one person
+    async fn resolve_name(&self, name: &str) -> Result<AuthorLookup, SourceError> {
+        let max = self.options.max_candidates;
+        let results = join_all(self.sources.iter().map(|s| s.search_authors(name, max))).await;
+
+        let mut records = Vec::new();
+        for (source, result) in self.sources.iter().zip(results) {
+            match result {
+                Ok(found) => records.extend(found),
+                Err(e) => tracing::debug!("{} author search failed: {}", source.id(), e),
+            }
+        }
+
+        let mut clusters = cluster_records(name, records);
+        match clusters.len() {
+            0 => Err(SourceError::NotFound(format!(
+                "No author found for '{}'",
+                name
+            ))),
+            1 => {
+                let cluster = clusters.remove(0);
+                let orcid = cluster.iter().find_map(|r| r.orcid.clone());
+                let records = match orcid {
+                    // Records found by ORCID are more reliable than same-name matches
+                    Some(orcid) => {
+                        let mut records = self.records_for_orcid(&orcid).await.unwrap_or_default();
+                        for record in self.fetch_records(&cluster).await {
+                            let same_person = record.orcid.as_deref().is_none_or(|o| o == orcid);
+                            if same_person && !records.iter().any(|r| r.key() == record.key()) {
+                                records.push(record);
+                            }
+                        }
+                        records
+                    }
+                    None => self.fetch_records(&cluster).await,
+                };
+                Ok(self.found(records))
+            }
+            _ => {
+                let mut candidates: Vec<AuthorCandidate> =
+                    clusters.iter().map(|c| candidate(c)).collect();
+                candidates.sort_by(|a, b| {
+                    b.paper_count
+                        .cmp(&a.paper_count)
+                        .then(b.citation_count.cmp(&a.citation_count))
+                });
+                Ok(AuthorLookup::Ambiguous {
+                    query: name.to_string(),
+                    candidates,
+                })
+            }
+        }
+    }
+
+    /// Full records (with publications) for every source that knows `orcid`
+    async fn records_for_orcid(&self, orcid: &str) -> Result<Vec<Author>, SourceError> {
+        let max_papers = self.options.max_papers;
+        let results = join_all(
+            self.sources
+                .iter()
+                .map(|s| s.get_author_by_orcid(orcid, max_papers)),
+        )
+        .await;
+
+        let mut records = Vec::new();
+        let mut fallback = Vec::new();
+        for (source, result) in self.sources.iter().zip(results) {
+            match result {
+                Ok(record) => records.push(record),
+                Err(SourceError::NotImplemented) => fallback.push(Arc::clone(source)),
+                Err(e) => tracing::debug!("{} ORCID lookup failed: {}", source.id(), e),
+            }
+        }
+
+        let Some(name) = records.first().map(|r| r.name.clone()) else {
+            return Err(SourceError::NotFound(format!(
+                "No author found for ORCID {}",
+                orcid
+            )));
+        };
+
+        // Sources without ORCID lookup: check same-name records for the iD
+        let matches = join_all(
+            fallback
+                .iter()
+                .map(|source| self.find_by_name_and_orcid(source.as_ref(), &name, orcid)),
+        )
+        .await;
+        records.extend(matches.into_iter().flatten());
+        Ok(records)
+    }
+
+    /// The record in `source` named `name` whose full profile carries `orcid`
+    async fn find_by_name_and_orcid(
+        &self,
+        source: &dyn Source,
+        name: &str,
+        orcid: &str,
+    ) -> Option<Author> {
+        let wanted = normalize_author_name(name);
+        let candidates = source
+            .search_authors(name, self.options.max_candidates)
+            .await
+            .ok()?;
+
+        for record in candidates
+            .into_iter()
+            .filter(|r| normalize_author_name(&r.name) == wanted)
+            .filter(|r| r.orcid.as_deref().is_none_or(|o| o == orcid))
+            .take(MAX_ORCID_CHECKS)
+        {
+            match source
+                .get_author(&record.author_id, self.options.max_papers)
+                .await
+            {
+                Ok(full) if full.orcid.as_deref() == Some(orcid) => return Some(full),
+                Ok(_) => {}
+                Err(e) => tracing::debug!("{} author lookup failed: {}", source.id(), e),
+            }
+        }
+        None
+    }
+
+    /// Full records for `(source, author id)` pairs
+    async fn records_for_keys(
+        &self,
+        keys: &[(String, String)],
+    ) -> Result<Vec<Author>, SourceError> {
+        let max_papers = self.options.max_papers;
+        let results = join_all(keys.iter().map(|(source_id, author_id)| {
+            let source = self.sources.iter().find(|s| s.id() == source_id);
+            async move {
+                match source {
+                    Some(source) => source.get_author(author_id, max_papers).await,
+                    None => Err(SourceError::InvalidRequest(format!(
+                        "Unknown author source: {}",
+                        source_id
+                    ))),
+                }
+            }
+        }))
+        .await;
+
+        let mut records = Vec::new();
+        for result in results {
+            records.push(result?);
+        }
+        Ok(records)
+    }
+
+    /// Fetch the full record behind each search result, keeping the search result on failure
+    async fn fetch_records(&self, cluster: &[Author]) -> Vec<Author> {
+        let max_papers = self.options.max_papers;
+        let results = join_all(cluster.iter().map(|record| async move {
+            let source = self.sources.iter().find(|s| s.id() == record.source.id());
+            match source {
+                Some(source) => source.get_author(&record.author_id, max_papers).await,
+                None => Err(SourceError::NotImplemented),
+            }
+        }))
+        .await;
+
+        cluster
+            .iter()
+            .zip(results)
+            .map(|(record, result)| match result {
+                Ok(full) => full,
+                Err(e) => {
+                    tracing::debug!("Author lookup for {} failed: {}", record.key(), e);
+                    record.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Parse `<source>:<id>[,<source>:<id>...]` where every source is in use
+    fn parse_record_keys(&self, query: &str) -> Option<Vec<(String, String)>> {
+        query
+            .split(',')
+            .map(|key| {
+                let (source, id) = key.trim().split_once(':')?;
+                let known = self.sources.iter().any(|s| s.id() == source);
+                (known && !id.is_empty()).then(|| (source.to_string(), id.to_string()))
+            })
+            .collect()
+    }
+
+    fn found(&self, records: Vec<Author>) -> AuthorLookup {
+        AuthorLookup::Found {
+            profile: Box::new(merge_records(
+                records,
+                self.options.max_papers,
+                self.options.top,
+            )),
+        }
+    }
+}
+
+/// Group search results into the people they describe
+///
+/// Records sharing an ORCID iD are one person. A record without an ORCID iD is
+/// only merged when its source returned no other record for the name and at most
+/// one person has been found so far; otherwise it stays a separate candidate.
+/// Records whose name differs from `query` are dropped when any record matches it.
+pub fn cluster_records(query: &str, records: Vec<Author>) -> Vec<Vec<Author>> {
+    let wanted = normalize_author_name(query);
+    let matching: Vec<Author> = records
+        .iter()
+        .filter(|r| normalize_author_name(&r.name) == wanted)
+        .cloned()
+        .collect();
+    let records = if matching.is_empty() {
+        records
+    } else {
+        matching
+    };
+
+    let mut clusters: Vec<Vec<Author>> = Vec::new();
+    let mut without_orcid: Vec<Author> = Vec::new();
+    for record in records {
+        match &record.orcid {
+            Some(orcid) => match clusters
+                .iter_mut()
+                .find(|c| c[0].orcid.as_deref() == Some(orcid.as_str()))
+            {
+                Some(cluster) => cluster.push(record),
+                None => clusters.push(vec![record]),
+            },
+            None => without_orcid.push(record),
+        }
+    }
+
+    let mut per_source: HashMap<String, usize> = HashMap::new();
+    for record in &without_orcid {
+        *per_source
+            .entry(record.source.id().to_string())
+            .or_default() += 1;
+    }
+    let (unique, shared): (Vec<Author>, Vec<Author>) = without_orcid
+        .into_iter()
+        .partition(|r| per_source[r.source.id()] == 1);
+
+    for record in unique {
+        match clusters.len() {
+            0 => clusters.push(vec![record]),
+            1 => clusters[0].push(record),
+            _ => clusters.push(vec![record]),
+        }
+    }
+    clusters.extend(shared.into_iter().map(|record| vec![record]));
+
+    clusters
+}
+
+/// Summarize a cluster of records as a candidate the caller can choose
+fn candidate(records: &[Author]) -> AuthorCandidate {
+    let orcid = records.iter().find_map(|r| r.orcid.clone());
+    let keys: Vec<String> = records.iter().map(Author::key).collect();
+
+    let mut merged = Author::new("", display_name(records), records[0].source.clone());
+    for record in records {
+        for affiliation in &record.affiliations {
+            merged.add_affiliation(affiliation);
+        }
+    }
+
+    AuthorCandidate {
+        id: match &orcid {
+            Some(orcid) => format!("orcid:{}", orcid),
+            None => keys.join(","),
+        },
+        name: merged.name,
+        orcid,
+        affiliations: merged.affiliations,
+        paper_count: records.iter().filter_map(|r| r.paper_count).max(),
+        citation_count: records.iter().filter_map(|r| r.citation_count).max(),
+        records: keys,
+    }
+}
+
+/// Merge source records describing one person into a profile
+///
+/// Counts take the largest value any source reports. Publications are
+/// deduplicated across sources and sorted by citations; the h-index is computed
+/// from them when no source reports one.
+pub fn merge_records(records: Vec<Author>, max_papers: usize, top: usize) -> AuthorProfile {
+    let name = display_name(&records);
+    let orcid = records.iter().find_map(|r| r.orcid.clone());
+
+    let mut affiliations = Author::new("", "", records[0].source.clone());
+    for record in &records {
+        for affiliation in &record.affiliations {
+            affiliations.add_affiliation(affiliation);
+        }
+    }
+
+    let papers: Vec<Paper> = records.iter().flat_map(|r| r.papers.clone()).collect();
+    let mut papers = deduplicate_papers(papers, DuplicateStrategy::First);
+    papers.sort_by(|a, b| {
+        b.citations
+            .unwrap_or(0)
+            .cmp(&a.citations.unwrap_or(0))
+            .then(b.year().cmp(&a.year()))
+    });
+    papers.truncate(max_papers);
+
+    let h_index = records
+        .iter()
+        .filter_map(|r| r.h_index)
+        .max()
+        .or_else(|| h_index(&papers));
+
+    let own_names: Vec<String> = records
+        .iter()
+        .map(|r| normalize_author_name(&r.name))
+        .collect();
+    let co_authors = top_names(
+        papers.iter().flat_map(|p| {
+            p.author_list()
+                .into_iter()
+                .filter(|a| !own_names.contains(&normalize_author_name(a)))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        }),
+        top,
+        normalize_author_name,
+    );
+    let top_venues = top_names(
+        papers.iter().filter_map(|p| p.venue().map(str::to_string)),
+        top,
+        |v| v.to_lowercase(),
+    );
+
+    AuthorProfile {
+        name,
+        orcid,
+        affiliations: affiliations.affiliations,
+        paper_count: records.iter().filter_map(|r| r.paper_count).max(),
+        citation_count: records.iter().filter_map(|r| r.citation_count).max(),
+        h_index,
+        top_venues,
+        co_authors,
+        papers,
+        records: records
+            .into_iter()
+            .map(|mut r| {
+                r.papers.clear();
+                r
+            })
+            .collect(),
+    }
+}
+
+/// Name to show for a group of records: ORCID's if present, else the first
+/// record's, without DBLP's homonym number
+fn display_name(records: &[Author]) -> String {
+    let name = records
+        .iter()
+        .find(|r| r.source.id() == "orcid" && !r.name.is_empty())
+        .or_else(|| records.iter().find(|r| !r.name.is_empty()))
+        .map(|r| r.name.as_str())
+        .unwrap_or_default();
+    name.trim_end_matches(|c: char| c.is_ascii_digit())
+        .trim()
+        .to_string()
+}
+
+/// h-index of a publication list (0 when no citation counts are known)
+fn h_index(papers: &[Paper]) -> Option<u32> {
+    let mut citations: Vec<u32> = papers.iter().filter_map(|p| p.citations).collect();
+    if citations.is_empty() {
+        return None;
+    }
+    citations.sort_unstable_by(|a, b| b.cmp(a));
+    let h = citations
+        .iter()
+        .enumerate()
+        .take_while(|(i, c)| **c as usize > *i)
+        .count();
+    Some(h as u32)
+}
+
+/// The `top` most frequent names, grouped by `key` and shown in their first spelling
+fn top_names(
+    names: impl Iterator<Item = String>,
+    top: usize,
+    key: impl Fn(&str) -> String,
+) -> Vec<NameCount> {
+    let mut counts: Vec<NameCount> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for name in names {
+        let k = key(&name);
+        if k.is_empty() {
+            continue;
+        }
+        match index.get(&k) {
+            Some(&i) => counts[i].count += 1,
+            None => {
+                index.insert(k, counts.len());
+                counts.push(NameCount { name, count: 1 });
+            }
+        }
+    }
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    counts.truncate(top);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+    use crate::sources::SourceCapabilities;
+    use async_trait::async_trait;
+
+    fn record(source: SourceType, id: &str, name: &str, orcid: Option<&str>) -> Author {
+        let mut author = Author::new(id, name, source);
+        author.orcid = orcid.map(str::to_string);
+        author
+    }
+
+    fn paper(id: &str, title: &str, authors: &str, venue: &str, citations: u32) -> Paper {
+        PaperBuilder::new(id, title, "", SourceType::OpenAlex)
+            .authors(authors)
+            .citations(citations)
+            .extra("venue", serde_json::Value::String(venue.to_string()))
+            .build()
+    }
+
+    const ORCID_A: &str = "0000-0002-1825-0097";
+    const ORCID_B: &str = "0000-0001-5109-3700";
+    const ORCID_C: &str = "0000-0003-1415-9269";
+
+    #[test]
+    fn test_cluster_by_orcid() {
+        let clusters = cluster_records(
+            "Jane Smith",
+            vec![
+                record(SourceType::OpenAlex, "A1", "Jane Smith", Some(ORCID_A)),
+                record(SourceType::Orcid, ORCID_A, "Jane Smith", Some(ORCID_A)),
+                record(SourceType::DBLP, "12/345", "Jane Smith", None),
+                // Different name, dropped because others match the query
+                record(SourceType::OpenAlex, "A9", "J. Smithson", None),
+            ],
+        );
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].len(), 3);
+    }
+
+    #[test]
+    fn test_cluster_keeps_homonyms_apart() {
+        // Two ORCID iDs are two people
+        let clusters = cluster_records(
+            "Wei Wang",
+            vec![
+                record(SourceType::OpenAlex, "A1", "Wei Wang", Some(ORCID_A)),
+                record(SourceType::OpenAlex, "A2", "Wei Wang", Some(ORCID_B)),
+                record(SourceType::SemanticScholar, "1", "Wei Wang", None),
+            ],
+        );
+        assert_eq!(clusters.len(), 3);
+
+        // A source with two records for the name knows two people
+        let clusters = cluster_records(
+            "Wei Wang",
+            vec![
+                record(SourceType::DBLP, "35/7092-1", "Wei Wang 0001", None),
+                record(SourceType::DBLP, "35/7092-2", "Wei Wang 0002", None),
+            ],
+        );
+        assert_eq!(clusters.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_records() {
+        let mut openalex = record(SourceType::OpenAlex, "A1", "Jane Smith", Some(ORCID_A));
+        openalex.paper_count = Some(3);
+        openalex.citation_count = Some(120);
+        openalex.add_affiliation("MIT");
+        openalex.papers = vec![
+            paper(
+                "1",
+                "Graphs and their many uses",
+                "Jane Smith; Bob Jones",
+                "JMLR",
+                50,
+            ),
+            paper(
+                "2",
+                "Trees and their few uses",
+                "Jane Smith; Bob Jones; Ann Lee",
+                "ICML",
+                5,
+            ),
+            paper("3", "Forests of decision trees", "Jane Smith", "JMLR", 1),
+        ];
+
+        let mut dblp = record(SourceType::DBLP, "12/345", "Jane Smith 0001", None);
+        dblp.paper_count = Some(4);
+        dblp.add_affiliation("mit");
+        dblp.add_affiliation("Stanford University");
+        let mut duplicate = paper("x", "Graphs and their many uses", "Jane Smith", "JMLR", 0);
+        duplicate.source = SourceType::DBLP;
+        dblp.papers = vec![duplicate];
+
+        let profile = merge_records(vec![openalex, dblp], 10, 10);
+        assert_eq!(profile.name, "Jane Smith");
+        assert_eq!(profile.orcid.as_deref(), Some(ORCID_A));
+        assert_eq!(profile.affiliations, vec!["MIT", "Stanford University"]);
+        assert_eq!(profile.paper_count, Some(4));
+        assert_eq!(profile.citation_count, Some(120));
+        assert_eq!(profile.papers.len(), 3);
+        assert_eq!(profile.papers[0].paper_id, "1");
+        // No source reported an h-index: 50, 5, 1 citations give 2
+        assert_eq!(profile.h_index, Some(2));
+        assert_eq!(
+            profile.top_venues[0],
+            NameCount {
+                name: "JMLR".to_string(),
+                count: 2
+            }
+        );
+        assert_eq!(
+            profile.co_authors,
+            vec![
+                NameCount {
+                    name: "Bob Jones".to_string(),
+                    count: 2
+                },
+                NameCount {
+                    name: "Ann Lee".to_string(),
+                    count: 1
+                },
+            ]
+        );
+        assert!(profile.records.iter().all(|r| r.papers.is_empty()));
+    }
+
+    /// Author source that serves fixed records
+    #[derive(Debug)]
+    struct AuthorSource {
+        id: &'static str,
+        records: Vec<Author>,
+        orcid_lookup: bool,
+    }
+
+    #[async_trait]
+    impl Source for AuthorSource {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn name(&self) -> &str {
+            self.id
+        }
+
+        fn capabilities(&self) -> SourceCapabilities {
+            SourceCapabilities::AUTHOR_PROFILES
+        }
+
+        async fn search_authors(
+            &self,
+            name: &str,
+            _max_results: usize,
+        ) -> Result<Vec<Author>, SourceError> {
+            Ok(self
+                .records
+                .iter()
+                .filter(|r| normalize_author_name(&r.name) == normalize_author_name(name))
+                .cloned()
+                .map(|mut r| {
+                    // Search results carry no publications, and DBLP's no ORCID
+                    r.papers.clear();
+                    if !self.orcid_lookup {
+                        r.orcid = None;
+                    }
+                    r
+                })
+                .collect())
+        }
+
+        async fn get_author(
+            &self,
+            author_id: &str,
+            _max_papers: usize,
+        ) -> Result<Author, SourceError> {
+            self.records
+                .iter()
+                .find(|r| r.author_id == author_id)
+                .cloned()
+                .ok_or_else(|| SourceError::NotFound(author_id.to_string()))
+        }
+
+        async fn get_author_by_orcid(
+            &self,
+            orcid: &str,
+            _max_papers: usize,
+        ) -> Result<Author, SourceError> {
+            if !self.orcid_lookup {
+                return Err(SourceError::NotImplemented);
+            }
+            self.records
+                .iter()
+                .find(|r| r.orcid.as_deref() == Some(orcid))
+                .cloned()
+                .ok_or_else(|| SourceError::NotFound(orcid.to_string()))
+        }
+    }
+
+    fn resolver() -> AuthorResolver {
+        let mut jane = record(SourceType::OpenAlex, "A1", "Jane Smith", Some(ORCID_A));
+        jane.papers = vec![paper(
+            "1",
+            "Graphs and their many uses",
+            "Jane Smith",
+            "JMLR",
+            9,
+        )];
+        let wei_b = record(SourceType::OpenAlex, "A2", "Wei Wang", Some(ORCID_B));
+        let wei_c = record(SourceType::OpenAlex, "A3", "Wei Wang", Some(ORCID_C));
+
+        let mut jane_dblp = record(SourceType::DBLP, "12/345", "Jane Smith", Some(ORCID_A));
+        jane_dblp.papers = vec![paper(
+            "x",
+            "Trees and their few uses",
+            "Jane Smith",
+            "ICML",
+            1,
+        )];
+
+        let sources: Vec<Arc<dyn Source>> = vec![
+            Arc::new(AuthorSource {
+                id: "openalex",
+                records: vec![jane, wei_b, wei_c],
+                orcid_lookup: true,
+            }),
+            Arc::new(AuthorSource {
+                id: "dblp",
+                records: vec![jane_dblp],
+                orcid_lookup: false,
+            }),
+        ];
+        AuthorResolver::new(sources, AuthorOptions::new())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_name_merges_sources() {
+        let AuthorLookup::Found { profile } = resolver().resolve("Smith, Jane").await.unwrap()
+        else {
+            panic!("expected a single profile");
+        };
+        assert_eq!(profile.orcid.as_deref(), Some(ORCID_A));
+        assert_eq!(profile.records.len(), 2);
+        assert_eq!(profile.papers.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_orcid_checks_sources_without_lookup() {
+        let AuthorLookup::Found { profile } = resolver().resolve(ORCID_A).await.unwrap() else {
+            panic!("expected a single profile");
+        };
+        let keys: Vec<String> = profile.records.iter().map(Author::key).collect();
+        assert!(keys.contains(&"dblp:12/345".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_ambiguous_name() {
+        let resolver = resolver();
+        let AuthorLookup::Ambiguous { candidates, .. } =
+            resolver.resolve("Wei Wang").await.unwrap()
+        else {
+            panic!("expected candidates");
+        };
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].id, format!("orcid:{}", ORCID_B));
+
+        // Choosing a record key resolves to that person only
+        let AuthorLookup::Found { profile } = resolver.resolve("openalex:A3").await.unwrap() else {
+            panic!("expected a single profile");
+        };
+        assert_eq!(profile.orcid.as_deref(), Some(ORCID_C));
+
+        assert!(matches!(
+            resolver.resolve("Nobody Atall").await,
+            Err(SourceError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,322 @@
+//! Cite keys in LaTeX and Pandoc Markdown manuscripts.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::BibIssue;
+
+/// Commands that match the `\...cite...` pattern but take no cite keys
+const NOT_CITATIONS: &[&str] = &["citestyle", "citesetup", "defcitealias"];
+
+/// One citation of a key in a manuscript
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Citation {
+    /// The cite key
+    pub key: String,
+
+    /// File the citation is in
+    pub file: String,
+
+    /// Line number, starting at 1
+    pub line: usize,
+}
+
+/// Where a cited key stands after syncing the bib file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStatus {
+    /// The bib file already has an up-to-date entry
+    InBib,
+    /// An identifier key was resolved and a new entry written
+    Added,
+    /// An identifier key's entry gained or refreshed fields
+    Updated,
+    /// An identifier key no source knew
+    Unresolved,
+    /// A plain key with no entry in the bib file
+    Missing,
+}
+
+/// One cite key of the manuscript
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CitedKey {
+    /// The cite key
+    pub key: String,
+
+    /// Whether the bib file has it
+    pub status: KeyStatus,
+
+    /// Where the key is cited, as `<file>:<line>`
+    pub locations: Vec<String>,
+
+    /// Fields added or refreshed
+    pub fields: Vec<String>,
+
+    /// Retraction or newer version of the cited paper
+    pub issues: Vec<BibIssue>,
+}
+
+/// What `manuscript` found, one entry per distinct cite key
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct ManuscriptReport {
+    /// Cite keys in order of first citation
+    pub keys: Vec<CitedKey>,
+}
+
+impl ManuscriptReport {
+    /// Number of keys with the given status
+    pub fn count(&self, status: KeyStatus) -> usize {
+        self.keys.iter().filter(|k| k.status == status).count()
+    }
+
+    /// Whether the bib file changed
+    pub fn changed(&self) -> bool {
+        self.keys
+            .iter()
+            .any(|k| matches!(k.status, KeyStatus::Added | KeyStatus::Updated))
+    }
+
+    /// Keys with a retraction or newer version
+    pub fn with_issues(&self) -> impl Iterator<Item = &CitedKey> {
+        self.keys.iter().filter(|k| !k.issues.is_empty())
+    }
+
+    /// One-line summary, e.g. `14 keys cited: 10 in bib, 2 added, 0 updated, 1 unresolved, 1 missing; 1 with issues`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} keys cited: {} in bib, {} added, {} updated, {} unresolved, {} missing; {} with issues",
+            self.keys.len(),
+            self.count(KeyStatus::InBib),
+            self.count(KeyStatus::Added),
+            self.count(KeyStatus::Updated),
+            self.count(KeyStatus::Unresolved),
+            self.count(KeyStatus::Missing),
+            self.with_issues().count()
+        )
+    }
+}
+
+/// Find the citations in a manuscript file
+///
+/// `.tex`/`.ltx` files are read as LaTeX, `.aux` files as the citations
+/// LaTeX wrote for BibTeX or biblatex, and anything else as Pandoc Markdown.
+pub fn scan(file: &str, text: &str) -> Vec<Citation> {
+    let extension = file
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    let keys = match extension.as_str() {
+        "tex" | "ltx" => scan_latex(text),
+        "aux" => scan_aux(text),
+        _ => scan_markdown(text),
+    };
+    keys.into_iter()
+        .map(|(key, line)| Citation {
+            key,
+            file: file.to_string(),
+            line,
+        })
+        .collect()
+}
+
+/// Cite keys grouped by key, in order of first citation
+pub fn group(citations: &[Citation]) -> Vec<(String, Vec<&Citation>)> {
+    let mut index = HashMap::new();
+    let mut grouped: Vec<(String, Vec<&Citation>)> = Vec::new();
+    for citation in citations {
+        let slot = *index.entry(citation.key.as_str()).or_insert_with(|| {
+            grouped.push((citation.key.clone(), Vec::new()));
+            grouped.len() - 1
+        });
+        grouped[slot].1.push(citation);
+    }
+    grouped
+}
+
+/// The ID an identifier-style key resolves to: `doi:10.1038/nature14539`
+/// gives the DOI, `arxiv:1706.03762` the arXiv ID
+pub fn identifier(key: &str) -> Option<String> {
+    let (scheme, id) = key.split_once(':')?;
+    let id = id.trim();
+    if id.is_empty() {
+        return None;
+    }
+    match scheme.to_lowercase().as_str() {
+        "doi" if id.starts_with("10.") => Some(id.to_string()),
+        "arxiv" => Some(format!("arxiv:{}", id)),
+        _ => None,
+    }
+}
+
+/// Keys of `\cite`, `\citep`, `\parencite`, `\nocite` and the other cite
+/// commands, with their line numbers; `%` comments are skipped
+fn scan_latex(text: &str) -> Vec<(String, usize)> {
+    static CITE: OnceLock<Regex> = OnceLock::new();
+    let cite = CITE.get_or_init(|| {
+        Regex::new(r"\\([A-Za-z]*cite[A-Za-z]*)\*?(?:\s*\[[^\]]*\]){0,2}\s*\{([^}]*)\}").unwrap()
+    });
+
+    let text: String = text
+        .lines()
+        .map(strip_comment)
+        .collect::<Vec<_>>()
+        .join("\n");
+    cite.captures_iter(&text)
+        .filter(|c| !NOT_CITATIONS.contains(&&c[1]))
+        .flat_map(|c| {
+            let keys = c.get(2).unwrap();
+            split_keys(&text, keys.start(), keys.as_str())
+        })
+        .collect()
+}
+
+/// Keys of `\citation` (BibTeX) and `\abx@aux@cite` (biblatex) lines
+fn scan_aux(text: &str) -> Vec<(String, usize)> {
+    static CITATION: OnceLock<Regex> = OnceLock::new();
+    let citation = CITATION.get_or_init(|| {
+        Regex::new(r"\\(?:citation|abx@aux@cite(?:\{[^}]*\})?)\{([^}]*)\}").unwrap()
+    });
+    citation
+        .captures_iter(text)
+        .flat_map(|c| {
+            let keys = c.get(1).unwrap();
+            split_keys(text, keys.start(), keys.as_str())
+        })
+        .collect()
+}
+
+/// Pandoc citations, `[@key]`, `[-@key; @other]`, `@key` and `@{key}`,
+/// outside fenced code blocks
+fn scan_markdown(text: &str) -> Vec<(String, usize)> {
+    static CITE: OnceLock<Regex> = OnceLock::new();
+    let cite = CITE.get_or_init(|| {
+        Regex::new(
+            r"(?:^|[\s\[;\-])@(?:\{([^}]+)\}|([A-Za-z0-9_](?:[A-Za-z0-9_]|[:.#$%&\-+?<>~/][A-Za-z0-9_])*))",
+        )
+        .unwrap()
+    });
+
+    let mut keys = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (number, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(marker) if trimmed.starts_with(marker) => fence = None,
+            Some(_) => {}
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => keys.extend(
+                cite.captures_iter(line)
+                    .filter_map(|c| c.get(1).or(c.get(2)))
+                    .map(|key| (key.as_str().trim().to_string(), number + 1)),
+            ),
+        }
+    }
+    keys
+}
+
+/// The line without its `%` comment; `\%` is an escaped percent sign
+fn strip_comment(line: &str) -> &str {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'%' => return &line[..i],
+            _ => i += 1,
+        }
+    }
+    line
+}
+
+/// Comma-separated keys found at byte `start` of `text`, with line numbers
+fn split_keys(text: &str, start: usize, keys: &str) -> Vec<(String, usize)> {
+    let line = text[..start].matches('\n').count() + 1;
+    let mut offset = 0;
+    let mut found = Vec::new();
+    for key in keys.split(',') {
+        let leading = key.len() - key.trim_start().len();
+        let key_line = line + keys[..offset + leading].matches('\n').count();
+        offset += key.len() + 1;
+        let key = key.trim();
+        if !key.is_empty() && key != "*" {
+            found.push((key.to_string(), key_line));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_latex() {
+        let tex = r"Deep nets \citep[see][p.~4]{lecun2015, doi:10.1038/nature14539}.
+% \cite{commented}
+100\% sure \textcite{vaswani2017}\nocite{*}
+\citestyle{plain}\parencite*{a,
+  b}
+";
+        let citations = scan("paper.tex", tex);
+        let keys: Vec<(&str, usize)> = citations.iter().map(|c| (c.key.as_str(), c.line)).collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("lecun2015", 1),
+                ("doi:10.1038/nature14539", 1),
+                ("vaswani2017", 3),
+                ("a", 4),
+                ("b", 5),
+            ]
+        );
+        assert_eq!(citations[0].file, "paper.tex");
+    }
+
+    #[test]
+    fn test_scan_aux() {
+        let aux = "\\relax\n\\citation{a,b}\n\\abx@aux@cite{0}{c}\n\\bibdata{refs}\n";
+        let keys: Vec<String> = scan("paper.aux", aux).into_iter().map(|c| c.key).collect();
+        assert_eq!(keys, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_scan_markdown() {
+        let md = "As shown [@doi:10.1038/nature14539, p. 3; -@arxiv:1706.03762].\n\
+                  @smith2020 says so, mail me at me@example.org.\n\
+                  ```\n@not_a_citation\n```\n\
+                  See @{doi:10.1000/a(b)}.\n";
+        let keys: Vec<(String, usize)> = scan("paper.md", md)
+            .into_iter()
+            .map(|c| (c.key, c.line))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("doi:10.1038/nature14539".to_string(), 1),
+                ("arxiv:1706.03762".to_string(), 1),
+                ("smith2020".to_string(), 2),
+                ("doi:10.1000/a(b)".to_string(), 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identifier_keys() {
+        assert_eq!(
+            identifier("doi:10.1038/nature14539").as_deref(),
+            Some("10.1038/nature14539")
+        );
+        assert_eq!(
+            identifier("arXiv:1706.03762").as_deref(),
+            Some("arxiv:1706.03762")
+        );
+        assert_eq!(identifier("lecun2015"), None);
+        assert_eq!(identifier("doi:"), None);
+        assert_eq!(identifier("sec:intro"), None);
+    }
+}
@@ -0,0 +1,691 @@
+//! Managed BibTeX files: papers added with stable cite keys, and entries
+//! checked against the sources.
+//!
+//! - [`BibManager`]: Resolves paper IDs into entries of a [`BibFile`] and
+//!   checks existing entries
+//! - [`AddReport`]: What `bib add` did for each ID
+//! - [`CheckReport`]: Entries with a missing DOI, a year that disagrees with
+//!   the sources, a retraction, or a published version of a preprint
+//! - [`manuscript`]: Cite keys of LaTeX and Pandoc Markdown manuscripts,
+//!   synced into the bib file by [`BibManager::manuscript`]
+//!
+//! The file itself is edited by [`BibFile`], which keeps its formatting and
+//! the values edited by hand.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use research_master::bib::BibManager;
+//! use research_master::formats::{BibFile, KeyPattern};
+//! use research_master::sources::SourceRegistry;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut file = BibFile::parse(&std::fs::read_to_string("references.bib")?)?;
+//!
+//! let registry = SourceRegistry::new();
+//! let manager = BibManager::new(registry.all().cloned().collect());
+//! let ids = vec!["10.1038/nature14539".to_string()];
+//! let report = manager.add(&mut file, &ids, &KeyPattern::default()).await?;
+//! std::fs::write("references.bib", file.to_string())?;
+//! println!("{}", report.summary());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod manuscript;
+
+use std::fmt;
+use std::sync::Arc;
+
+use futures_util::{stream, StreamExt};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::enrich::find_by_title;
+use crate::formats::{BibEntry, BibFile, EntryChange, FormatError, KeyPattern};
+use crate::graph::{normalize_doi, resolve_seed};
+use crate::models::{Paper, SourceType};
+use crate::sources::{Source, SourceError};
+use crate::versions::{is_preprint, is_preprint_doi};
+use manuscript::{Citation, CitedKey, KeyStatus, ManuscriptReport};
+
+/// Sources entries are checked against, in order of preference
+pub const CHECK_SOURCES: &[&str] = &["openalex", "crossref", "semantic"];
+
+/// Number of IDs resolved, or entries checked, concurrently
+const CONCURRENCY: usize = 4;
+
+/// Outcome of adding one ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AddStatus {
+    /// A new entry was appended
+    Added,
+    /// An existing entry gained or refreshed fields
+    Updated,
+    /// An existing entry was already up to date
+    Unchanged,
+    /// No source knew the ID
+    NotFound,
+}
+
+/// What adding one ID did
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AddEntry {
+    /// The ID as given
+    pub id: String,
+
+    /// Cite key of the new or matched entry
+    pub key: Option<String>,
+
+    /// Outcome
+    pub status: AddStatus,
+
+    /// Fields added or refreshed
+    pub fields: Vec<String>,
+}
+
+/// What `bib add` did, one entry per ID
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct AddReport {
+    /// One entry per ID, in input order
+    pub entries: Vec<AddEntry>,
+}
+
+impl AddReport {
+    /// Number of IDs with the given outcome
+    pub fn count(&self, status: AddStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+
+    /// Whether the file changed
+    pub fn changed(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|e| matches!(e.status, AddStatus::Added | AddStatus::Updated))
+    }
+
+    /// One-line summary, e.g. `2 added, 1 updated, 0 unchanged, 1 not found`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} updated, {} unchanged, {} not found",
+            self.count(AddStatus::Added),
+            self.count(AddStatus::Updated),
+            self.count(AddStatus::Unchanged),
+            self.count(AddStatus::NotFound),
+        )
+    }
+}
+
+/// A problem found with an entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum BibIssue {
+    /// The entry has no DOI; `suggested` is the DOI a source has for it
+    MissingDoi { suggested: Option<String> },
+    /// No source agrees with the entry's year
+    YearMismatch {
+        year: i32,
+        found: i32,
+        source: String,
+    },
+    /// The paper has been retracted
+    Retracted { source: String },
+    /// The entry is a preprint, and a source has the published version
+    NewerVersion { doi: String, source: String },
+    /// No source had a matching record
+    NotFound,
+}
+
+impl fmt::Display for BibIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BibIssue::MissingDoi {
+                suggested: Some(doi),
+            } => write!(f, "missing DOI (found {})", doi),
+            BibIssue::MissingDoi { suggested: None } => f.write_str("missing DOI"),
+            BibIssue::YearMismatch {
+                year,
+                found,
+                source,
+            } => write!(f, "year {} but {} has {}", year, source, found),
+            BibIssue::Retracted { source } => write!(f, "retracted (according to {})", source),
+            BibIssue::NewerVersion { doi, source } => {
+                write!(
+                    f,
+                    "preprint; published version {} (found in {})",
+                    doi, source
+                )
+            }
+            BibIssue::NotFound => f.write_str("not found in any source"),
+        }
+    }
+}
+
+/// The problems found with one entry
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CheckEntry {
+    /// Cite key
+    pub key: String,
+
+    /// Entry title
+    pub title: String,
+
+    /// Problems found; empty when the entry is fine
+    pub issues: Vec<BibIssue>,
+
+    /// Lookups that failed, as `<source>: <error>`
+    pub errors: Vec<String>,
+}
+
+/// What `bib check` found, one entry per BibTeX entry
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct CheckReport {
+    /// One entry per BibTeX entry, in file order
+    pub entries: Vec<CheckEntry>,
+}
+
+impl CheckReport {
+    /// Entries with at least one issue
+    pub fn with_issues(&self) -> impl Iterator<Item = &CheckEntry> {
+        self.entries.iter().filter(|e| !e.issues.is_empty())
+    }
+
+    /// One-line summary, e.g. `12 entries checked, 2 with issues`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} entries checked, {} with issues",
+            self.entries.len(),
+            self.with_issues().count()
+        )
+    }
+}
+
+/// Adds papers to BibTeX files and checks their entries against the sources
+#[derive(Debug)]
+pub struct BibManager {
+    sources: Vec<Arc<dyn Source>>,
+}
+
+impl BibManager {
+    /// Create a manager looking papers up in the given sources
+    pub fn new(sources: Vec<Arc<dyn Source>>) -> Self {
+        Self { sources }
+    }
+
+    /// Look up the metadata for a DOI or arXiv ID
+    pub async fn resolve(&self, id: &str) -> Option<Paper> {
+        let paper = resolve_seed(&self.sources, id).await;
+        if !paper.title.is_empty() {
+            return Some(paper);
+        }
+        if paper.source == SourceType::Arxiv {
+            // arXiv registers a DataCite DOI for every paper
+            let doi = format!("10.48550/arXiv.{}", paper.paper_id);
+            let paper = resolve_seed(&self.sources, &doi).await;
+            if !paper.title.is_empty() {
+                return Some(paper);
+            }
+        }
+        None
+    }
+
+    /// Resolve every ID and add it to the file, or refresh its entry
+    pub async fn add(
+        &self,
+        file: &mut BibFile,
+        ids: &[String],
+        pattern: &KeyPattern,
+    ) -> Result<AddReport, FormatError> {
+        let resolved: Vec<Option<Paper>> = stream::iter(ids)
+            .map(|id| self.resolve(id))
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut report = AddReport::default();
+        for (id, paper) in ids.iter().zip(resolved) {
+            let mut entry = AddEntry {
+                id: id.clone(),
+                key: None,
+                status: AddStatus::NotFound,
+                fields: Vec::new(),
+            };
+            if let Some(paper) = paper {
+                let (key, change) = file.add(&paper, pattern)?;
+                entry.key = Some(key);
+                (entry.status, entry.fields) = match change {
+                    EntryChange::Added => (AddStatus::Added, Vec::new()),
+                    EntryChange::Updated(fields) if fields.is_empty() => {
+                        (AddStatus::Unchanged, fields)
+                    }
+                    EntryChange::Updated(fields) => (AddStatus::Updated, fields),
+                };
+            }
+            report.entries.push(entry);
+        }
+        Ok(report)
+    }
+
+    /// Check every entry of the file against the sources
+    pub async fn check(&self, file: &BibFile) -> CheckReport {
+        let entries = stream::iter(file.entries())
+            .map(|entry| self.check_entry(entry))
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+        CheckReport { entries }
+    }
+
+    /// Sync the keys a manuscript cites into the file
+    ///
+    /// Identifier keys (`doi:...`, `arxiv:...`) are resolved and written
+    /// under the cited key; other keys are only looked up. With `check`, the
+    /// cited entries are also checked for retractions and published versions
+    /// of preprints.
+    pub async fn manuscript(
+        &self,
+        file: &mut BibFile,
+        citations: &[Citation],
+        check: bool,
+    ) -> Result<ManuscriptReport, FormatError> {
+        let cited = manuscript::group(citations);
+        let resolved: Vec<Option<Paper>> = stream::iter(&cited)
+            .map(|(key, _)| async move {
+                match manuscript::identifier(key) {
+                    Some(id) => self.resolve(&id).await,
+                    None => None,
+                }
+            })
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut report = ManuscriptReport::default();
+        for ((key, citations), paper) in cited.iter().zip(resolved) {
+            let mut entry = CitedKey {
+                key: key.clone(),
+                status: KeyStatus::InBib,
+                locations: citations
+                    .iter()
+                    .map(|c| format!("{}:{}", c.file, c.line))
+                    .collect(),
+                fields: Vec::new(),
+                issues: Vec::new(),
+            };
+            match paper {
+                Some(paper) => match file.add_as(&paper, key)? {
+                    EntryChange::Added => entry.status = KeyStatus::Added,
+                    EntryChange::Updated(fields) if fields.is_empty() => {}
+                    EntryChange::Updated(fields) => {
+                        entry.status = KeyStatus::Updated;
+                        entry.fields = fields;
+                    }
+                },
+                None if file.get(key).is_some() => {}
+                None if manuscript::identifier(key).is_some() => {
+                    entry.status = KeyStatus::Unresolved
+                }
+                None => entry.status = KeyStatus::Missing,
+            }
+            report.keys.push(entry);
+        }
+
+        if check {
+            let checked: Vec<Option<CheckEntry>> = stream::iter(&report.keys)
+                .map(|cited| async {
+                    match file.get(&cited.key) {
+                        Some(entry) => Some(self.check_entry(entry).await),
+                        None => None,
+                    }
+                })
+                .buffered(CONCURRENCY)
+                .collect()
+                .await;
+            for (cited, checked) in report.keys.iter_mut().zip(checked) {
+                cited.issues = checked
+                    .into_iter()
+                    .flat_map(|c| c.issues)
+                    .filter(|issue| {
+                        matches!(
+                            issue,
+                            BibIssue::Retracted { .. } | BibIssue::NewerVersion { .. }
+                        )
+                    })
+                    .collect();
+            }
+        }
+        Ok(report)
+    }
+
+    async fn check_entry(&self, entry: &BibEntry) -> CheckEntry {
+        let paper = entry.to_paper();
+        let mut checked = CheckEntry {
+            key: entry.key().to_string(),
+            title: paper.title.clone(),
+            issues: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        let doi = paper.doi.as_deref().and_then(normalize_doi);
+        let mut records = Vec::new();
+        for source_id in CHECK_SOURCES {
+            let Some(source) = self.sources.iter().find(|s| s.id() == *source_id) else {
+                continue;
+            };
+            let record = match doi {
+                Some(ref doi) if source.supports_doi_lookup() => {
+                    source.get_by_doi(doi).await.map(Some)
+                }
+                None if source.supports_search() => find_by_title(source.as_ref(), &paper).await,
+                _ => continue,
+            };
+            match record {
+                Ok(Some(record)) => records.push((source.id().to_string(), record)),
+                Ok(None) | Err(SourceError::NotFound(_)) => {}
+                Err(e) => checked.errors.push(format!("{}: {}", source.id(), e)),
+            }
+        }
+
+        if doi.is_none() {
+            let suggested = records
+                .iter()
+                .find_map(|(_, r)| r.doi.as_deref().and_then(normalize_doi));
+            checked.issues.push(BibIssue::MissingDoi { suggested });
+        }
+        if let Some(year) = paper.year() {
+            let years: Vec<(&str, i32)> = records
+                .iter()
+                .filter_map(|(source, r)| Some((source.as_str(), r.year()?)))
+                .collect();
+            if let Some(&(source, found)) = years.first() {
+                if years.iter().all(|(_, y)| *y != year) {
+                    checked.issues.push(BibIssue::YearMismatch {
+                        year,
+                        found,
+                        source: source.to_string(),
+                    });
+                }
+            }
+        }
+        if is_retracted(&paper) {
+            checked.issues.push(BibIssue::Retracted {
+                source: "entry title".to_string(),
+            });
+        } else if let Some((source, _)) = records.iter().find(|(_, r)| is_retracted(r)) {
+            checked.issues.push(BibIssue::Retracted {
+                source: source.clone(),
+            });
+        }
+        if is_preprint(&paper) {
+            if let Some((source, doi)) = self.published_version(&paper, &records).await {
+                checked.issues.push(BibIssue::NewerVersion { doi, source });
+            }
+        }
+        if records.is_empty() && checked.errors.is_empty() {
+            checked.issues.push(BibIssue::NotFound);
+        }
+        checked
+    }
+
+    /// The source and DOI of the published version of a preprint: a record
+    /// already found with a journal DOI, or else the best title match
+    async fn published_version(
+        &self,
+        paper: &Paper,
+        records: &[(String, Paper)],
+    ) -> Option<(String, String)> {
+        let published = |r: &Paper| {
+            r.doi
+                .as_deref()
+                .and_then(normalize_doi)
+                .filter(|doi| !is_preprint_doi(doi))
+        };
+        if let Some(found) = records
+            .iter()
+            .find_map(|(source, r)| Some((source.clone(), published(r)?)))
+        {
+            return Some(found);
+        }
+        // Records looked up by the preprint's DOI are the preprint itself
+        paper.doi.as_ref()?;
+        for source_id in CHECK_SOURCES {
+            let Some(source) = self.sources.iter().find(|s| s.id() == *source_id) else {
+                continue;
+            };
+            if !source.supports_search() {
+                continue;
+            }
+            if let Ok(Some(record)) = find_by_title(source.as_ref(), paper).await {
+                if let Some(doi) = published(&record) {
+                    return Some((source.id().to_string(), doi));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Whether a record is flagged as retracted, or titled as a retraction the
+/// way publishers rename retracted articles
+fn is_retracted(paper: &Paper) -> bool {
+    let title = paper.title.trim_start().to_lowercase();
+    paper.is_retracted()
+        || title.starts_with("retracted:")
+        || title.starts_with("retracted article:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    use crate::models::{PaperBuilder, PaperStatus, SearchQuery, SearchResponse, StatusKind};
+    use crate::sources::SourceCapabilities;
+
+    /// A source that knows a fixed list of papers by DOI and title
+    #[derive(Debug)]
+    struct RecordSource {
+        id: &'static str,
+        records: Vec<Paper>,
+    }
+
+    #[async_trait]
+    impl Source for RecordSource {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn name(&self) -> &str {
+            self.id
+        }
+
+        fn capabilities(&self) -> SourceCapabilities {
+            SourceCapabilities::SEARCH | SourceCapabilities::DOI_LOOKUP
+        }
+
+        async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
+            Ok(SearchResponse::new(
+                self.records.clone(),
+                self.id,
+                &query.query,
+            ))
+        }
+
+        async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
+            self.records
+                .iter()
+                .find(|r| {
+                    r.doi
+                        .as_deref()
+                        .is_some_and(|d| d.eq_ignore_ascii_case(doi))
+                })
+                .cloned()
+                .ok_or_else(|| SourceError::NotFound(doi.to_string()))
+        }
+    }
+
+    fn manager() -> BibManager {
+        let records = vec![
+            PaperBuilder::new(
+                "W1",
+                "Deep learning for image recognition",
+                "https://openalex.org/W1",
+                SourceType::OpenAlex,
+            )
+            .authors("Yann LeCun; Yoshua Bengio; Geoffrey Hinton")
+            .published_date("2015")
+            .doi("10.1038/nature14539")
+            .build(),
+            PaperBuilder::new(
+                "W2",
+                "Generative adversarial nets in practice",
+                "",
+                SourceType::OpenAlex,
+            )
+            .published_date("2014")
+            .doi("10.1000/gan")
+            .status(PaperStatus::new(StatusKind::Retracted, "openalex"))
+            .build(),
+            PaperBuilder::new(
+                "W3",
+                "Attention is all you need for sequence models",
+                "",
+                SourceType::OpenAlex,
+            )
+            .published_date("2017")
+            .doi("10.5555/published")
+            .build(),
+        ];
+        let source = RecordSource {
+            id: "openalex",
+            records,
+        };
+        BibManager::new(vec![Arc::new(source)])
+    }
+
+    #[tokio::test]
+    async fn test_add_ids() {
+        let mut file = BibFile::default();
+        let ids = vec![
+            "https://doi.org/10.1038/NATURE14539".to_string(),
+            "10.1000/missing".to_string(),
+        ];
+        let pattern = KeyPattern::parse("{author}_{shortyear}").unwrap();
+        let report = manager().add(&mut file, &ids, &pattern).await.unwrap();
+
+        assert_eq!(report.entries[0].key.as_deref(), Some("lecun_15"));
+        assert_eq!(report.entries[0].status, AddStatus::Added);
+        assert_eq!(report.entries[1].status, AddStatus::NotFound);
+        assert_eq!(
+            report.summary(),
+            "1 added, 0 updated, 0 unchanged, 1 not found"
+        );
+        assert!(file.to_string().starts_with("@misc{lecun_15,\n"));
+
+        let report = manager().add(&mut file, &ids[..1], &pattern).await.unwrap();
+        assert_eq!(report.entries[0].status, AddStatus::Unchanged);
+        assert!(!report.changed());
+    }
+
+    #[tokio::test]
+    async fn test_check_entries() {
+        let file = BibFile::parse(
+            r#"@article{lecun2016, title = {Deep Learning for Image Recognition}, year = 2016}
+@article{gan, title = {Generative Adversarial Nets in Practice}, year = 2014, doi = {10.1000/gan}}
+@article{fine, title = {Deep learning for image recognition}, year = {2015}, doi = {10.1038/nature14539}}
+@misc{unknown, title = {A paper nobody has heard of}, doi = {10.1/none}}
+@misc{vaswani, title = {Attention Is All You Need for Sequence Models}, year = 2017,
+  eprint = {1706.03762}, archiveprefix = {arXiv}}
+"#,
+        )
+        .unwrap();
+        let report = manager().check(&file).await;
+
+        assert_eq!(
+            report.entries[0].issues,
+            vec![
+                BibIssue::MissingDoi {
+                    suggested: Some("10.1038/nature14539".to_string())
+                },
+                BibIssue::YearMismatch {
+                    year: 2016,
+                    found: 2015,
+                    source: "openalex".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            report.entries[1].issues,
+            vec![BibIssue::Retracted {
+                source: "openalex".to_string()
+            }]
+        );
+        assert!(report.entries[2].issues.is_empty());
+        assert_eq!(report.entries[3].issues, vec![BibIssue::NotFound]);
+        assert_eq!(
+            report.entries[4].issues,
+            vec![
+                BibIssue::MissingDoi {
+                    suggested: Some("10.5555/published".to_string())
+                },
+                BibIssue::NewerVersion {
+                    doi: "10.5555/published".to_string(),
+                    source: "openalex".to_string()
+                },
+            ]
+        );
+        assert_eq!(report.summary(), "5 entries checked, 4 with issues");
+        assert_eq!(
+            report.entries[0].issues[1].to_string(),
+            "year 2016 but openalex has 2015"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_manuscript_keys() {
+        let mut file = BibFile::parse(
+            "@article{gan, title = {Generative Adversarial Nets in Practice}, doi = {10.1000/gan}}\n",
+        )
+        .unwrap();
+        let citations = manuscript::scan(
+            "paper.md",
+            "See [@doi:10.1038/nature14539; @gan] and @smith2020.\n\
+             Also [@doi:10.1000/missing] and again @gan.\n",
+        );
+        let report = manager()
+            .manuscript(&mut file, &citations, true)
+            .await
+            .unwrap();
+
+        let statuses: Vec<(&str, KeyStatus)> = report
+            .keys
+            .iter()
+            .map(|k| (k.key.as_str(), k.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("doi:10.1038/nature14539", KeyStatus::Added),
+                ("gan", KeyStatus::InBib),
+                ("smith2020", KeyStatus::Missing),
+                ("doi:10.1000/missing", KeyStatus::Unresolved),
+            ]
+        );
+        assert_eq!(report.keys[1].locations, vec!["paper.md:1", "paper.md:2"]);
+        assert!(report.keys[0].issues.is_empty());
+        assert_eq!(
+            report.keys[1].issues,
+            vec![BibIssue::Retracted {
+                source: "openalex".to_string()
+            }]
+        );
+        assert!(file.get("doi:10.1038/nature14539").is_some());
+        assert!(report.changed());
+        assert_eq!(
+            report.summary(),
+            "4 keys cited: 1 in bib, 1 added, 0 updated, 1 unresolved, 1 missing; 1 with issues"
+        );
+    }
+}
@@ -3,10 +3,14 @@
 mod file_config;
 
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 
 const TEST_MODE_ENV_VAR: &str = "RESEARCH_MASTER_TEST_MODE";
 
+/// Environment variable that turns on offline mode (`true`)
+pub const OFFLINE_ENV_VAR: &str = "RESEARCH_MASTER_OFFLINE";
+
 /// Cache configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheConfig {
@@ -26,9 +30,72 @@ pub struct CacheConfig {
     #[serde(default = "default_citation_ttl")]
     pub citation_ttl_seconds: u64,
 
-    /// Maximum cache size in MB (default: 500MB)
+    /// Maximum cache size in MB (default: 500MB); the least recently used
+    /// entries are removed beyond it (0 for no limit)
+    /// Maps to RESEARCH_MASTER_CACHE_MAX_SIZE_MB environment variable
     #[serde(default = "default_max_cache_size")]
     pub max_size_mb: usize,
+
+    /// Compress cached values (default: true)
+    /// Maps to RESEARCH_MASTER_CACHE_COMPRESS environment variable
+    #[serde(default = "default_true")]
+    pub compress: bool,
+
+    /// Cache HTTP responses and revalidate them with `If-None-Match` /
+    /// `If-Modified-Since` while caching is enabled (default: true)
+    /// Maps to RESEARCH_MASTER_CACHE_HTTP_ENABLED environment variable
+    #[serde(default = "default_true")]
+    pub http_enabled: bool,
+
+    /// Maximum size of the HTTP response cache in MB (default: 100MB)
+    /// Maps to RESEARCH_MASTER_CACHE_HTTP_MAX_SIZE_MB environment variable
+    #[serde(default = "default_http_max_cache_size")]
+    pub http_max_size_mb: usize,
+
+    /// Per-source HTTP cache settings
+    /// Maps to RESEARCH_MASTER_CACHE_HTTP_SOURCES environment variable
+    /// (e.g. `openalex=3600,google_scholar=off`)
+    #[serde(default)]
+    pub http_sources: Vec<HttpCacheSourceConfig>,
+}
+
+/// HTTP cache settings of one source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpCacheSourceConfig {
+    /// Source ID (e.g. `openalex`) or API host (e.g. `api.openalex.org`)
+    pub source: String,
+
+    /// Whether responses of the source are cached (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// How long responses without `Cache-Control: max-age` stay fresh, in
+    /// seconds (default: none, so they are revalidated on every use)
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+}
+
+impl HttpCacheSourceConfig {
+    /// Parse a comma-separated list of `source=<seconds>` or `source=off`
+    pub fn parse_list(value: &str) -> Vec<Self> {
+        value
+            .split(',')
+            .filter_map(|item| {
+                let (source, setting) = item.split_once('=')?;
+                let setting = setting.trim();
+                let enabled = !matches!(
+                    setting.to_ascii_lowercase().as_str(),
+                    "off" | "false" | "disabled"
+                );
+                Some(Self {
+                    source: source.trim().to_string(),
+                    enabled,
+                    ttl_seconds: setting.parse().ok(),
+                })
+            })
+            .filter(|config| !config.source.is_empty())
+            .collect()
+    }
 }
 
 impl Default for CacheConfig {
@@ -38,7 +105,23 @@ impl Default for CacheConfig {
             directory: None,
             search_ttl_seconds: default_search_ttl(),
             citation_ttl_seconds: default_citation_ttl(),
-            max_size_mb: default_max_cache_size(),
+            max_size_mb: std::env::var("RESEARCH_MASTER_CACHE_MAX_SIZE_MB")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_max_cache_size),
+            compress: std::env::var("RESEARCH_MASTER_CACHE_COMPRESS")
+                .map(|value| !value.eq_ignore_ascii_case("false"))
+                .unwrap_or(true),
+            http_enabled: std::env::var("RESEARCH_MASTER_CACHE_HTTP_ENABLED")
+                .map(|value| !value.eq_ignore_ascii_case("false"))
+                .unwrap_or(true),
+            http_max_size_mb: std::env::var("RESEARCH_MASTER_CACHE_HTTP_MAX_SIZE_MB")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(default_http_max_cache_size),
+            http_sources: std::env::var("RESEARCH_MASTER_CACHE_HTTP_SOURCES")
+                .map(|value| HttpCacheSourceConfig::parse_list(&value))
+                .unwrap_or_default(),
         }
     }
 }
@@ -55,6 +138,10 @@ fn default_max_cache_size() -> usize {
     500
 }
 
+fn default_http_max_cache_size() -> usize {
+    100
+}
+
 /// Get the default cache directory for the platform
 pub fn default_cache_dir() -> PathBuf {
     // Try platform-specific cache directories first
@@ -111,6 +198,261 @@ pub struct Config {
     /// Cache settings
     #[serde(default)]
     pub cache: CacheConfig,
+
+    /// MCP server settings
+    #[serde(default)]
+    pub mcp: McpConfig,
+
+    /// Managed BibTeX file settings
+    #[serde(default)]
+    pub bib: BibConfig,
+
+    /// Retraction checking settings
+    #[serde(default)]
+    pub retractions: RetractionConfig,
+
+    /// Metadata enrichment settings
+    #[serde(default)]
+    pub enrich: EnrichConfig,
+
+    /// Answer from the cache and downloaded PDFs only, without network access
+    /// Maps to RESEARCH_MASTER_OFFLINE environment variable
+    #[serde(default)]
+    pub offline: bool,
+}
+
+/// Per-client quotas enforced by the MCP HTTP server
+///
+/// A value of `0` disables the corresponding limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpConfig {
+    /// Requests per minute allowed for each client
+    /// Maps to RESEARCH_MASTER_MCP_CLIENT_REQUESTS_PER_MINUTE environment variable
+    #[serde(default = "default_client_requests_per_minute")]
+    pub client_requests_per_minute: u32,
+
+    /// Maximum number of tool calls a client may have in flight at once
+    /// Maps to RESEARCH_MASTER_MCP_CLIENT_MAX_CONCURRENT_TOOL_CALLS environment variable
+    #[serde(default = "default_client_max_concurrent_tool_calls")]
+    pub client_max_concurrent_tool_calls: usize,
+
+    /// Maximum number of `download_paper` calls per client per UTC day
+    /// Maps to RESEARCH_MASTER_MCP_CLIENT_DAILY_DOWNLOADS environment variable
+    #[serde(default = "default_client_daily_downloads")]
+    pub client_daily_downloads: u32,
+
+    /// Reverse proxies whose `X-Forwarded-For`/`X-Real-IP` headers identify clients
+    /// Maps to RESEARCH_MASTER_MCP_TRUSTED_PROXIES environment variable (comma-separated)
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+impl McpConfig {
+    fn from_env() -> Self {
+        Self {
+            client_requests_per_minute: std::env::var(
+                "RESEARCH_MASTER_MCP_CLIENT_REQUESTS_PER_MINUTE",
+            )
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(default_client_requests_per_minute),
+            client_max_concurrent_tool_calls: std::env::var(
+                "RESEARCH_MASTER_MCP_CLIENT_MAX_CONCURRENT_TOOL_CALLS",
+            )
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(default_client_max_concurrent_tool_calls),
+            client_daily_downloads: std::env::var("RESEARCH_MASTER_MCP_CLIENT_DAILY_DOWNLOADS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_else(default_client_daily_downloads),
+            trusted_proxies: std::env::var("RESEARCH_MASTER_MCP_TRUSTED_PROXIES")
+                .map(|s| {
+                    s.split(',')
+                        .filter_map(|ip| ip.trim().parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    fn without_env() -> Self {
+        Self {
+            client_requests_per_minute: default_client_requests_per_minute(),
+            client_max_concurrent_tool_calls: default_client_max_concurrent_tool_calls(),
+            client_daily_downloads: default_client_daily_downloads(),
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+fn default_client_requests_per_minute() -> u32 {
+    120
+}
+
+fn default_client_max_concurrent_tool_calls() -> usize {
+    4
+}
+
+fn default_client_daily_downloads() -> u32 {
+    200
+}
+
+/// Settings for `bib add`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BibConfig {
+    /// Cite key pattern for new entries, e.g. `{author}{year}{firstword}`
+    /// Maps to RESEARCH_MASTER_BIB_KEY_PATTERN environment variable
+    #[serde(default = "default_key_pattern")]
+    pub key_pattern: String,
+}
+
+impl Default for BibConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+impl BibConfig {
+    fn from_env() -> Self {
+        Self {
+            key_pattern: std::env::var("RESEARCH_MASTER_BIB_KEY_PATTERN")
+                .unwrap_or_else(|_| default_key_pattern()),
+        }
+    }
+
+    fn without_env() -> Self {
+        Self {
+            key_pattern: default_key_pattern(),
+        }
+    }
+}
+
+fn default_key_pattern() -> String {
+    crate::formats::KeyPattern::DEFAULT.to_string()
+}
+
+/// Settings for retraction checking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetractionConfig {
+    /// Local copy of the Retraction Watch dataset (CSV), checked before the sources
+    /// Maps to RESEARCH_MASTER_RETRACTIONS_RETRACTION_WATCH environment variable
+    #[serde(default)]
+    pub retraction_watch: Option<PathBuf>,
+}
+
+impl Default for RetractionConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+impl RetractionConfig {
+    fn from_env() -> Self {
+        Self {
+            retraction_watch: std::env::var("RESEARCH_MASTER_RETRACTIONS_RETRACTION_WATCH")
+                .ok()
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+        }
+    }
+
+    fn without_env() -> Self {
+        Self {
+            retraction_watch: None,
+        }
+    }
+}
+
+/// Settings for enriching search results (`search --enrich`, the `enrich` tool argument)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrichConfig {
+    /// Sources to look papers up in, in order of preference
+    /// Maps to RESEARCH_MASTER_ENRICH_SOURCES environment variable (comma-separated)
+    #[serde(default = "default_enrich_sources")]
+    pub sources: Vec<String>,
+
+    /// Number of papers looked up concurrently (default: 4)
+    /// Maps to RESEARCH_MASTER_ENRICH_CONCURRENCY environment variable
+    #[serde(default = "default_enrich_concurrency")]
+    pub concurrency: usize,
+
+    /// Time allowed for enriching one result list in seconds, 0 for no limit (default: 30)
+    /// Maps to RESEARCH_MASTER_ENRICH_TIME_BUDGET_SECONDS environment variable
+    #[serde(default = "default_enrich_time_budget")]
+    pub time_budget_seconds: u64,
+}
+
+impl Default for EnrichConfig {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+impl EnrichConfig {
+    fn from_env() -> Self {
+        let defaults = Self::without_env();
+        Self {
+            sources: std::env::var("RESEARCH_MASTER_ENRICH_SOURCES")
+                .ok()
+                .map(|s| {
+                    s.split(',')
+                        .map(|id| id.trim().to_string())
+                        .filter(|id| !id.is_empty())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|ids| !ids.is_empty())
+                .unwrap_or(defaults.sources),
+            concurrency: std::env::var("RESEARCH_MASTER_ENRICH_CONCURRENCY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.concurrency),
+            time_budget_seconds: std::env::var("RESEARCH_MASTER_ENRICH_TIME_BUDGET_SECONDS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.time_budget_seconds),
+        }
+    }
+
+    fn without_env() -> Self {
+        Self {
+            sources: default_enrich_sources(),
+            concurrency: default_enrich_concurrency(),
+            time_budget_seconds: default_enrich_time_budget(),
+        }
+    }
+
+    /// Enrichment options for these settings, filling in every field
+    pub fn options(&self) -> crate::enrich::EnrichOptions {
+        let options = crate::enrich::EnrichOptions::new()
+            .sources(self.sources.clone())
+            .concurrency(self.concurrency);
+        match self.time_budget_seconds {
+            0 => options,
+            seconds => options.time_budget(std::time::Duration::from_secs(seconds)),
+        }
+    }
+}
+
+fn default_enrich_sources() -> Vec<String> {
+    crate::enrich::DEFAULT_ENRICH_SOURCES
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn default_enrich_concurrency() -> usize {
+    4
+}
+
+fn default_enrich_time_budget() -> u64 {
+    30
 }
 
 /// Source configuration
@@ -243,9 +585,16 @@ impl Config {
         Self {
             api_keys: ApiKeys::from_env(),
             downloads: DownloadConfig::default(),
-            rate_limits: RateLimitConfig::default(),
+            rate_limits: RateLimitConfig::from_env(),
             sources: SourceConfig::from_env(),
             cache: CacheConfig::default(),
+            mcp: McpConfig::from_env(),
+            bib: BibConfig::from_env(),
+            retractions: RetractionConfig::from_env(),
+            enrich: EnrichConfig::from_env(),
+            offline: std::env::var(OFFLINE_ENV_VAR)
+                .map(|value| value.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
         }
     }
 
@@ -256,6 +605,11 @@ impl Config {
             rate_limits: RateLimitConfig::default(),
             sources: SourceConfig::without_env(),
             cache: CacheConfig::default(),
+            mcp: McpConfig::without_env(),
+            bib: BibConfig::without_env(),
+            retractions: RetractionConfig::without_env(),
+            enrich: EnrichConfig::without_env(),
+            offline: false,
         }
     }
 }
@@ -308,6 +662,13 @@ pub struct RateLimitConfig {
     /// Maximum concurrent requests
     #[serde(default = "default_max_concurrent")]
     pub max_concurrent_requests: usize,
+
+    /// Share each host's request schedule, including waits asked for by
+    /// `Retry-After` and `X-RateLimit-*` headers, with other processes
+    /// through `rate_limits.json` in the cache directory (default: true)
+    /// Maps to RESEARCH_MASTER_RATE_LIMITS_SHARED_STATE environment variable
+    #[serde(default = "default_true")]
+    pub shared_state: bool,
 }
 
 impl Default for RateLimitConfig {
@@ -315,6 +676,18 @@ impl Default for RateLimitConfig {
         Self {
             default_requests_per_second: default_rps(),
             max_concurrent_requests: default_max_concurrent(),
+            shared_state: true,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    fn from_env() -> Self {
+        Self {
+            shared_state: std::env::var(crate::utils::SHARED_STATE_ENV_VAR)
+                .map(|value| value.eq_ignore_ascii_case("true"))
+                .unwrap_or(true),
+            ..Self::default()
         }
     }
 }
@@ -454,6 +827,27 @@ mod tests {
         assert!(cache.search_ttl_seconds == 1800);
         assert!(cache.citation_ttl_seconds == 900);
         assert!(cache.max_size_mb == 500);
+        assert!(cache.http_max_size_mb == 100);
+    }
+
+    #[test]
+    fn test_http_cache_sources_parse() {
+        let sources = HttpCacheSourceConfig::parse_list("openalex=3600, google_scholar=off,,bad");
+        assert_eq!(
+            sources,
+            vec![
+                HttpCacheSourceConfig {
+                    source: "openalex".to_string(),
+                    enabled: true,
+                    ttl_seconds: Some(3600),
+                },
+                HttpCacheSourceConfig {
+                    source: "google_scholar".to_string(),
+                    enabled: false,
+                    ttl_seconds: None,
+                },
+            ]
+        );
     }
 
     #[test]
@@ -468,6 +862,68 @@ mod tests {
         let rate = RateLimitConfig::default();
         assert_eq!(rate.default_requests_per_second, 5.0);
         assert_eq!(rate.max_concurrent_requests, 10);
+        assert!(rate.shared_state);
+    }
+
+    #[test]
+    fn test_mcp_config_without_env() {
+        let mcp = McpConfig::without_env();
+        assert_eq!(mcp.client_requests_per_minute, 120);
+        assert_eq!(mcp.client_max_concurrent_tool_calls, 4);
+        assert_eq!(mcp.client_daily_downloads, 200);
+        assert!(mcp.trusted_proxies.is_empty());
+
+        let config: Config =
+            toml::from_str("[mcp]\ntrusted_proxies = [\"10.0.0.1\", \"::1\"]\n").unwrap();
+        assert_eq!(config.mcp.trusted_proxies.len(), 2);
+    }
+
+    #[test]
+    fn test_bib_config_without_env() {
+        let bib = BibConfig::without_env();
+        assert_eq!(bib.key_pattern, "{author}{year}{firstword}");
+    }
+
+    #[test]
+    fn test_retraction_config_without_env() {
+        let retractions = RetractionConfig::without_env();
+        assert!(retractions.retraction_watch.is_none());
+
+        let config: Config =
+            toml::from_str("[retractions]\nretraction_watch = \"/data/retraction_watch.csv\"\n")
+                .unwrap();
+        assert_eq!(
+            config.retractions.retraction_watch,
+            Some(PathBuf::from("/data/retraction_watch.csv"))
+        );
+    }
+
+    #[test]
+    fn test_enrich_config_without_env() {
+        let enrich = EnrichConfig::without_env();
+        assert_eq!(enrich.sources[0], "semantic");
+        assert_eq!(enrich.concurrency, 4);
+        assert_eq!(
+            enrich.options().time_budget,
+            Some(std::time::Duration::from_secs(30))
+        );
+
+        let config: Config =
+            toml::from_str("[enrich]\nsources = [\"crossref\"]\ntime_budget_seconds = 0\n")
+                .unwrap();
+        assert_eq!(config.enrich.sources, vec!["crossref"]);
+        assert_eq!(config.enrich.concurrency, 4);
+        assert!(config.enrich.options().time_budget.is_none());
+    }
+
+    #[test]
+    fn test_offline_config() {
+        assert!(!Config::without_env().offline);
+
+        let config: Config = toml::from_str("offline = true\n").unwrap();
+        assert!(config.offline);
+        let config: Config = toml::from_str("[cache]\nenabled = true\n").unwrap();
+        assert!(!config.offline);
     }
 
     #[test]
@@ -0,0 +1,336 @@
+//! CSL items: the CSL-JSON data a style renders.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::CslError;
+use crate::models::Paper;
+
+/// Variables that hold names
+const NAME_VARIABLES: &[&str] = &[
+    "author",
+    "chair",
+    "collection-editor",
+    "compiler",
+    "composer",
+    "container-author",
+    "contributor",
+    "curator",
+    "director",
+    "editor",
+    "editorial-director",
+    "executive-producer",
+    "guest",
+    "host",
+    "illustrator",
+    "interviewer",
+    "narrator",
+    "organizer",
+    "original-author",
+    "performer",
+    "producer",
+    "recipient",
+    "reviewed-author",
+    "script-writer",
+    "series-creator",
+    "translator",
+];
+
+/// Variables that hold dates
+const DATE_VARIABLES: &[&str] = &[
+    "accessed",
+    "available-date",
+    "event-date",
+    "issued",
+    "original-date",
+    "submitted",
+];
+
+/// A personal or institutional name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Name {
+    pub family: String,
+    pub given: String,
+    pub dropping_particle: String,
+    pub non_dropping_particle: String,
+    pub suffix: String,
+    /// Institutional names and names that can't be split
+    pub literal: String,
+}
+
+impl Name {
+    fn from_json(value: &Value) -> Option<Self> {
+        let part = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_str)
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        let name = Name {
+            family: part("family"),
+            given: part("given"),
+            dropping_particle: part("dropping-particle"),
+            non_dropping_particle: part("non-dropping-particle"),
+            suffix: part("suffix"),
+            literal: part("literal"),
+        };
+        (!name.family.is_empty() || !name.literal.is_empty() || !name.given.is_empty())
+            .then_some(name)
+    }
+
+    /// The family name with its non-dropping particle, e.g. "van Gogh"
+    pub fn full_family(&self) -> String {
+        join_words([self.non_dropping_particle.as_str(), self.family.as_str()])
+    }
+}
+
+fn join_words<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    parts
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A date or date range
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Date {
+    /// Year, month and day of the start; month and day are 0 when unknown
+    pub start: [i32; 3],
+    /// End of a range
+    pub end: Option<[i32; 3]>,
+    /// Season (1-4) for dates like "Spring 2020"
+    pub season: Option<u32>,
+    /// Approximate date
+    pub circa: bool,
+    /// Text to print as is when the date could not be parsed
+    pub literal: Option<String>,
+}
+
+impl Date {
+    fn from_json(value: &Value) -> Option<Self> {
+        let mut date = Date {
+            circa: value.get("circa").is_some_and(|c| match c {
+                Value::Bool(b) => *b,
+                Value::Number(n) => n.as_i64() != Some(0),
+                Value::String(s) => !s.is_empty(),
+                _ => false,
+            }),
+            season: value
+                .get("season")
+                .and_then(number)
+                .filter(|s| (1..=4).contains(s))
+                .map(|s| s as u32),
+            ..Default::default()
+        };
+
+        if let Some(ranges) = value.get("date-parts").and_then(Value::as_array) {
+            let parts: Vec<[i32; 3]> = ranges.iter().filter_map(date_parts).collect();
+            date.start = *parts.first()?;
+            date.end = parts.get(1).copied().filter(|end| *end != date.start);
+            return Some(date);
+        }
+
+        let raw = value
+            .get("raw")
+            .or_else(|| value.get("literal"))
+            .and_then(Value::as_str)?
+            .trim();
+        let mut ranges = raw.split('/').map(parse_iso);
+        match ranges.next().flatten() {
+            Some(start) => {
+                date.start = start;
+                date.end = ranges.next().flatten();
+            }
+            None => date.literal = Some(raw.to_string()),
+        }
+        Some(date)
+    }
+
+    /// Sort key: `YYYYYMMDD` with a sign-preserving year
+    pub(super) fn sort_key(&self) -> String {
+        let [year, month, day] = self.start;
+        format!("{:05}{:02}{:02}", year + 50000, month, day)
+    }
+}
+
+/// `[2020, 5, 1]`, with numbers or numeric strings
+fn date_parts(value: &Value) -> Option<[i32; 3]> {
+    let parts = value.as_array()?;
+    let part = |i: usize| parts.get(i).and_then(number).unwrap_or(0) as i32;
+    let year = parts.first().and_then(number)? as i32;
+    Some([year, part(1), part(2)])
+}
+
+fn number(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// `2020`, `2020-05` or `2020-05-01`
+fn parse_iso(text: &str) -> Option<[i32; 3]> {
+    let mut parts = text.trim().split('-');
+    let year = parts.next()?.parse().ok()?;
+    let mut next = || parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    Some([year, next(), next()])
+}
+
+/// A CSL item: one entry to cite
+#[derive(Debug, Clone, Default)]
+pub struct Item {
+    /// Item ID, unique within a bibliography
+    pub id: String,
+    /// CSL item type, e.g. `article-journal`
+    pub kind: String,
+    pub(super) variables: HashMap<String, String>,
+    pub(super) names: HashMap<String, Vec<Name>>,
+    pub(super) dates: HashMap<String, Date>,
+}
+
+impl Item {
+    /// Build an item from a CSL-JSON object
+    pub fn from_json(value: &Value) -> Result<Self, CslError> {
+        let Value::Object(map) = value else {
+            return Err(CslError::Invalid(
+                "a CSL item must be a JSON object".to_string(),
+            ));
+        };
+        let mut item = Item {
+            kind: "document".to_string(),
+            ..Default::default()
+        };
+        for (key, value) in map {
+            match key.as_str() {
+                "id" => item.id = text(value).unwrap_or_default(),
+                "type" => item.kind = text(value).unwrap_or_else(|| "document".to_string()),
+                key if NAME_VARIABLES.contains(&key) => {
+                    let names: Vec<Name> = value
+                        .as_array()
+                        .map(|names| names.iter().filter_map(Name::from_json).collect())
+                        .unwrap_or_default();
+                    if !names.is_empty() {
+                        item.names.insert(key.to_string(), names);
+                    }
+                }
+                key if DATE_VARIABLES.contains(&key) => {
+                    if let Some(date) = Date::from_json(value) {
+                        item.dates.insert(key.to_string(), date);
+                    }
+                }
+                _ => {
+                    if let Some(text) = text(value) {
+                        item.variables.insert(key.to_string(), text);
+                    }
+                }
+            }
+        }
+        Ok(item)
+    }
+
+    /// Build items from a CSL-JSON array
+    pub fn parse_all(content: &str) -> Result<Vec<Self>, CslError> {
+        let value: Value = serde_json::from_str(content)
+            .map_err(|e| CslError::Invalid(format!("invalid CSL-JSON: {}", e)))?;
+        match value {
+            Value::Array(items) => items.iter().map(Self::from_json).collect(),
+            item => Ok(vec![Self::from_json(&item)?]),
+        }
+    }
+
+    /// Build an item from a paper, as it would be exported to CSL-JSON
+    pub fn from_paper(paper: &Paper) -> Self {
+        Self::from_papers(std::slice::from_ref(paper)).remove(0)
+    }
+
+    /// Build items from papers, with unique cite keys as IDs
+    pub fn from_papers(papers: &[Paper]) -> Vec<Self> {
+        crate::formats::csl_items(papers)
+            .iter()
+            .filter_map(|value| Self::from_json(value).ok())
+            .collect()
+    }
+
+    /// A text or number variable
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.variables
+            .get(name)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// A name variable
+    pub fn names(&self, name: &str) -> &[Name] {
+        self.names.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// A date variable
+    pub fn date(&self, name: &str) -> Option<&Date> {
+        self.dates.get(name)
+    }
+
+    /// Whether a variable of any kind has a value
+    pub(super) fn has(&self, name: &str) -> bool {
+        self.variable(name).is_some()
+            || !self.names(name).is_empty()
+            || self.dates.contains_key(name)
+    }
+
+    pub(super) fn is_name_variable(name: &str) -> bool {
+        NAME_VARIABLES.contains(&name)
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_item_from_json() {
+        let item = Item::from_json(&json!({
+            "id": "doe2020",
+            "type": "article-journal",
+            "author": [
+                {"family": "Gogh", "given": "Vincent", "non-dropping-particle": "van"},
+                {"literal": "WHO"},
+                {}
+            ],
+            "issued": {"date-parts": [[2020, "5"], [2021]]},
+            "accessed": {"raw": "2024-01-02"},
+            "original-date": {"literal": "Spring 1850"},
+            "volume": 12,
+            "title": " A title "
+        }))
+        .unwrap();
+        assert_eq!(item.id, "doe2020");
+        assert_eq!(item.kind, "article-journal");
+        assert_eq!(item.names("author").len(), 2);
+        assert_eq!(item.names("author")[0].full_family(), "van Gogh");
+        assert_eq!(item.date("issued").unwrap().start, [2020, 5, 0]);
+        assert_eq!(item.date("issued").unwrap().end, Some([2021, 0, 0]));
+        assert_eq!(item.date("accessed").unwrap().start, [2024, 1, 2]);
+        assert_eq!(
+            item.date("original-date").unwrap().literal.as_deref(),
+            Some("Spring 1850")
+        );
+        assert_eq!(item.variable("volume"), Some("12"));
+        assert_eq!(item.variable("title"), Some("A title"));
+        assert!(item.has("issued") && !item.has("editor"));
+
+        assert!(Item::from_json(&json!([1])).is_err());
+    }
+}
@@ -0,0 +1,328 @@
+//! CSL locale files: terms, ordinals and localized date formats.
+
+use std::collections::HashMap;
+
+use super::style::{parse_date, DateElement, TermForm};
+use super::xml::{self, Node};
+use super::CslError;
+
+/// The subset of the standard `locales-en-US.xml` used when no locale is given
+const EN_US: &str = r#"<locale xml:lang="en-US">
+  <style-options punctuation-in-quote="true"/>
+  <date form="text">
+    <date-part name="month" suffix=" "/>
+    <date-part name="day" suffix=", "/>
+    <date-part name="year"/>
+  </date>
+  <date form="numeric">
+    <date-part name="month" form="numeric-leading-zeros" suffix="/"/>
+    <date-part name="day" form="numeric-leading-zeros" suffix="/"/>
+    <date-part name="year"/>
+  </date>
+  <terms>
+    <term name="accessed">accessed</term>
+    <term name="and">and</term>
+    <term name="and" form="symbol">&amp;</term>
+    <term name="and others">and others</term>
+    <term name="anonymous">anonymous</term>
+    <term name="anonymous" form="short">anon.</term>
+    <term name="at">at</term>
+    <term name="available at">available at</term>
+    <term name="by">by</term>
+    <term name="circa">circa</term>
+    <term name="circa" form="short">c.</term>
+    <term name="cited">cited</term>
+    <term name="edition"><single>edition</single><multiple>editions</multiple></term>
+    <term name="edition" form="short">ed.</term>
+    <term name="et-al">et al.</term>
+    <term name="forthcoming">forthcoming</term>
+    <term name="from">from</term>
+    <term name="ibid">ibid.</term>
+    <term name="in">in</term>
+    <term name="in press">in press</term>
+    <term name="internet">internet</term>
+    <term name="no date">no date</term>
+    <term name="no date" form="short">n.d.</term>
+    <term name="online">online</term>
+    <term name="presented at">presented at the</term>
+    <term name="reference"><single>reference</single><multiple>references</multiple></term>
+    <term name="retrieved">retrieved</term>
+    <term name="scale">scale</term>
+    <term name="version">version</term>
+    <term name="ad">AD</term>
+    <term name="bc">BC</term>
+    <term name="open-quote">“</term>
+    <term name="close-quote">”</term>
+    <term name="open-inner-quote">‘</term>
+    <term name="close-inner-quote">’</term>
+    <term name="page-range-delimiter">–</term>
+    <term name="ordinal">th</term>
+    <term name="ordinal-01">st</term>
+    <term name="ordinal-02">nd</term>
+    <term name="ordinal-03">rd</term>
+    <term name="ordinal-11">th</term>
+    <term name="ordinal-12">th</term>
+    <term name="ordinal-13">th</term>
+    <term name="long-ordinal-01">first</term>
+    <term name="long-ordinal-02">second</term>
+    <term name="long-ordinal-03">third</term>
+    <term name="long-ordinal-04">fourth</term>
+    <term name="long-ordinal-05">fifth</term>
+    <term name="long-ordinal-06">sixth</term>
+    <term name="long-ordinal-07">seventh</term>
+    <term name="long-ordinal-08">eighth</term>
+    <term name="long-ordinal-09">ninth</term>
+    <term name="long-ordinal-10">tenth</term>
+    <term name="book"><single>book</single><multiple>books</multiple></term>
+    <term name="chapter"><single>chapter</single><multiple>chapters</multiple></term>
+    <term name="figure"><single>figure</single><multiple>figures</multiple></term>
+    <term name="issue"><single>issue</single><multiple>issues</multiple></term>
+    <term name="line"><single>line</single><multiple>lines</multiple></term>
+    <term name="note"><single>note</single><multiple>notes</multiple></term>
+    <term name="page"><single>page</single><multiple>pages</multiple></term>
+    <term name="paragraph"><single>paragraph</single><multiple>paragraphs</multiple></term>
+    <term name="section"><single>section</single><multiple>sections</multiple></term>
+    <term name="volume"><single>volume</single><multiple>volumes</multiple></term>
+    <term name="book" form="short"><single>bk.</single><multiple>bks.</multiple></term>
+    <term name="chapter" form="short"><single>chap.</single><multiple>chaps.</multiple></term>
+    <term name="figure" form="short"><single>fig.</single><multiple>figs.</multiple></term>
+    <term name="issue" form="short"><single>no.</single><multiple>nos.</multiple></term>
+    <term name="line" form="short"><single>l.</single><multiple>ll.</multiple></term>
+    <term name="note" form="short"><single>n.</single><multiple>nn.</multiple></term>
+    <term name="page" form="short"><single>p.</single><multiple>pp.</multiple></term>
+    <term name="paragraph" form="short"><single>para.</single><multiple>paras.</multiple></term>
+    <term name="section" form="short"><single>sec.</single><multiple>secs.</multiple></term>
+    <term name="volume" form="short"><single>vol.</single><multiple>vols.</multiple></term>
+    <term name="number-of-pages"><single>page</single><multiple>pages</multiple></term>
+    <term name="number-of-pages" form="short"><single>p.</single><multiple>pp.</multiple></term>
+    <term name="number-of-volumes"><single>volume</single><multiple>volumes</multiple></term>
+    <term name="number-of-volumes" form="short"><single>vol.</single><multiple>vols.</multiple></term>
+    <term name="collection-editor"><single>editor</single><multiple>editors</multiple></term>
+    <term name="container-author"><single>author</single><multiple>authors</multiple></term>
+    <term name="director"><single>director</single><multiple>directors</multiple></term>
+    <term name="editor"><single>editor</single><multiple>editors</multiple></term>
+    <term name="editorial-director"><single>editor</single><multiple>editors</multiple></term>
+    <term name="illustrator"><single>illustrator</single><multiple>illustrators</multiple></term>
+    <term name="translator"><single>translator</single><multiple>translators</multiple></term>
+    <term name="director" form="short"><single>dir.</single><multiple>dirs.</multiple></term>
+    <term name="editor" form="short"><single>ed.</single><multiple>eds.</multiple></term>
+    <term name="collection-editor" form="short"><single>ed.</single><multiple>eds.</multiple></term>
+    <term name="editorial-director" form="short"><single>ed.</single><multiple>eds.</multiple></term>
+    <term name="illustrator" form="short"><single>ill.</single><multiple>ills.</multiple></term>
+    <term name="translator" form="short"><single>tran.</single><multiple>trans.</multiple></term>
+    <term name="container-author" form="verb">by</term>
+    <term name="director" form="verb">directed by</term>
+    <term name="editor" form="verb">edited by</term>
+    <term name="editorial-director" form="verb">edited by</term>
+    <term name="illustrator" form="verb">illustrated by</term>
+    <term name="interviewer" form="verb">interview by</term>
+    <term name="recipient" form="verb">to</term>
+    <term name="reviewed-author" form="verb">by</term>
+    <term name="translator" form="verb">translated by</term>
+    <term name="editortranslator" form="verb">edited &amp; translated by</term>
+    <term name="editor" form="verb-short">ed. by</term>
+    <term name="translator" form="verb-short">trans. by</term>
+    <term name="month-01">January</term>
+    <term name="month-02">February</term>
+    <term name="month-03">March</term>
+    <term name="month-04">April</term>
+    <term name="month-05">May</term>
+    <term name="month-06">June</term>
+    <term name="month-07">July</term>
+    <term name="month-08">August</term>
+    <term name="month-09">September</term>
+    <term name="month-10">October</term>
+    <term name="month-11">November</term>
+    <term name="month-12">December</term>
+    <term name="month-01" form="short">Jan.</term>
+    <term name="month-02" form="short">Feb.</term>
+    <term name="month-03" form="short">Mar.</term>
+    <term name="month-04" form="short">Apr.</term>
+    <term name="month-05" form="short">May</term>
+    <term name="month-06" form="short">Jun.</term>
+    <term name="month-07" form="short">Jul.</term>
+    <term name="month-08" form="short">Aug.</term>
+    <term name="month-09" form="short">Sep.</term>
+    <term name="month-10" form="short">Oct.</term>
+    <term name="month-11" form="short">Nov.</term>
+    <term name="month-12" form="short">Dec.</term>
+    <term name="season-01">Spring</term>
+    <term name="season-02">Summer</term>
+    <term name="season-03">Autumn</term>
+    <term name="season-04">Winter</term>
+  </terms>
+</locale>"#;
+
+/// A term's singular and plural forms
+#[derive(Debug, Clone)]
+struct Term {
+    single: String,
+    multiple: String,
+}
+
+/// Terms and date formats for one language
+///
+/// Loaded from a standard CSL locale file (`locales-xx-XX.xml`) with
+/// [`Locale::from_file`]. Anything a locale leaves out falls back to
+/// [`Locale::en_us`] when the [`Processor`](super::Processor) is built.
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    /// Language tag, e.g. `en-US`
+    pub lang: Option<String>,
+    terms: HashMap<(String, TermForm), Term>,
+    dates: HashMap<String, DateElement>,
+    punctuation_in_quote: Option<bool>,
+}
+
+impl Locale {
+    /// Parse a locale from its XML
+    pub fn parse(xml: &str) -> Result<Self, CslError> {
+        let root = xml::parse(xml)?;
+        if root.name != "locale" {
+            return Err(CslError::Invalid(format!(
+                "expected a <locale> element, found <{}>",
+                root.name
+            )));
+        }
+        Self::from_node(&root)
+    }
+
+    /// Load a locale from a CSL locale file
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, CslError> {
+        let path = path.as_ref();
+        let xml = std::fs::read_to_string(path).map_err(|source| CslError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse(&xml)
+    }
+
+    /// The built-in American English locale
+    pub fn en_us() -> Self {
+        Self::parse(EN_US).expect("built-in locale is valid")
+    }
+
+    /// A `<locale>` element of a locale file or style
+    pub(super) fn from_node(node: &Node) -> Result<Self, CslError> {
+        let mut locale = Locale {
+            lang: node.attr("lang").map(str::to_string),
+            punctuation_in_quote: node
+                .child("style-options")
+                .and_then(|o| o.attr("punctuation-in-quote"))
+                .map(|v| v == "true"),
+            ..Default::default()
+        };
+        for term in node.children_named("terms").flat_map(|t| &t.children) {
+            let Some(name) = term.attr("name") else {
+                continue;
+            };
+            let (single, multiple) = match (term.child("single"), term.child("multiple")) {
+                (Some(single), Some(multiple)) => (single.text.clone(), multiple.text.clone()),
+                (Some(single), None) => (single.text.clone(), single.text.clone()),
+                _ => (term.text.clone(), term.text.clone()),
+            };
+            locale.terms.insert(
+                (name.to_string(), TermForm::parse(term.attr("form"))),
+                Term { single, multiple },
+            );
+        }
+        for date in node.children_named("date") {
+            if let Some(form) = date.attr("form") {
+                locale.dates.insert(form.to_string(), parse_date(date)?);
+            }
+        }
+        Ok(locale)
+    }
+
+    /// Override terms, dates and options with those defined in `other`
+    pub fn merge(&mut self, other: &Locale) {
+        // A locale's ordinal terms replace all inherited ones (CSL 1.0.1)
+        let is_ordinal = |name: &str| name.starts_with("ordinal");
+        if other.terms.keys().any(|(name, _)| is_ordinal(name)) {
+            self.terms.retain(|(name, _), _| !is_ordinal(name));
+        }
+        self.terms
+            .extend(other.terms.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self.dates
+            .extend(other.dates.iter().map(|(k, v)| (k.clone(), v.clone())));
+        if other.punctuation_in_quote.is_some() {
+            self.punctuation_in_quote = other.punctuation_in_quote;
+        }
+        if other.lang.is_some() {
+            self.lang = other.lang.clone();
+        }
+    }
+
+    /// A term in the given form, falling back to less specific forms
+    pub(super) fn term(&self, name: &str, form: TermForm, plural: bool) -> Option<&str> {
+        let mut form = Some(form);
+        while let Some(current) = form {
+            if let Some(term) = self.terms.get(&(name.to_string(), current)) {
+                return Some(if plural { &term.multiple } else { &term.single });
+            }
+            form = current.fallback();
+        }
+        None
+    }
+
+    /// A localized date format (`text` or `numeric`)
+    pub(super) fn date(&self, form: &str) -> Option<&DateElement> {
+        self.dates.get(form)
+    }
+
+    /// Whether trailing commas and periods go inside closing quotes
+    pub(super) fn punctuation_in_quote(&self) -> bool {
+        self.punctuation_in_quote.unwrap_or(false)
+    }
+
+    /// `n` as an ordinal, e.g. `2nd`, or `second` in long form
+    pub(super) fn ordinal(&self, n: u64, long: bool) -> String {
+        if long && (1..=10).contains(&n) {
+            if let Some(term) = self.term(&format!("long-ordinal-{:02}", n), TermForm::Long, false)
+            {
+                return term.to_string();
+            }
+        }
+        let suffix = [n % 100, n % 10]
+            .into_iter()
+            .find_map(|k| self.term(&format!("ordinal-{:02}", k), TermForm::Long, false))
+            .or_else(|| self.term("ordinal", TermForm::Long, false))
+            .unwrap_or_default();
+        format!("{}{}", n, suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terms_and_ordinals() {
+        let mut locale = Locale::en_us();
+        assert_eq!(locale.term("page", TermForm::Short, true), Some("pp."));
+        assert_eq!(locale.term("and", TermForm::Symbol, false), Some("&"));
+        // verb-short falls back to verb, then long
+        assert_eq!(locale.term("by", TermForm::VerbShort, false), Some("by"));
+        assert_eq!(locale.term("nonexistent", TermForm::Long, false), None);
+
+        assert_eq!(locale.ordinal(1, false), "1st");
+        assert_eq!(locale.ordinal(12, false), "12th");
+        assert_eq!(locale.ordinal(22, false), "22nd");
+        assert_eq!(locale.ordinal(104, false), "104th");
+        assert_eq!(locale.ordinal(3, true), "third");
+
+        let german = Locale::parse(
+            r#"<locale xml:lang="de-DE">
+              <style-options punctuation-in-quote="false"/>
+              <terms><term name="and">und</term></terms>
+            </locale>"#,
+        )
+        .unwrap();
+        locale.merge(&german);
+        assert_eq!(locale.lang.as_deref(), Some("de-DE"));
+        assert_eq!(locale.term("and", TermForm::Long, false), Some("und"));
+        assert_eq!(locale.term("et-al", TermForm::Long, false), Some("et al."));
+        assert!(!locale.punctuation_in_quote());
+        assert!(locale.date("text").is_some());
+    }
+}
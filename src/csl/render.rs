@@ -0,0 +1,1159 @@
+//! Rendering items with a style's elements.
+
+use std::collections::HashSet;
+
+use super::item::{Item, Name};
+use super::locale::Locale;
+use super::style::{
+    Condition, DateElement, DatePart, Element, Formatting, Layout, Match, NameOptions, NamePart,
+    NamesElement, NumberForm, Plural, Style, TermForm, Test, TextCase, TextSource,
+};
+use super::OutputFormat;
+
+/// Deepest macro nesting before a macro is taken to be recursive
+const MAX_MACRO_DEPTH: usize = 32;
+
+/// Words title case leaves in lowercase unless they start the text
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "down", "for", "from", "in", "into", "nor", "of",
+    "on", "onto", "or", "over", "so", "the", "till", "to", "up", "via", "with", "yet",
+];
+
+/// Rendering state of one item, set up by disambiguation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct ItemState {
+    pub citation_number: usize,
+    pub year_suffix: Option<usize>,
+    /// Names shown beyond `et-al-use-first`
+    pub extra_names: usize,
+    /// Given names to add to names: 0 none, 1 initials, 2 full given names
+    pub given_names: u8,
+    /// Value for `disambiguate="true"` conditions
+    pub disambiguate: bool,
+}
+
+/// Mutable state while rendering one item
+struct Context<'a> {
+    item: &'a Item,
+    state: &'a ItemState,
+    layout: &'a Layout,
+    bibliography: bool,
+    sort_mode: bool,
+    /// Variables a group asked for, and how many of them had a value
+    called: usize,
+    rendered: usize,
+    /// Variables used by a `<substitute>`, which are not rendered again
+    suppressed: HashSet<String>,
+    /// Variables rendered while recording for a substitution
+    recording: Option<Vec<String>>,
+    year_suffix_done: bool,
+    /// Output of the first `<names>`, for `subsequent-author-substitute`
+    first_names: Option<String>,
+    /// The previous entry's first names and what to replace them with when
+    /// this entry's are the same
+    author_substitute: Option<(&'a str, &'a str)>,
+    depth: usize,
+}
+
+/// Renders items with a style and locale
+pub(super) struct Renderer<'a> {
+    pub style: &'a Style,
+    pub locale: &'a Locale,
+    pub format: OutputFormat,
+}
+
+impl<'a> Renderer<'a> {
+    /// One item with a layout's elements, without the layout's affixes
+    pub fn item(
+        &self,
+        item: &Item,
+        state: &ItemState,
+        layout: &Layout,
+        bibliography: bool,
+    ) -> String {
+        self.entry(item, state, layout, bibliography, None).0
+    }
+
+    /// One item, replacing its first names with a substitute when they match
+    /// `author_substitute.0`
+    ///
+    /// Returns the output and the rendered first names.
+    pub fn entry<'b>(
+        &self,
+        item: &'b Item,
+        state: &'b ItemState,
+        layout: &'b Layout,
+        bibliography: bool,
+        author_substitute: Option<(&'b str, &'b str)>,
+    ) -> (String, Option<String>) {
+        let mut ctx = Context::new(item, state, layout, bibliography);
+        ctx.author_substitute = author_substitute;
+        let output = self.elements(&layout.elements, "", &mut ctx);
+        (output, ctx.first_names)
+    }
+
+    /// Sort key values for an item, one per key of the layout's sort
+    pub fn sort_keys(&self, item: &Item, state: &ItemState, layout: &Layout) -> Vec<String> {
+        layout
+            .sort
+            .iter()
+            .map(|key| {
+                let mut ctx = Context::new(item, state, layout, true);
+                ctx.sort_mode = true;
+                match &key.source {
+                    TextSource::Variable(name) => self.sort_variable(name, &mut ctx),
+                    TextSource::Macro(name) => {
+                        let text = self.call_macro(name, &mut ctx);
+                        text.trim_start_matches(|c: char| !c.is_alphanumeric())
+                            .to_lowercase()
+                    }
+                    _ => String::new(),
+                }
+            })
+            .collect()
+    }
+
+    fn sort_variable(&self, name: &str, ctx: &mut Context<'_>) -> String {
+        let item = ctx.item;
+        if name == "citation-number" {
+            return format!("{:010}", ctx.state.citation_number);
+        }
+        if Item::is_name_variable(name) {
+            return item
+                .names(name)
+                .iter()
+                .map(|n| self.name(n, &NameOptions::default(), ctx, None, None, true))
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase();
+        }
+        if let Some(date) = item.date(name) {
+            return date.sort_key();
+        }
+        match item.variable(name) {
+            Some(value) => match value.parse::<u64>() {
+                Ok(n) => format!("{:020}", n),
+                Err(_) => value.to_lowercase(),
+            },
+            None => String::new(),
+        }
+    }
+
+    /// Final touches on a complete citation or entry
+    pub fn finish(&self, text: &str) -> String {
+        let mut text = text.trim().to_string();
+        if self.locale.punctuation_in_quote() {
+            if let Some(close) = self.locale.term("close-quote", TermForm::Long, false) {
+                let close = self.escape(close);
+                if !close.is_empty() {
+                    for punct in [".", ","] {
+                        text = text.replace(
+                            &format!("{}{}", close, punct),
+                            &format!("{}{}", punct, close),
+                        );
+                    }
+                }
+            }
+        }
+        text
+    }
+
+    fn elements(&self, elements: &[Element], delimiter: &str, ctx: &mut Context<'_>) -> String {
+        let mut out = String::new();
+        for element in elements {
+            let text = self.element(element, ctx);
+            if text.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                append(&mut out, &self.escape(delimiter));
+            }
+            append(&mut out, &text);
+        }
+        out
+    }
+
+    fn element(&self, element: &Element, ctx: &mut Context<'_>) -> String {
+        match element {
+            Element::Text {
+                source,
+                form,
+                plural,
+                quotes,
+                strip_periods,
+                text_case,
+                formatting,
+            } => {
+                let text = match source {
+                    TextSource::Variable(name) => {
+                        let value = self.variable(name, *form, ctx);
+                        self.escape(&value)
+                    }
+                    TextSource::Macro(name) => self.call_macro(name, ctx),
+                    TextSource::Term(name) => {
+                        self.escape(self.locale.term(name, *form, *plural).unwrap_or_default())
+                    }
+                    TextSource::Value(value) => self.escape(value),
+                };
+                let text = self.finish_text(text, *strip_periods, *text_case);
+                let text = if *quotes && !text.is_empty() {
+                    self.quote(&text)
+                } else {
+                    text
+                };
+                self.format(text, formatting)
+            }
+            Element::Number {
+                variable,
+                form,
+                text_case,
+                formatting,
+            } => {
+                let value = self.variable(variable, TermForm::Long, ctx);
+                let text = if is_numeric(&value) {
+                    self.number(&value, *form)
+                } else {
+                    value
+                };
+                let text = self.finish_text(self.escape(&text), false, *text_case);
+                self.format(text, formatting)
+            }
+            Element::Label {
+                variable,
+                form,
+                plural,
+                strip_periods,
+                text_case,
+                formatting,
+            } => {
+                let Some(value) = self.raw_variable(variable, ctx) else {
+                    return String::new();
+                };
+                let plural = match plural {
+                    Plural::Always => true,
+                    Plural::Never => false,
+                    Plural::Contextual => is_plural(variable, &value),
+                };
+                let term = self.label_term(variable);
+                let text = self.locale.term(term, *form, plural).unwrap_or_default();
+                let text = self.finish_text(self.escape(text), *strip_periods, *text_case);
+                self.format(text, formatting)
+            }
+            Element::Date(date) => self.date(date, ctx),
+            Element::Names(names) => self.names(names, ctx, None),
+            Element::Group {
+                children,
+                delimiter,
+                formatting,
+            } => {
+                let (called, rendered) = (ctx.called, ctx.rendered);
+                ctx.called = 0;
+                ctx.rendered = 0;
+                let text = self.elements(children, delimiter, ctx);
+                let suppress = ctx.called > 0 && ctx.rendered == 0;
+                ctx.called += called;
+                ctx.rendered += rendered;
+                if suppress {
+                    String::new()
+                } else {
+                    self.format(text, formatting)
+                }
+            }
+            Element::Choose { branches } => {
+                match branches
+                    .iter()
+                    .find(|(condition, _)| self.test(condition, ctx))
+                {
+                    Some((_, children)) => self.elements(children, "", ctx),
+                    None => String::new(),
+                }
+            }
+        }
+    }
+
+    fn call_macro(&self, name: &str, ctx: &mut Context<'_>) -> String {
+        let Some(elements) = self.style.macros.get(name) else {
+            return String::new();
+        };
+        if ctx.depth >= MAX_MACRO_DEPTH {
+            return String::new();
+        }
+        ctx.depth += 1;
+        let text = self.elements(elements, "", ctx);
+        ctx.depth -= 1;
+        text
+    }
+
+    /// A variable's value for `<text>` and `<number>`, counting it for groups
+    fn variable(&self, name: &str, form: TermForm, ctx: &mut Context<'_>) -> String {
+        ctx.called += 1;
+        let value = match name {
+            "year-suffix" => ctx.state.year_suffix.map(suffix_letters),
+            "citation-number" => Some(ctx.state.citation_number.to_string()),
+            _ if form == TermForm::Short => self
+                .raw_variable(&format!("{}-short", name), ctx)
+                .or_else(|| self.raw_variable(name, ctx)),
+            _ => self.raw_variable(name, ctx),
+        };
+        let Some(value) = value else {
+            return String::new();
+        };
+        ctx.rendered += 1;
+        if let Some(recorded) = ctx.recording.as_mut() {
+            recorded.push(name.to_string());
+        }
+        if name == "year-suffix" {
+            ctx.year_suffix_done = true;
+        }
+        if name == "page" || name == "locator" {
+            return self.page_range(&value);
+        }
+        value
+    }
+
+    fn raw_variable(&self, name: &str, ctx: &Context<'_>) -> Option<String> {
+        if ctx.suppressed.contains(name) {
+            return None;
+        }
+        ctx.item.variable(name).map(str::to_string)
+    }
+
+    fn label_term<'b>(&self, variable: &'b str) -> &'b str {
+        match variable {
+            "locator" => "page",
+            other => other,
+        }
+    }
+
+    fn page_range(&self, value: &str) -> String {
+        let delimiter = self
+            .locale
+            .term("page-range-delimiter", TermForm::Long, false)
+            .unwrap_or("–");
+        let value = value.replace("--", "-");
+        let mut out = String::new();
+        let chars: Vec<char> = value.chars().collect();
+        for (i, &c) in chars.iter().enumerate() {
+            let between_digits = i > 0
+                && chars[i - 1].is_ascii_alphanumeric()
+                && chars.get(i + 1).is_some_and(|n| n.is_ascii_alphanumeric());
+            if (c == '-' || c == '–') && between_digits {
+                out.push_str(delimiter);
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    fn number(&self, value: &str, form: NumberForm) -> String {
+        let mut out = String::new();
+        let mut digits = String::new();
+        let flush = |digits: &mut String, out: &mut String| {
+            if digits.is_empty() {
+                return;
+            }
+            let n: u64 = digits.parse().unwrap_or(0);
+            out.push_str(&match form {
+                NumberForm::Numeric => digits.clone(),
+                NumberForm::Ordinal => self.locale.ordinal(n, false),
+                NumberForm::LongOrdinal => self.locale.ordinal(n, true),
+                NumberForm::Roman => roman(n),
+            });
+            digits.clear();
+        };
+        for c in value.chars() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+            } else {
+                flush(&mut digits, &mut out);
+                out.push(c);
+            }
+        }
+        flush(&mut digits, &mut out);
+        out
+    }
+
+    fn test(&self, condition: &Condition, ctx: &Context<'_>) -> bool {
+        let mut results = condition.tests.iter().map(|test| match test {
+            Test::Type(kind) => ctx.item.kind == *kind,
+            Test::Variable(name) => match name.as_str() {
+                "year-suffix" => ctx.state.year_suffix.is_some(),
+                "citation-number" => true,
+                _ => !ctx.suppressed.contains(name) && ctx.item.has(name),
+            },
+            Test::IsNumeric(name) => match name.as_str() {
+                "citation-number" => true,
+                _ => ctx.item.variable(name).is_some_and(is_numeric),
+            },
+            Test::IsUncertainDate(name) => ctx.item.date(name).is_some_and(|d| d.circa),
+            Test::Locator => false,
+            // Every citation is a first citation; positions don't apply to bibliographies
+            Test::Position(position) => !ctx.bibliography && position == "first",
+            Test::Disambiguate(value) => (!ctx.bibliography && ctx.state.disambiguate) == *value,
+        });
+        match condition.mode {
+            Match::All => results.all(|r| r),
+            Match::Any => results.any(|r| r),
+            Match::None => !results.any(|r| r),
+        }
+    }
+
+    fn date(&self, element: &DateElement, ctx: &mut Context<'_>) -> String {
+        ctx.called += 1;
+        if ctx.suppressed.contains(&element.variable) {
+            return String::new();
+        }
+        let Some(date) = ctx.item.date(&element.variable) else {
+            return String::new();
+        };
+        ctx.rendered += 1;
+        if let Some(recorded) = ctx.recording.as_mut() {
+            recorded.push(element.variable.clone());
+        }
+
+        if let Some(literal) = &date.literal {
+            let text = self.finish_text(self.escape(literal), false, element.text_case);
+            return self.format(text, &element.formatting);
+        }
+
+        let (parts, delimiter) = match element.form.as_deref().and_then(|f| self.locale.date(f)) {
+            Some(localized) => {
+                let keep: &[&str] = match element.precision {
+                    1 => &["year"],
+                    2 => &["year", "month"],
+                    _ => &["year", "month", "day"],
+                };
+                let parts: Vec<DatePart> = localized
+                    .parts
+                    .iter()
+                    .filter(|p| keep.contains(&p.name.as_str()))
+                    .map(|p| {
+                        let mut part = p.clone();
+                        if let Some(own) = element.parts.iter().find(|o| o.name == p.name) {
+                            part.refine(own);
+                        }
+                        part
+                    })
+                    .collect();
+                (parts, localized.delimiter.clone())
+            }
+            None => (element.parts.clone(), element.delimiter.clone()),
+        };
+
+        let add_suffix = element.variable == "issued"
+            && !self.style.renders_year_suffix
+            && !ctx.year_suffix_done
+            && ctx.state.year_suffix.is_some();
+        let suffix = if add_suffix {
+            ctx.year_suffix_done = true;
+            ctx.state.year_suffix.map(suffix_letters)
+        } else {
+            None
+        };
+
+        let mut text = self.date_parts(
+            date.start,
+            date.season,
+            &parts,
+            &delimiter,
+            suffix.as_deref(),
+        );
+        if let Some(end) = date.end {
+            let end = self.date_parts(end, None, &parts, &delimiter, None);
+            if !end.is_empty() {
+                let range = parts
+                    .iter()
+                    .find_map(|p| p.range_delimiter.clone())
+                    .unwrap_or_else(|| "–".to_string());
+                text = format!("{}{}{}", text, self.escape(&range), end);
+            }
+        }
+        let text = self.finish_text(text, false, element.text_case);
+        self.format(text, &element.formatting)
+    }
+
+    fn date_parts(
+        &self,
+        [year, month, day]: [i32; 3],
+        season: Option<u32>,
+        parts: &[DatePart],
+        delimiter: &str,
+        year_suffix: Option<&str>,
+    ) -> String {
+        let mut out = String::new();
+        for part in parts {
+            let value = match part.name.as_str() {
+                "year" => {
+                    let mut text = match part.form.as_deref() {
+                        Some("short") => format!("{:02}", year.rem_euclid(100)),
+                        _ => year.abs().to_string(),
+                    };
+                    if year < 0 {
+                        text.push_str(
+                            self.locale
+                                .term("bc", TermForm::Long, false)
+                                .unwrap_or("BC"),
+                        );
+                    } else if year > 0 && year < 1000 {
+                        text.push_str(
+                            self.locale
+                                .term("ad", TermForm::Long, false)
+                                .unwrap_or("AD"),
+                        );
+                    }
+                    if let Some(suffix) = year_suffix {
+                        text.push_str(suffix);
+                    }
+                    text
+                }
+                "month" if month == 0 => match season {
+                    Some(season) => self
+                        .locale
+                        .term(&format!("season-{:02}", season), TermForm::Long, false)
+                        .unwrap_or_default()
+                        .to_string(),
+                    None => continue,
+                },
+                "month" => match part.form.as_deref() {
+                    Some("numeric") => month.to_string(),
+                    Some("numeric-leading-zeros") => format!("{:02}", month),
+                    form => {
+                        let form = TermForm::parse(form);
+                        self.locale
+                            .term(&format!("month-{:02}", month), form, false)
+                            .unwrap_or_default()
+                            .to_string()
+                    }
+                },
+                "day" if day == 0 => continue,
+                "day" => match part.form.as_deref() {
+                    Some("numeric-leading-zeros") => format!("{:02}", day),
+                    Some("ordinal") => self.locale.ordinal(day as u64, false),
+                    _ => day.to_string(),
+                },
+                _ => continue,
+            };
+            let text = self.finish_text(self.escape(&value), part.strip_periods, part.text_case);
+            let text = self.format(text, &part.formatting);
+            if text.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                append(&mut out, &self.escape(delimiter));
+            }
+            append(&mut out, &text);
+        }
+        out
+    }
+
+    /// Render a `<names>` element; `parent` is the names element whose
+    /// `<substitute>` this one is in
+    fn names(
+        &self,
+        element: &NamesElement,
+        ctx: &mut Context<'_>,
+        parent: Option<&NamesElement>,
+    ) -> String {
+        // A bare `<names>` in a substitute borrows its parent's name options
+        let inherited;
+        let element = match parent {
+            Some(parent) if element.name.is_none() => {
+                inherited = NamesElement {
+                    variables: element.variables.clone(),
+                    delimiter: element
+                        .delimiter
+                        .clone()
+                        .or_else(|| parent.delimiter.clone()),
+                    formatting: element.formatting.clone(),
+                    substitute: Vec::new(),
+                    ..parent.clone()
+                };
+                &inherited
+            }
+            _ => element,
+        };
+        let options = element
+            .name
+            .clone()
+            .unwrap_or_default()
+            .inherit(&ctx.layout.names);
+
+        let mut rendered = Vec::new();
+        let mut seen: Vec<&[Name]> = Vec::new();
+        for variable in &element.variables {
+            ctx.called += 1;
+            if ctx.suppressed.contains(variable) {
+                continue;
+            }
+            let names = ctx.item.names(variable);
+            if names.is_empty() || seen.contains(&names) {
+                continue;
+            }
+            seen.push(names);
+            ctx.rendered += 1;
+            if let Some(recorded) = ctx.recording.as_mut() {
+                recorded.push(variable.clone());
+            }
+
+            let mut text = self.name_list(names, element, &options, ctx);
+            if let Some((label, before)) = &element.label {
+                let label = self.names_label(label, variable, names.len());
+                if !label.is_empty() && !text.is_empty() {
+                    text = if *before {
+                        format!("{}{}", label, text)
+                    } else {
+                        format!("{}{}", text, label)
+                    };
+                }
+            }
+            rendered.push(text);
+        }
+
+        let text = if rendered.is_empty() {
+            self.substitute(element, ctx)
+        } else {
+            let delimiter = element
+                .delimiter
+                .clone()
+                .or_else(|| options.names_delimiter.clone())
+                .unwrap_or_default();
+            let text = rendered.join(&self.escape(&delimiter));
+            self.format(text, &element.formatting)
+        };
+        if !text.is_empty() && !ctx.sort_mode && ctx.first_names.is_none() {
+            ctx.first_names = Some(text.clone());
+            if let Some((previous, substitute)) = ctx.author_substitute {
+                if previous == text {
+                    return self.escape(substitute);
+                }
+            }
+        }
+        text
+    }
+
+    fn substitute(&self, element: &NamesElement, ctx: &mut Context<'_>) -> String {
+        for substitute in &element.substitute {
+            let recording = ctx.recording.replace(Vec::new());
+            let text = match substitute {
+                Element::Names(names) => self.names(names, ctx, Some(element)),
+                other => self.element(other, ctx),
+            };
+            let used = std::mem::replace(&mut ctx.recording, recording).unwrap_or_default();
+            if !text.is_empty() {
+                if let Some(outer) = ctx.recording.as_mut() {
+                    outer.extend(used.iter().cloned());
+                }
+                ctx.suppressed.extend(used);
+                return text;
+            }
+        }
+        String::new()
+    }
+
+    fn names_label(&self, label: &Element, variable: &str, count: usize) -> String {
+        let Element::Label {
+            form,
+            plural,
+            strip_periods,
+            text_case,
+            formatting,
+            ..
+        } = label
+        else {
+            return String::new();
+        };
+        let plural = match plural {
+            Plural::Always => true,
+            Plural::Never => false,
+            Plural::Contextual => count > 1,
+        };
+        let term = self
+            .locale
+            .term(variable, *form, plural)
+            .unwrap_or_default();
+        let text = self.finish_text(self.escape(term), *strip_periods, *text_case);
+        self.format(text, formatting)
+    }
+
+    fn name_list(
+        &self,
+        names: &[Name],
+        element: &NamesElement,
+        options: &NameOptions,
+        ctx: &Context<'_>,
+    ) -> String {
+        let total = names.len();
+        let mut shown = total;
+        if let (Some(min), Some(first)) = (options.et_al_min, options.et_al_use_first) {
+            if !ctx.sort_mode && total >= min && first < total {
+                let extra = if ctx.bibliography {
+                    0
+                } else {
+                    ctx.state.extra_names
+                };
+                shown = (first + extra).min(total);
+            }
+        }
+        let et_al = shown < total;
+
+        if options.form.as_deref() == Some("count") {
+            return shown.to_string();
+        }
+
+        let rendered: Vec<String> = names[..shown]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let inverted = ctx.sort_mode
+                    || match options.name_as_sort_order.as_deref() {
+                        Some("all") => true,
+                        Some("first") => i == 0,
+                        _ => false,
+                    };
+                self.name(
+                    name,
+                    options,
+                    ctx,
+                    element.family.as_ref(),
+                    element.given.as_ref(),
+                    inverted,
+                )
+            })
+            .collect();
+
+        let delimiter = self.escape(options.delimiter.as_deref().unwrap_or(", "));
+        let inverted_first = options.name_as_sort_order.is_some() || ctx.sort_mode;
+        let contextual = |rule: Option<&str>, count: usize| match rule {
+            Some("always") => true,
+            Some("never") => false,
+            Some("after-inverted-name") => inverted_first && count <= 2,
+            _ => count >= 3,
+        };
+
+        if et_al {
+            let term = self
+                .locale
+                .term(&element.et_al_term, TermForm::Long, false)
+                .unwrap_or_default();
+            if options.et_al_use_last == Some(true) && shown + 1 < total {
+                let last = self.name(
+                    &names[total - 1],
+                    options,
+                    ctx,
+                    element.family.as_ref(),
+                    element.given.as_ref(),
+                    false,
+                );
+                return format!("{}{}… {}", rendered.join(&delimiter), delimiter, last);
+            }
+            let mut text = rendered.join(&delimiter);
+            if term.is_empty() || ctx.sort_mode {
+                return text;
+            }
+            let before = contextual(options.delimiter_precedes_et_al.as_deref(), shown + 1);
+            text.push_str(if before { &delimiter } else { " " });
+            text.push_str(&self.format(self.escape(term), &element.et_al_formatting));
+            return text;
+        }
+
+        let and = match options.and.as_deref() {
+            Some("symbol") => self.locale.term("and", TermForm::Symbol, false),
+            Some(_) => self.locale.term("and", TermForm::Long, false),
+            None => None,
+        };
+        match (rendered.as_slice(), and) {
+            ([], _) => String::new(),
+            ([only], _) => only.clone(),
+            ([init @ .., last], Some(and)) if !ctx.sort_mode => {
+                let before = contextual(options.delimiter_precedes_last.as_deref(), shown);
+                format!(
+                    "{}{}{} {}",
+                    init.join(&delimiter),
+                    if before { delimiter.as_str() } else { " " },
+                    self.escape(and),
+                    last
+                )
+            }
+            (all, _) => all.join(&delimiter),
+        }
+    }
+
+    /// One name, in display order ("Given Family") or inverted ("Family, Given")
+    fn name(
+        &self,
+        name: &Name,
+        options: &NameOptions,
+        ctx: &Context<'_>,
+        family_part: Option<&NamePart>,
+        given_part: Option<&NamePart>,
+        inverted: bool,
+    ) -> String {
+        if !name.literal.is_empty() {
+            return self.name_part(&name.literal, family_part);
+        }
+        let family = self.name_part(&name.full_family(), family_part);
+        let given_names = if ctx.bibliography {
+            0
+        } else {
+            ctx.state.given_names
+        };
+        let short = options.form.as_deref() == Some("short") && given_names == 0;
+        if short && !ctx.sort_mode {
+            return family;
+        }
+
+        let given = match &options.initialize_with {
+            Some(with) if given_names < 2 && options.initialize != Some(false) => {
+                initials(&name.given, with)
+            }
+            _ => name.given.clone(),
+        };
+        let given = join(&[&given, &name.dropping_particle], " ");
+        let given = self.name_part(&given, given_part);
+
+        if inverted {
+            let separator = self.escape(options.sort_separator.as_deref().unwrap_or(", "));
+            let mut text = join(&[&family, &given], &separator);
+            if !name.suffix.is_empty() {
+                text = format!("{}{}{}", text, separator, self.escape(&name.suffix));
+            }
+            text
+        } else {
+            join(&[&given, &family, &self.escape(&name.suffix)], " ")
+        }
+    }
+
+    fn name_part(&self, text: &str, part: Option<&NamePart>) -> String {
+        let text = self.escape(text);
+        match part {
+            Some(part) => self.format(
+                self.finish_text(text, false, part.text_case),
+                &part.formatting,
+            ),
+            None => text,
+        }
+    }
+
+    /// Apply `strip-periods` and `text-case`
+    fn finish_text(
+        &self,
+        text: String,
+        strip_periods: bool,
+        text_case: Option<TextCase>,
+    ) -> String {
+        let text = if strip_periods {
+            map_text(&text, |s| s.replace('.', ""))
+        } else {
+            text
+        };
+        match text_case {
+            Some(case) => apply_case(&text, case),
+            None => text,
+        }
+    }
+
+    fn quote(&self, text: &str) -> String {
+        let open = self
+            .locale
+            .term("open-quote", TermForm::Long, false)
+            .unwrap_or("\"");
+        let close = self
+            .locale
+            .term("close-quote", TermForm::Long, false)
+            .unwrap_or("\"");
+        format!("{}{}{}", self.escape(open), text, self.escape(close))
+    }
+
+    /// Wrap in font formatting (HTML only) and add affixes
+    fn format(&self, text: String, formatting: &Formatting) -> String {
+        if text.is_empty() {
+            return text;
+        }
+        let mut text = text;
+        if self.format == OutputFormat::Html {
+            let wrap = |text: String, on: Option<bool>, open: &str, close: &str| {
+                if on == Some(true) {
+                    format!("{}{}{}", open, text, close)
+                } else {
+                    text
+                }
+            };
+            text = wrap(text, formatting.italic, "<i>", "</i>");
+            text = wrap(text, formatting.bold, "<b>", "</b>");
+            text = wrap(
+                text,
+                formatting.small_caps,
+                "<span style=\"font-variant:small-caps;\">",
+                "</span>",
+            );
+            text = wrap(
+                text,
+                formatting.underline,
+                "<span style=\"text-decoration:underline;\">",
+                "</span>",
+            );
+            text = wrap(text, Some(formatting.superscript), "<sup>", "</sup>");
+        }
+        let mut out = self.escape(&formatting.prefix);
+        append(&mut out, &text);
+        append(&mut out, &self.escape(&formatting.suffix));
+        out
+    }
+
+    /// Apply a layout's affixes and formatting to a complete citation or entry
+    pub fn layout(&self, text: String, layout: &Layout) -> String {
+        self.format(text, &layout.formatting)
+    }
+
+    pub fn escape(&self, text: &str) -> String {
+        match self.format {
+            OutputFormat::Text => text.to_string(),
+            OutputFormat::Html => text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+        }
+    }
+}
+
+impl<'a> Context<'a> {
+    fn new(item: &'a Item, state: &'a ItemState, layout: &'a Layout, bibliography: bool) -> Self {
+        Context {
+            item,
+            state,
+            layout,
+            bibliography,
+            sort_mode: false,
+            called: 0,
+            rendered: 0,
+            suppressed: HashSet::new(),
+            recording: None,
+            year_suffix_done: false,
+            first_names: None,
+            author_substitute: None,
+            depth: 0,
+        }
+    }
+}
+
+/// Append `piece`, dropping a leading period after closing punctuation
+fn append(out: &mut String, piece: &str) {
+    let piece = match piece.strip_prefix('.') {
+        Some(rest) if last_visible(out).is_some_and(|c| matches!(c, '.' | '?' | '!')) => rest,
+        _ => piece,
+    };
+    out.push_str(piece);
+}
+
+/// Last character outside HTML tags
+fn last_visible(text: &str) -> Option<char> {
+    let mut text = text;
+    while text.ends_with('>') {
+        match text.rfind('<') {
+            Some(start) => text = &text[..start],
+            None => break,
+        }
+    }
+    text.chars().last()
+}
+
+fn join(parts: &[&str], separator: &str) -> String {
+    parts
+        .iter()
+        .filter(|p| !p.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// `John Ronald` with ". " gives "J. R."; hyphenated names keep the hyphen
+fn initials(given: &str, with: &str) -> String {
+    given
+        .split_whitespace()
+        .map(|word| {
+            word.split('-')
+                .filter_map(|part| {
+                    let first = part.chars().next()?;
+                    // Keep existing initials ("J.") and lowercase particles as they are
+                    Some(if part.len() <= 2 && part.ends_with('.') {
+                        format!("{}{}", first, with)
+                    } else if first.is_lowercase() {
+                        format!("{} ", part)
+                    } else {
+                        format!("{}{}", first, with)
+                    })
+                })
+                .map(|s| s.trim_end().to_string())
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect::<Vec<_>>()
+        .join(if with.ends_with(' ') { " " } else { "" })
+}
+
+/// `a`..`z`, then `aa`, `ab`, ...
+fn suffix_letters(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+fn roman(n: u64) -> String {
+    if n == 0 || n > 3999 {
+        return n.to_string();
+    }
+    const NUMERALS: &[(u64, &str)] = &[
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut n = n;
+    let mut out = String::new();
+    for &(value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+/// Numbers, optionally with letter affixes, separated by `-`, `,` or `&`
+fn is_numeric(value: &str) -> bool {
+    let tokens: Vec<&str> = value.split(['-', '–', ',', '&']).map(str::trim).collect();
+    !tokens.is_empty()
+        && tokens.iter().all(|token| {
+            let letters = token.trim_matches(|c: char| c.is_ascii_alphabetic());
+            !letters.is_empty() && letters.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+/// Contextual plural for labels: ranges, lists or counts above one
+fn is_plural(variable: &str, value: &str) -> bool {
+    if variable.starts_with("number-of-") {
+        return value.trim().parse::<u64>().is_ok_and(|n| n > 1);
+    }
+    value.contains(['-', '–', ',', '&'])
+}
+
+/// Apply `f` to the text outside HTML tags
+fn map_text(text: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&f(&rest[..start]));
+        let end = rest[start..]
+            .find('>')
+            .map_or(rest.len(), |e| start + e + 1);
+        out.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    out.push_str(&f(rest));
+    out
+}
+
+fn apply_case(text: &str, case: TextCase) -> String {
+    match case {
+        TextCase::Lowercase => map_text(text, str::to_lowercase),
+        TextCase::Uppercase => map_text(text, str::to_uppercase),
+        TextCase::CapitalizeFirst => capitalize_words(text, |i, _| i == 0),
+        TextCase::CapitalizeAll => capitalize_words(text, |_, _| true),
+        TextCase::Sentence => {
+            let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+            if !letters.is_empty() && letters.iter().all(|c| c.is_uppercase()) {
+                capitalize_words(&map_text(text, str::to_lowercase), |i, _| i == 0)
+            } else {
+                capitalize_words(text, |i, _| i == 0)
+            }
+        }
+        TextCase::Title => capitalize_words(text, |i, word| i == 0 || !STOP_WORDS.contains(&word)),
+    }
+}
+
+/// Uppercase the first letter of the words `pick` selects; words are counted
+/// from 0 and passed in lowercase
+fn capitalize_words(text: &str, pick: impl Fn(usize, &str) -> bool) -> String {
+    let mut index = 0;
+    map_text(text, |segment| {
+        let mut out = String::new();
+        let mut word = String::new();
+        let mut flush = |word: &mut String, out: &mut String| {
+            if word.is_empty() {
+                return;
+            }
+            let lower = word.to_lowercase();
+            // Words already containing capitals (acronyms, names) are left alone
+            let plain = word.chars().all(|c| !c.is_uppercase());
+            let mut chars = word.chars();
+            if plain && pick(index, &lower) {
+                if let Some(first) = chars.next() {
+                    out.extend(first.to_uppercase());
+                    out.push_str(chars.as_str());
+                }
+            } else {
+                out.push_str(word);
+            }
+            index += 1;
+            word.clear();
+        };
+        for c in segment.chars() {
+            if c.is_alphanumeric() || c == '\'' || c == '’' {
+                word.push(c);
+            } else {
+                flush(&mut word, &mut out);
+                out.push(c);
+            }
+        }
+        flush(&mut word, &mut out);
+        out
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(initials("John Ronald", ". "), "J. R.");
+        assert_eq!(initials("Jean-Paul", "."), "J.-P.");
+        assert_eq!(suffix_letters(0), "a");
+        assert_eq!(suffix_letters(26), "aa");
+        assert_eq!(roman(14), "xiv");
+        assert!(
+            is_numeric("12-15") && is_numeric("2a") && !is_numeric("2nd ed") && !is_numeric("")
+        );
+        assert_eq!(
+            apply_case("the art of war in <i>practice</i>", TextCase::Title),
+            "The Art of War in <i>Practice</i>"
+        );
+        assert_eq!(
+            apply_case("DEEP LEARNING", TextCase::Sentence),
+            "Deep learning"
+        );
+        assert_eq!(
+            apply_case("a DNA study", TextCase::CapitalizeFirst),
+            "A DNA study"
+        );
+
+        let mut out = "Is attention all you need?".to_string();
+        append(&mut out, ". ");
+        assert_eq!(out, "Is attention all you need? ");
+    }
+}
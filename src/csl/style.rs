@@ -0,0 +1,814 @@
+//! CSL style files: parsing a style into rendering elements.
+
+use std::collections::HashMap;
+
+use super::locale::Locale;
+use super::xml::{self, Node};
+use super::CslError;
+
+/// A parsed CSL style
+///
+/// Loaded from the XML of a `.csl` file with [`Style::parse`] or
+/// [`Style::from_file`]; rendered with a [`Processor`](super::Processor).
+#[derive(Debug, Clone)]
+pub struct Style {
+    /// Title from the style's `<info>` block
+    pub title: String,
+    /// Style ID (usually a zotero.org URL)
+    pub id: Option<String>,
+    /// Locale the style is written for (`default-locale`)
+    pub default_locale: Option<String>,
+    /// Whether citations are notes rather than in-text references
+    pub note: bool,
+    pub(super) macros: HashMap<String, Vec<Element>>,
+    /// Locale overrides in the style, with the language they apply to
+    pub(super) locales: Vec<(Option<String>, Locale)>,
+    pub(super) citation: Layout,
+    pub(super) bibliography: Option<Layout>,
+    /// Whether any element renders `year-suffix` explicitly
+    pub(super) renders_year_suffix: bool,
+}
+
+impl Style {
+    /// Parse a style from its XML
+    pub fn parse(xml: &str) -> Result<Self, CslError> {
+        let root = xml::parse(xml)?;
+        if root.name != "style" {
+            return Err(CslError::Invalid(format!(
+                "expected a <style> element, found <{}>",
+                root.name
+            )));
+        }
+
+        let info = root.child("info");
+        let info_text = |name: &str| {
+            info.and_then(|i| i.child(name))
+                .map(|n| n.text.trim().to_string())
+                .filter(|t| !t.is_empty())
+        };
+        let style_names = NameOptions::parse(&root);
+
+        let mut macros = HashMap::new();
+        for node in root.children_named("macro") {
+            let name = node
+                .attr("name")
+                .ok_or_else(|| CslError::Invalid("<macro> without a name".to_string()))?;
+            macros.insert(name.to_string(), parse_children(node)?);
+        }
+
+        let locales = root
+            .children_named("locale")
+            .map(|node| {
+                Ok((
+                    node.attr("lang").map(str::to_string),
+                    Locale::from_node(node)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, CslError>>()?;
+
+        let citation = root
+            .child("citation")
+            .ok_or_else(|| CslError::Invalid("style has no <citation>".to_string()))?;
+        let citation = Layout::parse(citation, &style_names)?;
+        let bibliography = root
+            .child("bibliography")
+            .map(|node| Layout::parse(node, &style_names))
+            .transpose()?;
+
+        let style = Style {
+            title: info_text("title").unwrap_or_else(|| "Untitled style".to_string()),
+            id: info_text("id"),
+            default_locale: root.attr("default-locale").map(str::to_string),
+            note: root.attr("class") == Some("note"),
+            renders_year_suffix: false,
+            macros,
+            locales,
+            citation,
+            bibliography,
+        };
+        style.check_macros()?;
+        Ok(Style {
+            renders_year_suffix: style.any_element(&|e| {
+                matches!(e, Element::Text { source: TextSource::Variable(v), .. } if v == "year-suffix")
+            }),
+            ..style
+        })
+    }
+
+    /// Load a style from a `.csl` file
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, CslError> {
+        let path = path.as_ref();
+        let xml = std::fs::read_to_string(path).map_err(|source| CslError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse(&xml)
+    }
+
+    /// Whether the style defines a bibliography
+    pub fn has_bibliography(&self) -> bool {
+        self.bibliography.is_some()
+    }
+
+    /// Every macro call must refer to a defined macro
+    fn check_macros(&self) -> Result<(), CslError> {
+        let mut missing = None;
+        self.walk(&mut |e| {
+            if let Element::Text {
+                source: TextSource::Macro(name),
+                ..
+            } = e
+            {
+                if !self.macros.contains_key(name) {
+                    missing.get_or_insert_with(|| name.clone());
+                }
+            }
+        });
+        let layouts = std::iter::once(&self.citation).chain(self.bibliography.as_ref());
+        for key in layouts.flat_map(|layout| &layout.sort) {
+            if let TextSource::Macro(name) = &key.source {
+                if !self.macros.contains_key(name) {
+                    missing.get_or_insert_with(|| name.clone());
+                }
+            }
+        }
+        match missing {
+            Some(name) => Err(CslError::Invalid(format!("undefined macro '{}'", name))),
+            None => Ok(()),
+        }
+    }
+
+    fn any_element(&self, predicate: &dyn Fn(&Element) -> bool) -> bool {
+        let mut found = false;
+        self.walk(&mut |e| found |= predicate(e));
+        found
+    }
+
+    /// Visit every element of the layouts and macros
+    fn walk(&self, visit: &mut dyn FnMut(&Element)) {
+        let layouts = std::iter::once(&self.citation).chain(self.bibliography.as_ref());
+        for layout in layouts {
+            walk_elements(&layout.elements, visit);
+        }
+        for elements in self.macros.values() {
+            walk_elements(elements, visit);
+        }
+    }
+}
+
+fn walk_elements(elements: &[Element], visit: &mut dyn FnMut(&Element)) {
+    for element in elements {
+        visit(element);
+        match element {
+            Element::Group { children, .. } => walk_elements(children, visit),
+            Element::Choose { branches, .. } => {
+                for (_, children) in branches {
+                    walk_elements(children, visit);
+                }
+            }
+            Element::Names(names) => walk_elements(&names.substitute, visit),
+            _ => {}
+        }
+    }
+}
+
+/// A citation or bibliography layout with its options
+#[derive(Debug, Clone)]
+pub(super) struct Layout {
+    pub elements: Vec<Element>,
+    pub formatting: Formatting,
+    pub delimiter: String,
+    pub sort: Vec<SortKey>,
+    pub names: NameOptions,
+    pub add_names: bool,
+    pub add_givenname: bool,
+    pub add_year_suffix: bool,
+    pub subsequent_author_substitute: Option<String>,
+}
+
+impl Layout {
+    fn parse(node: &Node, inherited: &NameOptions) -> Result<Self, CslError> {
+        let layout = node
+            .child("layout")
+            .ok_or_else(|| CslError::Invalid(format!("<{}> has no <layout>", node.name)))?;
+        let sort = node
+            .child("sort")
+            .map(|sort| sort.children_named("key").map(SortKey::parse).collect())
+            .unwrap_or_default();
+        let flag = |name: &str| node.attr(name) == Some("true");
+        Ok(Layout {
+            elements: parse_children(layout)?,
+            formatting: Formatting::parse(layout),
+            delimiter: layout.attr("delimiter").unwrap_or_default().to_string(),
+            sort,
+            names: NameOptions::parse(node).inherit(inherited),
+            add_names: flag("disambiguate-add-names"),
+            add_givenname: flag("disambiguate-add-givenname"),
+            add_year_suffix: flag("disambiguate-add-year-suffix"),
+            subsequent_author_substitute: node
+                .attr("subsequent-author-substitute")
+                .map(str::to_string),
+        })
+    }
+}
+
+/// A `<key>` of a `<sort>`
+#[derive(Debug, Clone)]
+pub(super) struct SortKey {
+    pub source: TextSource,
+    pub descending: bool,
+}
+
+impl SortKey {
+    fn parse(node: &Node) -> Self {
+        let source = match (node.attr("variable"), node.attr("macro")) {
+            (Some(variable), _) => TextSource::Variable(variable.to_string()),
+            (None, Some(name)) => TextSource::Macro(name.to_string()),
+            (None, None) => TextSource::Value(String::new()),
+        };
+        SortKey {
+            source,
+            descending: node.attr("sort") == Some("descending"),
+        }
+    }
+}
+
+/// A rendering element of a layout or macro
+#[derive(Debug, Clone)]
+pub(super) enum Element {
+    Text {
+        source: TextSource,
+        form: TermForm,
+        plural: bool,
+        quotes: bool,
+        strip_periods: bool,
+        text_case: Option<TextCase>,
+        formatting: Formatting,
+    },
+    Number {
+        variable: String,
+        form: NumberForm,
+        text_case: Option<TextCase>,
+        formatting: Formatting,
+    },
+    Label {
+        variable: String,
+        form: TermForm,
+        plural: Plural,
+        strip_periods: bool,
+        text_case: Option<TextCase>,
+        formatting: Formatting,
+    },
+    Date(Box<DateElement>),
+    Names(Box<NamesElement>),
+    Group {
+        children: Vec<Element>,
+        delimiter: String,
+        formatting: Formatting,
+    },
+    Choose {
+        branches: Vec<(Condition, Vec<Element>)>,
+    },
+}
+
+/// What a `<text>` element renders
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum TextSource {
+    Variable(String),
+    Macro(String),
+    Term(String),
+    Value(String),
+}
+
+/// `<date>`, either localized (`form` set) or with its own date parts
+#[derive(Debug, Clone)]
+pub(super) struct DateElement {
+    pub variable: String,
+    pub form: Option<String>,
+    /// Which parts a localized date shows: 1 = year, 2 = year-month, 3 = all
+    pub precision: usize,
+    pub parts: Vec<DatePart>,
+    pub delimiter: String,
+    pub text_case: Option<TextCase>,
+    pub formatting: Formatting,
+}
+
+/// `<date-part>`
+#[derive(Debug, Clone)]
+pub(super) struct DatePart {
+    pub name: String,
+    pub form: Option<String>,
+    pub strip_periods: bool,
+    pub text_case: Option<TextCase>,
+    pub range_delimiter: Option<String>,
+    pub formatting: Formatting,
+}
+
+impl DatePart {
+    fn parse(node: &Node) -> Self {
+        DatePart {
+            name: node.attr("name").unwrap_or("year").to_string(),
+            form: node.attr("form").map(str::to_string),
+            strip_periods: node.attr("strip-periods") == Some("true"),
+            text_case: TextCase::parse(node),
+            range_delimiter: node.attr("range-delimiter").map(str::to_string),
+            formatting: Formatting::parse(node),
+        }
+    }
+
+    /// Override attributes with those set on `other` (a style's date part
+    /// refining a localized date)
+    pub fn refine(&mut self, other: &DatePart) {
+        if other.form.is_some() {
+            self.form = other.form.clone();
+        }
+        if other.text_case.is_some() {
+            self.text_case = other.text_case;
+        }
+        if other.range_delimiter.is_some() {
+            self.range_delimiter = other.range_delimiter.clone();
+        }
+        self.strip_periods |= other.strip_periods;
+        self.formatting.refine(&other.formatting);
+    }
+}
+
+/// `<names>` with its `<name>`, `<et-al>`, `<label>` and `<substitute>`
+#[derive(Debug, Clone)]
+pub(super) struct NamesElement {
+    pub variables: Vec<String>,
+    /// Options from the `<name>` child and the `<names>` element itself
+    pub name: Option<NameOptions>,
+    pub family: Option<NamePart>,
+    pub given: Option<NamePart>,
+    pub et_al_term: String,
+    pub et_al_formatting: Formatting,
+    /// The label, and whether it comes before the names
+    pub label: Option<(Element, bool)>,
+    pub substitute: Vec<Element>,
+    pub delimiter: Option<String>,
+    pub formatting: Formatting,
+}
+
+/// `<name-part>` formatting
+#[derive(Debug, Clone, Default)]
+pub(super) struct NamePart {
+    pub text_case: Option<TextCase>,
+    pub formatting: Formatting,
+}
+
+/// Inheritable name options (CSL "inheritable name options")
+#[derive(Debug, Clone, Default)]
+pub(super) struct NameOptions {
+    pub and: Option<String>,
+    pub delimiter: Option<String>,
+    pub delimiter_precedes_last: Option<String>,
+    pub delimiter_precedes_et_al: Option<String>,
+    pub et_al_min: Option<usize>,
+    pub et_al_use_first: Option<usize>,
+    pub et_al_use_last: Option<bool>,
+    pub initialize: Option<bool>,
+    pub initialize_with: Option<String>,
+    pub name_as_sort_order: Option<String>,
+    pub sort_separator: Option<String>,
+    pub form: Option<String>,
+    pub names_delimiter: Option<String>,
+}
+
+impl NameOptions {
+    /// Options set on an element; `name-delimiter` and `name-form` are the
+    /// inheritable spellings of `delimiter` and `form`
+    fn parse(node: &Node) -> Self {
+        let text = |name: &str| node.attr(name).map(str::to_string);
+        let number = |name: &str| node.attr(name).and_then(|v| v.parse().ok());
+        let (delimiter, form) = if node.name == "name" {
+            (text("delimiter"), text("form"))
+        } else {
+            (text("name-delimiter"), text("name-form"))
+        };
+        NameOptions {
+            and: text("and"),
+            delimiter,
+            delimiter_precedes_last: text("delimiter-precedes-last"),
+            delimiter_precedes_et_al: text("delimiter-precedes-et-al"),
+            et_al_min: number("et-al-min"),
+            et_al_use_first: number("et-al-use-first"),
+            et_al_use_last: node.attr("et-al-use-last").map(|v| v == "true"),
+            initialize: node.attr("initialize").map(|v| v != "false"),
+            initialize_with: text("initialize-with"),
+            name_as_sort_order: text("name-as-sort-order"),
+            sort_separator: text("sort-separator"),
+            form,
+            names_delimiter: text("names-delimiter"),
+        }
+    }
+
+    /// Fill options not set here from `parent`
+    pub fn inherit(mut self, parent: &NameOptions) -> Self {
+        macro_rules! inherit {
+            ($($field:ident),*) => {
+                $(if self.$field.is_none() {
+                    self.$field = parent.$field.clone();
+                })*
+            };
+        }
+        inherit!(
+            and,
+            delimiter,
+            delimiter_precedes_last,
+            delimiter_precedes_et_al,
+            et_al_min,
+            et_al_use_first,
+            et_al_use_last,
+            initialize,
+            initialize_with,
+            name_as_sort_order,
+            sort_separator,
+            form,
+            names_delimiter
+        );
+        self
+    }
+}
+
+/// A `<if>`/`<else-if>`/`<else>` test
+#[derive(Debug, Clone, Default)]
+pub(super) struct Condition {
+    pub tests: Vec<Test>,
+    pub mode: Match,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Test {
+    Type(String),
+    Variable(String),
+    IsNumeric(String),
+    IsUncertainDate(String),
+    /// Locators are not tracked, so these tests are always false
+    Locator,
+    Position(String),
+    Disambiguate(bool),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) enum Match {
+    #[default]
+    All,
+    Any,
+    None,
+}
+
+impl Condition {
+    fn parse(node: &Node) -> Self {
+        let mut tests = Vec::new();
+        let mut add = |attr: &str, test: fn(String) -> Test| {
+            for value in node.attr(attr).unwrap_or_default().split_whitespace() {
+                tests.push(test(value.to_string()));
+            }
+        };
+        add("type", Test::Type);
+        add("variable", Test::Variable);
+        add("is-numeric", Test::IsNumeric);
+        add("is-uncertain-date", Test::IsUncertainDate);
+        add("locator", |_| Test::Locator);
+        add("position", Test::Position);
+        if let Some(value) = node.attr("disambiguate") {
+            tests.push(Test::Disambiguate(value == "true"));
+        }
+        let mode = match node.attr("match") {
+            Some("any") => Match::Any,
+            Some("none") => Match::None,
+            _ => Match::All,
+        };
+        Condition { tests, mode }
+    }
+}
+
+/// Term forms, with their fallback order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(super) enum TermForm {
+    #[default]
+    Long,
+    Short,
+    Verb,
+    VerbShort,
+    Symbol,
+}
+
+impl TermForm {
+    pub fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some("short") => TermForm::Short,
+            Some("verb") => TermForm::Verb,
+            Some("verb-short") => TermForm::VerbShort,
+            Some("symbol") => TermForm::Symbol,
+            _ => TermForm::Long,
+        }
+    }
+
+    /// Form to try when a term has no definition in this form
+    pub fn fallback(self) -> Option<TermForm> {
+        match self {
+            TermForm::Long => None,
+            TermForm::Short | TermForm::Verb => Some(TermForm::Long),
+            TermForm::VerbShort => Some(TermForm::Verb),
+            TermForm::Symbol => Some(TermForm::Short),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) enum NumberForm {
+    #[default]
+    Numeric,
+    Ordinal,
+    LongOrdinal,
+    Roman,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) enum Plural {
+    #[default]
+    Contextual,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TextCase {
+    Lowercase,
+    Uppercase,
+    CapitalizeFirst,
+    CapitalizeAll,
+    Sentence,
+    Title,
+}
+
+impl TextCase {
+    fn parse(node: &Node) -> Option<Self> {
+        Some(match node.attr("text-case")? {
+            "lowercase" => TextCase::Lowercase,
+            "uppercase" => TextCase::Uppercase,
+            "capitalize-first" => TextCase::CapitalizeFirst,
+            "capitalize-all" => TextCase::CapitalizeAll,
+            "sentence" => TextCase::Sentence,
+            "title" => TextCase::Title,
+            _ => return None,
+        })
+    }
+}
+
+/// Affixes and font formatting shared by most elements
+#[derive(Debug, Clone, Default)]
+pub(super) struct Formatting {
+    pub prefix: String,
+    pub suffix: String,
+    pub italic: Option<bool>,
+    pub bold: Option<bool>,
+    pub small_caps: Option<bool>,
+    pub underline: Option<bool>,
+    pub superscript: bool,
+}
+
+impl Formatting {
+    fn parse(node: &Node) -> Self {
+        let flag = |name: &str, on: &str, off: &str| match node.attr(name) {
+            Some(value) if value == on => Some(true),
+            Some(value) if value == off => Some(false),
+            _ => None,
+        };
+        Formatting {
+            prefix: node.attr("prefix").unwrap_or_default().to_string(),
+            suffix: node.attr("suffix").unwrap_or_default().to_string(),
+            italic: flag("font-style", "italic", "normal")
+                .or_else(|| flag("font-style", "oblique", "normal")),
+            bold: flag("font-weight", "bold", "normal"),
+            small_caps: flag("font-variant", "small-caps", "normal"),
+            underline: flag("text-decoration", "underline", "none"),
+            superscript: node.attr("vertical-align") == Some("sup"),
+        }
+    }
+
+    fn refine(&mut self, other: &Formatting) {
+        if !other.prefix.is_empty() {
+            self.prefix = other.prefix.clone();
+        }
+        if !other.suffix.is_empty() {
+            self.suffix = other.suffix.clone();
+        }
+        self.italic = other.italic.or(self.italic);
+        self.bold = other.bold.or(self.bold);
+        self.small_caps = other.small_caps.or(self.small_caps);
+        self.underline = other.underline.or(self.underline);
+        self.superscript |= other.superscript;
+    }
+}
+
+/// Parse the rendering elements among a node's children
+pub(super) fn parse_children(node: &Node) -> Result<Vec<Element>, CslError> {
+    let mut elements = Vec::new();
+    for child in &node.children {
+        if let Some(element) = parse_element(child)? {
+            elements.push(element);
+        }
+    }
+    Ok(elements)
+}
+
+fn parse_element(node: &Node) -> Result<Option<Element>, CslError> {
+    let text_case = TextCase::parse(node);
+    let formatting = Formatting::parse(node);
+    let strip_periods = node.attr("strip-periods") == Some("true");
+    let element = match node.name.as_str() {
+        "text" => {
+            let source = if let Some(variable) = node.attr("variable") {
+                TextSource::Variable(variable.to_string())
+            } else if let Some(name) = node.attr("macro") {
+                TextSource::Macro(name.to_string())
+            } else if let Some(term) = node.attr("term") {
+                TextSource::Term(term.to_string())
+            } else if let Some(value) = node.attr("value") {
+                TextSource::Value(value.to_string())
+            } else {
+                return Err(CslError::Invalid(
+                    "<text> needs a variable, macro, term or value".to_string(),
+                ));
+            };
+            Element::Text {
+                source,
+                form: TermForm::parse(node.attr("form")),
+                plural: node.attr("plural") == Some("true"),
+                quotes: node.attr("quotes") == Some("true"),
+                strip_periods,
+                text_case,
+                formatting,
+            }
+        }
+        "number" => Element::Number {
+            variable: required(node, "variable")?,
+            form: match node.attr("form") {
+                Some("ordinal") => NumberForm::Ordinal,
+                Some("long-ordinal") => NumberForm::LongOrdinal,
+                Some("roman") => NumberForm::Roman,
+                _ => NumberForm::Numeric,
+            },
+            text_case,
+            formatting,
+        },
+        "label" => Element::Label {
+            variable: node.attr("variable").unwrap_or("locator").to_string(),
+            form: TermForm::parse(node.attr("form")),
+            plural: match node.attr("plural") {
+                Some("always") => Plural::Always,
+                Some("never") => Plural::Never,
+                _ => Plural::Contextual,
+            },
+            strip_periods,
+            text_case,
+            formatting,
+        },
+        "date" => Element::Date(Box::new(parse_date(node)?)),
+        "names" => Element::Names(Box::new(parse_names(node)?)),
+        "group" => Element::Group {
+            children: parse_children(node)?,
+            delimiter: node.attr("delimiter").unwrap_or_default().to_string(),
+            formatting,
+        },
+        "choose" => {
+            let mut branches = Vec::new();
+            for branch in &node.children {
+                let condition = match branch.name.as_str() {
+                    "if" | "else-if" => Condition::parse(branch),
+                    "else" => Condition::default(),
+                    _ => continue,
+                };
+                branches.push((condition, parse_children(branch)?));
+            }
+            Element::Choose { branches }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(element))
+}
+
+pub(super) fn parse_date(node: &Node) -> Result<DateElement, CslError> {
+    Ok(DateElement {
+        variable: node.attr("variable").unwrap_or_default().to_string(),
+        form: node.attr("form").map(str::to_string),
+        precision: match node.attr("date-parts") {
+            Some("year") => 1,
+            Some("year-month") => 2,
+            _ => 3,
+        },
+        parts: node
+            .children_named("date-part")
+            .map(DatePart::parse)
+            .collect(),
+        delimiter: node.attr("delimiter").unwrap_or_default().to_string(),
+        text_case: TextCase::parse(node),
+        formatting: Formatting::parse(node),
+    })
+}
+
+fn parse_names(node: &Node) -> Result<NamesElement, CslError> {
+    let variables: Vec<String> = required(node, "variable")
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+
+    let mut names = NamesElement {
+        variables,
+        name: None,
+        family: None,
+        given: None,
+        et_al_term: "et-al".to_string(),
+        et_al_formatting: Formatting::default(),
+        label: None,
+        substitute: Vec::new(),
+        delimiter: node.attr("delimiter").map(str::to_string),
+        formatting: Formatting::parse(node),
+    };
+
+    // The label goes before the names only when it comes before `<name>`
+    let name_index = node.children.iter().position(|c| c.name == "name");
+    for (index, child) in node.children.iter().enumerate() {
+        match child.name.as_str() {
+            "name" => {
+                names.name = Some(NameOptions::parse(child));
+                for part in child.children_named("name-part") {
+                    let parsed = NamePart {
+                        text_case: TextCase::parse(part),
+                        formatting: Formatting::parse(part),
+                    };
+                    match part.attr("name") {
+                        Some("family") => names.family = Some(parsed),
+                        Some("given") => names.given = Some(parsed),
+                        _ => {}
+                    }
+                }
+            }
+            "et-al" => {
+                names.et_al_term = child.attr("term").unwrap_or("et-al").to_string();
+                names.et_al_formatting = Formatting::parse(child);
+            }
+            "label" => {
+                if let Some(mut label) = parse_element(child)? {
+                    if let Element::Label { variable, .. } = &mut label {
+                        variable.clear();
+                    }
+                    names.label = Some((label, name_index.is_some_and(|n| index < n)));
+                }
+            }
+            "substitute" => names.substitute = parse_children(child)?,
+            _ => {}
+        }
+    }
+    Ok(names)
+}
+
+fn required(node: &Node, attr: &str) -> Result<String, CslError> {
+    node.attr(attr)
+        .map(str::to_string)
+        .ok_or_else(|| CslError::Invalid(format!("<{}> needs a {} attribute", node.name, attr)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_style() {
+        let style = Style::parse(
+            r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text"
+                      default-locale="en-GB" et-al-min="3">
+              <info><title>Test Style</title></info>
+              <macro name="author"><names variable="author"><name initialize-with=". "/></names></macro>
+              <citation disambiguate-add-year-suffix="true" et-al-use-first="1">
+                <layout prefix="(" suffix=")" delimiter="; ">
+                  <group delimiter=", ">
+                    <text macro="author"/>
+                    <date variable="issued"><date-part name="year"/></date>
+                  </group>
+                </layout>
+              </citation>
+            </style>"#,
+        )
+        .unwrap();
+        assert_eq!(style.title, "Test Style");
+        assert_eq!(style.default_locale.as_deref(), Some("en-GB"));
+        assert!(!style.has_bibliography());
+        assert!(style.citation.add_year_suffix);
+        assert_eq!(style.citation.delimiter, "; ");
+        assert_eq!(style.citation.names.et_al_min, Some(3));
+        assert_eq!(style.citation.names.et_al_use_first, Some(1));
+        assert!(!style.renders_year_suffix);
+
+        let error = Style::parse(
+            r#"<style><citation><layout><text macro="nope"/></layout></citation></style>"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("undefined macro 'nope'"));
+        assert!(Style::parse("<style/>").is_err());
+    }
+}
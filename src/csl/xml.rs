@@ -0,0 +1,136 @@
+//! A minimal XML tree for CSL style and locale files.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+
+use super::CslError;
+
+/// An element with its attributes, child elements and text content
+#[derive(Debug, Clone, Default)]
+pub(super) struct Node {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    pub text: String,
+}
+
+impl Node {
+    /// Value of an attribute
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// First child element with the given name
+    pub fn child(&self, name: &str) -> Option<&Node> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// All child elements with the given name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+}
+
+/// Parse a document into its root element
+///
+/// Namespace prefixes are dropped, so `cs:text` and `text` are the same element.
+pub(super) fn parse(xml: &str) -> Result<Node, CslError> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<Node> = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| xml_error(&reader, e.to_string()))?;
+        match event {
+            Event::Start(ref e) => stack.push(start_node(&reader, e)?),
+            Event::Empty(ref e) => {
+                let node = start_node(&reader, e)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+            Event::End(_) => {
+                let node = stack
+                    .pop()
+                    .ok_or_else(|| xml_error(&reader, "unexpected closing tag".to_string()))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+            Event::Text(e) => {
+                if let Some(node) = stack.last_mut() {
+                    let text = e
+                        .unescape()
+                        .map_err(|e| xml_error(&reader, e.to_string()))?;
+                    node.text.push_str(&text);
+                }
+            }
+            Event::CData(e) => {
+                if let Some(node) = stack.last_mut() {
+                    node.text.push_str(&String::from_utf8_lossy(&e));
+                }
+            }
+            Event::Eof => {
+                return Err(xml_error(&reader, "unexpected end of document".to_string()));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn start_node(reader: &Reader<&[u8]>, e: &BytesStart<'_>) -> Result<Node, CslError> {
+    let mut node = Node {
+        name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+        ..Default::default()
+    };
+    for attr in e.attributes() {
+        let attr = attr.map_err(|e| xml_error(reader, e.to_string()))?;
+        let value = attr
+            .decode_and_unescape_value(reader.decoder())
+            .map_err(|e| xml_error(reader, e.to_string()))?;
+        node.attrs.push((
+            String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned(),
+            value.into_owned(),
+        ));
+    }
+    Ok(node)
+}
+
+fn xml_error(reader: &Reader<&[u8]>, message: String) -> CslError {
+    CslError::Xml {
+        position: reader.buffer_position() as usize,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tree() {
+        let root = parse(
+            r#"<?xml version="1.0"?>
+            <style xmlns="http://purl.org/net/xbiblio/csl" class="in-text">
+              <!-- comment -->
+              <cs:text value="a &amp; b"/>
+              <term name="and">and</term>
+            </style>"#,
+        )
+        .unwrap();
+        assert_eq!(root.name, "style");
+        assert_eq!(root.attr("class"), Some("in-text"));
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].attr("value"), Some("a & b"));
+        assert_eq!(root.child("term").unwrap().text, "and");
+
+        assert!(parse("<style><text></style>").is_err());
+        assert!(parse("<style>").is_err());
+    }
+}
@@ -0,0 +1,776 @@
+//! Filling in missing paper metadata from the sources.
+//!
+//! [`Enricher`] looks each paper up by DOI, by arXiv or PubMed ID when it has
+//! no DOI, or by title as a last resort, in a list of sources (Semantic
+//! Scholar, OpenAlex, Crossref, Europe PMC and Unpaywall by default) and fills
+//! in the fields that are missing: DOI, abstract, citation count, open-access
+//! PDF URL, venue and keywords. Existing values are never overwritten. Every
+//! change is listed in an [`EnrichReport`] together with the source it came
+//! from, and recorded on the paper itself in the `enriched_from` extra field.
+//!
+//! Lookups run a few papers at a time and can be given a time budget; papers
+//! not reached when it runs out are left as they are and reported as skipped.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use research_master::enrich::{EnrichOptions, Enricher};
+//! use research_master::formats::ImportFormat;
+//! use research_master::sources::SourceRegistry;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let bib = std::fs::read_to_string("references.bib").unwrap();
+//! let papers = ImportFormat::Bibtex.read(&bib).unwrap();
+//!
+//! let registry = SourceRegistry::new();
+//! let enricher = Enricher::new(registry.all().cloned().collect(), EnrichOptions::new());
+//! let (papers, report) = enricher.enrich(papers).await;
+//! println!("{} of {} entries enriched", report.count(research_master::enrich::EnrichStatus::Enriched), papers.len());
+//! # }
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{stream, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strsim::jaro_winkler;
+use tokio::time::{timeout_at, Instant};
+
+use crate::graph::normalize_doi;
+use crate::models::SourceType;
+use crate::models::{Paper, SearchQuery};
+use crate::sources::{Source, SourceError};
+
+/// Sources used for enrichment by default, in order of preference
+pub const DEFAULT_ENRICH_SOURCES: &[&str] = &[
+    "semantic",
+    "openalex",
+    "crossref",
+    "europe_pmc",
+    "unpaywall",
+];
+
+/// Extra field recording which source each enriched field came from
+pub const PROVENANCE_KEY: &str = "enriched_from";
+
+/// Candidates requested from each source when looking a paper up by title
+const TITLE_SEARCH_RESULTS: usize = 5;
+
+/// Minimum similarity of normalized titles for a title search hit to count
+const TITLE_SIMILARITY: f64 = 0.95;
+
+/// Titles shorter than this are too generic to look up by title
+const MIN_TITLE_LEN: usize = 20;
+
+/// A field enrichment can fill in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrichField {
+    /// Digital Object Identifier
+    Doi,
+    /// Abstract text
+    Abstract,
+    /// Citation count
+    Citations,
+    /// Open-access PDF URL
+    PdfUrl,
+    /// Journal or conference name
+    Venue,
+    /// Keywords
+    Keywords,
+}
+
+impl EnrichField {
+    /// Every field, in the order they are reported
+    pub const ALL: [EnrichField; 6] = [
+        EnrichField::Doi,
+        EnrichField::Abstract,
+        EnrichField::Citations,
+        EnrichField::PdfUrl,
+        EnrichField::Venue,
+        EnrichField::Keywords,
+    ];
+
+    /// Field name as used in reports
+    pub fn name(self) -> &'static str {
+        match self {
+            EnrichField::Doi => "doi",
+            EnrichField::Abstract => "abstract",
+            EnrichField::Citations => "citations",
+            EnrichField::PdfUrl => "pdf_url",
+            EnrichField::Venue => "venue",
+            EnrichField::Keywords => "keywords",
+        }
+    }
+
+    fn is_missing(self, paper: &Paper) -> bool {
+        match self {
+            EnrichField::Doi => paper.doi.as_deref().is_none_or(|d| d.trim().is_empty()),
+            EnrichField::Abstract => paper.r#abstract.trim().is_empty(),
+            EnrichField::Citations => paper.citations.is_none(),
+            EnrichField::PdfUrl => paper.pdf_url.as_deref().is_none_or(|u| u.trim().is_empty()),
+            EnrichField::Venue => paper.venue().is_none(),
+            EnrichField::Keywords => paper.keyword_list().is_empty(),
+        }
+    }
+
+    /// Copy the field from `from` into `paper`, returning the value copied
+    fn fill(self, paper: &mut Paper, from: &Paper) -> Option<String> {
+        match self {
+            EnrichField::Doi => {
+                let doi = from.doi.as_deref().filter(|d| !d.trim().is_empty())?;
+                paper.doi = Some(doi.trim().to_string());
+                Some(doi.trim().to_string())
+            }
+            EnrichField::Abstract => {
+                let text = from.r#abstract.trim();
+                if text.is_empty() {
+                    return None;
+                }
+                paper.r#abstract = text.to_string();
+                Some(text.to_string())
+            }
+            EnrichField::Citations => {
+                let count = from.citations?;
+                paper.citations = Some(count);
+                Some(count.to_string())
+            }
+            EnrichField::PdfUrl => {
+                let url = from.pdf_url.as_deref().filter(|u| !u.trim().is_empty())?;
+                paper.pdf_url = Some(url.to_string());
+                Some(url.to_string())
+            }
+            EnrichField::Venue => {
+                let venue = from.venue()?.to_string();
+                paper.extra.get_or_insert_with(Default::default).insert(
+                    "venue".to_string(),
+                    serde_json::Value::String(venue.clone()),
+                );
+                Some(venue)
+            }
+            EnrichField::Keywords => {
+                let keywords = from.keyword_list().join(";");
+                if keywords.is_empty() {
+                    return None;
+                }
+                paper.keywords = Some(keywords.clone());
+                Some(keywords)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for EnrichField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Options for enrichment
+#[derive(Debug, Clone)]
+pub struct EnrichOptions {
+    /// Fields to fill in when missing
+    pub fields: Vec<EnrichField>,
+
+    /// Sources to look papers up in, in order of preference
+    pub sources: Vec<String>,
+
+    /// Number of papers looked up concurrently
+    pub concurrency: usize,
+
+    /// Time allowed for the whole run; `None` for no limit
+    pub time_budget: Option<Duration>,
+}
+
+impl Default for EnrichOptions {
+    fn default() -> Self {
+        Self {
+            fields: EnrichField::ALL.to_vec(),
+            sources: DEFAULT_ENRICH_SOURCES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            concurrency: 4,
+            time_budget: None,
+        }
+    }
+}
+
+impl EnrichOptions {
+    /// Create options with the defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the fields to fill in
+    pub fn fields(mut self, fields: Vec<EnrichField>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Set the sources to look papers up in, in order of preference
+    pub fn sources(mut self, sources: Vec<String>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Set the number of papers looked up concurrently
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Stop looking papers up once the run has taken this long
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+}
+
+/// Outcome of enriching one paper
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrichStatus {
+    /// At least one field was filled in
+    Enriched,
+    /// The paper was found, but the sources had nothing to add
+    Unchanged,
+    /// No selected field was missing
+    Complete,
+    /// No source had a matching record
+    NotFound,
+    /// The time budget ran out before the paper was looked up
+    Skipped,
+}
+
+/// A field filled in by enrichment
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FieldChange {
+    /// The field that was filled in
+    pub field: EnrichField,
+
+    /// Source the value came from
+    pub source: String,
+
+    /// The new value (abstracts are shortened)
+    pub value: String,
+}
+
+/// What enrichment did to one paper
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EnrichEntry {
+    /// Position of the paper in the input
+    pub index: usize,
+
+    /// Paper ID (the cite key for imported entries)
+    pub paper_id: String,
+
+    /// Paper title
+    pub title: String,
+
+    /// Outcome
+    pub status: EnrichStatus,
+
+    /// How the paper was matched: `doi`, `id` or `title`
+    pub matched_by: Option<String>,
+
+    /// Fields filled in
+    pub changes: Vec<FieldChange>,
+
+    /// Selected fields still missing afterwards
+    pub missing: Vec<EnrichField>,
+
+    /// Lookups that failed, as `<source>: <error>`
+    pub errors: Vec<String>,
+}
+
+/// What enrichment did to a list of papers
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct EnrichReport {
+    /// One entry per paper, in input order
+    pub entries: Vec<EnrichEntry>,
+}
+
+impl EnrichReport {
+    /// Number of papers with the given outcome
+    pub fn count(&self, status: EnrichStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+
+    /// Number of times a field was filled in
+    pub fn filled(&self, field: EnrichField) -> usize {
+        self.entries
+            .iter()
+            .flat_map(|e| &e.changes)
+            .filter(|c| c.field == field)
+            .count()
+    }
+
+    /// One-line summary, e.g. `12 enriched, 3 unchanged, 4 complete, 1 not found`
+    ///
+    /// Papers skipped because the time budget ran out are only mentioned when
+    /// there are any.
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} enriched, {} unchanged, {} complete, {} not found",
+            self.count(EnrichStatus::Enriched),
+            self.count(EnrichStatus::Unchanged),
+            self.count(EnrichStatus::Complete),
+            self.count(EnrichStatus::NotFound),
+        );
+        let skipped = self.count(EnrichStatus::Skipped);
+        if skipped > 0 {
+            summary.push_str(&format!(", {} skipped", skipped));
+        }
+        let filled: Vec<String> = EnrichField::ALL
+            .iter()
+            .map(|&f| (f, self.filled(f)))
+            .filter(|(_, n)| *n > 0)
+            .map(|(f, n)| format!("{} {}", n, f))
+            .collect();
+        if !filled.is_empty() {
+            summary.push_str(&format!(" (filled: {})", filled.join(", ")));
+        }
+        summary
+    }
+}
+
+/// Fills in missing paper metadata from other sources
+#[derive(Debug)]
+pub struct Enricher {
+    sources: Vec<Arc<dyn Source>>,
+    options: EnrichOptions,
+}
+
+impl Enricher {
+    /// Create an enricher using the given sources, filtered by `options.sources`
+    pub fn new(sources: Vec<Arc<dyn Source>>, options: EnrichOptions) -> Self {
+        let sources = options
+            .sources
+            .iter()
+            .filter_map(|id| sources.iter().find(|s| s.id() == id).cloned())
+            .filter(|s| s.supports_doi_lookup() || s.supports_search())
+            .collect();
+        Self { sources, options }
+    }
+
+    /// IDs of the sources papers are looked up in, in order
+    pub fn source_ids(&self) -> Vec<&str> {
+        self.sources.iter().map(|s| s.id()).collect()
+    }
+
+    /// Enrich every paper, returning the papers in input order and a report
+    pub async fn enrich(&self, papers: Vec<Paper>) -> (Vec<Paper>, EnrichReport) {
+        let deadline = self
+            .options
+            .time_budget
+            .map(|budget| Instant::now() + budget);
+        let results: Vec<(Paper, EnrichEntry)> = stream::iter(papers.into_iter().enumerate())
+            .map(|(index, mut paper)| async move {
+                let entry = self.enrich_paper(index, &mut paper, deadline).await;
+                (paper, entry)
+            })
+            .buffered(self.options.concurrency.max(1))
+            .collect()
+            .await;
+
+        let (papers, entries) = results.into_iter().unzip();
+        (papers, EnrichReport { entries })
+    }
+
+    async fn enrich_paper(
+        &self,
+        index: usize,
+        paper: &mut Paper,
+        deadline: Option<Instant>,
+    ) -> EnrichEntry {
+        let mut entry = EnrichEntry {
+            index,
+            paper_id: paper.paper_id.clone(),
+            title: paper.title.clone(),
+            status: EnrichStatus::Complete,
+            matched_by: None,
+            changes: Vec::new(),
+            missing: self.missing(paper),
+            errors: Vec::new(),
+        };
+        if entry.missing.is_empty() {
+            return entry;
+        }
+
+        let mut out_of_time = false;
+        for source in &self.sources {
+            let found = match deadline {
+                Some(deadline) => {
+                    match timeout_at(deadline, lookup(source.as_ref(), paper)).await {
+                        Ok(found) => found,
+                        Err(_) => {
+                            out_of_time = true;
+                            break;
+                        }
+                    }
+                }
+                None => lookup(source.as_ref(), paper).await,
+            };
+            let record = match found {
+                Ok(Some((record, matched_by))) => {
+                    entry
+                        .matched_by
+                        .get_or_insert_with(|| matched_by.to_string());
+                    record
+                }
+                Ok(None) => continue,
+                Err(e) => {
+                    entry.errors.push(format!("{}: {}", source.id(), e));
+                    continue;
+                }
+            };
+
+            for field in self.missing(paper) {
+                if let Some(value) = field.fill(paper, &record) {
+                    record_provenance(paper, field, source.id());
+                    entry.changes.push(FieldChange {
+                        field,
+                        source: source.id().to_string(),
+                        value: shorten(&value),
+                    });
+                }
+            }
+            if self.missing(paper).is_empty() {
+                break;
+            }
+        }
+
+        entry.missing = self.missing(paper);
+        entry.status = if !entry.changes.is_empty() {
+            EnrichStatus::Enriched
+        } else if entry.matched_by.is_some() {
+            EnrichStatus::Unchanged
+        } else if out_of_time {
+            EnrichStatus::Skipped
+        } else {
+            EnrichStatus::NotFound
+        };
+        entry
+    }
+
+    fn missing(&self, paper: &Paper) -> Vec<EnrichField> {
+        self.options
+            .fields
+            .iter()
+            .copied()
+            .filter(|f| f.is_missing(paper))
+            .collect()
+    }
+}
+
+/// Look a paper up in one source, returning the record and how it was matched
+///
+/// Papers with a DOI are only looked up by DOI. Otherwise the arXiv or PubMed
+/// ID is tried with sources that can look it up, then the title.
+async fn lookup(
+    source: &dyn Source,
+    paper: &Paper,
+) -> Result<Option<(Paper, &'static str)>, SourceError> {
+    if let Some(doi) = paper.doi.as_deref().and_then(normalize_doi) {
+        if !source.supports_doi_lookup() {
+            return Ok(None);
+        }
+        return source.get_by_doi(&doi).await.map(|r| Some((r, "doi")));
+    }
+
+    if let Some(id) = external_id(paper) {
+        match source.get_by_id(&id).await {
+            Ok(record) => return Ok(Some((record, "id"))),
+            Err(SourceError::NotImplemented | SourceError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    if !source.supports_search() {
+        return Ok(None);
+    }
+    Ok(find_by_title(source, paper)
+        .await?
+        .map(|record| (record, "title")))
+}
+
+/// The paper's arXiv or PubMed ID, as `arXiv:<id>` or `PMID:<id>`
+///
+/// This is the form Semantic Scholar and Europe PMC accept in `get_by_id`.
+/// arXiv IDs lose their version suffix.
+pub(crate) fn external_id(paper: &Paper) -> Option<String> {
+    let id = paper.paper_id.trim();
+    if id.is_empty() {
+        return None;
+    }
+    match paper.source {
+        SourceType::Arxiv => {
+            let id = id.strip_prefix("arXiv:").unwrap_or(id);
+            let id = match id.rsplit_once('v') {
+                Some((base, version)) if version.chars().all(|c| c.is_ascii_digit()) => base,
+                _ => id,
+            };
+            Some(format!("arXiv:{}", id))
+        }
+        SourceType::PubMed if id.chars().all(|c| c.is_ascii_digit()) => {
+            Some(format!("PMID:{}", id))
+        }
+        _ => None,
+    }
+}
+
+/// Note in the paper's `enriched_from` extra field where a field came from
+fn record_provenance(paper: &mut Paper, field: EnrichField, source: &str) {
+    let extra = paper.extra.get_or_insert_with(Default::default);
+    let provenance = extra
+        .entry(PROVENANCE_KEY.to_string())
+        .or_insert_with(|| serde_json::json!({}));
+    if let Some(map) = provenance.as_object_mut() {
+        map.insert(
+            field.name().to_string(),
+            serde_json::Value::String(source.to_string()),
+        );
+    }
+}
+
+/// Search a source for the paper's title and return a close match
+pub(crate) async fn find_by_title(
+    source: &dyn Source,
+    paper: &Paper,
+) -> Result<Option<Paper>, SourceError> {
+    let title = title_key(&paper.title);
+    if title.len() < MIN_TITLE_LEN {
+        return Ok(None);
+    }
+
+    let query = SearchQuery::new(paper.title.trim()).max_results(TITLE_SEARCH_RESULTS);
+    let response = source.search(&query).await?;
+    Ok(response.papers.into_iter().find(|candidate| {
+        let years_agree = match (paper.year(), candidate.year()) {
+            (Some(a), Some(b)) => (a - b).abs() <= 1,
+            _ => true,
+        };
+        years_agree && jaro_winkler(&title, &title_key(&candidate.title)) >= TITLE_SIMILARITY
+    }))
+}
+
+/// Lowercase title words, for comparing titles
+pub(crate) fn title_key(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Keep report values short: abstracts are cut to their first 80 characters
+fn shorten(value: &str) -> String {
+    const MAX_CHARS: usize = 80;
+    if value.chars().count() <= MAX_CHARS {
+        value.to_string()
+    } else {
+        let cut: String = value.chars().take(MAX_CHARS).collect();
+        format!("{}...", cut.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    use crate::models::{PaperBuilder, SearchResponse, SourceType};
+    use crate::sources::{SourceCapabilities, SourceError};
+
+    /// A source that knows one paper by DOI and by title
+    #[derive(Debug)]
+    struct RecordSource {
+        id: &'static str,
+        record: Paper,
+    }
+
+    #[async_trait]
+    impl Source for RecordSource {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn name(&self) -> &str {
+            self.id
+        }
+
+        fn capabilities(&self) -> SourceCapabilities {
+            SourceCapabilities::SEARCH | SourceCapabilities::DOI_LOOKUP
+        }
+
+        async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
+            Ok(SearchResponse::new(
+                vec![self.record.clone()],
+                self.id,
+                &query.query,
+            ))
+        }
+
+        async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
+            match self.record.doi.as_deref() {
+                Some(known) if known.eq_ignore_ascii_case(doi) => Ok(self.record.clone()),
+                _ => Err(SourceError::NotFound(doi.to_string())),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enrich_fills_missing_fields_only() {
+        let title = "Attention Is All You Need for Sequence Transduction";
+        let semantic = RecordSource {
+            id: "semantic",
+            record: PaperBuilder::new("s2", title, "", SourceType::SemanticScholar)
+                .doi("10.5555/attention")
+                .abstract_text("The dominant sequence transduction models...")
+                .citations(100_000)
+                .published_date("2017")
+                .extra("venue", serde_json::json!("NeurIPS"))
+                .build(),
+        };
+        let unpaywall = RecordSource {
+            id: "unpaywall",
+            record: PaperBuilder::new("u", title, "", SourceType::Unpaywall)
+                .doi("10.5555/attention")
+                .pdf_url("https://example.org/attention.pdf")
+                .build(),
+        };
+        let sources: Vec<Arc<dyn Source>> = vec![Arc::new(unpaywall), Arc::new(semantic)];
+        let enricher = Enricher::new(sources, EnrichOptions::new());
+        assert_eq!(enricher.source_ids(), vec!["semantic", "unpaywall"]);
+
+        let by_title = PaperBuilder::new("vaswani2017", title, "", SourceType::Arxiv)
+            .published_date("2017-06")
+            .abstract_text("Our own abstract")
+            .build();
+        let complete = PaperBuilder::new("done", "Some complete paper", "", SourceType::Arxiv)
+            .doi("10.1/x")
+            .abstract_text("x")
+            .citations(1)
+            .pdf_url("https://example.org/x.pdf")
+            .extra("venue", serde_json::json!("ICML"))
+            .keywords("x")
+            .build();
+        let unknown = PaperBuilder::new("x", "Short", "", SourceType::Arxiv).build();
+
+        let (papers, report) = enricher.enrich(vec![by_title, complete, unknown]).await;
+
+        assert_eq!(papers[0].doi.as_deref(), Some("10.5555/attention"));
+        assert_eq!(papers[0].r#abstract, "Our own abstract");
+        assert_eq!(papers[0].citations, Some(100_000));
+        assert_eq!(
+            papers[0].pdf_url.as_deref(),
+            Some("https://example.org/attention.pdf")
+        );
+        assert_eq!(papers[0].venue(), Some("NeurIPS"));
+        assert_eq!(
+            papers[0].extra.as_ref().unwrap()[PROVENANCE_KEY],
+            serde_json::json!({
+                "doi": "semantic",
+                "citations": "semantic",
+                "venue": "semantic",
+                "pdf_url": "unpaywall"
+            })
+        );
+
+        let entry = &report.entries[0];
+        assert_eq!(entry.status, EnrichStatus::Enriched);
+        assert_eq!(entry.matched_by.as_deref(), Some("title"));
+        let sources: Vec<(&str, &str)> = entry
+            .changes
+            .iter()
+            .map(|c| (c.field.name(), c.source.as_str()))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("doi", "semantic"),
+                ("citations", "semantic"),
+                ("venue", "semantic"),
+                ("pdf_url", "unpaywall")
+            ]
+        );
+        assert_eq!(entry.missing, vec![EnrichField::Keywords]);
+
+        assert_eq!(report.entries[1].status, EnrichStatus::Complete);
+        assert_eq!(report.entries[2].status, EnrichStatus::NotFound);
+        assert_eq!(
+            report.summary(),
+            "1 enriched, 0 unchanged, 1 complete, 1 not found (filled: 1 doi, 1 citations, 1 pdf_url, 1 venue)"
+        );
+    }
+
+    /// A source that only answers `get_by_id`, slowly for unknown IDs
+    #[derive(Debug)]
+    struct IdSource {
+        record: Paper,
+    }
+
+    #[async_trait]
+    impl Source for IdSource {
+        fn id(&self) -> &str {
+            "semantic"
+        }
+
+        fn name(&self) -> &str {
+            "semantic"
+        }
+
+        fn capabilities(&self) -> SourceCapabilities {
+            SourceCapabilities::DOI_LOOKUP
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<Paper, SourceError> {
+            if id == "arXiv:1706.03762" {
+                return Ok(self.record.clone());
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Err(SourceError::NotFound(id.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enrich_by_id_within_time_budget() {
+        let source = IdSource {
+            record: PaperBuilder::new("s2", "Attention", "", SourceType::SemanticScholar)
+                .abstract_text("The dominant sequence transduction models...")
+                .keywords("attention;transformers")
+                .build(),
+        };
+        let options = EnrichOptions::new()
+            .fields(vec![EnrichField::Abstract, EnrichField::Keywords])
+            .time_budget(Duration::from_millis(200));
+        let enricher = Enricher::new(vec![Arc::new(source)], options);
+
+        let known = PaperBuilder::new("1706.03762v5", "Attention", "", SourceType::Arxiv).build();
+        let slow = PaperBuilder::new("2001.00001", "Something else", "", SourceType::Arxiv).build();
+        let (papers, report) = enricher.enrich(vec![known, slow]).await;
+
+        assert_eq!(papers[0].keyword_list(), vec!["attention", "transformers"]);
+        assert_eq!(report.entries[0].matched_by.as_deref(), Some("id"));
+        assert_eq!(report.entries[1].status, EnrichStatus::Skipped);
+        assert!(papers[1].r#abstract.is_empty());
+        assert!(report
+            .summary()
+            .ends_with("0 not found, 1 skipped (filled: 1 abstract, 1 keywords)"));
+    }
+
+    #[test]
+    fn test_external_id() {
+        let arxiv = PaperBuilder::new("arXiv:1706.03762v5", "", "", SourceType::Arxiv).build();
+        assert_eq!(external_id(&arxiv).as_deref(), Some("arXiv:1706.03762"));
+        let pubmed = PaperBuilder::new("32939066", "", "", SourceType::PubMed).build();
+        assert_eq!(external_id(&pubmed).as_deref(), Some("PMID:32939066"));
+        let other = PaperBuilder::new("W1", "", "", SourceType::OpenAlex).build();
+        assert!(external_id(&other).is_none());
+    }
+}
@@ -0,0 +1,624 @@
+//! BibTeX files edited in place.
+//!
+//! A [`BibFile`] keeps the text of a `.bib` file and rewrites only the fields
+//! it changes, so comments, formatting and entry order survive. New entries
+//! are appended at the end.
+//!
+//! A `@comment{research-master-sync: ...}` block at the end of the file
+//! records a fingerprint of every value research-master wrote. A field is
+//! refreshed only while it still matches its fingerprint: values edited by
+//! hand are kept, and fields deleted by hand stay deleted.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+
+use serde_json::json;
+
+use super::bibtex::{self, KeyPattern, Parser, RawField};
+use super::{fingerprint, FormatError};
+use crate::enrich::title_key;
+use crate::graph::normalize_doi;
+use crate::models::{Paper, SourceType};
+
+/// Start of the comment holding the field fingerprints
+const SYNC_MARKER: &str = "research-master-sync:";
+
+/// A BibTeX file that can be updated without reformatting it
+#[derive(Debug, Clone, Default)]
+pub struct BibFile {
+    segments: Vec<Segment>,
+    /// `@string` macros, for re-reading edited entries
+    strings: HashMap<String, String>,
+    /// Fingerprints of the values written, by cite key and field name
+    sync: HashMap<String, Vec<(String, String)>>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    /// Text between entries, including comments, preambles and macros
+    Text(String),
+    Entry(BibEntry),
+}
+
+/// How [`BibFile::add`] changed the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryChange {
+    /// A new entry was appended
+    Added,
+    /// An existing entry was matched; the fields that were added or refreshed
+    Updated(Vec<String>),
+}
+
+/// An entry of a BibTeX file
+#[derive(Debug, Clone)]
+pub struct BibEntry {
+    /// Source text, from `@` to the closing delimiter
+    text: String,
+    kind: String,
+    key: String,
+    /// Offset just past the key
+    key_end: usize,
+    /// Fields with offsets into `text`
+    fields: Vec<RawField>,
+}
+
+impl BibEntry {
+    /// Parse one entry; `text` runs from `@` to the closing delimiter
+    fn parse(text: String, strings: &HashMap<String, String>) -> Result<Self, FormatError> {
+        let mut parser = Parser::new(&text);
+        parser.strings = strings.clone();
+        parser.pos = 1;
+        let kind = parser.ident().to_lowercase();
+        parser.skip_ws();
+        let close = if parser.peek() == Some(b'(') {
+            b')'
+        } else {
+            b'}'
+        };
+        parser.pos += 1;
+        let raw = parser.entry(close, 1)?;
+        Ok(Self {
+            kind,
+            key: raw.key,
+            key_end: raw.key_end,
+            fields: raw.fields,
+            text,
+        })
+    }
+
+    /// Entry type, lowercase (`article`, `inproceedings`, ...)
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Cite key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Plain text of a field, with macros expanded and LaTeX decoded
+    pub fn field(&self, name: &str) -> Option<String> {
+        self.fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(name))
+            .map(|f| bibtex::decode(&f.value))
+            .filter(|v| !v.is_empty())
+    }
+
+    /// The entry as a paper, as `import` reads it
+    pub fn to_paper(&self) -> Paper {
+        let fields = self
+            .fields
+            .iter()
+            .map(|f| (f.name.clone(), f.value.clone()))
+            .collect();
+        bibtex::to_paper(&self.kind, &self.key, fields)
+    }
+
+    /// Whether the entry describes the same paper, by DOI, arXiv ID or title
+    fn matches(&self, paper: &Paper) -> bool {
+        let doi = paper.doi.as_deref().and_then(normalize_doi);
+        if doi.is_some() && self.field("doi").as_deref().and_then(normalize_doi) == doi {
+            return true;
+        }
+        if paper.source == SourceType::Arxiv
+            && self.field("eprint").as_deref() == Some(paper.paper_id.as_str())
+        {
+            return true;
+        }
+        let title = title_key(&paper.title);
+        !title.is_empty() && self.field("title").map(|t| title_key(&t)) == Some(title)
+    }
+
+    /// Text inserted after the last field to add `name = {value}`, copying
+    /// the separator and alignment of the existing fields
+    fn new_field(&self, name: &str, value: &str) -> String {
+        let Some(last) = self.fields.last() else {
+            return format!(",\n  {} = {{{}}}", name, value);
+        };
+        let previous_end = match self.fields.len() {
+            1 => self.key_end,
+            n => self.fields[n - 2].value_span.end,
+        };
+        let mut separator = self.text[previous_end..last.name_start].to_string();
+        if !separator.contains(',') {
+            separator.insert(0, ',');
+        }
+
+        // Padding between name and `=`: aligned fields line their `=` up
+        let gaps: Vec<(usize, &str)> = self
+            .fields
+            .iter()
+            .map(|f| {
+                let gap = &self.text[f.name_start + f.name.len()..f.value_span.start];
+                (f.name.len(), gap)
+            })
+            .collect();
+        let aligned = gaps
+            .iter()
+            .any(|(_, gap)| gap.len() - gap.trim_start().len() > 1);
+        let (_, last_gap) = gaps[gaps.len() - 1];
+        let gap = if aligned {
+            let width = gaps
+                .iter()
+                .map(|(len, gap)| len + gap.len() - gap.trim_start().len())
+                .max()
+                .unwrap_or(0);
+            let pad = width.saturating_sub(name.len()).max(1);
+            format!("{}{}", " ".repeat(pad), last_gap.trim_start())
+        } else {
+            last_gap.to_string()
+        };
+        format!("{}{}{}{{{}}}", separator, name, gap, value)
+    }
+}
+
+impl BibFile {
+    /// Parse a BibTeX file, keeping its text
+    pub fn parse(text: &str) -> Result<Self, FormatError> {
+        let mut parser = Parser::new(text);
+        let mut file = Self::default();
+        let mut copied = 0;
+
+        while let Some(at) = text[parser.pos..].find('@') {
+            let begin = parser.pos + at;
+            parser.pos = begin + 1;
+            let start = parser.pos;
+            let kind = parser.ident().to_lowercase();
+            parser.skip_ws();
+            let close = match parser.peek() {
+                Some(b'{') => b'}',
+                Some(b'(') => b')',
+                _ => continue,
+            };
+            parser.pos += 1;
+            let body = parser.pos;
+
+            match kind.as_str() {
+                "" => continue,
+                "comment" => {
+                    parser.skip_group(close, start)?;
+                    let comment = &text[body..parser.pos - 1];
+                    if let Some(records) = comment.trim_start().strip_prefix(SYNC_MARKER) {
+                        file.sync = parse_sync(records);
+                        file.push_text(&text[copied..begin]);
+                        copied = parser.pos;
+                        for newline in ["\r\n", "\n"] {
+                            if text[copied..].starts_with(newline) {
+                                copied += newline.len();
+                                break;
+                            }
+                        }
+                    }
+                }
+                "preamble" => parser.skip_group(close, start)?,
+                "string" => parser.string(close, start)?,
+                _ => {
+                    parser.entry(close, start)?;
+                    let entry =
+                        BibEntry::parse(text[begin..parser.pos].to_string(), &parser.strings)?;
+                    file.push_text(&text[copied..begin]);
+                    file.segments.push(Segment::Entry(entry));
+                    copied = parser.pos;
+                }
+            }
+        }
+        file.push_text(&text[copied..]);
+        file.strings = parser.strings;
+        Ok(file)
+    }
+
+    /// The file without the sync comment
+    fn text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.as_str(),
+                Segment::Entry(entry) => entry.text.as_str(),
+            })
+            .collect()
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.segments.push(Segment::Text(text.to_string()));
+        }
+    }
+
+    /// Entries in file order
+    pub fn entries(&self) -> impl Iterator<Item = &BibEntry> {
+        self.segments.iter().filter_map(|s| match s {
+            Segment::Entry(entry) => Some(entry),
+            Segment::Text(_) => None,
+        })
+    }
+
+    /// The entry describing the same paper, matched by DOI, arXiv ID or title
+    pub fn find(&self, paper: &Paper) -> Option<&BibEntry> {
+        self.entries().find(|e| e.matches(paper))
+    }
+
+    /// The entry with the given cite key
+    pub fn get(&self, key: &str) -> Option<&BibEntry> {
+        self.entries().find(|e| e.key == key)
+    }
+
+    /// Add a paper, or refresh the entry that already describes it
+    ///
+    /// New entries get a key from `pattern`, with a letter appended when the
+    /// key is taken. Existing entries keep their key and type; their fields are
+    /// added or refreshed unless edited or deleted by hand. Returns the key and
+    /// what changed.
+    pub fn add(
+        &mut self,
+        paper: &Paper,
+        pattern: &KeyPattern,
+    ) -> Result<(String, EntryChange), FormatError> {
+        let index = self
+            .segments
+            .iter()
+            .position(|s| matches!(s, Segment::Entry(e) if e.matches(paper)));
+        if let Some(index) = index {
+            let (key, changed) = self.update(index, paper)?;
+            return Ok((key, EntryChange::Updated(changed)));
+        }
+
+        let taken: HashSet<String> = self.entries().map(|e| e.key.to_lowercase()).collect();
+        let base = pattern.key(paper);
+        let mut key = base.clone();
+        let mut suffix = b'a';
+        while taken.contains(&key.to_lowercase()) {
+            key = format!("{}{}", base, suffix as char);
+            suffix = suffix.saturating_add(1);
+        }
+        self.insert(paper, &key)?;
+        Ok((key, EntryChange::Added))
+    }
+
+    /// Add a paper under a given key, or refresh the entry with that key
+    ///
+    /// Used for keys a manuscript already cites, such as `doi:10.1038/nature14539`.
+    pub fn add_as(&mut self, paper: &Paper, key: &str) -> Result<EntryChange, FormatError> {
+        let index = self
+            .segments
+            .iter()
+            .position(|s| matches!(s, Segment::Entry(e) if e.key == key));
+        match index {
+            Some(index) => Ok(EntryChange::Updated(self.update(index, paper)?.1)),
+            None => {
+                self.insert(paper, key)?;
+                Ok(EntryChange::Added)
+            }
+        }
+    }
+
+    fn insert(&mut self, paper: &Paper, key: &str) -> Result<(), FormatError> {
+        let text = bibtex::write_entry(paper, key);
+        let entry = BibEntry::parse(text.trim_end().to_string(), &self.strings)?;
+
+        let written = self.text();
+        let written = written.trim_end_matches([' ', '\t']);
+        if written.ends_with('\n') && !written.ends_with("\n\n") {
+            self.push_text("\n");
+        } else if !written.is_empty() && !written.ends_with('\n') {
+            self.push_text("\n\n");
+        }
+
+        let records = bibtex::fields(paper)
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), fingerprint(&value)))
+            .collect();
+        self.sync.insert(key.to_string(), records);
+        self.segments.push(Segment::Entry(entry));
+        self.push_text("\n");
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        index: usize,
+        paper: &Paper,
+    ) -> Result<(String, Vec<String>), FormatError> {
+        let Segment::Entry(entry) = &self.segments[index] else {
+            unreachable!("index points at an entry");
+        };
+
+        // Write the paper as the type the entry already has
+        let mut paper = paper.clone();
+        paper
+            .extra
+            .get_or_insert_with(HashMap::new)
+            .insert("entry_type".to_string(), json!(entry.kind));
+
+        let records = self.sync.entry(entry.key.clone()).or_default();
+        let mut replaced: Vec<(Range<usize>, String)> = Vec::new();
+        let mut added = String::new();
+        let mut changed = Vec::new();
+        for (name, value) in bibtex::fields(&paper) {
+            let recorded = records
+                .iter()
+                .position(|(field, _)| field == name)
+                .map(|i| records[i].1.clone());
+            match entry.fields.iter().find(|f| f.name == name) {
+                Some(field) if field.value == value => {}
+                Some(field) if recorded.as_deref() == Some(&fingerprint(&field.value)) => {
+                    replaced.push((field.value_span.clone(), format!("{{{}}}", value)));
+                }
+                // Edited by hand, or never written by us
+                Some(_) => continue,
+                // Deleted by hand
+                None if recorded.is_some() => continue,
+                None => added.push_str(&entry.new_field(name, &value)),
+            }
+            records.retain(|(field, _)| field != name);
+            records.push((name.to_string(), fingerprint(&value)));
+            if !entry
+                .fields
+                .iter()
+                .any(|f| f.name == name && f.value == value)
+            {
+                changed.push(name.to_string());
+            }
+        }
+
+        let key = entry.key.clone();
+        if changed.is_empty() {
+            return Ok((key, changed));
+        }
+        let mut text = entry.text.clone();
+        let insert_at = entry
+            .fields
+            .last()
+            .map_or(entry.key_end, |f| f.value_span.end);
+        text.insert_str(insert_at, &added);
+        replaced.sort_by_key(|(span, _)| std::cmp::Reverse(span.start));
+        for (span, value) in replaced {
+            text.replace_range(span, &value);
+        }
+        self.segments[index] = Segment::Entry(BibEntry::parse(text, &self.strings)?);
+        Ok((key, changed))
+    }
+}
+
+impl fmt::Display for BibFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = self.text();
+
+        let records: Vec<String> = self
+            .entries()
+            .filter_map(|entry| {
+                let fields = self.sync.get(&entry.key).filter(|r| !r.is_empty())?;
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(name, hash)| format!("{}:{}", name, hash))
+                    .collect();
+                Some(format!("{} {}", entry.key, fields.join(" ")))
+            })
+            .collect();
+        if !records.is_empty() {
+            out.truncate(out.trim_end().len());
+            if !out.is_empty() {
+                out.push_str("\n\n");
+            }
+            out.push_str(&format!(
+                "@comment{{{}\n{}\n}}\n",
+                SYNC_MARKER,
+                records.join("\n")
+            ));
+        }
+        f.write_str(&out)
+    }
+}
+
+/// Fingerprints from the sync comment: one `key field:hash ...` line per entry
+fn parse_sync(records: &str) -> HashMap<String, Vec<(String, String)>> {
+    records
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let key = words.next()?;
+            let fields = words
+                .filter_map(|w| w.rsplit_once(':'))
+                .map(|(name, hash)| (name.to_string(), hash.to_string()))
+                .collect();
+            Some((key.to_string(), fields))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PaperBuilder;
+
+    const MANUAL: &str = r#"% My references
+@string{nips = {Advances in Neural Information Processing Systems}}
+
+@Article{lecun15,
+  author    = {LeCun, Yann and Bengio, Yoshua},
+  title     = {Deep learning},
+  journal   = {Nature},
+  year      = 2015,
+}
+"#;
+
+    fn paper() -> Paper {
+        PaperBuilder::new(
+            "1706.03762",
+            "Attention Is All You Need",
+            "https://arxiv.org/abs/1706.03762",
+            SourceType::Arxiv,
+        )
+        .authors("Ashish Vaswani; Noam Shazeer")
+        .published_date("2017-06-12")
+        .build()
+    }
+
+    #[test]
+    fn test_round_trip_keeps_text() {
+        let file = BibFile::parse(MANUAL).unwrap();
+        assert_eq!(file.to_string(), MANUAL);
+        assert_eq!(file.entries().count(), 1);
+
+        let entry = file.entries().next().unwrap();
+        assert_eq!(entry.key(), "lecun15");
+        assert_eq!(entry.kind(), "article");
+        assert_eq!(entry.field("year").as_deref(), Some("2015"));
+        assert_eq!(entry.to_paper().authors, "Yann LeCun; Yoshua Bengio");
+    }
+
+    #[test]
+    fn test_add_new_and_existing_entries() {
+        let mut file = BibFile::parse(MANUAL).unwrap();
+        let pattern = KeyPattern::default();
+
+        let (key, change) = file.add(&paper(), &pattern).unwrap();
+        assert_eq!(key, "vaswani2017attention");
+        assert_eq!(change, EntryChange::Added);
+        let text = file.to_string();
+        assert!(text.starts_with(MANUAL));
+        assert!(text.contains("}\n\n@misc{vaswani2017attention,\n  author = {Vaswani, Ashish"));
+        assert!(text.contains("\n@comment{research-master-sync:\nvaswani2017attention author:"));
+
+        // A manual entry matched by title gains the missing fields, aligned
+        // like its others, and keeps its key, type and values
+        let lecun = PaperBuilder::new("x", "Deep Learning", "", SourceType::OpenAlex)
+            .authors("Yann LeCun")
+            .published_date("2015-05-28")
+            .doi("10.1038/nature14539")
+            .build();
+        let (key, change) = file.add(&lecun, &pattern).unwrap();
+        assert_eq!(key, "lecun15");
+        assert_eq!(
+            change,
+            EntryChange::Updated(vec!["month".to_string(), "doi".to_string()])
+        );
+        let text = file.to_string();
+        assert!(text.contains(
+            "  year      = 2015,\n  month     = {5},\n  doi       = {10.1038/nature14539},\n}"
+        ));
+        assert!(text.contains("  author    = {LeCun, Yann and Bengio, Yoshua},\n"));
+
+        // Adding again changes nothing, and the fingerprints survive a reload
+        let mut file = BibFile::parse(&text).unwrap();
+        assert_eq!(file.to_string(), text);
+        assert_eq!(
+            file.add(&paper(), &pattern).unwrap().1,
+            EntryChange::Updated(Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_update_keeps_manual_edits() {
+        let mut file = BibFile::default();
+        let pattern = KeyPattern::default();
+        file.add(&paper(), &pattern).unwrap();
+
+        // Edit the title, delete the URL
+        let text = file
+            .to_string()
+            .replace(
+                "{Attention Is All You Need}",
+                "{{Attention} Is All You Need}",
+            )
+            .replace(",\n  url = {https://arxiv.org/abs/1706.03762}", "");
+        let mut file = BibFile::parse(&text).unwrap();
+
+        let mut newer = paper();
+        newer.title = "Attention is all you need".to_string();
+        newer.url = "https://arxiv.org/abs/1706.03762v7".to_string();
+        newer.authors = "Ashish Vaswani; Noam Shazeer; Niki Parmar".to_string();
+        let (key, change) = file.add(&newer, &pattern).unwrap();
+        assert_eq!(key, "vaswani2017attention");
+        assert_eq!(change, EntryChange::Updated(vec!["author".to_string()]));
+
+        let text = file.to_string();
+        assert!(text.contains("{{Attention} Is All You Need}"));
+        assert!(text.contains("Shazeer, Noam and Parmar, Niki}"));
+        assert!(!text.contains("url = "));
+    }
+
+    #[test]
+    fn test_add_as_cited_key() {
+        let mut file = BibFile::parse(MANUAL).unwrap();
+        let key = "doi:10.1038/nature14539";
+        let lecun = PaperBuilder::new("x", "Deep learning", "", SourceType::OpenAlex)
+            .doi("10.1038/nature14539")
+            .build();
+        assert_eq!(file.add_as(&lecun, key).unwrap(), EntryChange::Added);
+        assert_eq!(
+            file.get(key).unwrap().field("doi").as_deref(),
+            Some("10.1038/nature14539")
+        );
+        assert!(file
+            .to_string()
+            .contains("@misc{doi:10.1038/nature14539,\n"));
+        assert_eq!(
+            file.add_as(&lecun, key).unwrap(),
+            EntryChange::Updated(Vec::new())
+        );
+        assert!(file.get("lecun15").is_some());
+    }
+
+    #[test]
+    fn test_new_keys_avoid_existing_ones() {
+        let mut file = BibFile::parse("@misc{vaswani2017attention, title = {Other}}").unwrap();
+        let (key, _) = file.add(&paper(), &KeyPattern::default()).unwrap();
+        assert_eq!(key, "vaswani2017attentiona");
+        assert!(file
+            .to_string()
+            .starts_with("@misc{vaswani2017attention, title = {Other}}\n\n@misc{"));
+    }
+
+    #[test]
+    fn test_add_entry_with_braces_in_doi() {
+        let mut file = BibFile::default();
+        let mut odd = paper();
+        odd.doi = Some("10.1000/a}b{c\\d".to_string());
+        odd.url = "https://example.org/}x".to_string();
+        let (key, _) = file.add(&odd, &KeyPattern::default()).unwrap();
+
+        // The entry still ends where it should, and a reload gives the
+        // original DOI and an equivalent URL
+        let file = BibFile::parse(&file.to_string()).unwrap();
+        let entry = file.get(&key).unwrap();
+        assert_eq!(entry.field("doi").as_deref(), Some("10.1000/a}b{c\\d"));
+        assert_eq!(entry.to_paper().doi.as_deref(), Some("10.1000/a}b{c\\d"));
+        assert_eq!(
+            entry.field("url").as_deref(),
+            Some("https://example.org/%7Dx")
+        );
+        assert_eq!(
+            entry.field("title").as_deref(),
+            Some("Attention Is All You Need")
+        );
+
+        // Adding it again finds nothing to change
+        let mut file = file;
+        assert_eq!(
+            file.add(&odd, &KeyPattern::default()).unwrap().1,
+            EntryChange::Updated(Vec::new())
+        );
+    }
+}
@@ -0,0 +1,974 @@
+//! BibTeX/BibLaTeX reading and writing.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use serde_json::json;
+
+use super::{
+    display_name, entry_type, extra_text, inverted_name, iso_date, join_keywords, split_name,
+    unique_keys, FormatError,
+};
+use crate::models::{Paper, PaperBuilder, SourceType};
+
+/// Fields kept in `extra` on import and written back on export, in output order
+const EXTRA_FIELDS: &[&str] = &[
+    "editor",
+    "volume",
+    "number",
+    "pages",
+    "chapter",
+    "edition",
+    "series",
+    "publisher",
+    "address",
+    "school",
+    "institution",
+    "organization",
+    "howpublished",
+    "isbn",
+    "issn",
+    "eprint",
+    "archiveprefix",
+    "primaryclass",
+    "note",
+];
+
+/// Title words skipped when building cite keys
+const KEY_STOPWORDS: &[&str] = &[
+    "a", "an", "the", "on", "of", "in", "for", "and", "to", "with", "from", "towards",
+];
+
+/// Default cite key: first author's family name, year and first significant
+/// title word, e.g. `vaswani2017attention`
+pub fn cite_key(paper: &Paper) -> String {
+    let family = paper
+        .author_list()
+        .first()
+        .map(|name| split_name(name).0)
+        .unwrap_or_default();
+    let word = paper
+        .title
+        .split(|c: char| !c.is_alphanumeric())
+        .map(key_text)
+        .find(|w| !w.is_empty() && !KEY_STOPWORDS.contains(&w.as_str()))
+        .unwrap_or_default();
+
+    let mut key = key_text(&family);
+    if key.is_empty() {
+        key.push_str("anon");
+    }
+    if let Some(year) = paper.year() {
+        key.push_str(&year.to_string());
+    }
+    key.push_str(&word);
+    key
+}
+
+/// Lowercase ASCII letters and digits of `text`, with common accents removed
+fn key_text(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| c.to_lowercase())
+        .map(|c| match c {
+            'à'..='å' => 'a',
+            'ç' => 'c',
+            'è'..='ë' => 'e',
+            'ì'..='ï' => 'i',
+            'ñ' => 'n',
+            'ò'..='ö' | 'ø' => 'o',
+            'ù'..='ü' => 'u',
+            'ý' | 'ÿ' => 'y',
+            _ => c,
+        })
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
+
+/// Placeholders allowed in a [`KeyPattern`]
+const KEY_PLACEHOLDERS: &[&str] = &[
+    "author",
+    "authors",
+    "year",
+    "shortyear",
+    "firstword",
+    "title",
+];
+
+/// A cite key pattern such as `{author}{year}{firstword}`
+///
+/// Placeholders are `{author}` (first author's family name), `{authors}` (up
+/// to three family names, or the first followed by `etal`), `{year}`,
+/// `{shortyear}` (last two digits), `{firstword}` (first significant title
+/// word) and `{title}` (first three significant title words). Text outside the
+/// placeholders is copied into the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPattern {
+    parts: Vec<KeyPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeyPart {
+    Text(String),
+    Placeholder(&'static str),
+}
+
+impl KeyPattern {
+    /// The pattern of [`cite_key`]
+    pub const DEFAULT: &'static str = "{author}{year}{firstword}";
+
+    /// Parse a pattern, rejecting unknown placeholders and characters that are
+    /// not allowed in keys
+    pub fn parse(pattern: &str) -> Result<Self, FormatError> {
+        let invalid = |message: String| FormatError::KeyPattern(message);
+        let mut parts = Vec::new();
+        let mut rest = pattern.trim();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('{') {
+                let (name, tail) = after
+                    .split_once('}')
+                    .ok_or_else(|| invalid(format!("unclosed '{{' in '{}'", pattern)))?;
+                let placeholder = KEY_PLACEHOLDERS
+                    .iter()
+                    .find(|p| p.eq_ignore_ascii_case(name.trim()))
+                    .ok_or_else(|| {
+                        invalid(format!(
+                            "unknown placeholder '{{{}}}' (expected one of {})",
+                            name,
+                            KEY_PLACEHOLDERS
+                                .iter()
+                                .map(|p| format!("{{{}}}", p))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ))
+                    })?;
+                parts.push(KeyPart::Placeholder(placeholder));
+                rest = tail;
+            } else {
+                let end = rest.find('{').unwrap_or(rest.len());
+                let text = &rest[..end];
+                if let Some(c) = text.chars().find(|c| !is_key_char(*c)) {
+                    return Err(invalid(format!("'{}' is not allowed in cite keys", c)));
+                }
+                parts.push(KeyPart::Text(text.to_string()));
+                rest = &rest[end..];
+            }
+        }
+        if !parts.iter().any(|p| matches!(p, KeyPart::Placeholder(_))) {
+            return Err(invalid(format!("'{}' has no placeholders", pattern)));
+        }
+        Ok(Self { parts })
+    }
+
+    /// The key for a paper; empty author names become `anon`
+    pub fn key(&self, paper: &Paper) -> String {
+        let families: Vec<String> = paper
+            .author_list()
+            .iter()
+            .map(|name| key_text(&split_name(name).0))
+            .filter(|family| !family.is_empty())
+            .collect();
+        let words: Vec<String> = paper
+            .title
+            .split(|c: char| !c.is_alphanumeric())
+            .map(key_text)
+            .filter(|w| !w.is_empty() && !KEY_STOPWORDS.contains(&w.as_str()))
+            .take(3)
+            .collect();
+        let year = paper.year().map(|y| y.to_string()).unwrap_or_default();
+
+        let mut key = String::new();
+        for part in &self.parts {
+            match part {
+                KeyPart::Text(text) => key.push_str(text),
+                KeyPart::Placeholder("author") => {
+                    key.push_str(families.first().map_or("anon", String::as_str))
+                }
+                KeyPart::Placeholder("authors") => match families.len() {
+                    0 => key.push_str("anon"),
+                    1..=3 => key.push_str(&families.concat()),
+                    _ => {
+                        key.push_str(&families[0]);
+                        key.push_str("etal");
+                    }
+                },
+                KeyPart::Placeholder("year") => key.push_str(&year),
+                KeyPart::Placeholder("shortyear") => {
+                    key.push_str(year.get(year.len().saturating_sub(2)..).unwrap_or(""))
+                }
+                KeyPart::Placeholder("firstword") => {
+                    key.push_str(words.first().map_or("", String::as_str))
+                }
+                KeyPart::Placeholder(_) => key.push_str(&words.concat()),
+            }
+        }
+        key
+    }
+}
+
+impl Default for KeyPattern {
+    fn default() -> Self {
+        Self::parse(Self::DEFAULT).expect("default pattern is valid")
+    }
+}
+
+impl std::str::FromStr for KeyPattern {
+    type Err = FormatError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Self::parse(pattern)
+    }
+}
+
+/// Characters kept from pattern text; BibTeX keys cannot contain spaces,
+/// commas or braces
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-_:./+".contains(c)
+}
+
+/// Parse every entry of a BibTeX file
+pub(super) fn read(content: &str) -> Result<Vec<Paper>, FormatError> {
+    let mut parser = Parser::new(content);
+    let mut papers = Vec::new();
+
+    while let Some(at) = content[parser.pos..].find('@') {
+        parser.pos += at + 1;
+        let start = parser.pos;
+        let kind = parser.ident().to_lowercase();
+        parser.skip_ws();
+        let close = match parser.peek() {
+            Some(b'{') => b'}',
+            Some(b'(') => b')',
+            // A stray '@' outside an entry, which BibTeX treats as a comment
+            _ => continue,
+        };
+        parser.pos += 1;
+
+        match kind.as_str() {
+            "" => continue,
+            "comment" | "preamble" => parser.skip_group(close, start)?,
+            "string" => parser.string(close, start)?,
+            _ => {
+                let entry = parser.entry(close, start)?;
+                let fields = entry.fields.into_iter().map(|f| (f.name, f.value));
+                papers.push(to_paper(&kind, &entry.key, fields.collect()));
+            }
+        }
+    }
+
+    Ok(papers)
+}
+
+/// An entry's key and fields, with their positions in the source
+#[derive(Debug, Clone)]
+pub(super) struct RawEntry {
+    pub(super) key: String,
+    /// Byte offset just past the key
+    pub(super) key_end: usize,
+    pub(super) fields: Vec<RawField>,
+}
+
+/// A field with its value macros expanded and the positions of its name and
+/// raw value in the source
+#[derive(Debug, Clone)]
+pub(super) struct RawField {
+    pub(super) name: String,
+    pub(super) value: String,
+    pub(super) name_start: usize,
+    pub(super) value_span: Range<usize>,
+}
+
+/// Cursor over BibTeX source; structural characters are all ASCII
+pub(super) struct Parser<'a> {
+    text: &'a str,
+    pub(super) pos: usize,
+    pub(super) strings: HashMap<String, String>,
+}
+
+impl<'a> Parser<'a> {
+    pub(super) fn new(text: &'a str) -> Self {
+        let months = [
+            "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+        ];
+        let strings = months
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (m.to_string(), (i + 1).to_string()))
+            .collect();
+        Self {
+            text,
+            pos: 0,
+            strings,
+        }
+    }
+
+    pub(super) fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    pub(super) fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, at: usize, message: impl Into<String>) -> FormatError {
+        FormatError::Parse {
+            line: self.text[..at.min(self.text.len())].matches('\n').count() + 1,
+            message: message.into(),
+        }
+    }
+
+    pub(super) fn expect(&mut self, byte: u8, start: usize) -> Result<(), FormatError> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(
+                start,
+                format!("expected '{}' in entry starting here", byte as char),
+            ))
+        }
+    }
+
+    /// Entry types, field names and macro names
+    pub(super) fn ident(&mut self) -> &'a str {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|b| b.is_ascii_alphanumeric() || b"_-:.+/".contains(&b))
+        {
+            self.pos += 1;
+        }
+        &self.text[start..self.pos]
+    }
+
+    /// Skip the body of `@comment{...}` or `@preamble{...}`
+    pub(super) fn skip_group(&mut self, close: u8, start: usize) -> Result<(), FormatError> {
+        let mut depth = 0usize;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'{' => depth += 1,
+                b'}' if depth > 0 => depth -= 1,
+                _ if b == close && depth == 0 => return Ok(()),
+                _ => {}
+            }
+        }
+        Err(self.error(start, "unterminated entry"))
+    }
+
+    /// The body of `@string{name = value}`, remembered for later fields
+    pub(super) fn string(&mut self, close: u8, start: usize) -> Result<(), FormatError> {
+        self.skip_ws();
+        let name = self.ident().to_lowercase();
+        self.skip_ws();
+        self.expect(b'=', start)?;
+        let value = self.value(close, start)?;
+        self.skip_ws();
+        self.expect(close, start)?;
+        self.strings.insert(name, value);
+        Ok(())
+    }
+
+    /// `key, name = value, ...` up to the closing delimiter
+    pub(super) fn entry(&mut self, close: u8, start: usize) -> Result<RawEntry, FormatError> {
+        self.skip_ws();
+        let key_start = self.pos;
+        while self.peek().is_some_and(|b| b != b',' && b != close) {
+            self.pos += 1;
+        }
+        let key = self.text[key_start..self.pos].trim().to_string();
+        let key_end = key_start + self.text[key_start..self.pos].trim_end().len();
+
+        let mut fields = Vec::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => return Err(self.error(start, "unterminated entry")),
+                Some(b) if b == close => {
+                    self.pos += 1;
+                    return Ok(RawEntry {
+                        key,
+                        key_end,
+                        fields,
+                    });
+                }
+                Some(b',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let name_start = self.pos;
+            let name = self.ident().to_lowercase();
+            if name.is_empty() {
+                return Err(self.error(self.pos, format!("expected a field name in '{}'", key)));
+            }
+            self.skip_ws();
+            self.expect(b'=', start)?;
+            self.skip_ws();
+            let value_start = self.pos;
+            let value = self.value(close, start)?;
+            let value_end = value_start + self.text[value_start..self.pos].trim_end().len();
+            fields.push(RawField {
+                name,
+                value,
+                name_start,
+                value_span: value_start..value_end,
+            });
+        }
+    }
+
+    /// A field value: braced, quoted, number or macro pieces joined with `#`
+    pub(super) fn value(&mut self, close: u8, start: usize) -> Result<String, FormatError> {
+        let mut value = String::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                Some(b'{') => {
+                    self.pos += 1;
+                    value.push_str(self.delimited(b'}', start)?);
+                }
+                Some(b'"') => {
+                    self.pos += 1;
+                    value.push_str(self.delimited(b'"', start)?);
+                }
+                Some(b) if b.is_ascii_alphanumeric() => {
+                    let word = self.ident();
+                    match self.strings.get(&word.to_lowercase()) {
+                        Some(expanded) => value.push_str(expanded),
+                        None => value.push_str(word),
+                    }
+                }
+                Some(b) if b == close || b == b',' => return Ok(value),
+                _ => return Err(self.error(start, "malformed field value")),
+            }
+            self.skip_ws();
+            if self.peek() == Some(b'#') {
+                self.pos += 1;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Text up to `end` at brace depth zero, keeping inner braces
+    fn delimited(&mut self, end: u8, start: usize) -> Result<&'a str, FormatError> {
+        let from = self.pos;
+        let mut depth = 0usize;
+        while let Some(b) = self.peek() {
+            match b {
+                b'\\' => self.pos += 1,
+                b'{' => depth += 1,
+                b'}' if depth > 0 => depth -= 1,
+                _ if b == end && depth == 0 => {
+                    let text = &self.text[from..self.pos];
+                    self.pos += 1;
+                    return Ok(text);
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        Err(self.error(start, "unbalanced braces or quotes"))
+    }
+}
+
+pub(super) fn to_paper(kind: &str, key: &str, fields: Vec<(String, String)>) -> Paper {
+    let mut paper = PaperBuilder::new(key, "", "", SourceType::Other("bibtex".to_string()))
+        .extra("citekey", json!(key))
+        .extra("entry_type", json!(kind))
+        .build();
+
+    let mut date = None;
+    let (mut year, mut month, mut day) = (None, None, None);
+
+    for (name, raw) in fields {
+        let value = decode(&raw);
+        if value.is_empty() {
+            continue;
+        }
+        match name.as_str() {
+            "title" => paper.title = value,
+            "author" => paper.authors = names(&raw).join("; "),
+            "editor" => {
+                paper = with_extra(paper, "editor", names(&raw).join("; "));
+            }
+            "abstract" => paper.r#abstract = value,
+            "doi" => paper.doi = Some(strip_doi_prefix(&value)),
+            "url" => paper.url = value,
+            "date" => date = Some(value),
+            "year" => year = Some(value),
+            "month" => month = Some(value),
+            "day" => day = Some(value),
+            "keywords" => paper.keywords = join_keywords([value.as_str()]),
+            "journal" | "journaltitle" => paper = with_extra(paper, "journal", value),
+            "pages" => paper = with_extra(paper, "pages", value.replace("--", "-")),
+            _ => paper = with_extra(paper, &name, value),
+        }
+    }
+
+    paper.published_date = date
+        .filter(|d| iso_date(d.get(..4).unwrap_or(""), None, None).is_some())
+        .map(|d| d.chars().take(10).collect())
+        .or_else(|| iso_date(year.as_deref()?, month.as_deref(), day.as_deref()));
+    paper
+}
+
+fn with_extra(mut paper: Paper, key: &str, value: String) -> Paper {
+    paper
+        .extra
+        .get_or_insert_with(HashMap::new)
+        .insert(key.to_string(), json!(value));
+    paper
+}
+
+/// DOI without a resolver URL or `doi:` prefix
+fn strip_doi_prefix(doi: &str) -> String {
+    let lower = doi.to_lowercase();
+    for prefix in [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "doi:",
+    ] {
+        if lower.starts_with(prefix) {
+            return doi[prefix.len()..].trim().to_string();
+        }
+    }
+    doi.trim().to_string()
+}
+
+/// Names in an `author` or `editor` field, as "Given Family"
+fn names(raw: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut depth = 0i32;
+
+    for word in raw.split_whitespace() {
+        if depth == 0 && word.eq_ignore_ascii_case("and") {
+            names.push(current.join(" "));
+            current.clear();
+            continue;
+        }
+        depth += word.matches('{').count() as i32 - word.matches('}').count() as i32;
+        current.push(word);
+    }
+    names.push(current.join(" "));
+
+    names
+        .iter()
+        .map(|n| decode(n))
+        .filter(|n| !n.is_empty() && n != "others")
+        .map(|n| display_name(&n))
+        .collect()
+}
+
+/// Plain text of a BibTeX value: LaTeX accents and escapes decoded, braces
+/// removed and whitespace collapsed
+pub(super) fn decode(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let Some(&next) = chars.peek() else {
+                    break;
+                };
+                if !next.is_ascii_alphabetic() {
+                    chars.next();
+                    if "\"'`^~=.".contains(next) {
+                        let letter = accent_argument(&mut chars);
+                        push_accented(&mut out, next, &letter);
+                    } else if next == '\\' {
+                        out.push(' ');
+                    } else {
+                        out.push(next);
+                    }
+                    continue;
+                }
+
+                let mut command = String::new();
+                while let Some(&l) = chars.peek().filter(|l| l.is_ascii_alphabetic()) {
+                    command.push(l);
+                    chars.next();
+                }
+                let symbol = match command.as_str() {
+                    "ss" => Some("ß"),
+                    "o" => Some("ø"),
+                    "O" => Some("Ø"),
+                    "aa" => Some("å"),
+                    "AA" => Some("Å"),
+                    "ae" => Some("æ"),
+                    "AE" => Some("Æ"),
+                    "oe" => Some("œ"),
+                    "OE" => Some("Œ"),
+                    "l" => Some("ł"),
+                    "L" => Some("Ł"),
+                    "i" => Some("ı"),
+                    "textendash" => Some("–"),
+                    "textemdash" => Some("—"),
+                    "textbackslash" => Some("\\"),
+                    _ => None,
+                };
+                if let Some(symbol) = symbol {
+                    out.push_str(symbol);
+                    if chars.peek() == Some(&'{') {
+                        let mut lookahead = chars.clone();
+                        lookahead.next();
+                        if lookahead.peek() == Some(&'}') {
+                            chars.next();
+                            chars.next();
+                        }
+                    }
+                } else if command.len() == 1 && "cvuHkrdb".contains(command.as_str()) {
+                    let letter = accent_argument(&mut chars);
+                    push_accented(&mut out, command.chars().next().unwrap_or(' '), &letter);
+                } else if matches!(
+                    command.as_str(),
+                    "emph" | "textit" | "textbf" | "textsc" | "textrm" | "texttt" | "mbox"
+                ) {
+                    // Formatting commands: keep the argument, whose braces are dropped below
+                } else {
+                    out.push('\\');
+                    out.push_str(&command);
+                }
+            }
+            '{' | '}' => {}
+            '~' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The letter an accent command applies to: `{o}`, `o` or `{\i}`
+fn accent_argument(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    while chars.peek() == Some(&' ') {
+        chars.next();
+    }
+    if chars.peek() != Some(&'{') {
+        return chars.next().map(String::from).unwrap_or_default();
+    }
+    chars.next();
+    let mut argument = String::new();
+    for c in chars.by_ref() {
+        if c == '}' {
+            break;
+        }
+        argument.push(c);
+    }
+    match argument.as_str() {
+        "\\i" => "i".to_string(),
+        "\\j" => "j".to_string(),
+        _ => argument,
+    }
+}
+
+fn push_accented(out: &mut String, accent: char, letter: &str) {
+    const TABLE: &[(char, &str, &str)] = &[
+        ('"', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+        ('\'', "aeiouycnszAEIOUYCNSZ", "áéíóúýćńśźÁÉÍÓÚÝĆŃŚŹ"),
+        ('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+        ('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+        ('~', "anoANO", "ãñõÃÑÕ"),
+        ('=', "aeiouAEIOU", "āēīōūĀĒĪŌŪ"),
+        ('.', "zZ", "żŻ"),
+        ('c', "csCS", "çşÇŞ"),
+        ('v', "cszrenCSZREN", "čšžřěňČŠŽŘĚŇ"),
+        ('u', "agAG", "ăğĂĞ"),
+        ('H', "ouOU", "őűŐŰ"),
+        ('k', "aeAE", "ąęĄĘ"),
+        ('r', "auAU", "åůÅŮ"),
+    ];
+    let mut letters = letter.chars();
+    if let (Some(l), None) = (letters.next(), letters.next()) {
+        let accented =
+            TABLE
+                .iter()
+                .find(|(a, _, _)| *a == accent)
+                .and_then(|(_, plain, marked)| {
+                    plain
+                        .chars()
+                        .position(|p| p == l)
+                        .and_then(|i| marked.chars().nth(i))
+                });
+        if let Some(accented) = accented {
+            out.push(accented);
+            return;
+        }
+    }
+    out.push_str(letter);
+}
+
+/// Write papers as BibTeX entries
+///
+/// Retracted and corrected papers are preceded by a `%` comment with the
+/// warning, which BibTeX and biber ignore.
+pub(super) fn write(papers: &[Paper]) -> String {
+    papers
+        .iter()
+        .zip(unique_keys(papers))
+        .map(|(paper, key)| match paper.status {
+            Some(ref status) => format!("% {}\n{}", status.warning(), write_entry(paper, &key)),
+            None => write_entry(paper, &key),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One entry, ending in a newline
+pub(super) fn write_entry(paper: &Paper, key: &str) -> String {
+    let body: Vec<String> = fields(paper)
+        .iter()
+        .map(|(name, value)| format!("  {} = {{{}}}", name, value))
+        .collect();
+    format!(
+        "@{}{{{},\n{}\n}}\n",
+        entry_type(paper),
+        key,
+        body.join(",\n")
+    )
+}
+
+/// The fields written for a paper, in output order, with values escaped
+pub(super) fn fields(paper: &Paper) -> Vec<(&'static str, String)> {
+    let kind = entry_type(paper);
+    let mut fields: Vec<(&str, String)> = Vec::new();
+
+    let authors = paper.author_list();
+    if !authors.is_empty() {
+        let names: Vec<String> = authors.iter().map(|a| inverted_name(a)).collect();
+        fields.push(("author", escape(&names.join(" and "))));
+    }
+    if !paper.title.trim().is_empty() {
+        fields.push(("title", escape(paper.title.trim())));
+    }
+
+    let venue_field = match kind {
+        "inproceedings" | "incollection" => "booktitle",
+        _ => "journal",
+    };
+    let venue = extra_text(paper, venue_field).or_else(|| match kind {
+        "article" | "inproceedings" | "incollection" => paper.venue().map(str::to_string),
+        _ => None,
+    });
+    if let Some(venue) = venue {
+        fields.push((venue_field, escape(&venue)));
+    }
+
+    if let Some(date) = paper.published_date.as_deref() {
+        if let Some(year) = paper.year() {
+            fields.push(("year", year.to_string()));
+        }
+        if let Some(month) = super::date_month(date) {
+            fields.push(("month", month.to_string()));
+        }
+    }
+
+    for &name in EXTRA_FIELDS {
+        let Some(value) = extra_text(paper, name) else {
+            continue;
+        };
+        let value = match name {
+            "editor" => value
+                .split(';')
+                .map(|n| inverted_name(n.trim()))
+                .collect::<Vec<_>>()
+                .join(" and "),
+            "pages" => value.replace("--", "-").replace('-', "--"),
+            _ => value,
+        };
+        fields.push((name, escape(&value)));
+    }
+    if paper.source == SourceType::Arxiv && extra_text(paper, "eprint").is_none() {
+        fields.push(("eprint", paper.paper_id.clone()));
+        fields.push(("archiveprefix", "arXiv".to_string()));
+    }
+
+    if let Some(doi) = paper.doi.as_deref().filter(|d| !d.trim().is_empty()) {
+        fields.push(("doi", escape_doi(doi.trim())));
+    }
+    if !paper.url.trim().is_empty() {
+        fields.push(("url", escape_url(paper.url.trim())));
+    }
+    let keywords = paper.keyword_list();
+    if !keywords.is_empty() {
+        fields.push(("keywords", escape(&keywords.join(", "))));
+    }
+    if !paper.r#abstract.trim().is_empty() {
+        fields.push(("abstract", escape(paper.r#abstract.trim())));
+    }
+    fields
+}
+
+/// Escape a DOI so that it reads back unchanged
+///
+/// Braces and backslashes are escaped rather than balanced, since dropping
+/// one would make it a different identifier.
+fn escape_doi(doi: &str) -> String {
+    let mut out = String::with_capacity(doi.len());
+    for c in doi.chars() {
+        match c {
+            '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' => out.push_str("\\textbackslash{}"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Percent-encode the braces and backslashes of a URL, which then points to
+/// the same resource and cannot end the field early
+fn escape_url(url: &str) -> String {
+    url.replace('\\', "%5C")
+        .replace('{', "%7B")
+        .replace('}', "%7D")
+}
+
+/// Escape characters that are special in BibTeX text fields
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut depth = 0i32;
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '#' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '{' => {
+                depth += 1;
+                out.push(c);
+            }
+            // Drop closing braces that would end the field early
+            '}' if depth == 0 => {}
+            '}' => {
+                depth -= 1;
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out.extend(std::iter::repeat_n('}', depth.max(0) as usize));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+This text outside entries is ignored.
+
+@string{ieee = "IEEE Transactions on Pattern Analysis"}
+
+@comment{jabref-meta: databaseType:bibtex;}
+
+@Article{smith2020,
+  author    = {M{\"u}ller, J{\"o}rg and Smith, Jane and {World Health Organization}},
+  title     = {{Deep} Learning for {\'E}cole \& Beyond},
+  journal   = ieee # " and Machine Intelligence",
+  year      = 2020,
+  month     = mar,
+  volume    = {42},
+  pages     = {1--10},
+  doi       = {https://doi.org/10.1109/TPAMI.2020.1},
+  keywords  = {deep learning, vision},
+  mendeley-tags = {to-read},
+}
+
+@inproceedings(conf,
+  title = "A {"}Quoted{"} Title",
+  booktitle = {Proceedings of NeurIPS},
+  date = {2019-12-08}
+)
+"#;
+
+    #[test]
+    fn test_read_bibtex() {
+        let papers = read(SAMPLE).unwrap();
+        assert_eq!(papers.len(), 2);
+
+        let paper = &papers[0];
+        assert_eq!(paper.paper_id, "smith2020");
+        assert_eq!(paper.title, "Deep Learning for École & Beyond");
+        assert_eq!(
+            paper.authors,
+            "Jörg Müller; Jane Smith; World Health Organization"
+        );
+        assert_eq!(
+            paper.venue(),
+            Some("IEEE Transactions on Pattern Analysis and Machine Intelligence")
+        );
+        assert_eq!(paper.published_date.as_deref(), Some("2020-03"));
+        assert_eq!(paper.doi.as_deref(), Some("10.1109/TPAMI.2020.1"));
+        assert_eq!(paper.keywords.as_deref(), Some("deep learning; vision"));
+        assert_eq!(extra_text(paper, "pages").as_deref(), Some("1-10"));
+        assert_eq!(
+            extra_text(paper, "mendeley-tags").as_deref(),
+            Some("to-read")
+        );
+        assert_eq!(entry_type(paper), "article");
+
+        let paper = &papers[1];
+        assert_eq!(paper.title, "A \"Quoted\" Title");
+        assert_eq!(paper.venue(), Some("Proceedings of NeurIPS"));
+        assert_eq!(paper.published_date.as_deref(), Some("2019-12-08"));
+        assert_eq!(entry_type(paper), "inproceedings");
+    }
+
+    #[test]
+    fn test_key_pattern() {
+        let paper = PaperBuilder::new("1", "On the Origin of Species", "", SourceType::Arxiv)
+            .authors("Charles Darwin; Alfred Russel Wallace; Ada Lovelace; Émile Borel")
+            .published_date("1859-11-24")
+            .build();
+        assert_eq!(KeyPattern::default().key(&paper), cite_key(&paper));
+        let key = |pattern: &str| KeyPattern::parse(pattern).unwrap().key(&paper);
+        assert_eq!(key("{author}{year}{firstword}"), "darwin1859origin");
+        assert_eq!(key("{Author}:{shortyear}"), "darwin:59");
+        assert_eq!(key("{authors}-{title}"), "darwinetal-originspecies");
+
+        let anonymous = PaperBuilder::new("2", "", "", SourceType::Arxiv).build();
+        assert_eq!(KeyPattern::default().key(&anonymous), "anon");
+
+        assert!(KeyPattern::parse("{author}{month}").is_err());
+        assert!(KeyPattern::parse("{author} {year}").is_err());
+        assert!(KeyPattern::parse("{author").is_err());
+        assert!(KeyPattern::parse("plain").is_err());
+    }
+
+    #[test]
+    fn test_read_bibtex_errors() {
+        let err = read("@article{key,\n  title = {Unclosed\n").unwrap_err();
+        assert!(matches!(err, FormatError::Parse { line: 1, .. }));
+        assert!(read("@article{key, = {x}}").is_err());
+        assert!(read("no entries here").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bibtex_round_trip() {
+        let papers = read(SAMPLE).unwrap();
+        let written = write(&papers);
+        assert!(written.starts_with("@article{smith2020,\n"));
+        assert!(written.contains("author = {Müller, Jörg and Smith, Jane and "));
+        assert!(written.contains("title = {Deep Learning for École \\& Beyond}"));
+        assert!(written.contains("pages = {1--10}"));
+        assert!(written.contains("month = {3}"));
+        assert!(written.contains("@inproceedings{conf,\n"));
+        assert!(written.contains("booktitle = {Proceedings of NeurIPS}"));
+
+        let again = read(&written).unwrap();
+        assert_eq!(again.len(), 2);
+        assert_eq!(again[0].title, papers[0].title);
+        assert_eq!(again[0].authors, papers[0].authors);
+        assert_eq!(again[0].doi, papers[0].doi);
+        assert_eq!(again[1].published_date.as_deref(), Some("2019-12"));
+    }
+}
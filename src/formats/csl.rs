@@ -0,0 +1,343 @@
+//! CSL-JSON reading and writing.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use super::{
+    csl_type, date_month, entry_type, extra_text, from_csl_type, iso_date, join_keywords,
+    split_name, unique_keys, FormatError,
+};
+use crate::models::{Paper, PaperBuilder, SourceType};
+
+/// CSL variables stored in `extra` under BibTeX field names
+const RENAMED: &[(&str, &str)] = &[
+    ("container-title", "container_title"),
+    ("collection-title", "series"),
+    ("issue", "number"),
+    ("page", "pages"),
+    ("publisher-place", "address"),
+    ("ISSN", "issn"),
+    ("ISBN", "isbn"),
+];
+
+/// `extra` fields written under their own name
+const SAME_NAME: &[&str] = &[
+    "volume",
+    "publisher",
+    "edition",
+    "note",
+    "language",
+    "version",
+];
+
+/// Parse a CSL-JSON array of items (or a single item)
+pub(super) fn read(content: &str) -> Result<Vec<Paper>, FormatError> {
+    let items = match serde_json::from_str::<Value>(content)? {
+        Value::Array(items) => items,
+        item @ Value::Object(_) => vec![item],
+        _ => return Ok(Vec::new()),
+    };
+
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, item)| match item {
+            Value::Object(map) => Some(to_paper(map, index)),
+            _ => None,
+        })
+        .collect())
+}
+
+fn to_paper(item: serde_json::Map<String, Value>, index: usize) -> Paper {
+    let id = match item.get("id") {
+        Some(Value::String(id)) => id.clone(),
+        Some(Value::Number(id)) => id.to_string(),
+        _ => format!("csl-{}", index + 1),
+    };
+    let mut paper = PaperBuilder::new(id.clone(), "", "", SourceType::Other("csl".to_string()))
+        .extra("citekey", json!(id))
+        .build();
+    let mut extra: HashMap<String, Value> = HashMap::new();
+
+    for (name, value) in item {
+        let text = text(&value);
+        match name.as_str() {
+            "id" => {}
+            "type" => {
+                let kind = text.as_deref().map(from_csl_type).unwrap_or("misc");
+                extra.insert("entry_type".to_string(), json!(kind));
+            }
+            "title" => paper.title = text.unwrap_or_default(),
+            "author" => paper.authors = names(&value).join("; "),
+            "editor" => {
+                extra.insert("editor".to_string(), json!(names(&value).join("; ")));
+            }
+            "abstract" => paper.r#abstract = text.unwrap_or_default(),
+            "DOI" => paper.doi = text,
+            "URL" => paper.url = text.unwrap_or_default(),
+            "issued" => paper.published_date = date(&value),
+            "keyword" => paper.keywords = text.as_deref().and_then(|k| join_keywords([k])),
+            _ => {
+                let key = RENAMED
+                    .iter()
+                    .find(|(csl, _)| *csl == name)
+                    .map(|(_, bib)| bib.to_string())
+                    .unwrap_or(name);
+                extra.insert(key, value);
+            }
+        }
+    }
+
+    paper.extra.get_or_insert_with(HashMap::new).extend(extra);
+    paper
+}
+
+/// A string or number variable as text
+fn text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Names as "Given Family"; institutions use `literal`
+fn names(value: &Value) -> Vec<String> {
+    let Some(names) = value.as_array() else {
+        return Vec::new();
+    };
+    names
+        .iter()
+        .filter_map(|name| {
+            if let Some(literal) = name.get("literal").and_then(Value::as_str) {
+                return Some(literal.trim().to_string());
+            }
+            let part = |key: &str| {
+                name.get(key)
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+            };
+            let parts: Vec<&str> = [
+                part("given"),
+                part("dropping-particle"),
+                part("non-dropping-particle"),
+                part("family"),
+                part("suffix"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            (!parts.is_empty()).then(|| parts.join(" "))
+        })
+        .collect()
+}
+
+/// ISO date from `{"date-parts": [[2020, 5, 1]]}` or `{"raw": "2020-05-01"}`
+fn date(value: &Value) -> Option<String> {
+    if let Some(parts) = value
+        .get("date-parts")
+        .and_then(|p| p.get(0))
+        .and_then(Value::as_array)
+    {
+        let part = |i: usize| parts.get(i).and_then(text);
+        return iso_date(&part(0)?, part(1).as_deref(), part(2).as_deref());
+    }
+    let raw = value
+        .get("raw")
+        .or_else(|| value.get("literal"))
+        .and_then(Value::as_str)?;
+    let mut parts = raw.trim().split(['-', '/']);
+    iso_date(parts.next()?, parts.next(), parts.next())
+}
+
+/// CSL-JSON items for papers, with unique cite keys as IDs
+pub(super) fn items(papers: &[Paper]) -> Vec<Value> {
+    papers
+        .iter()
+        .zip(unique_keys(papers))
+        .map(|(paper, key)| item(paper, &key))
+        .collect()
+}
+
+/// Write papers as a CSL-JSON array
+pub(super) fn write(papers: &[Paper]) -> String {
+    serde_json::to_string_pretty(&items(papers)).unwrap_or_default() + "\n"
+}
+
+fn item(paper: &Paper, key: &str) -> Value {
+    let kind = entry_type(paper);
+    let mut item = serde_json::Map::new();
+    item.insert("id".to_string(), json!(key));
+    item.insert("type".to_string(), json!(csl_type(kind)));
+    if !paper.title.trim().is_empty() {
+        item.insert("title".to_string(), json!(paper.title.trim()));
+    }
+
+    let authors: Vec<&str> = paper.author_list();
+    if !authors.is_empty() {
+        item.insert("author".to_string(), csl_names(authors));
+    }
+    if let Some(editors) = extra_text(paper, "editor") {
+        let editors: Vec<&str> = editors
+            .split(';')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .collect();
+        item.insert("editor".to_string(), csl_names(editors));
+    }
+
+    if let (Some(date), Some(year)) = (paper.published_date.as_deref(), paper.year()) {
+        let mut parts = vec![json!(year)];
+        if let Some(month) = date_month(date) {
+            parts.push(json!(month));
+            if let Some(day) = date.get(8..10).and_then(|d| d.parse::<u32>().ok()) {
+                parts.push(json!(day));
+            }
+        }
+        item.insert("issued".to_string(), json!({ "date-parts": [parts] }));
+    }
+
+    let container = extra_text(paper, "booktitle")
+        .filter(|_| kind != "article")
+        .or_else(|| paper.venue().map(str::to_string));
+    if let Some(container) = container {
+        item.insert("container-title".to_string(), json!(container));
+    }
+    for &(csl, name) in RENAMED.iter().filter(|(csl, _)| *csl != "container-title") {
+        if let Some(value) = extra_text(paper, name) {
+            let value = if name == "pages" {
+                value.replace("--", "-")
+            } else {
+                value
+            };
+            item.insert(csl.to_string(), json!(value));
+        }
+    }
+    for &name in SAME_NAME {
+        if let Some(value) = extra_text(paper, name) {
+            item.insert(name.to_string(), json!(value));
+        }
+    }
+
+    if let Some(doi) = paper.doi.as_deref().filter(|d| !d.trim().is_empty()) {
+        item.insert("DOI".to_string(), json!(doi.trim()));
+    }
+    if !paper.url.trim().is_empty() {
+        item.insert("URL".to_string(), json!(paper.url.trim()));
+    }
+    if !paper.r#abstract.trim().is_empty() {
+        item.insert("abstract".to_string(), json!(paper.r#abstract.trim()));
+    }
+    let keywords = paper.keyword_list();
+    if !keywords.is_empty() {
+        item.insert("keyword".to_string(), json!(keywords.join(", ")));
+    }
+    if let Some(ref status) = paper.status {
+        item.insert("status".to_string(), json!(status.kind.to_string()));
+    }
+    item.insert("citation-key".to_string(), json!(key));
+    Value::Object(item)
+}
+
+/// Names as `{"family", "given"}`; single words are kept as `literal`
+fn csl_names(names: Vec<&str>) -> Value {
+    names
+        .into_iter()
+        .map(|name| match split_name(name) {
+            (family, given) if given.is_empty() => json!({ "literal": family }),
+            (family, given) => json!({ "family": family, "given": given }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{entry_type, extra_text};
+
+    #[test]
+    fn test_read_csl_json() {
+        let content = r#"[
+          {
+            "id": "vaswani2017",
+            "type": "paper-conference",
+            "title": "Attention Is All You Need",
+            "author": [
+              {"family": "Vaswani", "given": "Ashish"},
+              {"literal": "Google Brain"}
+            ],
+            "issued": {"date-parts": [[2017, 12]]},
+            "container-title": "Advances in NeurIPS",
+            "page": "5998-6008",
+            "volume": 30,
+            "DOI": "10.5555/3295222.3295349",
+            "keyword": "transformers, attention",
+            "citation-label": "custom"
+          },
+          {"type": "book", "title": "Untitled", "issued": {"raw": "1999"}}
+        ]"#;
+
+        let papers = read(content).unwrap();
+        assert_eq!(papers.len(), 2);
+
+        let paper = &papers[0];
+        assert_eq!(paper.paper_id, "vaswani2017");
+        assert_eq!(paper.authors, "Ashish Vaswani; Google Brain");
+        assert_eq!(paper.published_date.as_deref(), Some("2017-12"));
+        assert_eq!(paper.venue(), Some("Advances in NeurIPS"));
+        assert_eq!(paper.doi.as_deref(), Some("10.5555/3295222.3295349"));
+        assert_eq!(paper.keywords.as_deref(), Some("transformers; attention"));
+        assert_eq!(extra_text(paper, "volume").as_deref(), Some("30"));
+        assert_eq!(extra_text(paper, "pages").as_deref(), Some("5998-6008"));
+        assert_eq!(
+            extra_text(paper, "citation-label").as_deref(),
+            Some("custom")
+        );
+        assert_eq!(entry_type(paper), "inproceedings");
+
+        assert_eq!(papers[1].paper_id, "csl-2");
+        assert_eq!(papers[1].published_date.as_deref(), Some("1999"));
+        assert_eq!(entry_type(&papers[1]), "book");
+
+        assert!(read("not json").is_err());
+    }
+
+    #[test]
+    fn test_csl_json_round_trip() {
+        let paper = PaperBuilder::new(
+            "1706.03762",
+            "Attention Is All You Need",
+            "",
+            SourceType::Arxiv,
+        )
+        .authors("Ashish Vaswani; Plato")
+        .published_date("2017-06-12")
+        .doi("10.5555/3295222.3295349")
+        .extra("entry_type", json!("inproceedings"))
+        .extra("booktitle", json!("Advances in NeurIPS"))
+        .extra("pages", json!("5998--6008"))
+        .extra("volume", json!(30))
+        .build();
+
+        let items = items(std::slice::from_ref(&paper));
+        let item = &items[0];
+        assert_eq!(item["id"], "vaswani2017attention");
+        assert_eq!(item["type"], "paper-conference");
+        assert_eq!(item["author"][0]["family"], "Vaswani");
+        assert_eq!(item["author"][1]["literal"], "Plato");
+        assert_eq!(item["issued"]["date-parts"][0], json!([2017, 6, 12]));
+        assert_eq!(item["container-title"], "Advances in NeurIPS");
+        assert_eq!(item["page"], "5998-6008");
+        assert_eq!(item["volume"], "30");
+
+        let again = read(&write(&[paper])).unwrap();
+        assert_eq!(again[0].title, "Attention Is All You Need");
+        assert_eq!(again[0].authors, "Ashish Vaswani; Plato");
+        assert_eq!(again[0].published_date.as_deref(), Some("2017-06-12"));
+        assert_eq!(again[0].venue(), Some("Advances in NeurIPS"));
+        assert_eq!(entry_type(&again[0]), "inproceedings");
+    }
+}
@@ -0,0 +1,82 @@
+//! CSV writing.
+
+use crate::models::Paper;
+
+const HEADER: &[&str] = &[
+    "title",
+    "authors",
+    "year",
+    "venue",
+    "doi",
+    "url",
+    "pdf_url",
+    "citations",
+    "source",
+    "paper_id",
+    "status",
+];
+
+/// Write papers as CSV, one row per paper
+pub(super) fn write(papers: &[Paper]) -> String {
+    let mut out = HEADER.join(",");
+    out.push('\n');
+    for paper in papers {
+        let row = [
+            paper.title.clone(),
+            paper.authors.clone(),
+            paper.year().map(|y| y.to_string()).unwrap_or_default(),
+            paper.venue().unwrap_or_default().to_string(),
+            paper.doi.clone().unwrap_or_default(),
+            paper.url.clone(),
+            paper.pdf_url.clone().unwrap_or_default(),
+            paper.citations.map(|c| c.to_string()).unwrap_or_default(),
+            paper.source.id().to_string(),
+            paper.paper_id.clone(),
+            paper
+                .status
+                .as_ref()
+                .map(|s| s.kind.to_string())
+                .unwrap_or_default(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| quote(field)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote a field if it contains a delimiter, quote or line break (RFC 4180)
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+
+    #[test]
+    fn test_write_csv() {
+        let paper = PaperBuilder::new(
+            "1706.03762",
+            "Attention, \"all\" you need",
+            "",
+            SourceType::Arxiv,
+        )
+        .authors("Ashish Vaswani")
+        .published_date("2017-06-12")
+        .citations(100)
+        .build();
+        let csv = write(&[paper]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], HEADER.join(","));
+        assert_eq!(
+            lines[1],
+            "\"Attention, \"\"all\"\" you need\",Ashish Vaswani,2017,,,,,100,arxiv,1706.03762,"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! EndNote XML writing.
+
+use std::fmt::Write;
+
+use super::{container_title, entry_type, extra_text, inverted_name, page_range, unique_keys};
+use crate::models::Paper;
+use crate::utils::xml_escape;
+
+/// `extra` fields written as EndNote elements of the same meaning
+const EXTRA_ELEMENTS: &[(&str, &str)] = &[
+    ("volume", "volume"),
+    ("number", "number"),
+    ("edition", "edition"),
+    ("publisher", "publisher"),
+    ("address", "pub-location"),
+    ("isbn", "isbn"),
+    ("issn", "isbn"),
+    ("note", "notes"),
+    ("language", "language"),
+];
+
+/// Write papers as an EndNote XML document
+pub(super) fn write(papers: &[Paper]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<xml>\n  <records>\n");
+    for (number, (paper, key)) in papers.iter().zip(unique_keys(papers)).enumerate() {
+        write_record(&mut out, paper, &key, number + 1);
+    }
+    out.push_str("  </records>\n</xml>\n");
+    out
+}
+
+fn write_record(out: &mut String, paper: &Paper, key: &str, number: usize) {
+    let kind = entry_type(paper);
+    let (name, code) = ref_type(kind);
+    out.push_str("    <record>\n");
+    element(out, 6, "rec-number", &number.to_string());
+    let _ = writeln!(out, "      <ref-type name=\"{}\">{}</ref-type>", name, code);
+
+    let editors = extra_text(paper, "editor").unwrap_or_default();
+    let editors: Vec<&str> = editors
+        .split(';')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .collect();
+    if !paper.author_list().is_empty() || !editors.is_empty() {
+        out.push_str("      <contributors>\n");
+        names(out, "authors", &paper.author_list());
+        names(out, "secondary-authors", &editors);
+        out.push_str("      </contributors>\n");
+    }
+
+    let container = container_title(paper, kind);
+    out.push_str("      <titles>\n");
+    element(out, 8, "title", paper.title.trim());
+    if let Some(ref container) = container {
+        element(out, 8, "secondary-title", container);
+    }
+    out.push_str("      </titles>\n");
+    if let Some(ref container) = container.filter(|_| kind == "article") {
+        out.push_str("      <periodical>\n");
+        element(out, 8, "full-title", container);
+        out.push_str("      </periodical>\n");
+    }
+
+    if let Some((start, end)) = page_range(paper) {
+        match end {
+            Some(end) => element(out, 6, "pages", &format!("{}-{}", start, end)),
+            None => element(out, 6, "pages", &start),
+        }
+    }
+    let mut written = Vec::new();
+    for &(field, tag) in EXTRA_ELEMENTS {
+        // ISBN and ISSN share an element; keep the first
+        if written.contains(&tag) {
+            continue;
+        }
+        if let Some(value) = extra_text(paper, field) {
+            element(out, 6, tag, &value);
+            written.push(tag);
+        }
+    }
+
+    let keywords = paper.keyword_list();
+    if !keywords.is_empty() {
+        out.push_str("      <keywords>\n");
+        for keyword in keywords {
+            element(out, 8, "keyword", keyword);
+        }
+        out.push_str("      </keywords>\n");
+    }
+
+    if let Some(year) = paper.year() {
+        out.push_str("      <dates>\n");
+        element(out, 8, "year", &year.to_string());
+        if let Some(date) = paper.published_date.as_deref().filter(|d| d.len() > 4) {
+            out.push_str("        <pub-dates>\n");
+            element(out, 10, "date", date);
+            out.push_str("        </pub-dates>\n");
+        }
+        out.push_str("      </dates>\n");
+    }
+
+    if let Some(doi) = paper.doi.as_deref().filter(|d| !d.trim().is_empty()) {
+        element(out, 6, "electronic-resource-num", doi.trim());
+    }
+    if !paper.r#abstract.trim().is_empty() {
+        element(out, 6, "abstract", paper.r#abstract.trim());
+    }
+    if let Some(ref status) = paper.status {
+        element(out, 6, "research-notes", &status.warning());
+    }
+
+    let pdf = paper.pdf_url.as_deref().filter(|u| !u.trim().is_empty());
+    if !paper.url.trim().is_empty() || pdf.is_some() {
+        out.push_str("      <urls>\n");
+        if !paper.url.trim().is_empty() {
+            out.push_str("        <related-urls>\n");
+            element(out, 10, "url", paper.url.trim());
+            out.push_str("        </related-urls>\n");
+        }
+        if let Some(pdf) = pdf {
+            out.push_str("        <pdf-urls>\n");
+            element(out, 10, "url", pdf.trim());
+            out.push_str("        </pdf-urls>\n");
+        }
+        out.push_str("      </urls>\n");
+    }
+    element(out, 6, "label", key);
+    out.push_str("    </record>\n");
+}
+
+/// Names as `<author>` elements wrapped in `group`
+fn names(out: &mut String, group: &str, names: &[&str]) {
+    if names.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "        <{}>", group);
+    for name in names {
+        element(out, 10, "author", &inverted_name(name));
+    }
+    let _ = writeln!(out, "        </{}>", group);
+}
+
+fn element(out: &mut String, indent: usize, tag: &str, value: &str) {
+    let _ = writeln!(
+        out,
+        "{:indent$}<{tag}>{}</{tag}>",
+        "",
+        xml_escape(value),
+        indent = indent,
+        tag = tag
+    );
+}
+
+/// EndNote reference type name and number for a BibTeX entry type
+fn ref_type(entry_type: &str) -> (&'static str, u32) {
+    match entry_type {
+        "article" => ("Journal Article", 17),
+        "inproceedings" => ("Conference Paper", 47),
+        "book" => ("Book", 6),
+        "incollection" => ("Book Section", 5),
+        "phdthesis" | "mastersthesis" => ("Thesis", 32),
+        "techreport" => ("Report", 27),
+        "unpublished" => ("Unpublished Work", 34),
+        "online" => ("Web Page", 12),
+        _ => ("Generic", 13),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+    use serde_json::json;
+
+    #[test]
+    fn test_write_endnote_xml() {
+        let paper = PaperBuilder::new("1", "Deep <learning>", "https://x.org/1", SourceType::Arxiv)
+            .authors("Yann LeCun; Yoshua Bengio")
+            .published_date("2015-05-28")
+            .doi("10.1038/nature14539")
+            .pdf_url("https://x.org/1.pdf")
+            .keywords("neural networks; AI")
+            .extra("journal", json!("Nature"))
+            .extra("volume", json!("521"))
+            .extra("pages", json!("436--444"))
+            .build();
+        let xml = write(&[paper]);
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<ref-type name=\"Journal Article\">17</ref-type>"));
+        assert!(xml.contains("<author>LeCun, Yann</author>"));
+        assert!(xml.contains("<title>Deep &lt;learning&gt;</title>"));
+        assert!(xml.contains("<full-title>Nature</full-title>"));
+        assert!(xml.contains("<pages>436-444</pages>"));
+        assert!(xml.contains("<volume>521</volume>"));
+        assert!(xml.contains("<keyword>AI</keyword>"));
+        assert!(xml.contains("<year>2015</year>"));
+        assert!(xml.contains("<date>2015-05-28</date>"));
+        assert!(
+            xml.contains("<electronic-resource-num>10.1038/nature14539</electronic-resource-num>")
+        );
+        assert!(xml.contains("<pdf-urls>"));
+        assert!(xml.contains("<label>lecun2015deep</label>"));
+        // Well-formed: every record is closed
+        assert_eq!(
+            xml.matches("<record>").count(),
+            xml.matches("</record>").count()
+        );
+    }
+}
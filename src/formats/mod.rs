@@ -0,0 +1,580 @@
+//! Reading and writing bibliography files.
+//!
+//! - [`ImportFormat`]: BibTeX, RIS, CSL-JSON and research-master JSON readers that
+//!   turn entries into [`Paper`] values
+//! - [`ExportFormat`]: BibTeX, RIS, CSL-JSON, EndNote XML, MODS, CSV, JSON and
+//!   JSON Lines writers, and Markdown notes written one file per paper
+//! - [`BibFile`]: A `.bib` file updated in place, keeping its formatting and
+//!   the values edited by hand
+//!
+//! Imported fields without a `Paper` counterpart (volume, pages, publisher, ...)
+//! are kept in `extra` under their lowercase field names, so they survive a
+//! round trip. The entry type is kept as `entry_type` (in BibTeX vocabulary:
+//! `article`, `inproceedings`, `book`, ...) and the BibTeX key or RIS/CSL ID as
+//! `citekey`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use research_master::formats::{ExportFormat, ImportFormat};
+//!
+//! let bib = r#"@article{vaswani2017,
+//!   author = {Vaswani, Ashish and Shazeer, Noam},
+//!   title = {Attention Is All You Need},
+//!   year = 2017,
+//!   volume = {30}
+//! }"#;
+//!
+//! let papers = ImportFormat::Bibtex.read(bib).unwrap();
+//! assert_eq!(papers[0].authors, "Ashish Vaswani; Noam Shazeer");
+//!
+//! let ris = ExportFormat::Ris.write(&papers);
+//! assert!(ris.contains("VL  - 30"));
+//! ```
+
+mod bibfile;
+mod bibtex;
+mod csl;
+mod csv;
+mod endnote;
+mod mods;
+mod notes;
+mod ris;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::models::{Paper, SearchResponse};
+
+pub use bibfile::{BibEntry, BibFile, EntryChange};
+pub use bibtex::{cite_key, KeyPattern};
+
+/// Errors that can occur while reading a bibliography file
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error("Parse error at line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid cite key pattern: {0}")]
+    KeyPattern(String),
+}
+
+/// Bibliography formats that can be read into papers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    /// BibTeX/BibLaTeX (`.bib`)
+    Bibtex,
+    /// RIS (EndNote, Zotero, Mendeley)
+    Ris,
+    /// CSL-JSON (Zotero, Pandoc, citeproc)
+    CslJson,
+    /// JSON array of papers, as written by `--output json` and `export --format json`
+    Json,
+}
+
+impl ImportFormat {
+    /// Guess the format from a file's extension and content
+    pub fn detect(path: &Path, content: &str) -> Option<Self> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("bib" | "bibtex") => return Some(Self::Bibtex),
+            Some("ris" | "nbib") => return Some(Self::Ris),
+            _ => {}
+        }
+
+        let trimmed = content.trim_start();
+        if trimmed.starts_with('@') {
+            Some(Self::Bibtex)
+        } else if trimmed.starts_with("TY  -") {
+            Some(Self::Ris)
+        } else if trimmed.starts_with('[') || trimmed.starts_with('{') {
+            // Our own JSON always carries `paper_id`; CSL-JSON never does
+            if trimmed.contains("\"paper_id\"") {
+                Some(Self::Json)
+            } else {
+                Some(Self::CslJson)
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Parse all entries of a file in this format
+    pub fn read(self, content: &str) -> Result<Vec<Paper>, FormatError> {
+        match self {
+            ImportFormat::Bibtex => bibtex::read(content),
+            ImportFormat::Ris => Ok(ris::read(content)),
+            ImportFormat::CslJson => csl::read(content),
+            ImportFormat::Json => read_json(content),
+        }
+    }
+}
+
+impl std::fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ImportFormat::Bibtex => "BibTeX",
+            ImportFormat::Ris => "RIS",
+            ImportFormat::CslJson => "CSL-JSON",
+            ImportFormat::Json => "JSON",
+        })
+    }
+}
+
+/// Formats papers can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// BibTeX entries
+    #[default]
+    Bibtex,
+    /// RIS records
+    Ris,
+    /// CSL-JSON items, as read by citeproc processors and Zotero
+    CslJson,
+    /// EndNote XML records
+    EndnoteXml,
+    /// MODS XML collection, for library systems
+    Mods,
+    /// CSV with one row per paper
+    Csv,
+    /// JSON array of papers
+    Json,
+    /// JSON Lines: one paper per line, for streaming pipelines
+    Jsonl,
+    /// Markdown notes with YAML front matter, one file per paper (Obsidian)
+    Notes,
+}
+
+impl ExportFormat {
+    /// Conventional file extension for the format
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Bibtex => "bib",
+            ExportFormat::Ris => "ris",
+            ExportFormat::CslJson => "json",
+            ExportFormat::EndnoteXml => "xml",
+            ExportFormat::Mods => "xml",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Notes => "md",
+        }
+    }
+
+    /// Whether [`write_to`](Self::write_to) writes a directory of files
+    /// rather than a single file
+    pub fn is_directory(self) -> bool {
+        self == ExportFormat::Notes
+    }
+
+    /// Serialize `papers` in this format
+    ///
+    /// Notes are concatenated; use [`write_to`](Self::write_to) for one file
+    /// per paper.
+    pub fn write(self, papers: &[Paper]) -> String {
+        match self {
+            ExportFormat::Bibtex => bibtex::write(papers),
+            ExportFormat::Ris => ris::write(papers),
+            ExportFormat::CslJson => csl::write(papers),
+            ExportFormat::EndnoteXml => endnote::write(papers),
+            ExportFormat::Mods => mods::write(papers),
+            ExportFormat::Csv => csv::write(papers),
+            ExportFormat::Json => serde_json::to_string_pretty(papers).unwrap_or_default() + "\n",
+            ExportFormat::Jsonl => papers
+                .iter()
+                .filter_map(|paper| serde_json::to_string(paper).ok())
+                .map(|line| line + "\n")
+                .collect(),
+            ExportFormat::Notes => notes::write(papers),
+        }
+    }
+
+    /// Write `papers` to `path`
+    ///
+    /// Notes go into `path` as a directory, one `<citekey>.md` file per paper;
+    /// notes edited since they were written are not overwritten. Other formats
+    /// replace the file at `path`.
+    pub fn write_to(self, papers: &[Paper], path: &Path) -> std::io::Result<ExportSummary> {
+        if self.is_directory() {
+            return notes::write_dir(papers, path);
+        }
+        std::fs::write(path, self.write(papers))?;
+        Ok(ExportSummary {
+            written: vec![path.to_path_buf()],
+            ..Default::default()
+        })
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportFormat::CslJson => f.write_str("csl-json"),
+            ExportFormat::EndnoteXml => f.write_str("endnote-xml"),
+            ExportFormat::Mods => f.write_str("mods"),
+            ExportFormat::Notes => f.write_str("notes"),
+            other => f.write_str(other.extension()),
+        }
+    }
+}
+
+/// Files written by [`ExportFormat::write_to`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ExportSummary {
+    /// Files created or replaced
+    pub written: Vec<PathBuf>,
+    /// Notes that were already up to date
+    pub unchanged: Vec<PathBuf>,
+    /// Notes left as they were because they were edited after being written
+    pub kept: Vec<PathBuf>,
+}
+
+/// CSL-JSON items for papers, with unique cite keys as IDs
+pub fn csl_items(papers: &[Paper]) -> Vec<serde_json::Value> {
+    csl::items(papers)
+}
+
+/// Venue a paper appeared in: the journal for articles, otherwise the book
+/// or proceedings title
+pub(crate) fn container_title(paper: &Paper, entry_type: &str) -> Option<String> {
+    extra_text(paper, "booktitle")
+        .filter(|_| entry_type != "article")
+        .or_else(|| paper.venue().map(str::to_string))
+}
+
+/// First and last page from the `pages` field
+pub(crate) fn page_range(paper: &Paper) -> Option<(String, Option<String>)> {
+    let pages = extra_text(paper, "pages")?.replace("--", "-");
+    match pages.split_once('-') {
+        Some((start, end)) => Some((start.trim().to_string(), Some(end.trim().to_string()))),
+        None => Some((pages, None)),
+    }
+}
+
+/// Read papers from JSON: a bare array, or a search response with a `papers` field
+fn read_json(content: &str) -> Result<Vec<Paper>, FormatError> {
+    match serde_json::from_str::<Vec<Paper>>(content) {
+        Ok(papers) => Ok(papers),
+        Err(e) => serde_json::from_str::<SearchResponse>(content)
+            .map(|response| response.papers)
+            .map_err(|_| FormatError::Json(e)),
+    }
+}
+
+/// Entry types in BibTeX vocabulary, with their RIS and CSL counterparts
+const ENTRY_TYPES: &[(&str, &str, &str)] = &[
+    ("article", "JOUR", "article-journal"),
+    ("inproceedings", "CONF", "paper-conference"),
+    ("book", "BOOK", "book"),
+    ("incollection", "CHAP", "chapter"),
+    ("phdthesis", "THES", "thesis"),
+    ("mastersthesis", "THES", "thesis"),
+    ("techreport", "RPRT", "report"),
+    ("unpublished", "UNPB", "manuscript"),
+    ("online", "ELEC", "webpage"),
+    ("misc", "GEN", "document"),
+];
+
+/// Entry type of a paper in BibTeX vocabulary
+///
+/// Uses the imported `entry_type` when there is one; otherwise papers with a
+/// venue are taken to be journal articles.
+pub(crate) fn entry_type(paper: &Paper) -> &'static str {
+    if let Some(imported) = extra_text(paper, "entry_type").map(|t| t.to_lowercase()) {
+        let known = ENTRY_TYPES.iter().find(|(bib, _, _)| *bib == imported);
+        if let Some((bib, _, _)) = known {
+            return bib;
+        }
+        return match imported.as_str() {
+            "conference" | "proceedings" => "inproceedings",
+            "inbook" => "incollection",
+            "thesis" => "phdthesis",
+            "report" => "techreport",
+            "electronic" | "www" => "online",
+            _ => "misc",
+        };
+    }
+    if paper.venue().is_some() {
+        "article"
+    } else {
+        "misc"
+    }
+}
+
+/// RIS type for a BibTeX entry type
+pub(crate) fn ris_type(entry_type: &str) -> &'static str {
+    ENTRY_TYPES
+        .iter()
+        .find(|(bib, _, _)| *bib == entry_type)
+        .map(|(_, ris, _)| *ris)
+        .unwrap_or("GEN")
+}
+
+/// CSL item type for a BibTeX entry type
+pub(crate) fn csl_type(entry_type: &str) -> &'static str {
+    ENTRY_TYPES
+        .iter()
+        .find(|(bib, _, _)| *bib == entry_type)
+        .map(|(_, _, csl)| *csl)
+        .unwrap_or("document")
+}
+
+/// BibTeX entry type for a RIS type
+pub(crate) fn from_ris_type(ris: &str) -> &'static str {
+    match ris {
+        "CPAPER" => "inproceedings",
+        "EBOOK" | "EDBOOK" => "book",
+        "ECHAP" => "incollection",
+        "REPORT" => "techreport",
+        "WEB" | "BLOG" => "online",
+        "MANSCPT" => "unpublished",
+        _ => ENTRY_TYPES
+            .iter()
+            .find(|(_, r, _)| *r == ris)
+            .map(|(bib, _, _)| *bib)
+            .unwrap_or("misc"),
+    }
+}
+
+/// BibTeX entry type for a CSL item type
+pub(crate) fn from_csl_type(csl: &str) -> &'static str {
+    match csl {
+        "article" | "article-magazine" | "article-newspaper" => "article",
+        "post" | "post-weblog" => "online",
+        _ => ENTRY_TYPES
+            .iter()
+            .find(|(_, _, c)| *c == csl)
+            .map(|(bib, _, _)| *bib)
+            .unwrap_or("misc"),
+    }
+}
+
+/// A text or number field from `extra`
+pub(crate) fn extra_text(paper: &Paper, key: &str) -> Option<String> {
+    let value = match paper.extra.as_ref()?.get(key)? {
+        serde_json::Value::String(s) => s.trim().to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!value.is_empty()).then_some(value)
+}
+
+/// Split a name into (family, given)
+///
+/// Accepts "Family, Given" and "Given Family"; a single word is a family name.
+pub(crate) fn split_name(name: &str) -> (String, String) {
+    let name = name.trim();
+    if let Some((family, given)) = name.split_once(',') {
+        return (family.trim().to_string(), given.trim().to_string());
+    }
+    match name.rsplit_once(char::is_whitespace) {
+        Some((given, family)) => (family.trim().to_string(), given.trim().to_string()),
+        None => (name.to_string(), String::new()),
+    }
+}
+
+/// Name as "Family, Given", as BibTeX and RIS expect
+pub(crate) fn inverted_name(name: &str) -> String {
+    let (family, given) = split_name(name);
+    if given.is_empty() {
+        family
+    } else {
+        format!("{}, {}", family, given)
+    }
+}
+
+/// Name as "Given Family", the form used in [`Paper::authors`]
+///
+/// "Family, Given" and "Family, Suffix, Given" are reordered; other names are
+/// kept as they are.
+pub(crate) fn display_name(name: &str) -> String {
+    let parts: Vec<&str> = name.split(',').map(str::trim).collect();
+    match parts.as_slice() {
+        [family, given] if !given.is_empty() => format!("{} {}", given, family),
+        [family, suffix, given] => format!("{} {} {}", given, family, suffix),
+        _ => name.trim().to_string(),
+    }
+}
+
+/// ISO date from year, month and day parts; month may be a number or a name
+pub(crate) fn iso_date(year: &str, month: Option<&str>, day: Option<&str>) -> Option<String> {
+    let year = year.trim();
+    if year.len() != 4 || !year.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let Some(month) = month.and_then(month_number) else {
+        return Some(year.to_string());
+    };
+    match day
+        .and_then(|d| d.trim().parse::<u32>().ok())
+        .filter(|d| (1..=31).contains(d))
+    {
+        Some(day) => Some(format!("{}-{:02}-{:02}", year, month, day)),
+        None => Some(format!("{}-{:02}", year, month)),
+    }
+}
+
+fn month_number(month: &str) -> Option<u32> {
+    let month = month.trim().to_lowercase();
+    if let Ok(n) = month.parse::<u32>() {
+        return (1..=12).contains(&n).then_some(n);
+    }
+    const NAMES: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let prefix = month.get(..3)?;
+    NAMES
+        .iter()
+        .position(|name| *name == prefix)
+        .map(|i| i as u32 + 1)
+}
+
+/// Month of an ISO date, if it has one
+pub(crate) fn date_month(date: &str) -> Option<u32> {
+    date.get(5..7).and_then(|m| m.parse().ok())
+}
+
+/// Join keyword lists separated by commas or semicolons into `a; b; c`
+pub(crate) fn join_keywords<'a>(values: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let keywords: Vec<&str> = values
+        .into_iter()
+        .flat_map(|v| v.split([',', ';']))
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .collect();
+    (!keywords.is_empty()).then(|| keywords.join("; "))
+}
+
+/// Cite keys for a set of papers, made unique with `a`, `b`, ... suffixes
+pub(crate) fn unique_keys(papers: &[Paper]) -> Vec<String> {
+    let mut used = HashSet::new();
+    papers
+        .iter()
+        .map(|paper| {
+            let base = extra_text(paper, "citekey").unwrap_or_else(|| cite_key(paper));
+            let mut key = base.clone();
+            let mut suffix = b'a';
+            while !used.insert(key.clone()) {
+                key = format!("{}{}", base, suffix as char);
+                suffix = suffix.saturating_add(1);
+            }
+            key
+        })
+        .collect()
+}
+
+/// FNV-1a hash of the text, as hex
+pub(crate) fn fingerprint(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, PaperStatus, SourceType, StatusKind};
+
+    #[test]
+    fn test_detect_format() {
+        let path = Path::new("refs.txt");
+        assert_eq!(
+            ImportFormat::detect(Path::new("refs.bib"), ""),
+            Some(ImportFormat::Bibtex)
+        );
+        assert_eq!(
+            ImportFormat::detect(path, "TY  - JOUR\nER  - \n"),
+            Some(ImportFormat::Ris)
+        );
+        assert_eq!(
+            ImportFormat::detect(path, r#"[{"id": "x", "type": "book"}]"#),
+            Some(ImportFormat::CslJson)
+        );
+        assert_eq!(
+            ImportFormat::detect(path, r#"[{"paper_id": "x"}]"#),
+            Some(ImportFormat::Json)
+        );
+        assert_eq!(ImportFormat::detect(path, "plain text"), None);
+    }
+
+    #[test]
+    fn test_names_and_dates() {
+        assert_eq!(display_name("Vaswani, Ashish"), "Ashish Vaswani");
+        assert_eq!(
+            display_name("King, Jr., Martin Luther"),
+            "Martin Luther King Jr."
+        );
+        assert_eq!(
+            display_name("World Health Organization"),
+            "World Health Organization"
+        );
+        assert_eq!(inverted_name("Ashish Vaswani"), "Vaswani, Ashish");
+        assert_eq!(inverted_name("Plato"), "Plato");
+
+        assert_eq!(
+            iso_date("2017", Some("dec"), None).as_deref(),
+            Some("2017-12")
+        );
+        assert_eq!(
+            iso_date("2017", Some("6"), Some("12")).as_deref(),
+            Some("2017-06-12")
+        );
+        assert_eq!(iso_date("17", None, None), None);
+    }
+
+    #[test]
+    fn test_unique_keys() {
+        let paper = PaperBuilder::new("1", "Attention Is All You Need", "", SourceType::Arxiv)
+            .authors("Ashish Vaswani; Noam Shazeer")
+            .published_date("2017-06-12")
+            .build();
+        let keys = unique_keys(&[paper.clone(), paper]);
+        assert_eq!(keys, vec!["vaswani2017attention", "vaswani2017attentiona"]);
+    }
+
+    #[test]
+    fn test_exports_warn_about_retractions() {
+        let paper = PaperBuilder::new("1", "Hydroxychloroquine", "", SourceType::CrossRef)
+            .authors("Mandeep Mehra")
+            .published_date("2020")
+            .status(PaperStatus::new(StatusKind::Retracted, "crossref").date("2020-06-05"))
+            .build();
+        let warning = "RETRACTED (crossref, 2020-06-05)";
+        let papers = [paper];
+
+        assert!(ExportFormat::Bibtex.write(&papers).starts_with(&format!(
+            "% {}\n@misc{{mehra2020hydroxychloroquine,",
+            warning
+        )));
+        assert!(ExportFormat::Ris
+            .write(&papers)
+            .contains(&format!("N1  - {}\n", warning)));
+        assert!(ExportFormat::CslJson
+            .write(&papers)
+            .contains("\"status\": \"retracted\""));
+        assert!(ExportFormat::EndnoteXml
+            .write(&papers)
+            .contains(&format!("<research-notes>{}</research-notes>", warning)));
+        assert!(ExportFormat::Mods.write(&papers).contains(warning));
+        assert!(ExportFormat::Csv.write(&papers).ends_with(",retracted\n"));
+        assert!(ExportFormat::Json
+            .write(&papers)
+            .contains("\"kind\": \"retracted\""));
+        let note = ExportFormat::Notes.write(&papers);
+        assert!(note.contains("status: \"retracted\"\n"));
+        assert!(note.contains(&format!("> [!warning] {}\n", warning)));
+    }
+}
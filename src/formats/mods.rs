@@ -0,0 +1,255 @@
+//! MODS (Metadata Object Description Schema) XML writing.
+
+use std::fmt::Write;
+
+use super::{container_title, entry_type, extra_text, page_range, split_name, unique_keys};
+use crate::models::Paper;
+use crate::utils::xml_escape;
+
+/// Write papers as a MODS collection
+pub(super) fn write(papers: &[Paper]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(
+        "<modsCollection xmlns=\"http://www.loc.gov/mods/v3\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"http://www.loc.gov/mods/v3 \
+         http://www.loc.gov/standards/mods/v3/mods-3-8.xsd\">\n",
+    );
+    for (paper, key) in papers.iter().zip(unique_keys(papers)) {
+        write_record(&mut out, paper, &key);
+    }
+    out.push_str("</modsCollection>\n");
+    out
+}
+
+fn write_record(out: &mut String, paper: &Paper, key: &str) {
+    let kind = entry_type(paper);
+    let _ = writeln!(out, "  <mods ID=\"{}\">", xml_escape(key));
+    out.push_str("    <titleInfo>\n");
+    element(out, 6, "title", paper.title.trim());
+    out.push_str("    </titleInfo>\n");
+
+    for author in paper.author_list() {
+        name(out, author, "author");
+    }
+    if let Some(editors) = extra_text(paper, "editor") {
+        for editor in editors.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            name(out, editor, "editor");
+        }
+    }
+
+    element(out, 4, "typeOfResource", "text");
+    element(out, 4, "genre", genre(kind));
+
+    let publisher = extra_text(paper, "publisher");
+    let place = extra_text(paper, "address");
+    if paper.published_date.is_some() || publisher.is_some() || place.is_some() {
+        out.push_str("    <originInfo>\n");
+        if let Some(place) = place {
+            out.push_str("      <place>\n");
+            let _ = writeln!(
+                out,
+                "        <placeTerm type=\"text\">{}</placeTerm>",
+                xml_escape(&place)
+            );
+            out.push_str("      </place>\n");
+        }
+        if let Some(publisher) = publisher {
+            element(out, 6, "publisher", &publisher);
+        }
+        if let Some(date) = paper.published_date.as_deref() {
+            let _ = writeln!(
+                out,
+                "      <dateIssued encoding=\"w3cdtf\">{}</dateIssued>",
+                xml_escape(date)
+            );
+        }
+        if let Some(edition) = extra_text(paper, "edition") {
+            element(out, 6, "edition", &edition);
+        }
+        out.push_str("    </originInfo>\n");
+    }
+
+    if let Some(container) = container_title(paper, kind) {
+        out.push_str("    <relatedItem type=\"host\">\n");
+        out.push_str("      <titleInfo>\n");
+        element(out, 8, "title", &container);
+        out.push_str("      </titleInfo>\n");
+        if kind == "article" {
+            out.push_str("      <originInfo>\n");
+            element(out, 8, "issuance", "continuing");
+            out.push_str("      </originInfo>\n");
+            element(out, 6, "genre", "periodical");
+        }
+        let volume = extra_text(paper, "volume");
+        let issue = extra_text(paper, "number");
+        let pages = page_range(paper);
+        if volume.is_some() || issue.is_some() || pages.is_some() {
+            out.push_str("      <part>\n");
+            for (kind, value) in [("volume", volume), ("issue", issue)] {
+                if let Some(value) = value {
+                    let _ = writeln!(
+                        out,
+                        "        <detail type=\"{}\"><number>{}</number></detail>",
+                        kind,
+                        xml_escape(&value)
+                    );
+                }
+            }
+            if let Some((start, end)) = pages {
+                out.push_str("        <extent unit=\"page\">\n");
+                element(out, 10, "start", &start);
+                if let Some(end) = end {
+                    element(out, 10, "end", &end);
+                }
+                out.push_str("        </extent>\n");
+            }
+            out.push_str("      </part>\n");
+        }
+        out.push_str("    </relatedItem>\n");
+    }
+
+    if !paper.r#abstract.trim().is_empty() {
+        element(out, 4, "abstract", paper.r#abstract.trim());
+    }
+    let keywords = paper.keyword_list();
+    if !keywords.is_empty() {
+        out.push_str("    <subject>\n");
+        for keyword in keywords {
+            element(out, 6, "topic", keyword);
+        }
+        out.push_str("    </subject>\n");
+    }
+
+    if let Some(ref status) = paper.status {
+        let _ = writeln!(
+            out,
+            "    <note type=\"publication status\">{}</note>",
+            xml_escape(&status.warning())
+        );
+    }
+
+    let mut identifiers = vec![("citekey", key.to_string())];
+    if let Some(doi) = paper.doi.as_deref().filter(|d| !d.trim().is_empty()) {
+        identifiers.push(("doi", doi.trim().to_string()));
+    }
+    for field in ["isbn", "issn"] {
+        if let Some(value) = extra_text(paper, field) {
+            identifiers.push((field, value));
+        }
+    }
+    for (kind, value) in identifiers {
+        let _ = writeln!(
+            out,
+            "    <identifier type=\"{}\">{}</identifier>",
+            kind,
+            xml_escape(&value)
+        );
+    }
+
+    let pdf = paper.pdf_url.as_deref().filter(|u| !u.trim().is_empty());
+    if !paper.url.trim().is_empty() || pdf.is_some() {
+        out.push_str("    <location>\n");
+        if !paper.url.trim().is_empty() {
+            element(out, 6, "url", paper.url.trim());
+        }
+        if let Some(pdf) = pdf {
+            let _ = writeln!(
+                out,
+                "      <url access=\"raw object\">{}</url>",
+                xml_escape(pdf.trim())
+            );
+        }
+        out.push_str("    </location>\n");
+    }
+    out.push_str("  </mods>\n");
+}
+
+/// A personal name, or a corporate one when it is a single word
+fn name(out: &mut String, name: &str, role: &str) {
+    let (family, given) = split_name(name);
+    if given.is_empty() {
+        out.push_str("    <name type=\"corporate\">\n");
+        element(out, 6, "namePart", &family);
+    } else {
+        out.push_str("    <name type=\"personal\">\n");
+        let _ = writeln!(
+            out,
+            "      <namePart type=\"given\">{}</namePart>",
+            xml_escape(&given)
+        );
+        let _ = writeln!(
+            out,
+            "      <namePart type=\"family\">{}</namePart>",
+            xml_escape(&family)
+        );
+    }
+    let _ = writeln!(
+        out,
+        "      <role><roleTerm authority=\"marcrelator\" type=\"text\">{}</roleTerm></role>",
+        role
+    );
+    out.push_str("    </name>\n");
+}
+
+fn element(out: &mut String, indent: usize, tag: &str, value: &str) {
+    let _ = writeln!(
+        out,
+        "{:indent$}<{tag}>{}</{tag}>",
+        "",
+        xml_escape(value),
+        indent = indent,
+        tag = tag
+    );
+}
+
+/// MODS genre for a BibTeX entry type
+fn genre(entry_type: &str) -> &'static str {
+    match entry_type {
+        "article" => "journal article",
+        "inproceedings" => "conference publication",
+        "book" => "book",
+        "incollection" => "book chapter",
+        "phdthesis" => "Ph.D. thesis",
+        "mastersthesis" => "Masters thesis",
+        "techreport" => "technical report",
+        "unpublished" => "unpublished",
+        "online" => "web page",
+        _ => "document",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+    use serde_json::json;
+
+    #[test]
+    fn test_write_mods() {
+        let paper = PaperBuilder::new("1", "Attention & Transformers", "", SourceType::Arxiv)
+            .authors("Ashish Vaswani; OpenAI")
+            .published_date("2017-12")
+            .doi("10.5555/3295222")
+            .extra("entry_type", json!("inproceedings"))
+            .extra("booktitle", json!("NeurIPS"))
+            .extra("pages", json!("5998--6008"))
+            .extra("volume", json!("30"))
+            .build();
+        let xml = write(&[paper]);
+        assert!(xml.contains("<mods ID=\"vaswani2017attention\">"));
+        assert!(xml.contains("<title>Attention &amp; Transformers</title>"));
+        assert!(xml.contains("<namePart type=\"family\">Vaswani</namePart>"));
+        assert!(xml.contains("<name type=\"corporate\">\n      <namePart>OpenAI</namePart>"));
+        assert!(xml.contains("<genre>conference publication</genre>"));
+        assert!(xml.contains("<dateIssued encoding=\"w3cdtf\">2017-12</dateIssued>"));
+        assert!(xml.contains(
+            "<relatedItem type=\"host\">\n      <titleInfo>\n        <title>NeurIPS</title>"
+        ));
+        assert!(xml.contains("<detail type=\"volume\"><number>30</number></detail>"));
+        assert!(xml.contains("<start>5998</start>"));
+        assert!(xml.contains("<end>6008</end>"));
+        assert!(xml.contains("<identifier type=\"doi\">10.5555/3295222</identifier>"));
+        assert!(xml.trim_end().ends_with("</modsCollection>"));
+    }
+}
@@ -0,0 +1,253 @@
+//! Markdown notes with YAML front matter, one file per paper.
+//!
+//! The notes work as an Obsidian (or Logseq, Foam, ...) vault: the front matter
+//! holds the identifiers, authors, tags and cite key, and the body the title
+//! and abstract.
+//!
+//! Every note records a fingerprint of its generated text in a YAML comment.
+//! Writing into a directory replaces a note only while it still matches its
+//! fingerprint; notes the user has edited are left alone.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{container_title, entry_type, fingerprint, unique_keys, ExportSummary};
+use crate::models::Paper;
+
+/// Start of the fingerprint comment line
+const FINGERPRINT: &str = "# research-master: ";
+
+/// Write papers as notes, one after another
+pub(super) fn write(papers: &[Paper]) -> String {
+    papers
+        .iter()
+        .zip(unique_keys(papers))
+        .map(|(paper, key)| note(paper, &key))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Write one `<citekey>.md` note per paper into `dir`
+pub(super) fn write_dir(papers: &[Paper], dir: &Path) -> io::Result<ExportSummary> {
+    fs::create_dir_all(dir)?;
+    let mut summary = ExportSummary::default();
+    for (paper, key) in papers.iter().zip(unique_keys(papers)) {
+        let path = dir.join(format!("{}.md", file_name(&key)));
+        let note = note(paper, &key);
+        match fs::read_to_string(&path) {
+            Ok(existing) if existing == note => summary.unchanged.push(path),
+            Ok(existing) if !is_generated(&existing) => summary.kept.push(path),
+            Ok(_) => {
+                fs::write(&path, note)?;
+                summary.written.push(path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                fs::write(&path, note)?;
+                summary.written.push(path);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(summary)
+}
+
+/// The note for one paper
+fn note(paper: &Paper, key: &str) -> String {
+    let mut front = vec![
+        format!("title: {}", quote(paper.title.trim())),
+        format!("citekey: {}", quote(key)),
+    ];
+    list(&mut front, "authors", paper.author_list());
+    if let Some(year) = paper.year() {
+        front.push(format!("year: {}", year));
+    }
+    if let Some(date) = paper.published_date.as_deref().filter(|d| !d.is_empty()) {
+        front.push(format!("published: {}", quote(date)));
+    }
+    if let Some(venue) = container_title(paper, entry_type(paper)) {
+        front.push(format!("venue: {}", quote(&venue)));
+    }
+    if let Some(doi) = paper.doi.as_deref().filter(|d| !d.trim().is_empty()) {
+        front.push(format!("doi: {}", quote(doi.trim())));
+    }
+    front.push(format!("source: {}", quote(paper.source.id())));
+    front.push(format!("paper_id: {}", quote(&paper.paper_id)));
+    if !paper.url.trim().is_empty() {
+        front.push(format!("url: {}", quote(paper.url.trim())));
+    }
+    if let Some(pdf) = paper.pdf_url.as_deref().filter(|u| !u.trim().is_empty()) {
+        front.push(format!("pdf: {}", quote(pdf.trim())));
+    }
+    if let Some(citations) = paper.citations {
+        front.push(format!("citations: {}", citations));
+    }
+    if let Some(ref status) = paper.status {
+        front.push(format!("status: {}", quote(&status.kind.to_string())));
+    }
+    let mut tags: Vec<String> = Vec::new();
+    for tag in paper
+        .keyword_list()
+        .into_iter()
+        .chain(paper.category_list())
+        .filter_map(tag)
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    list(
+        &mut front,
+        "tags",
+        tags.iter().map(String::as_str).collect(),
+    );
+
+    let mut body = format!("# {}\n", paper.title.trim());
+    if let Some(ref status) = paper.status {
+        body.push_str(&format!("\n> [!warning] {}\n", status.warning()));
+    }
+    if !paper.r#abstract.trim().is_empty() {
+        body.push_str("\n## Abstract\n\n");
+        body.push_str(paper.r#abstract.trim());
+        body.push('\n');
+    }
+    body.push_str("\n## Notes\n");
+
+    let text = format!("---\n{}\n---\n\n{}", front.join("\n"), body);
+    format!("---\n{}{}\n{}", FINGERPRINT, fingerprint(&text), &text[4..])
+}
+
+/// Whether a note is still as it was generated
+fn is_generated(note: &str) -> bool {
+    let Some(rest) = note.strip_prefix("---\n") else {
+        return false;
+    };
+    let Some((line, rest)) = rest.split_once('\n') else {
+        return false;
+    };
+    line.strip_prefix(FINGERPRINT)
+        .is_some_and(|recorded| recorded == fingerprint(&format!("---\n{}", rest)))
+}
+
+/// A YAML list, omitted when empty
+fn list(front: &mut Vec<String>, key: &str, values: Vec<&str>) {
+    if values.is_empty() {
+        return;
+    }
+    front.push(format!("{}:", key));
+    for value in values {
+        front.push(format!("  - {}", quote(value)));
+    }
+}
+
+/// A YAML double-quoted string; JSON string escapes are valid YAML
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// A keyword as a tag: lowercase words joined by hyphens, without characters
+/// tags cannot contain; purely numeric tags are dropped
+fn tag(keyword: &str) -> Option<String> {
+    let mut tag = String::new();
+    for c in keyword.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || c == '_' || c == '/' {
+            tag.push(c);
+        } else if !tag.is_empty() && !tag.ends_with('-') {
+            tag.push('-');
+        }
+    }
+    let tag = tag.trim_end_matches('-');
+    (!tag.is_empty() && !tag.chars().all(|c| c.is_ascii_digit())).then(|| tag.to_string())
+}
+
+/// A cite key safe to use as a file name
+fn file_name(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+
+    fn paper() -> Paper {
+        PaperBuilder::new(
+            "1706.03762",
+            "Attention Is All You Need",
+            "https://arxiv.org/abs/1706.03762",
+            SourceType::Arxiv,
+        )
+        .authors("Ashish Vaswani; Noam \"N\" Shazeer")
+        .published_date("2017-06-12")
+        .abstract_text("The dominant sequence transduction models...")
+        .keywords("Machine Learning; attention; 2017")
+        .categories("cs.CL")
+        .build()
+    }
+
+    #[test]
+    fn test_note() {
+        let note = note(&paper(), "vaswani2017attention");
+        assert!(note.starts_with("---\n# research-master: "));
+        assert!(note.contains("\ntitle: \"Attention Is All You Need\"\n"));
+        assert!(note.contains("\ncitekey: \"vaswani2017attention\"\n"));
+        assert!(
+            note.contains("\nauthors:\n  - \"Ashish Vaswani\"\n  - \"Noam \\\"N\\\" Shazeer\"\n")
+        );
+        assert!(note.contains("\nyear: 2017\n"));
+        assert!(note.contains("\nsource: \"arxiv\"\n"));
+        assert!(note.contains(
+            "\ntags:\n  - \"machine-learning\"\n  - \"attention\"\n  - \"cs-cl\"\n---\n"
+        ));
+        assert!(note.contains("# Attention Is All You Need\n\n## Abstract\n\nThe dominant"));
+        assert!(is_generated(&note));
+        assert!(!is_generated(
+            &note.replace("## Notes\n", "## Notes\n\nMy thoughts.\n")
+        ));
+        assert!(!is_generated("# A note without front matter\n"));
+        assert_eq!(
+            tag("Self-Attention (NLP)").as_deref(),
+            Some("self-attention-nlp")
+        );
+        assert_eq!(file_name("doi:10.1/x"), "doi_10.1_x");
+    }
+
+    #[test]
+    fn test_write_dir_keeps_edited_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut paper = paper();
+
+        let summary = write_dir(&[paper.clone()], dir.path()).unwrap();
+        let path = dir.path().join("vaswani2017attention.md");
+        assert_eq!(summary.written, vec![path.clone()]);
+
+        let summary = write_dir(&[paper.clone()], dir.path()).unwrap();
+        assert_eq!(summary.unchanged, vec![path.clone()]);
+
+        // Unedited notes are refreshed
+        paper.citations = Some(100);
+        let summary = write_dir(&[paper.clone()], dir.path()).unwrap();
+        assert_eq!(summary.written, vec![path.clone()]);
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("citations: 100"));
+
+        // Edited notes are kept
+        let edited = fs::read_to_string(&path).unwrap() + "\nMy thoughts.\n";
+        fs::write(&path, &edited).unwrap();
+        paper.citations = Some(200);
+        let summary = write_dir(&[paper], dir.path()).unwrap();
+        assert_eq!(summary.kept, vec![path.clone()]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), edited);
+    }
+}
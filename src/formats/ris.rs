@@ -0,0 +1,349 @@
+//! RIS reading and writing.
+
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use super::{
+    date_month, display_name, entry_type, extra_text, from_ris_type, inverted_name, iso_date,
+    join_keywords, ris_type, unique_keys,
+};
+use crate::models::{Paper, PaperBuilder, SourceType};
+
+/// RIS tags stored in `extra` under BibTeX field names
+const EXTRA_TAGS: &[(&str, &str)] = &[
+    ("VL", "volume"),
+    ("IS", "number"),
+    ("PB", "publisher"),
+    ("CY", "address"),
+    ("ET", "edition"),
+    ("SN", "issn"),
+    ("N1", "note"),
+];
+
+/// Parse every record of a RIS file
+///
+/// RIS is line based and has no structure that can be malformed: unknown lines
+/// are treated as continuations and a missing `ER` ends the record at the end
+/// of the file.
+pub(super) fn read(content: &str) -> Vec<Paper> {
+    let mut papers = Vec::new();
+    let mut record: Option<Vec<(String, String)>> = None;
+
+    for line in content.lines() {
+        let line = line.trim_end().trim_start_matches('\u{feff}');
+        match parse_line(line) {
+            Some(("TY", value)) => {
+                if let Some(tags) = record.take() {
+                    papers.push(to_paper(tags, papers.len()));
+                }
+                record = Some(vec![("TY".to_string(), value.to_string())]);
+            }
+            Some(("ER", _)) => {
+                if let Some(tags) = record.take() {
+                    papers.push(to_paper(tags, papers.len()));
+                }
+            }
+            Some((tag, value)) => {
+                if let Some(tags) = record.as_mut() {
+                    tags.push((tag.to_string(), value.to_string()));
+                }
+            }
+            None if !line.trim().is_empty() => {
+                if let Some((_, value)) = record.as_mut().and_then(|tags| tags.last_mut()) {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            }
+            None => {}
+        }
+    }
+    if let Some(tags) = record {
+        papers.push(to_paper(tags, papers.len()));
+    }
+
+    papers
+}
+
+/// `TAG  - value`; some exporters drop the space after the hyphen
+fn parse_line(line: &str) -> Option<(&str, &str)> {
+    let bytes = line.as_bytes();
+    if bytes.len() < 5
+        || !bytes[0].is_ascii_uppercase()
+        || !bytes[1].is_ascii_alphanumeric()
+        || &bytes[2..5] != b"  -"
+    {
+        return None;
+    }
+    Some((&line[..2], line[5..].trim()))
+}
+
+fn to_paper(tags: Vec<(String, String)>, index: usize) -> Paper {
+    let mut paper = PaperBuilder::new("", "", "", SourceType::Other("ris".to_string())).build();
+    let mut fields: HashMap<String, String> = HashMap::new();
+    let mut authors = Vec::new();
+    let mut editors = Vec::new();
+    let mut keywords = Vec::new();
+    let mut kind = "misc";
+    let (mut start_page, mut end_page) = (None, None);
+    let mut date = None;
+
+    for (tag, value) in tags {
+        if value.is_empty() {
+            continue;
+        }
+        match tag.as_str() {
+            "TY" => kind = from_ris_type(&value),
+            "ID" => {
+                fields.insert("citekey".to_string(), value);
+            }
+            "TI" | "T1" => paper.title = value,
+            "AU" | "A1" => authors.push(display_name(&value)),
+            "A2" | "ED" => editors.push(display_name(&value)),
+            "AB" | "N2" => paper.r#abstract = value,
+            "DO" => paper.doi = Some(value),
+            "UR" if paper.url.is_empty() => paper.url = value,
+            "L1" if paper.pdf_url.is_none() && value.starts_with("http") => {
+                paper.pdf_url = Some(value)
+            }
+            "KW" => keywords.push(value),
+            "PY" | "Y1" | "DA" => {
+                if date.is_none() || tag == "DA" {
+                    date = ris_date(&value).or(date);
+                }
+            }
+            "JO" | "JF" | "JA" | "J2" => {
+                fields.entry("journal".to_string()).or_insert(value);
+            }
+            "T2" | "BT" => {
+                let field = match kind {
+                    "inproceedings" | "incollection" => "booktitle",
+                    "article" => "journal",
+                    _ => "series",
+                };
+                fields.entry(field.to_string()).or_insert(value);
+            }
+            "SP" => start_page = Some(value),
+            "EP" => end_page = Some(value),
+            _ => {
+                let name = EXTRA_TAGS
+                    .iter()
+                    .find(|(t, _)| *t == tag)
+                    .map(|(_, name)| name.to_string())
+                    .unwrap_or(tag);
+                fields
+                    .entry(name)
+                    .and_modify(|v| {
+                        v.push_str("; ");
+                        v.push_str(&value);
+                    })
+                    .or_insert(value);
+            }
+        }
+    }
+
+    paper.paper_id = fields
+        .get("citekey")
+        .cloned()
+        .or_else(|| paper.doi.clone())
+        .unwrap_or_else(|| format!("ris-{}", index + 1));
+    paper.authors = authors.join("; ");
+    paper.keywords = join_keywords(keywords.iter().map(String::as_str));
+    paper.published_date = date;
+
+    let pages = match (start_page, end_page) {
+        (Some(start), Some(end)) => Some(format!("{}-{}", start, end)),
+        (start, end) => start.or(end),
+    };
+    let extra = paper.extra.get_or_insert_with(HashMap::new);
+    extra.insert("entry_type".to_string(), json!(kind));
+    if !editors.is_empty() {
+        extra.insert("editor".to_string(), json!(editors.join("; ")));
+    }
+    if let Some(pages) = pages {
+        extra.insert("pages".to_string(), json!(pages));
+    }
+    for (name, value) in fields {
+        extra.insert(name, json!(value));
+    }
+    paper
+}
+
+/// ISO date from `2020`, `2020/05/12/`, `2020/05//` or `2020-05-12`
+fn ris_date(value: &str) -> Option<String> {
+    let mut parts = value.split(['/', '-']).map(str::trim);
+    let year = parts.next()?;
+    iso_date(year, parts.next().filter(|m| !m.is_empty()), parts.next())
+}
+
+/// Write papers as RIS records
+pub(super) fn write(papers: &[Paper]) -> String {
+    papers
+        .iter()
+        .zip(unique_keys(papers))
+        .map(|(paper, key)| write_record(paper, &key))
+        .collect()
+}
+
+fn write_record(paper: &Paper, key: &str) -> String {
+    let kind = entry_type(paper);
+    let mut lines: Vec<(&str, String)> = vec![("TY", ris_type(kind).to_string())];
+
+    for author in paper.author_list() {
+        lines.push(("AU", inverted_name(author)));
+    }
+    if let Some(editors) = extra_text(paper, "editor") {
+        for editor in editors.split(';').filter(|e| !e.trim().is_empty()) {
+            lines.push(("A2", inverted_name(editor.trim())));
+        }
+    }
+    if !paper.title.trim().is_empty() {
+        lines.push(("TI", paper.title.trim().to_string()));
+    }
+    let venue = extra_text(paper, "booktitle")
+        .filter(|_| kind != "article")
+        .or_else(|| paper.venue().map(str::to_string));
+    if let Some(venue) = venue {
+        lines.push((if kind == "article" { "JO" } else { "T2" }, venue));
+    }
+    if let Some(date) = paper.published_date.as_deref() {
+        if let Some(year) = paper.year() {
+            lines.push(("PY", year.to_string()));
+        }
+        let day = date
+            .get(8..10)
+            .filter(|d| d.chars().all(|c| c.is_ascii_digit()));
+        if let Some(month) = date_month(date) {
+            lines.push((
+                "DA",
+                format!(
+                    "{}/{:02}/{}/",
+                    paper.year().unwrap_or_default(),
+                    month,
+                    day.unwrap_or("")
+                ),
+            ));
+        }
+    }
+    for &(tag, name) in EXTRA_TAGS {
+        if let Some(value) = extra_text(paper, name) {
+            lines.push((tag, value));
+        }
+    }
+    if let Some(ref status) = paper.status {
+        lines.push(("N1", status.warning()));
+    }
+    if let Some(pages) = extra_text(paper, "pages") {
+        let pages = pages.replace("--", "-");
+        match pages.split_once('-') {
+            Some((start, end)) => {
+                lines.push(("SP", start.trim().to_string()));
+                lines.push(("EP", end.trim().to_string()));
+            }
+            None => lines.push(("SP", pages)),
+        }
+    }
+    if let Some(doi) = paper.doi.as_deref().filter(|d| !d.trim().is_empty()) {
+        lines.push(("DO", doi.trim().to_string()));
+    }
+    if !paper.url.trim().is_empty() {
+        lines.push(("UR", paper.url.trim().to_string()));
+    }
+    if let Some(pdf) = paper.pdf_url.as_deref() {
+        lines.push(("L1", pdf.to_string()));
+    }
+    for keyword in paper.keyword_list() {
+        lines.push(("KW", keyword.to_string()));
+    }
+    if !paper.r#abstract.trim().is_empty() {
+        let text = paper
+            .r#abstract
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines.push(("AB", text));
+    }
+    lines.push(("ID", key.to_string()));
+
+    let mut record: String = lines
+        .iter()
+        .map(|(tag, value)| format!("{}  - {}\n", tag, value))
+        .collect();
+    record.push_str("ER  - \n\n");
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\u{feff}TY  - JOUR
+AU  - Vaswani, Ashish
+AU  - Shazeer, Noam
+TI  - Attention is all
+  you need
+JO  - Advances in Neural Information Processing Systems
+PY  - 2017/12/04/
+VL  - 30
+SP  - 5998
+EP  - 6008
+DO  - 10.5555/3295222.3295349
+KW  - transformers
+KW  - attention
+C1  - custom one
+C1  - custom two
+ER  -
+
+TY  - CONF
+T1  - Second record without an end tag
+T2  - Proceedings of ACL
+PY  - 2019///
+";
+
+    #[test]
+    fn test_read_ris() {
+        let papers = read(SAMPLE);
+        assert_eq!(papers.len(), 2);
+
+        let paper = &papers[0];
+        assert_eq!(paper.paper_id, "10.5555/3295222.3295349");
+        assert_eq!(paper.title, "Attention is all you need");
+        assert_eq!(paper.authors, "Ashish Vaswani; Noam Shazeer");
+        assert_eq!(
+            paper.venue(),
+            Some("Advances in Neural Information Processing Systems")
+        );
+        assert_eq!(paper.published_date.as_deref(), Some("2017-12-04"));
+        assert_eq!(paper.keywords.as_deref(), Some("transformers; attention"));
+        assert_eq!(extra_text(paper, "pages").as_deref(), Some("5998-6008"));
+        assert_eq!(extra_text(paper, "volume").as_deref(), Some("30"));
+        assert_eq!(
+            extra_text(paper, "C1").as_deref(),
+            Some("custom one; custom two")
+        );
+
+        let paper = &papers[1];
+        assert_eq!(paper.paper_id, "ris-2");
+        assert_eq!(entry_type(paper), "inproceedings");
+        assert_eq!(paper.venue(), Some("Proceedings of ACL"));
+        assert_eq!(paper.published_date.as_deref(), Some("2019"));
+    }
+
+    #[test]
+    fn test_ris_round_trip() {
+        let papers = read(SAMPLE);
+        let written = write(&papers);
+        assert!(written.starts_with("TY  - JOUR\nAU  - Vaswani, Ashish\n"));
+        assert!(written.contains("DA  - 2017/12/04/\n"));
+        assert!(written.contains("SP  - 5998\nEP  - 6008\n"));
+        assert!(written.contains("TY  - CONF\n"));
+        assert!(written.contains("T2  - Proceedings of ACL\n"));
+
+        let again = read(&written);
+        assert_eq!(again.len(), 2);
+        assert_eq!(again[0].title, papers[0].title);
+        assert_eq!(again[0].authors, papers[0].authors);
+        assert_eq!(again[0].published_date, papers[0].published_date);
+        assert_eq!(again[1].venue(), papers[1].venue());
+    }
+}
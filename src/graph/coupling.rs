@@ -0,0 +1,432 @@
+//! Co-citation and bibliographic coupling analysis.
+//!
+//! Two papers are *co-cited* when a third paper cites both of them, and
+//! *bibliographically coupled* when they share references. Both point to
+//! papers that are intellectually close without citing each other directly.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use futures_util::{stream, StreamExt};
+use serde::Serialize;
+
+use super::snowball::{CitationFetcher, DEFAULT_CITATION_SOURCES};
+use super::CitationGraph;
+use crate::models::Paper;
+use crate::sources::Source;
+use crate::utils::CacheService;
+
+/// Limits for a coupling analysis
+#[derive(Debug, Clone)]
+pub struct CouplingOptions {
+    /// Maximum citations/references fetched per paper and direction
+    pub max_per_paper: usize,
+
+    /// Maximum citing papers whose references are fetched to find co-cited papers
+    pub max_citing: usize,
+
+    /// Number of missing papers to report
+    pub top: usize,
+
+    /// Citation sources to use, in order of preference
+    pub sources: Vec<String>,
+
+    /// Number of papers whose links are fetched concurrently
+    pub concurrency: usize,
+}
+
+impl Default for CouplingOptions {
+    fn default() -> Self {
+        Self {
+            max_per_paper: 20,
+            max_citing: 50,
+            top: 20,
+            sources: DEFAULT_CITATION_SOURCES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            concurrency: 4,
+        }
+    }
+}
+
+impl CouplingOptions {
+    /// Create options with the defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of citations/references fetched per paper
+    pub fn max_per_paper(mut self, max: usize) -> Self {
+        self.max_per_paper = max;
+        self
+    }
+
+    /// Set how many citing papers are expanded
+    pub fn max_citing(mut self, max: usize) -> Self {
+        self.max_citing = max;
+        self
+    }
+
+    /// Set the number of missing papers reported
+    pub fn top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    /// Set the citation sources, in order of preference
+    pub fn sources(mut self, sources: Vec<String>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Set how many papers are expanded concurrently
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+}
+
+/// Similarity between two papers of the analysed set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PaperPair {
+    /// Index of the first paper in [`CouplingReport::papers`]
+    pub a: usize,
+
+    /// Index of the second paper in [`CouplingReport::papers`]
+    pub b: usize,
+
+    /// Number of papers citing both
+    pub co_citations: usize,
+
+    /// Number of references the two share (bibliographic coupling strength)
+    pub coupling: usize,
+}
+
+/// A paper close to the set that is not part of it
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingPaper {
+    /// The paper
+    pub paper: Paper,
+
+    /// Papers outside the set that cite it together with a paper of the set
+    pub co_citations: usize,
+
+    /// Papers of the set that cite it
+    pub cited_by_set: usize,
+}
+
+impl MissingPaper {
+    /// Ranking score: how often the paper appears next to the set
+    pub fn score(&self) -> usize {
+        self.co_citations + self.cited_by_set
+    }
+}
+
+/// Result of a coupling analysis
+#[derive(Debug, Clone, Serialize)]
+pub struct CouplingReport {
+    /// The analysed papers, merged with any metadata found while fetching
+    pub papers: Vec<Paper>,
+
+    /// Pairs of set papers that are co-cited or coupled, strongest first
+    pub pairs: Vec<PaperPair>,
+
+    /// Papers outside the set ranked by closeness to it
+    pub missing: Vec<MissingPaper>,
+
+    /// The citation graph the analysis was computed from
+    #[serde(skip)]
+    pub graph: CitationGraph,
+}
+
+impl CouplingReport {
+    /// Analyse a citation graph whose seed nodes form the paper set
+    ///
+    /// At most `top` missing papers are reported.
+    pub fn from_graph(graph: CitationGraph, top: usize) -> Self {
+        let set: Vec<usize> = (0..graph.node_count())
+            .filter(|&i| graph.nodes()[i].seed)
+            .collect();
+        let in_set: HashSet<usize> = set.iter().copied().collect();
+
+        let mut references = vec![HashSet::new(); graph.node_count()];
+        let mut citers = vec![HashSet::new(); graph.node_count()];
+        for &(citing, cited) in graph.links() {
+            references[citing].insert(cited);
+            citers[cited].insert(citing);
+        }
+
+        let mut pairs = Vec::new();
+        for (i, &a) in set.iter().enumerate() {
+            for (j, &b) in set.iter().enumerate().skip(i + 1) {
+                let pair = PaperPair {
+                    a: i,
+                    b: j,
+                    co_citations: citers[a].intersection(&citers[b]).count(),
+                    coupling: references[a].intersection(&references[b]).count(),
+                };
+                if pair.co_citations > 0 || pair.coupling > 0 {
+                    pairs.push(pair);
+                }
+            }
+        }
+        pairs.sort_by_key(|p| std::cmp::Reverse(p.co_citations + p.coupling));
+
+        // Papers outside the set that cite at least one paper of the set
+        let citing_set: HashSet<usize> = set
+            .iter()
+            .flat_map(|&s| citers[s].iter().copied())
+            .filter(|c| !in_set.contains(c))
+            .collect();
+
+        let mut missing: Vec<MissingPaper> = (0..graph.node_count())
+            .filter(|i| !in_set.contains(i))
+            .map(|i| MissingPaper {
+                paper: graph.nodes()[i].paper.clone(),
+                co_citations: citers[i].intersection(&citing_set).count(),
+                cited_by_set: citers[i].intersection(&in_set).count(),
+            })
+            .filter(|m| m.score() > 0)
+            .collect();
+        missing.sort_by_key(|m| {
+            (
+                std::cmp::Reverse(m.score()),
+                std::cmp::Reverse(m.co_citations),
+                std::cmp::Reverse(m.paper.citations.unwrap_or(0)),
+            )
+        });
+        missing.truncate(top);
+
+        Self {
+            papers: set
+                .iter()
+                .map(|&i| graph.nodes()[i].paper.clone())
+                .collect(),
+            pairs,
+            missing,
+            graph,
+        }
+    }
+}
+
+/// A coupling analysis over citation sources
+#[derive(Debug, Clone)]
+pub struct Coupling {
+    fetcher: CitationFetcher,
+    options: CouplingOptions,
+}
+
+impl Coupling {
+    /// Prepare an analysis over `sources` with the given limits
+    pub fn new(sources: Vec<Arc<dyn Source>>, options: CouplingOptions) -> Self {
+        Self {
+            fetcher: CitationFetcher::new(sources, &options.sources),
+            options,
+        }
+    }
+
+    /// Cache citation and reference lookups
+    pub fn with_cache(mut self, cache: CacheService) -> Self {
+        self.fetcher = self.fetcher.with_cache(cache);
+        self
+    }
+
+    /// The lookup backend used by this analysis
+    pub fn fetcher(&self) -> &CitationFetcher {
+        &self.fetcher
+    }
+
+    /// Fetch the references and citations of `papers` and analyse them
+    ///
+    /// The references of up to `max_citing` citing papers are fetched as well,
+    /// since they are what makes two papers co-cited.
+    pub async fn run(&self, papers: Vec<Paper>) -> CouplingReport {
+        let mut graph = CitationGraph::new();
+        let mut set = Vec::new();
+        for paper in &papers {
+            let (index, added) = graph.add_paper(paper, 0, true);
+            if added {
+                set.push(index);
+            }
+        }
+        let papers: Vec<(usize, Paper)> = set
+            .iter()
+            .map(|&i| (i, graph.nodes()[i].paper.clone()))
+            .collect();
+
+        let max = self.options.max_per_paper;
+        let concurrency = self.options.concurrency.max(1);
+
+        let expanded: Vec<_> = stream::iter(papers)
+            .map(|(index, paper)| async move {
+                let references = self.fetcher.references(&paper, max).await;
+                let citations = self.fetcher.citations(&paper, max).await;
+                (index, paper, references, citations)
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        for (index, paper, references, citations) in expanded {
+            match references {
+                Ok(references) => {
+                    for reference in &references {
+                        let (other, _) = graph.add_paper(reference, 1, false);
+                        graph.add_edge(index, other);
+                    }
+                }
+                Err(e) => tracing::warn!("No references for '{}': {}", paper.paper_id, e),
+            }
+            match citations {
+                Ok(citations) => {
+                    for citing in &citations {
+                        let (other, _) = graph.add_paper(citing, 1, false);
+                        graph.add_edge(other, index);
+                    }
+                }
+                Err(e) => tracing::warn!("No citations for '{}': {}", paper.paper_id, e),
+            }
+        }
+
+        // Expand the papers citing the most of the set first
+        let mut citing: Vec<(usize, usize)> = Vec::new();
+        for &(from, to) in graph.links() {
+            if graph.nodes()[to].seed && !graph.nodes()[from].seed {
+                match citing.iter_mut().find(|(i, _)| *i == from) {
+                    Some((_, count)) => *count += 1,
+                    None => citing.push((from, 1)),
+                }
+            }
+        }
+        citing.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        citing.truncate(self.options.max_citing);
+
+        let papers: Vec<(usize, Paper)> = citing
+            .into_iter()
+            .map(|(i, _)| (i, graph.nodes()[i].paper.clone()))
+            .collect();
+        let expanded: Vec<_> = stream::iter(papers)
+            .map(|(index, paper)| async move {
+                (
+                    index,
+                    paper.paper_id.clone(),
+                    self.fetcher.references(&paper, max).await,
+                )
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
+
+        for (index, paper_id, references) in expanded {
+            match references {
+                Ok(references) => {
+                    for reference in &references {
+                        let (other, _) = graph.add_paper(reference, 2, false);
+                        graph.add_edge(index, other);
+                    }
+                }
+                Err(e) => tracing::debug!("No references for citing paper '{}': {}", paper_id, e),
+            }
+        }
+
+        CouplingReport::from_graph(graph, self.options.top)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+
+    fn paper(id: &str) -> Paper {
+        PaperBuilder::new(id, "", "", SourceType::SemanticScholar).build()
+    }
+
+    /// Build a graph from `citing -> cited` links; `seeds` form the set
+    fn graph(seeds: &[&str], links: &[(&str, &str)]) -> CitationGraph {
+        let mut graph = CitationGraph::new();
+        for seed in seeds {
+            graph.add_paper(&paper(seed), 0, true);
+        }
+        for (citing, cited) in links {
+            let (citing, _) = graph.add_paper(&paper(citing), 1, false);
+            let (cited, _) = graph.add_paper(&paper(cited), 1, false);
+            graph.add_edge(citing, cited);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_pairs() {
+        let graph = graph(
+            &["a", "b", "c"],
+            &[
+                // a and b share references r1 and r2
+                ("a", "r1"),
+                ("a", "r2"),
+                ("b", "r1"),
+                ("b", "r2"),
+                // x cites both a and c
+                ("x", "a"),
+                ("x", "c"),
+            ],
+        );
+        let report = CouplingReport::from_graph(graph, 10);
+
+        assert_eq!(report.papers.len(), 3);
+        assert_eq!(
+            report.pairs,
+            vec![
+                PaperPair {
+                    a: 0,
+                    b: 1,
+                    co_citations: 0,
+                    coupling: 2
+                },
+                PaperPair {
+                    a: 0,
+                    b: 2,
+                    co_citations: 1,
+                    coupling: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_papers() {
+        let graph = graph(
+            &["a", "b"],
+            &[
+                // m is co-cited with the set by x and y, and cited by a
+                ("x", "a"),
+                ("x", "m"),
+                ("y", "b"),
+                ("y", "m"),
+                ("a", "m"),
+                // n is only cited by b
+                ("b", "n"),
+                // z cites m without citing the set: not a co-citation
+                ("z", "m"),
+            ],
+        );
+        let report = CouplingReport::from_graph(graph, 10);
+
+        let ids: Vec<&str> = report
+            .missing
+            .iter()
+            .map(|m| m.paper.paper_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["m", "n"]);
+        assert_eq!(report.missing[0].co_citations, 2);
+        assert_eq!(report.missing[0].cited_by_set, 1);
+        assert_eq!(report.missing[0].score(), 3);
+
+        // Papers that only cite the set are not missing
+        assert!(!ids.contains(&"x"));
+
+        let report = CouplingReport::from_graph(report.graph, 1);
+        assert_eq!(report.missing.len(), 1);
+    }
+}
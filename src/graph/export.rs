@@ -0,0 +1,307 @@
+//! Citation graph serialization.
+
+use std::fmt::Write;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{CitationGraph, GraphNode};
+use crate::utils::xml_escape;
+
+/// Output format for a citation graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// JSON node and edge lists
+    #[default]
+    Json,
+    /// GraphML (yEd, Cytoscape, NetworkX)
+    Graphml,
+    /// GEXF 1.3 (Gephi)
+    Gexf,
+    /// Graphviz DOT
+    Dot,
+}
+
+impl GraphFormat {
+    /// Conventional file extension for the format
+    pub fn extension(self) -> &'static str {
+        match self {
+            GraphFormat::Json => "json",
+            GraphFormat::Graphml => "graphml",
+            GraphFormat::Gexf => "gexf",
+            GraphFormat::Dot => "dot",
+        }
+    }
+
+    /// Serialize `graph` in this format
+    pub fn write(self, graph: &CitationGraph) -> String {
+        match self {
+            GraphFormat::Json => write_json(graph),
+            GraphFormat::Graphml => write_graphml(graph),
+            GraphFormat::Gexf => write_gexf(graph),
+            GraphFormat::Dot => write_dot(graph),
+        }
+    }
+}
+
+impl std::fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// Node attributes shared by all formats: (name, type, value)
+fn attributes(node: &GraphNode) -> Vec<(&'static str, &'static str, String)> {
+    let paper = &node.paper;
+    let mut attrs = vec![
+        ("label", "string", node.label()),
+        ("paper_id", "string", paper.paper_id.clone()),
+        ("source", "string", paper.source.id().to_string()),
+    ];
+    if let Some(doi) = paper.doi.as_deref().filter(|d| !d.is_empty()) {
+        attrs.push(("doi", "string", doi.to_string()));
+    }
+    if !paper.authors.is_empty() {
+        attrs.push(("authors", "string", paper.authors.clone()));
+    }
+    if let Some(year) = paper.year() {
+        attrs.push(("year", "int", year.to_string()));
+    }
+    if let Some(citations) = paper.citations {
+        attrs.push(("citations", "int", citations.to_string()));
+    }
+    attrs.push(("depth", "int", node.depth.to_string()));
+    attrs.push(("seed", "boolean", node.seed.to_string()));
+    attrs
+}
+
+/// Attribute declarations, in the order used for GraphML keys and GEXF ids
+const ATTRIBUTE_KEYS: &[(&str, &str)] = &[
+    ("label", "string"),
+    ("paper_id", "string"),
+    ("source", "string"),
+    ("doi", "string"),
+    ("authors", "string"),
+    ("year", "int"),
+    ("citations", "int"),
+    ("depth", "int"),
+    ("seed", "boolean"),
+];
+
+fn write_json(graph: &CitationGraph) -> String {
+    let nodes: Vec<serde_json::Value> = graph
+        .nodes()
+        .iter()
+        .map(|node| {
+            let paper = &node.paper;
+            serde_json::json!({
+                "id": node.id,
+                "label": node.label(),
+                "paper_id": paper.paper_id,
+                "source": paper.source.id(),
+                "doi": paper.doi,
+                "title": paper.title,
+                "authors": paper.authors,
+                "year": paper.year(),
+                "citations": paper.citations,
+                "depth": node.depth,
+                "seed": node.seed,
+            })
+        })
+        .collect();
+
+    let value = serde_json::json!({
+        "directed": true,
+        "truncated": graph.truncated,
+        "nodes": nodes,
+        "edges": graph.edges(),
+    });
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+fn write_graphml(graph: &CitationGraph) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (name, kind) in ATTRIBUTE_KEYS {
+        let _ = writeln!(
+            out,
+            "  <key id=\"{name}\" for=\"node\" attr.name=\"{name}\" attr.type=\"{kind}\"/>"
+        );
+    }
+    out.push_str("  <graph id=\"citations\" edgedefault=\"directed\">\n");
+
+    for node in graph.nodes() {
+        let _ = writeln!(out, "    <node id=\"{}\">", xml_escape(&node.id));
+        for (name, _, value) in attributes(node) {
+            let _ = writeln!(
+                out,
+                "      <data key=\"{}\">{}</data>",
+                name,
+                xml_escape(&value)
+            );
+        }
+        out.push_str("    </node>\n");
+    }
+    for edge in graph.edges() {
+        let _ = writeln!(
+            out,
+            "    <edge source=\"{}\" target=\"{}\"/>",
+            xml_escape(&edge.source),
+            xml_escape(&edge.target)
+        );
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn write_gexf(graph: &CitationGraph) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+    out.push_str("  <graph mode=\"static\" defaultedgetype=\"directed\">\n");
+    out.push_str("    <attributes class=\"node\">\n");
+    // GEXF carries the label as a node attribute of its own
+    for (name, kind) in ATTRIBUTE_KEYS.iter().skip(1) {
+        let kind = if *kind == "int" { "integer" } else { kind };
+        let _ = writeln!(
+            out,
+            "      <attribute id=\"{name}\" title=\"{name}\" type=\"{kind}\"/>"
+        );
+    }
+    out.push_str("    </attributes>\n    <nodes>\n");
+
+    for node in graph.nodes() {
+        let _ = writeln!(
+            out,
+            "      <node id=\"{}\" label=\"{}\">",
+            xml_escape(&node.id),
+            xml_escape(&node.label())
+        );
+        out.push_str("        <attvalues>\n");
+        for (name, _, value) in attributes(node).into_iter().skip(1) {
+            let _ = writeln!(
+                out,
+                "          <attvalue for=\"{}\" value=\"{}\"/>",
+                name,
+                xml_escape(&value)
+            );
+        }
+        out.push_str("        </attvalues>\n      </node>\n");
+    }
+
+    out.push_str("    </nodes>\n    <edges>\n");
+    for (i, edge) in graph.edges().iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "      <edge id=\"{}\" source=\"{}\" target=\"{}\"/>",
+            i,
+            xml_escape(&edge.source),
+            xml_escape(&edge.target)
+        );
+    }
+    out.push_str("    </edges>\n  </graph>\n</gexf>\n");
+    out
+}
+
+fn write_dot(graph: &CitationGraph) -> String {
+    let mut out = String::from("digraph citations {\n  rankdir=LR;\n  node [shape=box];\n");
+
+    for node in graph.nodes() {
+        let mut label = node.label();
+        if let Some(year) = node.paper.year() {
+            let _ = write!(label, " ({})", year);
+        }
+        let style = if node.seed { ", style=bold" } else { "" };
+        let _ = writeln!(
+            out,
+            "  \"{}\" [label=\"{}\"{}];",
+            dot_escape(&node.id),
+            dot_escape(&label),
+            style
+        );
+    }
+    for edge in graph.edges() {
+        let _ = writeln!(
+            out,
+            "  \"{}\" -> \"{}\";",
+            dot_escape(&edge.source),
+            dot_escape(&edge.target)
+        );
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+fn dot_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+
+    fn graph() -> CitationGraph {
+        let mut graph = CitationGraph::new();
+        let seed = PaperBuilder::new("a", "Graphs & \"Networks\"", "", SourceType::Arxiv)
+            .published_date("2019-05-01")
+            .build();
+        let cited = PaperBuilder::new("b", "", "", SourceType::Arxiv)
+            .doi("10.1/B")
+            .citations(7)
+            .build();
+        let (a, _) = graph.add_paper(&seed, 0, true);
+        let (b, _) = graph.add_paper(&cited, 1, false);
+        graph.add_edge(a, b);
+        graph
+    }
+
+    #[test]
+    fn test_json_export() {
+        let value: serde_json::Value =
+            serde_json::from_str(&graph().export(GraphFormat::Json)).unwrap();
+        assert_eq!(value["nodes"][0]["year"], 2019);
+        assert_eq!(value["nodes"][1]["id"], "doi:10.1/b");
+        assert_eq!(value["edges"][0]["source"], "arxiv:a");
+        assert_eq!(value["edges"][0]["target"], "doi:10.1/b");
+    }
+
+    #[test]
+    fn test_xml_exports_are_well_formed() {
+        for format in [GraphFormat::Graphml, GraphFormat::Gexf] {
+            let xml = graph().export(format);
+            assert!(xml.contains("Graphs &amp; &quot;Networks&quot;"));
+
+            let mut reader = quick_xml::Reader::from_str(&xml);
+            let mut edges = 0;
+            loop {
+                match reader.read_event() {
+                    Ok(quick_xml::events::Event::Empty(e)) if e.name().as_ref() == b"edge" => {
+                        edges += 1
+                    }
+                    Ok(quick_xml::events::Event::Eof) => break,
+                    Ok(_) => {}
+                    Err(e) => panic!("{} export is not valid XML: {}", format, e),
+                }
+            }
+            assert_eq!(edges, 1);
+        }
+    }
+
+    #[test]
+    fn test_dot_export() {
+        let dot = graph().export(GraphFormat::Dot);
+        assert!(dot.starts_with("digraph citations {"));
+        assert!(
+            dot.contains("\"arxiv:a\" [label=\"Graphs & \\\"Networks\\\" (2019)\", style=bold];")
+        );
+        assert!(dot.contains("\"arxiv:a\" -> \"doi:10.1/b\";"));
+    }
+}
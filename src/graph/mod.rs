@@ -0,0 +1,369 @@
+//! Citation graphs built from citation and reference lookups.
+//!
+//! - [`CitationGraph`]: Papers (nodes) and citation links (edges), with papers
+//!   found through different sources merged into a single node
+//! - [`Snowball`]: Walk forward (citing) and/or backward (cited) links from seed
+//!   papers to a given depth
+//! - [`Coupling`]: Co-citation and bibliographic coupling within a paper set, and
+//!   the papers most often cited alongside it
+//! - [`CitationFetcher`]: Citation/reference lookups with source fallback and caching
+//! - [`GraphFormat`]: GraphML, GEXF, DOT and JSON node/edge list export
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use research_master::graph::{Direction, GraphFormat, Snowball, SnowballOptions};
+//! use research_master::models::{PaperBuilder, SourceType};
+//! use research_master::sources::SourceRegistry;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let registry = SourceRegistry::new();
+//! let seed = PaperBuilder::new("DOI:10.48550/arXiv.1706.03762", "", "", SourceType::SemanticScholar)
+//!     .build();
+//!
+//! let options = SnowballOptions::new()
+//!     .direction(Direction::Backward)
+//!     .depth(2)
+//!     .max_per_paper(10);
+//! let graph = Snowball::new(registry.all().cloned().collect(), options)
+//!     .run(vec![seed])
+//!     .await;
+//!
+//! println!("{}", graph.export(GraphFormat::Dot));
+//! # }
+//! ```
+
+mod coupling;
+mod export;
+mod snowball;
+
+pub use coupling::{Coupling, CouplingOptions, CouplingReport, MissingPaper, PaperPair};
+pub use export::GraphFormat;
+pub use snowball::{
+    citation_paper_id, parse_year_range, resolve_seed, CitationFetcher, Direction, Snowball,
+    SnowballOptions, DEFAULT_CITATION_SOURCES,
+};
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::models::Paper;
+
+/// Titles shorter than this are too generic to identify a paper on their own
+const MIN_TITLE_KEY_LEN: usize = 20;
+
+/// A paper in a citation graph
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    /// Stable node ID (`doi:<doi>` or `<source>:<paper_id>`)
+    pub id: String,
+
+    /// The paper, merged from every source it was found in
+    pub paper: Paper,
+
+    /// Number of hops from the nearest seed (0 for seeds)
+    pub depth: usize,
+
+    /// Whether the paper is one of the seeds
+    pub seed: bool,
+}
+
+impl GraphNode {
+    /// Short human-readable label for the node
+    pub fn label(&self) -> String {
+        let title = self.paper.title.trim();
+        if title.is_empty() {
+            self.id.clone()
+        } else {
+            title.to_string()
+        }
+    }
+}
+
+/// A citation link: `source` cites `target`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct GraphEdge {
+    /// ID of the citing node
+    pub source: String,
+
+    /// ID of the cited node
+    pub target: String,
+}
+
+/// A directed citation graph
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CitationGraph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+
+    /// Whether the walk stopped early because the node limit was reached
+    pub truncated: bool,
+
+    #[serde(skip)]
+    keys: HashMap<String, usize>,
+    #[serde(skip)]
+    edge_set: HashSet<(usize, usize)>,
+    #[serde(skip)]
+    links: Vec<(usize, usize)>,
+}
+
+impl CitationGraph {
+    /// Create an empty graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All nodes, in the order they were added
+    pub fn nodes(&self) -> &[GraphNode] {
+        &self.nodes
+    }
+
+    /// All edges, in the order they were added
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    /// Number of nodes
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Number of edges
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Find the node for a paper, matching by DOI, source ID or title
+    pub fn find(&self, paper: &Paper) -> Option<usize> {
+        node_keys(paper)
+            .iter()
+            .find_map(|key| self.keys.get(key).copied())
+    }
+
+    /// Look up a node by its ID
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Add a paper, or merge it into the node it duplicates
+    ///
+    /// Returns the node index and whether a new node was created.
+    pub fn add_paper(&mut self, paper: &Paper, depth: usize, seed: bool) -> (usize, bool) {
+        if let Some(index) = self.find(paper) {
+            let node = &mut self.nodes[index];
+            merge_paper(&mut node.paper, paper);
+            node.depth = node.depth.min(depth);
+            node.seed |= seed;
+            self.register_keys(index);
+            return (index, false);
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(GraphNode {
+            id: node_id(paper),
+            paper: paper.clone(),
+            depth,
+            seed,
+        });
+        self.register_keys(index);
+        (index, true)
+    }
+
+    /// Add a citation link between two nodes (`citing` cites `cited`)
+    ///
+    /// Duplicate links and self-citations are ignored.
+    pub fn add_edge(&mut self, citing: usize, cited: usize) -> bool {
+        if citing == cited || !self.edge_set.insert((citing, cited)) {
+            return false;
+        }
+        self.links.push((citing, cited));
+        self.edges.push(GraphEdge {
+            source: self.nodes[citing].id.clone(),
+            target: self.nodes[cited].id.clone(),
+        });
+        true
+    }
+
+    /// Edges as `(citing, cited)` node indices, in the order they were added
+    pub fn links(&self) -> &[(usize, usize)] {
+        &self.links
+    }
+
+    /// Number of links to and from each node, by node index
+    pub fn degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.nodes.len()];
+        for &(citing, cited) in &self.links {
+            degrees[citing] += 1;
+            degrees[cited] += 1;
+        }
+        degrees
+    }
+
+    /// Serialize the graph in the given format
+    pub fn export(&self, format: GraphFormat) -> String {
+        format.write(self)
+    }
+
+    fn register_keys(&mut self, index: usize) {
+        for key in node_keys(&self.nodes[index].paper) {
+            self.keys.entry(key).or_insert(index);
+        }
+    }
+}
+
+/// Normalize a DOI for comparison (lowercase, without resolver prefix)
+pub fn normalize_doi(doi: &str) -> Option<String> {
+    let doi = doi.trim().to_lowercase();
+    let doi = [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "doi:",
+    ]
+    .iter()
+    .find_map(|prefix| doi.strip_prefix(prefix))
+    .unwrap_or(&doi)
+    .to_string();
+    (!doi.is_empty()).then_some(doi)
+}
+
+/// All keys under which a paper can be recognized
+fn node_keys(paper: &Paper) -> Vec<String> {
+    let mut keys = vec![format!("{}:{}", paper.source.id(), paper.paper_id)];
+
+    if let Some(doi) = paper.doi.as_deref().and_then(normalize_doi) {
+        keys.push(format!("doi:{}", doi));
+    }
+
+    let title: String = paper
+        .title
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+    if title.len() >= MIN_TITLE_KEY_LEN {
+        keys.push(format!("title:{}", title));
+    }
+
+    keys
+}
+
+/// Node ID for a new paper: its DOI if known, otherwise its source ID
+fn node_id(paper: &Paper) -> String {
+    match paper.doi.as_deref().and_then(normalize_doi) {
+        Some(doi) => format!("doi:{}", doi),
+        None => format!("{}:{}", paper.source.id(), paper.paper_id),
+    }
+}
+
+/// Fill in fields of `target` that are missing but known in `other`
+fn merge_paper(target: &mut Paper, other: &Paper) {
+    fn fill(target: &mut Option<String>, other: &Option<String>) {
+        if target.as_deref().is_none_or(str::is_empty) {
+            if let Some(value) = other.as_deref().filter(|v| !v.is_empty()) {
+                *target = Some(value.to_string());
+            }
+        }
+    }
+
+    if target.title.is_empty() {
+        target.title = other.title.clone();
+    }
+    if target.authors.is_empty() {
+        target.authors = other.authors.clone();
+    }
+    if target.r#abstract.is_empty() {
+        target.r#abstract = other.r#abstract.clone();
+    }
+    if target.url.is_empty() {
+        target.url = other.url.clone();
+    }
+    fill(&mut target.doi, &other.doi);
+    fill(&mut target.published_date, &other.published_date);
+    fill(&mut target.pdf_url, &other.pdf_url);
+    target.citations = target.citations.max(other.citations);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+
+    #[test]
+    fn test_merge_across_sources() {
+        let mut graph = CitationGraph::new();
+
+        let s2 = PaperBuilder::new(
+            "abc",
+            "Attention Is All You Need",
+            "",
+            SourceType::SemanticScholar,
+        )
+        .doi("10.5555/3295222")
+        .build();
+        let oa = PaperBuilder::new(
+            "https://openalex.org/W1",
+            "Attention is all you need",
+            "",
+            SourceType::OpenAlex,
+        )
+        .doi("https://doi.org/10.5555/3295222")
+        .published_date("2017")
+        .citations(90)
+        .build();
+
+        assert_eq!(graph.add_paper(&s2, 1, false), (0, true));
+        assert_eq!(graph.add_paper(&oa, 0, true), (0, false));
+
+        let node = &graph.nodes()[0];
+        assert_eq!(node.id, "doi:10.5555/3295222");
+        assert_eq!(node.depth, 0);
+        assert!(node.seed);
+        assert_eq!(node.paper.year(), Some(2017));
+        assert_eq!(node.paper.citations, Some(90));
+    }
+
+    #[test]
+    fn test_merge_by_title_without_doi() {
+        let mut graph = CitationGraph::new();
+        let a = PaperBuilder::new("1", "Deep Residual Learning", "", SourceType::Arxiv).build();
+        let b =
+            PaperBuilder::new("W2", "Deep residual learning.", "", SourceType::OpenAlex).build();
+        let short = PaperBuilder::new("W3", "Introduction", "", SourceType::OpenAlex).build();
+        let short2 = PaperBuilder::new("W4", "Introduction", "", SourceType::OpenAlex).build();
+
+        graph.add_paper(&a, 0, true);
+        assert_eq!(graph.add_paper(&b, 1, false), (0, false));
+        assert_eq!(graph.add_paper(&short, 1, false).0, 1);
+        // Short generic titles are not merged
+        assert_eq!(graph.add_paper(&short2, 1, false).0, 2);
+    }
+
+    #[test]
+    fn test_edges_are_deduplicated() {
+        let mut graph = CitationGraph::new();
+        let a = PaperBuilder::new("a", "", "", SourceType::Arxiv).build();
+        let b = PaperBuilder::new("b", "", "", SourceType::Arxiv).build();
+        let (a, _) = graph.add_paper(&a, 0, true);
+        let (b, _) = graph.add_paper(&b, 1, false);
+
+        assert!(graph.add_edge(a, b));
+        assert!(!graph.add_edge(a, b));
+        assert!(!graph.add_edge(a, a));
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.edges()[0].source, "arxiv:a");
+        assert_eq!(graph.degrees(), vec![1, 1]);
+    }
+
+    #[test]
+    fn test_normalize_doi() {
+        assert_eq!(
+            normalize_doi("https://doi.org/10.1000/ABC").as_deref(),
+            Some("10.1000/abc")
+        );
+        assert_eq!(normalize_doi("doi:10.1/x").as_deref(), Some("10.1/x"));
+        assert_eq!(normalize_doi("  "), None);
+    }
+}
@@ -0,0 +1,760 @@
+//! Snowball sampling over citation links.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use futures_util::{stream, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{normalize_doi, CitationGraph};
+use crate::models::{CitationRequest, Paper, PaperBuilder, SourceType};
+use crate::sources::Source;
+use crate::utils::{CacheResult, CacheService};
+
+/// Citation sources tried by default, in order of preference
+pub const DEFAULT_CITATION_SOURCES: &[&str] = &["semantic", "openalex", "dimensions"];
+
+/// Which citation links to follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Papers citing the current paper
+    Forward,
+    /// Papers the current paper cites
+    Backward,
+    /// Both citing and cited papers
+    #[default]
+    Both,
+}
+
+impl Direction {
+    /// Whether citing papers are followed
+    pub fn forward(self) -> bool {
+        matches!(self, Direction::Forward | Direction::Both)
+    }
+
+    /// Whether cited papers are followed
+    pub fn backward(self) -> bool {
+        matches!(self, Direction::Backward | Direction::Both)
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Forward => write!(f, "forward"),
+            Direction::Backward => write!(f, "backward"),
+            Direction::Both => write!(f, "both"),
+        }
+    }
+}
+
+/// Limits for a snowball walk
+#[derive(Debug, Clone)]
+pub struct SnowballOptions {
+    /// Which links to follow
+    pub direction: Direction,
+
+    /// Number of hops from the seeds
+    pub depth: usize,
+
+    /// Maximum citations/references fetched per paper and direction
+    pub max_per_paper: usize,
+
+    /// Stop adding papers once the graph has this many nodes
+    pub max_nodes: usize,
+
+    /// Earliest publication year of papers added to the graph
+    pub year_from: Option<i32>,
+
+    /// Latest publication year of papers added to the graph
+    pub year_to: Option<i32>,
+
+    /// Minimum citation count of papers added to the graph
+    pub min_citations: Option<u32>,
+
+    /// Citation sources to use, in order of preference
+    pub sources: Vec<String>,
+
+    /// Number of papers whose links are fetched concurrently
+    pub concurrency: usize,
+}
+
+impl Default for SnowballOptions {
+    fn default() -> Self {
+        Self {
+            direction: Direction::Both,
+            depth: 1,
+            max_per_paper: 20,
+            max_nodes: 500,
+            year_from: None,
+            year_to: None,
+            min_citations: None,
+            sources: DEFAULT_CITATION_SOURCES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            concurrency: 4,
+        }
+    }
+}
+
+impl SnowballOptions {
+    /// Create options with the defaults (both directions, depth 1)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set which links to follow
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Set the number of hops
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Set the branching factor
+    pub fn max_per_paper(mut self, max: usize) -> Self {
+        self.max_per_paper = max;
+        self
+    }
+
+    /// Set the node limit
+    pub fn max_nodes(mut self, max: usize) -> Self {
+        self.max_nodes = max;
+        self
+    }
+
+    /// Only add papers published in this range (inclusive)
+    pub fn years(mut self, from: Option<i32>, to: Option<i32>) -> Self {
+        self.year_from = from;
+        self.year_to = to;
+        self
+    }
+
+    /// Only add papers with at least this many citations
+    pub fn min_citations(mut self, min: u32) -> Self {
+        self.min_citations = Some(min);
+        self
+    }
+
+    /// Set the citation sources, in order of preference
+    pub fn sources(mut self, sources: Vec<String>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Set how many papers are expanded concurrently
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Whether a newly found paper passes the year and citation filters
+    ///
+    /// Papers with an unknown year or citation count fail the respective
+    /// filter, since they cannot be shown to match it.
+    pub fn accepts(&self, paper: &Paper) -> bool {
+        if self.year_from.is_some() || self.year_to.is_some() {
+            let Some(year) = paper.year() else {
+                return false;
+            };
+            if self.year_from.is_some_and(|from| year < from)
+                || self.year_to.is_some_and(|to| year > to)
+            {
+                return false;
+            }
+        }
+
+        match self.min_citations {
+            Some(min) => paper.citations.is_some_and(|c| c >= min),
+            None => true,
+        }
+    }
+}
+
+/// Parse a year filter such as `2020`, `2018-2022`, `2010-` or `-2015`
+pub fn parse_year_range(value: &str) -> Result<(Option<i32>, Option<i32>), String> {
+    let parse = |s: &str| -> Result<Option<i32>, String> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(None);
+        }
+        s.parse()
+            .map(Some)
+            .map_err(|_| format!("Invalid year '{}' in year filter '{}'", s, value))
+    };
+
+    match value.split_once('-') {
+        Some((from, to)) => {
+            let range = (parse(from)?, parse(to)?);
+            if range == (None, None) {
+                return Err(format!("Invalid year filter '{}'", value));
+            }
+            Ok(range)
+        }
+        None => {
+            let year = parse(value)?.ok_or_else(|| "Empty year filter".to_string())?;
+            Ok((Some(year), Some(year)))
+        }
+    }
+}
+
+/// The ID under which `source_id` knows a paper, if it can identify it
+///
+/// A source's own papers use their own IDs. Semantic Scholar can look up other
+/// papers by DOI or arXiv ID, and other sources by DOI.
+pub fn citation_paper_id(paper: &Paper, source_id: &str) -> Option<String> {
+    if paper.source.id() == source_id {
+        return Some(paper.paper_id.clone());
+    }
+
+    let doi = paper.doi.as_deref().and_then(normalize_doi);
+    match (doi, source_id) {
+        (Some(doi), "semantic") => Some(format!("DOI:{}", doi)),
+        (None, "semantic") if paper.source == SourceType::Arxiv => {
+            Some(format!("ARXIV:{}", paper.paper_id))
+        }
+        // OpenAlex filters only accept OpenAlex work IDs
+        (_, "openalex") => None,
+        (Some(doi), _) => Some(doi),
+        (None, _) => None,
+    }
+}
+
+/// Citation and reference lookups that fall back across sources
+#[derive(Debug, Clone)]
+pub struct CitationFetcher {
+    sources: Vec<Arc<dyn Source>>,
+    cache: Option<CacheService>,
+}
+
+impl CitationFetcher {
+    /// Use the citation-capable `sources` named in `preferred`, in that order
+    pub fn new(sources: Vec<Arc<dyn Source>>, preferred: &[String]) -> Self {
+        let sources = preferred
+            .iter()
+            .filter_map(|id| {
+                sources
+                    .iter()
+                    .find(|s| s.id() == id && s.supports_citations())
+                    .cloned()
+            })
+            .collect();
+
+        Self {
+            sources,
+            cache: None,
+        }
+    }
+
+    /// Cache lookups in `cache`
+    pub fn with_cache(mut self, cache: CacheService) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// IDs of the sources in use, in order of preference
+    pub fn source_ids(&self) -> Vec<&str> {
+        self.sources.iter().map(|s| s.id()).collect()
+    }
+
+    /// Papers citing `paper`
+    pub async fn citations(&self, paper: &Paper, max_results: usize) -> Result<Vec<Paper>, String> {
+        self.fetch(paper, false, max_results).await
+    }
+
+    /// Papers cited by `paper`
+    pub async fn references(
+        &self,
+        paper: &Paper,
+        max_results: usize,
+    ) -> Result<Vec<Paper>, String> {
+        self.fetch(paper, true, max_results).await
+    }
+
+    async fn fetch(
+        &self,
+        paper: &Paper,
+        references: bool,
+        max_results: usize,
+    ) -> Result<Vec<Paper>, String> {
+        let kind = if references {
+            "references"
+        } else {
+            "citations"
+        };
+
+        // The paper's own source knows it best
+        let mut candidates: Vec<&Arc<dyn Source>> = self
+            .sources
+            .iter()
+            .filter(|s| s.id() == paper.source.id())
+            .collect();
+        candidates.extend(self.sources.iter().filter(|s| s.id() != paper.source.id()));
+
+        let mut last_error = None;
+        for source in candidates {
+            let Some(id) = citation_paper_id(paper, source.id()) else {
+                continue;
+            };
+
+            if let Some(ref cache) = self.cache {
+                let cached = if references {
+                    cache.get_references(&id, source.id(), max_results).await
+                } else {
+                    cache.get_citations(&id, source.id(), max_results).await
+                };
+                if let CacheResult::Hit(response) = cached {
+                    return Ok(response.papers);
+                }
+            }
+
+            let request = CitationRequest::new(&id).max_results(max_results);
+            let result = if references {
+                source.get_references(&request).await
+            } else {
+                source.get_citations(&request).await
+            };
+
+            match result {
+                Ok(response) => {
+                    if let Some(ref cache) = self.cache {
+                        if references {
+                            cache
+                                .set_references(source.id(), &id, max_results, &response)
+                                .await;
+                        } else {
+                            cache
+                                .set_citations(source.id(), &id, max_results, &response)
+                                .await;
+                        }
+                    }
+                    return Ok(response.papers);
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        "{} lookup for {} failed in {}: {}",
+                        kind,
+                        id,
+                        source.id(),
+                        e
+                    );
+                    last_error = Some(format!("{}: {}", source.id(), e));
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            format!(
+                "No citation source can look up {} for '{}'",
+                kind, paper.paper_id
+            )
+        }))
+    }
+}
+
+/// A snowball walk over citation links starting from seed papers
+#[derive(Debug, Clone)]
+pub struct Snowball {
+    fetcher: CitationFetcher,
+    options: SnowballOptions,
+}
+
+impl Snowball {
+    /// Prepare a walk over `sources` with the given limits
+    pub fn new(sources: Vec<Arc<dyn Source>>, options: SnowballOptions) -> Self {
+        Self {
+            fetcher: CitationFetcher::new(sources, &options.sources),
+            options,
+        }
+    }
+
+    /// Cache citation and reference lookups
+    pub fn with_cache(mut self, cache: CacheService) -> Self {
+        self.fetcher = self.fetcher.with_cache(cache);
+        self
+    }
+
+    /// The lookup backend used by this walk
+    pub fn fetcher(&self) -> &CitationFetcher {
+        &self.fetcher
+    }
+
+    /// Walk outwards from `seeds` and return the resulting graph
+    ///
+    /// Seeds are always included regardless of the filters. Lookups that fail
+    /// in every source are logged and skipped.
+    pub async fn run(&self, seeds: Vec<Paper>) -> CitationGraph {
+        let mut graph = CitationGraph::new();
+        // Repeated seeds map to the same node, wherever they appear
+        let mut seen = HashSet::new();
+        let mut frontier: Vec<usize> = seeds
+            .iter()
+            .map(|seed| graph.add_paper(seed, 0, true).0)
+            .filter(|&index| seen.insert(index))
+            .collect();
+
+        let direction = self.options.direction;
+        let max_per_paper = self.options.max_per_paper;
+
+        for depth in 1..=self.options.depth {
+            if frontier.is_empty() || graph.truncated {
+                break;
+            }
+
+            let papers: Vec<(usize, Paper)> = frontier
+                .iter()
+                .map(|&i| (i, graph.nodes()[i].paper.clone()))
+                .collect();
+
+            // Fetch in parallel, but merge in frontier order so the graph is
+            // the same on every run
+            let results: Vec<_> = stream::iter(papers)
+                .map(|(index, paper)| async move {
+                    let citing = if direction.forward() {
+                        self.fetcher.citations(&paper, max_per_paper).await
+                    } else {
+                        Ok(Vec::new())
+                    };
+                    let cited = if direction.backward() {
+                        self.fetcher.references(&paper, max_per_paper).await
+                    } else {
+                        Ok(Vec::new())
+                    };
+                    (index, paper, citing, cited)
+                })
+                .buffered(self.options.concurrency.max(1))
+                .collect()
+                .await;
+
+            let mut next = Vec::new();
+            for (index, paper, citing, cited) in results {
+                for (links, is_reference) in [(citing, false), (cited, true)] {
+                    let links = match links {
+                        Ok(links) => links,
+                        Err(e) => {
+                            tracing::warn!("Skipping links of '{}': {}", paper.paper_id, e);
+                            continue;
+                        }
+                    };
+
+                    for linked in links.iter().filter(|p| self.options.accepts(p)) {
+                        let Some(other) = self.add(&mut graph, linked, depth, &mut next) else {
+                            continue;
+                        };
+                        if is_reference {
+                            graph.add_edge(index, other);
+                        } else {
+                            graph.add_edge(other, index);
+                        }
+                    }
+                }
+            }
+
+            frontier = next;
+        }
+
+        graph
+    }
+
+    /// Add a linked paper unless the node limit is reached
+    fn add(
+        &self,
+        graph: &mut CitationGraph,
+        paper: &Paper,
+        depth: usize,
+        next: &mut Vec<usize>,
+    ) -> Option<usize> {
+        if graph.find(paper).is_none() && graph.node_count() >= self.options.max_nodes {
+            graph.truncated = true;
+            return None;
+        }
+
+        let (index, added) = graph.add_paper(paper, depth, false);
+        if added {
+            next.push(index);
+        }
+        Some(index)
+    }
+}
+
+/// Turn a user-supplied identifier into a seed paper
+///
+/// DOIs are looked up in the DOI-capable sources for full metadata; other IDs
+/// become placeholder papers that citation sources can still resolve
+/// (arXiv IDs, OpenAlex work IDs, Semantic Scholar IDs).
+pub async fn resolve_seed(sources: &[Arc<dyn Source>], id: &str) -> Paper {
+    let id = id.trim();
+
+    if let Some(doi) = normalize_doi(id).filter(|d| d.starts_with("10.")) {
+        for source_id in ["semantic", "openalex", "crossref"] {
+            let Some(source) = sources
+                .iter()
+                .find(|s| s.id() == source_id && s.supports_doi_lookup())
+            else {
+                continue;
+            };
+            match source.get_by_doi(&doi).await {
+                Ok(paper) => return paper,
+                Err(e) => tracing::debug!("DOI {} not found in {}: {}", doi, source_id, e),
+            }
+        }
+        return PaperBuilder::new(
+            format!("DOI:{}", doi),
+            "",
+            format!("https://doi.org/{}", doi),
+            SourceType::SemanticScholar,
+        )
+        .doi(doi)
+        .build();
+    }
+
+    let lower = id.to_lowercase();
+    if let Some(arxiv_id) = lower.strip_prefix("arxiv:") {
+        return PaperBuilder::new(arxiv_id.trim(), "", "", SourceType::Arxiv).build();
+    }
+    if id
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '.' || c == 'v')
+        && id.contains('.')
+    {
+        return PaperBuilder::new(id, "", "", SourceType::Arxiv).build();
+    }
+    if lower.starts_with("https://openalex.org/w")
+        || (id.starts_with('W') && id[1..].chars().all(|c| c.is_ascii_digit()) && id.len() > 1)
+    {
+        let work = id.rsplit('/').next().unwrap_or(id);
+        return PaperBuilder::new(
+            format!("https://openalex.org/{}", work),
+            "",
+            "",
+            SourceType::OpenAlex,
+        )
+        .build();
+    }
+
+    PaperBuilder::new(id, "", "", SourceType::SemanticScholar).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SearchResponse, SourceType};
+    use crate::sources::{SourceCapabilities, SourceError};
+    use std::collections::HashMap;
+
+    /// A citation source over a fixed graph (`citing -> cited`)
+    #[derive(Debug)]
+    struct GraphSource {
+        links: Vec<(&'static str, &'static str)>,
+        years: HashMap<&'static str, &'static str>,
+        fetched: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl GraphSource {
+        fn paper(&self, id: &str) -> Paper {
+            let mut builder = PaperBuilder::new(id, "", "", SourceType::SemanticScholar);
+            if let Some(year) = self.years.get(id) {
+                builder = builder.published_date(*year);
+            }
+            builder.build()
+        }
+
+        fn response(&self, ids: Vec<&str>) -> SearchResponse {
+            SearchResponse::new(
+                ids.into_iter().map(|id| self.paper(id)).collect(),
+                "semantic",
+                "",
+            )
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Source for GraphSource {
+        fn id(&self) -> &str {
+            "semantic"
+        }
+
+        fn name(&self) -> &str {
+            "Graph"
+        }
+
+        fn capabilities(&self) -> SourceCapabilities {
+            SourceCapabilities::CITATIONS
+        }
+
+        async fn get_citations(
+            &self,
+            request: &CitationRequest,
+        ) -> Result<SearchResponse, SourceError> {
+            self.fetched
+                .lock()
+                .unwrap()
+                .push(format!("citations:{}", request.paper_id));
+            let ids = self
+                .links
+                .iter()
+                .filter(|(_, cited)| *cited == request.paper_id)
+                .map(|(citing, _)| *citing)
+                .take(request.max_results)
+                .collect();
+            Ok(self.response(ids))
+        }
+
+        async fn get_references(
+            &self,
+            request: &CitationRequest,
+        ) -> Result<SearchResponse, SourceError> {
+            self.fetched
+                .lock()
+                .unwrap()
+                .push(format!("references:{}", request.paper_id));
+            let ids = self
+                .links
+                .iter()
+                .filter(|(citing, _)| *citing == request.paper_id)
+                .map(|(_, cited)| *cited)
+                .take(request.max_results)
+                .collect();
+            Ok(self.response(ids))
+        }
+    }
+
+    fn source() -> Vec<Arc<dyn Source>> {
+        vec![graph_source()]
+    }
+
+    fn graph_source() -> Arc<GraphSource> {
+        Arc::new(GraphSource {
+            // a cites b, b cites c, d cites a, e cites a
+            links: vec![("a", "b"), ("b", "c"), ("d", "a"), ("e", "a")],
+            years: HashMap::from([
+                ("a", "2015"),
+                ("b", "2010"),
+                ("c", "2005"),
+                ("d", "2018"),
+                ("e", "2020"),
+            ]),
+            fetched: std::sync::Mutex::new(Vec::new()),
+        })
+    }
+
+    fn seed() -> Paper {
+        PaperBuilder::new("a", "", "", SourceType::SemanticScholar)
+            .published_date("2015")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_snowball_backward_depth() {
+        let options = SnowballOptions::new()
+            .direction(Direction::Backward)
+            .depth(2);
+        let graph = Snowball::new(source(), options).run(vec![seed()]).await;
+
+        let ids: Vec<&str> = graph.nodes().iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["semantic:a", "semantic:b", "semantic:c"]);
+        assert_eq!(graph.nodes()[2].depth, 2);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.edges()[1].source, "semantic:b");
+        assert_eq!(graph.edges()[1].target, "semantic:c");
+    }
+
+    #[tokio::test]
+    async fn test_snowball_forward_with_filters() {
+        let options = SnowballOptions::new()
+            .direction(Direction::Both)
+            .years(Some(2016), None);
+        let graph = Snowball::new(source(), options).run(vec![seed()]).await;
+
+        // b (2010) is filtered out; seeds are kept regardless
+        let ids: Vec<&str> = graph.nodes().iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["semantic:a", "semantic:d", "semantic:e"]);
+        assert!(graph
+            .edges()
+            .iter()
+            .all(|e| e.target == "semantic:a" && e.source != "semantic:a"));
+    }
+
+    #[tokio::test]
+    async fn test_snowball_node_limit() {
+        let options = SnowballOptions::new().depth(3).max_nodes(2);
+        let graph = Snowball::new(source(), options).run(vec![seed()]).await;
+        assert_eq!(graph.node_count(), 2);
+        assert!(graph.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_snowball_repeated_seeds_fetched_once() {
+        let graph_source = graph_source();
+        let paper = |id: &str| PaperBuilder::new(id, "", "", SourceType::SemanticScholar).build();
+        let options = SnowballOptions::new()
+            .direction(Direction::Backward)
+            .depth(1);
+        let graph = Snowball::new(vec![graph_source.clone()], options)
+            .run(vec![paper("a"), paper("b"), paper("a"), paper("b")])
+            .await;
+
+        assert_eq!(graph.node_count(), 3);
+        let mut fetched = graph_source.fetched.lock().unwrap().clone();
+        fetched.sort();
+        assert_eq!(fetched, vec!["references:a", "references:b"]);
+    }
+
+    #[test]
+    fn test_citation_paper_id() {
+        let arxiv = PaperBuilder::new("1706.03762", "", "", SourceType::Arxiv).build();
+        assert_eq!(
+            citation_paper_id(&arxiv, "semantic").as_deref(),
+            Some("ARXIV:1706.03762")
+        );
+        assert_eq!(citation_paper_id(&arxiv, "openalex"), None);
+
+        let with_doi = PaperBuilder::new("W1", "", "", SourceType::OpenAlex)
+            .doi("https://doi.org/10.1/X")
+            .build();
+        assert_eq!(
+            citation_paper_id(&with_doi, "semantic").as_deref(),
+            Some("DOI:10.1/x")
+        );
+        assert_eq!(
+            citation_paper_id(&with_doi, "openalex").as_deref(),
+            Some("W1")
+        );
+    }
+
+    #[test]
+    fn test_parse_year_range() {
+        assert_eq!(parse_year_range("2020"), Ok((Some(2020), Some(2020))));
+        assert_eq!(parse_year_range("2018-2022"), Ok((Some(2018), Some(2022))));
+        assert_eq!(parse_year_range("2010-"), Ok((Some(2010), None)));
+        assert_eq!(parse_year_range("-2015"), Ok((None, Some(2015))));
+        assert!(parse_year_range("-").is_err());
+        assert!(parse_year_range("twenty").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_seed_placeholders() {
+        let sources: Vec<Arc<dyn Source>> = Vec::new();
+
+        let paper = resolve_seed(&sources, "10.1000/XYZ").await;
+        assert_eq!(paper.paper_id, "DOI:10.1000/xyz");
+        assert_eq!(paper.doi.as_deref(), Some("10.1000/xyz"));
+
+        let paper = resolve_seed(&sources, "arXiv:1706.03762").await;
+        assert_eq!(paper.source, SourceType::Arxiv);
+        assert_eq!(paper.paper_id, "1706.03762");
+
+        let paper = resolve_seed(&sources, "W2741809807").await;
+        assert_eq!(paper.paper_id, "https://openalex.org/W2741809807");
+
+        let paper = resolve_seed(&sources, "649def34f8be52c8b66281af98ae884c09aef38b").await;
+        assert_eq!(paper.source, SourceType::SemanticScholar);
+    }
+}
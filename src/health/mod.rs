@@ -0,0 +1,794 @@
+//! Deep health checks of the research sources.
+//!
+//! [`SourceChecker`] runs a canned query against each source and checks every
+//! capability the source advertises:
+//!
+//! - **search**: the canned query returns papers whose fields look parsed
+//!   (titles and IDs present, plausible years and DOIs, no markup left over)
+//! - **api_key**: a configured API key is accepted
+//! - **doi_lookup**: a known DOI resolves to a paper with that DOI
+//! - **download**: the first search result downloads as a PDF
+//! - **citations**: the first search result has citing papers
+//!
+//! Each check is timed, and checks slower than
+//! [`slow_after`](SourceChecker::slow_after) are reported as warnings. The
+//! resulting [`DoctorReport`] serializes to JSON and renders as JUnit XML, so
+//! that a scheduled run shows upstream API changes as failing tests.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use research_master::health::SourceChecker;
+//! use research_master::sources::SourceRegistry;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let registry = SourceRegistry::new();
+//! let report = SourceChecker::new()
+//!     .check_all(registry.all().cloned().collect())
+//!     .await;
+//! println!("{}", report.summary());
+//! std::fs::write("doctor.xml", report.to_junit()).unwrap();
+//! # }
+//! ```
+
+use std::fmt::Write as _;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Datelike, Utc};
+use futures_util::{stream, StreamExt};
+use serde::Serialize;
+
+use crate::graph::normalize_doi;
+use crate::models::{CitationRequest, DownloadRequest, Paper, SearchQuery};
+use crate::sources::{Source, SourceError};
+use crate::utils::xml_escape;
+
+/// Number of sources checked concurrently
+const CONCURRENCY: usize = 4;
+
+/// Papers asked for by the canned search
+const SEARCH_RESULTS: usize = 3;
+
+/// Smallest file accepted as a downloaded PDF
+const MIN_PDF_BYTES: u64 = 1024;
+
+/// Environment variables holding each source's API key
+pub const API_KEY_VARS: &[(&str, &str)] = &[
+    ("acm", "ACM_API_KEY"),
+    ("base", "BASE_API_KEY"),
+    ("core", "CORE_API_KEY"),
+    ("dimensions", "DIMENSIONS_API_KEY"),
+    ("ieee_xplore", "IEEE_XPLORE_API_KEY"),
+    ("jstor", "JSTOR_API_KEY"),
+    ("mdpi", "MDPI_API_KEY"),
+    ("semantic", "SEMANTIC_SCHOLAR_API_KEY"),
+    ("springer", "SPRINGER_API_KEY"),
+];
+
+/// Known query and DOI a source is checked with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    /// Query that should always find papers
+    pub query: &'static str,
+
+    /// DOI the source should know
+    pub doi: &'static str,
+}
+
+impl Probe {
+    /// Probe for general and computer science sources
+    const DEFAULT: Probe = Probe {
+        query: "deep learning",
+        doi: "10.1038/nature14539",
+    };
+
+    /// The probe for a source, by ID
+    pub fn for_source(source: &str) -> Probe {
+        match source {
+            "pubmed" | "pmc" | "europe_pmc" => Probe {
+                query: "CRISPR gene editing",
+                doi: "10.1126/science.1225829",
+            },
+            "biorxiv" | "medrxiv" => Probe {
+                query: "single-cell RNA sequencing",
+                doi: "10.1101/2020.02.07.937862",
+            },
+            "iacr" => Probe {
+                query: "lattice-based cryptography",
+                ..Self::DEFAULT
+            },
+            "ssrn" => Probe {
+                query: "corporate governance",
+                ..Self::DEFAULT
+            },
+            _ => Self::DEFAULT,
+        }
+    }
+}
+
+/// A check run against a source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// The canned query returns well-formed papers
+    Search,
+    /// The configured API key is accepted
+    ApiKey,
+    /// A known DOI resolves
+    DoiLookup,
+    /// A search result downloads as a PDF
+    Download,
+    /// A search result has citing papers
+    Citations,
+}
+
+impl Check {
+    /// Name used in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::ApiKey => "api_key",
+            Self::DoiLookup => "doi_lookup",
+            Self::Download => "download",
+            Self::Citations => "citations",
+        }
+    }
+}
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Outcome of a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Worked as expected
+    Pass,
+    /// Could not run, e.g. because the search it needs failed
+    Skip,
+    /// Worked, but slowly or with odd results
+    Warn,
+    /// Did not work
+    Fail,
+}
+
+impl CheckStatus {
+    /// Label used in reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Skip => "SKIP",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+/// Result of one check of one source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+    /// What was checked
+    pub check: Check,
+
+    /// How it went
+    pub status: CheckStatus,
+
+    /// How long the check took, in milliseconds
+    pub latency_ms: u64,
+
+    /// What went wrong or looked odd
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl CheckResult {
+    fn new(check: Check, status: CheckStatus, latency: Duration) -> Self {
+        Self {
+            check,
+            status,
+            latency_ms: latency.as_millis().try_into().unwrap_or(u64::MAX),
+            message: None,
+        }
+    }
+
+    fn skipped(check: Check, message: impl Into<String>) -> Self {
+        Self::new(check, CheckStatus::Skip, Duration::ZERO).message(message)
+    }
+
+    fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// Results of every check of one source
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceReport {
+    /// Source ID
+    pub source: String,
+
+    /// Source name
+    pub name: String,
+
+    /// Query the source was checked with
+    pub query: String,
+
+    /// Result of each check, in the order they ran
+    pub checks: Vec<CheckResult>,
+}
+
+impl SourceReport {
+    /// Worst status of the source's checks
+    pub fn status(&self) -> CheckStatus {
+        self.checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(CheckStatus::Skip)
+    }
+
+    /// Total time of the source's checks
+    pub fn latency_ms(&self) -> u64 {
+        self.checks.iter().map(|check| check.latency_ms).sum()
+    }
+}
+
+/// Results of checking a set of sources
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    /// When the checks started
+    pub checked_at: DateTime<Utc>,
+
+    /// One report per source, ordered by source ID
+    pub sources: Vec<SourceReport>,
+}
+
+impl DoctorReport {
+    fn count(&self, status: CheckStatus) -> usize {
+        self.sources
+            .iter()
+            .flat_map(|source| &source.checks)
+            .filter(|check| check.status == status)
+            .count()
+    }
+
+    /// Number of checks run
+    pub fn total(&self) -> usize {
+        self.sources.iter().map(|source| source.checks.len()).sum()
+    }
+
+    /// Number of failed checks
+    pub fn failures(&self) -> usize {
+        self.count(CheckStatus::Fail)
+    }
+
+    /// Whether no check failed
+    pub fn is_healthy(&self) -> bool {
+        self.failures() == 0
+    }
+
+    /// One-line summary, e.g. "12 sources, 40 checks: 35 passed, 2 warnings, 1 failed, 2 skipped"
+    pub fn summary(&self) -> String {
+        format!(
+            "{} sources, {} checks: {} passed, {} warnings, {} failed, {} skipped",
+            self.sources.len(),
+            self.total(),
+            self.count(CheckStatus::Pass),
+            self.count(CheckStatus::Warn),
+            self.failures(),
+            self.count(CheckStatus::Skip)
+        )
+    }
+
+    /// The report as JUnit XML: a test suite per source and a test case per check
+    ///
+    /// Failed checks become failures and skipped checks are skipped; warnings
+    /// pass, with their message in `system-out`.
+    pub fn to_junit(&self) -> String {
+        let seconds = |ms: u64| format!("{:.3}", ms as f64 / 1000.0);
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            out,
+            "<testsuites name=\"research-master doctor\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\" timestamp=\"{}\">",
+            self.total(),
+            self.failures(),
+            self.count(CheckStatus::Skip),
+            seconds(self.sources.iter().map(SourceReport::latency_ms).sum()),
+            self.checked_at.to_rfc3339()
+        );
+        for source in &self.sources {
+            let count = |status| {
+                source
+                    .checks
+                    .iter()
+                    .filter(|check| check.status == status)
+                    .count()
+            };
+            let _ = writeln!(
+                out,
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+                xml_escape(&source.source),
+                source.checks.len(),
+                count(CheckStatus::Fail),
+                count(CheckStatus::Skip),
+                seconds(source.latency_ms())
+            );
+            for check in &source.checks {
+                let _ = write!(
+                    out,
+                    "    <testcase classname=\"research-master.{}\" name=\"{}\" time=\"{}\"",
+                    xml_escape(&source.source),
+                    check.check,
+                    seconds(check.latency_ms)
+                );
+                let message = xml_escape(check.message.as_deref().unwrap_or_default());
+                match check.status {
+                    CheckStatus::Pass => out.push_str("/>\n"),
+                    CheckStatus::Fail => {
+                        let _ = writeln!(
+                            out,
+                            ">\n      <failure message=\"{}\"/>\n    </testcase>",
+                            message
+                        );
+                    }
+                    CheckStatus::Skip => {
+                        let _ = writeln!(
+                            out,
+                            ">\n      <skipped message=\"{}\"/>\n    </testcase>",
+                            message
+                        );
+                    }
+                    CheckStatus::Warn => {
+                        let _ = writeln!(
+                            out,
+                            ">\n      <system-out>{}</system-out>\n    </testcase>",
+                            message
+                        );
+                    }
+                }
+            }
+            out.push_str("  </testsuite>\n");
+        }
+        out.push_str("</testsuites>\n");
+        out
+    }
+}
+
+/// Runs the deep checks against sources
+#[derive(Debug, Clone)]
+pub struct SourceChecker {
+    /// Time each check may take before it fails
+    timeout: Duration,
+
+    /// Time after which a passing check is reported as slow
+    slow_after: Duration,
+}
+
+impl Default for SourceChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SourceChecker {
+    /// Create a checker with a 30 s timeout, warning about checks over 10 s
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            slow_after: Duration::from_secs(10),
+        }
+    }
+
+    /// Set the time each check may take before it fails
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the time after which a passing check is reported as slow
+    pub fn slow_after(mut self, slow_after: Duration) -> Self {
+        self.slow_after = slow_after;
+        self
+    }
+
+    /// Check several sources concurrently
+    pub async fn check_all(&self, sources: Vec<Arc<dyn Source>>) -> DoctorReport {
+        let checked_at = Utc::now();
+        let mut reports: Vec<SourceReport> = stream::iter(sources)
+            .map(|source| async move { self.check(source.as_ref()).await })
+            .buffer_unordered(CONCURRENCY)
+            .collect()
+            .await;
+        reports.sort_by(|a, b| a.source.cmp(&b.source));
+        DoctorReport {
+            checked_at,
+            sources: reports,
+        }
+    }
+
+    /// Run every check that applies to a source
+    pub async fn check(&self, source: &dyn Source) -> SourceReport {
+        let probe = Probe::for_source(source.id());
+        let mut checks = Vec::new();
+
+        let query = SearchQuery::new(probe.query).max_results(SEARCH_RESULTS);
+        let (search, papers) = match self.timed(source.search(&query)).await {
+            (Ok(response), elapsed) => {
+                let result = self.judge_papers(&response.papers, elapsed);
+                (result, response.papers)
+            }
+            (Err(e), elapsed) => (
+                CheckResult::new(Check::Search, CheckStatus::Fail, elapsed).message(e.to_string()),
+                Vec::new(),
+            ),
+        };
+        if source.supports_search() {
+            checks.push(search.clone());
+        }
+
+        if let Some(var) = configured_api_key(source.id()) {
+            checks.push(api_key_check(var, &search));
+        }
+
+        if source.supports_doi_lookup() {
+            checks.push(self.check_doi_lookup(source, probe.doi).await);
+        }
+
+        let first = papers.first();
+        if source.supports_download() {
+            checks.push(match first {
+                Some(paper) => self.check_download(source, paper).await,
+                None => CheckResult::skipped(Check::Download, "no search result to download"),
+            });
+        }
+        if source.supports_citations() {
+            checks.push(match first {
+                Some(paper) => self.check_citations(source, paper).await,
+                None => CheckResult::skipped(Check::Citations, "no search result to look up"),
+            });
+        }
+
+        SourceReport {
+            source: source.id().to_string(),
+            name: source.name().to_string(),
+            query: probe.query.to_string(),
+            checks,
+        }
+    }
+
+    /// Run a call with the timeout, measuring how long it took
+    async fn timed<T>(
+        &self,
+        call: impl Future<Output = Result<T, SourceError>>,
+    ) -> (Result<T, SourceError>, Duration) {
+        let started = Instant::now();
+        let result = match tokio::time::timeout(self.timeout, call).await {
+            Ok(result) => result,
+            Err(_) => Err(SourceError::Network(format!(
+                "timed out after {} s",
+                self.timeout.as_secs()
+            ))),
+        };
+        (result, started.elapsed())
+    }
+
+    /// Result of a check that worked, flagged if it was slow
+    fn passed(&self, check: Check, elapsed: Duration) -> CheckResult {
+        let result = CheckResult::new(check, CheckStatus::Pass, elapsed);
+        if elapsed > self.slow_after {
+            let result = CheckResult {
+                status: CheckStatus::Warn,
+                ..result
+            };
+            return result.message(format!("slow: {:.1} s", elapsed.as_secs_f64()));
+        }
+        result
+    }
+
+    /// Judge the papers returned by the canned search
+    fn judge_papers(&self, papers: &[Paper], elapsed: Duration) -> CheckResult {
+        if papers.is_empty() {
+            return CheckResult::new(Check::Search, CheckStatus::Fail, elapsed)
+                .message("the canned query returned no papers");
+        }
+        let mut issues: Vec<String> = Vec::new();
+        for issue in papers.iter().flat_map(paper_issues) {
+            if !issues.contains(&issue) {
+                issues.push(issue);
+            }
+        }
+        if papers.iter().all(|paper| paper.title.trim().is_empty()) {
+            return CheckResult::new(Check::Search, CheckStatus::Fail, elapsed)
+                .message(issues.join("; "));
+        }
+        if !issues.is_empty() {
+            return CheckResult::new(Check::Search, CheckStatus::Warn, elapsed)
+                .message(issues.join("; "));
+        }
+        self.passed(Check::Search, elapsed)
+    }
+
+    async fn check_doi_lookup(&self, source: &dyn Source, doi: &str) -> CheckResult {
+        let (result, elapsed) = self.timed(source.get_by_doi(doi)).await;
+        match result {
+            Ok(paper) if paper.title.trim().is_empty() => {
+                CheckResult::new(Check::DoiLookup, CheckStatus::Fail, elapsed)
+                    .message(format!("{} resolved to a paper without a title", doi))
+            }
+            Ok(paper) if paper.doi.as_deref().and_then(normalize_doi) != normalize_doi(doi) => {
+                CheckResult::new(Check::DoiLookup, CheckStatus::Warn, elapsed).message(format!(
+                    "{} resolved to a paper with DOI {}",
+                    doi,
+                    paper.doi.as_deref().unwrap_or("(none)")
+                ))
+            }
+            Ok(_) => self.passed(Check::DoiLookup, elapsed),
+            Err(e) => CheckResult::new(Check::DoiLookup, CheckStatus::Fail, elapsed)
+                .message(e.to_string()),
+        }
+    }
+
+    async fn check_download(&self, source: &dyn Source, paper: &Paper) -> CheckResult {
+        let dir = match tempfile::tempdir() {
+            Ok(dir) => dir,
+            Err(e) => return CheckResult::skipped(Check::Download, e.to_string()),
+        };
+        let mut request = DownloadRequest::new(&paper.paper_id, dir.path().to_string_lossy());
+        if let Some(ref doi) = paper.doi {
+            request = request.doi(doi);
+        }
+        let (result, elapsed) = self.timed(source.download(&request)).await;
+        match result {
+            Ok(download) if !download.success => {
+                CheckResult::new(Check::Download, CheckStatus::Fail, elapsed).message(
+                    download
+                        .error
+                        .unwrap_or_else(|| "download failed".to_string()),
+                )
+            }
+            Ok(download) => match pdf_issue(Path::new(&download.path), download.bytes) {
+                Some(issue) => CheckResult::new(Check::Download, CheckStatus::Fail, elapsed)
+                    .message(format!("{}: {}", paper.paper_id, issue)),
+                None => self.passed(Check::Download, elapsed),
+            },
+            Err(e) => CheckResult::new(Check::Download, CheckStatus::Fail, elapsed)
+                .message(format!("{}: {}", paper.paper_id, e)),
+        }
+    }
+
+    async fn check_citations(&self, source: &dyn Source, paper: &Paper) -> CheckResult {
+        let request = CitationRequest::new(&paper.paper_id).max_results(SEARCH_RESULTS);
+        let (result, elapsed) = self.timed(source.get_citations(&request)).await;
+        match result {
+            Ok(response) if response.papers.is_empty() => {
+                CheckResult::new(Check::Citations, CheckStatus::Warn, elapsed)
+                    .message(format!("no citing papers for {}", paper.paper_id))
+            }
+            Ok(_) => self.passed(Check::Citations, elapsed),
+            Err(e) => CheckResult::new(Check::Citations, CheckStatus::Fail, elapsed)
+                .message(format!("{}: {}", paper.paper_id, e)),
+        }
+    }
+}
+
+/// Environment variable holding the source's API key, if one is configured
+fn configured_api_key(source: &str) -> Option<&'static str> {
+    let (_, var) = API_KEY_VARS.iter().find(|(id, _)| *id == source)?;
+    std::env::var(var).ok().filter(|key| !key.is_empty())?;
+    Some(var)
+}
+
+/// Whether the API key in `var` was accepted, judged from the search
+fn api_key_check(var: &str, search: &CheckResult) -> CheckResult {
+    let latency = Duration::from_millis(search.latency_ms);
+    let message = search.message.as_deref().unwrap_or_default();
+    let lower = message.to_lowercase();
+    let rejected = [
+        "401",
+        "403",
+        "unauthorized",
+        "forbidden",
+        "api key",
+        "apikey",
+    ]
+    .iter()
+    .any(|sign| lower.contains(sign));
+    match search.status {
+        CheckStatus::Fail if rejected => {
+            CheckResult::new(Check::ApiKey, CheckStatus::Fail, latency)
+                .message(format!("{} was rejected: {}", var, message))
+        }
+        CheckStatus::Fail | CheckStatus::Skip => CheckResult::skipped(
+            Check::ApiKey,
+            format!("could not verify {}: search failed", var),
+        ),
+        CheckStatus::Pass | CheckStatus::Warn => {
+            CheckResult::new(Check::ApiKey, CheckStatus::Pass, latency)
+        }
+    }
+}
+
+/// What looks wrongly parsed in a paper
+fn paper_issues(paper: &Paper) -> Vec<String> {
+    let mut issues = Vec::new();
+    let title = paper.title.trim();
+    if title.is_empty() {
+        issues.push("paper without a title".to_string());
+    } else if has_markup(title) {
+        issues.push(format!("markup left in title \"{}\"", title));
+    }
+    if paper.paper_id.trim().is_empty() {
+        issues.push(format!("no ID for \"{}\"", title));
+    }
+    if let Some(ref date) = paper.published_date {
+        let year = date.get(..4).and_then(|year| year.parse::<i32>().ok());
+        let latest = Utc::now().year() + 1;
+        if !year.is_some_and(|year| (1600..=latest).contains(&year)) {
+            issues.push(format!("implausible date \"{}\"", date));
+        }
+    }
+    if let Some(ref doi) = paper.doi {
+        if normalize_doi(doi).is_none_or(|doi| !doi.starts_with("10.")) {
+            issues.push(format!("malformed DOI \"{}\"", doi));
+        }
+    }
+    issues
+}
+
+/// Whether text still has HTML tags or entities in it
+fn has_markup(text: &str) -> bool {
+    let tag = text.find('<').is_some_and(|start| {
+        text[start + 1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '/')
+            && text[start..].contains('>')
+    });
+    let entity = ["&amp;", "&lt;", "&gt;", "&quot;", "&#"]
+        .iter()
+        .any(|entity| text.contains(entity));
+    tag || entity
+}
+
+/// What is wrong with a downloaded PDF, if anything
+fn pdf_issue(path: &Path, bytes: u64) -> Option<String> {
+    if bytes < MIN_PDF_BYTES {
+        return Some(format!("downloaded only {} bytes", bytes));
+    }
+    match std::fs::read(path) {
+        Ok(content) if content.starts_with(b"%PDF") => None,
+        Ok(_) => Some("downloaded file is not a PDF".to_string()),
+        Err(e) => Some(format!("cannot read {}: {}", path.display(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SearchResponse, SourceType};
+    use crate::sources::mock::make_paper;
+    use crate::sources::MockSource;
+
+    #[test]
+    fn test_paper_issues() {
+        let mut paper = make_paper("1", "Deep learning", SourceType::Arxiv);
+        paper.published_date = Some("2015-05-27".to_string());
+        paper.doi = Some("10.1038/nature14539".to_string());
+        assert!(paper_issues(&paper).is_empty());
+
+        paper.title = "Deep <i>learning</i> &amp; more".to_string();
+        paper.published_date = Some("0000-01-01".to_string());
+        paper.doi = Some("nature14539".to_string());
+        let issues = paper_issues(&paper);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].starts_with("markup left in title"));
+        assert!(issues[1].starts_with("implausible date"));
+        assert!(issues[2].starts_with("malformed DOI"));
+
+        assert!(!has_markup("x < y and y > z"));
+    }
+
+    #[tokio::test]
+    async fn test_check_search() {
+        let source = MockSource::new();
+        let checker = SourceChecker::new();
+
+        let report = checker.check(&source).await;
+        assert_eq!(report.source, "mock");
+        assert_eq!(report.checks.len(), 1);
+        assert_eq!(report.checks[0].check, Check::Search);
+        assert_eq!(report.checks[0].status, CheckStatus::Fail);
+
+        let papers = vec![make_paper("1", "Deep learning", SourceType::Arxiv)];
+        source.set_search_response(SearchResponse::new(papers, "Mock Source", "deep learning"));
+        let report = checker.check(&source).await;
+        assert_eq!(report.status(), CheckStatus::Pass);
+
+        source.set_search_response(SearchResponse::new(
+            vec![make_paper("1", "Deep learning", SourceType::Arxiv)],
+            "Mock Source",
+            "deep learning",
+        ));
+        let report = SourceChecker::new()
+            .slow_after(Duration::ZERO)
+            .check(&source)
+            .await;
+        assert_eq!(report.status(), CheckStatus::Warn);
+        assert!(report.checks[0]
+            .message
+            .as_deref()
+            .unwrap()
+            .starts_with("slow"));
+    }
+
+    #[test]
+    fn test_api_key_check() {
+        let failed = CheckResult::new(Check::Search, CheckStatus::Fail, Duration::ZERO)
+            .message("API error: HTTP 403 Forbidden");
+        let passed = CheckResult::new(Check::Search, CheckStatus::Pass, Duration::ZERO);
+
+        assert_eq!(configured_api_key("arxiv"), None);
+        assert_eq!(
+            api_key_check("DIMENSIONS_API_KEY", &passed).status,
+            CheckStatus::Pass
+        );
+        let rejected = api_key_check("DIMENSIONS_API_KEY", &failed);
+        assert_eq!(rejected.status, CheckStatus::Fail);
+        assert!(rejected
+            .message
+            .unwrap()
+            .starts_with("DIMENSIONS_API_KEY was rejected"));
+
+        let unverified = CheckResult::new(Check::Search, CheckStatus::Fail, Duration::ZERO)
+            .message("Network error: connection refused");
+        assert_eq!(
+            api_key_check("DIMENSIONS_API_KEY", &unverified).status,
+            CheckStatus::Skip
+        );
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let report = DoctorReport {
+            checked_at: Utc::now(),
+            sources: vec![SourceReport {
+                source: "arxiv".to_string(),
+                name: "arXiv".to_string(),
+                query: "deep learning".to_string(),
+                checks: vec![
+                    CheckResult::new(Check::Search, CheckStatus::Pass, Duration::from_millis(420)),
+                    CheckResult::new(Check::Download, CheckStatus::Fail, Duration::from_secs(2))
+                        .message("HTTP 503 <Service Unavailable>"),
+                    CheckResult::skipped(Check::Citations, "no search result to look up"),
+                ],
+            }],
+        };
+        assert!(!report.is_healthy());
+        assert_eq!(
+            report.summary(),
+            "1 sources, 3 checks: 1 passed, 0 warnings, 1 failed, 1 skipped"
+        );
+
+        let xml = report.to_junit();
+        assert!(xml.contains(r#"<testsuites name="research-master doctor" tests="3" failures="1" skipped="1" time="2.420""#));
+        assert!(xml.contains(
+            r#"<testsuite name="arxiv" tests="3" failures="1" skipped="1" time="2.420">"#
+        ));
+        assert!(xml.contains(
+            r#"<testcase classname="research-master.arxiv" name="search" time="0.420"/>"#
+        ));
+        assert!(xml.contains(r#"<failure message="HTTP 503 &lt;Service Unavailable&gt;"/>"#));
+        assert!(xml.contains(r#"<skipped message="no search result to look up"/>"#));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["sources"][0]["checks"][1]["status"], "fail");
+        assert_eq!(json["sources"][0]["checks"][1]["check"], "download");
+    }
+}
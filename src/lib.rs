@@ -12,13 +12,35 @@
 //! - [`mcp`]: MCP protocol implementation and server
 //! - [`utils`]: HTTP client, deduplication, and other utilities
 //! - [`config`]: Configuration management
+//! - [`graph`]: Citation graph snowballing and graph export
+//! - [`authors`]: Author profiles aggregated across sources
+//! - [`watch`]: Saved searches with digests of new papers
+//! - [`formats`]: BibTeX, RIS, CSL-JSON and CSV import/export
+//! - [`enrich`]: Filling in missing DOIs, abstracts, citation counts and PDF URLs
+//! - [`csl`]: Citation Style Language styles, locales and citation rendering
+//! - [`bib`]: Managed BibTeX files with stable cite keys, checked against the sources
+//! - [`retraction`]: Retraction, expression of concern and correction checks
+//! - [`open_access`]: Open-access status, licence and version of papers
+//! - [`versions`]: Preprint and published versions of the same work
+//! - [`health`]: Deep per-source health checks for `doctor --deep`
 
+pub mod authors;
+pub mod bib;
 pub mod config;
+pub mod csl;
+pub mod enrich;
+pub mod formats;
+pub mod graph;
+pub mod health;
 pub mod mcp;
 pub mod models;
+pub mod open_access;
+pub mod retraction;
 pub mod sources;
 pub mod ui;
 pub mod utils;
+pub mod versions;
+pub mod watch;
 
 // Re-export commonly used types
 pub use models::Paper;
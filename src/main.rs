@@ -2,17 +2,35 @@ use anyhow::Result;
 use clap::{Parser, Subcommand, ValueEnum};
 use clap_complete::shells::{Bash, Elvish, Fish, PowerShell, Zsh};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use research_master::config::{find_config_file, get_config, load_config};
+use research_master::authors::{AuthorOptions, AuthorResolver};
+use research_master::bib::manuscript::{self, KeyStatus, ManuscriptReport};
+use research_master::bib::{AddReport, AddStatus, BibManager, CheckReport};
+use research_master::config::{find_config_file, get_config, load_config, OFFLINE_ENV_VAR};
+use research_master::csl::{Item, Locale, OutputFormat as CslOutputFormat, Processor, Style};
+use research_master::enrich::{EnrichField, EnrichOptions, EnrichReport, EnrichStatus, Enricher};
+use research_master::formats::{self, BibFile, ImportFormat, KeyPattern};
+use research_master::graph::{
+    parse_year_range, resolve_seed, Coupling, CouplingOptions, CouplingReport, Direction,
+    GraphFormat, Snowball, SnowballOptions,
+};
+use research_master::health::{CheckStatus, DoctorReport, SourceChecker};
+use research_master::mcp::serve_monitoring;
 use research_master::mcp::server::McpServer;
+use research_master::mcp::ClientQuota;
 use research_master::models::{
-    CitationRequest, DownloadRequest, ReadRequest, SearchQuery, SortBy, SortOrder,
+    AuthorLookup, CitationRequest, DownloadRequest, ReadRequest, SearchQuery, SortBy, SortOrder,
 };
+use research_master::open_access::{OpenAccessFilter, OpenAccessResolver};
+use research_master::retraction::{RetractionChecker, RetractionReport, RetractionWatch};
 use research_master::sources::{SourceCapabilities, SourceRegistry};
 use research_master::utils::{
-    apply_cli_proxy_args, deduplicate_papers, find_duplicates, format_authors, format_source,
-    format_title, format_year, get_paper_table_columns, is_terminal, terminal_width, CacheService,
-    DuplicateStrategy, HistoryService,
+    apply_cli_proxy_args, deduplicate_papers, find_duplicates, format_age, format_authors,
+    format_source, format_title, format_year, get_paper_table_columns, is_terminal, terminal_width,
+    CacheFilter, CacheNamespace, CacheResult, CacheService, DuplicateStrategy, EntryInfo,
+    HistoryService, HostLimiter, HttpCache, HTTP_RECORD_ENV_VAR, HTTP_REPLAY_ENV_VAR,
 };
+use research_master::versions::{group_versions, version_kind, VersionPreference};
+use research_master::watch::{Follow, SavedSearch, WatchReport, WatchStore, Watcher};
 use std::io::IsTerminal;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -38,6 +56,9 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
     # Search by author
     research-master author \"Yoshua Bengio\" --max-results 10
 
+    # Author profile merged from OpenAlex, Semantic Scholar, DBLP and ORCID
+    research-master author-profile 0000-0002-1825-0097
+
     # Download a paper by arXiv ID
     research-master download 2310.12345 --source arxiv --output ./papers/
 
@@ -53,6 +74,14 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
     # Get related papers
     research-master related 2310.12345 --source arxiv
 
+    # Format a bibliography with any CSL style
+    research-master cite --input refs.bib --style-file ieee.csl --locale-file locales-de-DE.xml
+
+    # Save a search and report papers that are new since the last run
+    research-master watch add llm-agents \"large language model agents\" --sources arxiv,semantic
+    research-master watch follow-paper attention 10.48550/arXiv.1706.03762
+    research-master watch run --markdown digest.md --atom feed.xml
+
     # List all available sources
     research-master sources
 
@@ -70,6 +99,19 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
     research-master export --input papers.json --format json -O output.json
     research-master export --input papers.json --format ris -O output.ris
 
+    # Import a BibTeX or RIS file and fill in missing DOIs, abstracts and PDF links
+    research-master import library.ris --enrich --format bibtex -O references.bib
+
+    # Add papers to a managed BibTeX file and check its entries
+    research-master bib add 10.1038/nature14539 1706.03762 --file references.bib
+    research-master bib check --file references.bib
+
+    # Add the DOIs and arXiv IDs a manuscript cites to its bibliography
+    research-master manuscript paper.tex paper.aux --bib references.bib
+
+    # Find retracted, corrected and flagged papers in a bibliography
+    research-master check-retractions references.bib --database retraction_watch.csv
+
     # Bulk download from a file of paper IDs
     research-master bulk-download ./paper_ids.txt -o ./downloads/
 
@@ -108,10 +150,30 @@ struct Cli {
     #[arg(long, global = true)]
     env: bool,
 
-    /// Disable caching for this command (useful for testing fresh results)
+    /// Disable caching for this command, including the HTTP response cache
+    /// (useful for testing fresh results)
     #[arg(long, global = true, default_value_t = false)]
     no_cache: bool,
 
+    /// Work without network access: answer from the cache, cached papers and
+    /// downloaded PDFs, and fail fast when the network is needed
+    #[arg(long, global = true, conflicts_with = "no_cache")]
+    offline: bool,
+
+    /// Save every HTTP request and response to fixture files in DIR
+    /// (API keys are scrubbed)
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        conflicts_with = "http_replay"
+    )]
+    http_record: Option<PathBuf>,
+
+    /// Answer HTTP requests from fixture files in DIR, without the network
+    #[arg(long, global = true, value_name = "DIR")]
+    http_replay: Option<PathBuf>,
+
     /// Log to a file instead of stderr
     #[arg(long, global = true, value_name = "FILE")]
     log_file: Option<PathBuf>,
@@ -202,6 +264,8 @@ enum Source {
     Base,
     #[value(name = "springer")]
     Springer,
+    #[value(name = "orcid")]
+    Orcid,
     #[value(name = "google_scholar")]
     GoogleScholar,
     #[value(name = "all")]
@@ -243,6 +307,24 @@ enum DedupStrategy {
     Mark,
 }
 
+/// Which version of a work search results show
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum VersionChoice {
+    /// The published version, with its preprint listed under it
+    Published,
+    /// The preprint, with its published version listed under it
+    Preprint,
+}
+
+impl From<VersionChoice> for VersionPreference {
+    fn from(choice: VersionChoice) -> Self {
+        match choice {
+            VersionChoice::Published => VersionPreference::Published,
+            VersionChoice::Preprint => VersionPreference::Preprint,
+        }
+    }
+}
+
 /// Shell for completion generation
 #[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(clippy::enum_variant_names)]
@@ -264,14 +346,68 @@ enum Shell {
 enum ExportFormat {
     /// BibTeX format for citation managers
     Bibtex,
+    /// CSL-JSON (citeproc, Zotero, Pandoc)
+    CslJson,
     /// CSV spreadsheet format
     Csv,
+    /// EndNote XML
+    EndnoteXml,
     /// JSON format
     Json,
+    /// JSON Lines, one paper per line
+    Jsonl,
+    /// MODS XML for library systems
+    Mods,
+    /// Markdown notes with YAML front matter, one file per paper in the output directory
+    #[value(alias = "obsidian")]
+    Notes,
     /// RIS format (EndNote, Zotero)
     Ris,
 }
 
+/// Format of a file to import
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ImportFileFormat {
+    /// BibTeX/BibLaTeX
+    Bibtex,
+    /// RIS (EndNote, Zotero, Mendeley)
+    Ris,
+    /// CSL-JSON (Zotero, Pandoc)
+    CslJson,
+    /// JSON papers written by research-master
+    Json,
+}
+
+/// Metadata field filled in by enrichment
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum MetadataField {
+    /// Digital Object Identifier
+    Doi,
+    /// Abstract text
+    Abstract,
+    /// Citation count
+    Citations,
+    /// Open-access PDF URL
+    PdfUrl,
+    /// Journal or conference name
+    Venue,
+    /// Keywords
+    Keywords,
+}
+
+impl From<MetadataField> for EnrichField {
+    fn from(field: MetadataField) -> Self {
+        match field {
+            MetadataField::Doi => EnrichField::Doi,
+            MetadataField::Abstract => EnrichField::Abstract,
+            MetadataField::Citations => EnrichField::Citations,
+            MetadataField::PdfUrl => EnrichField::PdfUrl,
+            MetadataField::Venue => EnrichField::Venue,
+            MetadataField::Keywords => EnrichField::Keywords,
+        }
+    }
+}
+
 /// Config action
 #[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
 enum ConfigAction {
@@ -338,9 +474,31 @@ enum Commands {
         #[arg(long, value_enum, requires = "dedup")]
         dedup_strategy: Option<DedupStrategy>,
 
+        /// Group preprints with their published versions, showing this one
+        /// (default with --dedup: published)
+        #[arg(long, value_enum)]
+        versions: Option<VersionChoice>,
+
         /// Fetch detailed information (slower but more complete)
         #[arg(long, default_value_t = true)]
         fetch_details: bool,
+
+        /// Look up open-access status, licence and version (OpenAlex, Unpaywall)
+        #[arg(long)]
+        open_access: bool,
+
+        /// Keep only papers that are free to read (implies --open-access)
+        #[arg(long)]
+        open_access_only: bool,
+
+        /// Keep only papers under one of these licences, e.g. cc-by (implies --open-access)
+        #[arg(long, value_delimiter = ',')]
+        license: Vec<String>,
+
+        /// Fill in missing fields from other sources after deduplication
+        /// (default: all fields; sources and time budget come from the [enrich] config)
+        #[arg(long, value_enum, value_delimiter = ',', num_args = 0..=1, value_name = "FIELDS")]
+        enrich: Option<Vec<MetadataField>>,
     },
 
     /// Search for papers by author
@@ -370,6 +528,25 @@ enum Commands {
         dedup_strategy: Option<DedupStrategy>,
     },
 
+    /// Show an author profile merged across OpenAlex, Semantic Scholar, DBLP and ORCID
+    #[command(alias = "profile")]
+    AuthorProfile {
+        /// Author name, ORCID iD, or a candidate ID from an ambiguous lookup
+        query: String,
+
+        /// Maximum publications per source and in the profile
+        #[arg(long, short, default_value_t = 20)]
+        max_papers: usize,
+
+        /// Number of top venues and co-authors to list
+        #[arg(long, short, default_value_t = 10)]
+        top: usize,
+
+        /// Author profile sources to use, in order of preference
+        #[arg(long, value_enum, value_delimiter = ',')]
+        sources: Vec<Source>,
+    },
+
     /// Download a paper's PDF
     #[command(alias = "d")]
     Download {
@@ -454,6 +631,20 @@ enum Commands {
         max_results: usize,
     },
 
+    /// Build and export citation graphs
+    Graph {
+        /// Subcommand
+        #[command(subcommand)]
+        command: GraphCommands,
+    },
+
+    /// Saved searches with digests of papers new since the last run
+    Watch {
+        /// Subcommand
+        #[command(subcommand)]
+        command: WatchCommands,
+    },
+
     /// Get related/similar papers
     #[command(alias = "rel")]
     Related {
@@ -514,6 +705,26 @@ enum Commands {
         /// Host to bind to for SSE mode
         #[arg(long, default_value = "127.0.0.1")]
         host: String,
+
+        /// Per-client requests per minute in HTTP mode (0 = unlimited)
+        #[arg(long)]
+        client_rpm: Option<u32>,
+
+        /// Per-client concurrent tool calls in HTTP mode (0 = unlimited)
+        #[arg(long)]
+        client_concurrency: Option<usize>,
+
+        /// Per-client paper downloads per day in HTTP mode (0 = unlimited)
+        #[arg(long)]
+        client_daily_downloads: Option<u32>,
+
+        /// Reverse proxy whose X-Forwarded-For/X-Real-IP identify clients (repeatable)
+        #[arg(long = "trusted-proxy", value_name = "IP", value_delimiter = ',')]
+        trusted_proxies: Vec<std::net::IpAddr>,
+
+        /// Serve /metrics (Prometheus) and /healthz on this port in HTTP mode
+        #[arg(long)]
+        metrics_port: Option<u16>,
     },
 
     /// Deduplicate a JSON file containing papers
@@ -556,6 +767,22 @@ enum Commands {
         /// Verbose output
         #[arg(long, short)]
         verbose: bool,
+
+        /// Run a canned query against each source and check every capability it advertises
+        #[arg(long)]
+        deep: bool,
+
+        /// Only check these sources (with --deep)
+        #[arg(long, value_delimiter = ',', requires = "deep")]
+        source: Vec<String>,
+
+        /// Also write the deep check results as JUnit XML to this file
+        #[arg(long, value_name = "FILE", requires = "deep")]
+        junit: Option<PathBuf>,
+
+        /// Report checks slower than this many seconds as warnings (with --deep)
+        #[arg(long, value_name = "SECS", default_value_t = 10)]
+        slow: u64,
     },
 
     /// Update to the latest version
@@ -588,8 +815,8 @@ enum Commands {
         format: ExportFormat,
 
         /// Output file (stdout if not specified)
-        #[arg(short, long, short = 'O')]
-        output: Option<PathBuf>,
+        #[arg(long, short = 'O')]
+        output_file: Option<PathBuf>,
 
         /// Source to search if no input file provided
         #[arg(long, value_enum)]
@@ -604,6 +831,84 @@ enum Commands {
         max_results: usize,
     },
 
+    /// Import BibTeX, RIS or CSL-JSON files, optionally filling in missing metadata
+    Import {
+        /// Files to import (format detected from extension and content)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Input format (default: detect)
+        #[arg(long, value_enum)]
+        from: Option<ImportFileFormat>,
+
+        /// Look entries up across sources and fill in missing DOIs, abstracts,
+        /// citation counts, open-access PDF URLs, venues and keywords
+        #[arg(long)]
+        enrich: bool,
+
+        /// Fields to fill in with --enrich (default: all)
+        #[arg(long, value_enum, value_delimiter = ',', requires = "enrich")]
+        fields: Vec<MetadataField>,
+
+        /// Sources used by --enrich, in order of preference
+        #[arg(long, value_enum, value_delimiter = ',', requires = "enrich")]
+        sources: Vec<Source>,
+
+        /// Output format
+        #[arg(long, short, value_enum, default_value_t = ExportFormat::Bibtex)]
+        format: ExportFormat,
+
+        /// Output file (stdout if not specified)
+        #[arg(long, short = 'O')]
+        output_file: Option<PathBuf>,
+
+        /// Write the enrichment report as JSON to this file
+        #[arg(long, value_name = "FILE", requires = "enrich")]
+        report: Option<PathBuf>,
+    },
+
+    /// Keep a BibTeX file in sync: add papers with stable cite keys, check entries
+    Bib {
+        /// Subcommand
+        #[command(subcommand)]
+        command: BibCommands,
+    },
+
+    /// Sync the keys cited in LaTeX or Markdown manuscripts into a BibTeX file
+    Manuscript {
+        /// Manuscript files (.tex, .aux, or Pandoc Markdown)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// BibTeX file to update (created if missing)
+        #[arg(long, short, default_value = "references.bib")]
+        bib: PathBuf,
+
+        /// Report what would change without writing the file
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip checking cited papers for retractions and published versions
+        #[arg(long)]
+        no_check: bool,
+    },
+
+    /// Check the papers of a bibliography or JSON file for retractions,
+    /// expressions of concern and corrections
+    CheckRetractions {
+        /// Files to check (BibTeX, RIS, CSL-JSON or JSON search results)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Input format (default: detect)
+        #[arg(long, value_enum)]
+        from: Option<ImportFileFormat>,
+
+        /// Retraction Watch CSV file (default: retractions.retraction_watch in config)
+        #[arg(long, value_name = "CSV")]
+        database: Option<PathBuf>,
+    },
+
     /// Download multiple papers from a file
     #[command(alias = "bulk-dl")]
     BulkDownload {
@@ -688,12 +993,25 @@ enum Commands {
     /// Format a paper citation in various styles
     Cite {
         /// Paper ID (arXiv ID, DOI, PMC ID, etc.)
-        paper_id: String,
+        #[arg(required_unless_present = "input")]
+        paper_id: Option<String>,
 
         /// Citation style
         #[arg(long, value_enum, default_value_t = CitationStyle::Apa)]
         style: CitationStyle,
 
+        /// CSL style file (.csl) to format with instead of a built-in style
+        #[arg(long, value_name = "FILE")]
+        style_file: Option<PathBuf>,
+
+        /// CSL locale file for terms and date formats (default: built-in en-US)
+        #[arg(long, value_name = "FILE", requires = "style_file")]
+        locale_file: Option<PathBuf>,
+
+        /// Format every entry of a BibTeX, RIS, CSL-JSON or JSON file instead of a paper ID
+        #[arg(long, short, value_name = "FILE", conflicts_with = "paper_id")]
+        input: Option<PathBuf>,
+
         /// Source of the paper (auto-detected if not specified)
         #[arg(long, value_enum)]
         source: Option<Source>,
@@ -722,6 +1040,8 @@ enum CitationStyle {
 enum CitationOutputFormat {
     /// Plain text (default)
     Text,
+    /// HTML markup (with --style-file)
+    Html,
     /// BibTeX format
     Bibtex,
     /// JSON format
@@ -731,6 +1051,7 @@ enum CitationOutputFormat {
 #[derive(Subcommand, Debug)]
 enum CacheCommands {
     /// Show cache status and statistics
+    #[command(alias = "stats")]
     Status,
 
     /// Clear all cached data
@@ -741,6 +1062,328 @@ enum CacheCommands {
 
     /// Clear only citation cache
     ClearCitations,
+
+    /// Clear only the HTTP response cache
+    ClearHttp,
+
+    /// List cached entries, or show one entry
+    Inspect {
+        /// Only entries of this namespace
+        #[arg(long, value_enum)]
+        namespace: Option<CacheNamespaceArg>,
+
+        /// Only entries from this source (source ID, or host for HTTP responses)
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Show the value of the entry with this key (needs --namespace)
+        #[arg(long, requires = "namespace")]
+        key: Option<String>,
+
+        /// Maximum number of entries to list
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+
+    /// Remove cached entries by namespace, source or age
+    Invalidate {
+        /// Only entries of this namespace
+        #[arg(long, value_enum)]
+        namespace: Option<CacheNamespaceArg>,
+
+        /// Only entries from this source (source ID, or host for HTTP responses)
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Only entries stored longer ago than this (e.g. 30m, 12h, 7d, 2w)
+        #[arg(long, value_parser = parse_age)]
+        older_than: Option<Duration>,
+
+        /// Only entries past their TTL
+        #[arg(long)]
+        expired: bool,
+    },
+
+    /// Copy the cache into a new database file
+    Export {
+        /// File to write (must not exist)
+        path: PathBuf,
+    },
+
+    /// Add the entries of an exported cache file
+    Import {
+        /// File written by `cache export`
+        path: PathBuf,
+    },
+}
+
+/// Kind of cached data
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum CacheNamespaceArg {
+    /// Search results
+    Searches,
+    /// Citation, reference and open-access lookups
+    Citations,
+    /// Paper metadata
+    Metadata,
+    /// Downloaded PDFs
+    Pdfs,
+    /// Text extracted from PDFs
+    Text,
+    /// Raw HTTP responses
+    Http,
+}
+
+impl From<CacheNamespaceArg> for CacheNamespace {
+    fn from(namespace: CacheNamespaceArg) -> Self {
+        match namespace {
+            CacheNamespaceArg::Searches => CacheNamespace::Searches,
+            CacheNamespaceArg::Citations => CacheNamespace::Citations,
+            CacheNamespaceArg::Metadata => CacheNamespace::Metadata,
+            CacheNamespaceArg::Pdfs => CacheNamespace::Pdfs,
+            CacheNamespaceArg::Text => CacheNamespace::Text,
+            CacheNamespaceArg::Http => CacheNamespace::Http,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum GraphCommands {
+    /// Walk citation links outwards from seed papers
+    Snowball {
+        /// Seed papers (DOI, arXiv ID, OpenAlex work ID or Semantic Scholar ID)
+        #[arg(required = true)]
+        seeds: Vec<String>,
+
+        /// Which citation links to follow
+        #[arg(long, value_enum, default_value_t = GraphDirection::Both)]
+        direction: GraphDirection,
+
+        /// Number of hops from the seeds
+        #[arg(long, short, default_value_t = 1)]
+        depth: usize,
+
+        /// Maximum citations/references followed per paper and direction
+        #[arg(long, default_value_t = 20)]
+        max_per_paper: usize,
+
+        /// Stop once the graph has this many papers
+        #[arg(long, default_value_t = 500)]
+        max_nodes: usize,
+
+        /// Only add papers from these years (e.g. 2020, 2018-2022, 2010-, -2015)
+        #[arg(long, short)]
+        year: Option<String>,
+
+        /// Only add papers with at least this many citations
+        #[arg(long)]
+        min_citations: Option<u32>,
+
+        /// Citation sources to use, in order of preference
+        #[arg(long, value_enum, value_delimiter = ',')]
+        sources: Vec<Source>,
+
+        /// Graph output format
+        #[arg(long, short, value_enum, default_value_t = GraphOutputFormat::Json)]
+        format: GraphOutputFormat,
+
+        /// Output file (stdout if not specified)
+        #[arg(long, short = 'O')]
+        output_file: Option<PathBuf>,
+    },
+
+    /// Find co-cited and bibliographically coupled papers for a paper set
+    Coupling {
+        /// Papers of the set (DOI, arXiv ID, OpenAlex work ID or Semantic Scholar ID)
+        #[arg(required = true)]
+        seeds: Vec<String>,
+
+        /// Maximum citations/references fetched per paper and direction
+        #[arg(long, default_value_t = 20)]
+        max_per_paper: usize,
+
+        /// Maximum citing papers whose references are fetched
+        #[arg(long, default_value_t = 50)]
+        max_citing: usize,
+
+        /// Number of missing papers to list
+        #[arg(long, short, default_value_t = 20)]
+        top: usize,
+
+        /// Citation sources to use, in order of preference
+        #[arg(long, value_enum, value_delimiter = ',')]
+        sources: Vec<Source>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WatchCommands {
+    /// Save a search to re-run with `watch run`
+    Add {
+        /// Name of the saved search (letters, digits, '-', '_' or '.')
+        name: String,
+
+        /// Search query
+        query: String,
+
+        /// Sources to search (default: all searchable sources)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        sources: Vec<Source>,
+
+        /// Maximum results per source
+        #[arg(long, short, default_value_t = 20)]
+        max_results: usize,
+
+        /// Year filter (e.g. 2024, 2018-2022, 2010-)
+        #[arg(long, short)]
+        year: Option<String>,
+
+        /// Category/subject filter
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Author filter
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Sort results by field
+        #[arg(long, value_enum)]
+        sort_by: Option<SortField>,
+
+        /// Replace an existing saved search with the same name
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// Follow an author's new publications
+    FollowAuthor {
+        /// Name of the follow (letters, digits, '-', '_' or '.')
+        name: String,
+
+        /// ORCID iD or author record(s) such as openalex:A5023888391 or semantic:1741101
+        id: String,
+
+        /// Maximum recent publications fetched per author record and run
+        #[arg(long, short, default_value_t = 50)]
+        max_results: usize,
+
+        /// Replace an existing follow with the same name
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// Follow new papers citing a paper
+    FollowPaper {
+        /// Name of the follow (letters, digits, '-', '_' or '.')
+        name: String,
+
+        /// Paper (DOI, arXiv ID, OpenAlex work ID or Semantic Scholar ID)
+        id: String,
+
+        /// Maximum citing papers fetched per run
+        #[arg(long, short, default_value_t = 50)]
+        max_results: usize,
+
+        /// Replace an existing follow with the same name
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// List saved searches and follows
+    #[command(alias = "ls")]
+    List,
+
+    /// Remove a saved search or follow and its snapshot
+    #[command(alias = "rm")]
+    Remove {
+        /// Name of the saved search or follow
+        name: String,
+    },
+
+    /// Re-run saved searches and follows and report papers not seen in earlier runs
+    Run {
+        /// Saved searches and follows to run (default: all)
+        names: Vec<String>,
+
+        /// Write the digest as Markdown to this file
+        #[arg(long)]
+        markdown: Option<PathBuf>,
+
+        /// Write the digest as JSON to this file
+        #[arg(long)]
+        json: Option<PathBuf>,
+
+        /// Write the new papers as an Atom feed to this file
+        #[arg(long)]
+        atom: Option<PathBuf>,
+
+        /// Export the new papers to this file, or notes directory with `--export-format notes`
+        #[arg(long, value_name = "PATH")]
+        export: Option<PathBuf>,
+
+        /// Format for --export
+        #[arg(long, value_enum, default_value_t = ExportFormat::Bibtex, requires = "export")]
+        export_format: ExportFormat,
+
+        /// Report new papers without remembering them for the next run
+        #[arg(long)]
+        no_update: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BibCommands {
+    /// Add papers by DOI or arXiv ID, or refresh the entries that describe them
+    Add {
+        /// Papers to add (DOI or arXiv ID)
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// BibTeX file to update (created if missing)
+        #[arg(long, short, default_value = "references.bib")]
+        file: PathBuf,
+
+        /// Cite key pattern for new entries (default: `bib.key_pattern` from
+        /// the config, or {author}{year}{firstword})
+        #[arg(long, value_name = "PATTERN")]
+        key_pattern: Option<String>,
+
+        /// Report what would change without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Report entries with missing DOIs, years that disagree with the sources, retractions,
+    /// or preprints with a published version
+    Check {
+        /// BibTeX file to check
+        #[arg(long, short, default_value = "references.bib")]
+        file: PathBuf,
+    },
+}
+
+/// Citation links followed by the snowball walk
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum GraphDirection {
+    /// Papers citing each paper
+    Forward,
+    /// Papers cited by each paper
+    Backward,
+    /// Both directions
+    Both,
+}
+
+/// Output format for citation graphs
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum GraphOutputFormat {
+    /// JSON node and edge lists
+    Json,
+    /// GraphML (yEd, Cytoscape, NetworkX)
+    Graphml,
+    /// GEXF (Gephi)
+    Gexf,
+    /// Graphviz DOT
+    Dot,
 }
 
 /// Capability filter for listing sources
@@ -752,6 +1395,7 @@ enum CapabilityFilter {
     Citations,
     DoiLookup,
     AuthorSearch,
+    AuthorProfiles,
 }
 
 /// Print all available environment variables
@@ -791,8 +1435,18 @@ fn print_env_vars() {
     println!("  RESEARCH_MASTER_CACHE_DIRECTORY              Custom cache directory");
     println!("  RESEARCH_MASTER_CACHE_SEARCH_TTL_SECONDS     TTL for search results (default: 1800 = 30 min)");
     println!("  RESEARCH_MASTER_CACHE_CITATION_TTL_SECONDS   TTL for citation results (default: 900 = 15 min)");
+    println!("  RESEARCH_MASTER_CACHE_MAX_SIZE_MB            Cache size limit, least recently used entries go first (default: 500)");
+    println!(
+        "  RESEARCH_MASTER_CACHE_COMPRESS               Compress cached values (default: true)"
+    );
+    println!("  RESEARCH_MASTER_CACHE_HTTP_ENABLED           Cache and revalidate HTTP responses (default: true)");
+    println!("  RESEARCH_MASTER_CACHE_HTTP_MAX_SIZE_MB       HTTP response cache size limit (default: 100)");
+    println!("  RESEARCH_MASTER_CACHE_HTTP_SOURCES           Per-source HTTP cache TTLs, e.g. openalex=3600,google_scholar=off");
     println!();
     println!("Other Settings:");
+    println!("  RESEARCH_MASTER_OFFLINE     Answer from the cache and downloaded PDFs only (default: false)");
+    println!("  RESEARCH_MASTER_HTTP_RECORD Directory to record HTTP requests and responses into");
+    println!("  RESEARCH_MASTER_HTTP_REPLAY Directory to replay recorded HTTP responses from");
     println!("  RUST_LOG                    Rust logging level (e.g., debug, info, warn, error)");
     println!();
     println!("Example:");
@@ -810,6 +1464,24 @@ async fn main() -> Result<()> {
     // This allows sources to pick up the proxy settings via their normal env var reading
     apply_cli_proxy_args(cli.http_proxy, cli.https_proxy, cli.no_proxy);
 
+    // The HTTP response cache is set up from the environment on first use
+    if cli.no_cache {
+        std::env::set_var("RESEARCH_MASTER_CACHE_HTTP_ENABLED", "false");
+    }
+
+    // Sources and the cache read offline mode from the environment
+    if cli.offline {
+        std::env::set_var(OFFLINE_ENV_VAR, "true");
+    }
+
+    // The HTTP recorder is set up from the environment on first use
+    if let Some(dir) = &cli.http_record {
+        std::env::set_var(HTTP_RECORD_ENV_VAR, dir);
+    }
+    if let Some(dir) = &cli.http_replay {
+        std::env::set_var(HTTP_REPLAY_ENV_VAR, dir);
+    }
+
     // Show environment variables and exit if requested
     if cli.env {
         print_env_vars();
@@ -820,8 +1492,8 @@ async fn main() -> Result<()> {
     let env_filter = if cli.quiet { "error" } else { "warn" };
 
     let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_level(false)  // Don't show log level
-        .with_target(false)  // Don't show target
+        .with_level(false) // Don't show log level
+        .with_target(false) // Don't show target
         .with_thread_ids(false)
         .compact();
 
@@ -854,7 +1526,7 @@ async fn main() -> Result<()> {
     tokio::time::sleep(Duration::from_secs(0)).await; // Just to ensure runtime is initialized
 
     // Load configuration from file if specified or found in default locations
-    let _config = if let Some(config_path) = &cli.config {
+    let config = if let Some(config_path) = &cli.config {
         Some(load_config(config_path)?)
     } else if let Some(config_path) = find_config_file() {
         tracing::info!("Using config file: {}", config_path.display());
@@ -863,6 +1535,15 @@ async fn main() -> Result<()> {
         None
     };
 
+    if config.as_ref().is_some_and(|c| c.offline) {
+        std::env::set_var(OFFLINE_ENV_VAR, "true");
+    }
+    if let Some(config) = &config {
+        HostLimiter::init_global(&config.rate_limits);
+        HttpCache::init_global(config.cache.clone());
+    }
+    let offline = get_config().offline;
+
     // Create source registry
     let registry = SourceRegistry::new();
 
@@ -879,18 +1560,17 @@ async fn main() -> Result<()> {
             author,
             dedup,
             dedup_strategy,
+            versions,
             fetch_details,
+            open_access,
+            open_access_only,
+            license,
+            enrich,
         }) => {
             let mut search_query = SearchQuery::new(&query);
             search_query.max_results = max_results;
             search_query.year = year;
-            search_query.sort_by = sort_by.map(|s| match s {
-                SortField::Relevance => SortBy::Relevance,
-                SortField::Date => SortBy::Date,
-                SortField::Citations => SortBy::CitationCount,
-                SortField::Title => SortBy::Title,
-                SortField::Author => SortBy::Author,
-            });
+            search_query.sort_by = sort_by.map(to_sort_by);
             search_query.sort_order = order.map(|o| match o {
                 Order::Asc => SortOrder::Ascending,
                 Order::Desc => SortOrder::Descending,
@@ -923,15 +1603,17 @@ async fn main() -> Result<()> {
             };
 
             // Style for progress bars
-            let spinner_style = ProgressStyle::with_template("{prefix:.bold.dim} {spinner} {wide_msg}")
-                .unwrap()
-                .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ");
+            let spinner_style =
+                ProgressStyle::with_template("{prefix:.bold.dim} {spinner} {wide_msg}")
+                    .unwrap()
+                    .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ");
 
             for src in sources {
                 let src_id = src.id().to_string();
                 let src = Arc::clone(src);
                 let search_query = search_query.clone();
-                let cache_inner = cache.clone();
+                // Offline sources answer from the cache themselves, marking the papers
+                let cache_inner = if offline { None } else { cache.clone() };
                 let mp = mp.clone();
 
                 // Create progress bar for this source
@@ -955,7 +1637,10 @@ async fn main() -> Result<()> {
 
                     // Check cache first - if we have both cache and progress bar
                     if let (Some(cache_service), Some(pb)) = (cache.as_ref(), pb.as_ref()) {
-                        match cache_service.get_search(&search_query, &src_id_for_handle) {
+                        match cache_service
+                            .get_search(&search_query, &src_id_for_handle)
+                            .await
+                        {
                             research_master::utils::CacheResult::Hit(response) => {
                                 let elapsed = start.elapsed();
                                 let msg = format!(
@@ -963,8 +1648,9 @@ async fn main() -> Result<()> {
                                     response.papers.len(),
                                     elapsed.as_secs_f64()
                                 );
-                                let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}")
-                                    .unwrap();
+                                let style =
+                                    ProgressStyle::with_template("{prefix:.bold.dim} {msg}")
+                                        .unwrap();
                                 pb.set_style(style);
                                 pb.set_message(msg);
                                 pb.finish();
@@ -976,9 +1662,22 @@ async fn main() -> Result<()> {
                                 match src.search(&search_query).await {
                                     Ok(response) => {
                                         let elapsed = start.elapsed();
-                                        cache_service.set_search(&src_id_for_handle, &search_query, &response);
-                                        let msg = format!("{} papers ({:.1}s)", response.papers.len(), elapsed.as_secs_f64());
-                                        let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}").unwrap();
+                                        cache_service
+                                            .set_search(
+                                                &src_id_for_handle,
+                                                &search_query,
+                                                &response,
+                                            )
+                                            .await;
+                                        let msg = format!(
+                                            "{} papers ({:.1}s)",
+                                            response.papers.len(),
+                                            elapsed.as_secs_f64()
+                                        );
+                                        let style = ProgressStyle::with_template(
+                                            "{prefix:.bold.dim} {msg}",
+                                        )
+                                        .unwrap();
                                         pb.set_style(style);
                                         pb.set_message(msg);
                                         pb.finish();
@@ -986,8 +1685,15 @@ async fn main() -> Result<()> {
                                     }
                                     Err(e) => {
                                         let elapsed = start.elapsed();
-                                        let msg = format!("error after {:.1}s: {}", elapsed.as_secs_f64(), e.to_string().lines().next().unwrap_or("unknown error"));
-                                        let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}").unwrap();
+                                        let msg = format!(
+                                            "error after {:.1}s: {}",
+                                            elapsed.as_secs_f64(),
+                                            e.to_string().lines().next().unwrap_or("unknown error")
+                                        );
+                                        let style = ProgressStyle::with_template(
+                                            "{prefix:.bold.dim} {msg}",
+                                        )
+                                        .unwrap();
                                         pb.set_style(style);
                                         pb.set_message(msg);
                                         pb.finish();
@@ -1000,9 +1706,22 @@ async fn main() -> Result<()> {
                                 match src.search(&search_query).await {
                                     Ok(response) => {
                                         let elapsed = start.elapsed();
-                                        cache_service.set_search(&src_id_for_handle, &search_query, &response);
-                                        let msg = format!("{} papers ({:.1}s)", response.papers.len(), elapsed.as_secs_f64());
-                                        let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}").unwrap();
+                                        cache_service
+                                            .set_search(
+                                                &src_id_for_handle,
+                                                &search_query,
+                                                &response,
+                                            )
+                                            .await;
+                                        let msg = format!(
+                                            "{} papers ({:.1}s)",
+                                            response.papers.len(),
+                                            elapsed.as_secs_f64()
+                                        );
+                                        let style = ProgressStyle::with_template(
+                                            "{prefix:.bold.dim} {msg}",
+                                        )
+                                        .unwrap();
                                         pb.set_style(style);
                                         pb.set_message(msg);
                                         pb.finish();
@@ -1010,8 +1729,15 @@ async fn main() -> Result<()> {
                                     }
                                     Err(e) => {
                                         let elapsed = start.elapsed();
-                                        let msg = format!("error after {:.1}s: {}", elapsed.as_secs_f64(), e.to_string().lines().next().unwrap_or("unknown error"));
-                                        let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}").unwrap();
+                                        let msg = format!(
+                                            "error after {:.1}s: {}",
+                                            elapsed.as_secs_f64(),
+                                            e.to_string().lines().next().unwrap_or("unknown error")
+                                        );
+                                        let style = ProgressStyle::with_template(
+                                            "{prefix:.bold.dim} {msg}",
+                                        )
+                                        .unwrap();
                                         pb.set_style(style);
                                         pb.set_message(msg);
                                         pb.finish();
@@ -1027,11 +1753,19 @@ async fn main() -> Result<()> {
                         Ok(response) => {
                             let elapsed = start.elapsed();
                             if let Some(cache_service) = cache {
-                                cache_service.set_search(&src_id_for_handle, &search_query, &response);
+                                cache_service
+                                    .set_search(&src_id_for_handle, &search_query, &response)
+                                    .await;
                             }
                             if let Some(pb) = pb {
-                                let msg = format!("{} papers ({:.1}s)", response.papers.len(), elapsed.as_secs_f64());
-                                let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}").unwrap();
+                                let msg = format!(
+                                    "{} papers ({:.1}s)",
+                                    response.papers.len(),
+                                    elapsed.as_secs_f64()
+                                );
+                                let style =
+                                    ProgressStyle::with_template("{prefix:.bold.dim} {msg}")
+                                        .unwrap();
                                 pb.set_style(style);
                                 pb.set_message(msg);
                                 pb.finish();
@@ -1041,9 +1775,15 @@ async fn main() -> Result<()> {
                         Err(e) => {
                             let elapsed = start.elapsed();
                             if let Some(pb) = pb {
-                                let msg = format!("error after {:.1}s: {}", elapsed.as_secs_f64(), e.to_string().lines().next().unwrap_or("unknown error"));
-                                let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}").unwrap();
-                                pb.set_style(style);
+                                let msg = format!(
+                                    "error after {:.1}s: {}",
+                                    elapsed.as_secs_f64(),
+                                    e.to_string().lines().next().unwrap_or("unknown error")
+                                );
+                                let style =
+                                    ProgressStyle::with_template("{prefix:.bold.dim} {msg}")
+                                        .unwrap();
+                                pb.set_style(style);
                                 pb.set_message(msg);
                                 pb.finish();
                             }
@@ -1088,6 +1828,46 @@ async fn main() -> Result<()> {
                 all_papers = deduplicate_papers(all_papers, strategy);
             }
 
+            let versions = versions.or(dedup.then_some(VersionChoice::Published));
+            if let Some(prefer) = versions {
+                all_papers = group_versions(all_papers, prefer.into());
+            }
+
+            if let Some(fields) = enrich {
+                let mut options = config
+                    .map(|c| c.enrich)
+                    .unwrap_or_else(|| get_config().enrich)
+                    .options();
+                if !fields.is_empty() {
+                    options = options.fields(fields.into_iter().map(EnrichField::from).collect());
+                }
+                let enricher = Enricher::new(registry.all().cloned().collect(), options);
+                let (enriched, report) = enricher.enrich(all_papers).await;
+                all_papers = enriched;
+                if !quiet {
+                    eprintln!("Enrichment: {}", report.summary());
+                }
+            }
+
+            let oa_filter = OpenAccessFilter::new()
+                .open_access_only(open_access_only)
+                .licenses(license);
+            if open_access || oa_filter.is_active() {
+                let mut resolver = OpenAccessResolver::new(registry.all().cloned().collect());
+                if resolver.source_ids().is_empty() {
+                    anyhow::bail!("Open-access lookups need the openalex or unpaywall source");
+                }
+                if let Some(cache) = cache {
+                    resolver = resolver.with_cache(cache);
+                }
+                for error in resolver.annotate(&mut all_papers).await {
+                    if !quiet {
+                        eprintln!("Open-access lookup failed: {}", error);
+                    }
+                }
+                all_papers = oa_filter.apply(all_papers);
+            }
+
             output_papers(&all_papers, cli.output);
         }
 
@@ -1114,9 +1894,10 @@ async fn main() -> Result<()> {
             };
 
             // Style for progress bars
-            let spinner_style = ProgressStyle::with_template("{prefix:.bold.dim} {spinner} {wide_msg}")
-                .unwrap()
-                .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ");
+            let spinner_style =
+                ProgressStyle::with_template("{prefix:.bold.dim} {spinner} {wide_msg}")
+                    .unwrap()
+                    .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ");
 
             for src in sources {
                 let src_id = src.id().to_string();
@@ -1150,8 +1931,9 @@ async fn main() -> Result<()> {
                                     response.papers.len(),
                                     elapsed.as_secs_f64()
                                 );
-                                let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}")
-                                    .unwrap();
+                                let style =
+                                    ProgressStyle::with_template("{prefix:.bold.dim} {msg}")
+                                        .unwrap();
                                 pb.set_style(style);
                                 pb.set_message(msg);
                                 pb.finish();
@@ -1166,8 +1948,9 @@ async fn main() -> Result<()> {
                                     elapsed.as_secs_f64(),
                                     e.to_string().lines().next().unwrap_or("unknown error")
                                 );
-                                let style = ProgressStyle::with_template("{prefix:.bold.dim} {msg}")
-                                    .unwrap();
+                                let style =
+                                    ProgressStyle::with_template("{prefix:.bold.dim} {msg}")
+                                        .unwrap();
                                 pb.set_style(style);
                                 pb.set_message(msg);
                                 pb.finish();
@@ -1302,6 +2085,275 @@ async fn main() -> Result<()> {
             output_papers(&response.papers, cli.output);
         }
 
+        Some(Commands::AuthorProfile {
+            query,
+            max_papers,
+            top,
+            sources,
+        }) => {
+            let mut options = AuthorOptions::new().max_papers(max_papers).top(top);
+            if !sources.is_empty() {
+                options = options.sources(citation_source_ids(sources));
+            }
+
+            let resolver = AuthorResolver::new(registry.all().cloned().collect(), options);
+            let lookup = resolver.resolve(&query).await?;
+            output_author_lookup(&lookup, cli.output);
+        }
+
+        Some(Commands::Graph { command }) => match command {
+            GraphCommands::Snowball {
+                seeds,
+                direction,
+                depth,
+                max_per_paper,
+                max_nodes,
+                year,
+                min_citations,
+                sources,
+                format,
+                output_file,
+            } => {
+                let (year_from, year_to) = match year {
+                    Some(year) => parse_year_range(&year).map_err(|e| anyhow::anyhow!(e))?,
+                    None => (None, None),
+                };
+
+                let mut options = SnowballOptions::new()
+                    .direction(match direction {
+                        GraphDirection::Forward => Direction::Forward,
+                        GraphDirection::Backward => Direction::Backward,
+                        GraphDirection::Both => Direction::Both,
+                    })
+                    .depth(depth)
+                    .max_per_paper(max_per_paper)
+                    .max_nodes(max_nodes)
+                    .years(year_from, year_to);
+                if let Some(min) = min_citations {
+                    options = options.min_citations(min);
+                }
+                if !sources.is_empty() {
+                    options = options.sources(citation_source_ids(sources));
+                }
+
+                let all_sources: Vec<_> = registry.all().cloned().collect();
+                let mut snowball = Snowball::new(all_sources.clone(), options);
+                if snowball.fetcher().source_ids().is_empty() {
+                    anyhow::bail!("None of the selected sources support citation lookups");
+                }
+                if !cli.no_cache {
+                    let cache = CacheService::new();
+                    let _ = cache.initialize();
+                    snowball = snowball.with_cache(cache);
+                }
+
+                let mut seed_papers = Vec::with_capacity(seeds.len());
+                for seed in &seeds {
+                    seed_papers.push(resolve_seed(&all_sources, seed).await);
+                }
+
+                let graph = snowball.run(seed_papers).await;
+                let format = match format {
+                    GraphOutputFormat::Json => GraphFormat::Json,
+                    GraphOutputFormat::Graphml => GraphFormat::Graphml,
+                    GraphOutputFormat::Gexf => GraphFormat::Gexf,
+                    GraphOutputFormat::Dot => GraphFormat::Dot,
+                };
+                let exported = graph.export(format);
+
+                match output_file {
+                    Some(path) => std::fs::write(&path, exported)?,
+                    None => print!("{}", exported),
+                }
+
+                if !cli.quiet {
+                    eprintln!(
+                        "Citation graph: {} papers, {} links{}",
+                        graph.node_count(),
+                        graph.edge_count(),
+                        if graph.truncated {
+                            " (stopped at --max-nodes)"
+                        } else {
+                            ""
+                        }
+                    );
+                }
+            }
+            GraphCommands::Coupling {
+                seeds,
+                max_per_paper,
+                max_citing,
+                top,
+                sources,
+            } => {
+                let mut options = CouplingOptions::new()
+                    .max_per_paper(max_per_paper)
+                    .max_citing(max_citing)
+                    .top(top);
+                if !sources.is_empty() {
+                    options = options.sources(citation_source_ids(sources));
+                }
+
+                let all_sources: Vec<_> = registry.all().cloned().collect();
+                let mut coupling = Coupling::new(all_sources.clone(), options);
+                if coupling.fetcher().source_ids().is_empty() {
+                    anyhow::bail!("None of the selected sources support citation lookups");
+                }
+                if !cli.no_cache {
+                    let cache = CacheService::new();
+                    let _ = cache.initialize();
+                    coupling = coupling.with_cache(cache);
+                }
+
+                let mut papers = Vec::with_capacity(seeds.len());
+                for seed in &seeds {
+                    papers.push(resolve_seed(&all_sources, seed).await);
+                }
+
+                let report = coupling.run(papers).await;
+                output_coupling_report(&report, cli.output);
+            }
+        },
+
+        Some(Commands::Watch { command }) => {
+            let store = WatchStore::new();
+            match command {
+                WatchCommands::Add {
+                    name,
+                    query,
+                    sources,
+                    max_results,
+                    year,
+                    category,
+                    author,
+                    sort_by,
+                    replace,
+                } => {
+                    let search = SavedSearch::new(&name, query)
+                        .sources(citation_source_ids(sources))
+                        .max_results(max_results)
+                        .year(year)
+                        .category(category)
+                        .author(author)
+                        .sort_by(sort_by.map(to_sort_by));
+                    store.add(search, replace)?;
+                    if !cli.quiet {
+                        eprintln!("Saved search '{}'", name);
+                    }
+                }
+                WatchCommands::FollowAuthor {
+                    name,
+                    id,
+                    max_results,
+                    replace,
+                } => {
+                    let sources: Vec<_> = registry.all().cloned().collect();
+                    let follow = Follow::author(&sources, &name, &id)
+                        .await?
+                        .max_results(max_results);
+                    if !cli.quiet {
+                        eprintln!("Following {}", follow.describe());
+                    }
+                    store.add_follow(follow, replace)?;
+                }
+                WatchCommands::FollowPaper {
+                    name,
+                    id,
+                    max_results,
+                    replace,
+                } => {
+                    let sources: Vec<_> = registry.all().cloned().collect();
+                    let follow = Follow::paper(&sources, &name, &id)
+                        .await
+                        .max_results(max_results);
+                    if !cli.quiet {
+                        eprintln!("Following {}", follow.describe());
+                    }
+                    store.add_follow(follow, replace)?;
+                }
+                WatchCommands::List => {
+                    let searches = store.list()?;
+                    let follows = store.list_follows()?;
+                    let last_run = |name: &str| -> Result<String> {
+                        Ok(store
+                            .load_snapshot(name)?
+                            .and_then(|s| s.updated_at)
+                            .map_or("never".to_string(), |t| {
+                                t.format("%Y-%m-%d %H:%M").to_string()
+                            }))
+                    };
+                    if cli.output == OutputFormat::Json {
+                        let list = serde_json::json!({ "searches": searches, "follows": follows });
+                        println!("{}", serde_json::to_string_pretty(&list)?);
+                    } else if searches.is_empty() && follows.is_empty() {
+                        println!(
+                            "No saved searches or follows. Add one with `research-master watch add`."
+                        );
+                    } else {
+                        for search in &searches {
+                            let sources = if search.sources.is_empty() {
+                                "all sources".to_string()
+                            } else {
+                                search.sources.join(", ")
+                            };
+                            println!(
+                                "{}: \"{}\" ({}; last run: {})",
+                                search.name,
+                                search.query,
+                                sources,
+                                last_run(&search.name)?
+                            );
+                        }
+                        for follow in &follows {
+                            println!(
+                                "{}: {} (last run: {})",
+                                follow.name,
+                                follow.describe(),
+                                last_run(&follow.name)?
+                            );
+                        }
+                    }
+                }
+                WatchCommands::Remove { name } => {
+                    store.remove(&name)?;
+                    if !cli.quiet {
+                        eprintln!("Removed '{}'", name);
+                    }
+                }
+                WatchCommands::Run {
+                    names,
+                    markdown,
+                    json,
+                    atom,
+                    export,
+                    export_format,
+                    no_update,
+                } => {
+                    if store.list()?.is_empty() && store.list_follows()?.is_empty() {
+                        anyhow::bail!(
+                            "No saved searches or follows. Add one with `research-master watch add`."
+                        );
+                    }
+                    let watcher = Watcher::new(registry.all().cloned().collect(), store);
+                    let report = watcher.run(&names, !no_update).await?;
+
+                    if let Some(path) = markdown {
+                        std::fs::write(path, report.to_markdown())?;
+                    }
+                    if let Some(path) = json {
+                        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+                    }
+                    if let Some(path) = atom {
+                        std::fs::write(path, report.to_atom())?;
+                    }
+                    if let Some(path) = export {
+                        write_export(&report.new_papers(), export_format, Some(&path), cli.quiet)?;
+                    }
+                    output_watch_report(&report, cli.output);
+                }
+            }
+        }
+
         Some(Commands::Related {
             paper_id,
             source,
@@ -1355,6 +2407,9 @@ async fn main() -> Result<()> {
                 Some(CapabilityFilter::AuthorSearch) => {
                     registry.with_capability(SourceCapabilities::AUTHOR_SEARCH)
                 }
+                Some(CapabilityFilter::AuthorProfiles) => {
+                    registry.with_capability(SourceCapabilities::AUTHOR_PROFILES)
+                }
                 None => registry.all().collect(),
             };
 
@@ -1373,18 +2428,53 @@ async fn main() -> Result<()> {
             http,
             port,
             host,
+            client_rpm,
+            client_concurrency,
+            client_daily_downloads,
+            trusted_proxies,
+            metrics_port,
         }) => {
-            let server = McpServer::new(Arc::new(registry))?;
-
             // Use HTTP mode if --http flag is provided, otherwise use --stdio flag
             let use_http = http || !stdio;
 
+            // Count source calls only when someone can read the counts
+            let registry = match metrics_port {
+                Some(_) if use_http => Arc::new(registry.into_instrumented()),
+                _ => Arc::new(registry),
+            };
+            let server = McpServer::new(registry.clone())?;
+
             if use_http {
                 let addr = format!("{}:{}", host, port);
                 tracing::info!("Running MCP server in HTTP/SSE mode on {}", addr);
-                let (bound_addr, handle) = server.run_http(&addr).await?;
+
+                let mcp_config = config.map(|c| c.mcp).unwrap_or_else(|| get_config().mcp);
+                let mut quota = ClientQuota::from(&mcp_config);
+                if let Some(rpm) = client_rpm {
+                    quota.requests_per_minute = rpm;
+                }
+                if let Some(concurrency) = client_concurrency {
+                    quota.max_concurrent_tool_calls = concurrency;
+                }
+                if let Some(downloads) = client_daily_downloads {
+                    quota.daily_downloads = downloads;
+                }
+
+                let (bound_addr, handle) = if quota.is_unlimited() {
+                    server.run_http(&addr).await?
+                } else {
+                    let mut trusted = mcp_config.trusted_proxies;
+                    trusted.extend(trusted_proxies);
+                    server.run_http_with_quotas(&addr, quota, &trusted).await?
+                };
                 tracing::info!("MCP server listening on {}", bound_addr);
 
+                if let Some(metrics_port) = metrics_port {
+                    let metrics_addr = format!("{}:{}", host, metrics_port);
+                    let (metrics_addr, _) = serve_monitoring(&metrics_addr, registry).await?;
+                    tracing::info!("Metrics and health checks on http://{}", metrics_addr);
+                }
+
                 // Wait for the server to finish
                 handle
                     .await
@@ -1451,6 +2541,11 @@ async fn main() -> Result<()> {
                     } else {
                         println!("Cache: enabled");
                         println!("Directory: {}", stats.cache_dir.display());
+                        println!(
+                            "Database: {} (schema v{})",
+                            stats.database.display(),
+                            stats.schema_version
+                        );
                         println!(
                             "Search cache: {} items ({} KB)",
                             stats.search_count, stats.search_size_kb
@@ -1459,7 +2554,40 @@ async fn main() -> Result<()> {
                             "Citation cache: {} items ({} KB)",
                             stats.citation_count, stats.citation_size_kb
                         );
-                        println!("Total size: {} KB", stats.total_size_kb);
+                        for namespace in &stats.namespaces {
+                            let label = match namespace.namespace {
+                                CacheNamespace::Metadata => "Metadata cache",
+                                CacheNamespace::Pdfs => "PDF cache",
+                                CacheNamespace::Text => "Text cache",
+                                _ => continue,
+                            };
+                            println!(
+                                "{}: {} items ({} KB)",
+                                label,
+                                namespace.entries,
+                                namespace.size / 1024
+                            );
+                        }
+                        if stats.http_enabled {
+                            println!(
+                                "HTTP cache: {} responses ({} KB of {} KB)",
+                                stats.http.entries, stats.http.size_kb, stats.http.max_size_kb
+                            );
+                            println!(
+                                "HTTP requests: {} hits, {} revalidated, {} misses",
+                                stats.http.hits, stats.http.revalidated, stats.http.misses
+                            );
+                        } else {
+                            println!("HTTP cache: disabled");
+                        }
+                        if stats.max_size_kb > 0 {
+                            println!(
+                                "Total size: {} KB of {} KB",
+                                stats.total_size_kb, stats.max_size_kb
+                            );
+                        } else {
+                            println!("Total size: {} KB", stats.total_size_kb);
+                        }
                         println!("Search TTL: {} seconds", stats.ttl_search.as_secs());
                         println!("Citation TTL: {} seconds", stats.ttl_citations.as_secs());
                     }
@@ -1491,6 +2619,145 @@ async fn main() -> Result<()> {
                         eprintln!("Citation cache cleared successfully.");
                     }
                 }
+                CacheCommands::ClearHttp => {
+                    if !cli.quiet {
+                        eprintln!("Clearing HTTP response cache...");
+                    }
+                    cache.clear_http()?;
+                    if !cli.quiet {
+                        eprintln!("HTTP response cache cleared successfully.");
+                    }
+                }
+                CacheCommands::Inspect {
+                    namespace,
+                    source,
+                    key,
+                    limit,
+                } => {
+                    if !cache.is_enabled() {
+                        anyhow::bail!("Cache is disabled (set RESEARCH_MASTER_CACHE_ENABLED=true)");
+                    }
+                    if let (Some(namespace), Some(key)) = (namespace, key) {
+                        let namespace = CacheNamespace::from(namespace);
+                        let entry = match cache.get_entry(namespace, &key).await {
+                            CacheResult::Hit(entry) => entry,
+                            CacheResult::Expired => {
+                                anyhow::bail!("Cache entry {}/{} has expired", namespace, key)
+                            }
+                            CacheResult::Miss => {
+                                anyhow::bail!("No cache entry {}/{}", namespace, key)
+                            }
+                        };
+                        print_cache_entry_info(&entry.info);
+                        match std::str::from_utf8(&entry.value) {
+                            Ok(text) => println!("\n{}", text),
+                            Err(_) => println!("\n<{} bytes of binary data>", entry.value.len()),
+                        }
+                    } else {
+                        let mut filter = CacheFilter::new().limit(limit);
+                        if let Some(namespace) = namespace {
+                            filter = filter.namespace(namespace.into());
+                        }
+                        if let Some(source) = source {
+                            filter = filter.source(source);
+                        }
+                        let entries = cache.inspect(&filter)?;
+                        let json = match cli.output {
+                            OutputFormat::Json => true,
+                            OutputFormat::Auto => !std::io::stdout().is_terminal(),
+                            OutputFormat::Table | OutputFormat::Plain => false,
+                        };
+                        if json {
+                            println!("{}", serde_json::to_string_pretty(&entries)?);
+                        } else if entries.is_empty() {
+                            println!("No cached entries.");
+                        } else {
+                            for entry in &entries {
+                                print_cache_entry_info(entry);
+                            }
+                        }
+                    }
+                }
+                CacheCommands::Invalidate {
+                    namespace,
+                    source,
+                    older_than,
+                    expired,
+                } => {
+                    let mut filter = CacheFilter::new();
+                    if let Some(namespace) = namespace {
+                        filter = filter.namespace(namespace.into());
+                    }
+                    if let Some(source) = source {
+                        filter = filter.source(source);
+                    }
+                    if let Some(age) = older_than {
+                        filter = filter.older_than(age);
+                    }
+                    if expired {
+                        filter = filter.expired();
+                    }
+                    if filter.is_empty() {
+                        anyhow::bail!(
+                            "Give --namespace, --source, --older-than or --expired (use `cache clear` to remove everything)"
+                        );
+                    }
+                    let removed = cache.invalidate(&filter)?;
+                    if !cli.quiet {
+                        eprintln!("Removed {} cached entries.", removed);
+                    }
+                }
+                CacheCommands::Export { path } => {
+                    let count = cache.export(&path)?;
+                    if !cli.quiet {
+                        eprintln!("Exported {} cached entries to {}", count, path.display());
+                    }
+                }
+                CacheCommands::Import { path } => {
+                    let count = cache.import(&path)?;
+                    if !cli.quiet {
+                        eprintln!("Imported {} cached entries from {}", count, path.display());
+                    }
+                }
+            }
+        }
+
+        Some(Commands::Doctor {
+            deep: true,
+            source,
+            junit,
+            slow,
+            ..
+        }) => {
+            if offline {
+                anyhow::bail!("Deep checks need the network (offline mode is on)");
+            }
+            let mut sources = Vec::new();
+            for id in &source {
+                let found = registry
+                    .get(id)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown source: {}", id))?;
+                sources.push(found.clone());
+            }
+            if sources.is_empty() {
+                sources = registry.all().cloned().collect();
+            }
+            if !cli.quiet {
+                eprintln!("Checking {} sources...", sources.len());
+            }
+
+            let report = SourceChecker::new()
+                .timeout(Duration::from_secs(cli.timeout))
+                .slow_after(Duration::from_secs(slow))
+                .check_all(sources)
+                .await;
+            if let Some(path) = junit {
+                std::fs::write(&path, report.to_junit())
+                    .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+            }
+            output_doctor_report(&report, cli.output, cli.quiet);
+            if !report.is_healthy() {
+                anyhow::bail!("{} of {} checks failed", report.failures(), report.total());
             }
         }
 
@@ -1498,6 +2765,7 @@ async fn main() -> Result<()> {
             check_connectivity,
             check_api_keys,
             verbose,
+            ..
         }) => {
             println!("Research Master MCP - Doctor");
             println!("================================");
@@ -1534,7 +2802,10 @@ async fn main() -> Result<()> {
             }
 
             // Check connectivity if requested
-            if check_connectivity {
+            if check_connectivity && offline {
+                println!("\n[Connectivity]");
+                println!("  Skipped: offline mode is on");
+            } else if check_connectivity {
                 println!("\n[Connectivity]");
                 for (id, name, _) in &sources_info {
                     let test_url = format!("https://{}.org", id.replace('_', ""));
@@ -1601,6 +2872,10 @@ async fn main() -> Result<()> {
             #[cfg(unix)]
             use std::os::unix::fs::PermissionsExt;
 
+            if offline {
+                anyhow::bail!("Checking for updates needs the network (offline mode is on)");
+            }
+
             let current_version = env!("CARGO_PKG_VERSION");
             println!("Research Master MCP Updater");
             println!("============================");
@@ -1959,21 +3234,215 @@ directory = "~/.cache/research-master"
         Some(Commands::Export {
             input,
             format,
-            output,
-            source: _,
-            query: _,
-            max_results: _,
+            output_file,
+            source,
+            query,
+            max_results,
         }) => {
-            println!(
-                "Export command - format: {:?}, input: {:?}, output: {:?}",
-                format, input, output
-            );
-            println!(
-                "Example: research-master export --input papers.json --format bibtex -O output.bib"
-            );
-            println!(
-                "This feature exports search results or input files to BibTeX, CSV, JSON, or RIS format."
-            );
+            let mut papers = match (input, query) {
+                (Some(path), _) => read_paper_file(&path, None)?,
+                (None, Some(query)) => {
+                    let source =
+                        source.ok_or_else(|| anyhow::anyhow!("--query requires --source"))?;
+                    let source = get_source(&registry, source)?;
+                    source
+                        .search(&SearchQuery::new(query).max_results(max_results))
+                        .await?
+                        .papers
+                }
+                (None, None) => {
+                    anyhow::bail!("Provide --input <FILE>, or --source and --query to search")
+                }
+            };
+            papers.truncate(max_results);
+
+            write_export(&papers, format, output_file.as_deref(), cli.quiet)?;
+            if !cli.quiet {
+                eprintln!(
+                    "Exported {} papers as {}",
+                    papers.len(),
+                    to_export_format(format)
+                );
+            }
+        }
+
+        Some(Commands::Import {
+            files,
+            from,
+            enrich,
+            fields,
+            sources,
+            format,
+            output_file,
+            report,
+        }) => {
+            let mut papers = Vec::new();
+            for path in &files {
+                let read = read_paper_file(path, from)?;
+                if !cli.quiet {
+                    eprintln!("Read {} entries from {}", read.len(), path.display());
+                }
+                papers.extend(read);
+            }
+
+            if enrich {
+                let mut options = EnrichOptions::new();
+                if !fields.is_empty() {
+                    options = options.fields(fields.into_iter().map(EnrichField::from).collect());
+                }
+                if !sources.is_empty() {
+                    options = options.sources(citation_source_ids(sources));
+                }
+
+                let enricher = Enricher::new(registry.all().cloned().collect(), options);
+                if enricher.source_ids().is_empty() {
+                    anyhow::bail!("None of the selected sources support DOI lookup or search");
+                }
+                let (enriched, enrich_report) = enricher.enrich(papers).await;
+                papers = enriched;
+
+                if let Some(path) = report {
+                    std::fs::write(&path, serde_json::to_string_pretty(&enrich_report)?)?;
+                }
+                if !cli.quiet {
+                    print_enrich_report(&enrich_report);
+                }
+            }
+
+            write_export(&papers, format, output_file.as_deref(), cli.quiet)?;
+        }
+
+        Some(Commands::Bib { command }) => {
+            let manager = BibManager::new(registry.all().cloned().collect());
+            match command {
+                BibCommands::Add {
+                    ids,
+                    file,
+                    key_pattern,
+                    dry_run,
+                } => {
+                    let pattern = key_pattern.unwrap_or_else(|| {
+                        config
+                            .map(|c| c.bib)
+                            .unwrap_or_else(|| get_config().bib)
+                            .key_pattern
+                    });
+                    let pattern = KeyPattern::parse(&pattern)?;
+                    let text = match std::fs::read_to_string(&file) {
+                        Ok(text) => text,
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                        Err(e) => return Err(e.into()),
+                    };
+                    let mut bib = BibFile::parse(&text)
+                        .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+
+                    let report = manager
+                        .add(&mut bib, &ids, &pattern)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+                    if report.changed() && !dry_run {
+                        std::fs::write(&file, bib.to_string())?;
+                    }
+                    output_bib_add_report(&report, cli.output);
+                    if !cli.quiet {
+                        eprintln!(
+                            "{}: {}{}",
+                            file.display(),
+                            report.summary(),
+                            if dry_run {
+                                " (dry run, not written)"
+                            } else {
+                                ""
+                            }
+                        );
+                    }
+                }
+                BibCommands::Check { file } => {
+                    let text = std::fs::read_to_string(&file)
+                        .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+                    let bib = BibFile::parse(&text)
+                        .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+
+                    let report = manager.check(&bib).await;
+                    output_bib_check_report(&report, cli.output, cli.quiet);
+                }
+            }
+        }
+
+        Some(Commands::Manuscript {
+            files,
+            bib: bib_path,
+            dry_run,
+            no_check,
+        }) => {
+            let mut citations = Vec::new();
+            for file in &files {
+                let text = std::fs::read_to_string(file)
+                    .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+                citations.extend(manuscript::scan(&file.display().to_string(), &text));
+            }
+            let text = match std::fs::read_to_string(&bib_path) {
+                Ok(text) => text,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(e.into()),
+            };
+            let mut bib = BibFile::parse(&text)
+                .map_err(|e| anyhow::anyhow!("{}: {}", bib_path.display(), e))?;
+
+            let manager = BibManager::new(registry.all().cloned().collect());
+            let report = manager
+                .manuscript(&mut bib, &citations, !no_check)
+                .await
+                .map_err(|e| anyhow::anyhow!("{}: {}", bib_path.display(), e))?;
+            if report.changed() && !dry_run {
+                std::fs::write(&bib_path, bib.to_string())?;
+            }
+            output_manuscript_report(&report, cli.output, cli.quiet);
+            if !cli.quiet {
+                eprintln!(
+                    "{}: {}{}",
+                    bib_path.display(),
+                    report.summary(),
+                    if dry_run {
+                        " (dry run, not written)"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+
+        Some(Commands::CheckRetractions {
+            files,
+            from,
+            database,
+        }) => {
+            let mut papers = Vec::new();
+            for path in &files {
+                papers.extend(read_paper_file(path, from)?);
+            }
+
+            let mut checker = RetractionChecker::new(registry.all().cloned().collect());
+            let database = database.or_else(|| {
+                config
+                    .map(|c| c.retractions)
+                    .unwrap_or_else(|| get_config().retractions)
+                    .retraction_watch
+            });
+            if let Some(path) = database {
+                let database = RetractionWatch::open(&path)?;
+                if !cli.quiet {
+                    eprintln!(
+                        "Loaded {} papers with notices from {}",
+                        database.len(),
+                        path.display()
+                    );
+                }
+                checker = checker.with_database(database);
+            }
+
+            let report = checker.check_all(&mut papers).await;
+            output_retraction_report(&report, cli.output, cli.quiet);
         }
 
         Some(Commands::BulkDownload {
@@ -2228,71 +3697,128 @@ directory = "~/.cache/research-master"
         Some(Commands::Cite {
             paper_id,
             style,
+            style_file,
+            locale_file,
+            input,
             source: _,
             format,
         }) => {
-            use research_master::utils::{format_citation, get_structured_citation, CitationStyle as UtilsCitationStyle};
             use research_master::sources::Source;
+            use research_master::utils::{
+                format_citation, get_structured_citation, CitationStyle as UtilsCitationStyle,
+            };
 
-            let registry = SourceRegistry::new();
-
-            // Get all sources that support DOI lookup
-            let sources_vec: Vec<&Arc<dyn Source>> = registry.with_capability(
-                research_master::sources::SourceCapabilities::DOI_LOOKUP
-            );
+            // Load the style first so a bad file fails before any lookups
+            let processor = match style_file {
+                Some(ref path) => {
+                    let mut processor = Processor::new(Style::from_file(path)?);
+                    if let Some(ref path) = locale_file {
+                        processor = processor.locale(Locale::from_file(path)?);
+                    }
+                    if format == CitationOutputFormat::Html {
+                        processor = processor.format(CslOutputFormat::Html);
+                    }
+                    Some(processor)
+                }
+                None => None,
+            };
 
-            // Try to find the paper
-            let mut paper_opt = None;
-
-            // First, try DOI lookup if it looks like a DOI
-            if paper_id.contains("10.") && paper_id.contains("/") {
-                for source in &sources_vec {
-                    if source.supports_doi_lookup() {
-                        match source.get_by_doi(&paper_id).await {
-                            Ok(paper) => {
-                                paper_opt = Some(paper);
-                                break;
-                            }
-                            Err(e) => {
-                                tracing::debug!("DOI lookup failed for {}: {}", source.id(), e);
+            let papers = match (input, paper_id) {
+                (Some(path), _) => read_paper_file(&path, None)?,
+                (None, Some(paper_id)) => {
+                    let registry = SourceRegistry::new();
+
+                    // Get all sources that support DOI lookup
+                    let sources_vec: Vec<&Arc<dyn Source>> = registry
+                        .with_capability(research_master::sources::SourceCapabilities::DOI_LOOKUP);
+
+                    // Try to find the paper
+                    let mut paper_opt = None;
+
+                    // First, try DOI lookup if it looks like a DOI
+                    if paper_id.contains("10.") && paper_id.contains("/") {
+                        for source in &sources_vec {
+                            if source.supports_doi_lookup() {
+                                match source.get_by_doi(&paper_id).await {
+                                    Ok(paper) => {
+                                        paper_opt = Some(paper);
+                                        break;
+                                    }
+                                    Err(e) => {
+                                        tracing::debug!(
+                                            "DOI lookup failed for {}: {}",
+                                            source.id(),
+                                            e
+                                        );
+                                    }
+                                }
                             }
                         }
                     }
-                }
-            }
-
-            // If not found by DOI, try search as fallback
-            if paper_opt.is_none() {
-                // Get sources that support search
-                let search_sources: Vec<&Arc<dyn Source>> = registry.with_capability(
-                    research_master::sources::SourceCapabilities::SEARCH
-                );
 
-                for source in &search_sources {
-                    let search_query = SearchQuery {
-                        query: paper_id.clone(),
-                        max_results: 1,
-                        year: None,
-                        sort_by: None,
-                        sort_order: None,
-                        filters: std::collections::HashMap::new(),
-                        author: None,
-                        category: None,
-                        fetch_details: true,
-                    };
-                    match source.search(&search_query).await {
-                        Ok(response) => {
-                            if !response.papers.is_empty() {
-                                paper_opt = Some(response.papers[0].clone());
-                                break;
+                    // If not found by DOI, try search as fallback
+                    if paper_opt.is_none() {
+                        // Get sources that support search
+                        let search_sources: Vec<&Arc<dyn Source>> = registry
+                            .with_capability(research_master::sources::SourceCapabilities::SEARCH);
+
+                        for source in &search_sources {
+                            let search_query = SearchQuery {
+                                query: paper_id.clone(),
+                                max_results: 1,
+                                year: None,
+                                sort_by: None,
+                                sort_order: None,
+                                filters: std::collections::HashMap::new(),
+                                author: None,
+                                category: None,
+                                fetch_details: true,
+                            };
+                            match source.search(&search_query).await {
+                                Ok(response) => {
+                                    if !response.papers.is_empty() {
+                                        paper_opt = Some(response.papers[0].clone());
+                                        break;
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::debug!("Search failed for {}: {}", source.id(), e)
+                                }
                             }
                         }
-                        Err(e) => tracing::debug!("Search failed for {}: {}", source.id(), e),
                     }
+
+                    vec![paper_opt
+                        .ok_or_else(|| anyhow::anyhow!("Paper not found: {}", paper_id))?]
                 }
-            }
+                (None, None) => anyhow::bail!("Give a paper ID or --input"),
+            };
 
-            let paper = paper_opt.ok_or_else(|| anyhow::anyhow!("Paper not found: {}", paper_id))?;
+            if let Some(processor) = processor {
+                let rendered = processor.render(&Item::from_papers(&papers));
+                match format {
+                    CitationOutputFormat::Json => {
+                        let output = serde_json::json!({
+                            "style": processor.style().title,
+                            "citations": rendered.citations,
+                            "bibliography": rendered.bibliography,
+                        });
+                        println!("{}", serde_json::to_string_pretty(&output)?);
+                    }
+                    // Styles without a bibliography (or note styles) only have citations
+                    _ if rendered.bibliography.is_empty() => {
+                        for citation in &rendered.citations {
+                            println!("{}", citation);
+                        }
+                    }
+                    _ => {
+                        for entry in &rendered.bibliography {
+                            println!("{}", entry.text);
+                        }
+                    }
+                }
+                return Ok(());
+            }
 
             // Convert CLI CitationStyle to utils CitationStyle
             let utils_style = match style {
@@ -2303,14 +3829,26 @@ directory = "~/.cache/research-master"
             };
 
             match format {
-                CitationOutputFormat::Text | CitationOutputFormat::Bibtex => {
-                    let citation = format_citation(&paper, utils_style);
-                    println!("{}", citation);
+                CitationOutputFormat::Text
+                | CitationOutputFormat::Html
+                | CitationOutputFormat::Bibtex => {
+                    let citations: Vec<String> = papers
+                        .iter()
+                        .map(|paper| format_citation(paper, utils_style))
+                        .collect();
+                    println!("{}", citations.join("\n\n"));
                 }
-                CitationOutputFormat::Json => {
-                    let structured = get_structured_citation(&paper, utils_style);
+                CitationOutputFormat::Json if papers.len() == 1 => {
+                    let structured = get_structured_citation(&papers[0], utils_style);
                     println!("{}", serde_json::to_string_pretty(&structured).unwrap());
                 }
+                CitationOutputFormat::Json => {
+                    let structured: Vec<_> = papers
+                        .iter()
+                        .map(|paper| get_structured_citation(paper, utils_style))
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&structured)?);
+                }
             }
         }
 
@@ -2324,7 +3862,9 @@ directory = "~/.cache/research-master"
             println!("  sources          - List available sources");
             println!("  history          - Show search/download history");
             println!("  clear            - Clear cache, history, or downloads");
-            println!("  cite <id>        - Format paper citation (APA, MLA, Chicago, BibTeX)");
+            println!("  cite <id>        - Format paper citation (APA, MLA, Chicago, BibTeX, CSL)");
+            println!("  graph snowball   - Build a citation graph from seed papers");
+            println!("  graph coupling   - Find papers cited alongside a paper set");
             println!("  mcp              - Run MCP server");
         }
     }
@@ -2332,6 +3872,52 @@ directory = "~/.cache/research-master"
     Ok(())
 }
 
+/// Parse an age such as `45s`, `30m`, `12h`, `7d` or `2w`
+fn parse_age(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("Invalid age '{}' (expected e.g. 7d)", value))?;
+    let seconds = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "" | "d" => 86400,
+        "w" => 7 * 86400,
+        other => {
+            return Err(format!(
+                "Unknown age unit '{}' (use s, m, h, d or w)",
+                other
+            ))
+        }
+    };
+    Ok(Duration::from_secs(number * seconds))
+}
+
+/// Print one cached entry as a line for `cache inspect`
+fn print_cache_entry_info(entry: &EntryInfo) {
+    let age = format_age(entry.age());
+    let status = if entry.is_expired() { ", expired" } else { "" };
+    println!(
+        "{}/{}  [{}] {} ({} B, {} old{})",
+        entry.namespace,
+        entry.key,
+        if entry.source.is_empty() {
+            "-"
+        } else {
+            &entry.source
+        },
+        entry.description,
+        entry.raw_size,
+        age,
+        status
+    );
+}
+
 fn get_source(
     registry: &SourceRegistry,
     source: Source,
@@ -2359,6 +3945,87 @@ fn get_sources(
     }
 }
 
+/// Source IDs for `--sources` lists (graph, author-profile and watch commands)
+fn citation_source_ids(sources: Vec<Source>) -> Vec<String> {
+    sources
+        .into_iter()
+        .filter(|s| *s != Source::All)
+        .map(|s| source_to_id(s).to_string())
+        .collect()
+}
+
+fn to_export_format(format: ExportFormat) -> formats::ExportFormat {
+    match format {
+        ExportFormat::Bibtex => formats::ExportFormat::Bibtex,
+        ExportFormat::CslJson => formats::ExportFormat::CslJson,
+        ExportFormat::Csv => formats::ExportFormat::Csv,
+        ExportFormat::EndnoteXml => formats::ExportFormat::EndnoteXml,
+        ExportFormat::Json => formats::ExportFormat::Json,
+        ExportFormat::Jsonl => formats::ExportFormat::Jsonl,
+        ExportFormat::Mods => formats::ExportFormat::Mods,
+        ExportFormat::Notes => formats::ExportFormat::Notes,
+        ExportFormat::Ris => formats::ExportFormat::Ris,
+    }
+}
+
+/// Write exported papers to `output` (a directory for notes), or to stdout
+fn write_export(
+    papers: &[research_master::models::Paper],
+    format: ExportFormat,
+    output: Option<&std::path::Path>,
+    quiet: bool,
+) -> Result<()> {
+    let format = to_export_format(format);
+    let Some(path) = output else {
+        print!("{}", format.write(papers));
+        return Ok(());
+    };
+    let summary = format.write_to(papers, path)?;
+    if !quiet && format.is_directory() {
+        eprintln!(
+            "Wrote {} notes to {} ({} unchanged)",
+            summary.written.len(),
+            path.display(),
+            summary.unchanged.len()
+        );
+        for note in &summary.kept {
+            eprintln!("Kept edited note {}", note.display());
+        }
+    }
+    Ok(())
+}
+
+/// Read papers from a bibliography or JSON file, detecting the format unless given
+fn read_paper_file(
+    path: &std::path::Path,
+    format: Option<ImportFileFormat>,
+) -> Result<Vec<research_master::models::Paper>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let format = match format {
+        Some(ImportFileFormat::Bibtex) => ImportFormat::Bibtex,
+        Some(ImportFileFormat::Ris) => ImportFormat::Ris,
+        Some(ImportFileFormat::CslJson) => ImportFormat::CslJson,
+        Some(ImportFileFormat::Json) => ImportFormat::Json,
+        None => ImportFormat::detect(path, &content).ok_or_else(|| {
+            anyhow::anyhow!("Cannot detect the format of {}; use --from", path.display())
+        })?,
+    };
+    format
+        .read(&content)
+        .map_err(|e| anyhow::anyhow!("{} ({}): {}", path.display(), format, e))
+}
+
+fn to_sort_by(field: SortField) -> SortBy {
+    match field {
+        SortField::Relevance => SortBy::Relevance,
+        SortField::Date => SortBy::Date,
+        SortField::Citations => SortBy::CitationCount,
+        SortField::Title => SortBy::Title,
+        SortField::Author => SortBy::Author,
+    }
+}
+
 fn source_to_id(source: Source) -> &'static str {
     match source {
         Source::Arxiv => "arxiv",
@@ -2388,11 +4055,342 @@ fn source_to_id(source: Source) -> &'static str {
         Source::Osf => "osf",
         Source::Base => "base",
         Source::Springer => "springer",
+        Source::Orcid => "orcid",
         Source::GoogleScholar => "google_scholar",
         Source::All => unreachable!(),
     }
 }
 
+fn output_author_lookup(lookup: &AuthorLookup, format: OutputFormat) {
+    let json = match format {
+        OutputFormat::Json => true,
+        OutputFormat::Auto => !std::io::stdout().is_terminal(),
+        OutputFormat::Table | OutputFormat::Plain => false,
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(lookup).unwrap());
+        return;
+    }
+
+    let counts = |names: &[research_master::models::NameCount]| {
+        names
+            .iter()
+            .map(|n| format!("{} ({})", n.name, n.count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let optional = |value: Option<u32>| value.map_or("-".to_string(), |v| v.to_string());
+
+    match lookup {
+        AuthorLookup::Found { profile } => {
+            println!("{}", profile.name);
+            if let Some(ref orcid) = profile.orcid {
+                println!("  ORCID:        {}", orcid);
+            }
+            if !profile.affiliations.is_empty() {
+                println!("  Affiliations: {}", profile.affiliations.join("; "));
+            }
+            println!(
+                "  Papers: {}  Citations: {}  h-index: {}",
+                optional(profile.paper_count),
+                optional(profile.citation_count),
+                optional(profile.h_index)
+            );
+            if !profile.top_venues.is_empty() {
+                println!("  Top venues:   {}", counts(&profile.top_venues));
+            }
+            if !profile.co_authors.is_empty() {
+                println!("  Co-authors:   {}", counts(&profile.co_authors));
+            }
+            let records: Vec<String> = profile.records.iter().map(|r| r.key()).collect();
+            println!("  Records:      {}", records.join(", "));
+
+            println!();
+            output_papers(&profile.papers, format);
+        }
+        AuthorLookup::Ambiguous { query, candidates } => {
+            println!(
+                "\"{}\" matches {} people. Run again with one of these IDs:",
+                query,
+                candidates.len()
+            );
+            for candidate in candidates {
+                println!();
+                println!("  {}", candidate.id);
+                println!("    {}", candidate.name);
+                if !candidate.affiliations.is_empty() {
+                    println!("    {}", candidate.affiliations.join("; "));
+                }
+                println!(
+                    "    Papers: {}  Citations: {}",
+                    optional(candidate.paper_count),
+                    optional(candidate.citation_count)
+                );
+            }
+        }
+    }
+}
+
+fn output_coupling_report(report: &CouplingReport, format: OutputFormat) {
+    let json = match format {
+        OutputFormat::Json => true,
+        OutputFormat::Auto => !std::io::stdout().is_terminal(),
+        OutputFormat::Table | OutputFormat::Plain => false,
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    let label = |paper: &research_master::models::Paper| {
+        let title = if paper.title.is_empty() {
+            paper.paper_id.as_str()
+        } else {
+            paper.title.as_str()
+        };
+        match paper.year() {
+            Some(year) => format!("{} ({})", title, year),
+            None => title.to_string(),
+        }
+    };
+
+    println!("Paper set:");
+    for (i, paper) in report.papers.iter().enumerate() {
+        println!("  [{}] {}", i + 1, label(paper));
+    }
+
+    println!();
+    if report.pairs.is_empty() {
+        println!("No co-cited or coupled pairs in the set.");
+    } else {
+        println!("Related pairs:");
+        for pair in &report.pairs {
+            println!(
+                "  [{}] <-> [{}]: {} co-citations, {} shared references",
+                pair.a + 1,
+                pair.b + 1,
+                pair.co_citations,
+                pair.coupling
+            );
+        }
+    }
+
+    println!();
+    if report.missing.is_empty() {
+        println!("No papers found that are frequently cited alongside the set.");
+    } else {
+        println!("Papers you may be missing:");
+        for (i, missing) in report.missing.iter().enumerate() {
+            println!("  {}. {}", i + 1, label(&missing.paper));
+            println!(
+                "     co-cited with the set {} times, cited by {} of the set",
+                missing.co_citations, missing.cited_by_set
+            );
+            if let Some(ref doi) = missing.paper.doi {
+                println!("     DOI: {}", doi);
+            }
+        }
+    }
+}
+
+/// Print what enrichment changed to stderr, keeping stdout for the exported papers
+fn print_enrich_report(report: &EnrichReport) {
+    for entry in &report.entries {
+        let label = if entry.paper_id.is_empty() {
+            &entry.title
+        } else {
+            &entry.paper_id
+        };
+        match entry.status {
+            EnrichStatus::Enriched => {
+                let changes: Vec<String> = entry
+                    .changes
+                    .iter()
+                    .map(|c| format!("{} ({})", c.field, c.source))
+                    .collect();
+                eprintln!("  [{}] {}: {}", entry.index + 1, label, changes.join(", "));
+            }
+            EnrichStatus::NotFound => {
+                eprintln!("  [{}] {}: not found", entry.index + 1, label);
+            }
+            EnrichStatus::Skipped => {
+                eprintln!(
+                    "  [{}] {}: skipped (time budget used up)",
+                    entry.index + 1,
+                    label
+                );
+            }
+            EnrichStatus::Unchanged | EnrichStatus::Complete => {}
+        }
+    }
+    eprintln!("Enrichment: {}", report.summary());
+}
+
+fn output_bib_add_report(report: &AddReport, format: OutputFormat) {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    for entry in &report.entries {
+        let key = entry.key.as_deref().unwrap_or("-");
+        match entry.status {
+            AddStatus::Added => println!("added      {} ({})", key, entry.id),
+            AddStatus::Updated => println!(
+                "updated    {} ({}): {}",
+                key,
+                entry.id,
+                entry.fields.join(", ")
+            ),
+            AddStatus::Unchanged => println!("unchanged  {} ({})", key, entry.id),
+            AddStatus::NotFound => println!("not found  {}", entry.id),
+        }
+    }
+}
+
+fn output_bib_check_report(report: &CheckReport, format: OutputFormat, quiet: bool) {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    for entry in &report.entries {
+        for issue in &entry.issues {
+            println!("{}: {}", entry.key, issue);
+        }
+        if !quiet {
+            for error in &entry.errors {
+                eprintln!("  {}: lookup failed: {}", entry.key, error);
+            }
+        }
+    }
+    if !quiet {
+        eprintln!("{}", report.summary());
+    }
+}
+
+fn output_retraction_report(report: &RetractionReport, format: OutputFormat, quiet: bool) {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    for entry in &report.entries {
+        if let Some(status) = &entry.status {
+            println!("{}: {}: {}", entry.id, status.warning(), entry.title);
+        }
+        if !quiet {
+            for error in &entry.errors {
+                eprintln!("  {}: lookup failed: {}", entry.id, error);
+            }
+        }
+    }
+    if !quiet {
+        eprintln!("{}", report.summary());
+    }
+}
+
+fn output_doctor_report(report: &DoctorReport, format: OutputFormat, quiet: bool) {
+    use comfy_table::{Attribute, Cell, Color, Table};
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    let mut table = Table::new();
+    table.load_preset(comfy_table::presets::UTF8_FULL);
+    table.set_header(vec![
+        Cell::new("Source").add_attribute(Attribute::Bold),
+        Cell::new("Check").add_attribute(Attribute::Bold),
+        Cell::new("Status").add_attribute(Attribute::Bold),
+        Cell::new("Latency").add_attribute(Attribute::Bold),
+        Cell::new("Details").add_attribute(Attribute::Bold),
+    ]);
+    for source in &report.sources {
+        for (i, check) in source.checks.iter().enumerate() {
+            let name = if i == 0 { source.source.as_str() } else { "" };
+            let status = Cell::new(check.status.label()).fg(match check.status {
+                CheckStatus::Pass => Color::Green,
+                CheckStatus::Skip => Color::DarkGrey,
+                CheckStatus::Warn => Color::Yellow,
+                CheckStatus::Fail => Color::Red,
+            });
+            let latency = if check.status == CheckStatus::Skip {
+                String::new()
+            } else {
+                format!("{} ms", check.latency_ms)
+            };
+            table.add_row(vec![
+                Cell::new(name),
+                Cell::new(check.check),
+                status,
+                Cell::new(latency),
+                Cell::new(check.message.as_deref().unwrap_or("")),
+            ]);
+        }
+    }
+    println!("{table}");
+    if !quiet {
+        eprintln!("{}", report.summary());
+    }
+}
+
+fn output_manuscript_report(report: &ManuscriptReport, format: OutputFormat, quiet: bool) {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    for cited in &report.keys {
+        let location = cited.locations.first().map(String::as_str).unwrap_or("");
+        match cited.status {
+            KeyStatus::Missing => println!("{}: {}: no entry in the bib file", location, cited.key),
+            KeyStatus::Unresolved => {
+                println!("{}: {}: not found in any source", location, cited.key)
+            }
+            KeyStatus::Added if !quiet => println!("{}: added", cited.key),
+            KeyStatus::Updated if !quiet => {
+                println!("{}: updated {}", cited.key, cited.fields.join(", "))
+            }
+            _ => {}
+        }
+        for issue in &cited.issues {
+            println!("{}: {}: {}", location, cited.key, issue);
+        }
+    }
+}
+
+fn output_watch_report(report: &WatchReport, format: OutputFormat) {
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    for search in &report.searches {
+        println!(
+            "{}: {} new of {} papers{}",
+            search.name,
+            search.new_papers.len(),
+            search.total,
+            if search.first_run { " (first run)" } else { "" }
+        );
+        for error in &search.errors {
+            eprintln!("  failed: {}", error);
+        }
+        if !search.new_papers.is_empty() {
+            output_papers(&search.new_papers, format);
+            println!();
+        }
+    }
+}
+
+/// Time since a paper served from the cache was fetched
+fn cache_age(paper: &research_master::models::Paper) -> Option<Duration> {
+    let fetched = paper.cached_at()?;
+    Some((chrono::Utc::now() - fetched).to_std().unwrap_or_default())
+}
+
 fn output_papers(papers: &[research_master::models::Paper], format: OutputFormat) {
     let actual_format = if format == OutputFormat::Auto {
         if std::io::stdout().is_terminal() {
@@ -2418,11 +4416,36 @@ fn output_papers(papers: &[research_master::models::Paper], format: OutputFormat
                 if let Some(ref pdf_url) = paper.pdf_url {
                     println!("  PDF: {}", pdf_url);
                 }
+                if let Some(ref open_access) = paper.open_access {
+                    match open_access.url {
+                        Some(ref url) => println!("  Open access: {} ({})", open_access, url),
+                        None => println!("  Open access: {}", open_access),
+                    }
+                }
+                if let Some(ref status) = paper.status {
+                    println!("  WARNING: {}", status.warning());
+                }
+                if let Some(age) = cache_age(paper) {
+                    println!("  Cached: fetched {} ago", format_age(age));
+                }
+                for version in &paper.versions {
+                    let kind = version_kind(version).map_or("other".to_string(), |k| k.to_string());
+                    match version.doi {
+                        Some(ref doi) => println!(
+                            "  Version: {} {} {} (DOI: {})",
+                            kind, version.source, version.paper_id, doi
+                        ),
+                        None => println!(
+                            "  Version: {} {} {}",
+                            kind, version.source, version.paper_id
+                        ),
+                    }
+                }
                 println!();
             }
         }
         OutputFormat::Table => {
-            use comfy_table::{Attribute, Cell, ColumnConstraint, Table, Width};
+            use comfy_table::{Attribute, Cell, Color, ColumnConstraint, Table, Width};
             use owo_colors::OwoColorize;
 
             if papers.is_empty() {
@@ -2459,26 +4482,53 @@ fn output_papers(papers: &[research_master::models::Paper], format: OutputFormat
             ]);
 
             for paper in papers {
-                let year = format_year(
-                    paper
-                        .published_date
-                        .as_ref()
-                        .map(|d| d.as_str())
-                        .unwrap_or("?"),
-                );
-
-                let title = format_title(&paper.title, title_width);
+                let year = format_year(paper.published_date.as_deref().unwrap_or("?"));
+
+                // Retracted and corrected papers are flagged in red
+                let title = match paper.status {
+                    Some(ref status) => Cell::new(format_title(
+                        &format!("⚠ {}: {}", status.kind.label(), paper.title),
+                        title_width,
+                    ))
+                    .fg(Color::Red),
+                    None => Cell::new(format_title(&paper.title, title_width)),
+                }
+                .add_attribute(Attribute::Bold);
                 let authors = format_authors(&paper.authors, authors_width);
-                let source = format_source(&paper.source.to_string(), source_width);
+                // Grouped versions are counted after the source, e.g. "openalex +1"
+                let source = match paper.versions.len() {
+                    0 => paper.source.to_string(),
+                    n => format!("{} +{}", paper.source, n),
+                };
+                let source = format_source(&source, source_width);
 
                 table.add_row(vec![
-                    Cell::new(title).add_attribute(Attribute::Bold),
+                    title,
                     Cell::new(authors),
                     Cell::new(source),
                     Cell::new(year),
                 ]);
             }
             println!("{table}");
+
+            let ages: Vec<Duration> = papers.iter().filter_map(cache_age).collect();
+            if let (Some(newest), Some(oldest)) = (ages.iter().min(), ages.iter().max()) {
+                let fetched = if format_age(*newest) == format_age(*oldest) {
+                    format_age(*oldest)
+                } else {
+                    format!("{} to {}", format_age(*newest), format_age(*oldest))
+                };
+                println!(
+                    "{}",
+                    format!(
+                        "{} of {} papers served from the cache, fetched {} ago",
+                        ages.len(),
+                        papers.len(),
+                        fetched
+                    )
+                    .yellow()
+                );
+            }
         }
         OutputFormat::Auto => unreachable!(),
     }
@@ -2565,6 +4615,37 @@ mod tests {
         assert!(cli.no_cache);
     }
 
+    #[test]
+    fn test_cli_offline_flag() {
+        let cli = Cli::parse_from(["research-master", "search", "graphs", "--offline"]);
+        assert!(cli.offline);
+        let cli = Cli::parse_from(["research-master", "--offline", "mcp"]);
+        assert!(cli.offline);
+        assert!(
+            Cli::try_parse_from(["research-master", "--offline", "--no-cache", "search", "x"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_http_record_replay_flags() {
+        let cli = Cli::parse_from(["research-master", "--http-record", "rec", "search", "x"]);
+        assert_eq!(cli.http_record, Some(PathBuf::from("rec")));
+        assert_eq!(cli.http_replay, None);
+        let cli = Cli::parse_from(["research-master", "search", "x", "--http-replay", "rec"]);
+        assert_eq!(cli.http_replay, Some(PathBuf::from("rec")));
+        assert!(Cli::try_parse_from([
+            "research-master",
+            "--http-record",
+            "a",
+            "--http-replay",
+            "b",
+            "search",
+            "x"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn test_cli_search_command() {
         let cli = Cli::parse_from(["research-master", "search", "machine learning"]);
@@ -2681,6 +4762,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_mcp_client_quotas() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "mcp",
+            "--http",
+            "--client-rpm",
+            "30",
+            "--client-daily-downloads",
+            "0",
+            "--trusted-proxy",
+            "10.0.0.1",
+        ]);
+        match &cli.command {
+            Some(Commands::Mcp {
+                client_rpm,
+                client_concurrency,
+                client_daily_downloads,
+                trusted_proxies,
+                ..
+            }) => {
+                assert_eq!(*client_rpm, Some(30));
+                assert_eq!(*client_concurrency, None);
+                assert_eq!(*client_daily_downloads, Some(0));
+                assert_eq!(
+                    trusted_proxies,
+                    &vec!["10.0.0.1".parse::<std::net::IpAddr>().unwrap()]
+                );
+            }
+            _ => panic!("Expected Mcp command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_mcp_metrics_port() {
+        let cli = Cli::parse_from(["research-master", "mcp", "--http", "--metrics-port", "9100"]);
+        match &cli.command {
+            Some(Commands::Mcp { metrics_port, .. }) => assert_eq!(*metrics_port, Some(9100)),
+            _ => panic!("Expected Mcp command"),
+        }
+        let cli = Cli::parse_from(["research-master", "mcp", "--http"]);
+        match &cli.command {
+            Some(Commands::Mcp { metrics_port, .. }) => assert_eq!(*metrics_port, None),
+            _ => panic!("Expected Mcp command"),
+        }
+    }
+
     #[test]
     fn test_cli_mcp_http_mode() {
         // Just verify the command parses - stdio defaults to true so http doesn't override it
@@ -2817,6 +4945,445 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_graph_snowball() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "graph",
+            "snowball",
+            "10.1000/xyz",
+            "1706.03762",
+            "--direction",
+            "backward",
+            "--depth",
+            "2",
+            "--year",
+            "2015-",
+            "--sources",
+            "semantic,openalex",
+            "--format",
+            "graphml",
+            "-O",
+            "graph.graphml",
+        ]);
+        match cli.command {
+            Some(Commands::Graph {
+                command:
+                    GraphCommands::Snowball {
+                        seeds,
+                        direction,
+                        depth,
+                        max_per_paper,
+                        year,
+                        sources,
+                        format,
+                        output_file,
+                        ..
+                    },
+            }) => {
+                assert_eq!(seeds, vec!["10.1000/xyz", "1706.03762"]);
+                assert_eq!(direction, GraphDirection::Backward);
+                assert_eq!(depth, 2);
+                assert_eq!(max_per_paper, 20);
+                assert_eq!(year.as_deref(), Some("2015-"));
+                assert_eq!(sources, vec![Source::Semantic, Source::OpenAlex]);
+                assert_eq!(format, GraphOutputFormat::Graphml);
+                assert_eq!(output_file, Some(PathBuf::from("graph.graphml")));
+            }
+            _ => panic!("Expected graph snowball command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_graph_coupling() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "graph",
+            "coupling",
+            "10.1000/a",
+            "10.1000/b",
+            "--top",
+            "5",
+        ]);
+        match cli.command {
+            Some(Commands::Graph {
+                command:
+                    GraphCommands::Coupling {
+                        seeds,
+                        max_per_paper,
+                        max_citing,
+                        top,
+                        sources,
+                    },
+            }) => {
+                assert_eq!(seeds.len(), 2);
+                assert_eq!(max_per_paper, 20);
+                assert_eq!(max_citing, 50);
+                assert_eq!(top, 5);
+                assert!(sources.is_empty());
+            }
+            _ => panic!("Expected graph coupling command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_bib_commands() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "bib",
+            "add",
+            "10.1038/nature14539",
+            "1706.03762",
+            "--file",
+            "paper/refs.bib",
+            "--key-pattern",
+            "{author}_{year}",
+            "--dry-run",
+        ]);
+        match cli.command {
+            Some(Commands::Bib {
+                command:
+                    BibCommands::Add {
+                        ids,
+                        file,
+                        key_pattern,
+                        dry_run,
+                    },
+            }) => {
+                assert_eq!(ids, vec!["10.1038/nature14539", "1706.03762"]);
+                assert_eq!(file, PathBuf::from("paper/refs.bib"));
+                assert_eq!(key_pattern.as_deref(), Some("{author}_{year}"));
+                assert!(dry_run);
+            }
+            _ => panic!("Expected bib add command"),
+        }
+        assert!(Cli::try_parse_from(["research-master", "bib", "add"]).is_err());
+
+        let cli = Cli::parse_from(["research-master", "bib", "check"]);
+        match cli.command {
+            Some(Commands::Bib {
+                command: BibCommands::Check { file },
+            }) => assert_eq!(file, PathBuf::from("references.bib")),
+            _ => panic!("Expected bib check command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_manuscript_command() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "manuscript",
+            "paper.tex",
+            "notes.md",
+            "-b",
+            "paper/refs.bib",
+            "--no-check",
+        ]);
+        match cli.command {
+            Some(Commands::Manuscript {
+                files,
+                bib,
+                dry_run,
+                no_check,
+            }) => {
+                assert_eq!(
+                    files,
+                    vec![PathBuf::from("paper.tex"), PathBuf::from("notes.md")]
+                );
+                assert_eq!(bib, PathBuf::from("paper/refs.bib"));
+                assert!(!dry_run);
+                assert!(no_check);
+            }
+            _ => panic!("Expected manuscript command"),
+        }
+        assert!(Cli::try_parse_from(["research-master", "manuscript"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_check_retractions_command() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "check-retractions",
+            "references.bib",
+            "results.json",
+            "--database",
+            "retraction_watch.csv",
+        ]);
+        match cli.command {
+            Some(Commands::CheckRetractions {
+                files,
+                from,
+                database,
+            }) => {
+                assert_eq!(
+                    files,
+                    vec![
+                        PathBuf::from("references.bib"),
+                        PathBuf::from("results.json")
+                    ]
+                );
+                assert!(from.is_none());
+                assert_eq!(database, Some(PathBuf::from("retraction_watch.csv")));
+            }
+            _ => panic!("Expected check-retractions command"),
+        }
+        assert!(Cli::try_parse_from(["research-master", "check-retractions"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_import_command() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "import",
+            "library.ris",
+            "refs.json",
+            "--from",
+            "csl-json",
+            "--enrich",
+            "--fields",
+            "doi,pdf-url",
+            "-f",
+            "ris",
+            "-O",
+            "out.ris",
+        ]);
+        match cli.command {
+            Some(Commands::Import {
+                files,
+                from,
+                enrich,
+                fields,
+                sources,
+                format,
+                output_file,
+                report,
+            }) => {
+                assert_eq!(files.len(), 2);
+                assert_eq!(from, Some(ImportFileFormat::CslJson));
+                assert!(enrich);
+                assert_eq!(fields, vec![MetadataField::Doi, MetadataField::PdfUrl]);
+                assert!(sources.is_empty());
+                assert_eq!(format, ExportFormat::Ris);
+                assert_eq!(output_file, Some(PathBuf::from("out.ris")));
+                assert!(report.is_none());
+            }
+            _ => panic!("Expected import command"),
+        }
+
+        // Enrichment options need --enrich
+        assert!(
+            Cli::try_parse_from(["research-master", "import", "refs.bib", "--fields", "doi"])
+                .is_err()
+        );
+
+        let cli = Cli::parse_from(["research-master", "export", "-i", "refs.bib", "-O", "x.csv"]);
+        match cli.command {
+            Some(Commands::Export {
+                input, output_file, ..
+            }) => {
+                assert_eq!(input, Some(PathBuf::from("refs.bib")));
+                assert_eq!(output_file, Some(PathBuf::from("x.csv")));
+            }
+            _ => panic!("Expected export command"),
+        }
+
+        for (value, format) in [
+            ("endnote-xml", formats::ExportFormat::EndnoteXml),
+            ("mods", formats::ExportFormat::Mods),
+            ("jsonl", formats::ExportFormat::Jsonl),
+            ("notes", formats::ExportFormat::Notes),
+        ] {
+            let cli = Cli::parse_from(["research-master", "export", "-i", "refs.bib", "-f", value]);
+            match cli.command {
+                Some(Commands::Export { format: f, .. }) => {
+                    assert_eq!(to_export_format(f), format);
+                    assert_eq!(to_export_format(f).to_string(), value);
+                }
+                _ => panic!("Expected export command"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_cli_cite_style_file() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "cite",
+            "-i",
+            "refs.bib",
+            "--style-file",
+            "ieee.csl",
+            "--locale-file",
+            "locales-de-DE.xml",
+            "--format",
+            "html",
+        ]);
+        match cli.command {
+            Some(Commands::Cite {
+                paper_id,
+                style_file,
+                locale_file,
+                input,
+                format,
+                ..
+            }) => {
+                assert!(paper_id.is_none());
+                assert_eq!(style_file, Some(PathBuf::from("ieee.csl")));
+                assert_eq!(locale_file, Some(PathBuf::from("locales-de-DE.xml")));
+                assert_eq!(input, Some(PathBuf::from("refs.bib")));
+                assert_eq!(format, CitationOutputFormat::Html);
+            }
+            _ => panic!("Expected cite command"),
+        }
+
+        // A locale only applies to a CSL style
+        assert!(Cli::try_parse_from([
+            "research-master",
+            "cite",
+            "10.1038/nature14539",
+            "--locale-file",
+            "locales-de-DE.xml"
+        ])
+        .is_err());
+        // A paper ID or an input file, not both or neither
+        assert!(Cli::try_parse_from(["research-master", "cite", "x", "-i", "refs.bib"]).is_err());
+        assert!(Cli::try_parse_from(["research-master", "cite"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_author_profile_command() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "profile",
+            "Jane Smith",
+            "--sources",
+            "orcid,dblp",
+            "-m",
+            "5",
+        ]);
+        match cli.command {
+            Some(Commands::AuthorProfile {
+                query,
+                max_papers,
+                top,
+                sources,
+            }) => {
+                assert_eq!(query, "Jane Smith");
+                assert_eq!(max_papers, 5);
+                assert_eq!(top, 10);
+                assert_eq!(citation_source_ids(sources), vec!["orcid", "dblp"]);
+            }
+            _ => panic!("Expected author-profile command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_watch_commands() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "watch",
+            "add",
+            "agents",
+            "llm agents",
+            "--sources",
+            "arxiv,semantic",
+            "--sort-by",
+            "date",
+        ]);
+        match cli.command {
+            Some(Commands::Watch {
+                command:
+                    WatchCommands::Add {
+                        name,
+                        sources,
+                        max_results,
+                        sort_by,
+                        replace,
+                        ..
+                    },
+            }) => {
+                assert_eq!(name, "agents");
+                assert_eq!(citation_source_ids(sources), vec!["arxiv", "semantic"]);
+                assert_eq!(max_results, 20);
+                assert_eq!(sort_by.map(to_sort_by), Some(SortBy::Date));
+                assert!(!replace);
+            }
+            _ => panic!("Expected watch add command"),
+        }
+
+        let cli = Cli::parse_from([
+            "research-master",
+            "watch",
+            "run",
+            "agents",
+            "--atom",
+            "feed.xml",
+            "--export",
+            "vault/papers",
+            "--export-format",
+            "obsidian",
+            "--no-update",
+        ]);
+        match cli.command {
+            Some(Commands::Watch {
+                command:
+                    WatchCommands::Run {
+                        names,
+                        atom,
+                        markdown,
+                        export,
+                        export_format,
+                        no_update,
+                        ..
+                    },
+            }) => {
+                assert_eq!(names, vec!["agents"]);
+                assert_eq!(atom, Some(PathBuf::from("feed.xml")));
+                assert!(markdown.is_none());
+                assert_eq!(export, Some(PathBuf::from("vault/papers")));
+                assert_eq!(export_format, ExportFormat::Notes);
+                assert!(no_update);
+            }
+            _ => panic!("Expected watch run command"),
+        }
+        // --export-format only applies to --export
+        assert!(Cli::try_parse_from([
+            "research-master",
+            "watch",
+            "run",
+            "--export-format",
+            "mods"
+        ])
+        .is_err());
+
+        let cli = Cli::parse_from([
+            "research-master",
+            "watch",
+            "follow-author",
+            "jane",
+            "0000-0002-1825-0097",
+            "-m",
+            "20",
+        ]);
+        match cli.command {
+            Some(Commands::Watch {
+                command:
+                    WatchCommands::FollowAuthor {
+                        name,
+                        id,
+                        max_results,
+                        replace,
+                    },
+            }) => {
+                assert_eq!(name, "jane");
+                assert_eq!(id, "0000-0002-1825-0097");
+                assert_eq!(max_results, 20);
+                assert!(!replace);
+            }
+            _ => panic!("Expected watch follow-author command"),
+        }
+    }
+
     // References command tests
     #[test]
     fn test_cli_references_command() {
@@ -2919,6 +5486,17 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_cli_cache_stats_alias() {
+        let cli = Cli::parse_from(["research-master", "cache", "stats"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Cache {
+                command: CacheCommands::Status
+            })
+        ));
+    }
+
     #[test]
     fn test_cli_cache_clear() {
         let cli = Cli::parse_from(["research-master", "cache", "clear"]);
@@ -2952,6 +5530,114 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_cli_cache_inspect_and_invalidate() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "cache",
+            "inspect",
+            "--namespace",
+            "searches",
+            "--source",
+            "arxiv",
+        ]);
+        match cli.command {
+            Some(Commands::Cache {
+                command:
+                    CacheCommands::Inspect {
+                        namespace,
+                        source,
+                        key,
+                        limit,
+                    },
+            }) => {
+                assert_eq!(namespace, Some(CacheNamespaceArg::Searches));
+                assert_eq!(source.as_deref(), Some("arxiv"));
+                assert_eq!(key, None);
+                assert_eq!(limit, 50);
+            }
+            _ => panic!("Expected cache inspect command"),
+        }
+        assert!(
+            Cli::try_parse_from(["research-master", "cache", "inspect", "--key", "k"]).is_err()
+        );
+
+        let cli = Cli::parse_from([
+            "research-master",
+            "cache",
+            "invalidate",
+            "--source",
+            "openalex",
+            "--older-than",
+            "7d",
+        ]);
+        match cli.command {
+            Some(Commands::Cache {
+                command:
+                    CacheCommands::Invalidate {
+                        namespace,
+                        source,
+                        older_than,
+                        expired,
+                    },
+            }) => {
+                assert_eq!(namespace, None);
+                assert_eq!(source.as_deref(), Some("openalex"));
+                assert_eq!(older_than, Some(Duration::from_secs(7 * 86400)));
+                assert!(!expired);
+            }
+            _ => panic!("Expected cache invalidate command"),
+        }
+        assert!(Cli::try_parse_from([
+            "research-master",
+            "cache",
+            "invalidate",
+            "--older-than",
+            "7y"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_cli_cache_export_import() {
+        let cli = Cli::parse_from(["research-master", "cache", "export", "backup.db"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Cache {
+                command: CacheCommands::Export { ref path }
+            }) if path == &PathBuf::from("backup.db")
+        ));
+        let cli = Cli::parse_from(["research-master", "cache", "import", "backup.db"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Cache {
+                command: CacheCommands::Import { .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("45s"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_age("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_age("12h"), Ok(Duration::from_secs(43200)));
+        assert_eq!(parse_age("2w"), Ok(Duration::from_secs(14 * 86400)));
+        assert_eq!(parse_age("3"), Ok(Duration::from_secs(3 * 86400)));
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("5 years").is_err());
+    }
+
+    #[test]
+    fn test_cli_cache_clear_http() {
+        let cli = Cli::parse_from(["research-master", "cache", "clear-http"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Cache {
+                command: CacheCommands::ClearHttp
+            })
+        ));
+    }
+
     // Doctor command tests
     #[test]
     fn test_cli_doctor_command() {
@@ -2961,6 +5647,7 @@ mod tests {
                 check_connectivity,
                 check_api_keys,
                 verbose,
+                ..
             }) => {
                 assert!(!*check_connectivity);
                 assert!(!*check_api_keys);
@@ -2984,6 +5671,7 @@ mod tests {
                 check_connectivity,
                 check_api_keys,
                 verbose,
+                ..
             }) => {
                 assert!(*check_connectivity);
                 assert!(*check_api_keys);
@@ -2993,6 +5681,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_doctor_deep() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "doctor",
+            "--deep",
+            "--source",
+            "arxiv,pubmed",
+            "--junit",
+            "doctor.xml",
+            "--slow",
+            "5",
+        ]);
+        match &cli.command {
+            Some(Commands::Doctor {
+                deep,
+                source,
+                junit,
+                slow,
+                ..
+            }) => {
+                assert!(*deep);
+                assert_eq!(source, &vec!["arxiv".to_string(), "pubmed".to_string()]);
+                assert_eq!(junit.as_deref(), Some(std::path::Path::new("doctor.xml")));
+                assert_eq!(*slow, 5);
+            }
+            _ => panic!("Expected Doctor command"),
+        }
+
+        let cli = Cli::try_parse_from(["research-master", "doctor", "--junit", "doctor.xml"]);
+        assert!(cli.is_err());
+    }
+
     #[test]
     fn test_cli_doctor_alias() {
         let cli = Cli::parse_from(["research-master", "diag"]);
@@ -3148,6 +5869,13 @@ mod tests {
             "--dedup",
             "--dedup-strategy",
             "mark",
+            "--versions",
+            "published",
+            "--open-access-only",
+            "--license",
+            "cc-by,cc0",
+            "--enrich",
+            "abstract,citations",
         ]);
         match &cli.command {
             Some(Commands::Search {
@@ -3161,7 +5889,12 @@ mod tests {
                 author,
                 dedup,
                 dedup_strategy,
+                versions,
                 fetch_details,
+                open_access,
+                open_access_only,
+                license,
+                enrich,
             }) => {
                 assert_eq!(query, "transformer");
                 assert_eq!(*source, Source::Arxiv);
@@ -3173,7 +5906,51 @@ mod tests {
                 assert_eq!(author.clone(), Some("Vaswani".to_string()));
                 assert!(*dedup);
                 assert_eq!(*dedup_strategy, Some(DedupStrategy::Mark));
+                assert_eq!(*versions, Some(VersionChoice::Published));
                 assert!(*fetch_details); // Default is true
+                assert!(!*open_access);
+                assert!(*open_access_only);
+                assert_eq!(license, &vec!["cc-by".to_string(), "cc0".to_string()]);
+                assert_eq!(
+                    enrich,
+                    &Some(vec![MetadataField::Abstract, MetadataField::Citations])
+                );
+            }
+            _ => panic!("Expected Search command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_search_enrich_all_fields() {
+        let cli = Cli::parse_from(["research-master", "search", "transformer", "--enrich"]);
+        match cli.command {
+            Some(Commands::Search { enrich, .. }) => assert_eq!(enrich, Some(vec![])),
+            _ => panic!("Expected Search command"),
+        }
+
+        let cli = Cli::parse_from(["research-master", "search", "transformer"]);
+        match cli.command {
+            Some(Commands::Search { enrich, .. }) => assert!(enrich.is_none()),
+            _ => panic!("Expected Search command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_search_versions() {
+        let cli = Cli::parse_from([
+            "research-master",
+            "search",
+            "transformer",
+            "--versions",
+            "preprint",
+        ]);
+        match cli.command {
+            Some(Commands::Search { versions, .. }) => {
+                assert_eq!(versions, Some(VersionChoice::Preprint));
+                assert_eq!(
+                    VersionPreference::from(versions.unwrap()),
+                    VersionPreference::Preprint
+                );
             }
             _ => panic!("Expected Search command"),
         }
@@ -3210,10 +5987,11 @@ mod tests {
             Source::Osf,
             Source::Base,
             Source::Springer,
+            Source::Orcid,
             Source::GoogleScholar,
             Source::All,
         ];
-        assert_eq!(variants.len(), 29);
+        assert_eq!(variants.len(), 30);
     }
 
     #[test]
@@ -3246,6 +6024,7 @@ mod tests {
             (Source::Osf, "osf"),
             (Source::Base, "base"),
             (Source::Springer, "springer"),
+            (Source::Orcid, "orcid"),
             (Source::GoogleScholar, "google_scholar"),
         ];
         for (source, expected_id) in tests {
@@ -3285,6 +6064,7 @@ mod tests {
         assert_eq!(CapabilityFilter::Citations as i32, 3);
         assert_eq!(CapabilityFilter::DoiLookup as i32, 4);
         assert_eq!(CapabilityFilter::AuthorSearch as i32, 5);
+        assert_eq!(CapabilityFilter::AuthorProfiles as i32, 6);
     }
 
     // Download with all options
@@ -0,0 +1,1119 @@
+//! Typed arguments for the unified MCP tools.
+//!
+//! Every tool deserializes its arguments into one of the structs below. Unknown
+//! fields and mistyped values (e.g. `"max_results": "20"`) are rejected with an
+//! error naming the tool and the offending field instead of being silently
+//! replaced by defaults.
+//!
+//! The JSON Schemas advertised to clients are derived from the same structs, so
+//! the documented and the accepted arguments cannot drift apart.
+
+use std::sync::Arc;
+
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::output::Verbosity;
+use super::tools::ToolError;
+use crate::enrich::EnrichField;
+use crate::formats::ExportFormat;
+use crate::graph::{parse_year_range, Direction, GraphFormat};
+use crate::models::{SortBy, SortOrder, VersionKind};
+use crate::sources::Source;
+use crate::utils::DuplicateStrategy;
+use crate::versions::VersionPreference;
+
+/// Arguments accepted by an MCP tool
+pub trait ToolArgs: DeserializeOwned + JsonSchema {
+    /// Check constraints that cannot be expressed in the type itself
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// JSON Schema describing the arguments
+    fn schema() -> Value {
+        input_schema::<Self>()
+    }
+}
+
+/// Deserialize and validate the arguments for `tool`
+///
+/// Errors name the tool and the path of the offending field.
+pub fn parse_args<T: ToolArgs>(tool: &str, args: Value) -> Result<T, ToolError> {
+    // Clients may omit `arguments` entirely for tools without required fields
+    let args = if args.is_null() {
+        Value::Object(serde_json::Map::new())
+    } else {
+        args
+    };
+
+    let parsed: T = serde_path_to_error::deserialize(args)
+        .map_err(|e| {
+            let path = e.path().to_string();
+            if path == "." {
+                format!("Invalid arguments for '{}': {}", tool, e.inner())
+            } else {
+                format!("Invalid arguments for '{}': {}: {}", tool, path, e.inner())
+            }
+        })
+        .map_err(ToolError::InvalidParams)?;
+
+    parsed.validate().map_err(|e| {
+        ToolError::InvalidParams(format!("Invalid arguments for '{}': {}", tool, e))
+    })?;
+
+    Ok(parsed)
+}
+
+/// Check that `source_id` names an available source
+///
+/// Suggests the closest available source on a likely typo.
+pub fn check_source(sources: &[Arc<dyn Source>], source_id: &str) -> Result<(), ToolError> {
+    if sources.iter().any(|s| s.id() == source_id) {
+        return Ok(());
+    }
+
+    let closest = sources
+        .iter()
+        .map(|s| (strsim::levenshtein(s.id(), source_id), s.id()))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance);
+
+    let available: Vec<&str> = sources.iter().map(|s| s.id()).collect();
+    match closest {
+        Some((_, suggestion)) => Err(ToolError::InvalidParams(format!(
+            "Unknown source '{}'. Did you mean '{}'? Available sources: {}",
+            source_id,
+            suggestion,
+            available.join(", ")
+        ))),
+        None => Err(ToolError::InvalidParams(format!(
+            "Unknown source '{}'. Available sources: {}",
+            source_id,
+            available.join(", ")
+        ))),
+    }
+}
+
+/// Restrict the `source` property of an input schema to the given source IDs
+pub fn with_source_enum(mut schema: Value, source_ids: &[&str]) -> Value {
+    if let Some(source) = schema.pointer_mut("/properties/source") {
+        let mut ids = source_ids.to_vec();
+        ids.sort_unstable();
+        source["enum"] = Value::from(ids);
+    }
+    schema
+}
+
+/// Generate the JSON Schema for a tool's input
+pub fn input_schema<T: JsonSchema + ?Sized>() -> Value {
+    // Optional arguments are simply omitted, so there is no need to advertise `null`
+    let mut schema = generate_schema::<T>(false);
+    if let Some(object) = schema.as_object_mut() {
+        // Argument struct names mean nothing to clients
+        object.remove("title");
+    }
+    schema
+}
+
+/// Generate the JSON Schema for a tool's output
+pub fn output_schema<T: JsonSchema + ?Sized>() -> Value {
+    generate_schema::<T>(true)
+}
+
+fn generate_schema<T: JsonSchema + ?Sized>(nullable_options: bool) -> Value {
+    let generator = SchemaSettings::draft07()
+        .with(|s| {
+            s.option_add_null_type = nullable_options;
+            s.inline_subschemas = true;
+            s.meta_schema = None;
+        })
+        .into_generator();
+
+    let mut schema = serde_json::to_value(generator.into_root_schema_for::<T>())
+        .expect("JSON Schema is always serializable");
+
+    if let Some(object) = schema.as_object_mut() {
+        // The tool description already covers what the struct docs say
+        object.remove("description");
+    }
+    flatten_string_enums(&mut schema);
+    schema
+}
+
+/// Rewrite `oneOf` lists of single-value string enums as one plain `enum`
+///
+/// schemars emits `oneOf` for enums with documented variants, which many MCP
+/// clients do not render as a list of choices.
+fn flatten_string_enums(schema: &mut Value) {
+    match schema {
+        Value::Object(object) => {
+            let values: Option<Vec<Value>> = object
+                .get("oneOf")
+                .and_then(|v| v.as_array())
+                .and_then(|variants| variants.iter().map(single_string_enum).collect());
+
+            if let Some(values) = values {
+                object.remove("oneOf");
+                object.insert("type".to_string(), Value::from("string"));
+                object.insert("enum".to_string(), Value::Array(values));
+            }
+
+            object.values_mut().for_each(flatten_string_enums);
+        }
+        Value::Array(items) => items.iter_mut().for_each(flatten_string_enums),
+        _ => {}
+    }
+}
+
+/// The value of a `{"enum": ["value"]}` schema, if it has exactly one string value
+fn single_string_enum(variant: &Value) -> Option<Value> {
+    match variant.get("enum")?.as_array()?.as_slice() {
+        [value] if value.is_string() => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn default_max_results() -> usize {
+    10
+}
+
+fn default_citation_results() -> usize {
+    20
+}
+
+fn default_citation_source() -> Option<String> {
+    Some("semantic".to_string())
+}
+
+fn default_output_path() -> String {
+    "./downloads".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn check_max_results(max_results: usize) -> Result<(), String> {
+    if max_results == 0 {
+        return Err("max_results must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+fn check_handles_given(handles: &[String]) -> Result<(), String> {
+    if handles.is_empty() {
+        return Err("handles must not be empty".to_string());
+    }
+    Ok(())
+}
+
+fn check_not_empty(field: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("{} must not be empty", field));
+    }
+    Ok(())
+}
+
+/// Arguments for `search_papers`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SearchPapersArgs {
+    /// Search query string
+    pub query: String,
+
+    /// Specific source to search (e.g., 'arxiv', 'semantic', 'pubmed'). If not specified, searches all sources.
+    pub source: Option<String>,
+
+    /// Maximum number of results per source
+    #[serde(default = "default_max_results")]
+    #[schemars(range(min = 1))]
+    pub max_results: usize,
+
+    /// Year filter (e.g., '2020', '2018-2022', '2010-', '-2015')
+    pub year: Option<String>,
+
+    /// Category/subject filter
+    pub category: Option<String>,
+
+    /// Field to sort results by (ignored by sources that cannot sort)
+    pub sort_by: Option<SortBy>,
+
+    /// Sort direction
+    pub sort_order: Option<SortOrder>,
+
+    /// Look up each paper's open-access status, licence and version
+    #[serde(default)]
+    pub open_access: bool,
+
+    /// Return only papers that are free to read (implies open_access)
+    #[serde(default)]
+    pub open_access_only: bool,
+
+    /// Return only papers under one of these licences, e.g. ['cc-by'] (implies open_access)
+    #[serde(default)]
+    pub licenses: Vec<String>,
+
+    /// Fill in these fields from other sources when a result is missing them,
+    /// e.g. ['abstract', 'citations']; an empty list fills in every field
+    pub enrich: Option<Vec<EnrichField>>,
+
+    /// Group preprints with their published versions, returning 'published'
+    /// or 'preprint' with the other listed under it
+    pub versions: Option<VersionPreference>,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for SearchPapersArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_not_empty("query", &self.query)?;
+        check_max_results(self.max_results)
+    }
+}
+
+/// Arguments for `search_by_author`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SearchByAuthorArgs {
+    /// Author name
+    pub author: String,
+
+    /// Specific source to search. If not specified, searches all sources with author search capability.
+    pub source: Option<String>,
+
+    /// Maximum results per source
+    #[serde(default = "default_max_results")]
+    #[schemars(range(min = 1))]
+    pub max_results: usize,
+
+    /// Year filter (e.g., '2020', '2018-2022', '2010-', '-2015')
+    pub year: Option<String>,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for SearchByAuthorArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_not_empty("author", &self.author)?;
+        check_max_results(self.max_results)
+    }
+}
+
+/// Arguments for `get_paper`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetPaperArgs {
+    /// Paper identifier (e.g., '2301.12345', 'arXiv:2301.12345', 'PMC12345678') or working-set handle (e.g., '#3')
+    pub paper_id: String,
+
+    /// Override auto-detection and use specific source
+    pub source: Option<String>,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for GetPaperArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_not_empty("paper_id", &self.paper_id)
+    }
+}
+
+/// Arguments for `read_paper`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PaperIdArgs {
+    /// Paper identifier (e.g., '2301.12345', 'arXiv:2301.12345', 'PMC12345678') or working-set handle (e.g., '#3')
+    pub paper_id: String,
+
+    /// Override auto-detection and use specific source
+    pub source: Option<String>,
+}
+
+impl ToolArgs for PaperIdArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_not_empty("paper_id", &self.paper_id)
+    }
+}
+
+/// Arguments for `download_paper`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DownloadPaperArgs {
+    /// Paper identifier or working-set handle (e.g., '#3')
+    pub paper_id: String,
+
+    /// Override auto-detection and use specific source
+    pub source: Option<String>,
+
+    /// Save path for the PDF
+    #[serde(default = "default_output_path")]
+    pub output_path: String,
+
+    /// Auto-generate filename from paper title
+    #[serde(default = "default_true")]
+    pub auto_filename: bool,
+
+    /// Download this version of a working-set paper whose versions were
+    /// grouped: 'preprint' or 'published'
+    pub version: Option<VersionKind>,
+}
+
+impl ToolArgs for DownloadPaperArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_not_empty("paper_id", &self.paper_id)
+    }
+}
+
+/// Arguments for `get_citations` and `get_references`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CitationArgs {
+    /// Paper identifier or working-set handle (e.g., '#3')
+    pub paper_id: String,
+
+    /// Specific source (default: 'semantic')
+    #[serde(default = "default_citation_source")]
+    pub source: Option<String>,
+
+    /// Maximum results
+    #[serde(default = "default_citation_results")]
+    #[schemars(range(min = 1))]
+    pub max_results: usize,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for CitationArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_not_empty("paper_id", &self.paper_id)?;
+        check_max_results(self.max_results)
+    }
+}
+
+/// Arguments for `lookup_by_doi`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LookupByDoiArgs {
+    /// Digital Object Identifier (e.g., '10.48550/arXiv.2301.12345')
+    pub doi: String,
+
+    /// Specific source to query. If not specified, queries all sources with DOI lookup capability.
+    pub source: Option<String>,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for LookupByDoiArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_not_empty("doi", &self.doi)
+    }
+}
+
+/// Arguments for `deduplicate_papers`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeduplicatePapersArgs {
+    /// Array of paper objects
+    pub papers: Vec<crate::models::Paper>,
+
+    /// Deduplication strategy: 'first' (keep first), 'last' (keep last), or 'mark' (add is_duplicate flag)
+    #[serde(default)]
+    pub strategy: DuplicateStrategy,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for DeduplicatePapersArgs {}
+
+/// Arguments for `list_working_set`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ListWorkingSetArgs {
+    /// Only list pinned papers
+    #[serde(default)]
+    pub pinned_only: bool,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for ListWorkingSetArgs {}
+
+/// Arguments for `pin_papers`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PinPapersArgs {
+    /// Handles of the papers to pin (e.g., ['#3', '#5'])
+    pub handles: Vec<String>,
+
+    /// Unpin the papers instead
+    #[serde(default)]
+    pub unpin: bool,
+}
+
+impl ToolArgs for PinPapersArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_handles_given(&self.handles)
+    }
+}
+
+/// Arguments for `drop_papers`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DropPapersArgs {
+    /// Handles of the papers to drop (e.g., ['#3', '#5']), pinned or not
+    #[serde(default)]
+    pub handles: Vec<String>,
+
+    /// Drop every paper that is not pinned
+    #[serde(default)]
+    pub unpinned: bool,
+}
+
+impl ToolArgs for DropPapersArgs {
+    fn validate(&self) -> Result<(), String> {
+        if self.handles.is_empty() && !self.unpinned {
+            return Err("give either handles or unpinned: true".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Arguments for `export_working_set`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExportWorkingSetArgs {
+    /// Export format: 'bibtex' (default), 'ris', 'csl_json', 'endnote_xml', 'mods', 'csv', 'json', 'jsonl' or 'notes' (Markdown notes with YAML front matter; output_path is then a directory with one note per paper)
+    #[serde(default)]
+    pub format: ExportFormat,
+
+    /// Handles of the papers to export (default: the whole working set)
+    pub handles: Option<Vec<String>>,
+
+    /// Only export pinned papers
+    #[serde(default)]
+    pub pinned_only: bool,
+
+    /// Write the export to this file instead of returning it
+    pub output_path: Option<String>,
+}
+
+impl ToolArgs for ExportWorkingSetArgs {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(ref handles) = self.handles {
+            check_handles_given(handles)?;
+        }
+        if let Some(ref path) = self.output_path {
+            check_not_empty("output_path", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Deepest snowball walk accepted over MCP
+const MAX_SNOWBALL_DEPTH: usize = 3;
+
+/// Largest citation graph built over MCP
+const MAX_SNOWBALL_NODES: usize = 1000;
+
+fn default_snowball_depth() -> usize {
+    1
+}
+
+fn default_snowball_nodes() -> usize {
+    200
+}
+
+/// Arguments for `snowball`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SnowballArgs {
+    /// Seed papers: working-set handles (e.g., '#3'), DOIs, arXiv IDs, OpenAlex work IDs or Semantic Scholar IDs
+    pub seeds: Vec<String>,
+
+    /// Links to follow: 'forward' (citing papers), 'backward' (references) or 'both' (default)
+    #[serde(default)]
+    pub direction: Direction,
+
+    /// Number of hops from the seeds (1-3)
+    #[serde(default = "default_snowball_depth")]
+    #[schemars(range(min = 1, max = 3))]
+    pub depth: usize,
+
+    /// Maximum citations/references followed per paper and direction
+    #[serde(default = "default_citation_results")]
+    #[schemars(range(min = 1))]
+    pub max_per_paper: usize,
+
+    /// Stop once the graph has this many papers (at most 1000)
+    #[serde(default = "default_snowball_nodes")]
+    #[schemars(range(min = 1, max = 1000))]
+    pub max_nodes: usize,
+
+    /// Only add papers from these years (e.g., '2020', '2018-2022', '2010-', '-2015')
+    pub year: Option<String>,
+
+    /// Only add papers with at least this many citations
+    pub min_citations: Option<u32>,
+
+    /// Citation sources to use, in order of preference (default: semantic, openalex, dimensions)
+    pub sources: Option<Vec<String>>,
+
+    /// Graph format written to output_path: 'json' (default), 'graphml', 'gexf' or 'dot'
+    #[serde(default)]
+    pub format: GraphFormat,
+
+    /// Write the graph to this file
+    pub output_path: Option<String>,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for SnowballArgs {
+    fn validate(&self) -> Result<(), String> {
+        if self.seeds.is_empty() {
+            return Err("seeds must not be empty".to_string());
+        }
+        for seed in &self.seeds {
+            check_not_empty("seeds", seed)?;
+        }
+        if !(1..=MAX_SNOWBALL_DEPTH).contains(&self.depth) {
+            return Err(format!(
+                "depth must be between 1 and {}",
+                MAX_SNOWBALL_DEPTH
+            ));
+        }
+        if self.max_per_paper == 0 {
+            return Err("max_per_paper must be at least 1".to_string());
+        }
+        if !(1..=MAX_SNOWBALL_NODES).contains(&self.max_nodes) {
+            return Err(format!(
+                "max_nodes must be between 1 and {}",
+                MAX_SNOWBALL_NODES
+            ));
+        }
+        if let Some(ref year) = self.year {
+            parse_year_range(year)?;
+        }
+        if let Some(ref path) = self.output_path {
+            check_not_empty("output_path", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Most citing papers expanded by `coupling_analysis`
+const MAX_COUPLING_CITING: usize = 100;
+
+fn default_coupling_citing() -> usize {
+    30
+}
+
+/// Arguments for `coupling_analysis`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CouplingArgs {
+    /// Papers of the set: working-set handles (e.g., '#3') or paper IDs (default: the whole working set)
+    pub papers: Option<Vec<String>>,
+
+    /// When using the working set, only analyse pinned papers
+    #[serde(default)]
+    pub pinned_only: bool,
+
+    /// Maximum citations/references fetched per paper and direction
+    #[serde(default = "default_citation_results")]
+    #[schemars(range(min = 1))]
+    pub max_per_paper: usize,
+
+    /// Maximum citing papers whose references are fetched to find co-cited papers (at most 100)
+    #[serde(default = "default_coupling_citing")]
+    #[schemars(range(max = 100))]
+    pub max_citing: usize,
+
+    /// Number of missing papers to return
+    #[serde(default = "default_max_results")]
+    #[schemars(range(min = 1))]
+    pub top: usize,
+
+    /// Citation sources to use, in order of preference (default: semantic, openalex, dimensions)
+    pub sources: Option<Vec<String>>,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for CouplingArgs {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(ref papers) = self.papers {
+            if papers.is_empty() {
+                return Err("papers must not be empty".to_string());
+            }
+            for paper in papers {
+                check_not_empty("papers", paper)?;
+            }
+        }
+        if self.max_per_paper == 0 {
+            return Err("max_per_paper must be at least 1".to_string());
+        }
+        if self.max_citing > MAX_COUPLING_CITING {
+            return Err(format!(
+                "max_citing must be at most {}",
+                MAX_COUPLING_CITING
+            ));
+        }
+        if self.top == 0 {
+            return Err("top must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Most publications returned by `get_author`
+const MAX_AUTHOR_PAPERS: usize = 200;
+
+/// Arguments for `get_author`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAuthorArgs {
+    /// Author name, ORCID iD, or the id of a candidate returned by an ambiguous lookup
+    pub query: String,
+
+    /// Maximum publications to return, most cited first (at most 200)
+    #[serde(default = "default_citation_results")]
+    #[schemars(range(min = 1, max = 200))]
+    pub max_papers: usize,
+
+    /// Author profile sources to use, in order of preference (default: orcid, openalex, semantic, dblp)
+    pub sources: Option<Vec<String>>,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for GetAuthorArgs {
+    fn validate(&self) -> Result<(), String> {
+        check_not_empty("query", &self.query)?;
+        if !(1..=MAX_AUTHOR_PAPERS).contains(&self.max_papers) {
+            return Err(format!(
+                "max_papers must be between 1 and {}",
+                MAX_AUTHOR_PAPERS
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Arguments for `check_saved_searches`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CheckSavedSearchesArgs {
+    /// Saved searches and follows to run, by name (default: all)
+    pub names: Option<Vec<String>>,
+
+    /// Remember the papers found so they are not reported as new next time
+    #[serde(default = "default_true")]
+    pub update: bool,
+
+    /// How much of each paper to return: 'ids', 'brief' (default) or 'full' (includes abstracts)
+    #[serde(default)]
+    pub verbosity: Verbosity,
+}
+
+impl ToolArgs for CheckSavedSearchesArgs {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(ref names) = self.names {
+            for name in names {
+                check_not_empty("names", name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Built-in citation style for `format_citation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CitationStyle {
+    /// APA 7th edition
+    #[default]
+    Apa,
+    /// MLA 9th edition
+    Mla,
+    /// Chicago 17th edition (author-date)
+    Chicago,
+    /// BibTeX entry
+    Bibtex,
+}
+
+impl From<CitationStyle> for crate::utils::CitationStyle {
+    fn from(style: CitationStyle) -> Self {
+        match style {
+            CitationStyle::Apa => Self::Apa,
+            CitationStyle::Mla => Self::Mla,
+            CitationStyle::Chicago => Self::Chicago,
+            CitationStyle::Bibtex => Self::Bibtex,
+        }
+    }
+}
+
+/// Arguments for `format_citation`
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FormatCitationArgs {
+    /// Papers to cite: working-set handles (e.g., '#3') or paper IDs (default: the whole working set)
+    pub papers: Option<Vec<String>>,
+
+    /// When using the working set, only cite pinned papers
+    #[serde(default)]
+    pub pinned_only: bool,
+
+    /// Built-in style: 'apa' (default), 'mla', 'chicago' or 'bibtex'; ignored with style_file
+    #[serde(default)]
+    pub style: CitationStyle,
+
+    /// Path to a CSL style file (.csl), e.g. one from the Zotero style repository
+    pub style_file: Option<String>,
+
+    /// Path to a CSL locale file for terms and date formats (default: built-in en-US)
+    pub locale_file: Option<String>,
+
+    /// Markup of CSL output: 'text' (default) or 'html'
+    #[serde(default)]
+    pub format: crate::csl::OutputFormat,
+
+    /// Cite this version ('preprint' or 'published') of papers whose versions
+    /// were grouped; other papers are cited as they are
+    pub version: Option<VersionKind>,
+}
+
+impl ToolArgs for FormatCitationArgs {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(ref papers) = self.papers {
+            if papers.is_empty() {
+                return Err("papers must not be empty".to_string());
+            }
+            for paper in papers {
+                check_not_empty("papers", paper)?;
+            }
+        }
+        if let Some(ref path) = self.style_file {
+            check_not_empty("style_file", path)?;
+        }
+        if let Some(ref path) = self.locale_file {
+            check_not_empty("locale_file", path)?;
+            if self.style_file.is_none() {
+                return Err("locale_file requires style_file".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_applies_defaults() {
+        let args: SearchPapersArgs =
+            parse_args("search_papers", json!({"query": "transformers"})).unwrap();
+        assert_eq!(args.query, "transformers");
+        assert_eq!(args.max_results, 10);
+        assert!(args.source.is_none());
+
+        let args: CitationArgs = parse_args("get_citations", json!({"paper_id": "x"})).unwrap();
+        assert_eq!(args.source.as_deref(), Some("semantic"));
+        assert_eq!(args.max_results, 20);
+    }
+
+    #[test]
+    fn test_parse_rejects_wrong_type() {
+        let err = parse_args::<SearchPapersArgs>(
+            "search_papers",
+            json!({"query": "x", "max_results": "20"}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("search_papers"));
+        assert!(err.to_string().contains("max_results"));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_field() {
+        let err =
+            parse_args::<SearchPapersArgs>("search_papers", json!({"query": "x", "max_result": 5}))
+                .unwrap_err();
+        assert!(err.to_string().contains("unknown field `max_result`"));
+    }
+
+    #[test]
+    fn test_parse_missing_required_and_validation() {
+        let err = parse_args::<GetPaperArgs>("get_paper", Value::Null).unwrap_err();
+        assert!(err.to_string().contains("missing field `paper_id`"));
+
+        let err = parse_args::<SearchPapersArgs>(
+            "search_papers",
+            json!({"query": "x", "max_results": 0}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("max_results must be at least 1"));
+    }
+
+    #[test]
+    fn test_parse_verbosity() {
+        let args: GetPaperArgs = parse_args("get_paper", json!({"paper_id": "x"})).unwrap();
+        assert_eq!(args.verbosity, Verbosity::Brief);
+
+        let args: SearchPapersArgs =
+            parse_args("search_papers", json!({"query": "x", "verbosity": "ids"})).unwrap();
+        assert_eq!(args.verbosity, Verbosity::Ids);
+
+        let schema = SearchPapersArgs::schema();
+        assert_eq!(
+            schema["properties"]["verbosity"]["enum"],
+            json!(["ids", "brief", "full"])
+        );
+    }
+
+    #[test]
+    fn test_parse_open_access_filters() {
+        let args: SearchPapersArgs = parse_args(
+            "search_papers",
+            json!({"query": "x", "open_access_only": true, "licenses": ["cc-by"]}),
+        )
+        .unwrap();
+        assert!(!args.open_access);
+        assert!(args.open_access_only);
+        assert_eq!(args.licenses, vec!["cc-by"]);
+
+        let args: SearchPapersArgs = parse_args("search_papers", json!({"query": "x"})).unwrap();
+        assert!(!args.open_access_only && args.licenses.is_empty());
+        assert!(args.enrich.is_none());
+    }
+
+    #[test]
+    fn test_parse_enrich_fields() {
+        let args: SearchPapersArgs = parse_args(
+            "search_papers",
+            json!({"query": "x", "enrich": ["abstract", "venue"]}),
+        )
+        .unwrap();
+        assert_eq!(
+            args.enrich,
+            Some(vec![EnrichField::Abstract, EnrichField::Venue])
+        );
+
+        let err = parse_args::<SearchPapersArgs>(
+            "search_papers",
+            json!({"query": "x", "enrich": ["authors"]}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("authors"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_versions() {
+        let args: SearchPapersArgs = parse_args(
+            "search_papers",
+            json!({"query": "x", "versions": "preprint"}),
+        )
+        .unwrap();
+        assert_eq!(args.versions, Some(VersionPreference::Preprint));
+
+        let args: DownloadPaperArgs = parse_args(
+            "download_paper",
+            json!({"paper_id": "#1", "version": "published"}),
+        )
+        .unwrap();
+        assert_eq!(args.version, Some(VersionKind::Published));
+
+        let err = parse_args::<DownloadPaperArgs>(
+            "download_paper",
+            json!({"paper_id": "#1", "version": "draft"}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("draft"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_dedup_strategy() {
+        let args: DeduplicatePapersArgs = parse_args(
+            "deduplicate_papers",
+            json!({"papers": [], "strategy": "mark"}),
+        )
+        .unwrap();
+        assert_eq!(args.strategy, DuplicateStrategy::Mark);
+
+        let err = parse_args::<DeduplicatePapersArgs>(
+            "deduplicate_papers",
+            json!({"papers": [], "strategy": "newest"}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("strategy"));
+    }
+
+    #[test]
+    fn test_parse_working_set_args() {
+        let args: ExportWorkingSetArgs =
+            parse_args("export_working_set", json!({"format": "json"})).unwrap();
+        assert_eq!(args.format, ExportFormat::Json);
+        assert!(args.handles.is_none());
+        let args: ExportWorkingSetArgs = parse_args(
+            "export_working_set",
+            json!({"format": "notes", "output_path": "vault"}),
+        )
+        .unwrap();
+        assert_eq!(args.format, ExportFormat::Notes);
+
+        let err = parse_args::<DropPapersArgs>("drop_papers", json!({})).unwrap_err();
+        assert!(err.to_string().contains("handles or unpinned"));
+
+        let err = parse_args::<PinPapersArgs>("pin_papers", json!({"handles": []})).unwrap_err();
+        assert!(err.to_string().contains("handles must not be empty"));
+    }
+
+    #[test]
+    fn test_parse_snowball_args() {
+        let args: SnowballArgs = parse_args(
+            "snowball",
+            json!({"seeds": ["#1"], "direction": "backward", "format": "gexf"}),
+        )
+        .unwrap();
+        assert_eq!(args.direction, Direction::Backward);
+        assert_eq!(args.format, GraphFormat::Gexf);
+        assert_eq!(args.depth, 1);
+
+        let err = parse_args::<SnowballArgs>("snowball", json!({"seeds": ["#1"], "depth": 5}))
+            .unwrap_err();
+        assert!(err.to_string().contains("depth must be between 1 and 3"));
+
+        let err = parse_args::<SnowballArgs>("snowball", json!({"seeds": ["#1"], "year": "soon"}))
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid year"));
+    }
+
+    #[test]
+    fn test_parse_coupling_args() {
+        let args: CouplingArgs = parse_args("coupling_analysis", json!({})).unwrap();
+        assert!(args.papers.is_none());
+        assert_eq!(args.max_citing, 30);
+        assert_eq!(args.top, 10);
+
+        let err =
+            parse_args::<CouplingArgs>("coupling_analysis", json!({"papers": []})).unwrap_err();
+        assert!(err.to_string().contains("papers must not be empty"));
+
+        let err = parse_args::<CouplingArgs>("coupling_analysis", json!({"max_citing": 500}))
+            .unwrap_err();
+        assert!(err.to_string().contains("max_citing must be at most 100"));
+    }
+
+    #[test]
+    fn test_input_schema() {
+        let schema = SearchPapersArgs::schema();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(schema["required"], json!(["query"]));
+        assert_eq!(schema["properties"]["max_results"]["default"], 10);
+        assert_eq!(schema["properties"]["sort_by"]["enum"][0], "relevance");
+        assert!(schema.get("definitions").is_none());
+
+        let schema = DeduplicatePapersArgs::schema();
+        assert_eq!(
+            schema["properties"]["strategy"]["enum"],
+            json!(["first", "last", "mark"])
+        );
+    }
+
+    #[test]
+    fn test_with_source_enum() {
+        let schema = with_source_enum(PaperIdArgs::schema(), &["semantic", "arxiv"]);
+        assert_eq!(
+            schema["properties"]["source"]["enum"],
+            json!(["arxiv", "semantic"])
+        );
+    }
+
+    #[test]
+    fn test_output_schema_allows_null_options() {
+        let schema = output_schema::<crate::models::Paper>();
+        assert_eq!(
+            schema["properties"]["doi"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(schema["properties"]["source"]["type"], "string");
+    }
+
+    #[test]
+    fn test_parse_get_author_args() {
+        let args: GetAuthorArgs =
+            parse_args("get_author", json!({"query": "0000-0002-1825-0097"})).unwrap();
+        assert_eq!(args.max_papers, 20);
+        assert!(args.sources.is_none());
+
+        let err = parse_args::<GetAuthorArgs>("get_author", json!({"query": " "})).unwrap_err();
+        assert!(err.to_string().contains("query must not be empty"));
+        let err =
+            parse_args::<GetAuthorArgs>("get_author", json!({"query": "x", "max_papers": 500}))
+                .unwrap_err();
+        assert!(err.to_string().contains("max_papers"));
+    }
+
+    #[test]
+    fn test_parse_check_saved_searches_args() {
+        let args: CheckSavedSearchesArgs = parse_args("check_saved_searches", json!({})).unwrap();
+        assert!(args.names.is_none());
+        assert!(args.update);
+
+        let err =
+            parse_args::<CheckSavedSearchesArgs>("check_saved_searches", json!({"names": [""]}))
+                .unwrap_err();
+        assert!(err.to_string().contains("names must not be empty"));
+    }
+
+    #[test]
+    fn test_parse_format_citation_args() {
+        let args: FormatCitationArgs =
+            parse_args("format_citation", json!({"papers": ["#1"], "style": "mla"})).unwrap();
+        assert_eq!(args.style, CitationStyle::Mla);
+        assert_eq!(args.format, crate::csl::OutputFormat::Text);
+        assert!(args.style_file.is_none());
+
+        let args: FormatCitationArgs = parse_args(
+            "format_citation",
+            json!({"style_file": "ieee.csl", "locale_file": "de.xml", "format": "html"}),
+        )
+        .unwrap();
+        assert!(args.papers.is_none());
+        assert_eq!(args.format, crate::csl::OutputFormat::Html);
+
+        let err =
+            parse_args::<FormatCitationArgs>("format_citation", json!({"papers": []})).unwrap_err();
+        assert!(err.to_string().contains("papers must not be empty"));
+        let err =
+            parse_args::<FormatCitationArgs>("format_citation", json!({"locale_file": "de.xml"}))
+                .unwrap_err();
+        assert!(err.to_string().contains("locale_file requires style_file"));
+    }
+}
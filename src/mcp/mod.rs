@@ -8,6 +8,12 @@
 //! - [`McpServer`]: Main MCP server that can run in stdio or HTTP/SSE mode
 //! - [`ToolRegistry`]: Registry of available MCP tools
 //! - [`Tool`]: Tool descriptor with name, description, and handler
+//! - [`args`]: Typed tool arguments from which the tool schemas are derived
+//! - [`ToolOutput`]: Tool results with structured content and a Markdown view
+//! - [`session`]: Per-session working sets that give returned papers short handles
+//! - [`ClientQuotaMiddleware`]: Per-client rate limits and quotas for HTTP mode
+//! - [`peer`]: Front listener passing each HTTP client's address to the quotas
+//! - [`monitor`]: `/metrics` and `/healthz` endpoints for HTTP mode
 //!
 //! # Server Modes
 //!
@@ -48,9 +54,19 @@
 //!
 //! [pmcp]: https://docs.rs/pmcp
 
+pub mod args;
+pub mod monitor;
+pub mod output;
+pub mod peer;
+pub mod quota;
 pub mod server;
+pub mod session;
 mod tools;
 pub mod unified_tools;
 
+pub use monitor::{serve_monitoring, HealthReport};
+pub use output::{ToolOutput, Verbosity};
+pub use quota::{ClientQuota, ClientQuotaMiddleware};
 pub use server::McpServer;
+pub use session::{Session, SessionStore};
 pub use tools::{Tool, ToolRegistry};
@@ -0,0 +1,281 @@
+//! Monitoring endpoints for the HTTP MCP server.
+//!
+//! The MCP transport owns every route of its own listener, so `/metrics` and
+//! `/healthz` are served on a second address:
+//!
+//! - `GET /metrics`: [`Metrics::global`] in Prometheus text format
+//! - `GET /healthz`: a JSON [`HealthReport`] of every source, with status
+//!   `503 Service Unavailable` when all of them are failing
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use pmcp::Error;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+use crate::sources::SourceRegistry;
+use crate::utils::{scrub_urls, CircuitState, Metrics, SourceHealth};
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Health of the server and each of its sources
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// `ok`, `degraded` (some sources failing) or `unavailable` (all failing)
+    pub status: &'static str,
+
+    /// Seconds since the server started counting
+    pub uptime_secs: u64,
+
+    /// Every registered source, by ID
+    pub sources: Vec<SourceStatus>,
+}
+
+/// Health of one source
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceStatus {
+    /// Source ID
+    pub id: String,
+
+    /// `ok`, `failing` or `idle` (not called yet)
+    pub status: &'static str,
+
+    /// Number of calls
+    pub requests: u64,
+
+    /// Number of calls that failed because of the source
+    pub failures: u64,
+
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+
+    /// Circuit breaker state (`closed`, `open` or `half_open`), if the source has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circuit: Option<&'static str>,
+
+    /// When the last call succeeded (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<String>,
+
+    /// When the last failure happened (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error_at: Option<String>,
+
+    /// Message of the last failure, with API keys in URLs redacted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl SourceStatus {
+    fn new(id: &str, health: SourceHealth) -> Self {
+        let status = if health.is_failing() {
+            "failing"
+        } else if health.requests == 0 {
+            "idle"
+        } else {
+            "ok"
+        };
+        let (last_error_at, last_error) = match health.last_failure {
+            Some((at, message)) => (Some(rfc3339(at)), Some(scrub_urls(&message))),
+            None => (None, None),
+        };
+        Self {
+            id: id.to_string(),
+            status,
+            requests: health.requests,
+            failures: health.failures,
+            consecutive_failures: health.consecutive_failures,
+            circuit: health.circuit.map(|state| match state {
+                CircuitState::Closed => "closed",
+                CircuitState::Open => "open",
+                CircuitState::HalfOpen => "half_open",
+            }),
+            last_success: health.last_success.map(rfc3339),
+            last_error_at,
+            last_error,
+        }
+    }
+}
+
+impl HealthReport {
+    /// Report on the sources of `registry` from what `metrics` has seen
+    pub fn new(registry: &SourceRegistry, metrics: &Metrics) -> Self {
+        let mut health = metrics.source_health();
+        let mut ids: Vec<&str> = registry.ids().collect();
+        ids.sort_unstable();
+        let sources: Vec<SourceStatus> = ids
+            .into_iter()
+            .map(|id| SourceStatus::new(id, health.remove(id).unwrap_or_default()))
+            .collect();
+
+        let failing = sources.iter().filter(|s| s.status == "failing").count();
+        let status = if failing == 0 {
+            "ok"
+        } else if failing == sources.len() {
+            "unavailable"
+        } else {
+            "degraded"
+        };
+        Self {
+            status,
+            uptime_secs: metrics.uptime().as_secs(),
+            sources,
+        }
+    }
+
+    /// HTTP status the report is served with
+    pub fn http_status(&self) -> StatusCode {
+        if self.status == "unavailable" {
+            StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            StatusCode::OK
+        }
+    }
+}
+
+/// Serve `/metrics` and `/healthz` for the sources of `registry` on `addr`
+pub async fn serve_monitoring(
+    addr: &str,
+    registry: Arc<SourceRegistry>,
+) -> Result<(SocketAddr, JoinHandle<()>), Error> {
+    let socket_addr: SocketAddr = addr
+        .parse()
+        .map_err(|e| Error::invalid_params(format!("Invalid address: {}", e)))?;
+
+    let app = Router::new()
+        .route("/metrics", get(metrics))
+        .route("/healthz", get(healthz))
+        .with_state(registry);
+
+    let listener = tokio::net::TcpListener::bind(socket_addr)
+        .await
+        .map_err(|e| Error::internal(format!("Failed to bind {}: {}", socket_addr, e)))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| Error::internal(e.to_string()))?;
+    tracing::info!("Serving /metrics and /healthz on {}", local_addr);
+
+    let handle = tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!("Monitoring server failed: {}", e);
+        }
+    });
+    Ok((local_addr, handle))
+}
+
+async fn metrics() -> Response {
+    (
+        [(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+        Metrics::global().render(),
+    )
+        .into_response()
+}
+
+async fn healthz(State(registry): State<Arc<SourceRegistry>>) -> Response {
+    let report = HealthReport::new(&registry, Metrics::global());
+    (report.http_status(), Json(report)).into_response()
+}
+
+fn rfc3339(at: SystemTime) -> String {
+    DateTime::<Utc>::from(at).to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::{MockSource, SourceError};
+    use std::time::Duration;
+
+    #[test]
+    fn test_health_report() {
+        let metrics = Metrics::new();
+        let mut registry = SourceRegistry::empty();
+        registry.register(Arc::new(MockSource::new()));
+
+        let report = HealthReport::new(&registry, &metrics);
+        assert_eq!(report.status, "ok");
+        assert_eq!(report.sources[0].id, "mock");
+        assert_eq!(report.sources[0].status, "idle");
+
+        let failure = SourceError::Network("connection refused".into());
+        for _ in 0..3 {
+            metrics.record_source_call("mock", "search", Duration::ZERO, Some(&failure));
+        }
+        let report = HealthReport::new(&registry, &metrics);
+        assert_eq!(report.status, "unavailable");
+        assert_eq!(report.http_status(), StatusCode::SERVICE_UNAVAILABLE);
+        let source = &report.sources[0];
+        assert_eq!(source.status, "failing");
+        assert_eq!(source.consecutive_failures, 3);
+        assert_eq!(
+            source.last_error.as_deref(),
+            Some("Network error: connection refused")
+        );
+
+        metrics.record_source_call("mock", "search", Duration::ZERO, None);
+        let report = HealthReport::new(&registry, &metrics);
+        assert_eq!(report.status, "ok");
+        assert_eq!(report.http_status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_health_report_redacts_api_keys() {
+        let metrics = Metrics::new();
+        let mut registry = SourceRegistry::empty();
+        registry.register(Arc::new(MockSource::new()));
+
+        let failure = SourceError::Network(
+            "error sending request for url (https://ieeexploreapi.ieee.org/api/v1/search/articles?querytext=x&apikey=s3cr3t)".into(),
+        );
+        metrics.record_source_call("mock", "search", Duration::ZERO, Some(&failure));
+        let report = HealthReport::new(&registry, &metrics);
+        let message = report.sources[0].last_error.as_deref().unwrap();
+        assert!(message.contains("apikey=REDACTED"));
+        assert!(!message.contains("s3cr3t"));
+    }
+
+    #[tokio::test]
+    async fn test_serve_monitoring() {
+        let registry = Arc::new(SourceRegistry::empty());
+        let (addr, handle) = serve_monitoring("127.0.0.1:0", registry).await.unwrap();
+        Metrics::global().record_tool_call("search_papers", true);
+
+        let client = reqwest::Client::new();
+        let metrics = client
+            .get(format!("http://{}/metrics", addr))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(metrics.status(), StatusCode::OK);
+        assert!(metrics.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+        assert!(metrics
+            .text()
+            .await
+            .unwrap()
+            .contains("research_master_tool_calls_total{tool=\"search_papers\",result=\"ok\"}"));
+
+        let health: serde_json::Value = client
+            .get(format!("http://{}/healthz", addr))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(health["status"], "ok");
+
+        handle.abort();
+    }
+}
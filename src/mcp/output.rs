@@ -0,0 +1,1284 @@
+//! Tool result formatting.
+//!
+//! Tool handlers return a [`ToolOutput`]: the full JSON result for
+//! `structuredContent` plus a compact Markdown rendering for the text content,
+//! which is much cheaper for LLM clients to read than raw `Paper` objects.
+//! [`Verbosity`] controls how much of each paper is included in either view.
+//!
+//! pmcp only knows how to return text content, so the output is passed through
+//! pmcp as an envelope that [`StructuredResultTransport`] (stdio) and
+//! [`StructuredResultMiddleware`] (HTTP) unpack into a proper `CallToolResult`.
+//! Tool errors with a code of their own (such as invalid params) travel the
+//! same way, because pmcp reports every failed tool call as an internal error.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use pmcp::server::http_middleware::{ServerHttpContext, ServerHttpMiddleware, ServerHttpResponse};
+use pmcp::shared::{Transport, TransportMessage};
+use pmcp::types::jsonrpc::{JSONRPCError, ResponsePayload};
+use reqwest::StatusCode;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::tools::ToolError;
+use crate::formats::ExportFormat;
+use crate::graph::GraphFormat;
+use crate::models::{AuthorCandidate, NameCount, OpenAccess, Paper, PaperStatus, VersionKind};
+use crate::utils::format_age;
+use crate::versions::version_kind;
+use crate::watch::WatchKind;
+
+/// Key under which a [`ToolOutput`] is wrapped while it passes through pmcp
+const ENVELOPE_KEY: &str = "__research_master_output";
+
+/// Number of authors listed before "et al." in Markdown
+const MAX_LISTED_AUTHORS: usize = 3;
+
+/// How much of each paper a tool returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    /// Identifiers only (paper ID, source, DOI)
+    Ids,
+    /// Identifiers plus title, authors, year, venue, links and citation count
+    #[default]
+    Brief,
+    /// Every field, including abstracts and source-specific metadata
+    Full,
+}
+
+/// A paper trimmed to the requested verbosity
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PaperView {
+    /// Working-set handle (e.g. `#3`), usable as `paper_id` in other tools
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+
+    /// Source-specific paper identifier
+    pub paper_id: String,
+
+    /// ID of the source the paper came from
+    pub source: String,
+
+    /// Digital Object Identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+
+    /// Paper title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Authors (semicolon-separated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<String>,
+
+    /// Publication year
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<String>,
+
+    /// Journal or conference, when the source reports one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+
+    /// Paper page URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Direct PDF URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_url: Option<String>,
+
+    /// Whether the paper is free to read (or has a PDF, when its status is unknown)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_access: Option<bool>,
+
+    /// Open-access status, licence and version, when looked up
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access: Option<OpenAccess>,
+
+    /// Citation count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citations: Option<u32>,
+
+    /// Abstract text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#abstract: Option<String>,
+
+    /// Publication date (ISO format)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_date: Option<String>,
+
+    /// Last updated date (ISO format)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_date: Option<String>,
+
+    /// Categories/tags (semicolon-separated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<String>,
+
+    /// Keywords (semicolon-separated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<String>,
+
+    /// Reference IDs (semicolon-separated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub references: Option<String>,
+
+    /// Source-specific metadata
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra: Option<HashMap<String, Value>>,
+
+    /// Retraction, expression of concern or correction (at every verbosity)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<PaperStatus>,
+
+    /// When the paper was fetched, if it was served from the cache (offline
+    /// mode), as RFC 3339
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_at: Option<String>,
+
+    /// Whether the paper is pinned in the working set (only set by `list_working_set`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
+
+    /// Other versions of the work (its preprint or published version), when
+    /// versions were grouped; pick one with `download_paper`'s `version`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<VersionView>,
+}
+
+/// Another version of a paper
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct VersionView {
+    /// Preprint or published version, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<VersionKind>,
+
+    /// Source-specific paper identifier
+    pub paper_id: String,
+
+    /// ID of the source the version came from
+    pub source: String,
+
+    /// Digital Object Identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+
+    /// Direct PDF URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdf_url: Option<String>,
+}
+
+impl VersionView {
+    fn new(paper: &Paper) -> Self {
+        Self {
+            kind: version_kind(paper),
+            paper_id: paper.paper_id.clone(),
+            source: paper.source.id().to_string(),
+            doi: paper.doi.clone(),
+            pdf_url: paper.pdf_url.clone(),
+        }
+    }
+}
+
+impl PaperView {
+    /// Build a view of `paper` containing the fields for `verbosity`
+    pub fn new(paper: &Paper, verbosity: Verbosity) -> Self {
+        let mut view = Self {
+            handle: None,
+            paper_id: paper.paper_id.clone(),
+            source: paper.source.id().to_string(),
+            doi: paper.doi.clone(),
+            title: None,
+            authors: None,
+            year: None,
+            venue: None,
+            url: None,
+            pdf_url: None,
+            open_access: None,
+            access: None,
+            citations: None,
+            r#abstract: None,
+            published_date: None,
+            updated_date: None,
+            categories: None,
+            keywords: None,
+            references: None,
+            extra: None,
+            status: paper.status.clone(),
+            cached_at: paper.cached_at().map(|at| at.to_rfc3339()),
+            pinned: None,
+            versions: Vec::new(),
+        };
+
+        if verbosity == Verbosity::Ids {
+            return view;
+        }
+
+        view.title = Some(paper.title.clone());
+        view.authors = non_empty(&paper.authors);
+        view.year = paper_year(paper);
+        view.venue = paper_venue(paper);
+        view.url = non_empty(&paper.url);
+        view.pdf_url = paper.pdf_url.clone();
+        view.open_access = Some(match paper.open_access {
+            Some(ref access) => access.status.is_open(),
+            None => paper.has_pdf(),
+        });
+        view.access = paper.open_access.clone();
+        view.citations = paper.citations;
+        view.versions = paper.versions.iter().map(VersionView::new).collect();
+
+        if verbosity == Verbosity::Full {
+            view.r#abstract = non_empty(&paper.r#abstract);
+            view.published_date = paper.published_date.clone();
+            view.updated_date = paper.updated_date.clone();
+            view.categories = paper.categories.clone();
+            view.keywords = paper.keywords.clone();
+            view.references = paper.references.clone();
+            view.extra = paper.extra.clone();
+        }
+
+        view
+    }
+
+    /// Render the paper as a single Markdown list entry (without the number)
+    fn to_markdown(&self) -> String {
+        let id = format!("`{}:{}`", self.source, self.paper_id);
+        let handle = match self.handle {
+            Some(ref handle) => format!("[{}] ", handle),
+            None => String::new(),
+        };
+
+        let warning = match self.status {
+            Some(ref status) => format!(" · ⚠ {}", status.warning()),
+            None => String::new(),
+        };
+
+        let Some(ref title) = self.title else {
+            // Verbosity::Ids
+            return match self.doi {
+                Some(ref doi) => format!("{}{} (DOI: {}){}", handle, id, doi, warning),
+                None => format!("{}{}{}", handle, id, warning),
+            };
+        };
+
+        let mut line = format!("{}**{}**", handle, title.trim());
+        if let Some(ref authors) = self.authors {
+            line.push_str(" — ");
+            line.push_str(&short_authors(authors));
+        }
+        if let Some(ref year) = self.year {
+            line.push_str(&format!(" ({})", year));
+        }
+        line.push('.');
+        if let Some(ref venue) = self.venue {
+            line.push_str(&format!(" *{}*.", venue));
+        }
+        line.push(' ');
+        line.push_str(&id);
+        if let Some(ref doi) = self.doi {
+            line.push_str(&format!(" · DOI: {}", doi));
+        }
+        if let Some(citations) = self.citations {
+            line.push_str(&format!(" · {} citations", citations));
+        }
+        match self.access {
+            Some(ref access) if access.status.is_open() => {
+                line.push_str(&format!(" · OA ({})", access))
+            }
+            Some(_) => {}
+            None if self.open_access == Some(true) => line.push_str(" · OA"),
+            None => {}
+        }
+        if let Some(fetched) = self
+            .cached_at
+            .as_deref()
+            .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+        {
+            let age = (chrono::Utc::now() - fetched.to_utc())
+                .to_std()
+                .unwrap_or_default();
+            line.push_str(&format!(" · cached {} ago", format_age(age)));
+        }
+        if self.pinned == Some(true) {
+            line.push_str(" · pinned");
+        }
+        for version in &self.versions {
+            let kind = version.kind.map_or("other".to_string(), |k| k.to_string());
+            line.push_str(&format!(
+                " · {} version: `{}:{}`",
+                kind, version.source, version.paper_id
+            ));
+        }
+        line.push_str(&warning);
+
+        if let Some(ref abstract_text) = self.r#abstract {
+            line.push_str("\n   > ");
+            line.push_str(
+                &abstract_text
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+
+        line
+    }
+}
+
+/// A list of papers returned by a tool
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PaperList {
+    /// Number of papers returned
+    pub count: usize,
+
+    /// The papers, trimmed to the requested verbosity
+    pub papers: Vec<PaperView>,
+
+    /// Total number of matches reported by the source (may exceed `count`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_results: Option<usize>,
+
+    /// Whether the source has more results available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+}
+
+impl PaperList {
+    /// Build a list of paper views
+    pub fn new(papers: &[Paper], verbosity: Verbosity) -> Self {
+        Self {
+            count: papers.len(),
+            papers: papers
+                .iter()
+                .map(|p| PaperView::new(p, verbosity))
+                .collect(),
+            total_results: None,
+            has_more: None,
+        }
+    }
+
+    /// Render the list as numbered Markdown
+    pub fn to_markdown(&self) -> String {
+        if self.papers.is_empty() {
+            return "No papers found.".to_string();
+        }
+
+        let mut text = match self.total_results {
+            Some(total) if total > self.count => {
+                format!("Showing {} of {} papers:\n\n", self.count, total)
+            }
+            _ if self.count == 1 => "Found 1 paper:\n\n".to_string(),
+            _ => format!("Found {} papers:\n\n", self.count),
+        };
+
+        for (i, paper) in self.papers.iter().enumerate() {
+            text.push_str(&format!("{}. {}\n", i + 1, paper.to_markdown()));
+        }
+
+        if self.has_more == Some(true) {
+            text.push_str("\nMore results are available.\n");
+        }
+
+        text
+    }
+}
+
+/// The result of pinning, unpinning or dropping working-set papers
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WorkingSetUpdate {
+    /// Handles of the papers that were changed
+    pub handles: Vec<String>,
+
+    /// Papers left in the working set
+    pub remaining: usize,
+
+    /// Pinned papers in the working set
+    pub pinned: usize,
+}
+
+/// The result of exporting the working set
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ExportResult {
+    /// Export format
+    pub format: ExportFormat,
+
+    /// Number of papers exported
+    pub count: usize,
+
+    /// File (or notes directory) the export was written to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Notes not overwritten because they were edited since the last export
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub kept: Vec<String>,
+
+    /// The exported document, when not written to a file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// A cited paper
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CitationView {
+    /// Working-set handle of the paper
+    pub handle: String,
+
+    /// Cite key the bibliography entries are identified by
+    pub id: String,
+
+    /// In-text citation (CSL styles) or formatted reference (built-in styles)
+    pub citation: String,
+}
+
+/// The result of formatting citations
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CitationResult {
+    /// Style name
+    pub style: String,
+
+    /// One citation per paper, in the order given
+    pub citations: Vec<CitationView>,
+
+    /// Bibliography entries in the style's order (CSL styles with a bibliography)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bibliography: Vec<crate::csl::Entry>,
+}
+
+impl CitationResult {
+    /// The bibliography, or the citations when there is none
+    pub fn to_markdown(&self) -> String {
+        if self.bibliography.is_empty() {
+            self.citations
+                .iter()
+                .map(|c| format!("[{}] {}", c.handle, c.citation))
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        } else {
+            self.bibliography
+                .iter()
+                .map(|entry| entry.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        }
+    }
+}
+
+/// A citation link between two listed papers
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct GraphLink {
+    /// Handle of the citing paper
+    pub citing: String,
+
+    /// Handle of the cited paper
+    pub cited: String,
+}
+
+/// The result of a snowball walk
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SnowballResult {
+    /// Papers in the graph
+    pub nodes: usize,
+
+    /// Citation links in the graph
+    pub edges: usize,
+
+    /// Whether the walk stopped at max_nodes
+    pub truncated: bool,
+
+    /// Graph format
+    pub format: GraphFormat,
+
+    /// File the graph was written to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// The most connected papers, added to the working set
+    pub papers: PaperList,
+
+    /// Citation links among the listed papers
+    pub links: Vec<GraphLink>,
+}
+
+impl SnowballResult {
+    /// Render the summary and the most connected papers as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut text = format!(
+            "Citation graph: {} papers, {} links",
+            self.nodes, self.edges
+        );
+        if self.truncated {
+            text.push_str(" (stopped at max_nodes)");
+        }
+        if let Some(ref path) = self.path {
+            text.push_str(&format!(", written to `{}` ({})", path, self.format));
+        }
+        text.push_str(".\n\n");
+
+        if self.papers.papers.is_empty() {
+            return text;
+        }
+
+        text.push_str("Most connected papers:\n\n");
+        for (i, paper) in self.papers.papers.iter().enumerate() {
+            text.push_str(&format!("{}. {}\n", i + 1, paper.to_markdown()));
+        }
+        text
+    }
+}
+
+/// Co-citation and coupling strength between two papers of the set
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PairView {
+    /// Handle of the first paper
+    pub a: String,
+
+    /// Handle of the second paper
+    pub b: String,
+
+    /// Number of papers citing both
+    pub co_citations: usize,
+
+    /// Number of references the two share
+    pub coupling: usize,
+}
+
+/// A paper frequently cited alongside the set
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MissingPaperView {
+    /// The paper
+    #[serde(flatten)]
+    pub paper: PaperView,
+
+    /// Papers outside the set citing it together with a paper of the set
+    pub co_citations: usize,
+
+    /// Papers of the set citing it
+    pub cited_by_set: usize,
+}
+
+/// The result of a co-citation and bibliographic coupling analysis
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CouplingResult {
+    /// The analysed papers
+    pub papers: PaperList,
+
+    /// Co-cited or coupled pairs within the set, strongest first
+    pub pairs: Vec<PairView>,
+
+    /// Papers not in the set, most closely related first
+    pub missing: Vec<MissingPaperView>,
+}
+
+impl CouplingResult {
+    /// Render the pairs and missing papers as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut text = format!("Analysed {} papers.\n\n", self.papers.count);
+
+        if self.pairs.is_empty() {
+            text.push_str("No co-cited or coupled pairs in the set.\n\n");
+        } else {
+            text.push_str("Related pairs:\n\n");
+            for pair in &self.pairs {
+                text.push_str(&format!(
+                    "- {} ↔ {}: {} co-citations, {} shared references\n",
+                    pair.a, pair.b, pair.co_citations, pair.coupling
+                ));
+            }
+            text.push('\n');
+        }
+
+        if self.missing.is_empty() {
+            text.push_str("No papers found that are frequently cited alongside the set.\n");
+            return text;
+        }
+
+        text.push_str("Papers you may be missing:\n\n");
+        for (i, missing) in self.missing.iter().enumerate() {
+            text.push_str(&format!(
+                "{}. {} · co-cited {}×, cited by {} in set\n",
+                i + 1,
+                missing.paper.to_markdown(),
+                missing.co_citations,
+                missing.cited_by_set
+            ));
+        }
+        text
+    }
+}
+
+/// Whether an author lookup identified one person
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorStatus {
+    /// One person matched; `profile` and `papers` are set
+    Found,
+    /// Several people match; call `get_author` again with a candidate's `id`
+    Ambiguous,
+}
+
+/// An author profile merged across sources
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AuthorView {
+    /// Display name
+    pub name: String,
+
+    /// ORCID iD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
+
+    /// Affiliations across all sources
+    pub affiliations: Vec<String>,
+
+    /// Largest paper count reported by any source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paper_count: Option<u32>,
+
+    /// Largest citation count reported by any source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citation_count: Option<u32>,
+
+    /// h-index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub h_index: Option<u32>,
+
+    /// Most frequent venues in the publication list
+    pub top_venues: Vec<NameCount>,
+
+    /// Most frequent co-authors in the publication list
+    pub co_authors: Vec<NameCount>,
+
+    /// Source records merged into the profile (`<source>:<author id>`)
+    pub records: Vec<String>,
+}
+
+/// The result of an author lookup
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AuthorResult {
+    /// Whether one person was identified
+    pub status: AuthorStatus,
+
+    /// The profile, when found
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<AuthorView>,
+
+    /// Deduplicated publications, most cited first, added to the working set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub papers: Option<PaperList>,
+
+    /// People the name may refer to, when ambiguous
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<Vec<AuthorCandidate>>,
+}
+
+impl AuthorResult {
+    /// Render the profile, or the candidates to choose from, as Markdown
+    pub fn to_markdown(&self, query: &str) -> String {
+        let counts = |names: &[NameCount]| {
+            names
+                .iter()
+                .map(|n| format!("{} ({})", n.name, n.count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let optional = |value: Option<u32>| value.map_or("?".to_string(), |v| v.to_string());
+
+        let mut text = String::new();
+        if let Some(ref candidates) = self.candidates {
+            text.push_str(&format!(
+                "\"{}\" matches {} people. Call get_author again with the id of the one you mean:\n\n",
+                query,
+                candidates.len()
+            ));
+            for (i, candidate) in candidates.iter().enumerate() {
+                text.push_str(&format!(
+                    "{}. `{}` — {}",
+                    i + 1,
+                    candidate.id,
+                    candidate.name
+                ));
+                if !candidate.affiliations.is_empty() {
+                    text.push_str(&format!(" · {}", candidate.affiliations.join("; ")));
+                }
+                text.push_str(&format!(
+                    " · {} papers, {} citations\n",
+                    optional(candidate.paper_count),
+                    optional(candidate.citation_count)
+                ));
+            }
+            return text;
+        }
+
+        let Some(ref profile) = self.profile else {
+            return text;
+        };
+        text.push_str(&format!("**{}**", profile.name));
+        if let Some(ref orcid) = profile.orcid {
+            text.push_str(&format!(" (ORCID {})", orcid));
+        }
+        text.push('\n');
+        if !profile.affiliations.is_empty() {
+            text.push_str(&format!(
+                "Affiliations: {}\n",
+                profile.affiliations.join("; ")
+            ));
+        }
+        text.push_str(&format!(
+            "Papers: {} · Citations: {} · h-index: {}\n",
+            optional(profile.paper_count),
+            optional(profile.citation_count),
+            optional(profile.h_index)
+        ));
+        if !profile.top_venues.is_empty() {
+            text.push_str(&format!("Top venues: {}\n", counts(&profile.top_venues)));
+        }
+        if !profile.co_authors.is_empty() {
+            text.push_str(&format!(
+                "Frequent co-authors: {}\n",
+                counts(&profile.co_authors)
+            ));
+        }
+        text.push_str(&format!("Records: {}\n", profile.records.join(", ")));
+
+        if let Some(ref papers) = self.papers {
+            if !papers.papers.is_empty() {
+                text.push_str("\nPublications:\n\n");
+                for (i, paper) in papers.papers.iter().enumerate() {
+                    text.push_str(&format!("{}. {}\n", i + 1, paper.to_markdown()));
+                }
+            }
+        }
+        text
+    }
+}
+
+/// New papers found by one saved search or follow
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SavedSearchView {
+    /// Saved search or follow name
+    pub name: String,
+
+    /// 'search', 'author' (new publications) or 'paper' (new citing papers)
+    pub kind: WatchKind,
+
+    /// Search query, or the followed author or paper
+    pub query: String,
+
+    /// Whether this was the first run (every paper is new)
+    pub first_run: bool,
+
+    /// Papers returned by this run after deduplication
+    pub total: usize,
+
+    /// Papers not returned by any earlier run, added to the working set
+    pub new_papers: PaperList,
+
+    /// Sources that failed, as `<source>: <error>`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// The result of re-running saved searches and follows
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SavedSearchesResult {
+    /// When the searches were run (RFC 3339)
+    pub run_at: String,
+
+    /// Number of new papers across all searches
+    pub new_count: usize,
+
+    /// One digest per saved search and follow
+    pub searches: Vec<SavedSearchView>,
+}
+
+impl SavedSearchesResult {
+    /// Render the digest as Markdown
+    pub fn to_markdown(&self) -> String {
+        let follows = self
+            .searches
+            .iter()
+            .filter(|s| s.kind != WatchKind::Search)
+            .count();
+        let mut text = format!(
+            "{} new paper{} across {} saved search{} and {} follow{}.\n",
+            self.new_count,
+            if self.new_count == 1 { "" } else { "s" },
+            self.searches.len() - follows,
+            if self.searches.len() - follows == 1 {
+                ""
+            } else {
+                "es"
+            },
+            follows,
+            if follows == 1 { "" } else { "s" }
+        );
+        for search in &self.searches {
+            let what = match search.kind {
+                WatchKind::Search => format!("Query: `{}`", search.query),
+                WatchKind::Author => format!("Publications by {}", search.query),
+                WatchKind::Paper => format!("Papers citing {}", search.query),
+            };
+            text.push_str(&format!(
+                "\n### {} ({} new of {})\n\n{}{}\n",
+                search.name,
+                search.new_papers.count,
+                search.total,
+                what,
+                if search.first_run {
+                    " · first run"
+                } else {
+                    ""
+                }
+            ));
+            for error in &search.errors {
+                text.push_str(&format!("\nFailed: {}\n", error));
+            }
+            if !search.new_papers.papers.is_empty() {
+                text.push('\n');
+                for (i, paper) in search.new_papers.papers.iter().enumerate() {
+                    text.push_str(&format!("{}. {}\n", i + 1, paper.to_markdown()));
+                }
+            }
+        }
+        text
+    }
+}
+
+/// The result of a tool call: structured JSON plus a Markdown view of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolOutput {
+    /// Full result, returned as `structuredContent`
+    pub structured: Value,
+
+    /// Compact Markdown summary, returned as text content
+    pub text: String,
+}
+
+impl ToolOutput {
+    /// Create an output from a serializable result and its Markdown rendering
+    pub fn new(structured: impl Serialize, text: impl Into<String>) -> Result<Self, ToolError> {
+        Ok(Self {
+            structured: serde_json::to_value(structured).map_err(|e| e.to_string())?,
+            text: text.into(),
+        })
+    }
+
+    /// Output for a list of papers
+    pub fn papers(list: PaperList) -> Result<Self, ToolError> {
+        let text = list.to_markdown();
+        Self::new(list, text)
+    }
+
+    /// Output for a single paper
+    pub fn paper(view: PaperView) -> Result<Self, ToolError> {
+        let text = view.to_markdown();
+        Self::new(view, text)
+    }
+
+    /// Wrap the output so it survives pmcp's conversion to text content
+    pub fn to_envelope(&self) -> Value {
+        json!({
+            ENVELOPE_KEY: {
+                "text": self.text,
+                "structuredContent": self.structured,
+            }
+        })
+    }
+}
+
+/// Wrap a tool error so it survives pmcp's conversion of failed tool calls
+///
+/// The error is returned to pmcp as a successful result and turned back into
+/// a JSON-RPC error by [`unpack_tool_error`].
+pub fn error_envelope(error: pmcp::Error) -> Value {
+    json!({
+        ENVELOPE_KEY: {
+            "error": JSONRPCError::from(error),
+        }
+    })
+}
+
+/// The envelope inside a `tools/call` result, if there is one
+fn envelope(result: &Value) -> Option<serde_json::Map<String, Value>> {
+    let text = match result.get("content").and_then(|c| c.as_array()) {
+        Some(content) if content.len() == 1 => content[0]
+            .get("text")
+            .and_then(|t| t.as_str())
+            .filter(|t| t.contains(ENVELOPE_KEY))?,
+        _ => return None,
+    };
+
+    let Ok(Value::Object(mut envelope)) = serde_json::from_str::<Value>(text) else {
+        return None;
+    };
+    match envelope.remove(ENVELOPE_KEY) {
+        Some(Value::Object(output)) => Some(output),
+        _ => None,
+    }
+}
+
+/// The enveloped JSON-RPC error inside a `tools/call` result, if there is one
+pub fn unpack_tool_error(result: &Value) -> Option<JSONRPCError> {
+    let error = envelope(result)?.remove("error")?;
+    serde_json::from_value(error).ok()
+}
+
+/// Unpack an enveloped [`ToolOutput`] inside a `tools/call` result
+///
+/// Rewrites `result` into `{"content": [{"type": "text", "text": ...}],
+/// "structuredContent": ...}`. Returns whether the result was rewritten.
+pub fn unpack_tool_result(result: &mut Value) -> bool {
+    let Some(mut output) = envelope(result).filter(|o| !o.contains_key("error")) else {
+        return false;
+    };
+
+    let text = output.remove("text").unwrap_or(Value::Null);
+    let structured = output.remove("structuredContent").unwrap_or(Value::Null);
+    let is_error = result.get("isError").cloned().unwrap_or(Value::Bool(false));
+
+    *result = json!({
+        "content": [{"type": "text", "text": text}],
+        "structuredContent": structured,
+        "isError": is_error,
+    });
+    true
+}
+
+/// Transport wrapper that unpacks [`ToolOutput`] envelopes in outgoing responses
+///
+/// Enveloped tool errors are sent as JSON-RPC errors.
+#[derive(Debug)]
+pub struct StructuredResultTransport<T> {
+    inner: T,
+}
+
+impl<T> StructuredResultTransport<T> {
+    /// Wrap a transport
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for StructuredResultTransport<T> {
+    async fn send(&mut self, mut message: TransportMessage) -> pmcp::Result<()> {
+        if let TransportMessage::Response(ref mut response) = message {
+            if let ResponsePayload::Result(ref mut result) = response.payload {
+                match unpack_tool_error(result) {
+                    Some(error) => response.payload = ResponsePayload::Error(error),
+                    None => {
+                        unpack_tool_result(result);
+                    }
+                }
+            }
+        }
+        self.inner.send(message).await
+    }
+
+    async fn receive(&mut self) -> pmcp::Result<TransportMessage> {
+        self.inner.receive().await
+    }
+
+    async fn close(&mut self) -> pmcp::Result<()> {
+        self.inner.close().await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+
+    fn transport_type(&self) -> &'static str {
+        self.inner.transport_type()
+    }
+}
+
+/// HTTP middleware that unpacks [`ToolOutput`] envelopes in JSON-RPC responses
+///
+/// Enveloped tool errors are sent as JSON-RPC errors.
+#[derive(Debug, Default)]
+pub struct StructuredResultMiddleware;
+
+#[async_trait]
+impl ServerHttpMiddleware for StructuredResultMiddleware {
+    async fn on_response(
+        &self,
+        response: &mut ServerHttpResponse,
+        _context: &ServerHttpContext,
+    ) -> pmcp::Result<()> {
+        if response.status != StatusCode::OK {
+            return Ok(());
+        }
+
+        let Ok(mut body) = serde_json::from_slice::<Value>(&response.body) else {
+            return Ok(());
+        };
+
+        if let Some(error) = body.get("result").and_then(unpack_tool_error) {
+            if let Some(body) = body.as_object_mut() {
+                body.remove("result");
+                body.insert("error".to_string(), json!(error));
+            }
+            response.body = serde_json::to_vec(&body)?;
+        } else if body.get_mut("result").is_some_and(unpack_tool_result) {
+            response.body = serde_json::to_vec(&body)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Shorten a semicolon-separated author list for Markdown
+fn short_authors(authors: &str) -> String {
+    let names: Vec<&str> = authors
+        .split(';')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .collect();
+
+    if names.len() > MAX_LISTED_AUTHORS {
+        format!("{} et al.", names[..MAX_LISTED_AUTHORS].join(", "))
+    } else {
+        names.join(", ")
+    }
+}
+
+fn paper_year(paper: &Paper) -> Option<String> {
+    paper
+        .published_date
+        .as_deref()
+        .and_then(|d| d.get(..4))
+        .filter(|y| y.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+fn paper_venue(paper: &Paper) -> Option<String> {
+    paper.venue().map(str::to_string)
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType, StatusKind};
+
+    fn paper() -> Paper {
+        PaperBuilder::new(
+            "2301.00001",
+            "Attention Is All You Need",
+            "https://arxiv.org/abs/2301.00001",
+            SourceType::Arxiv,
+        )
+        .authors("Ashish Vaswani; Noam Shazeer; Niki Parmar; Jakob Uszkoreit")
+        .abstract_text("A long abstract.")
+        .doi("10.1000/xyz")
+        .published_date("2017-06-12")
+        .pdf_url("https://arxiv.org/pdf/2301.00001")
+        .citations(100)
+        .extra("venue", json!("NeurIPS"))
+        .build()
+    }
+
+    #[test]
+    fn test_paper_view_verbosity() {
+        let paper = paper();
+
+        let ids = serde_json::to_value(PaperView::new(&paper, Verbosity::Ids)).unwrap();
+        assert_eq!(
+            ids,
+            json!({"paper_id": "2301.00001", "source": "arxiv", "doi": "10.1000/xyz"})
+        );
+
+        let brief = PaperView::new(&paper, Verbosity::Brief);
+        assert_eq!(brief.year.as_deref(), Some("2017"));
+        assert_eq!(brief.venue.as_deref(), Some("NeurIPS"));
+        assert_eq!(brief.open_access, Some(true));
+        assert!(brief.r#abstract.is_none());
+
+        let full = PaperView::new(&paper, Verbosity::Full);
+        assert_eq!(full.r#abstract.as_deref(), Some("A long abstract."));
+        assert!(full.extra.is_some());
+    }
+
+    #[test]
+    fn test_paper_list_markdown() {
+        let list = PaperList::new(&[paper()], Verbosity::Brief);
+        let text = list.to_markdown();
+        assert!(text.starts_with("Found 1 paper:"));
+        assert!(text.contains("1. **Attention Is All You Need** — Ashish Vaswani, Noam Shazeer, Niki Parmar et al. (2017). *NeurIPS*."));
+        assert!(text.contains("`arxiv:2301.00001`"));
+        assert!(text.contains("· OA"));
+        assert!(!text.contains("A long abstract."));
+
+        let mut list = PaperList::new(&[paper()], Verbosity::Ids);
+        assert!(list
+            .to_markdown()
+            .contains("1. `arxiv:2301.00001` (DOI: 10.1000/xyz)"));
+        list.papers[0].handle = Some("#4".to_string());
+        assert!(list
+            .to_markdown()
+            .contains("1. [#4] `arxiv:2301.00001` (DOI: 10.1000/xyz)"));
+
+        assert_eq!(
+            PaperList::new(&[], Verbosity::Brief).to_markdown(),
+            "No papers found."
+        );
+    }
+
+    #[test]
+    fn test_paper_view_warns_about_retraction() {
+        let mut paper = paper();
+        paper.annotate(PaperStatus::new(StatusKind::Retracted, "crossref").date("2020-06-05"));
+
+        let ids = PaperList::new(std::slice::from_ref(&paper), Verbosity::Ids);
+        assert_eq!(
+            ids.papers[0].status.as_ref().map(|s| s.kind),
+            Some(StatusKind::Retracted)
+        );
+        assert!(ids
+            .to_markdown()
+            .contains("(DOI: 10.1000/xyz) · ⚠ RETRACTED (crossref, 2020-06-05)"));
+        assert!(PaperList::new(&[paper], Verbosity::Brief)
+            .to_markdown()
+            .contains("· OA · ⚠ RETRACTED (crossref, 2020-06-05)"));
+    }
+
+    #[test]
+    fn test_paper_view_shows_open_access() {
+        let mut paper = paper();
+        paper.open_access = Some(
+            OpenAccess::new(crate::models::OaStatus::Gold, "openalex")
+                .license("cc-by")
+                .version(Some(crate::models::OaVersion::Published)),
+        );
+        let list = PaperList::new(std::slice::from_ref(&paper), Verbosity::Brief);
+        assert_eq!(list.papers[0].open_access, Some(true));
+        assert!(list
+            .to_markdown()
+            .contains("· OA (gold, cc-by, published version)"));
+
+        paper.open_access = Some(OpenAccess::new(
+            crate::models::OaStatus::Closed,
+            "unpaywall",
+        ));
+        let list = PaperList::new(&[paper], Verbosity::Brief);
+        assert_eq!(list.papers[0].open_access, Some(false));
+        assert!(!list.to_markdown().contains("· OA"));
+    }
+
+    #[test]
+    fn test_paper_view_lists_versions() {
+        let mut paper = paper();
+        paper.versions = vec![PaperBuilder::new(
+            "2401.00001",
+            "Preprint",
+            "https://arxiv.org/abs/2401.00001",
+            SourceType::Arxiv,
+        )
+        .pdf_url("https://arxiv.org/pdf/2401.00001")
+        .build()];
+
+        let list = PaperList::new(std::slice::from_ref(&paper), Verbosity::Brief);
+        let version = &list.papers[0].versions[0];
+        assert_eq!(version.kind, Some(VersionKind::Preprint));
+        assert_eq!(version.source, "arxiv");
+        assert!(list
+            .to_markdown()
+            .contains("· preprint version: `arxiv:2401.00001`"));
+
+        let list = PaperList::new(&[paper], Verbosity::Ids);
+        assert!(list.papers[0].versions.is_empty());
+    }
+
+    #[test]
+    fn test_paper_view_marks_cached_papers() {
+        let mut paper = paper();
+        assert!(!PaperList::new(std::slice::from_ref(&paper), Verbosity::Brief)
+            .to_markdown()
+            .contains("cached"));
+
+        paper.mark_cached(chrono::Utc::now() - chrono::Duration::hours(3));
+        assert!(PaperView::new(&paper, Verbosity::Ids).cached_at.is_some());
+        assert!(PaperView::new(&paper, Verbosity::Brief)
+            .to_markdown()
+            .contains("· cached 3h ago"));
+    }
+
+    #[test]
+    fn test_unpack_tool_result() {
+        let output = ToolOutput::paper(PaperView::new(&paper(), Verbosity::Ids)).unwrap();
+        let mut result = json!({
+            "content": [{"type": "text", "text": output.to_envelope().to_string()}],
+            "isError": false
+        });
+
+        assert!(unpack_tool_result(&mut result));
+        assert_eq!(result["content"][0]["type"], "text");
+        assert_eq!(result["content"][0]["text"], output.text);
+        assert_eq!(result["structuredContent"], output.structured);
+
+        // Plain results are left untouched
+        let mut plain = json!({"content": [{"type": "text", "text": "{\"a\":1}"}]});
+        assert!(!unpack_tool_result(&mut plain));
+        assert_eq!(plain["content"][0]["text"], "{\"a\":1}");
+    }
+
+    #[tokio::test]
+    async fn test_middleware_unpacks_http_response() {
+        let output = ToolOutput::new(json!({"count": 0}), "No papers found.").unwrap();
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {"content": [{"type": "text", "text": output.to_envelope().to_string()}]}
+        });
+        let mut response = ServerHttpResponse::new(
+            StatusCode::OK,
+            http::HeaderMap::new(),
+            serde_json::to_vec(&body).unwrap(),
+        );
+
+        StructuredResultMiddleware
+            .on_response(&mut response, &ServerHttpContext::new("r1".to_string()))
+            .await
+            .unwrap();
+
+        let body: Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body["result"]["structuredContent"], json!({"count": 0}));
+        assert_eq!(body["result"]["content"][0]["text"], "No papers found.");
+    }
+
+    #[tokio::test]
+    async fn test_middleware_unpacks_tool_errors() {
+        let envelope = error_envelope(pmcp::Error::invalid_params("Unknown source 'arxv'"));
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {"content": [{"type": "text", "text": envelope.to_string()}]}
+        });
+        let mut response = ServerHttpResponse::new(
+            StatusCode::OK,
+            http::HeaderMap::new(),
+            serde_json::to_vec(&body).unwrap(),
+        );
+
+        StructuredResultMiddleware
+            .on_response(&mut response, &ServerHttpContext::new("r1".to_string()))
+            .await
+            .unwrap();
+
+        let body: Value = serde_json::from_slice(&response.body).unwrap();
+        assert!(body.get("result").is_none());
+        assert_eq!(
+            body["error"]["code"],
+            json!(pmcp::ErrorCode::INVALID_PARAMS.0)
+        );
+        assert_eq!(body["error"]["message"], "Unknown source 'arxv'");
+    }
+}
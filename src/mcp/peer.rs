@@ -0,0 +1,166 @@
+//! Front listener telling the MCP HTTP server who is calling.
+//!
+//! pmcp's HTTP transport binds its own listener and does not pass the peer
+//! address to middleware, so per-client quotas would have to trust whatever
+//! the caller claims. When quotas are on, the MCP server instead listens on a
+//! loopback port, and this listener forwards every request to it with the
+//! caller's address in [`PEER_HEADER`], replacing any value the caller sent.
+//! Responses, including SSE streams, are passed back as they arrive.
+
+use std::net::SocketAddr;
+
+use axum::body::{Body, Bytes};
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use futures_util::stream;
+use pmcp::Error;
+use tokio::task::JoinHandle;
+
+/// Header carrying the IP address the request came from
+pub const PEER_HEADER: &str = "x-research-master-peer";
+
+/// Largest request body forwarded
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Headers that apply to one connection and are not forwarded
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+#[derive(Debug, Clone)]
+struct Front {
+    backend: SocketAddr,
+    client: reqwest::Client,
+}
+
+/// Listen on `addr`, forwarding every request to the MCP server on `backend`
+pub async fn serve_front(
+    addr: SocketAddr,
+    backend: SocketAddr,
+) -> Result<(SocketAddr, JoinHandle<()>), Error> {
+    let client = reqwest::Client::builder()
+        .no_proxy()
+        .build()
+        .map_err(|e| Error::internal(e.to_string()))?;
+    let app = Router::new()
+        .fallback(forward)
+        .with_state(Front { backend, client });
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| Error::internal(format!("Failed to bind {}: {}", addr, e)))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| Error::internal(e.to_string()))?;
+
+    let handle = tokio::spawn(async move {
+        let app = app.into_make_service_with_connect_info::<SocketAddr>();
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!("MCP front listener failed: {}", e);
+        }
+    });
+    Ok((local_addr, handle))
+}
+
+async fn forward(
+    State(front): State<Front>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => return (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()).into_response(),
+    };
+
+    let mut headers = parts.headers;
+    for name in HOP_BY_HOP {
+        headers.remove(*name);
+    }
+    headers.remove(header::HOST);
+    let peer_ip =
+        HeaderValue::from_str(&peer.ip().to_string()).expect("IP addresses are valid headers");
+    headers.insert(PEER_HEADER, peer_ip);
+
+    let path = parts.uri.path_and_query().map_or("/", |p| p.as_str());
+    let upstream = front
+        .client
+        .request(parts.method, format!("http://{}{}", front.backend, path))
+        .headers(headers)
+        .body(body)
+        .send()
+        .await;
+    let upstream = match upstream {
+        Ok(upstream) => upstream,
+        Err(e) => return (StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+    };
+
+    let mut response = Response::builder().status(upstream.status());
+    for (name, value) in upstream.headers() {
+        if !HOP_BY_HOP.contains(&name.as_str()) {
+            response = response.header(name, value);
+        }
+    }
+    // Pass chunks on as they arrive so SSE streams keep flowing
+    let chunks = stream::unfold(Some(upstream), |upstream| async move {
+        let mut upstream = upstream?;
+        match upstream.chunk().await {
+            Ok(Some(chunk)) => Some((Ok::<Bytes, reqwest::Error>(chunk), Some(upstream))),
+            Ok(None) => None,
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+    response
+        .body(Body::from_stream(chunks))
+        .unwrap_or_else(|e| (StatusCode::BAD_GATEWAY, e.to_string()).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+
+    #[tokio::test]
+    async fn test_front_replaces_peer_header() {
+        // Echo the peer header back
+        let backend = Router::new().route(
+            "/",
+            post(|headers: HeaderMap| async move {
+                headers
+                    .get(PEER_HEADER)
+                    .map(|v| v.to_str().unwrap().to_string())
+                    .unwrap_or_default()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let backend_addr = listener.local_addr().unwrap();
+        let backend = tokio::spawn(async move { axum::serve(listener, backend).await.unwrap() });
+
+        let (addr, front) = serve_front("127.0.0.1:0".parse().unwrap(), backend_addr)
+            .await
+            .unwrap();
+        let peer = reqwest::Client::new()
+            .post(format!("http://{}/", addr))
+            .header(PEER_HEADER, "203.0.113.7")
+            .body("{}")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(peer, "127.0.0.1");
+
+        front.abort();
+        backend.abort();
+    }
+}
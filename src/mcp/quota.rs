@@ -0,0 +1,688 @@
+//! Per-client request budgets for the MCP HTTP server.
+//!
+//! When the HTTP transport is shared by many agents, a single runaway client can
+//! exhaust upstream API quotas (e.g. a Semantic Scholar API key) for everybody.
+//! [`ClientQuotaMiddleware`] plugs into pmcp's HTTP middleware chain and enforces
+//! three budgets per client:
+//!
+//! - requests per minute (any JSON-RPC POST)
+//! - concurrent `tools/call` requests in flight
+//! - `download_paper` calls per UTC day
+//!
+//! Clients are identified by IP address. Tokens, session IDs and forwarding
+//! headers are chosen by the caller, so a client could rotate them for a fresh
+//! budget; instead the address comes from [`PEER_HEADER`], which the front
+//! listener of [`crate::mcp::peer`] sets from the connection. `X-Forwarded-For`
+//! and `X-Real-IP` are only honoured when the connection comes from one of the
+//! configured trusted proxies.
+//!
+//! Downloads are charged when the call is admitted, so concurrent calls cannot
+//! overrun the budget, and refunded when the download fails.
+//!
+//! Over-limit calls receive HTTP 429 with a `Retry-After` header and a JSON-RPC
+//! error whose `data` carries the violated limit and a `retry_after_secs` hint.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::num::NonZeroU32;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use governor::{clock::Clock, clock::DefaultClock, DefaultKeyedRateLimiter, Quota, RateLimiter};
+use pmcp::server::http_middleware::{
+    ServerHttpContext, ServerHttpMiddleware, ServerHttpRequest, ServerHttpResponse,
+};
+use pmcp::ErrorCode;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::config::McpConfig;
+use crate::mcp::output::{unpack_tool_error, unpack_tool_result};
+use crate::mcp::peer::PEER_HEADER;
+
+/// Tool whose invocations count against the daily download budget
+const DOWNLOAD_TOOL: &str = "download_paper";
+
+/// Retry hint returned when a client has too many tool calls in flight
+const CONCURRENCY_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Per-client limits enforced by [`ClientQuotaMiddleware`]
+///
+/// A value of `0` disables the corresponding limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientQuota {
+    /// Requests per minute for each client
+    pub requests_per_minute: u32,
+
+    /// Maximum concurrent tool calls for each client
+    pub max_concurrent_tool_calls: usize,
+
+    /// Maximum `download_paper` calls per client per UTC day
+    pub daily_downloads: u32,
+}
+
+impl ClientQuota {
+    /// A quota that places no limits on clients
+    pub fn unlimited() -> Self {
+        Self {
+            requests_per_minute: 0,
+            max_concurrent_tool_calls: 0,
+            daily_downloads: 0,
+        }
+    }
+
+    /// Whether every limit is disabled
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::unlimited()
+    }
+}
+
+impl Default for ClientQuota {
+    fn default() -> Self {
+        Self::from(&McpConfig::default())
+    }
+}
+
+impl From<&McpConfig> for ClientQuota {
+    fn from(config: &McpConfig) -> Self {
+        Self {
+            requests_per_minute: config.client_requests_per_minute,
+            max_concurrent_tool_calls: config.client_max_concurrent_tool_calls,
+            daily_downloads: config.client_daily_downloads,
+        }
+    }
+}
+
+/// The budget a rejected request exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaLimit {
+    /// Requests per minute
+    RequestsPerMinute,
+    /// Concurrent tool calls
+    ConcurrentToolCalls,
+    /// Daily downloads
+    DailyDownloads,
+}
+
+impl QuotaLimit {
+    /// Identifier used in the JSON-RPC error data
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaLimit::RequestsPerMinute => "requests_per_minute",
+            QuotaLimit::ConcurrentToolCalls => "concurrent_tool_calls",
+            QuotaLimit::DailyDownloads => "daily_downloads",
+        }
+    }
+}
+
+/// A request that was rejected because a client exceeded its budget
+#[derive(Debug, Clone)]
+struct Rejection {
+    limit: QuotaLimit,
+    max: u64,
+    retry_after: Duration,
+    client: String,
+    id: Value,
+}
+
+impl Rejection {
+    /// Build the JSON-RPC error body returned to the client
+    fn to_json_rpc(&self) -> Value {
+        // Round up so clients never retry a fraction of a second too early
+        let retry_after_secs =
+            self.retry_after.as_secs() + u64::from(self.retry_after.subsec_nanos() > 0);
+
+        json!({
+            "jsonrpc": "2.0",
+            "id": self.id,
+            "error": {
+                "code": ErrorCode::RATE_LIMITED.0,
+                "message": format!(
+                    "Rate limit exceeded: {} (limit {}), retry after {}s",
+                    self.limit.as_str(),
+                    self.max,
+                    retry_after_secs
+                ),
+                "data": {
+                    "limit": self.limit.as_str(),
+                    "max": self.max,
+                    "retry_after_secs": retry_after_secs,
+                    "client": self.client,
+                }
+            }
+        })
+    }
+}
+
+/// Mutable bookkeeping shared by all requests
+#[derive(Debug, Default)]
+struct QuotaState {
+    /// Tool calls currently in flight, per client
+    in_flight: HashMap<String, usize>,
+
+    /// Request ID -> client for tool calls holding a concurrency slot
+    pending: HashMap<String, String>,
+
+    /// Request ID -> rejection awaiting its response
+    rejected: HashMap<String, Rejection>,
+
+    /// Download counts per client for the current UTC day
+    downloads: HashMap<String, (NaiveDate, u32)>,
+
+    /// Request ID -> client and day for downloads charged but not yet finished
+    charged: HashMap<String, (String, NaiveDate)>,
+}
+
+/// HTTP middleware enforcing per-client budgets on the MCP server
+///
+/// Uses the same `governor` rate limiter as [`crate::utils::HttpClient`], keyed
+/// by client instead of shared across the process.
+///
+/// Clients are told apart by [`PEER_HEADER`], so the middleware must run
+/// behind [`serve_front`](crate::mcp::peer::serve_front), as it does with
+/// [`McpServer::run_http_with_quotas`](crate::mcp::McpServer::run_http_with_quotas).
+pub struct ClientQuotaMiddleware {
+    quota: ClientQuota,
+    trusted_proxies: Vec<IpAddr>,
+    limiter: Option<DefaultKeyedRateLimiter<String>>,
+    state: Mutex<QuotaState>,
+}
+
+impl std::fmt::Debug for ClientQuotaMiddleware {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientQuotaMiddleware")
+            .field("quota", &self.quota)
+            .finish()
+    }
+}
+
+impl ClientQuotaMiddleware {
+    /// Create a middleware enforcing the given quota
+    pub fn new(quota: ClientQuota) -> Self {
+        let limiter = NonZeroU32::new(quota.requests_per_minute)
+            .map(|rpm| RateLimiter::keyed(Quota::per_minute(rpm)));
+
+        Self {
+            quota,
+            trusted_proxies: Vec::new(),
+            limiter,
+            state: Mutex::new(QuotaState::default()),
+        }
+    }
+
+    /// Honour `X-Forwarded-For` and `X-Real-IP` on requests from these proxies
+    pub fn trust_proxies(mut self, proxies: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.trusted_proxies = proxies.into_iter().collect();
+        self
+    }
+
+    /// The quota being enforced
+    pub fn quota(&self) -> ClientQuota {
+        self.quota
+    }
+
+    /// Check a request against the client's budgets, reserving a tool-call slot if accepted
+    fn admit(&self, client: &str, request_id: &str, body: &[u8]) -> Result<(), Box<Rejection>> {
+        let call = parse_json_rpc(body);
+        let reject = |limit: QuotaLimit, max: u64, retry_after: Duration| {
+            Box::new(Rejection {
+                limit,
+                max,
+                retry_after,
+                client: client.to_string(),
+                id: call.as_ref().map(|c| c.id.clone()).unwrap_or(Value::Null),
+            })
+        };
+
+        if let Some(ref limiter) = self.limiter {
+            if let Err(not_until) = limiter.check_key(&client.to_string()) {
+                let wait = not_until.wait_time_from(DefaultClock::default().now());
+                return Err(reject(
+                    QuotaLimit::RequestsPerMinute,
+                    u64::from(self.quota.requests_per_minute),
+                    wait,
+                ));
+            }
+        }
+
+        let Some(call) = call.as_ref().filter(|c| c.method == "tools/call") else {
+            return Ok(());
+        };
+
+        let mut state = self.state.lock().expect("quota state poisoned");
+        let today = Utc::now().date_naive();
+        let is_download = call.tool.as_deref() == Some(DOWNLOAD_TOOL);
+
+        if is_download && self.quota.daily_downloads > 0 {
+            let used = match state.downloads.get(client) {
+                Some((day, count)) if *day == today => *count,
+                _ => 0,
+            };
+            if used >= self.quota.daily_downloads {
+                return Err(reject(
+                    QuotaLimit::DailyDownloads,
+                    u64::from(self.quota.daily_downloads),
+                    until_next_utc_day(),
+                ));
+            }
+        }
+
+        if self.quota.max_concurrent_tool_calls > 0 {
+            let active = state.in_flight.get(client).copied().unwrap_or(0);
+            if active >= self.quota.max_concurrent_tool_calls {
+                return Err(reject(
+                    QuotaLimit::ConcurrentToolCalls,
+                    self.quota.max_concurrent_tool_calls as u64,
+                    CONCURRENCY_RETRY_AFTER,
+                ));
+            }
+            *state.in_flight.entry(client.to_string()).or_insert(0) += 1;
+            state
+                .pending
+                .insert(request_id.to_string(), client.to_string());
+        }
+
+        if is_download && self.quota.daily_downloads > 0 {
+            let entry = state
+                .downloads
+                .entry(client.to_string())
+                .or_insert((today, 0));
+            if entry.0 != today {
+                *entry = (today, 0);
+            }
+            entry.1 += 1;
+            state
+                .charged
+                .insert(request_id.to_string(), (client.to_string(), today));
+        }
+
+        Ok(())
+    }
+
+    /// Settle the download charged for a request, refunding it if the download failed
+    fn settle_download(&self, request_id: &str, succeeded: bool) {
+        let mut state = self.state.lock().expect("quota state poisoned");
+        let Some((client, day)) = state.charged.remove(request_id) else {
+            return;
+        };
+        if succeeded {
+            return;
+        }
+        if let Some((counted_day, count)) = state.downloads.get_mut(&client) {
+            if *counted_day == day {
+                *count = count.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Release the concurrency slot held by a request, if any
+    fn release(&self, request_id: &str) {
+        let mut state = self.state.lock().expect("quota state poisoned");
+        if let Some(client) = state.pending.remove(request_id) {
+            if let Some(active) = state.in_flight.get_mut(&client) {
+                *active = active.saturating_sub(1);
+                if *active == 0 {
+                    state.in_flight.remove(&client);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ServerHttpMiddleware for ClientQuotaMiddleware {
+    async fn on_request(
+        &self,
+        request: &mut ServerHttpRequest,
+        context: &ServerHttpContext,
+    ) -> pmcp::Result<()> {
+        let client = client_key(request, &self.trusted_proxies);
+
+        if let Err(rejection) = self.admit(&client, &context.request_id, &request.body) {
+            tracing::warn!(
+                "MCP client {} exceeded {} quota",
+                rejection.client,
+                rejection.limit.as_str()
+            );
+            // pmcp turns middleware errors into opaque 500s, so instead the body is
+            // emptied to short-circuit JSON-RPC handling, and `on_response`
+            // replaces the resulting parse error with a structured 429.
+            request.body.clear();
+            self.state
+                .lock()
+                .expect("quota state poisoned")
+                .rejected
+                .insert(context.request_id.clone(), *rejection);
+            return Ok(());
+        }
+
+        if let Some(ref limiter) = self.limiter {
+            limiter.retain_recent();
+        }
+
+        Ok(())
+    }
+
+    async fn on_response(
+        &self,
+        response: &mut ServerHttpResponse,
+        context: &ServerHttpContext,
+    ) -> pmcp::Result<()> {
+        let rejection = self
+            .state
+            .lock()
+            .expect("quota state poisoned")
+            .rejected
+            .remove(&context.request_id);
+
+        match rejection {
+            Some(rejection) => {
+                let retry_after = rejection.to_json_rpc()["error"]["data"]["retry_after_secs"]
+                    .as_u64()
+                    .unwrap_or(1);
+                response.status = StatusCode::TOO_MANY_REQUESTS;
+                response.add_header("content-type", "application/json");
+                response.add_header("retry-after", &retry_after.to_string());
+                response.body = serde_json::to_vec(&rejection.to_json_rpc())?;
+            }
+            None => {
+                self.release(&context.request_id);
+                self.settle_download(&context.request_id, download_succeeded(response));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn on_error(
+        &self,
+        _error: &pmcp::Error,
+        context: &ServerHttpContext,
+    ) -> pmcp::Result<()> {
+        self.state
+            .lock()
+            .expect("quota state poisoned")
+            .rejected
+            .remove(&context.request_id);
+        self.release(&context.request_id);
+        self.settle_download(&context.request_id, false);
+        Ok(())
+    }
+
+    fn priority(&self) -> i32 {
+        // Run after authentication but before anything that does real work
+        20
+    }
+}
+
+/// The parts of a JSON-RPC request relevant to quota accounting
+struct JsonRpcCall {
+    id: Value,
+    method: String,
+    tool: Option<String>,
+}
+
+/// Parse a single JSON-RPC request body, ignoring batches and malformed input
+fn parse_json_rpc(body: &[u8]) -> Option<JsonRpcCall> {
+    let value: Value = serde_json::from_slice(body).ok()?;
+    let method = value.get("method")?.as_str()?.to_string();
+    let tool = value
+        .get("params")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(|s| s.to_string());
+
+    Some(JsonRpcCall {
+        id: value.get("id").cloned().unwrap_or(Value::Null),
+        method,
+        tool,
+    })
+}
+
+/// Identify the client that sent a request
+///
+/// The forwarding headers are read from right to left, skipping the trusted
+/// proxies, so a client cannot choose its address by prepending entries.
+fn client_key(request: &ServerHttpRequest, trusted_proxies: &[IpAddr]) -> String {
+    let Some(peer) = request
+        .get_header(PEER_HEADER)
+        .and_then(|h| h.trim().parse::<IpAddr>().ok())
+    else {
+        return "unknown".to_string();
+    };
+
+    if trusted_proxies.contains(&peer) {
+        let forwarded = request
+            .get_header("x-forwarded-for")
+            .into_iter()
+            .flat_map(|h| h.rsplit(','))
+            .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+            .find(|ip| !trusted_proxies.contains(ip))
+            .or_else(|| {
+                request
+                    .get_header("x-real-ip")
+                    .and_then(|ip| ip.trim().parse().ok())
+            });
+        if let Some(ip) = forwarded {
+            return format!("ip:{}", ip);
+        }
+    }
+
+    format!("ip:{}", peer)
+}
+
+/// Whether a `download_paper` response reports a downloaded file
+fn download_succeeded(response: &ServerHttpResponse) -> bool {
+    if response.status != StatusCode::OK {
+        return false;
+    }
+    let Some(mut result) = serde_json::from_slice::<Value>(&response.body)
+        .ok()
+        .and_then(|mut body| body.get_mut("result").map(Value::take))
+    else {
+        return false;
+    };
+    if unpack_tool_error(&result).is_some() {
+        return false;
+    }
+    unpack_tool_result(&mut result);
+    result["isError"] != json!(true) && result["structuredContent"]["success"] != json!(false)
+}
+
+/// Time remaining until the daily download budget resets
+fn until_next_utc_day() -> Duration {
+    let now = Utc::now();
+    let midnight = (now.date_naive() + chrono::Days::new(1))
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc();
+    (midnight - now).to_std().unwrap_or(Duration::from_secs(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderMap;
+
+    fn request(headers: &[(&str, &str)], body: Value) -> ServerHttpRequest {
+        let mut request = ServerHttpRequest::new(
+            http::Method::POST,
+            "/".parse().unwrap(),
+            HeaderMap::new(),
+            serde_json::to_vec(&body).unwrap(),
+        );
+        for (name, value) in headers {
+            request.add_header(name, value);
+        }
+        request
+    }
+
+    fn tool_call(id: u64, tool: &str) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": {"name": tool, "arguments": {}}
+        })
+    }
+
+    fn quota(rpm: u32, concurrent: usize, downloads: u32) -> ClientQuota {
+        ClientQuota {
+            requests_per_minute: rpm,
+            max_concurrent_tool_calls: concurrent,
+            daily_downloads: downloads,
+        }
+    }
+
+    #[test]
+    fn test_client_key_ignores_spoofed_headers() {
+        let req = request(
+            &[
+                (PEER_HEADER, "203.0.113.5"),
+                ("authorization", "Bearer rotated-token"),
+                ("x-forwarded-for", "10.0.0.1"),
+                ("x-real-ip", "10.0.0.2"),
+            ],
+            json!({}),
+        );
+        assert_eq!(client_key(&req, &[]), "ip:203.0.113.5");
+
+        let req = request(&[("x-forwarded-for", "10.0.0.1")], json!({}));
+        assert_eq!(client_key(&req, &[]), "unknown");
+    }
+
+    #[test]
+    fn test_client_key_trusted_proxy() {
+        let proxy: IpAddr = "192.0.2.1".parse().unwrap();
+        let req = request(
+            &[
+                (PEER_HEADER, "192.0.2.1"),
+                ("x-forwarded-for", "10.9.9.9, 198.51.100.4, 192.0.2.1"),
+            ],
+            json!({}),
+        );
+        // The entry the client wrote itself is ignored
+        assert_eq!(client_key(&req, &[proxy]), "ip:198.51.100.4");
+
+        let req = request(
+            &[(PEER_HEADER, "192.0.2.1"), ("x-real-ip", "198.51.100.4")],
+            json!({}),
+        );
+        assert_eq!(client_key(&req, &[proxy]), "ip:198.51.100.4");
+    }
+
+    #[test]
+    fn test_requests_per_minute() {
+        let middleware = ClientQuotaMiddleware::new(quota(2, 0, 0));
+        let body =
+            serde_json::to_vec(&json!({"jsonrpc": "2.0", "id": 1, "method": "ping"})).unwrap();
+
+        assert!(middleware.admit("a", "r1", &body).is_ok());
+        assert!(middleware.admit("a", "r2", &body).is_ok());
+        let rejection = middleware.admit("a", "r3", &body).unwrap_err();
+        assert_eq!(rejection.limit, QuotaLimit::RequestsPerMinute);
+        assert!(rejection.retry_after > Duration::ZERO);
+
+        // Other clients have their own budget
+        assert!(middleware.admit("b", "r4", &body).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_tool_calls_released() {
+        let middleware = ClientQuotaMiddleware::new(quota(0, 1, 0));
+        let body = serde_json::to_vec(&tool_call(1, "search_papers")).unwrap();
+
+        assert!(middleware.admit("a", "r1", &body).is_ok());
+        let rejection = middleware.admit("a", "r2", &body).unwrap_err();
+        assert_eq!(rejection.limit, QuotaLimit::ConcurrentToolCalls);
+
+        middleware.release("r1");
+        assert!(middleware.admit("a", "r3", &body).is_ok());
+    }
+
+    #[test]
+    fn test_daily_downloads() {
+        let middleware = ClientQuotaMiddleware::new(quota(0, 0, 1));
+        let download = serde_json::to_vec(&tool_call(7, DOWNLOAD_TOOL)).unwrap();
+        let search = serde_json::to_vec(&tool_call(8, "search_papers")).unwrap();
+
+        assert!(middleware.admit("a", "r1", &download).is_ok());
+        let rejection = middleware.admit("a", "r2", &download).unwrap_err();
+        assert_eq!(rejection.limit, QuotaLimit::DailyDownloads);
+        assert_eq!(rejection.id, json!(7));
+
+        // Non-download tools are unaffected
+        assert!(middleware.admit("a", "r3", &search).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_failed_download_refunded() {
+        let middleware = ClientQuotaMiddleware::new(quota(0, 0, 1));
+        let download = serde_json::to_vec(&tool_call(7, DOWNLOAD_TOOL)).unwrap();
+        let respond = |success: bool| {
+            let output =
+                crate::mcp::ToolOutput::new(json!({"success": success}), "Download").unwrap();
+            let body = json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "result": {"content": [{"type": "text", "text": output.to_envelope().to_string()}]}
+            });
+            ServerHttpResponse::new(
+                StatusCode::OK,
+                HeaderMap::new(),
+                serde_json::to_vec(&body).unwrap(),
+            )
+        };
+
+        assert!(middleware.admit("a", "r1", &download).is_ok());
+        let ctx = ServerHttpContext::new("r1".to_string());
+        middleware
+            .on_response(&mut respond(false), &ctx)
+            .await
+            .unwrap();
+
+        assert!(middleware.admit("a", "r2", &download).is_ok());
+        let ctx = ServerHttpContext::new("r2".to_string());
+        middleware
+            .on_response(&mut respond(true), &ctx)
+            .await
+            .unwrap();
+
+        let rejection = middleware.admit("a", "r3", &download).unwrap_err();
+        assert_eq!(rejection.limit, QuotaLimit::DailyDownloads);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_request_gets_structured_429() {
+        let middleware = ClientQuotaMiddleware::new(quota(0, 0, 1));
+        let ctx = ServerHttpContext::new("r1".to_string());
+        let mut first = request(&[(PEER_HEADER, "203.0.113.5")], tool_call(1, DOWNLOAD_TOOL));
+        middleware.on_request(&mut first, &ctx).await.unwrap();
+        assert!(!first.body.is_empty());
+
+        let ctx = ServerHttpContext::new("r2".to_string());
+        let mut second = request(&[(PEER_HEADER, "203.0.113.5")], tool_call(2, DOWNLOAD_TOOL));
+        middleware.on_request(&mut second, &ctx).await.unwrap();
+        assert!(second.body.is_empty());
+
+        let mut response =
+            ServerHttpResponse::new(StatusCode::BAD_REQUEST, HeaderMap::new(), Vec::new());
+        middleware.on_response(&mut response, &ctx).await.unwrap();
+
+        assert_eq!(response.status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.get_header("retry-after").is_some());
+        let body: Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body["id"], json!(2));
+        assert_eq!(body["error"]["code"], json!(ErrorCode::RATE_LIMITED.0));
+        assert_eq!(body["error"]["data"]["limit"], json!("daily_downloads"));
+        assert!(body["error"]["data"]["retry_after_secs"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_unlimited_quota() {
+        assert!(ClientQuota::unlimited().is_unlimited());
+        assert!(!quota(1, 0, 0).is_unlimited());
+    }
+}
@@ -3,15 +3,23 @@
 //! This module provides the MCP server implementation using the pmcp crate
 //! for proper JSON-RPC handling over stdio and HTTP/SSE.
 
-use crate::mcp::tools::ToolRegistry;
+use crate::mcp::output::{error_envelope, StructuredResultMiddleware, StructuredResultTransport};
+use crate::mcp::peer::serve_front;
+use crate::mcp::quota::{ClientQuota, ClientQuotaMiddleware};
+use crate::mcp::session::{take_session_id, SessionMiddleware, SessionStore};
+use crate::mcp::tools::{ToolError, ToolRegistry};
 use crate::sources::SourceRegistry;
+use crate::utils::Metrics;
 use async_trait::async_trait;
 use pmcp::{
+    server::http_middleware::ServerHttpMiddlewareChain,
     server::streamable_http_server::{StreamableHttpServer, StreamableHttpServerConfig},
+    shared::StdioTransport,
+    types::ToolAnnotations,
     Error, RequestHandlerExtra, Server, ServerCapabilities, ToolHandler, ToolInfo,
 };
 use serde_json::Value;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
@@ -23,15 +31,18 @@ use tokio::task::JoinHandle;
 #[derive(Debug, Clone)]
 pub struct McpServer {
     server: Arc<Mutex<Server>>,
+    sessions: Arc<SessionStore>,
 }
 
 impl McpServer {
     /// Create a new MCP server with the given source registry
     pub fn new(sources: Arc<SourceRegistry>) -> Result<Self, pmcp::Error> {
         let tools = ToolRegistry::from_sources(&sources);
-        let server = Self::build_server_impl(tools)?;
+        let sessions = Arc::new(SessionStore::new());
+        let server = Self::build_server_impl(tools, &sessions)?;
         Ok(Self {
             server: Arc::new(Mutex::new(server)),
+            sessions,
         })
     }
 
@@ -41,7 +52,10 @@ impl McpServer {
     }
 
     /// Build the MCP server with tool handlers (internal implementation)
-    fn build_server_impl(tools: ToolRegistry) -> Result<Server, pmcp::Error> {
+    fn build_server_impl(
+        tools: ToolRegistry,
+        sessions: &Arc<SessionStore>,
+    ) -> Result<Server, pmcp::Error> {
         let mut builder = Server::builder()
             .name("research-master")
             .version(env!("CARGO_PKG_VERSION"))
@@ -52,13 +66,16 @@ impl McpServer {
             let name = tool.name.clone();
             let description = tool.description.clone();
             let input_schema = tool.input_schema.clone();
+            let output_schema = tool.output_schema.clone();
             let handler = tool.handler.clone();
 
             let tool_handler = ToolWrapper {
                 name,
                 description: Some(description),
                 input_schema,
+                output_schema,
                 handler,
+                sessions: sessions.clone(),
             };
             builder = builder.tool(tool_handler.name.clone(), tool_handler);
         }
@@ -67,19 +84,21 @@ impl McpServer {
     }
 
     /// Run the server in stdio mode (for Claude Desktop and other MCP clients)
-    pub async fn run(&self) -> Result<(), pmcp::Error> {
+    pub async fn run(self) -> Result<(), pmcp::Error> {
         tracing::info!("Starting MCP server in stdio mode");
 
-        // run_stdio() takes ownership, so we need to extract the Server from Arc<Mutex>
-        // Since we own the Arc and there should be no other references at this point,
-        // we can try_unwrap to get the Server out
-        let server = Arc::try_unwrap(self.server.clone())
+        // Running over stdio takes ownership, so we need to extract the Server from
+        // Arc<Mutex>. This only succeeds if no clones of this McpServer are alive.
+        let server = Arc::try_unwrap(self.server)
             .map_err(|_| Error::internal("Cannot unwrap Arc - multiple references exist"))?
             .into_inner();
 
         tracing::info!("MCP server initialized");
 
-        server.run_stdio().await
+        // Unpack structured tool results on their way out
+        server
+            .run(StructuredResultTransport::new(StdioTransport::new()))
+            .await
     }
 
     /// Run the server in HTTP/SSE mode
@@ -89,32 +108,106 @@ impl McpServer {
     pub async fn run_http(&self, addr: &str) -> Result<(SocketAddr, JoinHandle<()>), pmcp::Error> {
         tracing::info!("Starting MCP server in HTTP/SSE mode on {}", addr);
 
-        let socket_addr: SocketAddr = addr
+        let config = StreamableHttpServerConfig {
+            http_middleware: Some(Arc::new(Self::http_middleware(None))),
+            ..Default::default()
+        };
+
+        self.start_http(addr, config).await
+    }
+
+    /// Run the server in HTTP/SSE mode with per-client quotas
+    ///
+    /// Each client IP address gets its own request-rate, concurrency and daily
+    /// download budget. Over-limit calls receive HTTP 429 with a JSON-RPC error
+    /// carrying a retry-after hint.
+    ///
+    /// The MCP server listens on a loopback port behind a front listener on
+    /// `addr` that records each caller's address. Forwarding headers are only
+    /// believed on connections from `trusted_proxies`.
+    pub async fn run_http_with_quotas(
+        &self,
+        addr: &str,
+        quota: ClientQuota,
+        trusted_proxies: &[IpAddr],
+    ) -> Result<(SocketAddr, JoinHandle<()>), pmcp::Error> {
+        tracing::info!(
+            "Client quotas: {} req/min, {} concurrent tool calls, {} downloads/day",
+            quota.requests_per_minute,
+            quota.max_concurrent_tool_calls,
+            quota.daily_downloads
+        );
+
+        let front_addr: SocketAddr = addr
             .parse()
             .map_err(|e| Error::invalid_params(format!("Invalid address: {}", e)))?;
+        let middleware =
+            ClientQuotaMiddleware::new(quota).trust_proxies(trusted_proxies.iter().copied());
+        let config = StreamableHttpServerConfig {
+            http_middleware: Some(Arc::new(Self::http_middleware(Some(middleware)))),
+            ..Default::default()
+        };
 
-        // Create the HTTP server with default config
-        let http_server = StreamableHttpServer::new(socket_addr, self.server.clone());
-
-        // Start the server
-        http_server.start().await
+        let (backend_addr, backend) = self.start_http("127.0.0.1:0", config).await?;
+        let (local_addr, front) = serve_front(front_addr, backend_addr).await?;
+        let handle = tokio::spawn(async move {
+            let _ = front.await;
+            backend.abort();
+        });
+        Ok((local_addr, handle))
     }
 
     /// Run the server in HTTP/SSE mode with custom configuration
+    ///
+    /// If `config` brings its own middleware chain, it should include a
+    /// [`StructuredResultMiddleware`] so tool results carry `structuredContent`,
+    /// and a [`SessionMiddleware`] so each session gets its own working set.
     pub async fn run_http_with_config(
         &self,
         addr: &str,
-        config: StreamableHttpServerConfig,
+        mut config: StreamableHttpServerConfig,
     ) -> Result<(SocketAddr, JoinHandle<()>), pmcp::Error> {
         tracing::info!(
             "Starting MCP server in HTTP/SSE mode on {} (with custom config)",
             addr
         );
 
+        if config.http_middleware.is_none() {
+            config.http_middleware = Some(Arc::new(Self::http_middleware(None)));
+        }
+
+        self.start_http(addr, config).await
+    }
+
+    /// Build the HTTP middleware chain, optionally enforcing client quotas
+    fn http_middleware(quotas: Option<ClientQuotaMiddleware>) -> ServerHttpMiddlewareChain {
+        let mut chain = ServerHttpMiddlewareChain::new();
+        if let Some(quotas) = quotas {
+            chain.add(Arc::new(quotas));
+        }
+        chain.add(Arc::new(SessionMiddleware));
+        chain.add(Arc::new(StructuredResultMiddleware));
+        chain
+    }
+
+    /// Bind and start the HTTP server
+    async fn start_http(
+        &self,
+        addr: &str,
+        mut config: StreamableHttpServerConfig,
+    ) -> Result<(SocketAddr, JoinHandle<()>), pmcp::Error> {
         let socket_addr: SocketAddr = addr
             .parse()
             .map_err(|e| Error::invalid_params(format!("Invalid address: {}", e)))?;
 
+        // Forget a session's working set once the client ends the session
+        if config.on_session_closed.is_none() {
+            let sessions = self.sessions.clone();
+            config.on_session_closed = Some(Box::new(move |session_id| {
+                sessions.remove(session_id);
+            }));
+        }
+
         // Create the HTTP server with custom config
         let http_server =
             StreamableHttpServer::with_config(socket_addr, self.server.clone(), config);
@@ -130,23 +223,48 @@ struct ToolWrapper {
     name: String,
     description: Option<String>,
     input_schema: Value,
+    output_schema: Option<Value>,
     handler: Arc<dyn crate::mcp::tools::ToolHandler>,
+    sessions: Arc<SessionStore>,
 }
 
 #[async_trait]
 impl ToolHandler for ToolWrapper {
-    async fn handle(&self, args: Value, _extra: RequestHandlerExtra) -> Result<Value, Error> {
-        self.handler
-            .execute(args)
-            .await
-            .map_err(|e| Error::internal(&e))
+    async fn handle(&self, mut args: Value, _extra: RequestHandlerExtra) -> Result<Value, Error> {
+        let session_id = take_session_id(&mut args);
+        let session = self.sessions.get(session_id.as_deref());
+
+        let result = self.handler.execute(args, &session).await;
+        Metrics::global().record_tool_call(&self.name, result.is_ok());
+        match result {
+            Ok(output) => Ok(output.to_envelope()),
+            // pmcp would report this as an internal error
+            Err(error @ ToolError::InvalidParams(_)) => Ok(error_envelope(error.into())),
+            Err(error) => Err(error.into()),
+        }
     }
 
     fn metadata(&self) -> Option<ToolInfo> {
-        Some(ToolInfo::new(
+        let Some(ref output_schema) = self.output_schema else {
+            return Some(ToolInfo::new(
+                self.name.clone(),
+                self.description.clone(),
+                self.input_schema.clone(),
+            ));
+        };
+
+        // Schemas generated by schemars carry the Rust type name as their title
+        let type_name = output_schema
+            .get("title")
+            .and_then(|t| t.as_str())
+            .unwrap_or(&self.name)
+            .to_string();
+
+        Some(ToolInfo::with_annotations(
             self.name.clone(),
             self.description.clone(),
             self.input_schema.clone(),
+            ToolAnnotations::new().with_output_schema(output_schema.clone(), type_name),
         ))
     }
 }
@@ -155,3 +273,35 @@ impl ToolHandler for ToolWrapper {
 pub fn create_mcp_server(sources: Arc<SourceRegistry>) -> Result<McpServer, pmcp::Error> {
     McpServer::new(sources)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::args::{check_source, parse_args, SearchPapersArgs};
+    use pmcp::ErrorCode;
+    use serde_json::json;
+
+    #[test]
+    fn test_tool_error_codes() {
+        for args in [
+            json!({"query": "graphs", "unknown": 1}),
+            json!({"query": 42}),
+            json!({"query": "graphs", "max_results": 0}),
+        ] {
+            let error = parse_args::<SearchPapersArgs>("search_papers", args).unwrap_err();
+            assert_eq!(
+                Error::from(error).error_code(),
+                Some(ErrorCode::INVALID_PARAMS)
+            );
+        }
+
+        let error = check_source(&[], "arxv").unwrap_err();
+        assert_eq!(
+            Error::from(error).error_code(),
+            Some(ErrorCode::INVALID_PARAMS)
+        );
+
+        let error = ToolError::from("Network error: connection refused".to_string());
+        assert!(matches!(Error::from(error), Error::Internal(_)));
+    }
+}
@@ -0,0 +1,560 @@
+//! Per-session working sets for MCP conversations.
+//!
+//! Every paper a tool returns is added to the calling session's [`WorkingSet`]
+//! and gets a short handle such as `#3`. Handles can be passed as `paper_id` to
+//! any tool, so clients no longer need to repeat (and occasionally mangle) long
+//! identifiers across a conversation. Returning the same paper again keeps its
+//! handle.
+//!
+//! In HTTP mode each MCP session has its own working set: [`SessionMiddleware`]
+//! tags every `tools/call` with the caller's session ID, which the server strips
+//! off again before the tool sees its arguments. stdio serves a single client,
+//! which gets a single session.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use pmcp::server::http_middleware::{ServerHttpContext, ServerHttpMiddleware, ServerHttpRequest};
+use serde_json::Value;
+
+use super::output::{PaperList, PaperView, Verbosity};
+use crate::models::{Paper, VersionKind};
+use crate::versions::find_version;
+
+/// Argument under which the session ID is passed from the middleware to the server
+const SESSION_ARG: &str = "__research_master_session";
+
+/// Session used when the transport has no notion of sessions (stdio)
+const DEFAULT_SESSION: &str = "default";
+
+/// Working sets idle for longer than this are discarded
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A paper in a working set
+#[derive(Debug, Clone)]
+pub struct WorkingSetEntry {
+    /// Handle number (`#3` is handle 3)
+    pub handle: u32,
+
+    /// The paper as last returned by a tool
+    pub paper: Paper,
+
+    /// Pinned papers survive `drop_papers` with `unpinned: true`
+    pub pinned: bool,
+}
+
+impl WorkingSetEntry {
+    /// The handle as shown to clients, e.g. `#3`
+    pub fn handle_label(&self) -> String {
+        format_handle(self.handle)
+    }
+}
+
+/// The papers seen during one session, in the order they were first returned
+#[derive(Debug, Clone, Default)]
+pub struct WorkingSet {
+    entries: Vec<WorkingSetEntry>,
+    last_handle: u32,
+}
+
+impl WorkingSet {
+    /// Create an empty working set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a paper and return its handle
+    ///
+    /// A paper already in the set keeps its handle and pin, but its metadata is
+    /// replaced by the newer copy.
+    pub fn add(&mut self, paper: &Paper) -> u32 {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|e| same_paper(&e.paper, paper))
+        {
+            entry.paper = paper.clone();
+            return entry.handle;
+        }
+
+        // Handles are never reused, so a stale handle cannot silently point
+        // at a different paper
+        self.last_handle += 1;
+        self.entries.push(WorkingSetEntry {
+            handle: self.last_handle,
+            paper: paper.clone(),
+            pinned: false,
+        });
+        self.last_handle
+    }
+
+    /// Look up a paper by handle number
+    pub fn get(&self, handle: u32) -> Option<&WorkingSetEntry> {
+        self.entries.iter().find(|e| e.handle == handle)
+    }
+
+    /// All papers in the set
+    pub fn entries(&self) -> &[WorkingSetEntry] {
+        &self.entries
+    }
+
+    /// Number of papers in the set
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Pin or unpin a paper, returning false if the handle is unknown
+    pub fn set_pinned(&mut self, handle: u32, pinned: bool) -> bool {
+        match self.entries.iter_mut().find(|e| e.handle == handle) {
+            Some(entry) => {
+                entry.pinned = pinned;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a paper, returning false if the handle is unknown
+    pub fn remove(&mut self, handle: u32) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.handle != handle);
+        self.entries.len() != before
+    }
+
+    /// Remove every paper that is not pinned, returning the removed handles
+    pub fn remove_unpinned(&mut self) -> Vec<u32> {
+        let removed = self
+            .entries
+            .iter()
+            .filter(|e| !e.pinned)
+            .map(|e| e.handle)
+            .collect();
+        self.entries.retain(|e| e.pinned);
+        removed
+    }
+
+    /// Check that every handle is known, returning their numbers
+    pub fn check_handles(&self, handles: &[String]) -> Result<Vec<u32>, String> {
+        let mut numbers = Vec::with_capacity(handles.len());
+        let mut unknown = Vec::new();
+
+        for handle in handles {
+            match parse_handle_lenient(handle) {
+                Some(n) if self.get(n).is_some() => numbers.push(n),
+                _ => unknown.push(handle.as_str()),
+            }
+        }
+
+        if !unknown.is_empty() {
+            return Err(format!(
+                "Unknown handle(s): {}. Use list_working_set to see the current handles.",
+                unknown.join(", ")
+            ));
+        }
+
+        Ok(numbers)
+    }
+}
+
+/// A client's view of its working set, shared by all of its tool calls
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    working_set: Arc<Mutex<WorkingSet>>,
+}
+
+impl Session {
+    /// Create a session with an empty working set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lock the working set
+    pub fn working_set(&self) -> MutexGuard<'_, WorkingSet> {
+        self.working_set.lock().expect("working set poisoned")
+    }
+
+    /// Resolve `paper_id` if it is a handle
+    ///
+    /// Returns `Ok(None)` for ordinary paper IDs and an error for handles that
+    /// are not in the working set.
+    pub fn resolve(&self, paper_id: &str) -> Result<Option<Paper>, String> {
+        let Some(handle) = parse_handle(paper_id) else {
+            return Ok(None);
+        };
+
+        match self.working_set().get(handle) {
+            Some(entry) => Ok(Some(entry.paper.clone())),
+            None => Err(format!(
+                "Unknown handle '{}'. Use list_working_set to see the current handles.",
+                paper_id.trim()
+            )),
+        }
+    }
+
+    /// Resolve a `paper_id`/`source` argument pair
+    ///
+    /// A handle resolves to the paper's own ID, and to its source unless one
+    /// was given explicitly.
+    pub fn resolve_id(
+        &self,
+        paper_id: &str,
+        source: Option<&str>,
+    ) -> Result<(String, Option<String>), String> {
+        match self.resolve(paper_id)? {
+            Some(paper) => Ok((
+                paper.paper_id,
+                Some(source.unwrap_or(paper.source.id()).to_string()),
+            )),
+            None => Ok((paper_id.to_string(), source.map(str::to_string))),
+        }
+    }
+
+    /// Like [`resolve_id`](Self::resolve_id), but for the grouped version of
+    /// the given kind of a working-set paper
+    pub fn resolve_version(
+        &self,
+        paper_id: &str,
+        source: Option<&str>,
+        version: Option<VersionKind>,
+    ) -> Result<(String, Option<String>), String> {
+        let Some(kind) = version else {
+            return self.resolve_id(paper_id, source);
+        };
+        let paper = self.resolve(paper_id)?.ok_or_else(|| {
+            format!(
+                "A version can only be chosen for a working-set paper (e.g. '#3'), not '{}'",
+                paper_id
+            )
+        })?;
+        let found = find_version(&paper, kind)
+            .ok_or_else(|| format!("No {} version of {} is known", kind, paper_id))?;
+        Ok((found.paper_id.clone(), Some(found.source.id().to_string())))
+    }
+
+    /// Add papers to the working set and build a list of them with handles
+    pub fn paper_list(&self, papers: &[Paper], verbosity: Verbosity) -> PaperList {
+        let mut list = PaperList::new(papers, verbosity);
+        let mut working_set = self.working_set();
+        for (view, paper) in list.papers.iter_mut().zip(papers) {
+            view.handle = Some(format_handle(working_set.add(paper)));
+        }
+        list
+    }
+
+    /// Add a paper to the working set and build a view of it with its handle
+    pub fn paper_view(&self, paper: &Paper, verbosity: Verbosity) -> PaperView {
+        let mut view = PaperView::new(paper, verbosity);
+        view.handle = Some(format_handle(self.working_set().add(paper)));
+        view
+    }
+}
+
+/// Working sets of all active sessions
+#[derive(Debug, Default)]
+pub struct SessionStore {
+    sessions: Mutex<HashMap<String, (Session, Instant)>>,
+}
+
+impl SessionStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the session with the given ID, creating it if needed
+    ///
+    /// `None` selects the default session used by stdio.
+    pub fn get(&self, session_id: Option<&str>) -> Session {
+        let id = session_id.unwrap_or(DEFAULT_SESSION);
+        let now = Instant::now();
+
+        let mut sessions = self.sessions.lock().expect("session store poisoned");
+        sessions.retain(|key, (_, last_used)| {
+            key == id || now.duration_since(*last_used) < SESSION_IDLE_TIMEOUT
+        });
+
+        let (session, last_used) = sessions
+            .entry(id.to_string())
+            .or_insert_with(|| (Session::new(), now));
+        *last_used = now;
+        session.clone()
+    }
+
+    /// Forget a session's working set
+    pub fn remove(&self, session_id: &str) {
+        self.sessions
+            .lock()
+            .expect("session store poisoned")
+            .remove(session_id);
+    }
+
+    /// Number of sessions with a working set
+    pub fn len(&self) -> usize {
+        self.sessions.lock().expect("session store poisoned").len()
+    }
+
+    /// Whether no session has a working set yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Remove the session ID tagged onto tool arguments by [`SessionMiddleware`]
+pub fn take_session_id(args: &mut Value) -> Option<String> {
+    match args.as_object_mut()?.remove(SESSION_ARG)? {
+        Value::String(id) => Some(id),
+        _ => None,
+    }
+}
+
+/// HTTP middleware that tags tool calls with the caller's MCP session ID
+///
+/// Any session ID supplied by the client itself is discarded, so a client can
+/// only ever reach its own working set.
+#[derive(Debug, Default)]
+pub struct SessionMiddleware;
+
+#[async_trait]
+impl ServerHttpMiddleware for SessionMiddleware {
+    async fn on_request(
+        &self,
+        request: &mut ServerHttpRequest,
+        context: &ServerHttpContext,
+    ) -> pmcp::Result<()> {
+        let Ok(mut body) = serde_json::from_slice::<Value>(&request.body) else {
+            return Ok(());
+        };
+        if body.get("method").and_then(|m| m.as_str()) != Some("tools/call") {
+            return Ok(());
+        }
+        let Some(params) = body.get_mut("params").and_then(|p| p.as_object_mut()) else {
+            return Ok(());
+        };
+
+        let arguments = params
+            .entry("arguments")
+            .or_insert_with(|| Value::Object(Default::default()));
+        if arguments.is_null() {
+            *arguments = Value::Object(Default::default());
+        }
+        let Some(arguments) = arguments.as_object_mut() else {
+            return Ok(());
+        };
+
+        arguments.remove(SESSION_ARG);
+        if let Some(ref session_id) = context.session_id {
+            arguments.insert(SESSION_ARG.to_string(), Value::from(session_id.as_str()));
+        }
+
+        request.body = serde_json::to_vec(&body)?;
+        Ok(())
+    }
+}
+
+/// Format a handle number as shown to clients
+pub fn format_handle(handle: u32) -> String {
+    format!("#{}", handle)
+}
+
+/// Parse a handle such as `#3`
+///
+/// Only the `#` form is accepted, since bare numbers are valid paper IDs for
+/// some sources.
+pub fn parse_handle(value: &str) -> Option<u32> {
+    value.trim().strip_prefix('#')?.parse().ok()
+}
+
+/// Parse a handle in an argument that only ever holds handles, where the `#`
+/// is optional
+fn parse_handle_lenient(value: &str) -> Option<u32> {
+    let value = value.trim();
+    value.strip_prefix('#').unwrap_or(value).parse().ok()
+}
+
+/// Whether two papers are the same record from the same source
+fn same_paper(a: &Paper, b: &Paper) -> bool {
+    a.source == b.source && a.paper_id == b.paper_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+    use serde_json::json;
+
+    fn paper(id: &str) -> Paper {
+        PaperBuilder::new(id, format!("Paper {}", id), "", SourceType::Arxiv).build()
+    }
+
+    #[test]
+    fn test_handles_are_stable_and_never_reused() {
+        let mut set = WorkingSet::new();
+        assert_eq!(set.add(&paper("a")), 1);
+        assert_eq!(set.add(&paper("b")), 2);
+        assert_eq!(set.add(&paper("a")), 1);
+        assert_eq!(set.len(), 2);
+
+        assert!(set.remove(2));
+        assert!(!set.remove(2));
+        assert_eq!(set.add(&paper("c")), 3);
+        assert_eq!(set.add(&paper("b")), 4);
+    }
+
+    #[test]
+    fn test_remove_unpinned() {
+        let mut set = WorkingSet::new();
+        set.add(&paper("a"));
+        set.add(&paper("b"));
+        set.add(&paper("c"));
+        assert!(set.set_pinned(2, true));
+        assert!(!set.set_pinned(9, true));
+
+        assert_eq!(set.remove_unpinned(), vec![1, 3]);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.entries()[0].handle_label(), "#2");
+    }
+
+    #[test]
+    fn test_check_handles() {
+        let mut set = WorkingSet::new();
+        set.add(&paper("a"));
+        set.add(&paper("b"));
+
+        assert_eq!(
+            set.check_handles(&["#2".to_string(), "1".to_string()]),
+            Ok(vec![2, 1])
+        );
+        let err = set
+            .check_handles(&["#1".to_string(), "#7".to_string()])
+            .unwrap_err();
+        assert!(err.contains("#7"));
+        assert!(!err.contains("#1,"));
+    }
+
+    #[test]
+    fn test_session_resolve() {
+        let session = Session::new();
+        let list = session.paper_list(&[paper("2301.00001")], Verbosity::Ids);
+        assert_eq!(list.papers[0].handle.as_deref(), Some("#1"));
+
+        assert_eq!(
+            session.resolve("#1").unwrap().unwrap().paper_id,
+            "2301.00001"
+        );
+        assert!(session.resolve("2301.00001").unwrap().is_none());
+        assert!(session
+            .resolve("#2")
+            .unwrap_err()
+            .contains("Unknown handle"));
+
+        assert_eq!(
+            session.resolve_id(" #1 ", None).unwrap(),
+            ("2301.00001".to_string(), Some("arxiv".to_string()))
+        );
+        assert_eq!(
+            session.resolve_id("#1", Some("semantic")).unwrap().1,
+            Some("semantic".to_string())
+        );
+    }
+
+    #[test]
+    fn test_session_resolve_version() {
+        let session = Session::new();
+        let mut published = PaperBuilder::new("W42", "Paper", "", SourceType::OpenAlex)
+            .doi("10.1234/acl.42")
+            .build();
+        published.versions = vec![paper("2301.00001")];
+        session.paper_list(&[published], Verbosity::Ids);
+
+        assert_eq!(
+            session
+                .resolve_version("#1", None, Some(VersionKind::Preprint))
+                .unwrap(),
+            ("2301.00001".to_string(), Some("arxiv".to_string()))
+        );
+        assert_eq!(
+            session.resolve_version("#1", None, None).unwrap(),
+            ("W42".to_string(), Some("openalex".to_string()))
+        );
+        assert_eq!(
+            session
+                .resolve_version("#1", None, Some(VersionKind::Published))
+                .unwrap()
+                .0,
+            "W42"
+        );
+        assert!(session
+            .resolve_version("2301.00001", None, Some(VersionKind::Preprint))
+            .unwrap_err()
+            .contains("working-set paper"));
+    }
+
+    #[test]
+    fn test_session_store() {
+        let store = SessionStore::new();
+        store.get(Some("a")).working_set().add(&paper("x"));
+
+        assert_eq!(store.get(Some("a")).working_set().len(), 1);
+        assert!(store.get(Some("b")).working_set().is_empty());
+        assert!(store.get(None).working_set().is_empty());
+        assert_eq!(store.len(), 3);
+
+        store.remove("a");
+        assert!(store.get(Some("a")).working_set().is_empty());
+    }
+
+    #[test]
+    fn test_parse_handle() {
+        assert_eq!(parse_handle("#12"), Some(12));
+        assert_eq!(parse_handle(" #3 "), Some(3));
+        assert_eq!(parse_handle("12"), None);
+        assert_eq!(parse_handle("#x"), None);
+        assert_eq!(parse_handle_lenient("12"), Some(12));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_tags_tool_calls() {
+        let call = |args: Value| {
+            let body = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "list_working_set", "arguments": args}
+            });
+            ServerHttpRequest::new(
+                http::Method::POST,
+                "/".parse().unwrap(),
+                http::HeaderMap::new(),
+                serde_json::to_vec(&body).unwrap(),
+            )
+        };
+        let context = ServerHttpContext::with_session("r1".to_string(), "s1".to_string());
+
+        let mut request = call(Value::Null);
+        SessionMiddleware
+            .on_request(&mut request, &context)
+            .await
+            .unwrap();
+        let mut args: Value =
+            serde_json::from_slice::<Value>(&request.body).unwrap()["params"]["arguments"].clone();
+        assert_eq!(take_session_id(&mut args).as_deref(), Some("s1"));
+        assert_eq!(args, json!({}));
+
+        // Clients cannot pick someone else's session
+        let mut request = call(json!({SESSION_ARG: "s2"}));
+        SessionMiddleware
+            .on_request(&mut request, &ServerHttpContext::new("r2".to_string()))
+            .await
+            .unwrap();
+        let mut args: Value =
+            serde_json::from_slice::<Value>(&request.body).unwrap()["params"]["arguments"].clone();
+        assert_eq!(take_session_id(&mut args), None);
+    }
+}
@@ -5,12 +5,26 @@ use std::sync::Arc;
 
 use serde_json::Value;
 
+use super::args::{
+    output_schema, with_source_enum, CheckSavedSearchesArgs, CitationArgs, CouplingArgs,
+    DeduplicatePapersArgs, DownloadPaperArgs, DropPapersArgs, ExportWorkingSetArgs,
+    FormatCitationArgs, GetAuthorArgs, GetPaperArgs, ListWorkingSetArgs, LookupByDoiArgs,
+    PaperIdArgs, PinPapersArgs, SearchByAuthorArgs, SearchPapersArgs, SnowballArgs, ToolArgs,
+};
+use super::output::{
+    AuthorResult, CitationResult, CouplingResult, ExportResult, PaperList, PaperView,
+    SavedSearchesResult, SnowballResult, ToolOutput, WorkingSetUpdate,
+};
+use super::session::Session;
+use crate::models::{DownloadResult, ReadResult};
 use crate::sources::SourceRegistry;
 
 pub use super::unified_tools::{
-    DeduplicatePapersHandler, DownloadPaperHandler, GetCitationsHandler, GetPaperHandler,
-    GetReferencesHandler, LookupByDoiHandler, ReadPaperHandler, SearchByAuthorHandler,
-    SearchPapersHandler,
+    CheckSavedSearchesHandler, CouplingAnalysisHandler, DeduplicatePapersHandler,
+    DownloadPaperHandler, DropPapersHandler, ExportWorkingSetHandler, FormatCitationHandler,
+    GetAuthorHandler, GetCitationsHandler, GetPaperHandler, GetReferencesHandler,
+    ListWorkingSetHandler, LookupByDoiHandler, PinPapersHandler, ReadPaperHandler,
+    SearchByAuthorHandler, SearchPapersHandler, SnowballHandler,
 };
 
 /// An MCP tool that can be called by the client
@@ -25,6 +39,9 @@ pub struct Tool {
     /// JSON Schema for input parameters
     pub input_schema: serde_json::Value,
 
+    /// JSON Schema for the result, if the tool declares one
+    pub output_schema: Option<serde_json::Value>,
+
     /// Handler function to execute the tool
     pub handler: Arc<dyn ToolHandler>,
 }
@@ -35,6 +52,7 @@ impl std::fmt::Debug for Tool {
             .field("name", &self.name)
             .field("description", &self.description)
             .field("input_schema", &self.input_schema)
+            .field("output_schema", &self.output_schema)
             .finish()
     }
 }
@@ -42,8 +60,35 @@ impl std::fmt::Debug for Tool {
 /// Handler for executing a tool
 #[async_trait::async_trait]
 pub trait ToolHandler: Send + Sync + std::fmt::Debug {
-    /// Execute the tool with the given arguments
-    async fn execute(&self, args: Value) -> Result<Value, String>;
+    /// Execute the tool with the given arguments on behalf of `session`
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError>;
+}
+
+/// Error from a failed tool call
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ToolError {
+    /// The arguments were rejected before the tool did any work
+    #[error("{0}")]
+    InvalidParams(String),
+
+    /// The tool ran but failed, e.g. because a source was unreachable
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl From<String> for ToolError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+impl From<ToolError> for pmcp::Error {
+    fn from(error: ToolError) -> Self {
+        match error {
+            ToolError::InvalidParams(message) => pmcp::Error::invalid_params(message),
+            ToolError::Failed(message) => pmcp::Error::internal(message),
+        }
+    }
 }
 
 /// Registry for all MCP tools
@@ -69,9 +114,10 @@ impl ToolRegistry {
         registry
     }
 
-    /// Register unified tools (9 tools total instead of per-source tools)
+    /// Register unified tools (18 tools total instead of per-source tools)
     fn register_unified_tools(&mut self, sources: &Arc<Vec<Arc<dyn crate::sources::Source>>>) {
         let sources_count = sources.len();
+        let source_ids: Vec<&str> = sources.iter().map(|s| s.id()).collect();
 
         // 1. search_papers - Search across all or specific sources
         self.register(Tool {
@@ -80,33 +126,8 @@ impl ToolRegistry {
                 "Search for papers across {} available research sources",
                 sources_count
             ),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "query": {
-                        "type": "string",
-                        "description": "Search query string"
-                    },
-                    "source": {
-                        "type": "string",
-                        "description": "Specific source to search (e.g., 'arxiv', 'semantic', 'pubmed'). If not specified, searches all sources."
-                    },
-                    "max_results": {
-                        "type": "integer",
-                        "description": "Maximum number of results per source",
-                        "default": 10
-                    },
-                    "year": {
-                        "type": "string",
-                        "description": "Year filter (e.g., '2020', '2018-2022', '2010-', '-2015')"
-                    },
-                    "category": {
-                        "type": "string",
-                        "description": "Category/subject filter"
-                    }
-                },
-                "required": ["query"]
-            }),
+            input_schema: with_source_enum(SearchPapersArgs::schema(), &source_ids),
+            output_schema: Some(output_schema::<PaperList>()),
             handler: Arc::new(SearchPapersHandler {
                 sources: sources.clone(),
             }),
@@ -119,25 +140,8 @@ impl ToolRegistry {
                 "Search for papers by author across {} research sources",
                 sources_count
             ),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "author": {
-                        "type": "string",
-                        "description": "Author name"
-                    },
-                    "source": {
-                        "type": "string",
-                        "description": "Specific source to search. If not specified, searches all sources with author search capability."
-                    },
-                    "max_results": {
-                        "type": "integer",
-                        "description": "Maximum results per source",
-                        "default": 10
-                    }
-                },
-                "required": ["author"]
-            }),
+            input_schema: with_source_enum(SearchByAuthorArgs::schema(), &source_ids),
+            output_schema: Some(output_schema::<PaperList>()),
             handler: Arc::new(SearchByAuthorHandler {
                 sources: sources.clone(),
             }),
@@ -147,20 +151,8 @@ impl ToolRegistry {
         self.register(Tool {
             name: "get_paper".to_string(),
             description: "Get detailed metadata for a specific paper. Source is auto-detected from paper ID format.".to_string(),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "paper_id": {
-                        "type": "string",
-                        "description": "Paper identifier (e.g., '2301.12345', 'arXiv:2301.12345', 'PMC12345678')"
-                    },
-                    "source": {
-                        "type": "string",
-                        "description": "Override auto-detection and use specific source"
-                    }
-                },
-                "required": ["paper_id"]
-            }),
+            input_schema: with_source_enum(GetPaperArgs::schema(), &source_ids),
+            output_schema: Some(output_schema::<PaperView>()),
             handler: Arc::new(GetPaperHandler {
                 sources: sources.clone(),
             }),
@@ -170,30 +162,8 @@ impl ToolRegistry {
         self.register(Tool {
             name: "download_paper".to_string(),
             description: "Download a paper PDF to your local filesystem. Source is auto-detected from paper ID format.".to_string(),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "paper_id": {
-                        "type": "string",
-                        "description": "Paper identifier"
-                    },
-                    "source": {
-                        "type": "string",
-                        "description": "Override auto-detection and use specific source"
-                    },
-                    "output_path": {
-                        "type": "string",
-                        "description": "Save path for the PDF",
-                        "default": "./downloads"
-                    },
-                    "auto_filename": {
-                        "type": "boolean",
-                        "description": "Auto-generate filename from paper title",
-                        "default": true
-                    }
-                },
-                "required": ["paper_id"]
-            }),
+            input_schema: with_source_enum(DownloadPaperArgs::schema(), &source_ids),
+            output_schema: Some(output_schema::<DownloadResult>()),
             handler: Arc::new(DownloadPaperHandler {
                 sources: sources.clone(),
             }),
@@ -203,20 +173,8 @@ impl ToolRegistry {
         self.register(Tool {
             name: "read_paper".to_string(),
             description: "Extract and return the full text content from a paper PDF. Source is auto-detected from paper ID format. Requires poppler to be installed.".to_string(),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "paper_id": {
-                        "type": "string",
-                        "description": "Paper identifier"
-                    },
-                    "source": {
-                        "type": "string",
-                        "description": "Override auto-detection and use specific source"
-                    }
-                },
-                "required": ["paper_id"]
-            }),
+            input_schema: with_source_enum(PaperIdArgs::schema(), &source_ids),
+            output_schema: Some(output_schema::<ReadResult>()),
             handler: Arc::new(ReadPaperHandler {
                 sources: sources.clone(),
             }),
@@ -228,26 +186,8 @@ impl ToolRegistry {
             description:
                 "Get papers that cite a specific paper. Prefers Semantic Scholar for best results."
                     .to_string(),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "paper_id": {
-                        "type": "string",
-                        "description": "Paper identifier"
-                    },
-                    "source": {
-                        "type": "string",
-                        "description": "Specific source (default: 'semantic')",
-                        "default": "semantic"
-                    },
-                    "max_results": {
-                        "type": "integer",
-                        "description": "Maximum results",
-                        "default": 20
-                    }
-                },
-                "required": ["paper_id"]
-            }),
+            input_schema: with_source_enum(CitationArgs::schema(), &source_ids),
+            output_schema: Some(output_schema::<PaperList>()),
             handler: Arc::new(GetCitationsHandler {
                 sources: sources.clone(),
             }),
@@ -257,26 +197,8 @@ impl ToolRegistry {
         self.register(Tool {
             name: "get_references".to_string(),
             description: "Get papers referenced by a specific paper. Prefers Semantic Scholar for best results.".to_string(),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "paper_id": {
-                        "type": "string",
-                        "description": "Paper identifier"
-                    },
-                    "source": {
-                        "type": "string",
-                        "description": "Specific source (default: 'semantic')",
-                        "default": "semantic"
-                    },
-                    "max_results": {
-                        "type": "integer",
-                        "description": "Maximum results",
-                        "default": 20
-                    }
-                },
-                "required": ["paper_id"]
-            }),
+            input_schema: with_source_enum(CitationArgs::schema(), &source_ids),
+            output_schema: Some(output_schema::<PaperList>()),
             handler: Arc::new(GetReferencesHandler {
                 sources: sources.clone(),
             }),
@@ -285,21 +207,10 @@ impl ToolRegistry {
         // 8. lookup_by_doi - DOI lookup across all sources
         self.register(Tool {
             name: "lookup_by_doi".to_string(),
-            description: "Look up a paper by its DOI across all sources that support DOI lookup.".to_string(),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "doi": {
-                        "type": "string",
-                        "description": "Digital Object Identifier (e.g., '10.48550/arXiv.2301.12345')"
-                    },
-                    "source": {
-                        "type": "string",
-                        "description": "Specific source to query. If not specified, queries all sources with DOI lookup capability."
-                    }
-                },
-                "required": ["doi"]
-            }),
+            description: "Look up a paper by its DOI across all sources that support DOI lookup."
+                .to_string(),
+            input_schema: with_source_enum(LookupByDoiArgs::schema(), &source_ids),
+            output_schema: Some(output_schema::<PaperView>()),
             handler: Arc::new(LookupByDoiHandler {
                 sources: sources.clone(),
             }),
@@ -308,28 +219,105 @@ impl ToolRegistry {
         // 9. deduplicate_papers - Remove duplicates
         self.register(Tool {
             name: "deduplicate_papers".to_string(),
-            description: "Remove duplicate papers from a list using DOI matching and title similarity.".to_string(),
-            input_schema: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "papers": {
-                        "type": "array",
-                        "description": "Array of paper objects",
-                        "items": {
-                            "type": "object"
-                        }
-                    },
-                    "strategy": {
-                        "type": "string",
-                        "description": "Deduplication strategy: 'first' (keep first), 'last' (keep last), or 'mark' (add is_duplicate flag)",
-                        "enum": ["first", "last", "mark"],
-                        "default": "first"
-                    }
-                },
-                "required": ["papers"]
-            }),
+            description:
+                "Remove duplicate papers from a list using DOI matching and title similarity."
+                    .to_string(),
+            input_schema: DeduplicatePapersArgs::schema(),
+            output_schema: Some(output_schema::<PaperList>()),
             handler: Arc::new(DeduplicatePapersHandler),
         });
+
+        // 10-13. Working set - papers returned during this session, by handle
+        self.register(Tool {
+            name: "list_working_set".to_string(),
+            description: "List the papers returned so far in this session with their handles (e.g. '#3'). Handles can be used as paper_id in any tool.".to_string(),
+            input_schema: ListWorkingSetArgs::schema(),
+            output_schema: Some(output_schema::<PaperList>()),
+            handler: Arc::new(ListWorkingSetHandler),
+        });
+
+        self.register(Tool {
+            name: "pin_papers".to_string(),
+            description:
+                "Pin (or unpin) working-set papers so they are kept when dropping unpinned papers."
+                    .to_string(),
+            input_schema: PinPapersArgs::schema(),
+            output_schema: Some(output_schema::<WorkingSetUpdate>()),
+            handler: Arc::new(PinPapersHandler),
+        });
+
+        self.register(Tool {
+            name: "drop_papers".to_string(),
+            description:
+                "Remove papers from the working set by handle, or drop every unpinned paper."
+                    .to_string(),
+            input_schema: DropPapersArgs::schema(),
+            output_schema: Some(output_schema::<WorkingSetUpdate>()),
+            handler: Arc::new(DropPapersHandler),
+        });
+
+        self.register(Tool {
+            name: "export_working_set".to_string(),
+            description: "Export working-set papers as BibTeX, RIS, CSL-JSON, EndNote XML, MODS, CSV, JSON or JSON Lines, optionally to a file, or as Markdown notes written one per paper into a notes directory (e.g. an Obsidian vault) without overwriting notes the user has edited.".to_string(),
+            input_schema: ExportWorkingSetArgs::schema(),
+            output_schema: Some(output_schema::<ExportResult>()),
+            handler: Arc::new(ExportWorkingSetHandler),
+        });
+
+        // 14. snowball - Citation graph around seed papers
+        self.register(Tool {
+            name: "snowball".to_string(),
+            description: "Build a citation graph by following citations and/or references from seed papers, merging duplicates across Semantic Scholar, OpenAlex and Dimensions. Optionally writes GraphML, GEXF, DOT or JSON.".to_string(),
+            input_schema: SnowballArgs::schema(),
+            output_schema: Some(output_schema::<SnowballResult>()),
+            handler: Arc::new(SnowballHandler {
+                sources: sources.clone(),
+            }),
+        });
+
+        // 15. coupling_analysis - Co-citation and bibliographic coupling
+        self.register(Tool {
+            name: "coupling_analysis".to_string(),
+            description: "Analyse a set of papers (default: the working set) for co-citation and bibliographic coupling, and list papers frequently cited alongside the set that are not in it.".to_string(),
+            input_schema: CouplingArgs::schema(),
+            output_schema: Some(output_schema::<CouplingResult>()),
+            handler: Arc::new(CouplingAnalysisHandler {
+                sources: sources.clone(),
+            }),
+        });
+
+        // 16. get_author - Author profile merged across sources
+        self.register(Tool {
+            name: "get_author".to_string(),
+            description: "Get an author profile (affiliations, paper and citation counts, h-index, top venues, co-authors, deduplicated publications) merged from OpenAlex, Semantic Scholar, DBLP and ORCID. Accepts a name or ORCID iD; when a name matches several people, returns candidates to choose from instead of merging them.".to_string(),
+            input_schema: GetAuthorArgs::schema(),
+            output_schema: Some(output_schema::<AuthorResult>()),
+            handler: Arc::new(GetAuthorHandler {
+                sources: sources.clone(),
+            }),
+        });
+
+        // 17. check_saved_searches - New papers for saved searches
+        self.register(Tool {
+            name: "check_saved_searches".to_string(),
+            description: "Re-run the user's saved searches and follows (created with `research-master watch add`, `watch follow-author` and `watch follow-paper`) and return only papers not seen in earlier runs: new search results, new publications by followed authors, and new papers citing followed papers. Papers are matched by DOI/source ID or near-identical title. Use it to produce a 'what's new' digest; new papers are added to the working set.".to_string(),
+            input_schema: CheckSavedSearchesArgs::schema(),
+            output_schema: Some(output_schema::<SavedSearchesResult>()),
+            handler: Arc::new(CheckSavedSearchesHandler {
+                sources: sources.clone(),
+            }),
+        });
+
+        // 18. format_citation - Citations in a built-in or CSL style
+        self.register(Tool {
+            name: "format_citation".to_string(),
+            description: "Format citations and a bibliography for papers (default: the working set) in APA, MLA, Chicago or BibTeX, or in any Citation Style Language style given as a .csl file (e.g. from the Zotero style repository), with an optional CSL locale file for other languages. Returns an in-text citation per paper and, for CSL styles, the sorted bibliography as text or HTML.".to_string(),
+            input_schema: FormatCitationArgs::schema(),
+            output_schema: Some(output_schema::<CitationResult>()),
+            handler: Arc::new(FormatCitationHandler {
+                sources: sources.clone(),
+            }),
+        });
     }
 
     /// Register a tool
@@ -347,12 +335,17 @@ impl ToolRegistry {
         self.tools.get(name)
     }
 
-    /// Execute a tool by name
-    pub async fn execute(&self, name: &str, args: Value) -> Result<Value, String> {
+    /// Execute a tool by name on behalf of `session`
+    pub async fn execute(
+        &self,
+        name: &str,
+        args: Value,
+        session: &Session,
+    ) -> Result<ToolOutput, ToolError> {
         let tool = self
             .get(name)
             .ok_or_else(|| format!("Tool '{}' not found", name))?;
 
-        tool.handler.execute(args).await
+        tool.handler.execute(args, session).await
     }
 }
@@ -1,10 +1,39 @@
 //! Unified tool handlers with smart source selection.
 
+use std::path::Path;
 use std::sync::Arc;
 
 use serde_json::Value;
 
-use super::tools::ToolHandler;
+use super::args::{
+    check_source, parse_args, CheckSavedSearchesArgs, CitationArgs, CitationStyle, CouplingArgs,
+    DeduplicatePapersArgs, DownloadPaperArgs, DropPapersArgs, ExportWorkingSetArgs,
+    FormatCitationArgs, GetAuthorArgs, GetPaperArgs, ListWorkingSetArgs, LookupByDoiArgs,
+    PaperIdArgs, PinPapersArgs, SearchByAuthorArgs, SearchPapersArgs, SnowballArgs,
+};
+use super::output::{
+    AuthorResult, AuthorStatus, AuthorView, CitationResult, CitationView, CouplingResult,
+    ExportResult, GraphLink, MissingPaperView, PairView, PaperList, SavedSearchView,
+    SavedSearchesResult, SnowballResult, ToolOutput, Verbosity, WorkingSetUpdate,
+};
+use super::session::{format_handle, Session, WorkingSet};
+use super::tools::{ToolError, ToolHandler};
+use crate::authors::{AuthorOptions, AuthorResolver};
+use crate::config::get_config;
+use crate::csl::{Item, Locale, Processor, Style};
+use crate::enrich::Enricher;
+use crate::graph::{
+    citation_paper_id, parse_year_range, resolve_seed, Coupling, CouplingOptions, Snowball,
+    SnowballOptions,
+};
+use crate::models::AuthorLookup;
+use crate::open_access::{OpenAccessFilter, OpenAccessResolver};
+use crate::utils::CacheService;
+use crate::versions::{find_version, group_versions};
+use crate::watch::{WatchStore, Watcher};
+
+/// Papers listed (and added to the working set) from a snowball graph
+const MAX_LISTED_GRAPH_PAPERS: usize = 25;
 
 /// Helper function to auto-detect the appropriate source for a paper ID
 fn auto_detect_source(
@@ -78,6 +107,18 @@ fn auto_detect_source(
     Err("Could not auto-detect source. Please specify source explicitly.".to_string())
 }
 
+/// Build the paper list for a citation or reference response
+fn citation_list(
+    session: &Session,
+    response: &crate::models::SearchResponse,
+    verbosity: Verbosity,
+) -> PaperList {
+    let mut list = session.paper_list(&response.papers, verbosity);
+    list.total_results = response.total_results;
+    list.has_more = Some(response.has_more);
+    list
+}
+
 /// Helper function to check if a string starts with a specific prefix (case-insensitive)
 fn paper_id_upper_start(paper_id: &str, prefix: &str) -> bool {
     if paper_id.len() < prefix.len() {
@@ -95,28 +136,13 @@ pub struct SearchPapersHandler {
 
 #[async_trait::async_trait]
 impl ToolHandler for SearchPapersHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let query = args
-            .get("query")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'query' parameter")?;
-
-        let max_results = args
-            .get("max_results")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(10) as usize;
-
-        let year = args
-            .get("year")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: SearchPapersArgs = parse_args("search_papers", args)?;
 
-        let category = args
-            .get("category")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
-
-        let source_filter = args.get("source").and_then(|v| v.as_str());
+        let source_filter = args.source.as_deref();
+        if let Some(filter) = source_filter {
+            check_source(&self.sources, filter)?;
+        }
 
         let mut all_results = Vec::new();
 
@@ -133,14 +159,21 @@ impl ToolHandler for SearchPapersHandler {
                 continue;
             }
 
-            let mut search_query = crate::models::SearchQuery::new(query).max_results(max_results);
+            let mut search_query =
+                crate::models::SearchQuery::new(&args.query).max_results(args.max_results);
 
-            if let Some(ref year) = year {
+            if let Some(ref year) = args.year {
                 search_query = search_query.year(year);
             }
-            if let Some(ref cat) = category {
+            if let Some(ref cat) = args.category {
                 search_query = search_query.category(cat);
             }
+            if let Some(sort_by) = args.sort_by {
+                search_query = search_query.sort_by(sort_by);
+            }
+            if let Some(sort_order) = args.sort_order {
+                search_query = search_query.sort_order(sort_order);
+            }
 
             match source.search(&search_query).await {
                 Ok(response) => {
@@ -152,7 +185,41 @@ impl ToolHandler for SearchPapersHandler {
             }
         }
 
-        serde_json::to_value(all_results).map_err(|e| e.to_string())
+        if let Some(prefer) = args.versions {
+            all_results = group_versions(all_results, prefer);
+        }
+
+        if let Some(fields) = args.enrich {
+            let mut options = get_config().enrich.options();
+            if !fields.is_empty() {
+                options = options.fields(fields);
+            }
+            let enricher = Enricher::new(self.sources.iter().cloned().collect(), options);
+            let (enriched, report) = enricher.enrich(all_results).await;
+            tracing::info!("Enrichment: {}", report.summary());
+            all_results = enriched;
+        }
+
+        let filter = OpenAccessFilter::new()
+            .open_access_only(args.open_access_only)
+            .licenses(args.licenses);
+        if args.open_access || filter.is_active() {
+            let cache = CacheService::new();
+            let _ = cache.initialize();
+            let resolver =
+                OpenAccessResolver::new(self.sources.iter().cloned().collect()).with_cache(cache);
+            if resolver.source_ids().is_empty() {
+                return Err(ToolError::Failed(
+                    "Open-access lookups need the openalex or unpaywall source".to_string(),
+                ));
+            }
+            for error in resolver.annotate(&mut all_results).await {
+                tracing::warn!("Open-access lookup failed: {}", error);
+            }
+            all_results = filter.apply(all_results);
+        }
+
+        ToolOutput::papers(session.paper_list(&all_results, args.verbosity))
     }
 }
 
@@ -164,20 +231,13 @@ pub struct SearchByAuthorHandler {
 
 #[async_trait::async_trait]
 impl ToolHandler for SearchByAuthorHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let author = args
-            .get("author")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'author' parameter")?;
-
-        let max_results = args
-            .get("max_results")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(10) as usize;
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: SearchByAuthorArgs = parse_args("search_by_author", args)?;
 
-        let year = args.get("year").and_then(|v| v.as_str());
-
-        let source_filter = args.get("source").and_then(|v| v.as_str());
+        let source_filter = args.source.as_deref();
+        if let Some(filter) = source_filter {
+            check_source(&self.sources, filter)?;
+        }
 
         let mut all_results = Vec::new();
 
@@ -194,7 +254,10 @@ impl ToolHandler for SearchByAuthorHandler {
                 continue;
             }
 
-            match source.search_by_author(author, max_results, year).await {
+            match source
+                .search_by_author(&args.author, args.max_results, args.year.as_deref())
+                .await
+            {
                 Ok(response) => {
                     all_results.extend(response.papers);
                 }
@@ -204,7 +267,7 @@ impl ToolHandler for SearchByAuthorHandler {
             }
         }
 
-        serde_json::to_value(all_results).map_err(|e| e.to_string())
+        ToolOutput::papers(session.paper_list(&all_results, args.verbosity))
     }
 }
 
@@ -216,16 +279,13 @@ pub struct GetPaperHandler {
 
 #[async_trait::async_trait]
 impl ToolHandler for GetPaperHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let paper_id = args
-            .get("paper_id")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'paper_id' parameter")?;
-
-        let source_override = args.get("source").and_then(|v| v.as_str());
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: GetPaperArgs = parse_args("get_paper", args)?;
+        let (paper_id, source) = session.resolve_id(&args.paper_id, args.source.as_deref())?;
+        let paper_id = paper_id.as_str();
 
         // Find the appropriate source
-        let source = self.find_source(paper_id, source_override)?;
+        let source = self.find_source(paper_id, source.as_deref())?;
 
         // For now, we'll do a search with the paper ID as the query
         let search_query = crate::models::SearchQuery::new(paper_id).max_results(1);
@@ -236,10 +296,14 @@ impl ToolHandler for GetPaperHandler {
             .map_err(|e| e.to_string())?;
 
         if response.papers.is_empty() {
-            return Err(format!("Paper '{}' not found in {}", paper_id, source.id()));
+            return Err(ToolError::Failed(format!(
+                "Paper '{}' not found in {}",
+                paper_id,
+                source.id()
+            )));
         }
 
-        serde_json::to_value(&response.papers[0]).map_err(|e| e.to_string())
+        ToolOutput::paper(session.paper_view(&response.papers[0], args.verbosity))
     }
 }
 
@@ -251,27 +315,32 @@ pub struct DownloadPaperHandler {
 
 #[async_trait::async_trait]
 impl ToolHandler for DownloadPaperHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let paper_id = args
-            .get("paper_id")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'paper_id' parameter")?;
-
-        let source_override = args.get("source").and_then(|v| v.as_str());
-
-        let output_path = args
-            .get("output_path")
-            .and_then(|v| v.as_str())
-            .unwrap_or("./downloads");
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: DownloadPaperArgs = parse_args("download_paper", args)?;
+        let (paper_id, source) =
+            session.resolve_version(&args.paper_id, args.source.as_deref(), args.version)?;
 
         // Find the appropriate source
-        let source = self.find_source(paper_id, source_override)?;
+        let source = self.find_source(&paper_id, source.as_deref())?;
 
-        let request = crate::models::DownloadRequest::new(paper_id, output_path);
+        let request = crate::models::DownloadRequest::new(&paper_id, &args.output_path);
 
         let result = source.download(&request).await.map_err(|e| e.to_string())?;
 
-        serde_json::to_value(result).map_err(|e| e.to_string())
+        let text = if result.success {
+            format!(
+                "Downloaded `{}` to `{}` ({} bytes)",
+                paper_id, result.path, result.bytes
+            )
+        } else {
+            format!(
+                "Download of `{}` failed: {}",
+                paper_id,
+                result.error.as_deref().unwrap_or("unknown error")
+            )
+        };
+
+        ToolOutput::new(result, text)
     }
 }
 
@@ -283,22 +352,28 @@ pub struct ReadPaperHandler {
 
 #[async_trait::async_trait]
 impl ToolHandler for ReadPaperHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let paper_id = args
-            .get("paper_id")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'paper_id' parameter")?;
-
-        let source_override = args.get("source").and_then(|v| v.as_str());
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: PaperIdArgs = parse_args("read_paper", args)?;
+        let (paper_id, source) = session.resolve_id(&args.paper_id, args.source.as_deref())?;
 
         // Find the appropriate source
-        let source = self.find_source(paper_id, source_override)?;
+        let source = self.find_source(&paper_id, source.as_deref())?;
 
-        let request = crate::models::ReadRequest::new(paper_id, "./downloads");
+        let request = crate::models::ReadRequest::new(&paper_id, "./downloads");
 
         let result = source.read(&request).await.map_err(|e| e.to_string())?;
 
-        serde_json::to_value(result).map_err(|e| e.to_string())
+        let text = if result.success {
+            result.text.clone()
+        } else {
+            format!(
+                "Could not read `{}`: {}",
+                paper_id,
+                result.error.as_deref().unwrap_or("unknown error")
+            )
+        };
+
+        ToolOutput::new(result, text)
     }
 }
 
@@ -310,21 +385,17 @@ pub struct GetCitationsHandler {
 
 #[async_trait::async_trait]
 impl ToolHandler for GetCitationsHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let paper_id = args
-            .get("paper_id")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'paper_id' parameter")?;
-
-        let source_override = args.get("source").and_then(|v| v.as_str());
-
-        let max_results = args
-            .get("max_results")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(20) as usize;
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: CitationArgs = parse_args("get_citations", args)?;
 
         // Default to Semantic Scholar if not specified
-        let source_id = source_override.unwrap_or("semantic");
+        let source_id = args.source.as_deref().unwrap_or("semantic");
+        check_source(&self.sources, source_id)?;
+
+        let paper_id = match session.resolve(&args.paper_id)? {
+            Some(paper) => citation_paper_id(&paper, source_id).unwrap_or(paper.paper_id),
+            None => args.paper_id.clone(),
+        };
 
         let source = self
             .sources
@@ -333,17 +404,20 @@ impl ToolHandler for GetCitationsHandler {
             .ok_or_else(|| format!("Source '{}' not found", source_id))?;
 
         if !source.supports_citations() {
-            return Err(format!("Source '{}' does not support citations", source_id));
+            return Err(ToolError::Failed(format!(
+                "Source '{}' does not support citations",
+                source_id
+            )));
         }
 
-        let request = crate::models::CitationRequest::new(paper_id).max_results(max_results);
+        let request = crate::models::CitationRequest::new(&paper_id).max_results(args.max_results);
 
         let response = source
             .get_citations(&request)
             .await
             .map_err(|e| e.to_string())?;
 
-        serde_json::to_value(response).map_err(|e| e.to_string())
+        ToolOutput::papers(citation_list(session, &response, args.verbosity))
     }
 }
 
@@ -355,21 +429,17 @@ pub struct GetReferencesHandler {
 
 #[async_trait::async_trait]
 impl ToolHandler for GetReferencesHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let paper_id = args
-            .get("paper_id")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'paper_id' parameter")?;
-
-        let source_override = args.get("source").and_then(|v| v.as_str());
-
-        let max_results = args
-            .get("max_results")
-            .and_then(|v| v.as_u64())
-            .unwrap_or(20) as usize;
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: CitationArgs = parse_args("get_references", args)?;
 
         // Default to Semantic Scholar if not specified
-        let source_id = source_override.unwrap_or("semantic");
+        let source_id = args.source.as_deref().unwrap_or("semantic");
+        check_source(&self.sources, source_id)?;
+
+        let paper_id = match session.resolve(&args.paper_id)? {
+            Some(paper) => citation_paper_id(&paper, source_id).unwrap_or(paper.paper_id),
+            None => args.paper_id.clone(),
+        };
 
         let source = self
             .sources
@@ -378,20 +448,20 @@ impl ToolHandler for GetReferencesHandler {
             .ok_or_else(|| format!("Source '{}' not found", source_id))?;
 
         if !source.supports_citations() {
-            return Err(format!(
+            return Err(ToolError::Failed(format!(
                 "Source '{}' does not support references",
                 source_id
-            ));
+            )));
         }
 
-        let request = crate::models::CitationRequest::new(paper_id).max_results(max_results);
+        let request = crate::models::CitationRequest::new(&paper_id).max_results(args.max_results);
 
         let response = source
             .get_references(&request)
             .await
             .map_err(|e| e.to_string())?;
 
-        serde_json::to_value(response).map_err(|e| e.to_string())
+        ToolOutput::papers(citation_list(session, &response, args.verbosity))
     }
 }
 
@@ -403,13 +473,14 @@ pub struct LookupByDoiHandler {
 
 #[async_trait::async_trait]
 impl ToolHandler for LookupByDoiHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let doi = args
-            .get("doi")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'doi' parameter")?;
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: LookupByDoiArgs = parse_args("lookup_by_doi", args)?;
+        let doi = args.doi.as_str();
 
-        let source_filter = args.get("source").and_then(|v| v.as_str());
+        let source_filter = args.source.as_deref();
+        if let Some(filter) = source_filter {
+            check_source(&self.sources, filter)?;
+        }
 
         // Try each source that supports DOI lookup
         for source in self.sources.iter() {
@@ -427,7 +498,7 @@ impl ToolHandler for LookupByDoiHandler {
 
             match source.get_by_doi(doi).await {
                 Ok(paper) => {
-                    return serde_json::to_value(paper).map_err(|e| e.to_string());
+                    return ToolOutput::paper(session.paper_view(&paper, args.verbosity));
                 }
                 Err(e) => {
                     tracing::debug!("DOI lookup failed for {}: {}", source.id(), e);
@@ -435,7 +506,10 @@ impl ToolHandler for LookupByDoiHandler {
             }
         }
 
-        Err(format!("Paper with DOI '{}' not found", doi))
+        Err(ToolError::Failed(format!(
+            "Paper with DOI '{}' not found",
+            doi
+        )))
     }
 }
 
@@ -445,50 +519,626 @@ pub struct DeduplicatePapersHandler;
 
 #[async_trait::async_trait]
 impl ToolHandler for DeduplicatePapersHandler {
-    async fn execute(&self, args: Value) -> Result<Value, String> {
-        let papers: Vec<crate::models::Paper> = serde_json::from_value(
-            args.get("papers")
-                .ok_or("Missing 'papers' parameter")?
-                .clone(),
-        )
-        .map_err(|e| format!("Invalid papers array: {}", e))?;
-
-        let strategy_str = args
-            .get("strategy")
-            .and_then(|v| v.as_str())
-            .unwrap_or("first");
-
-        let strategy = match strategy_str {
-            "last" => crate::utils::DuplicateStrategy::Last,
-            "mark" => crate::utils::DuplicateStrategy::Mark,
-            _ => crate::utils::DuplicateStrategy::First,
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: DeduplicatePapersArgs = parse_args("deduplicate_papers", args)?;
+
+        let deduped = crate::utils::deduplicate_papers(args.papers, args.strategy);
+
+        ToolOutput::papers(session.paper_list(&deduped, args.verbosity))
+    }
+}
+
+/// Handler for listing the session's working set
+#[derive(Debug)]
+pub struct ListWorkingSetHandler;
+
+#[async_trait::async_trait]
+impl ToolHandler for ListWorkingSetHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: ListWorkingSetArgs = parse_args("list_working_set", args)?;
+
+        let (papers, pinned): (Vec<_>, Vec<_>) = session
+            .working_set()
+            .entries()
+            .iter()
+            .filter(|e| e.pinned || !args.pinned_only)
+            .map(|e| (e.paper.clone(), e.pinned))
+            .unzip();
+
+        let mut list = session.paper_list(&papers, args.verbosity);
+        for (view, pinned) in list.papers.iter_mut().zip(pinned) {
+            view.pinned = Some(pinned);
+        }
+
+        if list.papers.is_empty() {
+            let text = if args.pinned_only {
+                "No papers are pinned."
+            } else {
+                "The working set is empty. Papers returned by other tools are added to it."
+            };
+            return ToolOutput::new(list, text);
+        }
+
+        ToolOutput::papers(list)
+    }
+}
+
+/// Handler for pinning and unpinning working-set papers
+#[derive(Debug)]
+pub struct PinPapersHandler;
+
+#[async_trait::async_trait]
+impl ToolHandler for PinPapersHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: PinPapersArgs = parse_args("pin_papers", args)?;
+
+        let mut working_set = session.working_set();
+        let handles = working_set.check_handles(&args.handles)?;
+        for &handle in &handles {
+            working_set.set_pinned(handle, !args.unpin);
+        }
+
+        let update = working_set_update(&working_set, &handles);
+        let text = format!(
+            "{} {}. {} of {} papers in the working set are pinned.",
+            if args.unpin { "Unpinned" } else { "Pinned" },
+            update.handles.join(", "),
+            update.pinned,
+            update.remaining
+        );
+        ToolOutput::new(update, text)
+    }
+}
+
+/// Handler for dropping papers from the working set
+#[derive(Debug)]
+pub struct DropPapersHandler;
+
+#[async_trait::async_trait]
+impl ToolHandler for DropPapersHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: DropPapersArgs = parse_args("drop_papers", args)?;
+
+        let mut working_set = session.working_set();
+        let mut dropped = working_set.check_handles(&args.handles)?;
+        for &handle in &dropped {
+            working_set.remove(handle);
+        }
+        if args.unpinned {
+            dropped.extend(working_set.remove_unpinned());
+        }
+
+        let update = working_set_update(&working_set, &dropped);
+        let text = if update.handles.is_empty() {
+            format!(
+                "Nothing to drop. {} papers remain in the working set.",
+                update.remaining
+            )
+        } else {
+            format!(
+                "Dropped {}. {} papers remain in the working set.",
+                update.handles.join(", "),
+                update.remaining
+            )
+        };
+        ToolOutput::new(update, text)
+    }
+}
+
+/// Handler for exporting the working set
+#[derive(Debug)]
+pub struct ExportWorkingSetHandler;
+
+#[async_trait::async_trait]
+impl ToolHandler for ExportWorkingSetHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: ExportWorkingSetArgs = parse_args("export_working_set", args)?;
+
+        let papers: Vec<crate::models::Paper> = {
+            let working_set = session.working_set();
+            let entries = match args.handles {
+                Some(ref handles) => working_set
+                    .check_handles(handles)?
+                    .into_iter()
+                    .filter_map(|h| working_set.get(h))
+                    .collect(),
+                None => working_set.entries().iter().collect::<Vec<_>>(),
+            };
+            entries
+                .into_iter()
+                .filter(|e| e.pinned || !args.pinned_only)
+                .map(|e| e.paper.clone())
+                .collect()
+        };
+
+        if papers.is_empty() {
+            return Err(ToolError::Failed(
+                "Nothing to export: no papers match in the working set".to_string(),
+            ));
+        }
+
+        let mut result = ExportResult {
+            format: args.format,
+            count: papers.len(),
+            path: None,
+            kept: Vec::new(),
+            content: None,
+        };
+
+        let text = match args.output_path {
+            Some(path) => {
+                let summary = args
+                    .format
+                    .write_to(&papers, Path::new(&path))
+                    .map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+                let mut text = format!("Exported {} papers to `{}`", papers.len(), path);
+                if !summary.kept.is_empty() {
+                    text.push_str(&format!(
+                        "; kept {} edited note{}",
+                        summary.kept.len(),
+                        if summary.kept.len() == 1 { "" } else { "s" }
+                    ));
+                }
+                result.kept = summary
+                    .kept
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect();
+                result.path = Some(path);
+                text
+            }
+            None => {
+                let content = args.format.write(&papers);
+                result.content = Some(content.clone());
+                content
+            }
+        };
+
+        ToolOutput::new(result, text)
+    }
+}
+
+/// Handler for snowballing a citation graph from seed papers
+#[derive(Debug)]
+pub struct SnowballHandler {
+    pub sources: Arc<Vec<Arc<dyn crate::sources::Source>>>,
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for SnowballHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: SnowballArgs = parse_args("snowball", args)?;
+
+        let (year_from, year_to) = match args.year {
+            Some(ref year) => parse_year_range(year)?,
+            None => (None, None),
+        };
+        let mut options = SnowballOptions::new()
+            .direction(args.direction)
+            .depth(args.depth)
+            .max_per_paper(args.max_per_paper)
+            .max_nodes(args.max_nodes)
+            .years(year_from, year_to);
+        if let Some(min) = args.min_citations {
+            options = options.min_citations(min);
+        }
+        if let Some(sources) = args.sources {
+            for source_id in &sources {
+                check_source(&self.sources, source_id)?;
+            }
+            options = options.sources(sources);
+        }
+
+        let snowball = Snowball::new(self.sources.to_vec(), options);
+        if snowball.fetcher().source_ids().is_empty() {
+            return Err(ToolError::Failed(
+                "None of the selected sources support citation lookups".to_string(),
+            ));
+        }
+
+        let mut seeds = Vec::with_capacity(args.seeds.len());
+        for seed in &args.seeds {
+            let paper = match session.resolve(seed)? {
+                Some(paper) => paper,
+                None => resolve_seed(&self.sources, seed).await,
+            };
+            seeds.push(paper);
+        }
+
+        let graph = snowball.run(seeds).await;
+
+        let path = match args.output_path {
+            Some(path) => {
+                std::fs::write(&path, graph.export(args.format))
+                    .map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+                Some(path)
+            }
+            None => None,
+        };
+
+        // Seeds first, then by number of links
+        let degrees = graph.degrees();
+        let mut ranked: Vec<usize> = (0..graph.node_count()).collect();
+        ranked.sort_by_key(|&i| (!graph.nodes()[i].seed, std::cmp::Reverse(degrees[i])));
+        ranked.truncate(MAX_LISTED_GRAPH_PAPERS);
+
+        let papers: Vec<_> = ranked
+            .iter()
+            .map(|&i| graph.nodes()[i].paper.clone())
+            .collect();
+        let list = session.paper_list(&papers, args.verbosity);
+
+        let handles: std::collections::HashMap<&str, &str> = ranked
+            .iter()
+            .zip(&list.papers)
+            .filter_map(|(&i, view)| Some((graph.nodes()[i].id.as_str(), view.handle.as_deref()?)))
+            .collect();
+        let links = graph
+            .edges()
+            .iter()
+            .filter_map(|edge| {
+                Some(GraphLink {
+                    citing: handles.get(edge.source.as_str())?.to_string(),
+                    cited: handles.get(edge.target.as_str())?.to_string(),
+                })
+            })
+            .collect();
+
+        let result = SnowballResult {
+            nodes: graph.node_count(),
+            edges: graph.edge_count(),
+            truncated: graph.truncated,
+            format: args.format,
+            path,
+            papers: list,
+            links,
+        };
+        let text = result.to_markdown();
+        ToolOutput::new(result, text)
+    }
+}
+
+/// Handler for co-citation and bibliographic coupling analysis
+#[derive(Debug)]
+pub struct CouplingAnalysisHandler {
+    pub sources: Arc<Vec<Arc<dyn crate::sources::Source>>>,
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for CouplingAnalysisHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: CouplingArgs = parse_args("coupling_analysis", args)?;
+
+        let mut options = CouplingOptions::new()
+            .max_per_paper(args.max_per_paper)
+            .max_citing(args.max_citing)
+            .top(args.top);
+        if let Some(sources) = args.sources {
+            for source_id in &sources {
+                check_source(&self.sources, source_id)?;
+            }
+            options = options.sources(sources);
+        }
+
+        let coupling = Coupling::new(self.sources.to_vec(), options);
+        if coupling.fetcher().source_ids().is_empty() {
+            return Err(ToolError::Failed(
+                "None of the selected sources support citation lookups".to_string(),
+            ));
+        }
+
+        let papers = match args.papers {
+            Some(ids) => {
+                let mut papers = Vec::with_capacity(ids.len());
+                for id in &ids {
+                    let paper = match session.resolve(id)? {
+                        Some(paper) => paper,
+                        None => resolve_seed(&self.sources, id).await,
+                    };
+                    papers.push(paper);
+                }
+                papers
+            }
+            None => session
+                .working_set()
+                .entries()
+                .iter()
+                .filter(|e| e.pinned || !args.pinned_only)
+                .map(|e| e.paper.clone())
+                .collect(),
         };
+        if papers.is_empty() {
+            return Err(ToolError::Failed(
+                "Nothing to analyse: give papers or add papers to the working set first"
+                    .to_string(),
+            ));
+        }
 
-        let deduped = crate::utils::deduplicate_papers(papers, strategy);
+        let report = coupling.run(papers).await;
 
-        serde_json::to_value(deduped).map_err(|e| e.to_string())
+        let list = session.paper_list(&report.papers, args.verbosity);
+        let handle = |i: usize| list.papers[i].handle.clone().unwrap_or_default();
+        let pairs = report
+            .pairs
+            .iter()
+            .map(|pair| PairView {
+                a: handle(pair.a),
+                b: handle(pair.b),
+                co_citations: pair.co_citations,
+                coupling: pair.coupling,
+            })
+            .collect();
+        let missing = report
+            .missing
+            .iter()
+            .map(|m| MissingPaperView {
+                paper: session.paper_view(&m.paper, args.verbosity),
+                co_citations: m.co_citations,
+                cited_by_set: m.cited_by_set,
+            })
+            .collect();
+
+        let result = CouplingResult {
+            papers: list,
+            pairs,
+            missing,
+        };
+        let text = result.to_markdown();
+        ToolOutput::new(result, text)
+    }
+}
+
+/// Handler for author profiles merged across sources
+#[derive(Debug)]
+pub struct GetAuthorHandler {
+    pub sources: Arc<Vec<Arc<dyn crate::sources::Source>>>,
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for GetAuthorHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: GetAuthorArgs = parse_args("get_author", args)?;
+
+        let mut options = AuthorOptions::new().max_papers(args.max_papers);
+        if let Some(sources) = args.sources {
+            for source_id in &sources {
+                check_source(&self.sources, source_id)?;
+            }
+            options = options.sources(sources);
+        }
+
+        let resolver = AuthorResolver::new(self.sources.to_vec(), options);
+        let lookup = resolver
+            .resolve(&args.query)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let result = match lookup {
+            AuthorLookup::Found { profile } => AuthorResult {
+                status: AuthorStatus::Found,
+                papers: Some(session.paper_list(&profile.papers, args.verbosity)),
+                profile: Some(AuthorView {
+                    name: profile.name,
+                    orcid: profile.orcid,
+                    affiliations: profile.affiliations,
+                    paper_count: profile.paper_count,
+                    citation_count: profile.citation_count,
+                    h_index: profile.h_index,
+                    top_venues: profile.top_venues,
+                    co_authors: profile.co_authors,
+                    records: profile.records.iter().map(|r| r.key()).collect(),
+                }),
+                candidates: None,
+            },
+            AuthorLookup::Ambiguous { candidates, .. } => AuthorResult {
+                status: AuthorStatus::Ambiguous,
+                profile: None,
+                papers: None,
+                candidates: Some(candidates),
+            },
+        };
+        let text = result.to_markdown(&args.query);
+        ToolOutput::new(result, text)
+    }
+}
+
+/// Handler for re-running saved searches and follows and reporting new papers
+#[derive(Debug)]
+pub struct CheckSavedSearchesHandler {
+    pub sources: Arc<Vec<Arc<dyn crate::sources::Source>>>,
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for CheckSavedSearchesHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: CheckSavedSearchesArgs = parse_args("check_saved_searches", args)?;
+
+        let store = WatchStore::new();
+        let searches = store.list().map_err(|e| e.to_string())?;
+        let follows = store.list_follows().map_err(|e| e.to_string())?;
+        if searches.is_empty() && follows.is_empty() {
+            return Err(ToolError::Failed(
+                "No saved searches or follows. Add one with `research-master watch add <name> <query>`"
+                    .to_string()),
+            );
+        }
+
+        let watcher = Watcher::new(self.sources.to_vec(), store);
+        let report = watcher
+            .run(&args.names.unwrap_or_default(), args.update)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let result = SavedSearchesResult {
+            run_at: report.run_at.to_rfc3339(),
+            new_count: report.new_count(),
+            searches: report
+                .searches
+                .iter()
+                .map(|search| SavedSearchView {
+                    name: search.name.clone(),
+                    kind: search.kind,
+                    query: search.query.clone(),
+                    first_run: search.first_run,
+                    total: search.total,
+                    new_papers: session.paper_list(&search.new_papers, args.verbosity),
+                    errors: search.errors.clone(),
+                })
+                .collect(),
+        };
+        let text = result.to_markdown();
+        ToolOutput::new(result, text)
+    }
+}
+
+/// Summarize a change to the working set
+fn working_set_update(working_set: &WorkingSet, handles: &[u32]) -> WorkingSetUpdate {
+    WorkingSetUpdate {
+        handles: handles.iter().map(|&h| format_handle(h)).collect(),
+        remaining: working_set.len(),
+        pinned: working_set.entries().iter().filter(|e| e.pinned).count(),
     }
 }
 
 // Helper trait for source auto-detection
+/// Handler for formatting citations in a built-in or CSL style
+#[derive(Debug)]
+pub struct FormatCitationHandler {
+    pub sources: Arc<Vec<Arc<dyn crate::sources::Source>>>,
+}
+
+#[async_trait::async_trait]
+impl ToolHandler for FormatCitationHandler {
+    async fn execute(&self, args: Value, session: &Session) -> Result<ToolOutput, ToolError> {
+        let args: FormatCitationArgs = parse_args("format_citation", args)?;
+
+        // Load the style first so a bad file fails before any lookups
+        let processor = match args.style_file {
+            Some(ref path) => {
+                let mut processor =
+                    Processor::new(Style::from_file(path).map_err(|e| e.to_string())?)
+                        .format(args.format);
+                if let Some(ref path) = args.locale_file {
+                    processor =
+                        processor.locale(Locale::from_file(path).map_err(|e| e.to_string())?);
+                }
+                Some(processor)
+            }
+            None => None,
+        };
+
+        let papers = match args.papers {
+            Some(ids) => {
+                let mut papers = Vec::with_capacity(ids.len());
+                for id in &ids {
+                    let paper = match session.resolve(id)? {
+                        Some(paper) => paper,
+                        None => resolve_seed(&self.sources, id).await,
+                    };
+                    if paper.title.is_empty() {
+                        return Err(ToolError::Failed(format!(
+                            "No metadata found for '{}'. Look it up first (e.g. with get_paper) and cite its handle.",
+                            id.trim()
+                        )));
+                    }
+                    papers.push(paper);
+                }
+                papers
+            }
+            None => session
+                .working_set()
+                .entries()
+                .iter()
+                .filter(|e| e.pinned || !args.pinned_only)
+                .map(|e| e.paper.clone())
+                .collect(),
+        };
+        let papers: Vec<_> = match args.version {
+            Some(kind) => papers
+                .into_iter()
+                .map(|paper| find_version(&paper, kind).cloned().unwrap_or(paper))
+                .collect(),
+            None => papers,
+        };
+        if papers.is_empty() {
+            return Err(ToolError::Failed(
+                "Nothing to cite: give papers or add papers to the working set first".to_string(),
+            ));
+        }
+
+        let items = Item::from_papers(&papers);
+        let handles: Vec<String> = {
+            let mut working_set = session.working_set();
+            papers
+                .iter()
+                .map(|paper| format_handle(working_set.add(paper)))
+                .collect()
+        };
+
+        let result = match processor {
+            Some(processor) => {
+                let rendered = processor.render(&items);
+                CitationResult {
+                    style: processor.style().title.clone(),
+                    citations: handles
+                        .into_iter()
+                        .zip(items)
+                        .zip(rendered.citations)
+                        .map(|((handle, item), citation)| CitationView {
+                            handle,
+                            id: item.id,
+                            citation,
+                        })
+                        .collect(),
+                    bibliography: rendered.bibliography,
+                }
+            }
+            None => CitationResult {
+                style: match args.style {
+                    CitationStyle::Apa => "APA",
+                    CitationStyle::Mla => "MLA",
+                    CitationStyle::Chicago => "Chicago",
+                    CitationStyle::Bibtex => "BibTeX",
+                }
+                .to_string(),
+                citations: handles
+                    .into_iter()
+                    .zip(items)
+                    .zip(&papers)
+                    .map(|((handle, item), paper)| CitationView {
+                        handle,
+                        id: item.id,
+                        citation: crate::utils::format_citation(paper, args.style.into()),
+                    })
+                    .collect(),
+                bibliography: Vec::new(),
+            },
+        };
+
+        let text = result.to_markdown();
+        ToolOutput::new(result, text)
+    }
+}
+
 impl GetPaperHandler {
     fn find_source(
         &self,
         paper_id: &str,
         source_override: Option<&str>,
-    ) -> Result<Arc<dyn crate::sources::Source>, String> {
+    ) -> Result<Arc<dyn crate::sources::Source>, ToolError> {
         // If source is explicitly specified, use it
         if let Some(source_id) = source_override {
+            check_source(&self.sources, source_id)?;
             return self
                 .sources
                 .iter()
                 .find(|s| s.id() == source_id)
                 .cloned()
-                .ok_or_else(|| format!("Source '{}' not found", source_id));
+                .ok_or_else(|| ToolError::Failed(format!("Source '{}' not found", source_id)));
         }
 
         // Use shared auto-detection logic
-        auto_detect_source(&self.sources, paper_id)
+        Ok(auto_detect_source(&self.sources, paper_id)?)
     }
 }
 
@@ -497,19 +1147,20 @@ impl DownloadPaperHandler {
         &self,
         paper_id: &str,
         source_override: Option<&str>,
-    ) -> Result<Arc<dyn crate::sources::Source>, String> {
+    ) -> Result<Arc<dyn crate::sources::Source>, ToolError> {
         // If source is explicitly specified, use it
         if let Some(source_id) = source_override {
+            check_source(&self.sources, source_id)?;
             return self
                 .sources
                 .iter()
                 .find(|s| s.id() == source_id)
                 .cloned()
-                .ok_or_else(|| format!("Source '{}' not found", source_id));
+                .ok_or_else(|| ToolError::Failed(format!("Source '{}' not found", source_id)));
         }
 
         // Use shared auto-detection logic
-        auto_detect_source(&self.sources, paper_id)
+        Ok(auto_detect_source(&self.sources, paper_id)?)
     }
 }
 
@@ -518,19 +1169,20 @@ impl ReadPaperHandler {
         &self,
         paper_id: &str,
         source_override: Option<&str>,
-    ) -> Result<Arc<dyn crate::sources::Source>, String> {
+    ) -> Result<Arc<dyn crate::sources::Source>, ToolError> {
         // If source is explicitly specified, use it
         if let Some(source_id) = source_override {
+            check_source(&self.sources, source_id)?;
             return self
                 .sources
                 .iter()
                 .find(|s| s.id() == source_id)
                 .cloned()
-                .ok_or_else(|| format!("Source '{}' not found", source_id));
+                .ok_or_else(|| ToolError::Failed(format!("Source '{}' not found", source_id)));
         }
 
         // Use shared auto-detection logic
-        auto_detect_source(&self.sources, paper_id)
+        Ok(auto_detect_source(&self.sources, paper_id)?)
     }
 }
 
@@ -727,6 +1379,44 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_check_source_suggests_typo() {
+        let sources = make_test_sources();
+        assert!(check_source(&sources, "arxiv").is_ok());
+
+        let err = check_source(&sources, "arxv").unwrap_err();
+        assert!(err.to_string().contains("Did you mean 'arxiv'?"));
+
+        let err = check_source(&sources, "google").unwrap_err();
+        assert!(!err.to_string().contains("Did you mean"));
+        assert!(err.to_string().contains("semantic"));
+    }
+
+    #[tokio::test]
+    async fn test_handler_rejects_invalid_arguments() {
+        let handler = SearchPapersHandler {
+            sources: Arc::new(make_test_sources()),
+        };
+
+        let err = handler
+            .execute(
+                serde_json::json!({"query": "x", "max_results": "20"}),
+                &Session::new(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("max_results"));
+
+        let err = handler
+            .execute(
+                serde_json::json!({"query": "x", "source": "semantc"}),
+                &Session::new(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Did you mean 'semantic'?"));
+    }
+
     #[test]
     fn test_paper_id_upper_start_basic() {
         assert!(paper_id_upper_start("PMC12345", "PMC"));
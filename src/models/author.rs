@@ -0,0 +1,241 @@
+//! Author models for profile lookups across sources.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{Paper, SourceType};
+
+/// An author record from a single source
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Author {
+    /// Source-specific author identifier (OpenAlex ID, S2 author ID, DBLP pid, ORCID iD)
+    pub author_id: String,
+
+    /// Display name
+    pub name: String,
+
+    /// Source the record came from
+    #[schemars(with = "String")]
+    pub source: SourceType,
+
+    /// ORCID iD (bare form, e.g. `0000-0002-1825-0097`)
+    pub orcid: Option<String>,
+
+    /// Profile page URL
+    pub url: Option<String>,
+
+    /// Current and past affiliations
+    #[serde(default)]
+    pub affiliations: Vec<String>,
+
+    /// Number of works the source attributes to the author
+    pub paper_count: Option<u32>,
+
+    /// Total citations the source attributes to the author
+    pub citation_count: Option<u32>,
+
+    /// h-index reported by the source
+    pub h_index: Option<u32>,
+
+    /// Publications, empty for search results
+    #[serde(default)]
+    pub papers: Vec<Paper>,
+}
+
+impl Author {
+    /// Create a new author record with required fields
+    pub fn new(author_id: impl Into<String>, name: impl Into<String>, source: SourceType) -> Self {
+        Self {
+            author_id: author_id.into(),
+            name: name.into(),
+            source,
+            orcid: None,
+            url: None,
+            affiliations: Vec::new(),
+            paper_count: None,
+            citation_count: None,
+            h_index: None,
+            papers: Vec::new(),
+        }
+    }
+
+    /// Identifier qualified with the source, e.g. `openalex:A5023888391`
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.source.id(), self.author_id)
+    }
+
+    /// Add an affiliation, ignoring blanks and duplicates
+    pub fn add_affiliation(&mut self, affiliation: &str) {
+        let affiliation = affiliation.trim();
+        if !affiliation.is_empty()
+            && !self
+                .affiliations
+                .iter()
+                .any(|a| a.eq_ignore_ascii_case(affiliation))
+        {
+            self.affiliations.push(affiliation.to_string());
+        }
+    }
+}
+
+/// A name with the number of times it occurs in a publication list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct NameCount {
+    /// Venue or co-author name
+    pub name: String,
+    /// Number of publications
+    pub count: usize,
+}
+
+/// An author profile merged from one or more source records
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuthorProfile {
+    /// Display name
+    pub name: String,
+
+    /// ORCID iD, when any source knows it
+    pub orcid: Option<String>,
+
+    /// Affiliations across all sources
+    pub affiliations: Vec<String>,
+
+    /// Largest paper count reported by any source
+    pub paper_count: Option<u32>,
+
+    /// Largest citation count reported by any source
+    pub citation_count: Option<u32>,
+
+    /// Largest h-index reported by any source, or computed from the publication list
+    pub h_index: Option<u32>,
+
+    /// Most frequent venues in the publication list
+    pub top_venues: Vec<NameCount>,
+
+    /// Most frequent co-authors in the publication list
+    pub co_authors: Vec<NameCount>,
+
+    /// Deduplicated publications, most cited first
+    pub papers: Vec<Paper>,
+
+    /// Source records the profile was built from (without their papers)
+    pub records: Vec<Author>,
+}
+
+/// One person a name may refer to, offered when a lookup is ambiguous
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuthorCandidate {
+    /// Identifier to pass back to select this candidate (`orcid:...` or `<source>:<id>`)
+    pub id: String,
+
+    /// Display name
+    pub name: String,
+
+    /// ORCID iD, if known
+    pub orcid: Option<String>,
+
+    /// Affiliations reported by the matching sources
+    pub affiliations: Vec<String>,
+
+    /// Largest paper count reported
+    pub paper_count: Option<u32>,
+
+    /// Largest citation count reported
+    pub citation_count: Option<u32>,
+
+    /// Qualified source records that describe this candidate
+    pub records: Vec<String>,
+}
+
+/// Result of resolving an author name or identifier
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuthorLookup {
+    /// A single person was identified
+    Found {
+        /// The merged profile
+        profile: Box<AuthorProfile>,
+    },
+    /// Several people match; the caller should choose one by `id`
+    Ambiguous {
+        /// The name that was looked up
+        query: String,
+        /// Possible matches, most prolific first
+        candidates: Vec<AuthorCandidate>,
+    },
+}
+
+/// Extract a bare ORCID iD from an iD, `orcid:` prefix or orcid.org URL
+pub fn normalize_orcid(value: &str) -> Option<String> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix("orcid:")
+        .or_else(|| value.strip_prefix("ORCID:"))
+        .unwrap_or(value);
+    let bare = value
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("orcid.org/")
+        .trim_end_matches('/');
+
+    let groups: Vec<&str> = bare.split('-').collect();
+    let valid = groups.len() == 4
+        && groups.iter().all(|g| g.len() == 4)
+        && groups
+            .iter()
+            .flat_map(|g| g.chars())
+            .enumerate()
+            .all(|(i, c)| c.is_ascii_digit() || (i == 15 && matches!(c, 'X' | 'x')));
+    valid.then(|| bare.to_ascii_uppercase())
+}
+
+/// Normalize an author name for comparison ("Smith, Jane" and "jane  smith" match)
+pub fn normalize_author_name(name: &str) -> String {
+    let name = match name.split_once(',') {
+        Some((last, first)) => format!("{} {}", first, last),
+        None => name.to_string(),
+    };
+    name.split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+        .filter(|part| !part.is_empty())
+        // DBLP disambiguates homonyms with a numeric suffix ("Wei Wang 0001")
+        .filter(|part| !part.chars().all(|c| c.is_ascii_digit()))
+        .map(|part| part.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_orcid() {
+        let expected = Some("0000-0002-1825-009X".to_string());
+        assert_eq!(normalize_orcid("0000-0002-1825-009x"), expected);
+        assert_eq!(normalize_orcid("orcid:0000-0002-1825-009X"), expected);
+        assert_eq!(
+            normalize_orcid("https://orcid.org/0000-0002-1825-009X/"),
+            expected
+        );
+        assert_eq!(normalize_orcid("0000-0002-1825"), None);
+        assert_eq!(normalize_orcid("000X-0002-1825-0097"), None);
+        assert_eq!(normalize_orcid("Jane Smith"), None);
+    }
+
+    #[test]
+    fn test_normalize_author_name() {
+        assert_eq!(normalize_author_name("Smith, Jane"), "jane smith");
+        assert_eq!(normalize_author_name("Jane  Smith"), "jane smith");
+        assert_eq!(normalize_author_name("Wei Wang 0001"), "wei wang");
+        assert_eq!(normalize_author_name("J.-P. Sartre"), "j p sartre");
+    }
+
+    #[test]
+    fn test_author_key_and_affiliations() {
+        let mut author = Author::new("A1", "Jane Smith", SourceType::OpenAlex);
+        author.add_affiliation("MIT");
+        author.add_affiliation(" mit ");
+        author.add_affiliation("");
+        assert_eq!(author.key(), "openalex:A1");
+        assert_eq!(author.affiliations, vec!["MIT"]);
+    }
+}
@@ -10,6 +10,10 @@
 //! - [`ReadRequest`]/[`ReadResult`]: PDF text extraction operations
 //! - [`CitationRequest`]: Citation and reference lookup
 //! - [`SourceType`]: Enum of all supported research sources
+//! - [`PaperStatus`]: Retractions, expressions of concern and corrections
+//! - [`OpenAccess`]: Open-access status, licence and version
+//! - [`VersionKind`]: Preprint or published version of a work
+//! - [`Author`]/[`AuthorProfile`]: Author records and profiles merged across sources
 //!
 //! # Examples
 //!
@@ -34,10 +38,18 @@
 //!     .year("2020-");
 //! ```
 
+mod author;
 mod paper;
 mod search;
 
-pub use paper::{Paper, PaperBuilder, SourceType};
+pub use author::{
+    normalize_author_name, normalize_orcid, Author, AuthorCandidate, AuthorLookup, AuthorProfile,
+    NameCount,
+};
+pub use paper::{
+    OaStatus, OaVersion, OpenAccess, Paper, PaperBuilder, PaperStatus, SourceType, StatusKind,
+    VersionKind, CACHED_AT_KEY,
+};
 pub use search::{
     BatchDownloadRequest, BatchDownloadResult, CitationRequest, DownloadRequest, DownloadResult,
     ReadRequest, ReadResult, SearchQuery, SearchResponse, SortBy, SortOrder,
@@ -1,8 +1,13 @@
 //! Paper model representing a research paper from any source.
 
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Extra field recording when a paper served from the cache was fetched
+pub const CACHED_AT_KEY: &str = "cached_at";
+
 /// The source/repository where the paper was found
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -37,6 +42,7 @@ pub enum SourceType {
     Osf,
     Base,
     Springer,
+    Orcid,
     #[serde(untagged)]
     Other(String),
 }
@@ -75,6 +81,7 @@ impl SourceType {
             SourceType::Osf => "OSF Preprints",
             SourceType::Base => "BASE",
             SourceType::Springer => "Springer",
+            SourceType::Orcid => "ORCID",
             SourceType::Other(s) => s,
         }
     }
@@ -112,6 +119,7 @@ impl SourceType {
             SourceType::Osf => "osf",
             SourceType::Base => "base",
             SourceType::Springer => "springer",
+            SourceType::Orcid => "orcid",
             SourceType::Other(s) => s,
         }
     }
@@ -123,11 +131,327 @@ impl std::fmt::Display for SourceType {
     }
 }
 
+/// Editorial notice attached to a paper, in order of seriousness
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusKind {
+    /// A correction, erratum or corrigendum was published
+    Corrected,
+    /// The publisher has raised concerns about the paper
+    ExpressionOfConcern,
+    /// The paper was retracted or withdrawn
+    Retracted,
+}
+
+impl StatusKind {
+    /// The kind of a notice named by Crossref (`retraction`,
+    /// `expression_of_concern`, `erratum`, ...), PubMed or Retraction Watch
+    /// (`Expression of concern`); `None` for notices that change nothing,
+    /// such as addenda or reinstatements
+    pub fn from_notice(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace(['-', ' '], "_");
+        match name.as_str() {
+            "retraction" | "partial_retraction" | "withdrawal" | "removal" | "retracted" => {
+                Some(StatusKind::Retracted)
+            }
+            "expression_of_concern" => Some(StatusKind::ExpressionOfConcern),
+            "correction" | "erratum" | "corrigendum" | "corrected" => Some(StatusKind::Corrected),
+            _ => None,
+        }
+    }
+
+    /// Upper-case label used in warnings, e.g. `RETRACTED`
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusKind::Corrected => "CORRECTED",
+            StatusKind::ExpressionOfConcern => "EXPRESSION OF CONCERN",
+            StatusKind::Retracted => "RETRACTED",
+        }
+    }
+}
+
+impl std::fmt::Display for StatusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StatusKind::Corrected => "corrected",
+            StatusKind::ExpressionOfConcern => "expression of concern",
+            StatusKind::Retracted => "retracted",
+        })
+    }
+}
+
+/// A retraction, expression of concern or correction, and where it was found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PaperStatus {
+    /// What kind of notice it is
+    pub kind: StatusKind,
+
+    /// Where the notice was found (`crossref`, `pubmed`, `openalex`,
+    /// `retraction_watch`)
+    pub source: String,
+
+    /// DOI or URL of the notice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+
+    /// Date of the notice (ISO format)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+impl PaperStatus {
+    /// Create a status without notice details
+    pub fn new(kind: StatusKind, source: impl Into<String>) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+            notice: None,
+            date: None,
+        }
+    }
+
+    /// Set the DOI or URL of the notice
+    pub fn notice(mut self, notice: impl Into<String>) -> Self {
+        self.notice = Some(notice.into()).filter(|n: &String| !n.is_empty());
+        self
+    }
+
+    /// Set the date of the notice
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into()).filter(|d: &String| !d.is_empty());
+        self
+    }
+
+    /// Warning shown next to the paper, e.g.
+    /// `RETRACTED (crossref, 2020-06-04, notice 10.1016/S0140-6736(20)31324-6)`
+    pub fn warning(&self) -> String {
+        let mut details = vec![self.source.clone()];
+        details.extend(self.date.clone());
+        details.extend(self.notice.as_ref().map(|n| format!("notice {}", n)));
+        format!("{} ({})", self.kind.label(), details.join(", "))
+    }
+}
+
+impl std::fmt::Display for PaperStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} according to {}", self.kind, self.source)?;
+        if let Some(ref date) = self.date {
+            write!(f, " on {}", date)?;
+        }
+        if let Some(ref notice) = self.notice {
+            write!(f, " (notice {})", notice)?;
+        }
+        Ok(())
+    }
+}
+
+/// Open-access status in the Unpaywall/OpenAlex sense
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OaStatus {
+    /// Published open access in an open-access journal
+    Gold,
+    /// Free to read in a repository
+    Green,
+    /// Published open access under an open licence in a subscription journal
+    Hybrid,
+    /// Free to read on the publisher site without an open licence
+    Bronze,
+    /// Not freely readable
+    Closed,
+}
+
+impl OaStatus {
+    /// Parse the `oa_status` of Unpaywall or OpenAlex; OpenAlex's `diamond`
+    /// (no author charges) counts as gold
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "gold" | "diamond" => Some(OaStatus::Gold),
+            "green" => Some(OaStatus::Green),
+            "hybrid" => Some(OaStatus::Hybrid),
+            "bronze" => Some(OaStatus::Bronze),
+            "closed" => Some(OaStatus::Closed),
+            _ => None,
+        }
+    }
+
+    /// Whether the paper is free to read
+    pub fn is_open(&self) -> bool {
+        *self != OaStatus::Closed
+    }
+}
+
+impl std::fmt::Display for OaStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OaStatus::Gold => "gold",
+            OaStatus::Green => "green",
+            OaStatus::Hybrid => "hybrid",
+            OaStatus::Bronze => "bronze",
+            OaStatus::Closed => "closed",
+        })
+    }
+}
+
+/// Version of a paper at an open-access location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OaVersion {
+    /// The preprint, before peer review
+    Submitted,
+    /// The author's manuscript after peer review
+    Accepted,
+    /// The publisher's version of record
+    Published,
+}
+
+impl OaVersion {
+    /// Parse `submittedVersion`, `acceptedVersion` or `publishedVersion`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().trim_end_matches("version") {
+            "submitted" => Some(OaVersion::Submitted),
+            "accepted" => Some(OaVersion::Accepted),
+            "published" => Some(OaVersion::Published),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for OaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OaVersion::Submitted => "submitted",
+            OaVersion::Accepted => "accepted",
+            OaVersion::Published => "published",
+        })
+    }
+}
+
+/// Whether and how a paper is freely readable, and where this was found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct OpenAccess {
+    /// Open-access status
+    pub status: OaStatus,
+
+    /// Best free-to-read URL (PDF or landing page)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Licence of that copy, e.g. `cc-by` or `cc-by-nc-nd`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    /// Version of that copy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<OaVersion>,
+
+    /// Where this was found (`unpaywall` or `openalex`)
+    pub source: String,
+}
+
+impl OpenAccess {
+    /// Create an annotation without location details
+    pub fn new(status: OaStatus, source: impl Into<String>) -> Self {
+        Self {
+            status,
+            url: None,
+            license: None,
+            version: None,
+            source: source.into(),
+        }
+    }
+
+    /// Set the best free-to-read URL
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into()).filter(|u: &String| !u.is_empty());
+        self
+    }
+
+    /// Set the licence; `CC BY-NC 4.0` is stored as `cc-by-nc-4.0`
+    pub fn license(mut self, license: impl AsRef<str>) -> Self {
+        self.license = Some(normalize_license(license.as_ref())).filter(|l| !l.is_empty());
+        self
+    }
+
+    /// Set the version
+    pub fn version(mut self, version: Option<OaVersion>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Whether the licence is `license` or one of its versions: `cc-by`
+    /// matches `cc-by` and `cc-by-4.0` but not `cc-by-nc`
+    pub fn has_license(&self, license: &str) -> bool {
+        let wanted = normalize_license(license);
+        self.license.as_deref().is_some_and(|l| {
+            l == wanted
+                || l.strip_prefix(wanted.as_str())
+                    .and_then(|rest| rest.strip_prefix('-'))
+                    .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        })
+    }
+}
+
+impl std::fmt::Display for OpenAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.status)?;
+        if let Some(ref license) = self.license {
+            write!(f, ", {}", license)?;
+        }
+        if let Some(version) = self.version {
+            write!(f, ", {} version", version)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lowercase licence name with words joined by `-`
+fn normalize_license(license: &str) -> String {
+    license
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Whether a paper is a preprint or the published version of a work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionKind {
+    /// Posted on a preprint server, before or without peer review
+    Preprint,
+    /// Published in a journal or proceedings
+    Published,
+}
+
+impl VersionKind {
+    /// Parse `preprint` or `published`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "preprint" => Some(VersionKind::Preprint),
+            "published" => Some(VersionKind::Published),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for VersionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            VersionKind::Preprint => "preprint",
+            VersionKind::Published => "published",
+        })
+    }
+}
+
 /// A research paper from any academic source
 ///
 /// This struct provides a standardized format for papers across all sources,
 /// making it easy to work with papers from multiple repositories.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Paper {
     /// Unique identifier (source-specific: DOI, PMID, arXiv ID, etc.)
     pub paper_id: String,
@@ -157,6 +481,7 @@ pub struct Paper {
     pub url: String,
 
     /// Source where the paper was found
+    #[schemars(with = "String")]
     pub source: SourceType,
 
     /// Categories/tags (semicolon-separated)
@@ -173,6 +498,19 @@ pub struct Paper {
 
     /// Source-specific metadata (flexible JSON)
     pub extra: Option<HashMap<String, serde_json::Value>>,
+
+    /// Retraction, expression of concern or correction, if any is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<PaperStatus>,
+
+    /// Open-access status, licence and version, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_access: Option<OpenAccess>,
+
+    /// Other versions of the same work (the preprint of a published paper, or
+    /// the published version of a preprint), when versions have been grouped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<Paper>,
 }
 
 impl Paper {
@@ -194,6 +532,9 @@ impl Paper {
             citations: None,
             references: None,
             extra: None,
+            status: None,
+            open_access: None,
+            versions: Vec::new(),
         }
     }
 
@@ -241,6 +582,62 @@ impl Paper {
     pub fn has_pdf(&self) -> bool {
         self.pdf_url.is_some()
     }
+
+    /// Publication year, taken from the start of `published_date`
+    pub fn year(&self) -> Option<i32> {
+        self.published_date
+            .as_deref()
+            .and_then(|d| d.get(..4))
+            .filter(|y| y.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|y| y.parse().ok())
+    }
+
+    /// Record a notice, keeping the more serious one when the paper already
+    /// has a status
+    pub fn annotate(&mut self, status: PaperStatus) {
+        if self.status.as_ref().is_none_or(|s| status.kind > s.kind) {
+            self.status = Some(status);
+        }
+    }
+
+    /// Whether the paper is known to be retracted
+    pub fn is_retracted(&self) -> bool {
+        self.status
+            .as_ref()
+            .is_some_and(|s| s.kind == StatusKind::Retracted)
+    }
+
+    /// Journal or conference name, if the source provided one
+    pub fn venue(&self) -> Option<&str> {
+        let extra = self.extra.as_ref()?;
+        [
+            "venue",
+            "journal",
+            "container_title",
+            "conference",
+            "booktitle",
+        ]
+        .iter()
+        .find_map(|key| extra.get(*key).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    }
+
+    /// When the paper was fetched, if it was served from the cache
+    pub fn cached_at(&self) -> Option<DateTime<Utc>> {
+        let value = self.extra.as_ref()?.get(CACHED_AT_KEY)?.as_str()?;
+        DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|at| at.with_timezone(&Utc))
+    }
+
+    /// Mark the paper as served from the cache, fetched at `at`
+    pub fn mark_cached(&mut self, at: DateTime<Utc>) {
+        self.extra.get_or_insert_with(Default::default).insert(
+            CACHED_AT_KEY.to_string(),
+            serde_json::Value::String(at.to_rfc3339()),
+        );
+    }
 }
 
 /// Builder for constructing Paper objects
@@ -331,6 +728,18 @@ impl PaperBuilder {
         self
     }
 
+    /// Set the retraction, expression of concern or correction status
+    pub fn status(mut self, status: PaperStatus) -> Self {
+        self.paper.annotate(status);
+        self
+    }
+
+    /// Set the open-access status
+    pub fn open_access(mut self, open_access: OpenAccess) -> Self {
+        self.paper.open_access = Some(open_access);
+        self
+    }
+
     /// Build the Paper
     pub fn build(self) -> Paper {
         self.paper
@@ -431,6 +840,67 @@ mod tests {
         assert_eq!(without_doi.primary_id(), "1234");
     }
 
+    #[test]
+    fn test_year() {
+        let paper = PaperBuilder::new("1", "Test", "", SourceType::Arxiv)
+            .published_date("2017-06-12")
+            .build();
+        assert_eq!(paper.year(), Some(2017));
+
+        let undated = PaperBuilder::new("1", "Test", "", SourceType::Arxiv).build();
+        assert_eq!(undated.year(), None);
+
+        let bad = PaperBuilder::new("1", "Test", "", SourceType::Arxiv)
+            .published_date("n.d.")
+            .build();
+        assert_eq!(bad.year(), None);
+    }
+
+    #[test]
+    fn test_mark_cached() {
+        let mut paper = PaperBuilder::new("1", "Test", "", SourceType::Arxiv).build();
+        assert_eq!(paper.cached_at(), None);
+
+        let at = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        paper.mark_cached(at);
+        assert_eq!(paper.cached_at(), Some(at));
+    }
+
+    #[test]
+    fn test_status_keeps_most_serious_notice() {
+        let mut paper = PaperBuilder::new("1", "Test", "", SourceType::PubMed)
+            .status(PaperStatus::new(StatusKind::Corrected, "pubmed"))
+            .build();
+        assert!(!paper.is_retracted());
+
+        paper.annotate(
+            PaperStatus::new(StatusKind::Retracted, "crossref")
+                .date("2020-06-04")
+                .notice("10.1000/notice"),
+        );
+        paper.annotate(PaperStatus::new(StatusKind::ExpressionOfConcern, "pubmed"));
+        assert!(paper.is_retracted());
+        let status = paper.status.as_ref().unwrap();
+        assert_eq!(
+            status.warning(),
+            "RETRACTED (crossref, 2020-06-04, notice 10.1000/notice)"
+        );
+        assert_eq!(
+            status.to_string(),
+            "retracted according to crossref on 2020-06-04 (notice 10.1000/notice)"
+        );
+
+        let json = serde_json::to_value(&paper).unwrap();
+        assert_eq!(json["status"]["kind"], "retracted");
+        let unannotated = PaperBuilder::new("2", "Test", "", SourceType::PubMed).build();
+        assert!(serde_json::to_value(&unannotated)
+            .unwrap()
+            .get("status")
+            .is_none());
+    }
+
     #[test]
     fn test_paper_builder_all_fields() {
         let paper = PaperBuilder::new(
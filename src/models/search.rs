@@ -1,10 +1,11 @@
 //! Search request and response models.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Sort order for search results
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SortOrder {
     Ascending,
@@ -12,7 +13,7 @@ pub enum SortOrder {
 }
 
 /// Sort field for search results
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum SortBy {
     Relevance,
@@ -214,7 +215,7 @@ impl CitationRequest {
 }
 
 /// Search response containing papers and metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SearchResponse {
     /// Papers found
     pub papers: Vec<crate::models::Paper>,
@@ -262,7 +263,7 @@ impl SearchResponse {
 }
 
 /// Result of a download operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DownloadResult {
     /// Path where the file was saved
     pub path: String,
@@ -375,7 +376,7 @@ impl BatchDownloadResult {
 }
 
 /// Result of a paper read operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ReadResult {
     /// Extracted text content
     pub text: String,
@@ -0,0 +1,420 @@
+//! Open-access status, licence and version of papers.
+//!
+//! - [`OpenAccessResolver`]: Annotates papers with their
+//!   [`open_access`](crate::models::Paper::open_access) status, looked up by
+//!   DOI in OpenAlex (in batches) and Unpaywall, with answers cached by DOI
+//! - [`OpenAccessFilter`]: Keeps papers that are free to read or under a
+//!   given licence
+//!
+//! OpenAlex is asked first; Unpaywall is asked for the DOIs OpenAlex does not
+//! know, and for open papers OpenAlex reports no licence for. arXiv papers
+//! without a DOI are looked up by their arXiv DOI (`10.48550/arXiv.<id>`).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use research_master::models::SearchQuery;
+//! use research_master::open_access::{OpenAccessFilter, OpenAccessResolver};
+//! use research_master::sources::SourceRegistry;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let registry = SourceRegistry::new();
+//! let source = registry.get("crossref").unwrap();
+//! let mut papers = source.search(&SearchQuery::new("protein folding")).await?.papers;
+//!
+//! let resolver = OpenAccessResolver::new(registry.all().cloned().collect());
+//! resolver.annotate(&mut papers).await;
+//! let papers = OpenAccessFilter::new()
+//!     .licenses(vec!["cc-by".to_string()])
+//!     .apply(papers);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures_util::{stream, StreamExt};
+
+use crate::graph::normalize_doi;
+use crate::models::{OpenAccess, Paper, SourceType};
+use crate::sources::Source;
+use crate::utils::{CacheResult, CacheService};
+
+/// Sources asked for open-access status, in order
+pub const OPEN_ACCESS_SOURCES: &[&str] = &["openalex", "unpaywall"];
+
+/// DOIs per batch lookup
+const BATCH_SIZE: usize = 50;
+
+/// Number of batches looked up concurrently
+const CONCURRENCY: usize = 4;
+
+/// Looks up the open-access status of papers by DOI
+#[derive(Debug)]
+pub struct OpenAccessResolver {
+    sources: Vec<Arc<dyn Source>>,
+    cache: Option<CacheService>,
+}
+
+impl OpenAccessResolver {
+    /// Create a resolver using the open-access sources among `sources`
+    pub fn new(sources: Vec<Arc<dyn Source>>) -> Self {
+        let sources = OPEN_ACCESS_SOURCES
+            .iter()
+            .filter_map(|id| sources.iter().find(|s| s.id() == *id).cloned())
+            .filter(|s| s.supports_doi_lookup())
+            .collect();
+        Self {
+            sources,
+            cache: None,
+        }
+    }
+
+    /// Read and store answers in the cache
+    pub fn with_cache(mut self, cache: CacheService) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// IDs of the sources papers are looked up in, in order
+    pub fn source_ids(&self) -> Vec<&str> {
+        self.sources.iter().map(|s| s.id()).collect()
+    }
+
+    /// Annotate papers that have no open-access status yet
+    ///
+    /// Papers without a DOI, or whose DOI no source knows, are left as they
+    /// are. Returns the lookups that failed, as `<source>: <error>`.
+    pub async fn annotate(&self, papers: &mut [Paper]) -> Vec<String> {
+        let mut wanted: Vec<String> = Vec::new();
+        for paper in papers.iter().filter(|p| p.open_access.is_none()) {
+            if let Some(doi) = lookup_doi(paper) {
+                if !wanted.contains(&doi) {
+                    wanted.push(doi);
+                }
+            }
+        }
+
+        let mut answers: HashMap<String, Option<OpenAccess>> = HashMap::new();
+        if let Some(ref cache) = self.cache {
+            for doi in &wanted {
+                if let CacheResult::Hit(answer) = cache.get_open_access(doi).await {
+                    answers.insert(doi.clone(), answer);
+                }
+            }
+        }
+        let pending: Vec<String> = wanted
+            .into_iter()
+            .filter(|doi| !answers.contains_key(doi))
+            .collect();
+
+        let (found, errors) = self.lookup(&pending).await;
+        for doi in pending {
+            let answer = found.get(&doi).cloned();
+            // Only remember that a DOI is unknown when every source answered
+            if let Some(ref cache) = self.cache {
+                if answer.is_some() || errors.is_empty() {
+                    cache.set_open_access(&doi, answer.as_ref()).await;
+                }
+            }
+            answers.insert(doi, answer);
+        }
+
+        for paper in papers.iter_mut().filter(|p| p.open_access.is_none()) {
+            if let Some(doi) = lookup_doi(paper) {
+                paper.open_access = answers.get(&doi).cloned().flatten();
+            }
+        }
+        errors
+    }
+
+    /// Ask each source in turn for the DOIs that are still unresolved
+    async fn lookup(&self, dois: &[String]) -> (HashMap<String, OpenAccess>, Vec<String>) {
+        let mut found: HashMap<String, OpenAccess> = HashMap::new();
+        let mut errors = Vec::new();
+
+        for source in &self.sources {
+            let unresolved: Vec<String> = dois
+                .iter()
+                .filter(|doi| {
+                    found
+                        .get(*doi)
+                        .is_none_or(|o| o.status.is_open() && o.license.is_none())
+                })
+                .cloned()
+                .collect();
+            if unresolved.is_empty() {
+                break;
+            }
+
+            let batches: Vec<Vec<String>> = unresolved
+                .chunks(BATCH_SIZE)
+                .map(<[String]>::to_vec)
+                .collect();
+            let batches: Vec<_> = stream::iter(batches)
+                .map(|batch| async move { source.get_by_dois(&batch).await })
+                .buffer_unordered(CONCURRENCY)
+                .collect()
+                .await;
+            for batch in batches {
+                match batch {
+                    Ok(records) => {
+                        for record in records {
+                            let (Some(doi), Some(open_access)) =
+                                (lookup_doi(&record), record.open_access)
+                            else {
+                                continue;
+                            };
+                            match found.get_mut(&doi) {
+                                Some(known) => fill_gaps(known, open_access),
+                                None => {
+                                    found.insert(doi, open_access);
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => errors.push(format!("{}: {}", source.id(), e)),
+                }
+            }
+        }
+        (found, errors)
+    }
+}
+
+/// Keeps papers by open-access status and licence
+///
+/// Papers whose status is unknown are dropped by an active filter.
+#[derive(Debug, Clone, Default)]
+pub struct OpenAccessFilter {
+    /// Keep only papers that are free to read
+    pub open_access_only: bool,
+
+    /// Keep only papers under one of these licences, e.g. `cc-by`
+    pub licenses: Vec<String>,
+}
+
+impl OpenAccessFilter {
+    /// Create a filter that keeps every paper
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only papers that are free to read
+    pub fn open_access_only(mut self, open_access_only: bool) -> Self {
+        self.open_access_only = open_access_only;
+        self
+    }
+
+    /// Keep only papers under one of these licences
+    pub fn licenses(mut self, licenses: Vec<String>) -> Self {
+        self.licenses = licenses;
+        self
+    }
+
+    /// Whether the filter drops anything
+    pub fn is_active(&self) -> bool {
+        self.open_access_only || !self.licenses.is_empty()
+    }
+
+    /// Whether a paper passes the filter
+    pub fn matches(&self, paper: &Paper) -> bool {
+        if !self.is_active() {
+            return true;
+        }
+        let Some(ref open_access) = paper.open_access else {
+            return false;
+        };
+        (!self.open_access_only || open_access.status.is_open())
+            && (self.licenses.is_empty()
+                || self.licenses.iter().any(|l| open_access.has_license(l)))
+    }
+
+    /// The papers that pass the filter, in order
+    pub fn apply(&self, papers: Vec<Paper>) -> Vec<Paper> {
+        papers.into_iter().filter(|p| self.matches(p)).collect()
+    }
+}
+
+/// The DOI a paper is looked up by: its own, or the arXiv DOI of an arXiv paper
+fn lookup_doi(paper: &Paper) -> Option<String> {
+    paper
+        .doi
+        .as_deref()
+        .and_then(normalize_doi)
+        .or_else(|| match paper.source {
+            SourceType::Arxiv if !paper.paper_id.trim().is_empty() => {
+                let id = paper.paper_id.trim();
+                let id = id.strip_prefix("arXiv:").unwrap_or(id);
+                // The DOI names the paper, not one of its versions
+                let id = match id.rsplit_once('v') {
+                    Some((base, version)) if version.chars().all(|c| c.is_ascii_digit()) => base,
+                    _ => id,
+                };
+                normalize_doi(&format!("10.48550/arXiv.{}", id))
+            }
+            _ => None,
+        })
+}
+
+/// Fill in the licence, version and URL a first answer was missing
+fn fill_gaps(known: &mut OpenAccess, other: OpenAccess) {
+    if known.license.is_none() {
+        known.license = other.license;
+    }
+    if known.version.is_none() {
+        known.version = other.version;
+    }
+    if known.url.is_none() {
+        known.url = other.url;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    use crate::models::{OaStatus, OaVersion, PaperBuilder};
+    use crate::sources::{SourceCapabilities, SourceError};
+
+    /// A source that knows a fixed list of papers by DOI and counts lookups
+    #[derive(Debug)]
+    struct RecordSource {
+        id: &'static str,
+        records: Vec<Paper>,
+        lookups: std::sync::Mutex<Vec<usize>>,
+    }
+
+    impl RecordSource {
+        fn new(id: &'static str, records: Vec<Paper>) -> Self {
+            Self {
+                id,
+                records,
+                lookups: Default::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Source for RecordSource {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn name(&self) -> &str {
+            self.id
+        }
+
+        fn capabilities(&self) -> SourceCapabilities {
+            SourceCapabilities::DOI_LOOKUP
+        }
+
+        async fn get_by_dois(&self, dois: &[String]) -> Result<Vec<Paper>, SourceError> {
+            self.lookups.lock().unwrap().push(dois.len());
+            Ok(self
+                .records
+                .iter()
+                .filter(|r| dois.iter().any(|d| r.doi.as_deref() == Some(d.as_str())))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn record(doi: &str, open_access: OpenAccess) -> Paper {
+        PaperBuilder::new(doi, "", "", SourceType::OpenAlex)
+            .doi(doi)
+            .open_access(open_access)
+            .build()
+    }
+
+    fn paper(doi: &str) -> Paper {
+        PaperBuilder::new(doi, "", "", SourceType::CrossRef)
+            .doi(doi)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_annotate_and_filter() {
+        let openalex = Arc::new(RecordSource::new(
+            "openalex",
+            vec![
+                record(
+                    "10.1/gold",
+                    OpenAccess::new(OaStatus::Gold, "openalex").license("CC BY 4.0"),
+                ),
+                record("10.1/green", OpenAccess::new(OaStatus::Green, "openalex")),
+                record("10.1/closed", OpenAccess::new(OaStatus::Closed, "openalex")),
+            ],
+        ));
+        let unpaywall = Arc::new(RecordSource::new(
+            "unpaywall",
+            vec![
+                record(
+                    "10.1/green",
+                    OpenAccess::new(OaStatus::Green, "unpaywall")
+                        .license("cc-by-nc")
+                        .version(Some(OaVersion::Accepted)),
+                ),
+                record(
+                    "10.48550/arxiv.1706.03762",
+                    OpenAccess::new(OaStatus::Green, "unpaywall").license("cc-by"),
+                ),
+            ],
+        ));
+        let sources: Vec<Arc<dyn Source>> = vec![unpaywall.clone(), openalex.clone()];
+        let resolver = OpenAccessResolver::new(sources);
+        assert_eq!(resolver.source_ids(), vec!["openalex", "unpaywall"]);
+
+        let mut papers = vec![
+            paper("https://doi.org/10.1/GOLD"),
+            paper("10.1/green"),
+            paper("10.1/closed"),
+            paper("10.1/unknown"),
+            PaperBuilder::new("1706.03762v5", "", "", SourceType::Arxiv).build(),
+            paper("10.1/gold"),
+        ];
+        let errors = resolver.annotate(&mut papers).await;
+        assert!(errors.is_empty());
+
+        // One batch per source; Unpaywall only gets what OpenAlex left open
+        assert_eq!(*openalex.lookups.lock().unwrap(), vec![5]);
+        assert_eq!(*unpaywall.lookups.lock().unwrap(), vec![3]);
+
+        let gold = papers[0].open_access.as_ref().unwrap();
+        assert_eq!(gold.license.as_deref(), Some("cc-by-4.0"));
+        let green = papers[1].open_access.as_ref().unwrap();
+        assert_eq!(
+            (
+                green.source.as_str(),
+                green.license.as_deref(),
+                green.version
+            ),
+            ("openalex", Some("cc-by-nc"), Some(OaVersion::Accepted))
+        );
+        assert!(papers[3].open_access.is_none());
+        assert!(papers[4].open_access.is_some());
+
+        let open = OpenAccessFilter::new().open_access_only(true);
+        let kept: Vec<String> = open
+            .apply(papers.clone())
+            .into_iter()
+            .map(|p| p.paper_id)
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                "https://doi.org/10.1/GOLD",
+                "10.1/green",
+                "1706.03762v5",
+                "10.1/gold"
+            ]
+        );
+
+        let cc_by = OpenAccessFilter::new().licenses(vec!["cc-by".to_string()]);
+        assert_eq!(cc_by.apply(papers.clone()).len(), 3);
+        assert!(!OpenAccessFilter::new().is_active());
+        assert_eq!(OpenAccessFilter::new().apply(papers).len(), 6);
+    }
+}
@@ -0,0 +1,327 @@
+//! Retraction, expression of concern and correction checks.
+//!
+//! - [`RetractionChecker`]: Looks papers up in Crossref (`updated-by`
+//!   relations), PubMed (the "Retracted Publication" type and linked notices)
+//!   and OpenAlex, and in a local copy of the Retraction Watch dataset, and
+//!   records what it finds as the paper's [`status`](crate::models::Paper::status)
+//! - [`RetractionWatch`]: The Retraction Watch dataset, read from its CSV file
+//! - [`RetractionReport`]: The status of every checked paper
+//!
+//! Search results already carry the status the source reported; the checker
+//! asks every source, for papers from files or from sources that know nothing
+//! about retractions.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use research_master::formats::ImportFormat;
+//! use research_master::retraction::RetractionChecker;
+//! use research_master::sources::SourceRegistry;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut papers = ImportFormat::Bibtex.read(&std::fs::read_to_string("references.bib")?)?;
+//!
+//! let registry = SourceRegistry::new();
+//! let checker = RetractionChecker::new(registry.all().cloned().collect());
+//! let report = checker.check_all(&mut papers).await;
+//! for entry in report.flagged() {
+//!     println!("{}: {}", entry.id, entry.status.as_ref().unwrap().warning());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod retraction_watch;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use futures_util::{stream, StreamExt};
+use schemars::JsonSchema;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::enrich::find_by_title;
+use crate::formats::extra_text;
+use crate::graph::normalize_doi;
+use crate::models::{Paper, PaperStatus, StatusKind};
+use crate::sources::{Source, SourceError};
+
+pub use retraction_watch::RetractionWatch;
+
+/// Sources asked for notices, in order
+pub const CHECK_SOURCES: &[&str] = &["crossref", "pubmed", "openalex"];
+
+/// Number of papers checked concurrently
+const CONCURRENCY: usize = 4;
+
+/// Errors reading the Retraction Watch dataset
+#[derive(Debug, Error)]
+pub enum RetractionError {
+    #[error("cannot read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("not a Retraction Watch CSV file: no {0} column")]
+    MissingColumn(&'static str),
+}
+
+/// The status of one checked paper
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RetractionEntry {
+    /// Cite key, or the paper's DOI or ID
+    pub id: String,
+
+    /// Paper title
+    pub title: String,
+
+    /// Most serious notice found; `None` when the paper is in good standing
+    pub status: Option<PaperStatus>,
+
+    /// Lookups that failed, as `<source>: <error>`
+    pub errors: Vec<String>,
+}
+
+/// What `check-retractions` found, one entry per paper
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct RetractionReport {
+    /// One entry per paper, in input order
+    pub entries: Vec<RetractionEntry>,
+}
+
+impl RetractionReport {
+    /// Papers with a notice
+    pub fn flagged(&self) -> impl Iterator<Item = &RetractionEntry> {
+        self.entries.iter().filter(|e| e.status.is_some())
+    }
+
+    /// Number of papers whose most serious notice is of the given kind
+    pub fn count(&self, kind: StatusKind) -> usize {
+        self.flagged()
+            .filter(|e| e.status.as_ref().is_some_and(|s| s.kind == kind))
+            .count()
+    }
+
+    /// One-line summary, e.g. `12 papers checked: 1 retracted, 0 expressions of concern, 2 corrected`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} papers checked: {} retracted, {} expressions of concern, {} corrected",
+            self.entries.len(),
+            self.count(StatusKind::Retracted),
+            self.count(StatusKind::ExpressionOfConcern),
+            self.count(StatusKind::Corrected),
+        )
+    }
+}
+
+/// Finds retractions, expressions of concern and corrections of papers
+#[derive(Debug)]
+pub struct RetractionChecker {
+    sources: Vec<Arc<dyn Source>>,
+    database: Option<RetractionWatch>,
+}
+
+impl RetractionChecker {
+    /// Create a checker asking the given sources
+    pub fn new(sources: Vec<Arc<dyn Source>>) -> Self {
+        Self {
+            sources,
+            database: None,
+        }
+    }
+
+    /// Also look papers up in a local copy of the Retraction Watch dataset
+    pub fn with_database(mut self, database: RetractionWatch) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// Check one paper and record the most serious notice as its status
+    ///
+    /// Returns the lookups that failed.
+    pub async fn check(&self, paper: &mut Paper) -> Vec<String> {
+        let (notices, errors) = self.lookup(paper).await;
+        for status in notices {
+            paper.annotate(status);
+        }
+        errors
+    }
+
+    /// Check every paper, recording notices as their status
+    pub async fn check_all(&self, papers: &mut [Paper]) -> RetractionReport {
+        let found: Vec<(Vec<PaperStatus>, Vec<String>)> = stream::iter(papers.iter())
+            .map(|paper| self.lookup(paper))
+            .buffered(CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut report = RetractionReport::default();
+        for (paper, (notices, errors)) in papers.iter_mut().zip(found) {
+            for status in notices {
+                paper.annotate(status);
+            }
+            report.entries.push(RetractionEntry {
+                id: extra_text(paper, "citekey").unwrap_or_else(|| paper.primary_id().to_string()),
+                title: paper.title.clone(),
+                status: paper.status.clone(),
+                errors,
+            });
+        }
+        report
+    }
+
+    /// Notices for a paper from the dataset and the sources
+    ///
+    /// Papers are looked up by DOI, or by title when they have none.
+    async fn lookup(&self, paper: &Paper) -> (Vec<PaperStatus>, Vec<String>) {
+        let mut notices: Vec<PaperStatus> = self
+            .database
+            .as_ref()
+            .and_then(|database| database.lookup(paper))
+            .cloned()
+            .into_iter()
+            .collect();
+        let mut errors = Vec::new();
+
+        let doi = paper.doi.as_deref().and_then(normalize_doi);
+        for source_id in CHECK_SOURCES {
+            let Some(source) = self.sources.iter().find(|s| s.id() == *source_id) else {
+                continue;
+            };
+            let record = match doi {
+                Some(ref doi) if source.supports_doi_lookup() => {
+                    source.get_by_doi(doi).await.map(Some)
+                }
+                None if source.supports_search() => find_by_title(source.as_ref(), paper).await,
+                _ => continue,
+            };
+            match record {
+                Ok(Some(record)) => notices.extend(record.status),
+                Ok(None) | Err(SourceError::NotFound(_)) => {}
+                Err(e) => errors.push(format!("{}: {}", source.id(), e)),
+            }
+        }
+        (notices, errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    use crate::models::{PaperBuilder, SearchQuery, SearchResponse, SourceType};
+    use crate::sources::SourceCapabilities;
+
+    /// A source that knows a fixed list of papers by DOI
+    #[derive(Debug)]
+    struct RecordSource {
+        id: &'static str,
+        records: Vec<Paper>,
+    }
+
+    #[async_trait]
+    impl Source for RecordSource {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn name(&self) -> &str {
+            self.id
+        }
+
+        fn capabilities(&self) -> SourceCapabilities {
+            SourceCapabilities::SEARCH | SourceCapabilities::DOI_LOOKUP
+        }
+
+        async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
+            Ok(SearchResponse::new(
+                self.records.clone(),
+                self.id,
+                &query.query,
+            ))
+        }
+
+        async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
+            self.records
+                .iter()
+                .find(|r| r.doi.as_deref() == Some(doi))
+                .cloned()
+                .ok_or_else(|| SourceError::NotFound(doi.to_string()))
+        }
+    }
+
+    fn record(doi: &str, status: Option<PaperStatus>) -> Paper {
+        let mut paper = PaperBuilder::new(doi, "A paper about something", "", SourceType::CrossRef)
+            .doi(doi)
+            .build();
+        paper.status = status;
+        paper
+    }
+
+    #[tokio::test]
+    async fn test_check_all() {
+        let crossref = RecordSource {
+            id: "crossref",
+            records: vec![
+                record(
+                    "10.1000/retracted",
+                    Some(PaperStatus::new(StatusKind::Retracted, "crossref")),
+                ),
+                record(
+                    "10.1000/corrected",
+                    Some(PaperStatus::new(StatusKind::Corrected, "crossref")),
+                ),
+                record("10.1000/fine", None),
+            ],
+        };
+        let pubmed = RecordSource {
+            id: "pubmed",
+            records: vec![record(
+                "10.1000/corrected",
+                Some(PaperStatus::new(StatusKind::ExpressionOfConcern, "pubmed")),
+            )],
+        };
+        let database = RetractionWatch::parse(
+            "OriginalPaperDOI,OriginalPaperPubMedID,RetractionNature\n10.1000/fine,0,Correction\n",
+        )
+        .unwrap();
+        let checker = RetractionChecker::new(vec![Arc::new(crossref), Arc::new(pubmed)])
+            .with_database(database);
+
+        let mut papers = vec![
+            record("10.1000/RETRACTED", None),
+            record("10.1000/corrected", None),
+            record("10.1000/fine", None),
+            record("10.1000/unknown", None),
+        ];
+        papers[0]
+            .extra
+            .get_or_insert_with(Default::default)
+            .insert("citekey".to_string(), serde_json::json!("smith2020"));
+        let report = checker.check_all(&mut papers).await;
+
+        assert_eq!(report.entries[0].id, "smith2020");
+        assert!(papers[0].is_retracted());
+        assert_eq!(
+            papers[1]
+                .status
+                .as_ref()
+                .map(|s| (s.kind, s.source.as_str())),
+            Some((StatusKind::ExpressionOfConcern, "pubmed"))
+        );
+        assert_eq!(
+            papers[2].status.as_ref().map(|s| s.source.as_str()),
+            Some("retraction_watch")
+        );
+        assert!(report.entries[3].status.is_none());
+        assert_eq!(report.flagged().count(), 3);
+        assert_eq!(
+            report.summary(),
+            "4 papers checked: 1 retracted, 1 expressions of concern, 1 corrected"
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Local copy of the Retraction Watch dataset.
+//!
+//! The dataset is distributed by Crossref as a CSV file with one row per
+//! notice. Rows are indexed by the DOI and PubMed ID of the original paper.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::RetractionError;
+use crate::graph::normalize_doi;
+use crate::models::{Paper, PaperStatus, SourceType, StatusKind};
+
+/// Source name recorded in statuses found in the dataset
+pub const SOURCE: &str = "retraction_watch";
+
+/// Notices of the Retraction Watch dataset, by original paper
+#[derive(Debug, Clone, Default)]
+pub struct RetractionWatch {
+    by_doi: HashMap<String, PaperStatus>,
+    by_pmid: HashMap<String, PaperStatus>,
+}
+
+impl RetractionWatch {
+    /// Read the dataset from a CSV file
+    pub fn open(path: &Path) -> Result<Self, RetractionError> {
+        let text = std::fs::read_to_string(path).map_err(|source| RetractionError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&text)
+    }
+
+    /// Parse the dataset's CSV text
+    ///
+    /// Only `OriginalPaperDOI`, `OriginalPaperPubMedID`, `RetractionNature`,
+    /// `RetractionDate` and `RetractionDOI` are used. Reinstatements and other
+    /// notices that leave a paper standing are skipped.
+    pub fn parse(text: &str) -> Result<Self, RetractionError> {
+        let mut rows = records(text.trim_start_matches('\u{feff}')).into_iter();
+        let header = rows.next().unwrap_or_default();
+        let column = |name: &'static str| {
+            header
+                .iter()
+                .position(|h| h.trim() == name)
+                .ok_or(RetractionError::MissingColumn(name))
+        };
+        let doi = column("OriginalPaperDOI")?;
+        let pmid = column("OriginalPaperPubMedID")?;
+        let nature = column("RetractionNature")?;
+        let date = header.iter().position(|h| h.trim() == "RetractionDate");
+        let notice = header.iter().position(|h| h.trim() == "RetractionDOI");
+
+        let mut database = Self::default();
+        for row in rows {
+            let field = |index: usize| row.get(index).map(|v| v.trim()).filter(|v| known(v));
+            let Some(kind) = field(nature).and_then(StatusKind::from_notice) else {
+                continue;
+            };
+            let mut status = PaperStatus::new(kind, SOURCE);
+            if let Some(notice) = notice.and_then(field) {
+                status = status.notice(notice);
+            }
+            if let Some(date) = date.and_then(field).and_then(us_date) {
+                status = status.date(date);
+            }
+
+            if let Some(doi) = field(doi).and_then(normalize_doi) {
+                insert(&mut database.by_doi, doi, status.clone());
+            }
+            if let Some(pmid) = field(pmid).filter(|p| *p != "0") {
+                insert(&mut database.by_pmid, pmid.to_string(), status);
+            }
+        }
+        Ok(database)
+    }
+
+    /// Number of papers with a notice
+    pub fn len(&self) -> usize {
+        self.by_doi.len().max(self.by_pmid.len())
+    }
+
+    /// Whether the dataset has no notices
+    pub fn is_empty(&self) -> bool {
+        self.by_doi.is_empty() && self.by_pmid.is_empty()
+    }
+
+    /// The most serious notice for a paper, by DOI or PubMed ID
+    pub fn lookup(&self, paper: &Paper) -> Option<&PaperStatus> {
+        let by_doi = paper
+            .doi
+            .as_deref()
+            .and_then(normalize_doi)
+            .and_then(|doi| self.by_doi.get(&doi));
+        let pmid = match paper.source {
+            SourceType::PubMed => Some(paper.paper_id.clone()),
+            _ => crate::formats::extra_text(paper, "pmid"),
+        };
+        let by_pmid = pmid.and_then(|pmid| self.by_pmid.get(pmid.trim()));
+        match (by_doi, by_pmid) {
+            (Some(a), Some(b)) if b.kind > a.kind => Some(b),
+            (Some(a), _) => Some(a),
+            (None, b) => b,
+        }
+    }
+}
+
+/// Keep the more serious notice for a paper
+fn insert(index: &mut HashMap<String, PaperStatus>, key: String, status: PaperStatus) {
+    match index.get(&key) {
+        Some(existing) if existing.kind >= status.kind => {}
+        _ => {
+            index.insert(key, status);
+        }
+    }
+}
+
+/// Whether a value is filled in; the dataset writes `unavailable` for gaps
+fn known(value: &str) -> bool {
+    !value.is_empty() && !value.eq_ignore_ascii_case("unavailable")
+}
+
+/// `6/5/2020 0:00` as `2020-06-05`
+fn us_date(value: &str) -> Option<String> {
+    let date = value.split_whitespace().next()?;
+    let mut parts = date.split('/').map(|p| p.parse::<u32>().ok());
+    let (month, day, year) = (parts.next()??, parts.next()??, parts.next()??);
+    (1..=12)
+        .contains(&month)
+        .then(|| format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// Rows of a CSV file; quoted fields may hold commas, quotes and newlines
+fn records(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PaperBuilder;
+
+    const SAMPLE: &str = "\u{feff}\"Record ID\",\"Title\",\"RetractionDate\",\"RetractionDOI\",\"OriginalPaperDOI\",\"OriginalPaperPubMedID\",\"RetractionNature\",\"Reason\"\r
+\"1\",\"Hydroxychloroquine, \"\"or\"\" chloroquine\",\"6/5/2020 0:00\",\"10.1016/S0140-6736(20)31324-6\",\"10.1016/S0140-6736(20)31180-6\",\"32450107\",\"Retraction\",\"+Concerns\r
+about data;\"\r
+\"2\",\"Same paper\",\"6/3/2020 0:00\",\"unavailable\",\"10.1016/s0140-6736(20)31180-6\",\"0\",\"Expression of concern\",\"\"\r
+\"3\",\"Other paper\",\"1/2/2019 0:00\",\"\",\"10.1000/corrected\",\"0\",\"Correction\",\"\"\r
+\"4\",\"Back\",\"1/2/2019 0:00\",\"\",\"10.1000/back\",\"0\",\"Reinstatement\",\"\"\r
+";
+
+    #[test]
+    fn test_parse_dataset() {
+        let database = RetractionWatch::parse(SAMPLE).unwrap();
+        assert_eq!(database.len(), 2);
+
+        let paper = PaperBuilder::new("x", "", "", SourceType::CrossRef)
+            .doi("https://doi.org/10.1016/S0140-6736(20)31180-6")
+            .build();
+        let status = database.lookup(&paper).unwrap();
+        assert_eq!(status.kind, StatusKind::Retracted);
+        assert_eq!(status.date.as_deref(), Some("2020-06-05"));
+        assert_eq!(
+            status.notice.as_deref(),
+            Some("10.1016/S0140-6736(20)31324-6")
+        );
+
+        let by_pmid = PaperBuilder::new("32450107", "", "", SourceType::PubMed).build();
+        assert_eq!(
+            database.lookup(&by_pmid).map(|s| s.kind),
+            Some(StatusKind::Retracted)
+        );
+        let corrected = PaperBuilder::new("y", "", "", SourceType::CrossRef)
+            .doi("10.1000/corrected")
+            .build();
+        assert_eq!(
+            database.lookup(&corrected).map(|s| s.kind),
+            Some(StatusKind::Corrected)
+        );
+        let reinstated = PaperBuilder::new("z", "", "", SourceType::CrossRef)
+            .doi("10.1000/back")
+            .build();
+        assert!(database.lookup(&reinstated).is_none());
+
+        assert!(matches!(
+            RetractionWatch::parse("a,b\n1,2\n"),
+            Err(RetractionError::MissingColumn("OriginalPaperDOI"))
+        ));
+    }
+}
@@ -2,7 +2,9 @@
 
 use async_trait::async_trait;
 use feed_rs::parser;
-use std::sync::Arc;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
 use crate::models::{
     Paper, PaperBuilder, ReadRequest, ReadResult, SearchQuery, SearchResponse, SourceType,
@@ -134,6 +136,38 @@ impl ArxivSource {
         }
     }
 
+    /// The journal DOI and reference authors gave for each entry, by entry ID
+    ///
+    /// feed-rs drops the `arxiv:` elements, so they are read from the raw feed.
+    /// Values are keyed `published_doi` and `journal_ref`.
+    fn parse_journal_links(xml: &str) -> HashMap<String, Vec<(&'static str, String)>> {
+        static ENTRY: OnceLock<Regex> = OnceLock::new();
+        static FIELD: OnceLock<Regex> = OnceLock::new();
+        let entry = ENTRY.get_or_init(|| Regex::new(r"(?s)<entry\b.*?</entry>").unwrap());
+        let field = FIELD.get_or_init(|| {
+            Regex::new(r"(?s)<(id|arxiv:doi|arxiv:journal_ref)\b[^>]*>(.*?)</").unwrap()
+        });
+
+        let mut links = HashMap::new();
+        for entry in entry.find_iter(xml) {
+            let mut id = None;
+            let mut found = Vec::new();
+            for caps in field.captures_iter(entry.as_str()) {
+                let value = caps[2].split_whitespace().collect::<Vec<_>>().join(" ");
+                match &caps[1] {
+                    "id" => id = Some(value),
+                    _ if value.is_empty() => {}
+                    "arxiv:doi" => found.push(("published_doi", value)),
+                    _ => found.push(("journal_ref", value)),
+                }
+            }
+            if let (Some(id), false) = (id, found.is_empty()) {
+                links.insert(id, found);
+            }
+        }
+        links
+    }
+
     /// Parse arXiv Atom feed entry into Paper
     fn parse_entry(entry: &feed_rs::model::Entry) -> Result<Paper, SourceError> {
         // Extract paper ID from URL
@@ -281,13 +315,27 @@ impl Source for ArxivSource {
                 let feed = parser::parse(bytes.as_ref())
                     .map_err(|e| SourceError::Parse(format!("Failed to parse Atom feed: {}", e)))?;
 
-                Ok(feed)
+                Ok((feed, String::from_utf8_lossy(&bytes).into_owned()))
             }
         })
         .await?;
+        let (feed, xml) = feed;
 
-        let papers: Result<Vec<Paper>, SourceError> =
-            feed.entries.iter().map(Self::parse_entry).collect();
+        let links = Self::parse_journal_links(&xml);
+        let papers: Result<Vec<Paper>, SourceError> = feed
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut paper = Self::parse_entry(entry)?;
+                if let Some(entry_links) = links.get(entry.id.trim()) {
+                    let extra = paper.extra.get_or_insert_with(HashMap::new);
+                    for (key, value) in entry_links {
+                        extra.insert(key.to_string(), serde_json::Value::String(value.clone()));
+                    }
+                }
+                Ok(paper)
+            })
+            .collect();
 
         let papers = papers?;
 
@@ -445,6 +493,12 @@ mod tests {
             .as_ref()
             .expect("Title should be present");
         assert!(title.content.contains("Test Paper Title"));
+
+        let links = ArxivSource::parse_journal_links(mock_response);
+        assert_eq!(
+            links["http://arxiv.org/abs/2301.12345"],
+            vec![("published_doi", "10.1234/test".to_string())]
+        );
     }
 
     #[tokio::test]
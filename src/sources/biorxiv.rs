@@ -299,6 +299,7 @@ impl Source for BiorxivMedrxivSource {
         let response = self
             .client
             .get(pdf_url)
+            .no_cache()
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Failed to download PDF: {}", e)))?;
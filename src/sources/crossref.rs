@@ -1,10 +1,14 @@
 //! CrossRef research source implementation.
 
 use async_trait::async_trait;
+use regex::Regex;
 use serde::Deserialize;
-use std::sync::Arc;
+use serde_json::Value;
+use std::sync::{Arc, OnceLock};
 
-use crate::models::{Paper, PaperBuilder, SearchQuery, SearchResponse, SourceType};
+use crate::models::{
+    Paper, PaperBuilder, PaperStatus, SearchQuery, SearchResponse, SourceType, StatusKind,
+};
 use crate::sources::{Source, SourceCapabilities, SourceError};
 use crate::utils::{api_retry_config, with_retry, HttpClient};
 
@@ -130,48 +134,7 @@ impl Source for CrossRefSource {
                 SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
             })?;
 
-        let papers: Vec<Paper> = data
-            .message
-            .items
-            .into_iter()
-            .filter_map(|item| {
-                let title = item.get("title").and_then(|v| v.as_str()).unwrap_or_default().to_string();
-
-                let authors = item.get("author")
-                    .and_then(|v| v.as_array())
-                    .map(|authors| {
-                        authors.iter()
-                            .filter_map(|a| a.get("given").and_then(|g| g.as_str()))
-                            .collect::<Vec<_>>()
-                            .join("; ")
-                    })
-                    .unwrap_or_default();
-
-                let doi = item.get("doi").and_then(|v| v.as_str()).unwrap_or_default().to_string();
-
-                let url = item.get("url").and_then(|v| v.as_str()).unwrap_or_default().to_string();
-
-                let published_date = item.get("published-print")
-                    .and_then(|v| v.get("date-parts"))
-                    .and_then(|v| v.as_array())
-                    .and_then(|dates| dates.first())
-                    .and_then(|d| d.as_i64())
-                    .map(|y| y.to_string())
-                    .unwrap_or_default();
-
-                if title.is_empty() {
-                    return None;
-                }
-
-                Some(
-                    PaperBuilder::new(doi.clone(), title, url, SourceType::CrossRef)
-                        .authors(&authors)
-                        .doi(&doi)
-                        .published_date(&published_date)
-                        .build(),
-                )
-            })
-            .collect();
+        let papers: Vec<Paper> = data.message.items.iter().filter_map(parse_work).collect();
 
         Ok(SearchResponse::new(papers, "CrossRef", &query.query)
             .total_results(data.message.total_results))
@@ -197,50 +160,186 @@ impl Source for CrossRefSource {
             .await
             .unwrap_or_else(|_| "Failed to read response body".to_string());
 
-        let data: CRResponse = serde_json::from_str(&response_text)
+        let data: CRWorkResponse = serde_json::from_str(&response_text)
             .map_err(|e| {
                 let preview = response_text.chars().take(500).collect::<String>();
                 tracing::warn!("CrossRef DOI parse error: {}", preview);
                 SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
             })?;
 
-        let item = data
-            .message
-            .items
-            .first()
-            .ok_or_else(|| SourceError::NotFound("DOI not found".to_string()))?;
-
-        let title = item.get("title").and_then(|v| v.as_str()).unwrap_or_default().to_string();
-
-        let authors = item.get("author")
-            .and_then(|v| v.as_array())
-            .map(|authors| {
-                authors.iter()
-                    .filter_map(|a| a.get("given").and_then(|g| g.as_str()))
-                    .collect::<Vec<_>>()
-                    .join("; ")
+        parse_work(&data.message).ok_or_else(|| SourceError::NotFound("DOI not found".to_string()))
+    }
+}
+
+/// Convert a Crossref work into a paper
+///
+/// Retractions, expressions of concern and corrections listed in the work's
+/// `updated-by` relations become its status. A work that is itself such a
+/// notice lists the DOIs it updates in the `update_to` extra field.
+fn parse_work(item: &Value) -> Option<Paper> {
+    let title = first_text(item.get("title")?)?;
+    let doi = item.get("DOI").and_then(Value::as_str).unwrap_or_default();
+    let url = item.get("URL").and_then(Value::as_str).unwrap_or_default();
+
+    let authors = item
+        .get("author")
+        .and_then(Value::as_array)
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(|a| {
+                    let name = a.get("name").and_then(Value::as_str);
+                    let given = a.get("given").and_then(Value::as_str);
+                    let family = a.get("family").and_then(Value::as_str);
+                    match (given, family) {
+                        (Some(given), Some(family)) => Some(format!("{} {}", given, family)),
+                        (None, Some(family)) => Some(family.to_string()),
+                        _ => name.map(str::to_string),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+        .unwrap_or_default();
+
+    let published_date = ["published-print", "published-online", "published", "issued"]
+        .iter()
+        .find_map(|field| item.get(*field).and_then(date_parts));
+
+    let mut builder = PaperBuilder::new(doi, title, url, SourceType::CrossRef)
+        .authors(authors)
+        .doi(doi);
+    if let Some(date) = published_date {
+        builder = builder.published_date(date);
+    }
+    if let Some(venue) = item.get("container-title").and_then(first_text) {
+        builder = builder.extra("venue", Value::String(venue));
+    }
+    if let Some(count) = item.get("is-referenced-by-count").and_then(Value::as_u64) {
+        builder = builder.citations(count as u32);
+    }
+    if let Some(text) = item
+        .get("abstract")
+        .and_then(Value::as_str)
+        .map(jats_text)
+        .filter(|t| !t.is_empty())
+    {
+        builder = builder.abstract_text(text);
+    }
+    let subjects: Vec<&str> = item
+        .get("subject")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    if !subjects.is_empty() {
+        builder = builder.keywords(subjects.join(";"));
+    }
+
+    for update in relations(item, "updated-by") {
+        let Some(kind) = update
+            .get("type")
+            .and_then(Value::as_str)
+            .and_then(StatusKind::from_notice)
+        else {
+            continue;
+        };
+        let mut status = PaperStatus::new(kind, "crossref");
+        if let Some(notice) = update.get("DOI").and_then(Value::as_str) {
+            status = status.notice(notice);
+        }
+        if let Some(date) = update.get("updated").and_then(date_parts) {
+            status = status.date(date);
+        }
+        builder = builder.status(status);
+    }
+    let updates: Vec<Value> = relations(item, "update-to")
+        .map(|update| {
+            serde_json::json!({
+                "doi": update.get("DOI").cloned().unwrap_or(Value::Null),
+                "type": update.get("type").cloned().unwrap_or(Value::Null),
             })
-            .unwrap_or_default();
-
-        let doi = item.get("doi").and_then(|v| v.as_str()).unwrap_or_default().to_string();
-
-        let url = item.get("url").and_then(|v| v.as_str()).unwrap_or_default().to_string();
-
-        let published_date = item.get("published-print")
-            .and_then(|v| v.get("date-parts"))
-            .and_then(|v| v.as_array())
-            .and_then(|dates| dates.first())
-            .and_then(|d| d.as_i64())
-            .map(|y| y.to_string())
-            .unwrap_or_default();
-
-        Ok(
-            PaperBuilder::new(doi.clone(), title, url, SourceType::CrossRef)
-                .authors(&authors)
-                .doi(&doi)
-                .published_date(&published_date)
-                .build(),
-        )
+        })
+        .collect();
+    if !updates.is_empty() {
+        builder = builder.extra("update_to", Value::Array(updates));
+    }
+
+    // Links between a preprint and its published version
+    let links = [
+        ("is-preprint-of", "doi", "published_doi"),
+        ("has-preprint", "doi", "preprint_doi"),
+        ("has-preprint", "arxiv", "arxiv_id"),
+    ];
+    for (relation, id_type, key) in links {
+        let id = item
+            .get("relation")
+            .and_then(|r| r.get(relation))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|link| link.get("id-type").and_then(Value::as_str) == Some(id_type))
+            .find_map(|link| link.get("id").and_then(Value::as_str));
+        if let Some(id) = id {
+            builder = builder.extra(key, Value::String(id.trim().to_string()));
+        }
+    }
+
+    Some(builder.build())
+}
+
+/// Crossref relations of a kind (`updated-by`, `update-to`)
+fn relations<'a>(item: &'a Value, field: &str) -> impl Iterator<Item = &'a Value> {
+    item.get(field)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+/// A string, or the first string of an array, as Crossref titles come
+fn first_text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::Array(values) => values.first()?.as_str()?,
+        value => value.as_str()?,
+    };
+    Some(text.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Plain text of a JATS abstract, without its "Abstract" heading
+fn jats_text(jats: &str) -> String {
+    static TITLE: OnceLock<Regex> = OnceLock::new();
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let title = TITLE.get_or_init(|| Regex::new(r"(?s)<jats:title>.*?</jats:title>").unwrap());
+    let tag = TAG.get_or_init(|| Regex::new(r"<(/?)([^>]*)>").unwrap());
+
+    let text = title.replace_all(jats, " ");
+    // Paragraphs and sections end in a space; inline markup just goes
+    let text = tag.replace_all(&text, |caps: &regex::Captures| {
+        let name = caps[2].trim_start_matches("jats:");
+        if &caps[1] == "/" && (name == "p" || name == "sec") {
+            " "
+        } else {
+            ""
+        }
+    });
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// An ISO date from Crossref `{"date-parts": [[2020, 6, 4]]}`
+fn date_parts(value: &Value) -> Option<String> {
+    let parts: Vec<i64> = value
+        .get("date-parts")?
+        .get(0)?
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_i64)
+        .collect();
+    match parts.as_slice() {
+        [year] => Some(format!("{:04}", year)),
+        [year, month] => Some(format!("{:04}-{:02}", year, month)),
+        [year, month, day, ..] => Some(format!("{:04}-{:02}-{:02}", year, month, day)),
+        [] => None,
     }
 }
 
@@ -255,17 +354,18 @@ struct CRResponse {
 struct CRMessage {
     #[serde(rename = "total-results")]
     total_results: usize,
-    items: Vec<serde_json::Value>,
+    items: Vec<Value>,
 }
 
 #[derive(Debug, Deserialize)]
-struct CRDate {
-    _date: Option<String>,
+struct CRWorkResponse {
+    message: Value,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::formats::extra_text;
 
     #[test]
     fn test_crossref_source_creation() {
@@ -292,4 +392,97 @@ mod tests {
         let source = CrossRefSource::new().unwrap();
         assert_eq!(source.name(), "CrossRef");
     }
+
+    #[test]
+    fn test_parse_work_with_updates() {
+        let work = serde_json::json!({
+            "DOI": "10.1016/S0140-6736(20)31180-6",
+            "URL": "https://doi.org/10.1016/S0140-6736(20)31180-6",
+            "title": ["Hydroxychloroquine or chloroquine with or without a macrolide"],
+            "author": [{"given": "Mandeep R.", "family": "Mehra"}, {"name": "Consortium"}],
+            "container-title": ["The Lancet"],
+            "published-print": {"date-parts": [[2020, 5]]},
+            "is-referenced-by-count": 700,
+            "abstract": "<jats:title>Abstract</jats:title><jats:sec><jats:p>Hydroxychloroquine\n  was <jats:italic>not</jats:italic> beneficial.</jats:p><jats:p>H<jats:sub>2</jats:sub>O</jats:p></jats:sec>",
+            "subject": ["General Medicine"],
+            "updated-by": [
+                {
+                    "DOI": "10.1016/S0140-6736(20)31249-6",
+                    "type": "expression_of_concern",
+                    "updated": {"date-parts": [[2020, 6, 3]]}
+                },
+                {
+                    "DOI": "10.1016/S0140-6736(20)31324-6",
+                    "type": "retraction",
+                    "updated": {"date-parts": [[2020, 6, 5]]}
+                }
+            ]
+        });
+        let paper = parse_work(&work).unwrap();
+        assert_eq!(paper.authors, "Mandeep R. Mehra; Consortium");
+        assert_eq!(paper.published_date.as_deref(), Some("2020-05"));
+        assert_eq!(paper.venue(), Some("The Lancet"));
+        assert_eq!(paper.citations, Some(700));
+        assert_eq!(
+            paper.r#abstract,
+            "Hydroxychloroquine was not beneficial. H2O"
+        );
+        assert_eq!(paper.keywords.as_deref(), Some("General Medicine"));
+        let status = paper.status.unwrap();
+        assert_eq!(status.kind, StatusKind::Retracted);
+        assert_eq!(status.date.as_deref(), Some("2020-06-05"));
+        assert_eq!(
+            status.notice.as_deref(),
+            Some("10.1016/S0140-6736(20)31324-6")
+        );
+
+        let notice = serde_json::json!({
+            "DOI": "10.1016/S0140-6736(20)31324-6",
+            "title": "Retraction",
+            "update-to": [{"DOI": "10.1016/S0140-6736(20)31180-6", "type": "retraction"}]
+        });
+        let notice = parse_work(&notice).unwrap();
+        assert!(notice.status.is_none());
+        assert_eq!(
+            notice.extra.unwrap()["update_to"][0]["type"],
+            serde_json::json!("retraction")
+        );
+        assert!(parse_work(&serde_json::json!({"DOI": "10.1/x", "title": []})).is_none());
+    }
+
+    #[test]
+    fn test_parse_preprint_relations() {
+        let preprint = serde_json::json!({
+            "DOI": "10.1101/2020.03.01.123456",
+            "title": ["A preprint"],
+            "relation": {
+                "is-preprint-of": [{"id-type": "doi", "id": "10.1038/s41586-020-0001-1", "asserted-by": "subject"}]
+            }
+        });
+        let paper = parse_work(&preprint).unwrap();
+        assert_eq!(
+            extra_text(&paper, "published_doi").as_deref(),
+            Some("10.1038/s41586-020-0001-1")
+        );
+
+        let published = serde_json::json!({
+            "DOI": "10.1038/s41586-020-0001-1",
+            "title": ["The published paper"],
+            "relation": {
+                "has-preprint": [
+                    {"id-type": "doi", "id": "10.1101/2020.03.01.123456"},
+                    {"id-type": "arxiv", "id": "2003.00001"}
+                ]
+            }
+        });
+        let paper = parse_work(&published).unwrap();
+        assert_eq!(
+            extra_text(&paper, "preprint_doi").as_deref(),
+            Some("10.1101/2020.03.01.123456")
+        );
+        assert_eq!(
+            extra_text(&paper, "arxiv_id").as_deref(),
+            Some("2003.00001")
+        );
+    }
 }
@@ -7,12 +7,28 @@ use quick_xml::events::{BytesStart, Event};
 use quick_xml::reader::Reader;
 use std::sync::Arc;
 
-use crate::models::{Paper, PaperBuilder, SearchQuery, SearchResponse, SourceType};
+use crate::models::{
+    normalize_orcid, Author, Paper, PaperBuilder, SearchQuery, SearchResponse, SourceType,
+};
 use crate::sources::{Source, SourceCapabilities, SourceError};
 use crate::utils::{api_retry_config, with_retry, HttpClient};
 
 const DBLP_BASE_URL: &str = "https://dblp.org";
 const DBLP_SEARCH_URL: &str = "https://dblp.org/search/publ/api";
+const DBLP_AUTHOR_SEARCH_URL: &str = "https://dblp.org/search/author/api";
+
+/// Record types that appear inside `<r>` on a DBLP person page
+const RECORD_TYPES: &[&str] = &[
+    "article",
+    "inproceedings",
+    "proceedings",
+    "book",
+    "incollection",
+    "phdthesis",
+    "mastersthesis",
+    "www",
+    "data",
+];
 
 /// DBLP research source
 ///
@@ -152,6 +168,214 @@ impl DblpSource {
         Ok(papers)
     }
 
+    /// GET a DBLP URL as text, with retries
+    async fn fetch_text(&self, url: &str) -> Result<String, SourceError> {
+        let client = Arc::clone(&self.client);
+
+        with_retry(api_retry_config(), || {
+            let client = Arc::clone(&client);
+            let url = url.to_string();
+            async move {
+                let response = client
+                    .get(&url)
+                    .header("Accept", "application/xml")
+                    .send()
+                    .await
+                    .map_err(|e| SourceError::Network(format!("Failed to query DBLP: {}", e)))?;
+
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Err(SourceError::NotFound(url));
+                }
+                if !response.status().is_success() {
+                    return Err(SourceError::Api(format!(
+                        "DBLP API returned status: {}",
+                        response.status()
+                    )));
+                }
+
+                response
+                    .text()
+                    .await
+                    .map_err(|e| SourceError::Parse(format!("Failed to read XML: {}", e)))
+            }
+        })
+        .await
+    }
+
+    /// Parse the hits of a DBLP author search
+    fn parse_author_hits(xml_content: &str) -> Result<Vec<Author>, SourceError> {
+        let mut reader = Reader::from_str(xml_content);
+        let mut authors = Vec::new();
+        let mut current: Option<Author> = None;
+        let mut affiliation_note = false;
+        let mut text = String::new();
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(ref e)) => {
+                    text.clear();
+                    match e.name().as_ref() {
+                        b"hit" => current = Some(Author::new("", "", SourceType::DBLP)),
+                        b"note" => {
+                            affiliation_note = get_attr(e, "type").as_deref() == Some("affiliation")
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Text(e)) => text.push_str(&e.unescape().unwrap_or_default()),
+                Ok(Event::End(ref e)) => {
+                    let Some(author) = current.as_mut() else {
+                        continue;
+                    };
+                    match e.name().as_ref() {
+                        b"author" => author.name = text.trim().to_string(),
+                        b"url" => {
+                            let url = text.trim();
+                            if let Some(pid) = url.split("/pid/").nth(1) {
+                                author.author_id = pid.trim_end_matches(".html").to_string();
+                                author.url =
+                                    Some(format!("{}.html", url.trim_end_matches(".html")));
+                            }
+                        }
+                        b"note" if affiliation_note => author.add_affiliation(&text),
+                        b"hit" => {
+                            if let Some(author) = current.take() {
+                                if !author.author_id.is_empty() {
+                                    authors.push(author);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(e) => return Err(SourceError::Parse(format!("XML parsing error: {}", e))),
+            }
+        }
+
+        Ok(authors)
+    }
+
+    /// Parse a DBLP person page (`/pid/<pid>.xml`), keeping up to `max_papers` records
+    fn parse_person(
+        pid: &str,
+        xml_content: &str,
+        max_papers: usize,
+    ) -> Result<Author, SourceError> {
+        let mut reader = Reader::from_str(xml_content);
+        let mut author = Author::new(pid, "", SourceType::DBLP);
+        author.url = Some(format!("{}/pid/{}.html", DBLP_BASE_URL, pid));
+
+        let mut path: Vec<String> = Vec::new();
+        let mut text = String::new();
+        let mut affiliation_note = false;
+
+        // Fields of the record being parsed
+        let mut key = String::new();
+        let mut title = String::new();
+        let mut year = String::new();
+        let mut venue = String::new();
+        let mut doi = String::new();
+        let mut record_authors: Vec<String> = Vec::new();
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(ref e)) => {
+                    let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    match (path.len(), name.as_str()) {
+                        (0, "dblpperson") => {
+                            author.name = get_attr(e, "name").unwrap_or_default();
+                            author.paper_count = get_attr(e, "n").and_then(|n| n.parse().ok());
+                        }
+                        (2, record) if path[1] == "r" && RECORD_TYPES.contains(&record) => {
+                            key = get_attr(e, "key").unwrap_or_default();
+                            title.clear();
+                            year.clear();
+                            venue.clear();
+                            doi.clear();
+                            record_authors.clear();
+                        }
+                        (2, "note") if path[1] == "person" => {
+                            affiliation_note = get_attr(e, "type").as_deref() == Some("affiliation")
+                        }
+                        _ => {}
+                    }
+                    // Inline markup in titles (<i>, <sub>) is deeper and keeps the text
+                    if path.len() <= 3 {
+                        text.clear();
+                    }
+                    path.push(name);
+                }
+                Ok(Event::Text(e)) => text.push_str(&e.unescape().unwrap_or_default()),
+                Ok(Event::End(_)) => {
+                    let Some(name) = path.pop() else { break };
+                    let value = text.trim();
+                    match (path.len(), name.as_str()) {
+                        (2, "note") if path[1] == "person" && affiliation_note => {
+                            author.add_affiliation(value)
+                        }
+                        (2, "url")
+                            if path[1] == "person"
+                                && author.orcid.is_none()
+                                && value.contains("orcid.org") =>
+                        {
+                            author.orcid = normalize_orcid(value);
+                        }
+                        (3, field) if path[1] == "r" => match field {
+                            "author" | "editor" => record_authors.push(value.to_string()),
+                            "title" => title = value.trim_end_matches('.').to_string(),
+                            "year" => year = value.to_string(),
+                            "journal" | "booktitle" => venue = value.to_string(),
+                            "ee" if doi.is_empty() => {
+                                if let Some(d) = value.split("doi.org/").nth(1) {
+                                    doi = d.to_string();
+                                }
+                            }
+                            _ => {}
+                        },
+                        (2, record)
+                            if path[1] == "r"
+                                && RECORD_TYPES.contains(&record)
+                                && record != "www"
+                                && author.papers.len() < max_papers =>
+                        {
+                            let url = format!("{}/rec/{}.html", DBLP_BASE_URL, key);
+                            let mut builder = PaperBuilder::new(
+                                key.clone(),
+                                title.clone(),
+                                url,
+                                SourceType::DBLP,
+                            )
+                            .authors(record_authors.join("; "));
+                            if !doi.is_empty() {
+                                builder = builder.doi(doi.clone());
+                            }
+                            if !year.is_empty() {
+                                builder = builder.published_date(year.clone());
+                            }
+                            if !venue.is_empty() {
+                                builder = builder
+                                    .categories(venue.clone())
+                                    .extra("venue", serde_json::Value::String(venue.clone()));
+                            }
+                            author.papers.push(builder.build());
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(e) => return Err(SourceError::Parse(format!("XML parsing error: {}", e))),
+            }
+        }
+
+        if author.name.is_empty() {
+            return Err(SourceError::NotFound(format!("DBLP person {}", pid)));
+        }
+        Ok(author)
+    }
+
     /// Fallback text-based XML parsing for edge cases
     fn parse_xml_fallback(&self, xml_content: &str) -> Result<Vec<Paper>, SourceError> {
         let mut papers = Vec::new();
@@ -270,7 +494,7 @@ impl Source for DblpSource {
     }
 
     fn capabilities(&self) -> SourceCapabilities {
-        SourceCapabilities::SEARCH
+        SourceCapabilities::SEARCH | SourceCapabilities::AUTHOR_PROFILES
     }
 
     async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
@@ -358,6 +582,33 @@ impl Source for DblpSource {
         Ok(SearchResponse::new(papers, "DBLP", &query.query))
     }
 
+    async fn search_authors(
+        &self,
+        name: &str,
+        max_results: usize,
+    ) -> Result<Vec<Author>, SourceError> {
+        let url = format!(
+            "{}?q={}&h={}&format=xml",
+            DBLP_AUTHOR_SEARCH_URL,
+            urlencoding::encode(name),
+            max_results.clamp(1, 1000)
+        );
+        let xml_content = self.fetch_text(&url).await?;
+        Self::parse_author_hits(&xml_content)
+    }
+
+    async fn get_author(&self, author_id: &str, max_papers: usize) -> Result<Author, SourceError> {
+        let pid = author_id
+            .trim_start_matches(DBLP_BASE_URL)
+            .trim_start_matches("/pid/")
+            .trim_end_matches(".html")
+            .trim_end_matches(".xml");
+        let xml_content = self
+            .fetch_text(&format!("{}/pid/{}.xml", DBLP_BASE_URL, pid))
+            .await?;
+        Self::parse_person(pid, &xml_content, max_papers)
+    }
+
     async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
         let clean_doi = doi
             .replace("https://doi.org/", "")
@@ -570,4 +821,76 @@ mod tests {
         assert_eq!(papers[0].paper_id, "conf/chi/2024a");
         assert_eq!(papers[1].paper_id, "conf/chi/2024b");
     }
+
+    #[test]
+    fn test_parse_author_hits() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <result><hits total="2">
+            <hit score="6" id="1"><info>
+                <author>Wei Wang 0001</author>
+                <notes><note type="affiliation">University of New South Wales</note></notes>
+                <url>https://dblp.org/pid/35/7092-1</url>
+            </info></hit>
+            <hit score="6" id="2"><info>
+                <author>Wei Wang 0002</author>
+                <url>https://dblp.org/pid/35/7092-2</url>
+            </info></hit>
+        </hits></result>"#;
+
+        let authors = DblpSource::parse_author_hits(xml).unwrap();
+        assert_eq!(authors.len(), 2);
+        assert_eq!(authors[0].author_id, "35/7092-1");
+        assert_eq!(authors[0].name, "Wei Wang 0001");
+        assert_eq!(
+            authors[0].affiliations,
+            vec!["University of New South Wales"]
+        );
+        assert_eq!(
+            authors[1].url.as_deref(),
+            Some("https://dblp.org/pid/35/7092-2.html")
+        );
+    }
+
+    #[test]
+    fn test_parse_person() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <dblpperson name="Jane Smith" pid="12/345" n="2">
+            <person key="homepages/12/345" mdate="2024-01-01">
+                <author pid="12/345">Jane Smith</author>
+                <note type="affiliation">MIT</note>
+                <url>https://orcid.org/0000-0002-1825-0097</url>
+            </person>
+            <r><article key="journals/jml/Smith23" mdate="2024-01-01">
+                <author pid="12/345">Jane Smith</author>
+                <author pid="67/890">Bob Jones</author>
+                <title>Graphs with <i>many</i> edges.</title>
+                <year>2023</year>
+                <journal>J. Mach. Learn.</journal>
+                <ee>https://doi.org/10.1234/jml.2023</ee>
+            </article></r>
+            <r><inproceedings key="conf/icml/Smith22" mdate="2024-01-01">
+                <author pid="12/345">Jane Smith</author>
+                <title>Trees.</title>
+                <year>2022</year>
+                <booktitle>ICML</booktitle>
+            </inproceedings></r>
+        </dblpperson>"#;
+
+        let author = DblpSource::parse_person("12/345", xml, 10).unwrap();
+        assert_eq!(author.name, "Jane Smith");
+        assert_eq!(author.orcid.as_deref(), Some("0000-0002-1825-0097"));
+        assert_eq!(author.affiliations, vec!["MIT"]);
+        assert_eq!(author.paper_count, Some(2));
+        assert_eq!(author.papers.len(), 2);
+
+        let paper = &author.papers[0];
+        assert_eq!(paper.title, "Graphs with many edges");
+        assert_eq!(paper.authors, "Jane Smith; Bob Jones");
+        assert_eq!(paper.doi.as_deref(), Some("10.1234/jml.2023"));
+        assert_eq!(paper.venue(), Some("J. Mach. Learn."));
+        assert_eq!(author.papers[1].venue(), Some("ICML"));
+
+        let limited = DblpSource::parse_person("12/345", xml, 1).unwrap();
+        assert_eq!(limited.papers.len(), 1);
+    }
 }
@@ -101,8 +101,8 @@ impl EuropePmcSource {
         let url = result
             .full_text_url
             .as_ref()
-            .and_then(|urls| urls.first())
-            .cloned()
+            .and_then(|list| list.full_text_url.first())
+            .and_then(|link| link.url.clone())
             .unwrap_or_else(|| {
                 if let Some(pmid) = &result.pubmed_id {
                     format!("https://europepmc.org/article/med/{}", pmid)
@@ -121,47 +121,53 @@ impl EuropePmcSource {
         let published_date = result
             .published_date
             .as_ref()
+            .or(result.pub_year.as_ref())
             .cloned()
-            .or_else(|| {
-                result.journal_info.as_ref().and_then(|ji| {
-                    ji.journal_volume.as_ref().map(|_| {
-                        // Return a placeholder if we only have volume info
-                        "".to_string()
-                    })
-                })
-            })
             .unwrap_or_default();
 
-        PaperBuilder::new(id, title, url, SourceType::EuropePMC)
+        // Open-access full text, as a PDF link
+        let pdf_url = result
+            .full_text_url
+            .iter()
+            .flat_map(|list| &list.full_text_url)
+            .find(|link| {
+                link.document_style.as_deref() == Some("pdf")
+                    && link.availability_code.as_deref() == Some("OA")
+            })
+            .and_then(|link| link.url.clone());
+
+        let mut builder = PaperBuilder::new(id, title, url, SourceType::EuropePMC)
             .authors(authors)
             .abstract_text(abstract_text)
             .doi(result.doi.clone().unwrap_or_default())
-            .published_date(published_date)
-            .build()
-    }
-}
-
-impl Default for EuropePmcSource {
-    fn default() -> Self {
-        Self::new().expect("Failed to create EuropePmcSource")
-    }
-}
-
-#[async_trait]
-impl Source for EuropePmcSource {
-    fn id(&self) -> &str {
-        "europe_pmc"
-    }
-
-    fn name(&self) -> &str {
-        "EuropePMC"
-    }
-
-    fn capabilities(&self) -> SourceCapabilities {
-        SourceCapabilities::SEARCH | SourceCapabilities::READ
+            .published_date(published_date);
+        if let Some(pdf_url) = pdf_url {
+            builder = builder.pdf_url(pdf_url);
+        }
+        if let Some(count) = result.cited_by_count {
+            builder = builder.citations(count);
+        }
+        if let Some(journal) = result
+            .journal_info
+            .as_ref()
+            .and_then(|info| info.journal.as_ref())
+            .and_then(|journal| journal.title.clone())
+        {
+            builder = builder.extra("venue", serde_json::Value::String(journal));
+        }
+        if let Some(keywords) = result
+            .keyword_list
+            .as_ref()
+            .map(|list| list.keyword.join(";"))
+            .filter(|k| !k.is_empty())
+        {
+            builder = builder.keywords(keywords);
+        }
+        builder.build()
     }
 
-    async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
+    /// Run a query and return the results as papers
+    async fn query(&self, query: &SearchQuery) -> Result<Vec<Paper>, SourceError> {
         let search_url = format!("{}?{}", EUROPE_PMC_SEARCH_URL, self.build_search_url(query));
 
         let client = Arc::clone(&self.client);
@@ -192,61 +198,91 @@ impl Source for EuropePmcSource {
 
         let search_result = Self::parse_search_response(&json)?;
 
-        let papers = search_result
+        Ok(search_result
             .result_list
             .result
             .iter()
             .map(Self::parse_result)
-            .collect();
+            .collect())
+    }
 
+    /// Run a query expected to match a single record
+    async fn find_one(&self, query: String) -> Result<Paper, SourceError> {
+        let not_found = query.clone();
+        self.query(&SearchQuery::new(query).max_results(1))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(SourceError::NotFound(not_found))
+    }
+}
+
+impl Default for EuropePmcSource {
+    fn default() -> Self {
+        Self::new().expect("Failed to create EuropePmcSource")
+    }
+}
+
+#[async_trait]
+impl Source for EuropePmcSource {
+    fn id(&self) -> &str {
+        "europe_pmc"
+    }
+
+    fn name(&self) -> &str {
+        "EuropePMC"
+    }
+
+    fn capabilities(&self) -> SourceCapabilities {
+        SourceCapabilities::SEARCH | SourceCapabilities::READ | SourceCapabilities::DOI_LOOKUP
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
+        let papers = self.query(query).await?;
         Ok(SearchResponse::new(papers, "EuropePMC", &query.query))
     }
+
+    async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
+        self.find_one(format!("DOI:\"{}\"", doi)).await
+    }
+
+    /// Get a paper by PubMed ID (`PMID:<id>` or bare digits) or PMC ID
+    async fn get_by_id(&self, id: &str) -> Result<Paper, SourceError> {
+        let id = id.trim();
+        let (scheme, value) = id.split_once(':').unwrap_or(("", id));
+        match scheme.to_ascii_uppercase().as_str() {
+            "" | "PMID" if value.chars().all(|c| c.is_ascii_digit()) => {
+                self.find_one(format!("EXT_ID:{} AND SRC:MED", value)).await
+            }
+            "" | "PMCID" if value.to_ascii_uppercase().starts_with("PMC") => {
+                self.find_one(format!("PMCID:{}", value)).await
+            }
+            _ => Err(SourceError::NotImplemented),
+        }
+    }
 }
 
 /// Search result wrapper
 #[derive(Debug, Deserialize)]
-#[allow(non_snake_case, dead_code)]
 struct SearchResult {
-    #[allow(dead_code)]
-    version: String,
-    #[allow(dead_code)]
-    hitCount: u32,
-    #[allow(dead_code)]
-    request: SearchRequest,
     #[serde(rename = "resultList")]
     result_list: ResultList,
 }
 
-/// Search request info
-#[derive(Debug, Deserialize)]
-#[allow(non_snake_case, dead_code)]
-struct SearchRequest {
-    #[allow(dead_code)]
-    query: String,
-    #[allow(dead_code)]
-    resultType: String,
-    #[allow(dead_code)]
-    format: String,
-    #[allow(dead_code)]
-    pageSize: u32,
-}
-
 /// List of results
 #[derive(Debug, Deserialize)]
-#[allow(non_snake_case)]
 struct ResultList {
+    #[serde(default)]
     result: Vec<SearchResultItem>,
 }
 
 /// Individual search result
 #[derive(Debug, Deserialize)]
-#[allow(non_snake_case, dead_code)]
+#[serde(rename_all = "camelCase")]
 struct SearchResultItem {
-    #[serde(default)]
+    #[serde(default, rename = "pmid")]
     pubmed_id: Option<String>,
     #[serde(default)]
-    pmc_id: Option<String>,
-    #[serde(default)]
     doi: Option<String>,
     #[serde(default)]
     title: Option<String>,
@@ -254,27 +290,63 @@ struct SearchResultItem {
     author_string: Option<String>,
     #[serde(default)]
     abstract_text: Option<String>,
-    #[serde(default)]
+    #[serde(default, rename = "firstPublicationDate")]
     published_date: Option<String>,
     #[serde(default)]
+    pub_year: Option<String>,
+    #[serde(default)]
     journal_info: Option<JournalInfo>,
     #[serde(default)]
+    keyword_list: Option<KeywordList>,
+    #[serde(default)]
+    cited_by_count: Option<u32>,
+    #[serde(default)]
     external_id: Option<String>,
     #[serde(default)]
     id: Option<String>,
-    #[serde(default)]
-    full_text_url: Option<Vec<String>>,
+    #[serde(default, rename = "fullTextUrlList")]
+    full_text_url: Option<FullTextUrlList>,
 }
 
 /// Journal information
 #[derive(Debug, Deserialize)]
-#[allow(non_snake_case, dead_code)]
 struct JournalInfo {
-    journal_volume: Option<String>,
-    #[allow(dead_code)]
-    journal_issue: Option<String>,
-    #[allow(dead_code)]
-    pub_date: Option<String>,
+    #[serde(default)]
+    journal: Option<Journal>,
+}
+
+/// Journal the article appeared in
+#[derive(Debug, Deserialize)]
+struct Journal {
+    #[serde(default)]
+    title: Option<String>,
+}
+
+/// Author keywords
+#[derive(Debug, Deserialize)]
+struct KeywordList {
+    #[serde(default)]
+    keyword: Vec<String>,
+}
+
+/// Links to the full text
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FullTextUrlList {
+    #[serde(default)]
+    full_text_url: Vec<FullTextUrl>,
+}
+
+/// One full-text link
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FullTextUrl {
+    #[serde(default)]
+    availability_code: Option<String>,
+    #[serde(default)]
+    document_style: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
 }
 
 #[cfg(test)]
@@ -320,4 +392,45 @@ mod tests {
 
         assert!(url.contains("author=Smith%20J"));
     }
+
+    #[test]
+    fn test_parse_core_result() {
+        let json = r#"{
+            "version": "6.9",
+            "hitCount": 1,
+            "request": {"queryString": "DOI:\"10.1038/s41586-020-2649-2\"", "resultType": "core"},
+            "resultList": {"result": [{
+                "id": "32939066",
+                "source": "MED",
+                "pmid": "32939066",
+                "doi": "10.1038/s41586-020-2649-2",
+                "title": "Array programming with NumPy.",
+                "authorString": "Harris CR, Millman KJ",
+                "pubYear": "2020",
+                "journalInfo": {"journal": {"title": "Nature"}},
+                "abstractText": "Array programming provides a powerful syntax.",
+                "keywordList": {"keyword": ["Python", "Software"]},
+                "citedByCount": 4000,
+                "firstPublicationDate": "2020-09-16",
+                "fullTextUrlList": {"fullTextUrl": [
+                    {"availabilityCode": "S", "documentStyle": "doi", "url": "https://doi.org/10.1038/s41586-020-2649-2"},
+                    {"availabilityCode": "OA", "documentStyle": "pdf", "url": "https://europepmc.org/articles/PMC7759461?pdf=render"}
+                ]}
+            }]}
+        }"#;
+        let result = EuropePmcSource::parse_search_response(json).unwrap();
+        let paper = EuropePmcSource::parse_result(&result.result_list.result[0]);
+
+        assert_eq!(paper.paper_id, "32939066");
+        assert_eq!(paper.authors, "Harris CR, Millman KJ");
+        assert_eq!(paper.published_date.as_deref(), Some("2020-09-16"));
+        assert_eq!(paper.venue(), Some("Nature"));
+        assert_eq!(paper.keyword_list(), vec!["Python", "Software"]);
+        assert_eq!(paper.citations, Some(4000));
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("https://europepmc.org/articles/PMC7759461?pdf=render")
+        );
+        assert_eq!(paper.url, "https://doi.org/10.1038/s41586-020-2649-2");
+    }
 }
@@ -284,6 +284,7 @@ impl Source for HalSource {
         let pdf_response = self
             .client
             .get(pdf_url)
+            .no_cache()
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Failed to download PDF: {}", e)))?;
@@ -0,0 +1,212 @@
+//! Wrapper counting a source's calls for monitoring.
+//!
+//! Every call is passed on unchanged; its duration and outcome are reported
+//! to [`Metrics`], together with the bytes of each download. Calls the source
+//! does not implement are not counted.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+
+use crate::models::{
+    Author, CitationRequest, DownloadRequest, DownloadResult, Paper, ReadRequest, ReadResult,
+    SearchQuery, SearchResponse,
+};
+use crate::sources::{Source, SourceCapabilities, SourceError};
+use crate::utils::Metrics;
+
+/// A source whose calls are counted in [`Metrics`]
+#[derive(Debug)]
+pub struct InstrumentedSource {
+    /// The wrapped source
+    inner: Arc<dyn Source>,
+
+    /// Where calls are counted
+    metrics: &'static Metrics,
+}
+
+impl InstrumentedSource {
+    /// Count the calls of `inner` in [`Metrics::global`]
+    pub fn new(inner: Arc<dyn Source>) -> Self {
+        Self::with_metrics(inner, Metrics::global())
+    }
+
+    /// Count the calls of `inner` in `metrics`
+    pub fn with_metrics(inner: Arc<dyn Source>, metrics: &'static Metrics) -> Self {
+        Self { inner, metrics }
+    }
+
+    /// The wrapped source
+    pub fn inner(&self) -> &Arc<dyn Source> {
+        &self.inner
+    }
+
+    /// Run a call of the wrapped source, counting its duration and outcome
+    async fn timed<T>(
+        &self,
+        operation: &str,
+        call: impl Future<Output = Result<T, SourceError>>,
+    ) -> Result<T, SourceError> {
+        let started = Instant::now();
+        let result = call.await;
+        if !matches!(result, Err(SourceError::NotImplemented)) {
+            self.metrics.record_source_call(
+                self.inner.id(),
+                operation,
+                started.elapsed(),
+                result.as_ref().err(),
+            );
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Source for InstrumentedSource {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> SourceCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
+        self.timed("search", self.inner.search(query)).await
+    }
+
+    async fn search_by_author(
+        &self,
+        author: &str,
+        max_results: usize,
+        year: Option<&str>,
+    ) -> Result<SearchResponse, SourceError> {
+        self.timed(
+            "search_by_author",
+            self.inner.search_by_author(author, max_results, year),
+        )
+        .await
+    }
+
+    async fn search_authors(
+        &self,
+        name: &str,
+        max_results: usize,
+    ) -> Result<Vec<Author>, SourceError> {
+        self.timed(
+            "search_authors",
+            self.inner.search_authors(name, max_results),
+        )
+        .await
+    }
+
+    async fn get_author(&self, author_id: &str, max_papers: usize) -> Result<Author, SourceError> {
+        self.timed("get_author", self.inner.get_author(author_id, max_papers))
+            .await
+    }
+
+    async fn get_author_by_orcid(
+        &self,
+        orcid: &str,
+        max_papers: usize,
+    ) -> Result<Author, SourceError> {
+        self.timed(
+            "get_author_by_orcid",
+            self.inner.get_author_by_orcid(orcid, max_papers),
+        )
+        .await
+    }
+
+    async fn get_author_papers(
+        &self,
+        author_id: &str,
+        max_results: usize,
+    ) -> Result<Vec<Paper>, SourceError> {
+        self.timed(
+            "get_author_papers",
+            self.inner.get_author_papers(author_id, max_results),
+        )
+        .await
+    }
+
+    async fn download(&self, request: &DownloadRequest) -> Result<DownloadResult, SourceError> {
+        let result = self.timed("download", self.inner.download(request)).await?;
+        if result.success {
+            self.metrics.record_download(self.inner.id(), result.bytes);
+        }
+        Ok(result)
+    }
+
+    async fn read(&self, request: &ReadRequest) -> Result<ReadResult, SourceError> {
+        self.timed("read", self.inner.read(request)).await
+    }
+
+    async fn get_citations(
+        &self,
+        request: &CitationRequest,
+    ) -> Result<SearchResponse, SourceError> {
+        self.timed("get_citations", self.inner.get_citations(request))
+            .await
+    }
+
+    async fn get_references(
+        &self,
+        request: &CitationRequest,
+    ) -> Result<SearchResponse, SourceError> {
+        self.timed("get_references", self.inner.get_references(request))
+            .await
+    }
+
+    async fn get_related(&self, request: &CitationRequest) -> Result<SearchResponse, SourceError> {
+        self.timed("get_related", self.inner.get_related(request))
+            .await
+    }
+
+    async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
+        self.timed("get_by_doi", self.inner.get_by_doi(doi)).await
+    }
+
+    async fn get_by_dois(&self, dois: &[String]) -> Result<Vec<Paper>, SourceError> {
+        self.timed("get_by_dois", self.inner.get_by_dois(dois))
+            .await
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Paper, SourceError> {
+        self.timed("get_by_id", self.inner.get_by_id(id)).await
+    }
+
+    fn validate_id(&self, id: &str) -> Result<(), SourceError> {
+        self.inner.validate_id(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::MockSource;
+
+    #[tokio::test]
+    async fn test_counts_calls() {
+        let metrics: &'static Metrics = Box::leak(Box::new(Metrics::new()));
+        let source = InstrumentedSource::with_metrics(Arc::new(MockSource::new()), metrics);
+
+        source.search(&SearchQuery::new("graphs")).await.unwrap();
+        assert!(matches!(
+            source.get_by_doi("10.1/x").await,
+            Err(SourceError::NotImplemented)
+        ));
+
+        let text = metrics.render();
+        assert!(text.contains(
+            r#"research_master_source_requests_total{source="mock",operation="search",result="ok"} 1"#
+        ));
+        assert!(!text.contains(r#"operation="get_by_doi""#));
+        assert_eq!(metrics.source_health()["mock"].requests, 1);
+    }
+}
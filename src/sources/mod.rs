@@ -35,6 +35,7 @@
 //! - `osf` - Enable OSF Preprints source (default: enabled)
 //! - `base` - Enable BASE source (default: enabled)
 //! - `springer` - Enable Springer source (default: enabled)
+//! - `orcid` - Enable ORCID source (default: enabled)
 //! - `google_scholar` - Enable Google Scholar source (default: disabled, requires GOOGLE_SCHOLAR_ENABLED=true)
 //!
 //! # Feature Groups
@@ -84,6 +85,19 @@
 //! export RESEARCH_MASTER_ENABLED_SOURCES="arxiv,semantic"
 //! ```
 //!
+//! # Offline Mode
+//!
+//! With `RESEARCH_MASTER_OFFLINE=true` (or `offline = true` in the config file)
+//! every registered source is wrapped in an [`OfflineSource`], which answers
+//! from the cache and the downloaded PDFs and fails with
+//! [`SourceError::Offline`] when an operation needs the network.
+//!
+//! # Monitoring
+//!
+//! [`SourceRegistry::into_instrumented`] wraps every source in an
+//! [`InstrumentedSource`], which reports the duration and outcome of each call
+//! to [`Metrics`](crate::utils::Metrics).
+//!
 //! # Compile-Time vs Runtime
 //!
 //! Use **compile-time features** to reduce binary size by excluding sources entirely.
@@ -134,6 +148,8 @@ mod jstor;
 mod mdpi;
 #[cfg(feature = "source-openalex")]
 mod openalex;
+#[cfg(feature = "source-orcid")]
+mod orcid;
 #[cfg(feature = "source-osf")]
 mod osf;
 #[cfg(feature = "source-pmc")]
@@ -156,15 +172,19 @@ mod worldwidescience;
 #[cfg(feature = "source-zenodo")]
 mod zenodo;
 
+mod instrumented;
 pub mod mock;
+mod offline;
 
+pub use instrumented::InstrumentedSource;
 pub use mock::MockSource;
+pub use offline::OfflineSource;
 
 pub use registry::{SourceCapabilities, SourceRegistry};
 
 use crate::models::{
-    CitationRequest, DownloadRequest, DownloadResult, Paper, ReadRequest, ReadResult, SearchQuery,
-    SearchResponse,
+    Author, CitationRequest, DownloadRequest, DownloadResult, Paper, ReadRequest, ReadResult,
+    SearchQuery, SearchResponse,
 };
 use async_trait::async_trait;
 
@@ -222,6 +242,12 @@ pub trait Source: Send + Sync + std::fmt::Debug {
             .contains(SourceCapabilities::AUTHOR_SEARCH)
     }
 
+    /// Whether this source provides author profiles
+    fn supports_author_profiles(&self) -> bool {
+        self.capabilities()
+            .contains(SourceCapabilities::AUTHOR_PROFILES)
+    }
+
     // ========== SEARCH METHODS ==========
 
     /// Search for papers matching the query
@@ -239,6 +265,49 @@ pub trait Source: Send + Sync + std::fmt::Debug {
         Err(SourceError::NotImplemented)
     }
 
+    // ========== AUTHOR METHODS ==========
+
+    /// Find author records matching a name (without publication lists)
+    async fn search_authors(
+        &self,
+        _name: &str,
+        _max_results: usize,
+    ) -> Result<Vec<Author>, SourceError> {
+        Err(SourceError::NotImplemented)
+    }
+
+    /// Get an author record by its source-specific ID, with up to `max_papers` publications
+    async fn get_author(
+        &self,
+        _author_id: &str,
+        _max_papers: usize,
+    ) -> Result<Author, SourceError> {
+        Err(SourceError::NotImplemented)
+    }
+
+    /// Get the author record linked to an ORCID iD, with up to `max_papers` publications
+    async fn get_author_by_orcid(
+        &self,
+        _orcid: &str,
+        _max_papers: usize,
+    ) -> Result<Author, SourceError> {
+        Err(SourceError::NotImplemented)
+    }
+
+    /// Get up to `max_results` of an author's most recent publications, newest first
+    ///
+    /// The default sorts the publications returned by [`Source::get_author`], which
+    /// may not be the most recent ones if the source ranks them differently.
+    async fn get_author_papers(
+        &self,
+        author_id: &str,
+        max_results: usize,
+    ) -> Result<Vec<Paper>, SourceError> {
+        let mut papers = self.get_author(author_id, max_results).await?.papers;
+        papers.sort_by(|a, b| b.published_date.cmp(&a.published_date));
+        Ok(papers)
+    }
+
     // ========== DOWNLOAD METHODS ==========
 
     /// Download a paper's PDF to the specified path
@@ -283,6 +352,22 @@ pub trait Source: Send + Sync + std::fmt::Debug {
         Err(SourceError::NotImplemented)
     }
 
+    /// Get several papers by DOI, leaving out DOIs the source does not know
+    ///
+    /// The default looks the DOIs up one at a time; sources with a batch
+    /// endpoint override it.
+    async fn get_by_dois(&self, dois: &[String]) -> Result<Vec<Paper>, SourceError> {
+        let mut papers = Vec::new();
+        for doi in dois {
+            match self.get_by_doi(doi).await {
+                Ok(paper) => papers.push(paper),
+                Err(SourceError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(papers)
+    }
+
     /// Get a paper by its ID (source-specific)
     async fn get_by_id(&self, _id: &str) -> Result<Paper, SourceError> {
         Err(SourceError::NotImplemented)
@@ -329,11 +414,45 @@ pub enum SourceError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// The operation needs the network, which offline mode does not use
+    #[error("Offline: {0}")]
+    Offline(String),
+
     /// Other error
     #[error("Error: {0}")]
     Other(String),
 }
 
+impl SourceError {
+    /// Name of the error variant, as used in metrics labels
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SourceError::NotImplemented => "not_implemented",
+            SourceError::Network(_) => "network",
+            SourceError::Parse(_) => "parse",
+            SourceError::InvalidRequest(_) => "invalid_request",
+            SourceError::RateLimit => "rate_limit",
+            SourceError::NotFound(_) => "not_found",
+            SourceError::Api(_) => "api",
+            SourceError::Io(_) => "io",
+            SourceError::Offline(_) => "offline",
+            SourceError::Other(_) => "other",
+        }
+    }
+
+    /// Whether the error means the source itself is failing, rather than
+    /// that the request could not be answered
+    pub fn is_source_failure(&self) -> bool {
+        matches!(
+            self,
+            SourceError::Network(_)
+                | SourceError::Parse(_)
+                | SourceError::RateLimit
+                | SourceError::Api(_)
+        )
+    }
+}
+
 impl From<reqwest::Error> for SourceError {
     fn from(err: reqwest::Error) -> Self {
         SourceError::Network(err.to_string())
@@ -364,4 +483,12 @@ mod tests {
         assert!(caps.contains(SourceCapabilities::DOWNLOAD));
         assert!(!caps.contains(SourceCapabilities::CITATIONS));
     }
+
+    #[test]
+    fn test_source_error_kind() {
+        assert_eq!(SourceError::RateLimit.kind(), "rate_limit");
+        assert_eq!(SourceError::NotFound("x".into()).kind(), "not_found");
+        assert!(SourceError::Network("reset".into()).is_source_failure());
+        assert!(!SourceError::NotFound("x".into()).is_source_failure());
+    }
 }
@@ -0,0 +1,487 @@
+//! Offline wrapper answering a source's calls without network access.
+//!
+//! Searches, citation and reference lookups are answered from the cache
+//! (expired entries included), paper lookups from every paper the cache has
+//! seen, and downloads and reads from PDFs already on disk. Everything else
+//! fails with [`SourceError::Offline`] instead of waiting for the network.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::config::{get_config, CacheConfig};
+use crate::graph::parse_year_range;
+use crate::models::{
+    Author, CitationRequest, DownloadRequest, DownloadResult, Paper, ReadRequest, ReadResult,
+    SearchQuery, SearchResponse,
+};
+use crate::sources::{Source, SourceCapabilities, SourceError};
+use crate::utils::{CacheService, EntryInfo};
+
+/// A source answered from the cache and downloaded PDFs only
+///
+/// Papers served from the cache are marked with when they were fetched
+/// (see [`Paper::cached_at`]).
+#[derive(Debug)]
+pub struct OfflineSource {
+    /// The wrapped source, used for its ID, name and capabilities
+    inner: Arc<dyn Source>,
+
+    /// Cache to answer from
+    cache: CacheService,
+
+    /// Directory downloads are saved to by default
+    download_dir: PathBuf,
+}
+
+impl OfflineSource {
+    /// Wrap a source, reading the cache and download directory from the
+    /// configuration
+    ///
+    /// The cache is read even when caching is disabled, so that entries from
+    /// earlier runs are still used.
+    pub fn new(inner: Arc<dyn Source>) -> Self {
+        let config = get_config();
+        let cache = CacheService::from_config(CacheConfig {
+            enabled: true,
+            ..config.cache
+        });
+        Self::with_cache(inner, cache, config.downloads.default_path)
+    }
+
+    /// Wrap a source, answering from the given cache and download directory
+    pub fn with_cache(
+        inner: Arc<dyn Source>,
+        cache: CacheService,
+        download_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            download_dir: download_dir.into(),
+        }
+    }
+
+    /// The wrapped source
+    pub fn inner(&self) -> &Arc<dyn Source> {
+        &self.inner
+    }
+
+    fn needs_network(&self, what: &str) -> SourceError {
+        SourceError::Offline(format!(
+            "{} from {} needs the network",
+            what,
+            self.inner.id()
+        ))
+    }
+
+    /// Papers of cached responses (of this source, or of all), newest first,
+    /// each once and marked with when it was fetched
+    fn library(&self, all_sources: bool) -> Vec<Paper> {
+        let source = (!all_sources).then(|| self.inner.id());
+        let mut seen = HashSet::new();
+        let mut papers = Vec::new();
+        for (info, response) in self.cache.cached_responses(source) {
+            for paper in marked(response.papers, &info) {
+                if seen.insert((paper.source.id().to_string(), paper.paper_id.clone())) {
+                    papers.push(paper);
+                }
+            }
+        }
+        papers
+    }
+
+    /// A cached paper with this ID or DOI, preferring this source's papers
+    fn find_paper(&self, matches: impl Fn(&Paper) -> bool) -> Option<Paper> {
+        self.library(false)
+            .into_iter()
+            .find(|paper| matches(paper))
+            .or_else(|| self.library(true).into_iter().find(|paper| matches(paper)))
+    }
+
+    /// A PDF of the paper already on disk, named the way downloads name it
+    fn local_pdf(&self, paper_id: &str, doi: Option<&str>, save_path: &str) -> Option<PathBuf> {
+        let mut names = vec![paper_id.to_string()];
+        if let Some((_, id)) = paper_id.rsplit_once(':') {
+            names.push(id.to_string());
+        }
+        names.extend(doi.map(str::to_string));
+
+        let save_path = Path::new(save_path);
+        let dirs = [
+            save_path.to_path_buf(),
+            save_path.join(self.inner.id()),
+            self.download_dir.clone(),
+            self.download_dir.join(self.inner.id()),
+        ];
+        dirs.iter()
+            .flat_map(|dir| {
+                names
+                    .iter()
+                    .map(move |name| dir.join(format!("{}.pdf", name.replace('/', "_"))))
+            })
+            .find(|path| path.is_file())
+    }
+}
+
+#[async_trait]
+impl Source for OfflineSource {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn capabilities(&self) -> SourceCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
+        if let Some((info, mut response)) = self.cache.peek_search(query, self.inner.id()) {
+            response.papers = marked(response.papers, &info);
+            return Ok(response);
+        }
+
+        let terms = search_terms(&query.query);
+        let years = match query.year {
+            Some(ref year) => parse_year_range(year).map_err(SourceError::InvalidRequest)?,
+            None => (None, None),
+        };
+        let mut papers: Vec<Paper> = self
+            .library(false)
+            .into_iter()
+            .filter(|paper| matches_terms(paper, &terms))
+            .filter(|paper| in_years(paper, years))
+            .filter(|paper| {
+                query
+                    .author
+                    .as_deref()
+                    .is_none_or(|author| contains(&paper.authors, author))
+            })
+            .filter(|paper| {
+                query.category.as_deref().is_none_or(|category| {
+                    contains(paper.categories.as_deref().unwrap_or_default(), category)
+                })
+            })
+            .collect();
+        // Papers with the terms in their title first, otherwise newest fetched first
+        papers.sort_by_key(|paper| {
+            std::cmp::Reverse(
+                terms
+                    .iter()
+                    .filter(|term| contains(&paper.title, term))
+                    .count(),
+            )
+        });
+
+        let total = papers.len();
+        papers.truncate(query.max_results);
+        Ok(SearchResponse::new(papers, self.inner.name(), &query.query)
+            .total_results(total)
+            .has_more(total > query.max_results))
+    }
+
+    async fn search_by_author(
+        &self,
+        author: &str,
+        max_results: usize,
+        year: Option<&str>,
+    ) -> Result<SearchResponse, SourceError> {
+        let mut query = SearchQuery::new("").max_results(max_results);
+        query.author = Some(author.to_string());
+        query.year = year.map(str::to_string);
+        self.search(&query).await
+    }
+
+    async fn search_authors(
+        &self,
+        _name: &str,
+        _max_results: usize,
+    ) -> Result<Vec<Author>, SourceError> {
+        Err(self.needs_network("Author search"))
+    }
+
+    async fn get_author(
+        &self,
+        _author_id: &str,
+        _max_papers: usize,
+    ) -> Result<Author, SourceError> {
+        Err(self.needs_network("Author lookup"))
+    }
+
+    async fn get_author_by_orcid(
+        &self,
+        _orcid: &str,
+        _max_papers: usize,
+    ) -> Result<Author, SourceError> {
+        Err(self.needs_network("Author lookup"))
+    }
+
+    async fn download(&self, request: &DownloadRequest) -> Result<DownloadResult, SourceError> {
+        let path = self
+            .local_pdf(
+                &request.paper_id,
+                request.doi.as_deref(),
+                &request.save_path,
+            )
+            .ok_or_else(|| {
+                self.needs_network(&format!("Downloading {} (not on disk)", request.paper_id))
+            })?;
+        let bytes = std::fs::metadata(&path)?.len();
+        Ok(DownloadResult::success(path.to_string_lossy(), bytes))
+    }
+
+    async fn read(&self, request: &ReadRequest) -> Result<ReadResult, SourceError> {
+        let path = self
+            .local_pdf(&request.paper_id, None, &request.save_path)
+            .ok_or_else(|| {
+                self.needs_network(&format!("Reading {} (no PDF on disk)", request.paper_id))
+            })?;
+        match crate::utils::extract_text(&path) {
+            Ok((text, _method)) => {
+                let pages = (text.len() / 3000).max(1);
+                Ok(ReadResult::success(text).pages(pages))
+            }
+            Err(e) => Ok(ReadResult::error(format!(
+                "Text extraction from {} failed: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    async fn get_citations(
+        &self,
+        request: &CitationRequest,
+    ) -> Result<SearchResponse, SourceError> {
+        let (info, mut response) = self
+            .cache
+            .peek_citations(&request.paper_id, self.inner.id())
+            .ok_or_else(|| {
+                self.needs_network(&format!("Citations of {} (not cached)", request.paper_id))
+            })?;
+        response.papers = marked(response.papers, &info);
+        response.papers.truncate(request.max_results);
+        Ok(response)
+    }
+
+    async fn get_references(
+        &self,
+        request: &CitationRequest,
+    ) -> Result<SearchResponse, SourceError> {
+        let (info, mut response) = self
+            .cache
+            .peek_references(&request.paper_id, self.inner.id())
+            .ok_or_else(|| {
+                self.needs_network(&format!("References of {} (not cached)", request.paper_id))
+            })?;
+        response.papers = marked(response.papers, &info);
+        response.papers.truncate(request.max_results);
+        Ok(response)
+    }
+
+    async fn get_related(&self, _request: &CitationRequest) -> Result<SearchResponse, SourceError> {
+        Err(self.needs_network("Related papers"))
+    }
+
+    async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
+        self.find_paper(|paper| {
+            paper
+                .doi
+                .as_deref()
+                .is_some_and(|d| d.eq_ignore_ascii_case(doi))
+        })
+        .ok_or_else(|| self.needs_network(&format!("Looking up DOI {} (not cached)", doi)))
+    }
+
+    async fn get_by_dois(&self, dois: &[String]) -> Result<Vec<Paper>, SourceError> {
+        let library = self.library(true);
+        Ok(dois
+            .iter()
+            .filter_map(|doi| {
+                library
+                    .iter()
+                    .find(|paper| {
+                        paper
+                            .doi
+                            .as_deref()
+                            .is_some_and(|d| d.eq_ignore_ascii_case(doi))
+                    })
+                    .cloned()
+            })
+            .collect())
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Paper, SourceError> {
+        let source = self.inner.id();
+        self.library(false)
+            .into_iter()
+            .find(|paper| paper.paper_id == id && paper.source.id() == source)
+            .ok_or_else(|| self.needs_network(&format!("Looking up {} (not cached)", id)))
+    }
+
+    fn validate_id(&self, id: &str) -> Result<(), SourceError> {
+        self.inner.validate_id(id)
+    }
+}
+
+/// Mark papers of a cached response with when the response was stored
+fn marked(mut papers: Vec<Paper>, info: &EntryInfo) -> Vec<Paper> {
+    if let Some(at) = DateTime::<Utc>::from_timestamp(info.created_at as i64, 0) {
+        for paper in &mut papers {
+            paper.mark_cached(at);
+        }
+    }
+    papers
+}
+
+/// Lowercased words of a query, without boolean operators and field prefixes
+fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| c.is_whitespace() || c == '"' || c == '(' || c == ')')
+        .map(|word| word.rsplit(':').next().unwrap_or(word).to_lowercase())
+        .filter(|word| !word.is_empty() && !matches!(word.as_str(), "and" | "or" | "not"))
+        .collect()
+}
+
+/// Whether every term occurs in the paper's title, abstract, authors or keywords
+fn matches_terms(paper: &Paper, terms: &[String]) -> bool {
+    let text = format!(
+        "{} {} {} {}",
+        paper.title,
+        paper.r#abstract,
+        paper.authors,
+        paper.keywords.as_deref().unwrap_or_default()
+    )
+    .to_lowercase();
+    terms.iter().all(|term| text.contains(term.as_str()))
+}
+
+fn in_years(paper: &Paper, (from, to): (Option<i32>, Option<i32>)) -> bool {
+    if from.is_none() && to.is_none() {
+        return true;
+    }
+    paper
+        .year()
+        .is_some_and(|year| from.is_none_or(|from| year >= from) && to.is_none_or(|to| year <= to))
+}
+
+fn contains(text: &str, needle: &str) -> bool {
+    text.to_lowercase().contains(&needle.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+    use crate::sources::MockSource;
+    use tempfile::TempDir;
+
+    fn offline(dir: &TempDir) -> (OfflineSource, CacheService) {
+        let cache = CacheService::from_config(CacheConfig {
+            enabled: true,
+            directory: Some(dir.path().join("cache")),
+            ..CacheConfig::default()
+        });
+        let source = OfflineSource::with_cache(
+            Arc::new(MockSource::new()),
+            cache.clone(),
+            dir.path().join("downloads"),
+        );
+        (source, cache)
+    }
+
+    fn paper(id: &str, title: &str, year: &str) -> Paper {
+        PaperBuilder::new(id, title, "", SourceType::Other("mock".to_string()))
+            .doi(format!("10.1000/{}", id))
+            .published_date(year)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_offline_search_from_cache() {
+        let dir = TempDir::new().unwrap();
+        let (source, cache) = offline(&dir);
+        let query = SearchQuery::new("graph neural networks").max_results(5);
+        let response = SearchResponse::new(
+            vec![
+                paper("1", "Graph neural networks", "2019"),
+                paper("2", "Message passing on graphs", "2021"),
+            ],
+            "Mock Source",
+            "graph neural networks",
+        );
+        cache.set_search("mock", &query, &response).await;
+
+        // The same query is answered as it was cached
+        let found = source.search(&query).await.unwrap();
+        assert_eq!(found.papers.len(), 2);
+        assert!(found.papers.iter().all(|p| p.cached_at().is_some()));
+
+        // Other queries are matched against the cached papers
+        let found = source
+            .search(&SearchQuery::new("graphs").max_results(10))
+            .await
+            .unwrap();
+        assert_eq!(found.papers.len(), 1);
+        assert_eq!(found.papers[0].paper_id, "2");
+        let found = source
+            .search(&SearchQuery::new("graph").year("2020-"))
+            .await
+            .unwrap();
+        assert_eq!(found.papers.len(), 1);
+
+        let paper = source.get_by_doi("10.1000/1").await.unwrap();
+        assert_eq!(paper.title, "Graph neural networks");
+        assert!(paper.cached_at().is_some());
+        assert!(matches!(
+            source.get_by_doi("10.1000/3").await,
+            Err(SourceError::Offline(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_offline_citations_and_downloads() {
+        let dir = TempDir::new().unwrap();
+        let (source, cache) = offline(&dir);
+        let response =
+            SearchResponse::new(vec![paper("2", "Citing paper", "2021")], "Mock Source", "1");
+        cache.set_citations("mock", "1", 20, &response).await;
+
+        let request = CitationRequest::new("1").max_results(5);
+        assert_eq!(
+            source.get_citations(&request).await.unwrap().papers.len(),
+            1
+        );
+        assert!(matches!(
+            source.get_references(&request).await,
+            Err(SourceError::Offline(_))
+        ));
+
+        let downloads = dir.path().join("downloads").join("mock");
+        std::fs::create_dir_all(&downloads).unwrap();
+        std::fs::write(downloads.join("2301.12345.pdf"), b"%PDF-1.4").unwrap();
+        let save_path = dir.path().join("elsewhere");
+        let save_path = save_path.to_string_lossy();
+        let result = source
+            .download(&DownloadRequest::new("arxiv:2301.12345", save_path.clone()))
+            .await
+            .unwrap();
+        assert_eq!(result.bytes, 8);
+        assert!(matches!(
+            source
+                .download(&DownloadRequest::new("2301.99999", save_path))
+                .await,
+            Err(SourceError::Offline(_))
+        ));
+        assert!(matches!(
+            source.search_authors("Doe", 5).await,
+            Err(SourceError::Offline(_))
+        ));
+    }
+}
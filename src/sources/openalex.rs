@@ -2,9 +2,13 @@
 
 use async_trait::async_trait;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::models::{Paper, PaperBuilder, SearchQuery, SearchResponse, SourceType};
+use crate::models::{
+    normalize_orcid, Author, OaStatus, OaVersion, OpenAccess, Paper, PaperBuilder, PaperStatus,
+    SearchQuery, SearchResponse, SourceType, StatusKind,
+};
 use crate::sources::{
     CitationRequest, DownloadRequest, DownloadResult, ReadRequest, ReadResult, Source,
     SourceCapabilities, SourceError,
@@ -13,6 +17,13 @@ use crate::utils::{api_retry_config, with_retry, HttpClient};
 
 const OPENALEX_API_BASE: &str = "https://api.openalex.org";
 
+/// Most DOIs per batch lookup (OpenAlex allows 50 values per filter)
+const DOI_BATCH_SIZE: usize = 50;
+
+/// Fields requested for author objects
+const AUTHOR_FIELDS: &str =
+    "id,display_name,orcid,works_count,cited_by_count,summary_stats,last_known_institutions,affiliations";
+
 /// OpenAlex research source
 ///
 /// Uses the OpenAlex REST API.
@@ -84,16 +95,173 @@ impl OpenAlexSource {
         let pdf_url = data
             .best_open_access_pdf
             .as_ref()
-            .and_then(|p| p.url.clone());
+            .and_then(|p| p.url.clone())
+            .or_else(|| data.best_oa_location.as_ref()?.pdf_url.clone());
+
+        let mut builder =
+            PaperBuilder::new(paper_id, data.title.clone(), url, SourceType::OpenAlex)
+                .authors(authors)
+                .abstract_text(
+                    data.r#abstract
+                        .clone()
+                        .or_else(|| data.abstract_inverted_index.as_ref().map(rebuild_abstract))
+                        .unwrap_or_default(),
+                )
+                .doi(doi)
+                .published_date(published_date.unwrap_or_default())
+                .pdf_url(pdf_url.unwrap_or_default())
+                .citations(data.cited_by_count.unwrap_or(0) as u32);
+
+        let keywords: Vec<&str> = data
+            .keywords
+            .iter()
+            .filter_map(|k| k.display_name.as_deref())
+            .collect();
+        if !keywords.is_empty() {
+            builder = builder.keywords(keywords.join(";"));
+        }
+        // The arXiv copy of a published work links it to its preprint
+        if let Some(arxiv_id) = data
+            .locations
+            .iter()
+            .filter_map(|l| l.landing_page_url.as_deref())
+            .find_map(|url| url.split_once("arxiv.org/abs/"))
+            .map(|(_, id)| id.trim_end_matches('/'))
+        {
+            builder = builder.extra("arxiv_id", serde_json::Value::String(arxiv_id.to_string()));
+        }
+
+        if let Some(venue) = data
+            .primary_location
+            .as_ref()
+            .and_then(|l| l.source.as_ref())
+            .and_then(|s| s.display_name.clone())
+        {
+            builder = builder.extra("venue", serde_json::Value::String(venue));
+        }
+        if data.is_retracted {
+            builder = builder.status(PaperStatus::new(StatusKind::Retracted, "openalex"));
+        }
+        if let Some(open_access) =
+            Self::parse_open_access(data.open_access.as_ref(), data.best_oa_location.as_ref())
+        {
+            builder = builder.open_access(open_access);
+        }
+
+        builder.build()
+    }
+
+    /// Open-access status from a work's `open_access` and `best_oa_location`
+    fn parse_open_access(
+        open_access: Option<&OAOpenAccess>,
+        best: Option<&OABestLocation>,
+    ) -> Option<OpenAccess> {
+        let open_access = open_access?;
+        let status = open_access
+            .oa_status
+            .as_deref()
+            .and_then(OaStatus::parse)
+            .or_else(|| (!open_access.is_oa).then_some(OaStatus::Closed))?;
+
+        let mut annotation = OpenAccess::new(status, "openalex");
+        let url = best
+            .and_then(|b| b.pdf_url.clone().or_else(|| b.landing_page_url.clone()))
+            .or_else(|| open_access.oa_url.clone());
+        if let Some(url) = url {
+            annotation = annotation.url(url);
+        }
+        if let Some(best) = best {
+            if let Some(ref license) = best.license {
+                annotation = annotation.license(license);
+            }
+            annotation = annotation.version(best.version.as_deref().and_then(OaVersion::parse));
+        }
+        Some(annotation)
+    }
+
+    /// Parse an OpenAlex author object
+    fn parse_author(data: &OAAuthorData) -> Option<Author> {
+        let url = data.id.clone()?;
+        let author_id = url.rsplit('/').next().unwrap_or(&url).to_string();
 
-        PaperBuilder::new(paper_id, data.title.clone(), url, SourceType::OpenAlex)
-            .authors(authors)
-            .abstract_text(data.r#abstract.clone().unwrap_or_default())
-            .doi(doi)
-            .published_date(published_date.unwrap_or_default())
-            .pdf_url(pdf_url.unwrap_or_default())
-            .citations(data.cited_by_count.unwrap_or(0) as u32)
-            .build()
+        let mut author = Author::new(
+            author_id,
+            data.display_name.clone().unwrap_or_default(),
+            SourceType::OpenAlex,
+        );
+        author.url = Some(url);
+        author.orcid = data.orcid.as_deref().and_then(normalize_orcid);
+        author.paper_count = data.works_count;
+        author.citation_count = data.cited_by_count;
+        author.h_index = data.summary_stats.as_ref().and_then(|s| s.h_index);
+
+        for institution in &data.last_known_institutions {
+            if let Some(name) = &institution.display_name {
+                author.add_affiliation(name);
+            }
+        }
+        for affiliation in &data.affiliations {
+            if let Some(name) = &affiliation.institution.display_name {
+                author.add_affiliation(name);
+            }
+        }
+        Some(author)
+    }
+
+    /// GET an endpoint and decode the JSON body, with retries
+    async fn fetch_json<T>(&self, endpoint: &str) -> Result<T, SourceError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let url = self.add_email_if_present(&self.build_url(endpoint));
+        let client = Arc::clone(&self.client);
+
+        with_retry(api_retry_config(), || {
+            let client = Arc::clone(&client);
+            let url = url.clone();
+            async move {
+                let response = client.get(&url).send().await.map_err(|e| {
+                    SourceError::Network(format!("Failed to query OpenAlex: {}", e))
+                })?;
+
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Err(SourceError::NotFound(url));
+                }
+                if !response.status().is_success() {
+                    return Err(SourceError::Api(format!(
+                        "OpenAlex API returned status: {}",
+                        response.status()
+                    )));
+                }
+
+                response
+                    .json()
+                    .await
+                    .map_err(|e| SourceError::Parse(format!("Failed to parse JSON: {}", e)))
+            }
+        })
+        .await
+    }
+
+    /// Fetch an author object and their most cited works
+    async fn fetch_author(&self, key: &str, max_papers: usize) -> Result<Author, SourceError> {
+        let data: OAAuthorData = self
+            .fetch_json(&format!("/authors/{}?select={}", key, AUTHOR_FIELDS))
+            .await?;
+        let mut author = Self::parse_author(&data)
+            .ok_or_else(|| SourceError::NotFound(format!("OpenAlex author {}", key)))?;
+
+        if max_papers > 0 {
+            let works: WorksResponse = self
+                .fetch_json(&format!(
+                    "/works?filter=author.id:{}&sort=cited_by_count:desc&per-page={}",
+                    author.author_id,
+                    max_papers.min(200)
+                ))
+                .await?;
+            author.papers = works.results.iter().map(Self::parse_paper).collect();
+        }
+        Ok(author)
     }
 }
 
@@ -120,6 +288,7 @@ impl Source for OpenAlexSource {
             | SourceCapabilities::CITATIONS
             | SourceCapabilities::DOI_LOOKUP
             | SourceCapabilities::AUTHOR_SEARCH
+            | SourceCapabilities::AUTHOR_PROFILES
     }
 
     async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
@@ -263,6 +432,55 @@ impl Source for OpenAlexSource {
         Ok(SearchResponse::new(papers?, "OpenAlex", author))
     }
 
+    async fn search_authors(
+        &self,
+        name: &str,
+        max_results: usize,
+    ) -> Result<Vec<Author>, SourceError> {
+        let data: AuthorsResponse = self
+            .fetch_json(&format!(
+                "/authors?search={}&per-page={}&select={}",
+                urlencoding::encode(name),
+                max_results.clamp(1, 50),
+                AUTHOR_FIELDS
+            ))
+            .await?;
+
+        Ok(data.results.iter().filter_map(Self::parse_author).collect())
+    }
+
+    async fn get_author(&self, author_id: &str, max_papers: usize) -> Result<Author, SourceError> {
+        let key = author_id.rsplit('/').next().unwrap_or(author_id);
+        self.fetch_author(key, max_papers).await
+    }
+
+    async fn get_author_by_orcid(
+        &self,
+        orcid: &str,
+        max_papers: usize,
+    ) -> Result<Author, SourceError> {
+        let orcid = normalize_orcid(orcid)
+            .ok_or_else(|| SourceError::InvalidRequest(format!("Invalid ORCID iD: {}", orcid)))?;
+        self.fetch_author(&format!("orcid:{}", orcid), max_papers)
+            .await
+    }
+
+    async fn get_author_papers(
+        &self,
+        author_id: &str,
+        max_results: usize,
+    ) -> Result<Vec<Paper>, SourceError> {
+        let key = author_id.rsplit('/').next().unwrap_or(author_id);
+        let works: WorksResponse = self
+            .fetch_json(&format!(
+                "/works?filter=author.id:{}&sort=publication_date:desc&per-page={}",
+                key,
+                max_results.clamp(1, 200)
+            ))
+            .await?;
+        Ok(works.results.iter().map(Self::parse_paper).collect())
+    }
+
     async fn download(&self, request: &DownloadRequest) -> Result<DownloadResult, SourceError> {
         // Try to get the paper first to find PDF URL
         let url = format!("/works/{}", request.paper_id);
@@ -281,7 +499,7 @@ impl Source for OpenAlexSource {
             )));
         }
 
-        let data: WorkResponse = response
+        let data: OAPaper = response
             .json()
             .await
             .map_err(|e| SourceError::Parse(format!("Failed to parse JSON: {}", e)))?;
@@ -294,6 +512,7 @@ impl Source for OpenAlexSource {
         let pdf_response = self
             .client
             .get(&pdf_url)
+            .no_cache()
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Failed to download PDF: {}", e)))?;
@@ -378,7 +597,7 @@ impl Source for OpenAlexSource {
         request: &CitationRequest,
     ) -> Result<SearchResponse, SourceError> {
         let url = format!(
-            "/works?filter=referenceds:{}&per-page={}",
+            "/works?filter=cited_by:{}&per-page={}",
             urlencoding::encode(&request.paper_id),
             request.max_results
         );
@@ -446,43 +665,31 @@ impl Source for OpenAlexSource {
             return Err(SourceError::NotFound("DOI not found".to_string()));
         }
 
-        let data: WorkResponse = response
+        let data: OAPaper = response
             .json()
             .await
             .map_err(|e| SourceError::Parse(format!("Failed to parse JSON: {}", e)))?;
 
-        // Parse WorkResponse (same structure as OAPaper)
-        let authors = data
-            .authorships
-            .iter()
-            .filter_map(|a| a.author.display_name.as_ref())
-            .map(|s| s.as_str())
-            .collect::<Vec<_>>()
-            .join("; ");
-
-        let published_date = data.publication_year.as_ref().map(|y| y.to_string());
-        let doi_value = data.doi.clone().unwrap_or_default();
-        let url = data.id.clone().unwrap_or_default();
-        let paper_id = data
-            .id
-            .clone()
-            .unwrap_or_else(|| format!("OpenAlex:{}", doi_value));
-        let pdf_url = data
-            .best_open_access_pdf
-            .as_ref()
-            .and_then(|p| p.url.clone())
-            .unwrap_or_default();
+        Ok(Self::parse_paper(&data))
+    }
 
-        Ok(
-            PaperBuilder::new(paper_id, data.title.clone(), url, SourceType::OpenAlex)
-                .authors(authors)
-                .abstract_text(data.r#abstract.clone().unwrap_or_default())
-                .doi(doi_value)
-                .published_date(published_date.unwrap_or_default())
-                .pdf_url(pdf_url)
-                .citations(data.cited_by_count.unwrap_or(0) as u32)
-                .build(),
-        )
+    async fn get_by_dois(&self, dois: &[String]) -> Result<Vec<Paper>, SourceError> {
+        let mut papers = Vec::new();
+        for chunk in dois.chunks(DOI_BATCH_SIZE) {
+            let filter = chunk
+                .iter()
+                .map(|doi| urlencoding::encode(doi).into_owned())
+                .collect::<Vec<_>>()
+                .join("|");
+            let works: WorksResponse = self
+                .fetch_json(&format!(
+                    "/works?filter=doi:{}&per-page={}",
+                    filter, DOI_BATCH_SIZE
+                ))
+                .await?;
+            papers.extend(works.results.iter().map(Self::parse_paper));
+        }
+        Ok(papers)
     }
 }
 
@@ -498,7 +705,45 @@ struct OAPaper {
     doi: Option<String>,
     r#abstract: Option<String>,
     best_open_access_pdf: Option<OAPdf>,
+    #[serde(default)]
+    open_access: Option<OAOpenAccess>,
+    #[serde(default)]
+    best_oa_location: Option<OABestLocation>,
     authorships: Vec<OAAuthorship>,
+    #[serde(default)]
+    primary_location: Option<OALocation>,
+    #[serde(default)]
+    is_retracted: bool,
+    #[serde(default)]
+    abstract_inverted_index: Option<HashMap<String, Vec<usize>>>,
+    #[serde(default)]
+    keywords: Vec<OANamed>,
+    #[serde(default)]
+    locations: Vec<OABestLocation>,
+}
+
+/// Put an abstract back together from OpenAlex's word -> positions index
+fn rebuild_abstract(index: &HashMap<String, Vec<usize>>) -> String {
+    let mut words: Vec<(usize, &str)> = index
+        .iter()
+        .flat_map(|(word, positions)| positions.iter().map(move |&p| (p, word.as_str())))
+        .collect();
+    words.sort_unstable();
+    words
+        .into_iter()
+        .map(|(_, word)| word)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Deserialize)]
+struct OALocation {
+    source: Option<OANamed>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OANamed {
+    display_name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -506,6 +751,22 @@ struct OAPdf {
     url: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct OAOpenAccess {
+    #[serde(default)]
+    is_oa: bool,
+    oa_status: Option<String>,
+    oa_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OABestLocation {
+    landing_page_url: Option<String>,
+    pdf_url: Option<String>,
+    license: Option<String>,
+    version: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct OAAuthorship {
     author: OAAuthor,
@@ -528,19 +789,6 @@ struct Meta {
     count: usize,
 }
 
-#[derive(Debug, Deserialize)]
-struct WorkResponse {
-    id: Option<String>,
-    title: String,
-    publication_year: Option<i32>,
-    #[serde(rename = "cited_by_count")]
-    cited_by_count: Option<i32>,
-    doi: Option<String>,
-    r#abstract: Option<String>,
-    best_open_access_pdf: Option<OAPdf>,
-    authorships: Vec<OAAuthorship>,
-}
-
 #[derive(Debug, Deserialize)]
 struct AuthorsResponse {
     results: Vec<OAAuthorData>,
@@ -549,6 +797,30 @@ struct AuthorsResponse {
 #[derive(Debug, Deserialize)]
 struct OAAuthorData {
     id: Option<String>,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    orcid: Option<String>,
+    #[serde(default)]
+    works_count: Option<u32>,
+    #[serde(default)]
+    cited_by_count: Option<u32>,
+    #[serde(default)]
+    summary_stats: Option<OASummaryStats>,
+    #[serde(default)]
+    last_known_institutions: Vec<OANamed>,
+    #[serde(default)]
+    affiliations: Vec<OAAffiliation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OASummaryStats {
+    h_index: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OAAffiliation {
+    institution: OANamed,
 }
 
 #[cfg(test)]
@@ -641,8 +913,7 @@ mod tests {
         }
         "#;
 
-        let parse_result: Result<WorkResponse, serde_json::Error> =
-            serde_json::from_str(mock_response);
+        let parse_result: Result<OAPaper, serde_json::Error> = serde_json::from_str(mock_response);
         assert!(parse_result.is_ok(), "Should parse valid JSON");
 
         let paper = parse_result.unwrap();
@@ -652,6 +923,31 @@ mod tests {
         assert_eq!(paper.authorships.len(), 1);
     }
 
+    #[test]
+    fn test_parse_inverted_abstract_and_keywords() {
+        let data: OAPaper = serde_json::from_str(
+            r#"{
+                "id": "https://openalex.org/W1",
+                "title": "Transformers",
+                "authorships": [],
+                "abstract_inverted_index": {"is": [1, 4], "Attention": [0], "all": [2], "you": [3], "need": [5]},
+                "keywords": [{"display_name": "Attention"}, {"display_name": "Machine translation"}],
+                "locations": [
+                    {"landing_page_url": "https://doi.org/10.5555/attention", "pdf_url": null},
+                    {"landing_page_url": "http://arxiv.org/abs/1706.03762", "pdf_url": null}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let paper = OpenAlexSource::parse_paper(&data);
+        assert_eq!(paper.r#abstract, "Attention is all you is need");
+        assert_eq!(
+            paper.keyword_list(),
+            vec!["Attention", "Machine translation"]
+        );
+        assert_eq!(paper.extra.unwrap()["arxiv_id"], "1706.03762");
+    }
+
     #[test]
     fn test_parse_citations_response() {
         // Mock citations response (uses same structure as search)
@@ -709,4 +1005,75 @@ mod tests {
         assert!(authors.results[0].id.is_some());
         assert!(authors.results[1].id.is_some());
     }
+
+    #[test]
+    fn test_parse_author_profile() {
+        let mock_response = r#"
+        {
+            "id": "https://openalex.org/A5023888391",
+            "display_name": "Jane Smith",
+            "orcid": "https://orcid.org/0000-0002-1825-0097",
+            "works_count": 42,
+            "cited_by_count": 1234,
+            "summary_stats": {"h_index": 17},
+            "last_known_institutions": [{"display_name": "MIT"}],
+            "affiliations": [
+                {"institution": {"display_name": "MIT"}},
+                {"institution": {"display_name": "Stanford University"}}
+            ]
+        }
+        "#;
+
+        let data: OAAuthorData = serde_json::from_str(mock_response).unwrap();
+        let author = OpenAlexSource::parse_author(&data).unwrap();
+        assert_eq!(author.author_id, "A5023888391");
+        assert_eq!(author.orcid.as_deref(), Some("0000-0002-1825-0097"));
+        assert_eq!(author.paper_count, Some(42));
+        assert_eq!(author.h_index, Some(17));
+        assert_eq!(author.affiliations, vec!["MIT", "Stanford University"]);
+    }
+
+    #[test]
+    fn test_parse_open_access() {
+        let mock_response = r#"
+        {
+            "id": "https://openalex.org/W2",
+            "title": "An open paper",
+            "doi": "https://doi.org/10.1/open",
+            "authorships": [],
+            "open_access": {"is_oa": true, "oa_status": "hybrid", "oa_url": "https://example.org/landing"},
+            "best_oa_location": {
+                "landing_page_url": "https://example.org/landing",
+                "pdf_url": "https://example.org/open.pdf",
+                "license": "cc-by-nc",
+                "version": "publishedVersion"
+            }
+        }
+        "#;
+        let data: OAPaper = serde_json::from_str(mock_response).unwrap();
+        let paper = OpenAlexSource::parse_paper(&data);
+        let open_access = paper.open_access.unwrap();
+        assert_eq!(open_access.status, OaStatus::Hybrid);
+        assert_eq!(
+            open_access.url.as_deref(),
+            Some("https://example.org/open.pdf")
+        );
+        assert_eq!(open_access.license.as_deref(), Some("cc-by-nc"));
+        assert_eq!(open_access.version, Some(OaVersion::Published));
+        assert_eq!(
+            paper.pdf_url.as_deref(),
+            Some("https://example.org/open.pdf")
+        );
+
+        let closed: OAPaper = serde_json::from_str(
+            r#"{"title": "Closed", "authorships": [], "open_access": {"is_oa": false, "oa_status": null}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            OpenAlexSource::parse_paper(&closed)
+                .open_access
+                .map(|o| o.status),
+            Some(OaStatus::Closed)
+        );
+    }
 }
@@ -0,0 +1,472 @@
+//! ORCID research source implementation.
+//!
+//! Uses the ORCID public API for researcher records.
+//! API documentation: <https://info.orcid.org/documentation/api-tutorials/>
+//!
+//! ORCID only provides author profiles; it has no paper search.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::models::{normalize_orcid, Author, Paper, PaperBuilder, SourceType};
+use crate::sources::{Source, SourceCapabilities, SourceError};
+use crate::utils::{api_retry_config, with_retry, HttpClient};
+
+const ORCID_API_BASE: &str = "https://pub.orcid.org/v3.0";
+const ORCID_BASE_URL: &str = "https://orcid.org";
+
+/// ORCID research source
+///
+/// Uses the ORCID public API (no credentials required).
+#[derive(Debug, Clone)]
+pub struct OrcidSource {
+    client: Arc<HttpClient>,
+}
+
+impl OrcidSource {
+    pub fn new() -> Result<Self, SourceError> {
+        Ok(Self {
+            client: Arc::new(HttpClient::new()?),
+        })
+    }
+
+    /// GET an endpoint as JSON, with retries
+    async fn fetch_json<T>(&self, endpoint: &str) -> Result<T, SourceError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", ORCID_API_BASE, endpoint);
+        let client = Arc::clone(&self.client);
+
+        with_retry(api_retry_config(), || {
+            let client = Arc::clone(&client);
+            let url = url.clone();
+            async move {
+                let response = client
+                    .get(&url)
+                    .header("Accept", "application/json")
+                    .send()
+                    .await
+                    .map_err(|e| SourceError::Network(format!("Failed to query ORCID: {}", e)))?;
+
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Err(SourceError::NotFound(url));
+                }
+                if !response.status().is_success() {
+                    return Err(SourceError::Api(format!(
+                        "ORCID API returned status: {}",
+                        response.status()
+                    )));
+                }
+
+                response
+                    .json()
+                    .await
+                    .map_err(|e| SourceError::Parse(format!("Failed to parse JSON: {}", e)))
+            }
+        })
+        .await
+    }
+
+    /// Convert an expanded-search result into an [`Author`]
+    fn parse_search_result(result: &ExpandedResult) -> Option<Author> {
+        let orcid = normalize_orcid(result.orcid_id.as_deref()?)?;
+        let name = result
+            .credit_name
+            .clone()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| {
+                join_name(
+                    result.given_names.as_deref(),
+                    result.family_names.as_deref(),
+                )
+            });
+
+        let mut author = Author::new(orcid.clone(), name, SourceType::Orcid);
+        author.url = Some(format!("{}/{}", ORCID_BASE_URL, orcid));
+        author.orcid = Some(orcid);
+        for institution in &result.institution_name {
+            author.add_affiliation(institution);
+        }
+        Some(author)
+    }
+
+    /// Convert a full ORCID record into an [`Author`]
+    fn parse_record(orcid: &str, record: &OrcidRecord, max_papers: usize) -> Author {
+        let name = record
+            .person
+            .as_ref()
+            .and_then(|p| p.name.as_ref())
+            .map(|n| {
+                n.credit_name
+                    .as_ref()
+                    .map(|c| c.value.clone())
+                    .filter(|c| !c.trim().is_empty())
+                    .unwrap_or_else(|| {
+                        join_name(
+                            n.given_names.as_ref().map(|v| v.value.as_str()),
+                            n.family_name.as_ref().map(|v| v.value.as_str()),
+                        )
+                    })
+            })
+            .unwrap_or_default();
+
+        let mut author = Author::new(orcid, name, SourceType::Orcid);
+        author.url = Some(format!("{}/{}", ORCID_BASE_URL, orcid));
+        author.orcid = Some(orcid.to_string());
+
+        let Some(activities) = record.activities_summary.as_ref() else {
+            return author;
+        };
+
+        for group in activities
+            .employments
+            .iter()
+            .flat_map(|e| &e.affiliation_group)
+        {
+            for summary in &group.summaries {
+                if let Some(org) = &summary.employment_summary.organization {
+                    author.add_affiliation(&org.name);
+                }
+            }
+        }
+
+        if let Some(works) = &activities.works {
+            author.paper_count = Some(works.group.len() as u32);
+            author.papers = works
+                .group
+                .iter()
+                .filter_map(|g| g.work_summary.first())
+                .take(max_papers)
+                .map(|work| Self::parse_work(orcid, work))
+                .collect();
+        }
+        author
+    }
+
+    /// Convert a work summary into a [`Paper`]
+    fn parse_work(orcid: &str, work: &WorkSummary) -> Paper {
+        let title = work
+            .title
+            .as_ref()
+            .and_then(|t| t.title.as_ref())
+            .map(|t| t.value.clone())
+            .unwrap_or_default();
+        let doi = work
+            .external_ids
+            .as_ref()
+            .into_iter()
+            .flat_map(|ids| &ids.external_id)
+            .find(|id| id.external_id_type.eq_ignore_ascii_case("doi"))
+            .map(|id| id.external_id_value.clone());
+        let url = work
+            .url
+            .as_ref()
+            .map(|u| u.value.clone())
+            .or_else(|| doi.as_ref().map(|d| format!("https://doi.org/{}", d)))
+            .unwrap_or_else(|| format!("{}/{}", ORCID_BASE_URL, orcid));
+
+        let mut builder = PaperBuilder::new(
+            format!("{}/work/{}", orcid, work.put_code),
+            title,
+            url,
+            SourceType::Orcid,
+        );
+        if let Some(doi) = doi {
+            builder = builder.doi(doi);
+        }
+        if let Some(year) = work.publication_date.as_ref().and_then(|d| d.year.as_ref()) {
+            builder = builder.published_date(year.value.clone());
+        }
+        if let Some(journal) = &work.journal_title {
+            builder = builder.extra("venue", serde_json::Value::String(journal.value.clone()));
+        }
+        builder.build()
+    }
+}
+
+impl Default for OrcidSource {
+    fn default() -> Self {
+        Self::new().expect("Failed to create OrcidSource")
+    }
+}
+
+#[async_trait]
+impl Source for OrcidSource {
+    fn id(&self) -> &str {
+        "orcid"
+    }
+
+    fn name(&self) -> &str {
+        "ORCID"
+    }
+
+    fn capabilities(&self) -> SourceCapabilities {
+        SourceCapabilities::AUTHOR_PROFILES
+    }
+
+    async fn search_authors(
+        &self,
+        name: &str,
+        max_results: usize,
+    ) -> Result<Vec<Author>, SourceError> {
+        let query = format!("given-and-family-names:\"{}\"", name.replace('"', ""));
+        let data: ExpandedSearchResponse = self
+            .fetch_json(&format!(
+                "/expanded-search/?q={}&rows={}",
+                urlencoding::encode(&query),
+                max_results.clamp(1, 200)
+            ))
+            .await?;
+
+        Ok(data
+            .expanded_result
+            .iter()
+            .filter_map(Self::parse_search_result)
+            .collect())
+    }
+
+    async fn get_author(&self, author_id: &str, max_papers: usize) -> Result<Author, SourceError> {
+        let orcid = normalize_orcid(author_id).ok_or_else(|| {
+            SourceError::InvalidRequest(format!("Invalid ORCID iD: {}", author_id))
+        })?;
+        let record: OrcidRecord = self.fetch_json(&format!("/{}/record", orcid)).await?;
+        Ok(Self::parse_record(&orcid, &record, max_papers))
+    }
+
+    async fn get_author_by_orcid(
+        &self,
+        orcid: &str,
+        max_papers: usize,
+    ) -> Result<Author, SourceError> {
+        self.get_author(orcid, max_papers).await
+    }
+
+    fn validate_id(&self, id: &str) -> Result<(), SourceError> {
+        normalize_orcid(id)
+            .map(|_| ())
+            .ok_or_else(|| SourceError::InvalidRequest(format!("Invalid ORCID iD: {}", id)))
+    }
+}
+
+/// Join given and family names, skipping missing parts
+fn join_name(given: Option<&str>, family: Option<&str>) -> String {
+    [given, family]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// ===== ORCID API Types =====
+
+#[derive(Debug, Deserialize)]
+struct ExpandedSearchResponse {
+    #[serde(rename = "expanded-result", default)]
+    expanded_result: Vec<ExpandedResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ExpandedResult {
+    orcid_id: Option<String>,
+    given_names: Option<String>,
+    family_names: Option<String>,
+    credit_name: Option<String>,
+    #[serde(default)]
+    institution_name: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Value<T> {
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct OrcidRecord {
+    person: Option<Person>,
+    activities_summary: Option<ActivitiesSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Person {
+    name: Option<PersonName>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PersonName {
+    given_names: Option<Value<String>>,
+    family_name: Option<Value<String>>,
+    credit_name: Option<Value<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActivitiesSummary {
+    employments: Option<Employments>,
+    works: Option<Works>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Employments {
+    #[serde(default)]
+    affiliation_group: Vec<AffiliationGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AffiliationGroup {
+    #[serde(default)]
+    summaries: Vec<EmploymentSummaryWrapper>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct EmploymentSummaryWrapper {
+    employment_summary: EmploymentSummary,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmploymentSummary {
+    organization: Option<Organization>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Organization {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Works {
+    #[serde(default)]
+    group: Vec<WorkGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct WorkGroup {
+    #[serde(default)]
+    work_summary: Vec<WorkSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct WorkSummary {
+    put_code: u64,
+    title: Option<WorkTitle>,
+    external_ids: Option<ExternalIds>,
+    url: Option<Value<String>>,
+    publication_date: Option<PublicationDate>,
+    journal_title: Option<Value<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkTitle {
+    title: Option<Value<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ExternalIds {
+    #[serde(default)]
+    external_id: Vec<ExternalId>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ExternalId {
+    external_id_type: String,
+    external_id_value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublicationDate {
+    year: Option<Value<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orcid_capabilities() {
+        let source = OrcidSource::new().unwrap();
+        assert_eq!(source.id(), "orcid");
+        assert!(source.supports_author_profiles());
+        assert!(!source.supports_search());
+        assert!(source.validate_id("0000-0002-1825-0097").is_ok());
+        assert!(source.validate_id("not-an-orcid").is_err());
+    }
+
+    #[test]
+    fn test_parse_search_results() {
+        let mock_response = r#"
+        {
+            "expanded-result": [
+                {
+                    "orcid-id": "0000-0002-1825-0097",
+                    "given-names": "Jane",
+                    "family-names": "Smith",
+                    "credit-name": null,
+                    "institution-name": ["MIT", "Stanford University"]
+                }
+            ],
+            "num-found": 1
+        }
+        "#;
+
+        let data: ExpandedSearchResponse = serde_json::from_str(mock_response).unwrap();
+        let author = OrcidSource::parse_search_result(&data.expanded_result[0]).unwrap();
+        assert_eq!(author.name, "Jane Smith");
+        assert_eq!(author.key(), "orcid:0000-0002-1825-0097");
+        assert_eq!(author.affiliations.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let mock_response = r#"
+        {
+            "person": {"name": {
+                "given-names": {"value": "Jane"},
+                "family-name": {"value": "Smith"},
+                "credit-name": null
+            }},
+            "activities-summary": {
+                "employments": {"affiliation-group": [
+                    {"summaries": [{"employment-summary": {"organization": {"name": "MIT"}}}]}
+                ]},
+                "works": {"group": [
+                    {"work-summary": [{
+                        "put-code": 42,
+                        "title": {"title": {"value": "Graphs"}},
+                        "external-ids": {"external-id": [
+                            {"external-id-type": "doi", "external-id-value": "10.1234/graphs"}
+                        ]},
+                        "url": null,
+                        "publication-date": {"year": {"value": "2021"}},
+                        "journal-title": {"value": "J. Graphs"}
+                    }]},
+                    {"work-summary": [{"put-code": 43, "title": {"title": {"value": "Trees"}}}]}
+                ]}
+            }
+        }
+        "#;
+
+        let record: OrcidRecord = serde_json::from_str(mock_response).unwrap();
+        let author = OrcidSource::parse_record("0000-0002-1825-0097", &record, 10);
+        assert_eq!(author.name, "Jane Smith");
+        assert_eq!(author.affiliations, vec!["MIT"]);
+        assert_eq!(author.paper_count, Some(2));
+
+        let paper = &author.papers[0];
+        assert_eq!(paper.paper_id, "0000-0002-1825-0097/work/42");
+        assert_eq!(paper.doi.as_deref(), Some("10.1234/graphs"));
+        assert_eq!(paper.url, "https://doi.org/10.1234/graphs");
+        assert_eq!(paper.year(), Some(2021));
+        assert_eq!(paper.venue(), Some("J. Graphs"));
+    }
+}
@@ -196,6 +196,7 @@ impl Source for OsfSource {
 
         let response = client
             .get(&pdf_url)
+            .no_cache()
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Failed to download from OSF: {}", e)))?;
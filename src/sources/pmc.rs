@@ -363,6 +363,7 @@ impl Source for PmcSource {
         let response = self
             .client
             .get(&pdf_url)
+            .no_cache()
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Failed to download PDF: {}", e)))?;
@@ -5,7 +5,9 @@ use quick_xml::de::from_str;
 use serde::Deserialize;
 use std::sync::Arc;
 
-use crate::models::{Paper, PaperBuilder, SearchQuery, SearchResponse, SourceType};
+use crate::models::{
+    Paper, PaperBuilder, PaperStatus, SearchQuery, SearchResponse, SourceType, StatusKind,
+};
 use crate::sources::{Source, SourceCapabilities, SourceError};
 use crate::utils::{api_retry_config, with_retry, HttpClient};
 
@@ -132,6 +134,7 @@ impl PubMedSource {
         struct MedlineCitation {
             PMID: Option<Pmid>,
             Article: Option<Article>,
+            CommentsCorrectionsList: Option<CommentsCorrectionsList>,
         }
 
         #[derive(Debug, Deserialize)]
@@ -148,6 +151,36 @@ impl PubMedSource {
             ArticleTitle: Option<ArticleTitle>,
             Abstract: Option<Abstract>,
             AuthorList: Option<AuthorList>,
+            PublicationTypeList: Option<PublicationTypeList>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[allow(non_snake_case)]
+        struct PublicationTypeList {
+            #[serde(rename = "PublicationType", default)]
+            types: Vec<PublicationType>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct PublicationType {
+            #[serde(rename = "$text")]
+            name: String,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[allow(non_snake_case)]
+        struct CommentsCorrectionsList {
+            #[serde(rename = "CommentsCorrections", default)]
+            items: Vec<CommentsCorrections>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[allow(non_snake_case)]
+        struct CommentsCorrections {
+            #[serde(rename = "@RefType")]
+            ref_type: String,
+            RefSource: Option<String>,
+            PMID: Option<Pmid>,
         }
 
         #[derive(Debug, Deserialize)]
@@ -252,7 +285,7 @@ impl PubMedSource {
 
         #[derive(Debug, Deserialize)]
         struct ArticleId {
-            #[serde(rename = "IdType")]
+            #[serde(rename = "@IdType")]
             id_type: String,
             #[serde(rename = "$text")]
             value: String,
@@ -351,16 +384,52 @@ impl PubMedSource {
                 .and_then(|ail| ail.ids.iter().find(|id| id.id_type == "doi"))
                 .map(|id| id.value.clone());
 
+            // "Retracted Publication" is the publication type of retracted
+            // papers; the notices themselves are linked as comments
+            let retracted = article
+                .MedlineCitation
+                .as_ref()
+                .and_then(|m| m.Article.as_ref())
+                .and_then(|a| a.PublicationTypeList.as_ref())
+                .is_some_and(|list| {
+                    list.types
+                        .iter()
+                        .any(|t| t.name.trim() == "Retracted Publication")
+                });
+            let notices = article
+                .MedlineCitation
+                .as_ref()
+                .and_then(|m| m.CommentsCorrectionsList.as_ref())
+                .map(|list| list.items.as_slice())
+                .unwrap_or_default();
+
             let url = format!("https://pubmed.ncbi.nlm.nih.gov/{}/", pmid);
 
-            papers.push(
-                PaperBuilder::new(pmid, title, url, SourceType::PubMed)
-                    .authors(authors)
-                    .abstract_text(abstract_text)
-                    .doi(doi.unwrap_or_default())
-                    .published_date(published_date.unwrap_or_default())
-                    .build(),
-            );
+            let mut paper = PaperBuilder::new(pmid, title, url, SourceType::PubMed)
+                .authors(authors)
+                .abstract_text(abstract_text)
+                .doi(doi.unwrap_or_default())
+                .published_date(published_date.unwrap_or_default())
+                .build();
+            for notice in notices {
+                let kind = match notice.ref_type.as_str() {
+                    "RetractionIn" | "PartialRetractionIn" => StatusKind::Retracted,
+                    "ExpressionOfConcernIn" => StatusKind::ExpressionOfConcern,
+                    "ErratumIn" => StatusKind::Corrected,
+                    _ => continue,
+                };
+                let mut status = PaperStatus::new(kind, "pubmed");
+                if let Some(ref pmid) = notice.PMID {
+                    status = status.notice(format!("https://pubmed.ncbi.nlm.nih.gov/{}/", pmid.id));
+                } else if let Some(ref source) = notice.RefSource {
+                    status = status.notice(source.trim());
+                }
+                paper.annotate(status);
+            }
+            if retracted && !paper.is_retracted() {
+                paper.annotate(PaperStatus::new(StatusKind::Retracted, "pubmed"));
+            }
+            papers.push(paper);
         }
 
         Ok(papers)
@@ -384,7 +453,7 @@ impl Source for PubMedSource {
     }
 
     fn capabilities(&self) -> SourceCapabilities {
-        SourceCapabilities::SEARCH
+        SourceCapabilities::SEARCH | SourceCapabilities::DOI_LOOKUP
     }
 
     async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
@@ -500,6 +569,16 @@ impl Source for PubMedSource {
 
         Ok(SearchResponse::new(papers, "PubMed", &query.query))
     }
+
+    async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
+        let query = SearchQuery::new(format!("\"{}\"[doi]", doi)).max_results(1);
+        self.search(&query)
+            .await?
+            .papers
+            .into_iter()
+            .next()
+            .ok_or_else(|| SourceError::NotFound(format!("DOI not found in PubMed: {}", doi)))
+    }
 }
 
 #[cfg(test)]
@@ -581,4 +660,66 @@ mod tests {
         assert!(url.contains("2019-01-01"));
         assert!(url.contains("2021-12-31"));
     }
+
+    #[test]
+    fn test_parse_retracted_publication() {
+        let xml = r#"<?xml version="1.0"?>
+<PubmedArticleSet>
+  <PubmedArticle>
+    <MedlineCitation Status="MEDLINE" Owner="NLM">
+      <PMID Version="1">32450107</PMID>
+      <Article PubModel="Print-Electronic">
+        <Journal><JournalIssue><PubDate><Year>2020</Year></PubDate></JournalIssue></Journal>
+        <ArticleTitle>Hydroxychloroquine or chloroquine with or without a macrolide.</ArticleTitle>
+        <PublicationTypeList>
+          <PublicationType UI="D016428">Journal Article</PublicationType>
+          <PublicationType UI="D016441">Retracted Publication</PublicationType>
+        </PublicationTypeList>
+      </Article>
+      <CommentsCorrectionsList>
+        <CommentsCorrections RefType="ExpressionOfConcernIn">
+          <RefSource>Lancet. 2020 Jun 13;395(10240):e102</RefSource>
+          <PMID Version="1">32504543</PMID>
+        </CommentsCorrections>
+        <CommentsCorrections RefType="RetractionIn">
+          <RefSource>Lancet. 2020 Jun 13;395(10240):1820</RefSource>
+          <PMID Version="1">32511943</PMID>
+        </CommentsCorrections>
+        <CommentsCorrections RefType="CommentIn">
+          <RefSource>Lancet. 2020;395:1820</RefSource>
+        </CommentsCorrections>
+      </CommentsCorrectionsList>
+    </MedlineCitation>
+    <PubmedData>
+      <ArticleIdList>
+        <ArticleId IdType="doi">10.1016/S0140-6736(20)31180-6</ArticleId>
+      </ArticleIdList>
+    </PubmedData>
+  </PubmedArticle>
+  <PubmedArticle>
+    <MedlineCitation>
+      <PMID Version="1">1</PMID>
+      <Article>
+        <ArticleTitle>Fine paper</ArticleTitle>
+        <PublicationTypeList>
+          <PublicationType UI="D016428">Journal Article</PublicationType>
+        </PublicationTypeList>
+      </Article>
+    </MedlineCitation>
+  </PubmedArticle>
+</PubmedArticleSet>"#;
+        let papers = PubMedSource::parse_fetch_response(xml).unwrap();
+        assert_eq!(papers.len(), 2);
+        assert_eq!(
+            papers[0].doi.as_deref(),
+            Some("10.1016/S0140-6736(20)31180-6")
+        );
+        let status = papers[0].status.as_ref().unwrap();
+        assert_eq!(status.kind, StatusKind::Retracted);
+        assert_eq!(
+            status.notice.as_deref(),
+            Some("https://pubmed.ncbi.nlm.nih.gov/32511943/")
+        );
+        assert!(papers[1].status.is_none());
+    }
 }
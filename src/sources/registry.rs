@@ -3,7 +3,7 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use super::{Source, SourceError};
+use super::{InstrumentedSource, OfflineSource, Source, SourceError};
 use crate::config::SourceConfig;
 
 // Conditionally import source types based on feature flags
@@ -43,6 +43,8 @@ use super::jstor::JstorSource;
 use super::mdpi::MdpiSource;
 #[cfg(feature = "source-openalex")]
 use super::openalex::OpenAlexSource;
+#[cfg(feature = "source-orcid")]
+use super::orcid::OrcidSource;
 #[cfg(feature = "source-osf")]
 use super::osf::OsfSource;
 #[cfg(feature = "source-pmc")]
@@ -166,6 +168,7 @@ bitflags::bitflags! {
         const CITATIONS = 1 << 3;
         const DOI_LOOKUP = 1 << 4;
         const AUTHOR_SEARCH = 1 << 5;
+        const AUTHOR_PROFILES = 1 << 6;
     }
 }
 
@@ -184,6 +187,13 @@ impl SourceRegistry {
         Self::try_new().expect("Failed to initialize any sources")
     }
 
+    /// Create a registry without sources, to [`register`](Self::register) them one by one
+    pub fn empty() -> Self {
+        Self {
+            sources: HashMap::new(),
+        }
+    }
+
     /// Try to create a new registry with all available sources
     ///
     /// This will:
@@ -191,8 +201,8 @@ impl SourceRegistry {
     /// 2. Skip sources that fail to initialize (e.g., missing API keys)
     /// 3. Return an error only if no sources could be initialized
     pub fn try_new() -> Result<Self, SourceError> {
-        let source_config = crate::config::get_config().sources;
-        let filter = SourceFilter::from_config(&source_config);
+        let config = crate::config::get_config();
+        let filter = SourceFilter::from_config(&config.sources);
         let mut registry = Self {
             sources: HashMap::new(),
         };
@@ -297,6 +307,9 @@ impl SourceRegistry {
         #[cfg(feature = "source-springer")]
         try_register!(SpringerSource::new());
 
+        #[cfg(feature = "source-orcid")]
+        try_register!(OrcidSource::new());
+
         #[cfg(feature = "source-google_scholar")]
         try_register!(GoogleScholarSource::new());
 
@@ -308,9 +321,41 @@ impl SourceRegistry {
 
         tracing::info!("Initialized {} sources", registry.len());
 
+        if config.offline {
+            tracing::info!("Offline mode: answering from the cache and downloaded PDFs");
+            registry = registry.into_offline();
+        }
+
         Ok(registry)
     }
 
+    /// Wrap every source in an [`OfflineSource`], so that no call uses the network
+    pub fn into_offline(self) -> Self {
+        let sources = self
+            .sources
+            .into_iter()
+            .map(|(id, source)| {
+                let offline: Arc<dyn Source> = Arc::new(OfflineSource::new(source));
+                (id, offline)
+            })
+            .collect();
+        Self { sources }
+    }
+
+    /// Wrap every source in an [`InstrumentedSource`], so that its calls are
+    /// counted in [`Metrics`](crate::utils::Metrics)
+    pub fn into_instrumented(self) -> Self {
+        let sources = self
+            .sources
+            .into_iter()
+            .map(|(id, source)| {
+                let instrumented: Arc<dyn Source> = Arc::new(InstrumentedSource::new(source));
+                (id, instrumented)
+            })
+            .collect();
+        Self { sources }
+    }
+
     /// Register a new source
     pub fn register(&mut self, source: Arc<dyn Source>) {
         self.sources.insert(source.id().to_string(), source);
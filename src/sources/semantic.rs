@@ -4,17 +4,30 @@ use async_trait::async_trait;
 use serde::Deserialize;
 use std::sync::Arc;
 
-use crate::models::{Paper, PaperBuilder, SearchQuery, SearchResponse, SourceType};
+use crate::models::{
+    normalize_orcid, Author, Paper, PaperBuilder, SearchQuery, SearchResponse, SourceType,
+};
 use crate::sources::{
     CitationRequest, DownloadRequest, DownloadResult, ReadRequest, ReadResult, Source,
     SourceCapabilities, SourceError,
 };
 use crate::utils::{
-    api_retry_config, with_retry, CircuitBreaker, HttpClient, RateLimitedRequestBuilder,
+    api_retry_config, with_retry, CircuitBreaker, HttpClient, Metrics, RateLimitedRequestBuilder,
 };
 
 const SEMANTIC_API_BASE: &str = "https://api.semanticscholar.org/graph/v1";
 
+/// Fields requested for author objects
+const AUTHOR_FIELDS: &str = "name,url,affiliations,paperCount,citationCount,hIndex,externalIds";
+
+/// Fields requested for papers
+const PAPER_FIELDS: &str =
+    "paperId,title,abstract,year,venue,citationCount,authors,url,openAccessPdf,externalIds";
+
+/// Fields requested for an author's papers
+const AUTHOR_PAPER_FIELDS: &str =
+    "paperId,title,year,venue,citationCount,authors,url,openAccessPdf";
+
 /// Environment variable for Semantic Scholar rate limit (requests per second)
 const SEMANTIC_SCHOLAR_RATE_LIMIT_ENV: &str = "SEMANTIC_SCHOLAR_RATE_LIMIT";
 
@@ -56,6 +69,17 @@ impl SemanticScholarSource {
             .unwrap_or(DEFAULT_SEMANTIC_CIRCUIT_BREAKER_THRESHOLD)
     }
 
+    /// Circuit breaker for the API, reported in [`Metrics`]
+    fn circuit_breaker(threshold: usize) -> Arc<CircuitBreaker> {
+        let breaker = Arc::new(CircuitBreaker::new(
+            "semantic",
+            threshold,
+            std::time::Duration::from_secs(60),
+        ));
+        Metrics::global().watch_circuit_breaker(&breaker);
+        breaker
+    }
+
     /// Create a new Semantic Scholar source
     pub fn new() -> Result<Self, SourceError> {
         let rate_limit = Self::get_rate_limit();
@@ -65,11 +89,7 @@ impl SemanticScholarSource {
         Ok(Self {
             client: Arc::new(HttpClient::with_rate_limit(user_agent, rate_limit)?),
             api_key: std::env::var("SEMANTIC_SCHOLAR_API_KEY").ok(),
-            circuit_breaker: Arc::new(CircuitBreaker::new(
-                "semantic",
-                circuit_threshold,
-                std::time::Duration::from_secs(60),
-            )),
+            circuit_breaker: Self::circuit_breaker(circuit_threshold),
         })
     }
 
@@ -83,11 +103,7 @@ impl SemanticScholarSource {
         Ok(Self {
             client: Arc::new(HttpClient::with_rate_limit(user_agent, rate_limit)?),
             api_key: Some(api_key),
-            circuit_breaker: Arc::new(CircuitBreaker::new(
-                "semantic",
-                circuit_threshold,
-                std::time::Duration::from_secs(60),
-            )),
+            circuit_breaker: Self::circuit_breaker(circuit_threshold),
         })
     }
 
@@ -120,7 +136,11 @@ impl SemanticScholarSource {
 
         let published_date = data.year.as_ref().map(|y| y.to_string());
 
-        let doi = data.doi.clone().unwrap_or_default();
+        let doi = data
+            .doi
+            .clone()
+            .or_else(|| data.external_ids.as_ref()?.doi.clone())
+            .unwrap_or_default();
 
         let url = data.url.clone().unwrap_or_else(|| {
             if !doi.is_empty() {
@@ -142,7 +162,7 @@ impl SemanticScholarSource {
             .and_then(|p| p.url.clone())
             .unwrap_or_default();
 
-        PaperBuilder::new(
+        let mut builder = PaperBuilder::new(
             paper_id,
             data.title.clone(),
             url,
@@ -153,8 +173,93 @@ impl SemanticScholarSource {
         .doi(doi)
         .published_date(published_date.unwrap_or_default())
         .pdf_url(pdf_url)
-        .citations(data.citation_count.unwrap_or(0) as u32)
-        .build()
+        .citations(data.citation_count.unwrap_or(0) as u32);
+
+        if let Some(venue) = data.venue.as_deref().filter(|v| !v.is_empty()) {
+            builder = builder.extra("venue", serde_json::Value::String(venue.to_string()));
+        }
+        if let Some(arxiv) = data.external_ids.as_ref().and_then(|ids| ids.arxiv.clone()) {
+            builder = builder.extra("arxiv_id", serde_json::Value::String(arxiv));
+        }
+
+        builder.build()
+    }
+
+    /// Parse Semantic Scholar author data
+    fn parse_author(data: &S2AuthorData) -> Option<Author> {
+        let author_id = data.author_id.clone()?;
+        let mut author = Author::new(
+            author_id.clone(),
+            data.name.clone().unwrap_or_default(),
+            SourceType::SemanticScholar,
+        );
+        author.url =
+            Some(data.url.clone().unwrap_or_else(|| {
+                format!("https://www.semanticscholar.org/author/{}", author_id)
+            }));
+        author.orcid = data
+            .external_ids
+            .as_ref()
+            .and_then(|ids| ids.get("ORCID"))
+            .and_then(|v| v.as_str())
+            .and_then(normalize_orcid);
+        author.paper_count = data.paper_count;
+        author.citation_count = data.citation_count;
+        author.h_index = data.h_index;
+        for affiliation in &data.affiliations {
+            author.add_affiliation(affiliation);
+        }
+        Some(author)
+    }
+
+    /// GET an endpoint and decode the JSON body, with retries and the circuit breaker
+    async fn fetch_json<T>(&self, endpoint: &str) -> Result<T, SourceError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        if !self.circuit_breaker.can_request() {
+            return Err(SourceError::Api(
+                "Semantic Scholar circuit is open".to_string(),
+            ));
+        }
+
+        let url = self.build_url(endpoint);
+        let circuit_breaker = Arc::clone(&self.circuit_breaker);
+
+        with_retry(api_retry_config(), || {
+            let request = self.add_api_key_if_present(self.client.get(&url));
+            let circuit_breaker = Arc::clone(&circuit_breaker);
+            async move {
+                let response = request.send().await.map_err(|e| {
+                    SourceError::Network(format!("Failed to query Semantic Scholar: {}", e))
+                })?;
+
+                let status = response.status();
+                if status == reqwest::StatusCode::NOT_FOUND {
+                    return Err(SourceError::NotFound(
+                        "Semantic Scholar record not found".to_string(),
+                    ));
+                }
+                if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    circuit_breaker.record_failure();
+                    return Err(SourceError::RateLimit);
+                }
+                if !status.is_success() {
+                    circuit_breaker.record_failure();
+                    return Err(SourceError::Api(format!(
+                        "Semantic Scholar API returned status: {}",
+                        status
+                    )));
+                }
+                circuit_breaker.record_success();
+
+                response
+                    .json()
+                    .await
+                    .map_err(|e| SourceError::Parse(format!("Failed to parse JSON: {}", e)))
+            }
+        })
+        .await
     }
 }
 
@@ -181,13 +286,15 @@ impl Source for SemanticScholarSource {
             | SourceCapabilities::CITATIONS
             | SourceCapabilities::DOI_LOOKUP
             | SourceCapabilities::AUTHOR_SEARCH
+            | SourceCapabilities::AUTHOR_PROFILES
     }
 
     async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, SourceError> {
         let url = format!(
-            "/paper/search?query={}&limit={}",
+            "/paper/search?query={}&limit={}&fields={}",
             urlencoding::encode(&query.query),
-            query.max_results
+            query.max_results,
+            PAPER_FIELDS
         );
 
         // Check circuit breaker before making request
@@ -266,16 +373,18 @@ impl Source for SemanticScholarSource {
                     .await
                     .unwrap_or_else(|_| "Failed to read response body".to_string());
 
-                serde_json::from_str::<S2SearchResponse>(&response_text)
-                    .map_err(|e| {
-                        // Log the first 500 chars of response for debugging
-                        let preview = response_text.chars().take(500).collect::<String>();
-                        tracing::warn!(
-                            "Semantic Scholar parse error (first 500 chars): {}",
-                            preview
-                        );
-                        SourceError::Parse(format!("Failed to parse JSON: {}. Response preview: {}", e, preview))
-                    })
+                serde_json::from_str::<S2SearchResponse>(&response_text).map_err(|e| {
+                    // Log the first 500 chars of response for debugging
+                    let preview = response_text.chars().take(500).collect::<String>();
+                    tracing::warn!(
+                        "Semantic Scholar parse error (first 500 chars): {}",
+                        preview
+                    );
+                    SourceError::Parse(format!(
+                        "Failed to parse JSON: {}. Response preview: {}",
+                        e, preview
+                    ))
+                })
             }
         })
         .await;
@@ -352,12 +461,14 @@ impl Source for SemanticScholarSource {
                     .await
                     .unwrap_or_else(|_| "Failed to read response body".to_string());
 
-                serde_json::from_str::<AuthorSearchResponse>(&response_text)
-                    .map_err(|e| {
-                        let preview = response_text.chars().take(500).collect::<String>();
-                        tracing::warn!("Semantic Scholar author search parse error: {}", preview);
-                        SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
-                    })
+                serde_json::from_str::<AuthorSearchResponse>(&response_text).map_err(|e| {
+                    let preview = response_text.chars().take(500).collect::<String>();
+                    tracing::warn!("Semantic Scholar author search parse error: {}", preview);
+                    SourceError::Parse(format!(
+                        "Failed to parse JSON: {}. Response: {}",
+                        e, preview
+                    ))
+                })
             }
         })
         .await?;
@@ -394,12 +505,14 @@ impl Source for SemanticScholarSource {
                     .await
                     .unwrap_or_else(|_| "Failed to read response body".to_string());
 
-                serde_json::from_str::<PapersResponse>(&response_text)
-                    .map_err(|e| {
-                        let preview = response_text.chars().take(500).collect::<String>();
-                        tracing::warn!("Semantic Scholar papers parse error: {}", preview);
-                        SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
-                    })
+                serde_json::from_str::<PapersResponse>(&response_text).map_err(|e| {
+                    let preview = response_text.chars().take(500).collect::<String>();
+                    tracing::warn!("Semantic Scholar papers parse error: {}", preview);
+                    SourceError::Parse(format!(
+                        "Failed to parse JSON: {}. Response: {}",
+                        e, preview
+                    ))
+                })
             }
         })
         .await?;
@@ -413,6 +526,46 @@ impl Source for SemanticScholarSource {
         Ok(SearchResponse::new(papers?, "Semantic Scholar", author))
     }
 
+    async fn search_authors(
+        &self,
+        name: &str,
+        max_results: usize,
+    ) -> Result<Vec<Author>, SourceError> {
+        let data: AuthorSearchResponse = self
+            .fetch_json(&format!(
+                "/author/search?query={}&limit={}&fields={}",
+                urlencoding::encode(name),
+                max_results.clamp(1, 100),
+                AUTHOR_FIELDS
+            ))
+            .await?;
+
+        Ok(data.data.iter().filter_map(Self::parse_author).collect())
+    }
+
+    async fn get_author(&self, author_id: &str, max_papers: usize) -> Result<Author, SourceError> {
+        let id = urlencoding::encode(author_id);
+        let data: S2AuthorData = self
+            .fetch_json(&format!("/author/{}?fields={}", id, AUTHOR_FIELDS))
+            .await?;
+        let mut author = Self::parse_author(&data).ok_or_else(|| {
+            SourceError::NotFound(format!("Semantic Scholar author {}", author_id))
+        })?;
+
+        if max_papers > 0 {
+            let papers: PapersResponse = self
+                .fetch_json(&format!(
+                    "/author/{}/papers?limit={}&fields={}",
+                    id,
+                    max_papers.min(1000),
+                    AUTHOR_PAPER_FIELDS
+                ))
+                .await?;
+            author.papers = papers.data.iter().map(Self::parse_paper).collect();
+        }
+        Ok(author)
+    }
+
     async fn download(&self, request: &DownloadRequest) -> Result<DownloadResult, SourceError> {
         // Try to get the paper first to find PDF URL
         let url = format!("/paper/{}", urlencoding::encode(&request.paper_id));
@@ -435,12 +588,14 @@ impl Source for SemanticScholarSource {
             .await
             .unwrap_or_else(|_| "Failed to read response body".to_string());
 
-        let data: PaperResponse = serde_json::from_str(&response_text)
-            .map_err(|e| {
-                let preview = response_text.chars().take(500).collect::<String>();
-                tracing::warn!("Semantic Scholar paper details parse error: {}", preview);
-                SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
-            })?;
+        let data: PaperResponse = serde_json::from_str(&response_text).map_err(|e| {
+            let preview = response_text.chars().take(500).collect::<String>();
+            tracing::warn!("Semantic Scholar paper details parse error: {}", preview);
+            SourceError::Parse(format!(
+                "Failed to parse JSON: {}. Response: {}",
+                e, preview
+            ))
+        })?;
 
         let pdf_url = data
             .data
@@ -451,6 +606,7 @@ impl Source for SemanticScholarSource {
         let pdf_response = self
             .client
             .get(&pdf_url)
+            .no_cache()
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Failed to download PDF: {}", e)))?;
@@ -520,12 +676,14 @@ impl Source for SemanticScholarSource {
             .await
             .unwrap_or_else(|_| "Failed to read response body".to_string());
 
-        let data: CitationsResponse = serde_json::from_str(&response_text)
-            .map_err(|e| {
-                let preview = response_text.chars().take(500).collect::<String>();
-                tracing::warn!("Semantic Scholar citations parse error: {}", preview);
-                SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
-            })?;
+        let data: CitationsResponse = serde_json::from_str(&response_text).map_err(|e| {
+            let preview = response_text.chars().take(500).collect::<String>();
+            tracing::warn!("Semantic Scholar citations parse error: {}", preview);
+            SourceError::Parse(format!(
+                "Failed to parse JSON: {}. Response: {}",
+                e, preview
+            ))
+        })?;
 
         let papers: Result<Vec<Paper>, SourceError> = data
             .data
@@ -561,12 +719,14 @@ impl Source for SemanticScholarSource {
             .await
             .unwrap_or_else(|_| "Failed to read response body".to_string());
 
-        let data: ReferencesResponse = serde_json::from_str(&response_text)
-            .map_err(|e| {
-                let preview = response_text.chars().take(500).collect::<String>();
-                tracing::warn!("Semantic Scholar references parse error: {}", preview);
-                SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
-            })?;
+        let data: ReferencesResponse = serde_json::from_str(&response_text).map_err(|e| {
+            let preview = response_text.chars().take(500).collect::<String>();
+            tracing::warn!("Semantic Scholar references parse error: {}", preview);
+            SourceError::Parse(format!(
+                "Failed to parse JSON: {}. Response: {}",
+                e, preview
+            ))
+        })?;
 
         let papers: Result<Vec<Paper>, SourceError> = data
             .data
@@ -599,12 +759,14 @@ impl Source for SemanticScholarSource {
             .await
             .unwrap_or_else(|_| "Failed to read response body".to_string());
 
-        let data: RelatedResponse = serde_json::from_str(&response_text)
-            .map_err(|e| {
-                let preview = response_text.chars().take(500).collect::<String>();
-                tracing::warn!("Semantic Scholar related parse error: {}", preview);
-                SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
-            })?;
+        let data: RelatedResponse = serde_json::from_str(&response_text).map_err(|e| {
+            let preview = response_text.chars().take(500).collect::<String>();
+            tracing::warn!("Semantic Scholar related parse error: {}", preview);
+            SourceError::Parse(format!(
+                "Failed to parse JSON: {}. Response: {}",
+                e, preview
+            ))
+        })?;
 
         let papers: Result<Vec<Paper>, SourceError> = data
             .data
@@ -620,31 +782,22 @@ impl Source for SemanticScholarSource {
     }
 
     async fn get_by_doi(&self, doi: &str) -> Result<Paper, SourceError> {
-        // Search by DOI using the search API
-        let url = format!("/paper/search?query={}&limit=1", urlencoding::encode(doi));
-
-        let response = self
-            .add_api_key_if_present(self.client.get(&self.build_url(&url)))
-            .send()
-            .await
-            .map_err(|e| SourceError::Network(format!("Failed to search DOI: {}", e)))?;
-
-        let response_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read response body".to_string());
-
-        let data: S2SearchResponse = serde_json::from_str(&response_text)
-            .map_err(|e| {
-                let preview = response_text.chars().take(500).collect::<String>();
-                tracing::warn!("Semantic Scholar DOI parse error: {}", preview);
-                SourceError::Parse(format!("Failed to parse JSON: {}. Response: {}", e, preview))
-            })?;
+        let paper: S2Paper = self
+            .fetch_json(&format!("/paper/DOI:{}?fields={}", doi, PAPER_FIELDS))
+            .await?;
+        Ok(Self::parse_paper(&paper))
+    }
 
-        data.data
-            .first()
-            .map(Self::parse_paper)
-            .ok_or_else(|| SourceError::NotFound("DOI not found".to_string()))
+    /// Get a paper by Semantic Scholar ID, or by `arXiv:<id>` or `PMID:<id>`
+    async fn get_by_id(&self, id: &str) -> Result<Paper, SourceError> {
+        let id = match id.split_once(':') {
+            Some((scheme, rest)) => format!("{}:{}", scheme.to_ascii_uppercase(), rest),
+            None => id.to_string(),
+        };
+        let paper: S2Paper = self
+            .fetch_json(&format!("/paper/{}?fields={}", id, PAPER_FIELDS))
+            .await?;
+        Ok(Self::parse_paper(&paper))
     }
 }
 
@@ -667,6 +820,18 @@ struct S2Paper {
     url: Option<String>,
     #[serde(rename = "openAccessPdf")]
     open_access_pdf: Option<S2OpenAccessPdf>,
+    #[serde(default)]
+    venue: Option<String>,
+    #[serde(rename = "externalIds", default)]
+    external_ids: Option<S2ExternalIds>,
+}
+
+#[derive(Debug, Deserialize)]
+struct S2ExternalIds {
+    #[serde(rename = "DOI")]
+    doi: Option<String>,
+    #[serde(rename = "ArXiv")]
+    arxiv: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -683,6 +848,20 @@ struct S2Author {
 struct S2AuthorData {
     #[serde(rename = "authorId")]
     author_id: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    affiliations: Vec<String>,
+    #[serde(rename = "paperCount", default)]
+    paper_count: Option<u32>,
+    #[serde(rename = "citationCount", default)]
+    citation_count: Option<u32>,
+    #[serde(rename = "hIndex", default)]
+    h_index: Option<u32>,
+    #[serde(rename = "externalIds", default)]
+    external_ids: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -802,7 +981,9 @@ mod tests {
             "year": 2024,
             "authors": [{"name": "Alice Johnson"}, {"name": "Bob Williams"}],
             "url": "https://www.semanticscholar.org/paper/abc123",
-            "citationCount": 100
+            "citationCount": 100,
+            "venue": "ACL",
+            "externalIds": {"DOI": "10.1000/nlp", "ArXiv": "2401.00001"}
         }
         "#;
 
@@ -814,6 +995,11 @@ mod tests {
         assert_eq!(paper.title, "Advances in Natural Language Processing");
         assert_eq!(paper.year, Some(2024));
         assert_eq!(paper.authors.len(), 2);
+
+        let paper = SemanticScholarSource::parse_paper(&paper);
+        assert_eq!(paper.doi.as_deref(), Some("10.1000/nlp"));
+        assert_eq!(paper.venue(), Some("ACL"));
+        assert_eq!(paper.extra.unwrap()["arxiv_id"], "2401.00001");
     }
 
     #[test]
@@ -849,4 +1035,29 @@ mod tests {
         assert_eq!(refs.data[0].paper_id, Some("ref1".to_string()));
         assert_eq!(refs.data[1].paper_id, Some("ref2".to_string()));
     }
+
+    #[test]
+    fn test_parse_author_profile() {
+        let mock_response = r#"
+        {
+            "authorId": "1741101",
+            "name": "Jane Smith",
+            "affiliations": ["MIT"],
+            "paperCount": 40,
+            "citationCount": 1200,
+            "hIndex": 16,
+            "externalIds": {"DBLP": ["Jane Smith 0001"], "ORCID": "0000-0002-1825-0097"}
+        }
+        "#;
+
+        let data: S2AuthorData = serde_json::from_str(mock_response).unwrap();
+        let author = SemanticScholarSource::parse_author(&data).unwrap();
+        assert_eq!(author.key(), "semantic:1741101");
+        assert_eq!(author.orcid.as_deref(), Some("0000-0002-1825-0097"));
+        assert_eq!(author.h_index, Some(16));
+        assert_eq!(
+            author.url.as_deref(),
+            Some("https://www.semanticscholar.org/author/1741101")
+        );
+    }
 }
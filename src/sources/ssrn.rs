@@ -285,6 +285,7 @@ impl Source for SsrnSource {
         let pdf_response = self
             .client
             .get(&download_url)
+            .no_cache()
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Failed to download PDF: {}", e)))?;
@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use serde::Deserialize;
 use std::sync::Arc;
 
-use crate::models::{Paper, PaperBuilder, SourceType};
+use crate::models::{OaStatus, OaVersion, OpenAccess, Paper, PaperBuilder, SourceType};
 use crate::sources::{Source, SourceCapabilities, SourceError};
 use crate::utils::{api_retry_config, with_retry, HttpClient};
 
@@ -123,7 +123,7 @@ impl UnpaywallSource {
         let authors: String = item
             .authors
             .iter()
-            .filter_map(|a| a.name.clone())
+            .filter_map(UnpaywallAuthor::name)
             .collect::<Vec<_>>()
             .join("; ");
 
@@ -132,7 +132,7 @@ impl UnpaywallSource {
             .as_ref()
             .and_then(|loc| loc.url_for_pdf.clone());
 
-        Ok(PaperBuilder::new(
+        let mut builder = PaperBuilder::new(
             doi.to_string(),
             title,
             url,
@@ -142,8 +142,32 @@ impl UnpaywallSource {
         .published_date(&year)
         .abstract_text(&abstract_text)
         .doi(doi)
-        .pdf_url(pdf_url.unwrap_or_default())
-        .build())
+        .pdf_url(pdf_url.unwrap_or_default());
+        if let Some(open_access) = Self::parse_open_access(item) {
+            builder = builder.open_access(open_access);
+        }
+        Ok(builder.build())
+    }
+
+    /// Open-access status from `oa_status` and the best OA location
+    fn parse_open_access(item: &UnpaywallResponse) -> Option<OpenAccess> {
+        let status = item
+            .oa_status
+            .as_deref()
+            .and_then(OaStatus::parse)
+            .or_else(|| item.is_oa.is_some_and(|oa| !oa).then_some(OaStatus::Closed))?;
+
+        let mut open_access = OpenAccess::new(status, "unpaywall");
+        if let Some(ref best) = item.best_oa_location {
+            if let Some(url) = best.url_for_pdf.clone().or_else(|| best.url.clone()) {
+                open_access = open_access.url(url);
+            }
+            if let Some(ref license) = best.license {
+                open_access = open_access.license(license);
+            }
+            open_access = open_access.version(best.version.as_deref().and_then(OaVersion::parse));
+        }
+        Some(open_access)
     }
 }
 
@@ -154,18 +178,39 @@ struct UnpaywallResponse {
     #[serde(rename = "abstract")]
     abstract_text: Option<String>,
     published_date: Option<String>,
+    #[serde(default, rename = "z_authors")]
     authors: Vec<UnpaywallAuthor>,
+    is_oa: Option<bool>,
+    oa_status: Option<String>,
     best_oa_location: Option<UnpaywallLocation>,
 }
 
 #[derive(Debug, Deserialize)]
 struct UnpaywallAuthor {
-    name: Option<String>,
+    given: Option<String>,
+    family: Option<String>,
+    raw_author_name: Option<String>,
+}
+
+impl UnpaywallAuthor {
+    fn name(&self) -> Option<String> {
+        let name = [self.given.as_deref(), self.family.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(name)
+            .filter(|n| !n.is_empty())
+            .or_else(|| self.raw_author_name.clone())
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct UnpaywallLocation {
+    url: Option<String>,
     url_for_pdf: Option<String>,
+    license: Option<String>,
+    version: Option<String>,
 }
 
 #[cfg(test)]
@@ -177,4 +222,36 @@ mod tests {
         let source = UnpaywallSource::new();
         assert!(source.is_ok());
     }
+
+    #[test]
+    fn test_parse_open_access() {
+        let response: UnpaywallResponse = serde_json::from_str(
+            r#"{
+                "doi": "10.1/green",
+                "title": "A green paper",
+                "is_oa": true,
+                "oa_status": "green",
+                "z_authors": [{"given": "Ada", "family": "Lovelace"}],
+                "best_oa_location": {
+                    "url": "https://repository.example.org/1",
+                    "url_for_pdf": null,
+                    "license": "cc-by",
+                    "version": "acceptedVersion"
+                }
+            }"#,
+        )
+        .unwrap();
+        let source = UnpaywallSource::new().unwrap();
+        let paper = source.parse_result(&response, "10.1/green").unwrap();
+        let open_access = paper.open_access.unwrap();
+        assert_eq!(open_access.status, OaStatus::Green);
+        assert_eq!(
+            open_access.url.as_deref(),
+            Some("https://repository.example.org/1")
+        );
+        assert!(open_access.has_license("cc-by"));
+        assert_eq!(open_access.version, Some(OaVersion::Accepted));
+        assert_eq!(open_access.source, "unpaywall");
+        assert_eq!(paper.authors, "Ada Lovelace");
+    }
 }
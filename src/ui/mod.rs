@@ -53,6 +53,7 @@ pub fn source_icon(source: &str) -> &'static str {
         "osf" => "☁️",
         "base" => "🔍",
         "springer" => "📚",
+        "orcid" => "🆔",
         "google scholar" => "🔎",
         _ => "📄",
     }
@@ -213,11 +214,7 @@ pub fn print_search_header(query: &str, count: usize, duration: Duration) {
 
 /// Print download progress.
 pub fn print_download_progress(paper_id: &str, source: &str, progress: u64, total: u64) {
-    let percentage = if total > 0 {
-        (progress * 100 / total) as f64
-    } else {
-        0.0
-    };
+    let percentage = (progress * 100).checked_div(total).unwrap_or(0) as f64;
 
     print!(
         "\r{} Downloading {} from {}: {:.1}%",
@@ -414,7 +411,7 @@ impl ScientificSpinner {
 
     /// Update to a sub-operation
     pub fn update(&self, current: usize, total: usize) {
-        let percent = if total > 0 { (current * 100 / total).min(100) } else { 0 };
+        let percent = (current * 100).checked_div(total).unwrap_or(0).min(100);
         let msg = format!("({}/{}) {}%", current, total, percent);
         self.pb.set_message(msg);
     }
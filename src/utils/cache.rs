@@ -1,28 +1,42 @@
 //! Local caching for search results and other API responses.
 //!
-//! This module provides a file-based cache for storing search results,
-//! citation lookups, and other API responses to reduce network calls.
+//! This module provides a cache for storing search results, citation
+//! lookups, and other API responses to reduce network calls. Everything is
+//! kept in a single [`CacheStore`] database, one namespace per kind of data.
 //!
 //! # Cache Structure
 //!
 //! ```text
 //! ~/.cache/research-master/
-//!   searches/
-//!     <hash>.json
-//!   citations/
-//!     <hash>.json
+//!   cache.db      namespaces: searches, citations, metadata, pdfs, text, http
 //! ```
 //!
-//! Each cached item is a JSON file containing the cached data plus metadata.
-
-use crate::config::{CacheConfig, Config};
-use crate::models::{SearchQuery, SearchResponse};
+//! Caches written by older versions (`searches/` and `citations/` holding one
+//! JSON file per entry) are moved into the database the first time it is
+//! opened.
+
+use super::cache_store::{
+    CacheEntry, CacheFilter, CacheNamespace, CacheStore, CacheStoreError, EntryInfo,
+    NamespaceStats, CACHE_DB_FILE,
+};
+use super::http_cache::{HttpCache, HttpCacheStats};
+use super::metrics::Metrics;
+use crate::config::{get_config, CacheConfig};
+use crate::models::{OpenAccess, SearchQuery, SearchResponse};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, SystemTime};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Directories of the file-per-entry cache of older versions
+const LEGACY_DIRS: [(&str, CacheNamespace); 2] = [
+    ("searches", CacheNamespace::Searches),
+    ("citations", CacheNamespace::Citations),
+];
 
-/// Cache metadata stored with each cached item
+/// Cache metadata stored with each item by older versions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CacheMetadata {
     /// When the item was cached (Unix timestamp)
@@ -38,14 +52,19 @@ struct CacheMetadata {
     query: String,
 }
 
-/// Wrapper for cached search response
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct CachedSearchResponse {
+/// A cached item written by older versions
+#[derive(Debug, Clone, Deserialize)]
+struct LegacyEntry {
     /// Cache metadata
     metadata: CacheMetadata,
 
-    /// The actual search response
-    response: SearchResponse,
+    /// A search, citation or reference response
+    #[serde(default)]
+    response: Option<serde_json::Value>,
+
+    /// An open-access answer
+    #[serde(default)]
+    open_access: Option<serde_json::Value>,
 }
 
 /// Result of a cache lookup
@@ -66,20 +85,26 @@ pub struct CacheService {
     /// Base cache directory
     base_dir: PathBuf,
 
-    /// Search cache directory
-    search_dir: PathBuf,
-
-    /// Citation cache directory
-    citation_dir: PathBuf,
+    /// The store, opened on first use and shared by clones
+    store: Arc<OnceLock<Option<Arc<CacheStore>>>>,
 
     /// Configuration
     config: CacheConfig,
+
+    /// Offline mode: answers come from the cache, so nothing is written back
+    /// (which would make old answers look fresh)
+    read_only: bool,
 }
 
 impl CacheService {
     /// Create a new cache service with default config
+    ///
+    /// In offline mode the cache is only read.
     pub fn new() -> Self {
-        Self::from_config(Config::default().cache)
+        let config = get_config();
+        let mut cache = Self::from_config(config.cache);
+        cache.read_only = config.offline;
+        cache
     }
 
     /// Create a new cache service with the given config
@@ -89,22 +114,18 @@ impl CacheService {
             .clone()
             .unwrap_or_else(crate::config::default_cache_dir);
 
-        let search_dir = base_dir.join("searches");
-        let citation_dir = base_dir.join("citations");
-
         Self {
             base_dir,
-            search_dir,
-            citation_dir,
+            store: Arc::new(OnceLock::new()),
             config,
+            read_only: false,
         }
     }
 
-    /// Initialize the cache directories
+    /// Initialize the cache database
     pub fn initialize(&self) -> std::io::Result<()> {
         if self.config.enabled {
-            fs::create_dir_all(&self.search_dir)?;
-            fs::create_dir_all(&self.citation_dir)?;
+            self.open_store()?;
             tracing::info!("Cache initialized at: {}", self.base_dir.display());
         } else {
             tracing::debug!("Cache is disabled");
@@ -122,6 +143,53 @@ impl CacheService {
         &self.base_dir
     }
 
+    /// The store, or `None` when caching is disabled or the database cannot
+    /// be opened
+    pub fn store(&self) -> Option<&Arc<CacheStore>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        self.store
+            .get_or_init(|| match self.open_store_inner() {
+                Ok(store) => Some(Arc::new(store)),
+                Err(e) => {
+                    tracing::warn!("Cache unavailable: {}", e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    /// Open the store, reporting why it cannot be opened
+    fn open_store(&self) -> Result<&Arc<CacheStore>, CacheStoreError> {
+        if let Some(store) = self.store() {
+            return Ok(store);
+        }
+        let error = if !self.is_enabled() {
+            std::io::Error::other("Caching is disabled (set RESEARCH_MASTER_CACHE_ENABLED=true)")
+        } else {
+            // Opening failed earlier; open again to report why
+            match self.open_store_inner() {
+                Err(e) => return Err(e),
+                Ok(_) => std::io::Error::other("Cache database could not be opened"),
+            }
+        };
+        Err(CacheStoreError::Io(error))
+    }
+
+    fn open_store_inner(&self) -> Result<CacheStore, CacheStoreError> {
+        let mut store =
+            CacheStore::open(self.base_dir.join(CACHE_DB_FILE), self.config.max_size_mb)?;
+        if !self.config.compress {
+            store = store.without_compression();
+        }
+        let migrated = migrate_legacy(&self.base_dir, &store);
+        if migrated > 0 {
+            tracing::info!("Moved {} cached items into {}", migrated, CACHE_DB_FILE);
+        }
+        Ok(store)
+    }
+
     /// Generate a cache key for a search query
     fn search_cache_key(
         &self,
@@ -146,28 +214,38 @@ impl CacheService {
         format!("{:x}", digest)
     }
 
-    /// Generate a cache key for a citation lookup
-    fn citation_cache_key(&self, paper_id: &str, source: &str, max_results: usize) -> String {
-        let input = format!("{}|{}|{}", paper_id, source, max_results);
+    /// Generate a cache key for a citation or reference lookup
+    fn citation_cache_key(
+        &self,
+        kind: &str,
+        paper_id: &str,
+        source: &str,
+        max_results: usize,
+    ) -> String {
+        let input = format!("{}|{}|{}|{}", kind, paper_id, source, max_results);
         let digest = md5::compute(input.as_bytes());
         format!("{:x}", digest)
     }
 
-    /// Check if a cache entry is expired
-    fn is_expired(&self, expires_at: u64) -> bool {
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        now >= expires_at
-    }
-
     /// Read a cached search response
-    pub fn get_search(&self, query: &SearchQuery, source: &str) -> CacheResult<SearchResponse> {
-        if !self.is_enabled() {
-            return CacheResult::Miss;
-        }
+    pub async fn get_search(
+        &self,
+        query: &SearchQuery,
+        source: &str,
+    ) -> CacheResult<SearchResponse> {
+        let key = self.search_cache_key(
+            &query.query,
+            source,
+            query.max_results,
+            query.year.as_deref(),
+            query.author.as_deref(),
+            query.category.as_deref(),
+        );
+        self.get_json(CacheNamespace::Searches, &key).await
+    }
 
+    /// Cache a search response
+    pub async fn set_search(&self, source: &str, query: &SearchQuery, response: &SearchResponse) {
         let key = self.search_cache_key(
             &query.query,
             source,
@@ -176,32 +254,122 @@ impl CacheService {
             query.author.as_deref(),
             query.category.as_deref(),
         );
+        self.set_json(
+            CacheNamespace::Searches,
+            &key,
+            source,
+            &query.query,
+            response,
+            Duration::from_secs(self.config.search_ttl_seconds),
+        )
+        .await;
+    }
 
-        let cache_path = self.search_dir.join(&key);
+    /// Read a cached citation lookup
+    pub async fn get_citations(
+        &self,
+        paper_id: &str,
+        source: &str,
+        max_results: usize,
+    ) -> CacheResult<SearchResponse> {
+        self.get_citation_entry("citations", paper_id, source, max_results)
+            .await
+    }
 
-        match self.read_cache_file::<CachedSearchResponse>(&cache_path) {
-            Ok(cached) => {
-                if self.is_expired(cached.metadata.expires_at) {
-                    tracing::debug!("Cache expired for search: {}", key);
-                    CacheResult::Expired
-                } else {
-                    tracing::debug!("Cache HIT for search: {}", key);
-                    CacheResult::Hit(cached.response)
-                }
-            }
-            Err(_) => {
-                tracing::debug!("Cache MISS for search: {}", key);
-                CacheResult::Miss
-            }
-        }
+    /// Cache a citation lookup response
+    pub async fn set_citations(
+        &self,
+        source: &str,
+        paper_id: &str,
+        max_results: usize,
+        response: &SearchResponse,
+    ) {
+        self.set_citation_entry("citations", source, paper_id, max_results, response)
+            .await
     }
 
-    /// Cache a search response
-    pub fn set_search(&self, source: &str, query: &SearchQuery, response: &SearchResponse) {
-        if !self.is_enabled() {
-            return;
-        }
+    /// Read a cached reference lookup
+    pub async fn get_references(
+        &self,
+        paper_id: &str,
+        source: &str,
+        max_results: usize,
+    ) -> CacheResult<SearchResponse> {
+        self.get_citation_entry("references", paper_id, source, max_results)
+            .await
+    }
+
+    /// Cache a reference lookup response
+    pub async fn set_references(
+        &self,
+        source: &str,
+        paper_id: &str,
+        max_results: usize,
+        response: &SearchResponse,
+    ) {
+        self.set_citation_entry("references", source, paper_id, max_results, response)
+            .await
+    }
+
+    /// Read a cached open-access answer for a DOI
+    ///
+    /// A hit holds `None` when no source knew the DOI.
+    pub async fn get_open_access(&self, doi: &str) -> CacheResult<Option<OpenAccess>> {
+        let key = self.citation_cache_key("open_access", doi, "", 0);
+        self.get_json(CacheNamespace::Citations, &key).await
+    }
+
+    /// Cache the open-access answer for a DOI (stored with the citation lookups)
+    pub async fn set_open_access(&self, doi: &str, open_access: Option<&OpenAccess>) {
+        let key = self.citation_cache_key("open_access", doi, "", 0);
+        self.set_json(
+            CacheNamespace::Citations,
+            &key,
+            &open_access.map(|o| o.source.clone()).unwrap_or_default(),
+            &format!("open access for {}", doi),
+            &open_access,
+            Duration::from_secs(self.config.citation_ttl_seconds),
+        )
+        .await;
+    }
+
+    async fn get_citation_entry(
+        &self,
+        kind: &str,
+        paper_id: &str,
+        source: &str,
+        max_results: usize,
+    ) -> CacheResult<SearchResponse> {
+        let key = self.citation_cache_key(kind, paper_id, source, max_results);
+        self.get_json(CacheNamespace::Citations, &key).await
+    }
 
+    async fn set_citation_entry(
+        &self,
+        kind: &str,
+        source: &str,
+        paper_id: &str,
+        max_results: usize,
+        response: &SearchResponse,
+    ) {
+        let key = self.citation_cache_key(kind, paper_id, source, max_results);
+        self.set_json(
+            CacheNamespace::Citations,
+            &key,
+            source,
+            &format!("{} for {}", kind, paper_id),
+            response,
+            Duration::from_secs(self.config.citation_ttl_seconds),
+        )
+        .await;
+    }
+
+    /// A cached search response, expired or not, with its entry
+    pub fn peek_search(
+        &self,
+        query: &SearchQuery,
+        source: &str,
+    ) -> Option<(EntryInfo, SearchResponse)> {
         let key = self.search_cache_key(
             &query.query,
             source,
@@ -210,110 +378,209 @@ impl CacheService {
             query.author.as_deref(),
             query.category.as_deref(),
         );
-        let cache_path = self.search_dir.join(&key);
-
-        let cached = CachedSearchResponse {
-            metadata: CacheMetadata {
-                cached_at: SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
-                expires_at: SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs()
-                    + self.config.search_ttl_seconds,
-                source: source.to_string(),
-                query: query.query.clone(),
-            },
-            response: response.clone(),
-        };
+        let entry = self.store()?.peek(CacheNamespace::Searches, &key).ok()??;
+        let response = serde_json::from_slice(&entry.value).ok()?;
+        Some((entry.info, response))
+    }
 
-        if let Err(e) = self.write_cache_file(&cache_path, &cached) {
-            tracing::warn!("Failed to cache search result: {}", e);
-        } else {
-            tracing::debug!("Cached search result: {}", key);
-        }
+    /// A cached citation lookup of any size, expired or not, with its entry
+    pub fn peek_citations(
+        &self,
+        paper_id: &str,
+        source: &str,
+    ) -> Option<(EntryInfo, SearchResponse)> {
+        self.peek_citation_entry("citations", paper_id, source)
     }
 
-    /// Read a cached citation lookup
-    pub fn get_citations(
+    /// A cached reference lookup of any size, expired or not, with its entry
+    pub fn peek_references(
         &self,
         paper_id: &str,
         source: &str,
-        max_results: usize,
-    ) -> CacheResult<SearchResponse> {
-        if !self.is_enabled() {
-            return CacheResult::Miss;
-        }
+    ) -> Option<(EntryInfo, SearchResponse)> {
+        self.peek_citation_entry("references", paper_id, source)
+    }
 
-        let key = self.citation_cache_key(paper_id, source, max_results);
-        let cache_path = self.citation_dir.join(&key);
-
-        match self.read_cache_file::<CachedSearchResponse>(&cache_path) {
-            Ok(cached) => {
-                if self.is_expired(cached.metadata.expires_at) {
-                    tracing::debug!("Cache expired for citations: {}", key);
-                    CacheResult::Expired
-                } else {
-                    tracing::debug!("Cache HIT for citations: {}", key);
-                    CacheResult::Hit(cached.response)
+    /// Every cached search, citation and reference response (of one source,
+    /// or of all), newest first, expired ones included
+    pub fn cached_responses(&self, source: Option<&str>) -> Vec<(EntryInfo, SearchResponse)> {
+        let mut responses = Vec::new();
+        let Some(store) = self.store() else {
+            return responses;
+        };
+        for namespace in [CacheNamespace::Searches, CacheNamespace::Citations] {
+            let mut filter = CacheFilter::new().namespace(namespace);
+            if let Some(source) = source {
+                filter = filter.source(source);
+            }
+            for info in store.entries(&filter).unwrap_or_default() {
+                let Ok(Some(entry)) = store.peek(namespace, &info.key) else {
+                    continue;
+                };
+                // Open-access answers share the namespace but are not responses
+                if let Ok(response) = serde_json::from_slice(&entry.value) {
+                    responses.push((entry.info, response));
                 }
             }
-            Err(_) => {
-                tracing::debug!("Cache MISS for citations: {}", key);
+        }
+        responses.sort_by_key(|(info, _)| std::cmp::Reverse(info.created_at));
+        responses
+    }
+
+    fn peek_citation_entry(
+        &self,
+        kind: &str,
+        paper_id: &str,
+        source: &str,
+    ) -> Option<(EntryInfo, SearchResponse)> {
+        // Keys include the result count, so look the lookup up by description
+        let store = self.store()?;
+        let description = format!("{} for {}", kind, paper_id);
+        let filter = CacheFilter::new()
+            .namespace(CacheNamespace::Citations)
+            .source(source);
+        let info = store
+            .entries(&filter)
+            .ok()?
+            .into_iter()
+            .find(|info| info.description == description)?;
+        let entry = store.peek(CacheNamespace::Citations, &info.key).ok()??;
+        let response = serde_json::from_slice(&entry.value).ok()?;
+        Some((entry.info, response))
+    }
+
+    /// Read a cached value of any namespace
+    pub async fn get_entry(&self, namespace: CacheNamespace, key: &str) -> CacheResult<CacheEntry> {
+        let owned_key = key.to_string();
+        let entry = self
+            .with_store(move |store| store.get(namespace, &owned_key))
+            .await;
+        let Some(entry) = entry else {
+            return CacheResult::Miss;
+        };
+        match entry {
+            Ok(Some(entry)) if entry.info.is_expired() => {
+                tracing::debug!("Cache expired for {}: {}", namespace, key);
+                Metrics::global().record_cache(namespace.as_str(), "expired");
+                CacheResult::Expired
+            }
+            Ok(Some(entry)) => {
+                tracing::debug!("Cache HIT for {}: {}", namespace, key);
+                Metrics::global().record_cache(namespace.as_str(), "hit");
+                CacheResult::Hit(entry)
+            }
+            Ok(None) => {
+                tracing::debug!("Cache MISS for {}: {}", namespace, key);
+                Metrics::global().record_cache(namespace.as_str(), "miss");
+                CacheResult::Miss
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read cache: {}", e);
                 CacheResult::Miss
             }
         }
     }
 
-    /// Cache a citation lookup response
-    pub fn set_citations(&self, source: &str, paper_id: &str, response: &SearchResponse) {
-        if !self.is_enabled() {
+    /// Cache a value in any namespace; `ttl` of `None` keeps it until it is
+    /// evicted or invalidated
+    pub async fn set_entry(
+        &self,
+        namespace: CacheNamespace,
+        key: &str,
+        value: &[u8],
+        source: &str,
+        description: &str,
+        ttl: Option<Duration>,
+    ) {
+        if self.read_only {
             return;
         }
+        let (owned_key, value) = (key.to_string(), value.to_vec());
+        let (source, description) = (source.to_string(), description.to_string());
+        let stored = self
+            .with_store(move |store| {
+                store.put(namespace, &owned_key, &value, &source, &description, ttl)
+            })
+            .await;
+        match stored {
+            Some(Ok(())) => tracing::debug!("Cached {}: {}", namespace, key),
+            Some(Err(e)) => tracing::warn!("Failed to cache {}: {}", namespace, e),
+            None => {}
+        }
+    }
 
-        let key = self.citation_cache_key(paper_id, source, response.papers.len());
-        let cache_path = self.citation_dir.join(&key);
-
-        let cached = CachedSearchResponse {
-            metadata: CacheMetadata {
-                cached_at: SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
-                expires_at: SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs()
-                    + self.config.citation_ttl_seconds,
-                source: source.to_string(),
-                query: format!("citations for {}", paper_id),
-            },
-            response: response.clone(),
-        };
+    /// Run `f` on the store on the blocking pool, keeping SQLite I/O off the
+    /// async workers; `None` when there is no store
+    async fn with_store<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&CacheStore) -> T + Send + 'static,
+    ) -> Option<T> {
+        let cache = self.clone();
+        tokio::task::spawn_blocking(move || cache.store().map(|store| f(store)))
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Cache task failed: {}", e);
+                None
+            })
+    }
 
-        if let Err(e) = self.write_cache_file(&cache_path, &cached) {
-            tracing::warn!("Failed to cache citations: {}", e);
-        } else {
-            tracing::debug!("Cached citations: {}", key);
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        namespace: CacheNamespace,
+        key: &str,
+    ) -> CacheResult<T> {
+        match self.get_entry(namespace, key).await {
+            CacheResult::Hit(entry) => match serde_json::from_slice(&entry.value) {
+                Ok(value) => CacheResult::Hit(value),
+                Err(_) => CacheResult::Miss,
+            },
+            CacheResult::Expired => CacheResult::Expired,
+            CacheResult::Miss => CacheResult::Miss,
         }
     }
 
-    /// Read a cached file and deserialize it
-    fn read_cache_file<T: for<'de> Deserialize<'de>>(
+    async fn set_json<T: Serialize>(
         &self,
-        path: &Path,
-    ) -> Result<T, std::io::Error> {
-        let content = fs::read_to_string(path)?;
-        serde_json::from_str(&content)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+        namespace: CacheNamespace,
+        key: &str,
+        source: &str,
+        description: &str,
+        value: &T,
+        ttl: Duration,
+    ) {
+        match serde_json::to_vec(value) {
+            Ok(json) => {
+                self.set_entry(namespace, key, &json, source, description, Some(ttl))
+                    .await
+            }
+            Err(e) => tracing::warn!("Failed to cache {}: {}", namespace, e),
+        }
+    }
+
+    /// List cached entries
+    pub fn inspect(&self, filter: &CacheFilter) -> std::io::Result<Vec<EntryInfo>> {
+        match self.store() {
+            Some(store) => Ok(store.entries(filter)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Remove the cached entries matching a filter; returns how many were removed
+    pub fn invalidate(&self, filter: &CacheFilter) -> std::io::Result<usize> {
+        match self.store() {
+            Some(store) => Ok(store.invalidate(filter)?),
+            None => Ok(0),
+        }
+    }
+
+    /// Write a copy of the cache to a new file; returns the number of entries
+    pub fn export(&self, path: &Path) -> std::io::Result<usize> {
+        Ok(self.open_store()?.export(path)?)
     }
 
-    /// Serialize and write a cached file
-    fn write_cache_file<T: Serialize>(&self, path: &Path, data: &T) -> Result<(), std::io::Error> {
-        let content = serde_json::to_string_pretty(data)?;
-        fs::write(path, content)
+    /// Add the entries of an exported cache; returns the number of entries
+    pub fn import(&self, path: &Path) -> std::io::Result<usize> {
+        Ok(self.open_store()?.import(path)?)
     }
 
     /// Clear all cached data
@@ -322,82 +589,99 @@ impl CacheService {
             return Ok(());
         }
 
-        let _ = fs::remove_dir_all(&self.base_dir);
-        self.initialize()?;
+        self.open_store()?.clear(None)?;
         tracing::info!("Cache cleared");
         Ok(())
     }
 
     /// Clear only search cache
     pub fn clear_searches(&self) -> std::io::Result<()> {
+        self.clear_namespace(CacheNamespace::Searches)
+    }
+
+    /// Clear only citation cache
+    pub fn clear_citations(&self) -> std::io::Result<()> {
+        self.clear_namespace(CacheNamespace::Citations)
+    }
+
+    /// Clear only the HTTP response cache
+    pub fn clear_http(&self) -> std::io::Result<()> {
         if !self.is_enabled() {
             return Ok(());
         }
 
-        let _ = fs::remove_dir_all(&self.search_dir);
-        fs::create_dir_all(&self.search_dir)?;
-        tracing::info!("Search cache cleared");
+        self.open_store()?;
+        if let Some(http) = self.http_cache() {
+            http.clear()?;
+        }
+        tracing::info!("HTTP cache cleared");
         Ok(())
     }
 
-    /// Clear only citation cache
-    pub fn clear_citations(&self) -> std::io::Result<()> {
+    /// Clear one namespace
+    pub fn clear_namespace(&self, namespace: CacheNamespace) -> std::io::Result<()> {
         if !self.is_enabled() {
             return Ok(());
         }
 
-        let _ = fs::remove_dir_all(&self.citation_dir);
-        fs::create_dir_all(&self.citation_dir)?;
-        tracing::info!("Citation cache cleared");
+        self.open_store()?.clear(Some(namespace))?;
+        tracing::info!("{} cache cleared", namespace);
         Ok(())
     }
 
+    /// The HTTP response cache kept in this cache's store
+    pub fn http_cache(&self) -> Option<HttpCache> {
+        let store = self.store()?.clone();
+        Some(
+            HttpCache::new(store, self.config.http_max_size_mb)
+                .with_sources(self.config.http_sources.clone()),
+        )
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
-        if !self.is_enabled() {
+        let Some(store) = self.store() else {
             return CacheStats::disabled();
-        }
-
-        let search_count = self.search_dir.read_dir().map(|e| e.count()).unwrap_or(0);
-        let citation_count = self.citation_dir.read_dir().map(|e| e.count()).unwrap_or(0);
+        };
 
-        let search_size = self
-            .dir_size(&self.search_dir)
-            .map(|s| s / 1024)
-            .unwrap_or(0); // KB
-        let citation_size = self
-            .dir_size(&self.citation_dir)
-            .map(|s| s / 1024)
-            .unwrap_or(0); // KB
+        let namespaces = store.stats().unwrap_or_default();
+        let namespace = |namespace: CacheNamespace| {
+            namespaces
+                .iter()
+                .find(|stats| stats.namespace == namespace)
+                .copied()
+                .unwrap_or(NamespaceStats {
+                    namespace,
+                    entries: 0,
+                    size: 0,
+                    raw_size: 0,
+                })
+        };
+        let searches = namespace(CacheNamespace::Searches);
+        let citations = namespace(CacheNamespace::Citations);
+        let http = self
+            .http_cache()
+            .map(|http| http.stats())
+            .unwrap_or_default();
 
         CacheStats {
             enabled: true,
             cache_dir: self.base_dir.clone(),
-            search_count,
-            citation_count,
-            search_size_kb: search_size,
-            citation_size_kb: citation_size,
-            total_size_kb: search_size + citation_size,
+            database: store.path().to_path_buf(),
+            schema_version: store.schema_version().unwrap_or_default(),
+            search_count: searches.entries,
+            citation_count: citations.entries,
+            search_size_kb: searches.size / 1024,
+            citation_size_kb: citations.size / 1024,
+            total_size_kb: namespaces.iter().map(|stats| stats.size).sum::<u64>() / 1024,
+            max_size_kb: store.max_size() / 1024,
+            namespaces,
+            http_enabled: self.config.http_enabled,
+            http,
             ttl_search: Duration::from_secs(self.config.search_ttl_seconds),
             ttl_citations: Duration::from_secs(self.config.citation_ttl_seconds),
         }
     }
-
-    /// Calculate the total size of a directory
-    #[allow(clippy::only_used_in_recursion)]
-    fn dir_size(&self, path: &Path) -> Result<u64, std::io::Error> {
-        let mut size = 0;
-        if let Ok(entries) = path.read_dir() {
-            for entry in entries.flatten() {
-                size += if entry.path().is_dir() {
-                    self.dir_size(&entry.path()).unwrap_or(0)
-                } else {
-                    entry.metadata().map(|m| m.len()).unwrap_or(0)
-                };
-            }
-        }
-        Ok(size)
-    }
 }
 
 impl Default for CacheService {
@@ -406,6 +690,57 @@ impl Default for CacheService {
     }
 }
 
+/// Move the JSON files of the file-per-entry cache into the store, then
+/// remove their directories; returns the number of items moved
+fn migrate_legacy(base_dir: &Path, store: &CacheStore) -> usize {
+    let mut migrated = 0;
+    for (dir, namespace) in LEGACY_DIRS {
+        let dir = base_dir.join(dir);
+        let Ok(files) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            let Some(key) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Some(entry) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<LegacyEntry>(&json).ok())
+            else {
+                continue;
+            };
+            let value = entry
+                .response
+                .or(entry.open_access)
+                .unwrap_or(serde_json::Value::Null);
+            let ttl = entry
+                .metadata
+                .expires_at
+                .saturating_sub(entry.metadata.cached_at);
+            let stored = serde_json::to_vec(&value).ok().and_then(|json| {
+                store
+                    .put(
+                        namespace,
+                        key,
+                        &json,
+                        &entry.metadata.source,
+                        &entry.metadata.query,
+                        Some(Duration::from_secs(ttl)),
+                    )
+                    .ok()
+            });
+            if stored.is_some() {
+                migrated += 1;
+            }
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+    // Responses of the HTTP cache are refetched rather than moved
+    let _ = fs::remove_dir_all(base_dir.join("http"));
+    migrated
+}
+
 /// Statistics about the cache
 #[derive(Debug, Clone)]
 pub struct CacheStats {
@@ -415,6 +750,12 @@ pub struct CacheStats {
     /// Cache directory path
     pub cache_dir: PathBuf,
 
+    /// Path of the cache database
+    pub database: PathBuf,
+
+    /// Schema version of the cache database
+    pub schema_version: u32,
+
     /// Number of cached search results
     pub search_count: usize,
 
@@ -430,6 +771,18 @@ pub struct CacheStats {
     /// Total size in KB
     pub total_size_kb: u64,
 
+    /// Size limit in KB (0 for none)
+    pub max_size_kb: u64,
+
+    /// Entries and size of every namespace
+    pub namespaces: Vec<NamespaceStats>,
+
+    /// Whether HTTP responses are cached
+    pub http_enabled: bool,
+
+    /// HTTP response cache statistics
+    pub http: HttpCacheStats,
+
     /// TTL for search results
     pub ttl_search: Duration,
 
@@ -443,11 +796,17 @@ impl CacheStats {
         Self {
             enabled: false,
             cache_dir: PathBuf::new(),
+            database: PathBuf::new(),
+            schema_version: 0,
             search_count: 0,
             citation_count: 0,
             search_size_kb: 0,
             citation_size_kb: 0,
             total_size_kb: 0,
+            max_size_kb: 0,
+            namespaces: Vec::new(),
+            http_enabled: false,
+            http: HttpCacheStats::default(),
             ttl_search: Duration::ZERO,
             ttl_citations: Duration::ZERO,
         }
@@ -466,6 +825,10 @@ mod tests {
             search_ttl_seconds: 60, // 1 minute for tests
             citation_ttl_seconds: 30,
             max_size_mb: 10,
+            compress: true,
+            http_enabled: true,
+            http_max_size_mb: 10,
+            http_sources: Vec::new(),
         }
     }
 
@@ -485,10 +848,10 @@ mod tests {
         let query = SearchQuery::new("test query");
 
         // Cache a search
-        cache.set_search("test_source", &query, &response);
+        cache.set_search("test_source", &query, &response).await;
 
         // Should be a hit
-        match cache.get_search(&query, "test_source") {
+        match cache.get_search(&query, "test_source").await {
             CacheResult::Hit(r) => {
                 assert_eq!(r.source, "test_source");
                 assert_eq!(r.query, "test query");
@@ -498,7 +861,7 @@ mod tests {
 
         // Different query should be a miss
         let query2 = SearchQuery::new("different query");
-        match cache.get_search(&query2, "test_source") {
+        match cache.get_search(&query2, "test_source").await {
             CacheResult::Miss => {}
             _ => panic!("Expected cache miss for different query"),
         }
@@ -506,6 +869,67 @@ mod tests {
         cache.clear_all().unwrap();
     }
 
+    #[tokio::test]
+    async fn test_cache_citations_and_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            enabled: true,
+            directory: Some(temp_dir.path().to_path_buf()),
+            ..test_cache_config()
+        };
+
+        let cache = CacheService::from_config(config);
+        cache.initialize().unwrap();
+
+        let response = SearchResponse::new(vec![], "semantic".to_string(), "p1".to_string());
+        cache.set_citations("semantic", "p1", 20, &response).await;
+
+        // A short response is still found under the requested size
+        assert!(matches!(
+            cache.get_citations("p1", "semantic", 20).await,
+            CacheResult::Hit(_)
+        ));
+        // Citations and references are cached separately
+        assert!(matches!(
+            cache.get_references("p1", "semantic", 20).await,
+            CacheResult::Miss
+        ));
+
+        cache.set_references("semantic", "p1", 20, &response).await;
+        assert!(matches!(
+            cache.get_references("p1", "semantic", 20).await,
+            CacheResult::Hit(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cache_open_access() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            directory: Some(temp_dir.path().to_path_buf()),
+            ..test_cache_config()
+        };
+        let cache = CacheService::from_config(config);
+        cache.initialize().unwrap();
+
+        let open_access = OpenAccess::new(crate::models::OaStatus::Gold, "openalex");
+        cache.set_open_access("10.1/a", Some(&open_access)).await;
+        cache.set_open_access("10.1/unknown", None).await;
+
+        assert!(matches!(
+            cache.get_open_access("10.1/a").await,
+            CacheResult::Hit(Some(ref o)) if o.status == crate::models::OaStatus::Gold
+        ));
+        assert!(matches!(
+            cache.get_open_access("10.1/unknown").await,
+            CacheResult::Hit(None)
+        ));
+        assert!(matches!(
+            cache.get_open_access("10.1/b").await,
+            CacheResult::Miss
+        ));
+    }
+
     #[tokio::test]
     async fn test_cache_disabled() {
         let temp_dir = TempDir::new().unwrap();
@@ -523,9 +947,9 @@ mod tests {
         let query = SearchQuery::new("test query");
 
         // Cache should be ignored when disabled
-        cache.set_search("test_source", &query, &response);
+        cache.set_search("test_source", &query, &response).await;
 
-        match cache.get_search(&query, "test_source") {
+        match cache.get_search(&query, "test_source").await {
             CacheResult::Miss => {}
             _ => panic!("Expected cache miss when disabled"),
         }
@@ -539,7 +963,7 @@ mod tests {
             directory: Some(temp_dir.path().to_path_buf()),
             search_ttl_seconds: 0, // Immediate expiration for testing
             citation_ttl_seconds: 0,
-            max_size_mb: 10,
+            ..test_cache_config()
         };
 
         let cache = CacheService::from_config(config);
@@ -550,11 +974,126 @@ mod tests {
 
         let query = SearchQuery::new("test query");
 
-        cache.set_search("test_source", &query, &response);
+        cache.set_search("test_source", &query, &response).await;
 
-        match cache.get_search(&query, "test_source") {
+        match cache.get_search(&query, "test_source").await {
             CacheResult::Expired => {}
             _ => panic!("Expected cache expired"),
         }
     }
+
+    #[test]
+    fn test_cache_http_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            directory: Some(temp_dir.path().to_path_buf()),
+            ..test_cache_config()
+        };
+        let cache = CacheService::from_config(config);
+        cache.initialize().unwrap();
+
+        let http = cache.http_cache().unwrap();
+        assert_eq!(http.store().path(), temp_dir.path().join(CACHE_DB_FILE));
+        let request = reqwest::Request::new(
+            reqwest::Method::GET,
+            reqwest::Url::parse("https://api.example.org/works").unwrap(),
+        );
+        let key = http.key(&request).unwrap();
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("etag", "\"v1\"".parse().unwrap());
+        let body: Vec<u8> = (0..2048u32).map(|i| (i * 7 % 251) as u8).collect();
+        assert!(http.store_response(&key, request.url(), &headers, &body));
+        http.record(crate::utils::HttpCacheEvent::Revalidated);
+
+        let stats = cache.stats();
+        assert!(stats.http_enabled);
+        assert_eq!(stats.http.entries, 1);
+        assert_eq!(stats.http.revalidated, 1);
+        assert_eq!(stats.http.max_size_kb, 10 * 1024);
+
+        cache.clear_http().unwrap();
+        assert_eq!(cache.stats().http.entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cache_namespaces_and_invalidation() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            directory: Some(temp_dir.path().to_path_buf()),
+            ..test_cache_config()
+        };
+        let cache = CacheService::from_config(config);
+
+        cache
+            .set_entry(
+                CacheNamespace::Text,
+                "2401.00001",
+                b"full text",
+                "arxiv",
+                "",
+                None,
+            )
+            .await;
+        match cache.get_entry(CacheNamespace::Text, "2401.00001").await {
+            CacheResult::Hit(entry) => assert_eq!(entry.value, b"full text"),
+            _ => panic!("Expected cache hit"),
+        }
+
+        let query = SearchQuery::new("graph neural networks");
+        let response = SearchResponse::new(vec![], "openalex".to_string(), query.query.clone());
+        cache.set_search("openalex", &query, &response).await;
+        let listed = cache
+            .inspect(&CacheFilter::new().namespace(CacheNamespace::Searches))
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].source, "openalex");
+        assert_eq!(listed[0].description, "graph neural networks");
+
+        let removed = cache
+            .invalidate(&CacheFilter::new().source("openalex"))
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(matches!(
+            cache.get_search(&query, "openalex").await,
+            CacheResult::Miss
+        ));
+        assert_eq!(cache.stats().namespaces.len(), CacheNamespace::ALL.len());
+    }
+
+    #[tokio::test]
+    async fn test_cache_migrates_legacy_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let searches = temp_dir.path().join("searches");
+        fs::create_dir_all(&searches).unwrap();
+
+        let config = CacheConfig {
+            directory: Some(temp_dir.path().to_path_buf()),
+            ..test_cache_config()
+        };
+        let cache = CacheService::from_config(config.clone());
+        let query = SearchQuery::new("legacy query");
+        let key =
+            cache.search_cache_key(&query.query, "arxiv", query.max_results, None, None, None);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let legacy = serde_json::json!({
+            "metadata": {
+                "cached_at": now,
+                "expires_at": now + 600,
+                "source": "arxiv",
+                "query": "legacy query",
+            },
+            "response": SearchResponse::new(vec![], "arxiv".to_string(), "legacy query".to_string()),
+        });
+        fs::write(searches.join(&key), legacy.to_string()).unwrap();
+
+        match cache.get_search(&query, "arxiv").await {
+            CacheResult::Hit(response) => assert_eq!(response.query, "legacy query"),
+            _ => panic!("Expected the legacy entry to be migrated"),
+        }
+        assert!(!searches.exists());
+        assert!(temp_dir.path().join(CACHE_DB_FILE).exists());
+    }
 }
@@ -0,0 +1,1073 @@
+//! Single-file cache store.
+//!
+//! All cached data lives in one SQLite database, `cache.db` in the cache
+//! directory, divided into [`CacheNamespace`]s. Every entry records its
+//! source, when it was stored, when it expires (if ever) and when it was
+//! last used. Values are compressed with DEFLATE when that makes them
+//! smaller, and once the store is over its size limit the least recently
+//! used entries are removed. Triggers keep a running total of the stored
+//! size of each namespace, so checking the limit does not scan the entries.
+//!
+//! The schema version is kept in `PRAGMA user_version`. Opening a store
+//! migrates it to [`SCHEMA_VERSION`]; a store written by a newer version of
+//! research-master is refused rather than modified.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// File name of the store, in the cache directory
+pub const CACHE_DB_FILE: &str = "cache.db";
+
+/// Schema version written by this version of research-master
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Schema migrations; entry `n` moves a store from version `n` to `n + 1`
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE entries (
+        namespace   TEXT NOT NULL,
+        key         TEXT NOT NULL,
+        source      TEXT NOT NULL DEFAULT '',
+        description TEXT NOT NULL DEFAULT '',
+        value       BLOB NOT NULL,
+        compressed  INTEGER NOT NULL DEFAULT 0,
+        size        INTEGER NOT NULL,
+        raw_size    INTEGER NOT NULL,
+        created_at  INTEGER NOT NULL,
+        expires_at  INTEGER,
+        accessed_at INTEGER NOT NULL,
+        PRIMARY KEY (namespace, key)
+    );
+    CREATE INDEX entries_accessed ON entries (accessed_at);
+    CREATE INDEX entries_source ON entries (source, created_at);
+    CREATE TABLE counters (
+        name  TEXT PRIMARY KEY,
+        value INTEGER NOT NULL
+    );
+",
+    "
+    CREATE TABLE sizes (
+        namespace TEXT PRIMARY KEY,
+        size      INTEGER NOT NULL
+    );
+    INSERT INTO sizes (namespace, size)
+        SELECT namespace, SUM(size) FROM entries GROUP BY namespace;
+    CREATE TRIGGER entries_insert AFTER INSERT ON entries BEGIN
+        INSERT INTO sizes (namespace, size) VALUES (NEW.namespace, NEW.size)
+            ON CONFLICT (namespace) DO UPDATE SET size = size + NEW.size;
+    END;
+    CREATE TRIGGER entries_delete AFTER DELETE ON entries BEGIN
+        UPDATE sizes SET size = size - OLD.size WHERE namespace = OLD.namespace;
+    END;
+    CREATE TRIGGER entries_update AFTER UPDATE OF namespace, size ON entries BEGIN
+        UPDATE sizes SET size = size - OLD.size WHERE namespace = OLD.namespace;
+        INSERT INTO sizes (namespace, size) VALUES (NEW.namespace, NEW.size)
+            ON CONFLICT (namespace) DO UPDATE SET size = size + NEW.size;
+    END;
+",
+];
+
+/// Values smaller than this are stored as they are
+const COMPRESS_MIN_SIZE: usize = 256;
+
+/// How long to wait for another process holding the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Errors from the cache store
+#[derive(Debug, Error)]
+pub enum CacheStoreError {
+    #[error("Cache database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("Cache I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Cache schema version {found} is newer than the supported version {supported}")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("Cache export target already exists: {0}")]
+    ExportExists(PathBuf),
+}
+
+impl From<CacheStoreError> for std::io::Error {
+    fn from(error: CacheStoreError) -> Self {
+        match error {
+            CacheStoreError::Io(e) => e,
+            other => std::io::Error::other(other),
+        }
+    }
+}
+
+/// Kinds of cached data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheNamespace {
+    /// Search results
+    Searches,
+
+    /// Citation, reference and open-access lookups
+    Citations,
+
+    /// Paper metadata
+    Metadata,
+
+    /// Downloaded PDFs
+    Pdfs,
+
+    /// Text extracted from PDFs
+    Text,
+
+    /// Raw HTTP responses
+    Http,
+}
+
+impl CacheNamespace {
+    /// Every namespace
+    pub const ALL: [CacheNamespace; 6] = [
+        Self::Searches,
+        Self::Citations,
+        Self::Metadata,
+        Self::Pdfs,
+        Self::Text,
+        Self::Http,
+    ];
+
+    /// Name used in the database and on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Searches => "searches",
+            Self::Citations => "citations",
+            Self::Metadata => "metadata",
+            Self::Pdfs => "pdfs",
+            Self::Text => "text",
+            Self::Http => "http",
+        }
+    }
+}
+
+impl fmt::Display for CacheNamespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CacheNamespace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|namespace| namespace.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("Unknown cache namespace: {}", s))
+    }
+}
+
+/// Description of a stored entry, without its value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntryInfo {
+    /// Namespace of the entry
+    pub namespace: CacheNamespace,
+
+    /// Key within the namespace
+    pub key: String,
+
+    /// Source that provided the data
+    pub source: String,
+
+    /// What the entry holds (a query, a URL, a DOI, ...)
+    pub description: String,
+
+    /// Stored size in bytes, after compression
+    pub size: u64,
+
+    /// Size of the value in bytes
+    pub raw_size: u64,
+
+    /// When the entry was stored (Unix timestamp)
+    pub created_at: u64,
+
+    /// When the entry expires (Unix timestamp), if ever
+    pub expires_at: Option<u64>,
+
+    /// When the entry was last read or written (Unix timestamp)
+    pub accessed_at: u64,
+}
+
+impl EntryInfo {
+    /// Whether the entry is past its expiry
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires| unix_seconds() >= expires)
+    }
+
+    /// Time since the entry was stored
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(unix_seconds().saturating_sub(self.created_at))
+    }
+}
+
+/// A stored entry with its value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    /// Description of the entry
+    pub info: EntryInfo,
+
+    /// The value, decompressed
+    pub value: Vec<u8>,
+}
+
+/// Selects entries to list or invalidate
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheFilter {
+    namespace: Option<CacheNamespace>,
+    source: Option<String>,
+    older_than: Option<Duration>,
+    expired: bool,
+    limit: Option<usize>,
+}
+
+impl CacheFilter {
+    /// A filter matching every entry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only entries of a namespace
+    pub fn namespace(mut self, namespace: CacheNamespace) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
+    /// Only entries from a source
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Only entries stored longer ago than `age`
+    pub fn older_than(mut self, age: Duration) -> Self {
+        self.older_than = Some(age);
+        self
+    }
+
+    /// Only expired entries
+    pub fn expired(mut self) -> Self {
+        self.expired = true;
+        self
+    }
+
+    /// At most `limit` entries (when listing)
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether the filter matches every entry
+    pub fn is_empty(&self) -> bool {
+        self.namespace.is_none()
+            && self.source.is_none()
+            && self.older_than.is_none()
+            && !self.expired
+    }
+
+    /// SQL condition and its parameters
+    fn condition(&self) -> (String, Vec<Value>) {
+        let mut conditions = vec!["1 = 1".to_string()];
+        let mut values = Vec::new();
+        if let Some(namespace) = self.namespace {
+            conditions.push("namespace = ?".to_string());
+            values.push(Value::Text(namespace.as_str().to_string()));
+        }
+        if let Some(source) = &self.source {
+            conditions.push("source = ?".to_string());
+            values.push(Value::Text(source.clone()));
+        }
+        if let Some(age) = self.older_than {
+            conditions.push("created_at < ?".to_string());
+            values.push(Value::Integer(
+                unix_seconds().saturating_sub(age.as_secs()) as i64
+            ));
+        }
+        if self.expired {
+            conditions.push("expires_at IS NOT NULL AND expires_at <= ?".to_string());
+            values.push(Value::Integer(unix_seconds() as i64));
+        }
+        (conditions.join(" AND "), values)
+    }
+}
+
+/// Size of one namespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamespaceStats {
+    /// The namespace
+    pub namespace: CacheNamespace,
+
+    /// Number of entries
+    pub entries: usize,
+
+    /// Stored size in bytes, after compression
+    pub size: u64,
+
+    /// Size of the values in bytes
+    pub raw_size: u64,
+}
+
+/// Cache store backed by a single SQLite database
+#[derive(Debug)]
+pub struct CacheStore {
+    /// Path of the database
+    path: PathBuf,
+
+    /// Size limit in bytes; 0 for none
+    max_size: u64,
+
+    /// Whether new values are compressed
+    compress: bool,
+
+    /// Connection to the database
+    conn: Mutex<Connection>,
+}
+
+impl CacheStore {
+    /// Open (or create) the store at `path`, holding up to `max_size_mb`
+    /// (0 for no limit), and migrate it to the current schema
+    pub fn open(path: impl Into<PathBuf>, max_size_mb: usize) -> Result<Self, CacheStoreError> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(&path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        // Entries replaced by INSERT OR REPLACE only fire the delete trigger
+        // that keeps the size totals with recursive triggers on
+        conn.pragma_update(None, "recursive_triggers", true)?;
+        migrate(&conn)?;
+        Ok(Self {
+            path,
+            max_size: max_size_mb as u64 * 1024 * 1024,
+            compress: true,
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Store new values without compressing them
+    pub fn without_compression(mut self) -> Self {
+        self.compress = false;
+        self
+    }
+
+    /// Path of the database
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Size limit in bytes; 0 for none
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Schema version of the database
+    pub fn schema_version(&self) -> Result<u32, CacheStoreError> {
+        Ok(user_version(&self.conn())?)
+    }
+
+    /// Read an entry, expired or not, and mark it as used
+    pub fn get(
+        &self,
+        namespace: CacheNamespace,
+        key: &str,
+    ) -> Result<Option<CacheEntry>, CacheStoreError> {
+        let conn = self.conn();
+        let entry = read_entry(&conn, namespace, key)?;
+        if entry.is_some() {
+            conn.execute(
+                "UPDATE entries SET accessed_at = ?3 WHERE namespace = ?1 AND key = ?2",
+                params![namespace.as_str(), key, next_access(&conn)?],
+            )?;
+        }
+        Ok(entry)
+    }
+
+    /// Read an entry without marking it as used
+    pub fn peek(
+        &self,
+        namespace: CacheNamespace,
+        key: &str,
+    ) -> Result<Option<CacheEntry>, CacheStoreError> {
+        read_entry(&self.conn(), namespace, key)
+    }
+
+    /// Store a value, replacing any entry with the same key, then remove the
+    /// least recently used entries if the store is over its size limit
+    pub fn put(
+        &self,
+        namespace: CacheNamespace,
+        key: &str,
+        value: &[u8],
+        source: &str,
+        description: &str,
+        ttl: Option<Duration>,
+    ) -> Result<(), CacheStoreError> {
+        let (stored, compressed) = self.encode(value)?;
+        let now = unix_seconds();
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO entries
+                (namespace, key, source, description, value, compressed, size, raw_size,
+                 created_at, expires_at, accessed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                namespace.as_str(),
+                key,
+                source,
+                description,
+                stored,
+                compressed,
+                stored.len() as i64,
+                value.len() as i64,
+                now as i64,
+                ttl.map(|ttl| now.saturating_add(ttl.as_secs()) as i64),
+                next_access(&conn)?,
+            ],
+        )?;
+        if self.max_size > 0 {
+            evict(&conn, None, self.max_size)?;
+        }
+        Ok(())
+    }
+
+    /// Remove an entry; returns whether it existed
+    pub fn remove(&self, namespace: CacheNamespace, key: &str) -> Result<bool, CacheStoreError> {
+        let removed = self.conn().execute(
+            "DELETE FROM entries WHERE namespace = ?1 AND key = ?2",
+            params![namespace.as_str(), key],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Entries matching a filter, most recently stored first
+    pub fn entries(&self, filter: &CacheFilter) -> Result<Vec<EntryInfo>, CacheStoreError> {
+        let (condition, mut values) = filter.condition();
+        let limit = filter.limit.map(|limit| limit as i64).unwrap_or(-1);
+        values.push(Value::Integer(limit));
+        let conn = self.conn();
+        let mut statement = conn.prepare(&format!(
+            "SELECT {} FROM entries WHERE {} ORDER BY created_at DESC, key LIMIT ?",
+            INFO_COLUMNS, condition
+        ))?;
+        let rows = statement.query_map(params_from_iter(values), entry_info)?;
+        Ok(rows
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Remove the entries matching a filter; returns how many were removed
+    pub fn invalidate(&self, filter: &CacheFilter) -> Result<usize, CacheStoreError> {
+        let (condition, values) = filter.condition();
+        let removed = self.conn().execute(
+            &format!("DELETE FROM entries WHERE {}", condition),
+            params_from_iter(values),
+        )?;
+        Ok(removed)
+    }
+
+    /// Remove every entry of a namespace, or of all namespaces
+    pub fn clear(&self, namespace: Option<CacheNamespace>) -> Result<usize, CacheStoreError> {
+        let filter = match namespace {
+            Some(namespace) => CacheFilter::new().namespace(namespace),
+            None => CacheFilter::new(),
+        };
+        let removed = self.invalidate(&filter)?;
+        if namespace.is_none() {
+            self.conn().execute("DELETE FROM counters", [])?;
+        }
+        Ok(removed)
+    }
+
+    /// Remove the least recently used entries of a namespace until it holds
+    /// at most `max_size` bytes; returns how many were removed
+    pub fn evict_namespace(
+        &self,
+        namespace: CacheNamespace,
+        max_size: u64,
+    ) -> Result<usize, CacheStoreError> {
+        Ok(evict(&self.conn(), Some(namespace), max_size)?)
+    }
+
+    /// Size of every namespace
+    pub fn stats(&self) -> Result<Vec<NamespaceStats>, CacheStoreError> {
+        let conn = self.conn();
+        let mut statement = conn.prepare(
+            "SELECT namespace, COUNT(*), COALESCE(SUM(size), 0), COALESCE(SUM(raw_size), 0)
+             FROM entries GROUP BY namespace",
+        )?;
+        let mut sizes: HashMap<String, (usize, u64, u64)> = HashMap::new();
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (namespace, entries, size, raw_size) = row?;
+            sizes.insert(namespace, (entries as usize, size as u64, raw_size as u64));
+        }
+        Ok(CacheNamespace::ALL
+            .into_iter()
+            .map(|namespace| {
+                let (entries, size, raw_size) =
+                    sizes.get(namespace.as_str()).copied().unwrap_or_default();
+                NamespaceStats {
+                    namespace,
+                    entries,
+                    size,
+                    raw_size,
+                }
+            })
+            .collect())
+    }
+
+    /// Add one to a named counter
+    pub fn increment(&self, counter: &str) -> Result<(), CacheStoreError> {
+        self.conn().execute(
+            "INSERT INTO counters (name, value) VALUES (?1, 1)
+             ON CONFLICT (name) DO UPDATE SET value = value + 1",
+            params![counter],
+        )?;
+        Ok(())
+    }
+
+    /// Value of a named counter
+    pub fn counter(&self, counter: &str) -> Result<u64, CacheStoreError> {
+        let value: Option<i64> = self
+            .conn()
+            .query_row(
+                "SELECT value FROM counters WHERE name = ?1",
+                params![counter],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.unwrap_or(0) as u64)
+    }
+
+    /// Reset the counters whose names start with `prefix`
+    pub fn reset_counters(&self, prefix: &str) -> Result<(), CacheStoreError> {
+        self.conn().execute(
+            "DELETE FROM counters WHERE substr(name, 1, length(?1)) = ?1",
+            params![prefix],
+        )?;
+        Ok(())
+    }
+
+    /// Write a copy of the store to a new file; returns the number of entries
+    pub fn export(&self, path: &Path) -> Result<usize, CacheStoreError> {
+        if path.exists() {
+            return Err(CacheStoreError::ExportExists(path.to_path_buf()));
+        }
+        let conn = self.conn();
+        conn.execute(
+            "VACUUM INTO ?1",
+            params![path.to_string_lossy().to_string()],
+        )?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Copy the entries of an exported store into this one, replacing entries
+    /// with the same key; returns the number of entries copied
+    pub fn import(&self, path: &Path) -> Result<usize, CacheStoreError> {
+        if !path.exists() {
+            return Err(CacheStoreError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No cache export at {}", path.display()),
+            )));
+        }
+        // Bring the export up to the current schema first
+        drop(Self::open(path, 0)?);
+
+        let conn = self.conn();
+        conn.execute(
+            "ATTACH DATABASE ?1 AS import",
+            params![path.to_string_lossy().to_string()],
+        )?;
+        let copied = conn
+            .execute(
+                &format!(
+                    "INSERT OR REPLACE INTO entries ({0}) SELECT {0} FROM import.entries",
+                    ALL_COLUMNS
+                ),
+                [],
+            )
+            .map_err(CacheStoreError::from);
+        conn.execute("DETACH DATABASE import", [])?;
+        let copied = copied?;
+        if self.max_size > 0 {
+            evict(&conn, None, self.max_size)?;
+        }
+        Ok(copied)
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Compress a value if that makes it smaller
+    fn encode(&self, value: &[u8]) -> Result<(Vec<u8>, bool), CacheStoreError> {
+        if !self.compress || value.len() < COMPRESS_MIN_SIZE {
+            return Ok((value.to_vec(), false));
+        }
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(value)?;
+        let compressed = encoder.finish()?;
+        if compressed.len() < value.len() {
+            Ok((compressed, true))
+        } else {
+            Ok((value.to_vec(), false))
+        }
+    }
+}
+
+/// Columns describing an entry, in the order [`entry_info`] reads them
+const INFO_COLUMNS: &str =
+    "namespace, key, source, description, size, raw_size, created_at, expires_at, accessed_at";
+
+/// Every column, for copying entries between stores
+const ALL_COLUMNS: &str = "namespace, key, source, description, value, compressed, size, \
+     raw_size, created_at, expires_at, accessed_at";
+
+/// Bring a database to [`SCHEMA_VERSION`]
+fn migrate(conn: &Connection) -> Result<(), CacheStoreError> {
+    let version = user_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(CacheStoreError::UnsupportedVersion {
+            found: version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        tracing::debug!("Migrating cache schema to version {}", from + 1);
+        conn.execute_batch(&format!(
+            "BEGIN; {} PRAGMA user_version = {}; COMMIT;",
+            migration,
+            from + 1
+        ))?;
+    }
+    Ok(())
+}
+
+fn user_version(conn: &Connection) -> Result<u32, rusqlite::Error> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Read an entry and decompress its value
+fn read_entry(
+    conn: &Connection,
+    namespace: CacheNamespace,
+    key: &str,
+) -> Result<Option<CacheEntry>, CacheStoreError> {
+    let row = conn
+        .query_row(
+            &format!(
+                "SELECT {}, value, compressed FROM entries WHERE namespace = ?1 AND key = ?2",
+                INFO_COLUMNS
+            ),
+            params![namespace.as_str(), key],
+            |row| {
+                Ok((
+                    entry_info(row)?,
+                    row.get::<_, Vec<u8>>(9)?,
+                    row.get::<_, bool>(10)?,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((Some(info), stored, compressed)) = row else {
+        return Ok(None);
+    };
+    let value = if compressed {
+        let mut value = Vec::with_capacity(info.raw_size as usize);
+        DeflateDecoder::new(stored.as_slice()).read_to_end(&mut value)?;
+        value
+    } else {
+        stored
+    };
+    Ok(Some(CacheEntry { info, value }))
+}
+
+/// Read the [`INFO_COLUMNS`] of a row; `None` for an unknown namespace
+fn entry_info(row: &rusqlite::Row<'_>) -> rusqlite::Result<Option<EntryInfo>> {
+    let Ok(namespace) = row.get::<_, String>(0)?.parse() else {
+        return Ok(None);
+    };
+    Ok(Some(EntryInfo {
+        namespace,
+        key: row.get(1)?,
+        source: row.get(2)?,
+        description: row.get(3)?,
+        size: row.get::<_, i64>(4)? as u64,
+        raw_size: row.get::<_, i64>(5)? as u64,
+        created_at: row.get::<_, i64>(6)? as u64,
+        expires_at: row.get::<_, Option<i64>>(7)?.map(|t| t as u64),
+        accessed_at: row.get::<_, i64>(8)? as u64 / 1000,
+    }))
+}
+
+/// Access time for an entry being used now, in milliseconds and later than
+/// every other entry's, so the least recently used order has no ties
+fn next_access(conn: &Connection) -> Result<i64, rusqlite::Error> {
+    let latest: Option<i64> =
+        conn.query_row("SELECT MAX(accessed_at) FROM entries", [], |row| row.get(0))?;
+    Ok((unix_millis() as i64).max(latest.unwrap_or(0) + 1))
+}
+
+/// Remove the least recently used entries (of a namespace, or of all) until
+/// they hold at most `max_size` bytes
+fn evict(
+    conn: &Connection,
+    namespace: Option<CacheNamespace>,
+    max_size: u64,
+) -> Result<usize, rusqlite::Error> {
+    let namespace = namespace.map(|namespace| namespace.as_str());
+    let total: i64 = conn.query_row(
+        "SELECT COALESCE(SUM(size), 0) FROM sizes WHERE ?1 IS NULL OR namespace = ?1",
+        params![namespace],
+        |row| row.get(0),
+    )?;
+    let mut excess = total - max_size as i64;
+    if excess <= 0 {
+        return Ok(0);
+    }
+
+    let mut victims = Vec::new();
+    {
+        let mut statement = conn.prepare(
+            "SELECT namespace, key, size FROM entries WHERE ?1 IS NULL OR namespace = ?1
+             ORDER BY accessed_at",
+        )?;
+        let mut rows = statement.query(params![namespace])?;
+        while excess > 0 {
+            let Some(row) = rows.next()? else {
+                break;
+            };
+            excess -= row.get::<_, i64>(2)?;
+            victims.push((row.get::<_, String>(0)?, row.get::<_, String>(1)?));
+        }
+    }
+    for (namespace, key) in &victims {
+        conn.execute(
+            "DELETE FROM entries WHERE namespace = ?1 AND key = ?2",
+            params![namespace, key],
+        )?;
+    }
+    tracing::debug!("Evicted {} cache entries", victims.len());
+    Ok(victims.len())
+}
+
+fn unix_seconds() -> u64 {
+    unix_millis() / 1000
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store(dir: &TempDir) -> CacheStore {
+        CacheStore::open(dir.path().join(CACHE_DB_FILE), 0).unwrap()
+    }
+
+    /// Bytes that do not compress
+    fn noise(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 33) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_namespace_names() {
+        for namespace in CacheNamespace::ALL {
+            assert_eq!(namespace.as_str().parse::<CacheNamespace>(), Ok(namespace));
+        }
+        assert_eq!("PDFs".parse::<CacheNamespace>(), Ok(CacheNamespace::Pdfs));
+        assert!("papers".parse::<CacheNamespace>().is_err());
+    }
+
+    #[test]
+    fn test_put_get_and_compression() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+
+        let text = "retrieval augmented generation ".repeat(100);
+        store
+            .put(
+                CacheNamespace::Text,
+                "paper-1",
+                text.as_bytes(),
+                "arxiv",
+                "2401.00001",
+                Some(Duration::from_secs(60)),
+            )
+            .unwrap();
+        let entry = store.get(CacheNamespace::Text, "paper-1").unwrap().unwrap();
+        assert_eq!(entry.value, text.as_bytes());
+        assert_eq!(entry.info.source, "arxiv");
+        assert_eq!(entry.info.raw_size, text.len() as u64);
+        assert!(entry.info.size < entry.info.raw_size);
+        assert!(!entry.info.is_expired());
+
+        // Same key in another namespace is another entry
+        assert!(store
+            .get(CacheNamespace::Pdfs, "paper-1")
+            .unwrap()
+            .is_none());
+
+        store
+            .put(
+                CacheNamespace::Text,
+                "paper-1",
+                b"short",
+                "arxiv",
+                "",
+                Some(Duration::ZERO),
+            )
+            .unwrap();
+        let entry = store
+            .peek(CacheNamespace::Text, "paper-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.value, b"short");
+        assert!(entry.info.is_expired());
+
+        assert!(store.remove(CacheNamespace::Text, "paper-1").unwrap());
+        assert!(!store.remove(CacheNamespace::Text, "paper-1").unwrap());
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let dir = TempDir::new().unwrap();
+        let mut store = store(&dir);
+        store.max_size = 3000;
+
+        for key in ["a", "b", "c"] {
+            store
+                .put(CacheNamespace::Pdfs, key, &noise(900, 1), "", "", None)
+                .unwrap();
+        }
+        // Using "a" makes "b" the least recently used
+        assert!(store.get(CacheNamespace::Pdfs, "a").unwrap().is_some());
+        store
+            .put(CacheNamespace::Pdfs, "d", &noise(900, 2), "", "", None)
+            .unwrap();
+
+        assert!(store.peek(CacheNamespace::Pdfs, "b").unwrap().is_none());
+        for key in ["a", "c", "d"] {
+            assert!(store.peek(CacheNamespace::Pdfs, key).unwrap().is_some());
+        }
+
+        // A namespace can be held to a smaller limit
+        store.max_size = 0;
+        store
+            .put(CacheNamespace::Http, "x", &noise(500, 3), "", "", None)
+            .unwrap();
+        assert_eq!(
+            store.evict_namespace(CacheNamespace::Pdfs, 1000).unwrap(),
+            2
+        );
+        assert!(store.peek(CacheNamespace::Pdfs, "d").unwrap().is_some());
+        assert!(store.peek(CacheNamespace::Http, "x").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_filters_and_stats() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        store
+            .put(CacheNamespace::Searches, "s1", b"{}", "arxiv", "rag", None)
+            .unwrap();
+        store
+            .put(
+                CacheNamespace::Searches,
+                "s2",
+                b"{}",
+                "openalex",
+                "rag",
+                None,
+            )
+            .unwrap();
+        store
+            .put(CacheNamespace::Citations, "c1", b"{}", "openalex", "", None)
+            .unwrap();
+        store
+            .conn()
+            .execute(
+                "UPDATE entries SET created_at = created_at - 864000 WHERE key = 's2'",
+                [],
+            )
+            .unwrap();
+
+        let openalex = CacheFilter::new().source("openalex");
+        assert_eq!(store.entries(&openalex).unwrap().len(), 2);
+        assert_eq!(
+            store.entries(&CacheFilter::new().limit(1)).unwrap().len(),
+            1
+        );
+
+        let stats = store.stats().unwrap();
+        assert_eq!(stats.len(), CacheNamespace::ALL.len());
+        assert_eq!(stats[0].namespace, CacheNamespace::Searches);
+        assert_eq!(stats[0].entries, 2);
+
+        let stale = openalex.older_than(Duration::from_secs(7 * 86400));
+        assert_eq!(store.invalidate(&stale).unwrap(), 1);
+        assert!(store
+            .peek(CacheNamespace::Searches, "s2")
+            .unwrap()
+            .is_none());
+        assert!(store
+            .peek(CacheNamespace::Citations, "c1")
+            .unwrap()
+            .is_some());
+
+        assert_eq!(store.clear(Some(CacheNamespace::Searches)).unwrap(), 1);
+        assert_eq!(store.clear(None).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_counters() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        store.increment("http_hits").unwrap();
+        store.increment("http_hits").unwrap();
+        store.increment("other").unwrap();
+        assert_eq!(store.counter("http_hits").unwrap(), 2);
+        store.reset_counters("http_").unwrap();
+        assert_eq!(store.counter("http_hits").unwrap(), 0);
+        assert_eq!(store.counter("other").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_export_import() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        store
+            .put(
+                CacheNamespace::Metadata,
+                "10.1/a",
+                b"{\"title\":\"A\"}",
+                "crossref",
+                "",
+                None,
+            )
+            .unwrap();
+
+        let export = dir.path().join("export.db");
+        assert_eq!(store.export(&export).unwrap(), 1);
+        assert!(matches!(
+            store.export(&export),
+            Err(CacheStoreError::ExportExists(_))
+        ));
+
+        let other_dir = TempDir::new().unwrap();
+        let other = CacheStore::open(other_dir.path().join(CACHE_DB_FILE), 0).unwrap();
+        assert_eq!(other.import(&export).unwrap(), 1);
+        let entry = other
+            .peek(CacheNamespace::Metadata, "10.1/a")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.value, b"{\"title\":\"A\"}");
+        assert_eq!(entry.info.source, "crossref");
+    }
+
+    #[test]
+    fn test_size_totals() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        let totals_match = || {
+            let conn = store.conn();
+            let summed: i64 = conn
+                .query_row("SELECT COALESCE(SUM(size), 0) FROM entries", [], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            let kept: i64 = conn
+                .query_row("SELECT COALESCE(SUM(size), 0) FROM sizes", [], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            summed == kept
+        };
+
+        store
+            .put(CacheNamespace::Pdfs, "a", &noise(900, 1), "", "", None)
+            .unwrap();
+        store
+            .put(CacheNamespace::Http, "b", &noise(500, 2), "", "", None)
+            .unwrap();
+        // Replacing an entry swaps its size for the new one
+        store
+            .put(CacheNamespace::Pdfs, "a", &noise(300, 3), "", "", None)
+            .unwrap();
+        assert!(totals_match());
+
+        let export = dir.path().join("export.db");
+        store.export(&export).unwrap();
+        store.import(&export).unwrap();
+        assert!(totals_match());
+
+        assert!(store.remove(CacheNamespace::Pdfs, "a").unwrap());
+        assert!(totals_match());
+        store.clear(None).unwrap();
+        assert!(totals_match());
+        assert_eq!(
+            store.stats().unwrap().iter().map(|s| s.size).sum::<u64>(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(CACHE_DB_FILE);
+        drop(CacheStore::open(&path, 0).unwrap());
+        Connection::open(&path)
+            .unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        assert!(matches!(
+            CacheStore::open(&path, 0),
+            Err(CacheStoreError::UnsupportedVersion { .. })
+        ));
+    }
+}
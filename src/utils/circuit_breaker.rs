@@ -128,6 +128,11 @@ impl CircuitBreaker {
         Self::new(name, 5, Duration::from_secs(60))
     }
 
+    /// Name of the circuit
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     /// Get the current state
     pub fn state(&self) -> CircuitState {
         let state = self.state.load(Ordering::SeqCst);
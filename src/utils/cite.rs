@@ -196,11 +196,7 @@ fn format_mla(paper: &Paper) -> String {
     let authors = format_authors_mla(&paper.authors);
     let year = extract_year(paper.published_date.as_deref());
     let title = &paper.title;
-    let formatted_title = if title.ends_with('?') || title.ends_with('!') || title.ends_with('.') {
-        format!("\"{}\"", title)
-    } else {
-        format!("\"{}\"", title)
-    };
+    let formatted_title = format!("\"{}\"", title);
     let source = paper.source.name();
     let doi = paper.doi.as_deref().unwrap_or("");
 
@@ -263,7 +259,7 @@ fn format_bibtex(paper: &Paper) -> String {
                 if words.len() >= 2 {
                     format!("{} and {} {}", words.last().unwrap(), words[..words.len()-1].join(" "), words.last().unwrap())
                 } else {
-                    format!("{}", a)
+                    a.to_string()
                 }
             }
         }).collect::<Vec<_>>().join(" and ")
@@ -1,14 +1,19 @@
 //! Deduplication utilities for papers across sources.
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use strsim::jaro_winkler;
 
 use crate::models::Paper;
+use crate::versions::distinct_versions;
 
 /// Strategy for handling duplicates
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
 pub enum DuplicateStrategy {
     /// Keep the first occurrence of each duplicate group
+    #[default]
     First,
     /// Keep the last occurrence of each duplicate group
     Last,
@@ -67,6 +72,11 @@ fn are_duplicates(a: &Paper, b: &Paper) -> bool {
         }
     }
 
+    // A preprint and its published version are linked, not merged
+    if distinct_versions(a, b) {
+        return false;
+    }
+
     // Check title similarity
     let title_a = a.title.to_lowercase().trim().to_string();
     let title_b = b.title.to_lowercase().trim().to_string();
@@ -287,6 +297,10 @@ pub fn fast_deduplicate_papers(papers: Vec<Paper>, strategy: DuplicateStrategy)
 
 /// Calculate confidence that two papers are the same based on multiple signals
 fn title_similarity_confidence(a: &Paper, b: &Paper) -> bool {
+    if distinct_versions(a, b) {
+        return false;
+    }
+
     // High title similarity
     let title_a = a.title.to_lowercase().trim().to_string();
     let title_b = b.title.to_lowercase().trim().to_string();
@@ -437,6 +451,33 @@ mod tests {
         assert_eq!(deduped.len(), 2);
     }
 
+    #[test]
+    fn test_no_duplicates_preprint_and_published() {
+        let papers = vec![
+            PaperBuilder::new("1", "Test Paper", "https://arxiv.org/1", SourceType::Arxiv)
+                .authors("John Doe")
+                .build(),
+            PaperBuilder::new(
+                "2",
+                "Test Paper",
+                "https://doi.org/10.1234/journal",
+                SourceType::CrossRef,
+            )
+            .authors("John Doe")
+            .doi("10.1234/journal")
+            .build(),
+        ];
+
+        assert_eq!(
+            deduplicate_papers(papers.clone(), DuplicateStrategy::First).len(),
+            2
+        );
+        assert_eq!(
+            fast_deduplicate_papers(papers, DuplicateStrategy::First).len(),
+            2
+        );
+    }
+
     #[test]
     fn test_no_duplicates_different_titles() {
         let papers = vec![
@@ -416,6 +416,17 @@ pub fn format_year(year: &str) -> String {
     year.chars().take(4).collect()
 }
 
+/// Format an age in its largest whole unit, e.g. `45s`, `12m`, `3h` or `2d`.
+pub fn format_age(age: std::time::Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m", seconds / 60),
+        3600..=86399 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
+
 /// Get optimal column widths for a paper table.
 ///
 /// Returns (title_width, authors_width, source_width, year_width).
@@ -457,6 +468,15 @@ mod tests {
         assert_eq!(truncate_with_ellipsis("Hello World", 8), "Hello...");
     }
 
+    #[test]
+    fn test_format_age() {
+        use std::time::Duration;
+        assert_eq!(format_age(Duration::from_secs(45)), "45s");
+        assert_eq!(format_age(Duration::from_secs(720)), "12m");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600 + 59)), "3h");
+        assert_eq!(format_age(Duration::from_secs(9 * 86400)), "9d");
+    }
+
     #[test]
     fn test_truncate_with_ellipsis_empty() {
         assert_eq!(truncate_with_ellipsis("", 10), "");
@@ -9,8 +9,12 @@ use reqwest::{header, Client, StatusCode};
 use std::num::NonZeroU32;
 use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use super::http_cache::{HttpCache, HttpCacheEvent};
+use super::http_replay::{HttpRecorder, RecordedRequest, ReplayMode};
+use super::metrics::Metrics;
+use super::rate_limit::HostLimiter;
 use crate::models::{DownloadRequest, DownloadResult};
 use crate::sources::SourceError;
 
@@ -139,10 +143,17 @@ fn should_bypass_proxy(url: &str, no_proxy: &Option<Vec<String>>) -> bool {
 }
 
 /// Shared HTTP client with sensible defaults and rate limiting
+///
+/// Besides its own quota, every request waits for its host's turn in the
+/// [`HostLimiter`], which follows the rate-limit headers of the host's
+/// responses and shares the host's schedule with other processes. While the
+/// cache is enabled, GET requests are answered from the [`HttpCache`] or
+/// revalidated against it.
 #[derive(Debug, Clone)]
 pub struct HttpClient {
     client: Arc<Client>,
     rate_limiter: Option<Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>>,
+    min_interval: Option<Duration>,
     no_proxy: Option<Vec<String>>,
 }
 
@@ -150,15 +161,92 @@ pub struct HttpClient {
 pub struct RateLimitedRequestBuilder {
     inner: reqwest::RequestBuilder,
     rate_limiter: Option<Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>>,
+    host: Option<String>,
+    min_interval: Option<Duration>,
+    no_cache: bool,
 }
 
 impl RateLimitedRequestBuilder {
     /// Send the request (with rate limiting applied first)
+    ///
+    /// A GET is answered from the [`HttpCache`] while its stored response is
+    /// fresh; a stale one is revalidated with `If-None-Match` /
+    /// `If-Modified-Since`. When an [`HttpRecorder`] is active, requests are
+    /// instead answered from its recordings, or sent past the cache and
+    /// recorded.
     pub async fn send(self) -> Result<reqwest::Response, reqwest::Error> {
-        if let Some(ref limiter) = self.rate_limiter {
-            limiter.until_ready().await;
+        let no_cache = self.no_cache;
+        let (client, request) = self.inner.build_split();
+        let mut request = request?;
+        let pacing = Pacing {
+            rate_limiter: self.rate_limiter,
+            host: self.host,
+            min_interval: self.min_interval,
+        };
+
+        if let Some(recorder) = HttpRecorder::global() {
+            let recorded = RecordedRequest::new(&request);
+            return match recorder.mode() {
+                ReplayMode::Replay => Ok(recorder.respond(&recorded)),
+                ReplayMode::Record => {
+                    let response = pacing.execute(&client, request).await?;
+                    recorder.save(recorded, response).await
+                }
+            };
         }
-        self.inner.send().await
+
+        let cached = HttpCache::global()
+            .filter(|_| !no_cache)
+            .and_then(|cache| Some((cache, cache.key(&request)?)));
+        let Some((cache, key)) = cached else {
+            return pacing.execute(&client, request).await;
+        };
+
+        // The store is SQLite, so its calls run off the async workers
+        let lookup = key.clone();
+        let entry = off_workers(move || cache.get(&lookup)).await.flatten();
+        if let Some(entry) = entry.as_ref().filter(|entry| entry.is_fresh()) {
+            off_workers(move || cache.record(HttpCacheEvent::Hit)).await;
+            return Ok(entry.to_response());
+        }
+        if let Some(entry) = &entry {
+            entry.add_validators(request.headers_mut());
+        }
+
+        let url = request.url().clone();
+        let response = pacing.execute(&client, request).await?;
+        if let Some(mut entry) = entry.filter(|_| response.status() == StatusCode::NOT_MODIFIED) {
+            let headers = response.headers().clone();
+            let response = entry.to_response();
+            off_workers(move || {
+                cache.refresh(&key, &mut entry, &url, &headers);
+                cache.record(HttpCacheEvent::Revalidated);
+            })
+            .await;
+            return Ok(response);
+        }
+        off_workers(move || cache.record(HttpCacheEvent::Miss)).await;
+        if !cache.is_storable(&url, response.status(), response.headers()) {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        let stored_body = body.clone();
+        let stored_headers = headers.clone();
+        off_workers(move || cache.store_response(&key, &url, &stored_headers, &stored_body)).await;
+        let mut stored = http::Response::new(body);
+        *stored.status_mut() = status;
+        *stored.headers_mut() = headers;
+        Ok(reqwest::Response::from(stored))
+    }
+
+    /// Send the request past the [`HttpCache`], for large one-off bodies such
+    /// as PDFs that would push metadata responses out of it
+    pub fn no_cache(mut self) -> Self {
+        self.no_cache = true;
+        self
     }
 
     /// Add a header (accepts &str for convenience - most common use case)
@@ -178,26 +266,22 @@ impl RateLimitedRequestBuilder {
     }
 
     /// Basic auth
-    pub fn basic_auth<U, P>(self, username: U, password: Option<P>) -> Self
+    pub fn basic_auth<U, P>(mut self, username: U, password: Option<P>) -> Self
     where
         U: Into<String> + std::fmt::Display,
         P: Into<String> + std::fmt::Display,
     {
-        Self {
-            inner: self.inner.basic_auth(username, password),
-            rate_limiter: self.rate_limiter,
-        }
+        self.inner = self.inner.basic_auth(username, password);
+        self
     }
 
     /// Bearer auth
-    pub fn bearer_auth<T>(self, token: T) -> Self
+    pub fn bearer_auth<T>(mut self, token: T) -> Self
     where
         T: Into<String> + std::fmt::Display,
     {
-        Self {
-            inner: self.inner.bearer_auth(token),
-            rate_limiter: self.rate_limiter,
-        }
+        self.inner = self.inner.bearer_auth(token);
+        self
     }
 
     /// Query parameters
@@ -224,6 +308,46 @@ impl RateLimitedRequestBuilder {
     }
 }
 
+/// Run a cache call on the blocking pool; `None` if it failed
+async fn off_workers<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .inspect_err(|e| tracing::debug!("HTTP cache task failed: {}", e))
+        .ok()
+}
+
+/// Rate limits a request waits for before it is sent
+struct Pacing {
+    rate_limiter: Option<Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>>,
+    host: Option<String>,
+    min_interval: Option<Duration>,
+}
+
+impl Pacing {
+    /// Wait for the client's quota and the host's turn, then send the request
+    async fn execute(
+        self,
+        client: &Client,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let started = Instant::now();
+        if let Some(ref limiter) = self.rate_limiter {
+            limiter.until_ready().await;
+        }
+        let Some(host) = self.host else {
+            return client.execute(request).await;
+        };
+        let limiter = HostLimiter::global();
+        limiter.wait(&host, self.min_interval).await;
+        Metrics::global().record_rate_limit_wait(&host, started.elapsed());
+        let response = client.execute(request).await?;
+        limiter
+            .observe(&host, response.status(), response.headers())
+            .await;
+        Ok(response)
+    }
+}
+
 /// Environment variable for custom user agent
 pub const USER_AGENT_ENV_VAR: &str = "RESEARCH_MASTER_USER_AGENT";
 
@@ -243,6 +367,7 @@ impl HttpClient {
     /// Create a new HTTP client with a custom user agent
     pub fn with_user_agent(user_agent: &str) -> Result<Self, SourceError> {
         let rate_limiter = Self::create_rate_limiter();
+        let min_interval = min_interval(default_requests_per_second());
         let proxy = create_proxy_config();
 
         let mut builder = Client::builder()
@@ -266,6 +391,7 @@ impl HttpClient {
         Ok(Self {
             client: Arc::new(client),
             rate_limiter,
+            min_interval,
             no_proxy: proxy.no_proxy,
         })
     }
@@ -273,6 +399,7 @@ impl HttpClient {
     /// Create HTTP client with custom timeout
     pub fn with_timeout(user_agent: &str, timeout_secs: u64) -> Result<Self, SourceError> {
         let rate_limiter = Self::create_rate_limiter();
+        let min_interval = min_interval(default_requests_per_second());
         let proxy = create_proxy_config();
 
         let mut builder = Client::builder()
@@ -296,6 +423,7 @@ impl HttpClient {
         Ok(Self {
             client: Arc::new(client),
             rate_limiter,
+            min_interval,
             no_proxy: proxy.no_proxy,
         })
     }
@@ -323,6 +451,7 @@ impl HttpClient {
         Ok(Self {
             client: Arc::new(client),
             rate_limiter: None,
+            min_interval: None,
             no_proxy: proxy.no_proxy,
         })
     }
@@ -345,6 +474,7 @@ impl HttpClient {
             let quota = Quota::per_second(nonzero);
             Some(Arc::new(RateLimiter::direct(quota)))
         };
+        let min_interval = min_interval(requests_per_second);
 
         let proxy = create_proxy_config();
         let mut builder = Client::builder()
@@ -367,6 +497,7 @@ impl HttpClient {
         Ok(Self {
             client: Arc::new(client),
             rate_limiter,
+            min_interval,
             no_proxy: proxy.no_proxy,
         })
     }
@@ -386,6 +517,7 @@ impl HttpClient {
             let quota = Quota::per_second(nonzero);
             Some(Arc::new(RateLimiter::direct(quota)))
         };
+        let min_interval = min_interval(requests_per_second);
 
         let mut builder = Client::builder()
             .user_agent(user_agent)
@@ -407,16 +539,14 @@ impl HttpClient {
         Ok(Self {
             client: Arc::new(client),
             rate_limiter,
+            min_interval,
             no_proxy: None, // Per-source proxy doesn't use env no_proxy
         })
     }
 
     /// Create rate limiter from environment variable or default
     fn create_rate_limiter() -> Option<Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>> {
-        let requests_per_second = std::env::var(RATE_LIMIT_ENV_VAR)
-            .ok()
-            .and_then(|s| s.parse::<u32>().ok())
-            .unwrap_or(DEFAULT_REQUESTS_PER_SECOND);
+        let requests_per_second = default_requests_per_second();
 
         if requests_per_second == 0 {
             // Rate limiting disabled
@@ -442,6 +572,7 @@ impl HttpClient {
         Self {
             client,
             rate_limiter: Self::create_rate_limiter(),
+            min_interval: min_interval(default_requests_per_second()),
             no_proxy: None,
         }
     }
@@ -453,17 +584,21 @@ impl HttpClient {
 
     /// Create a rate-limited GET request builder
     pub fn get(&self, url: &str) -> RateLimitedRequestBuilder {
-        RateLimitedRequestBuilder {
-            inner: self.client.get(url),
-            rate_limiter: self.rate_limiter.clone(),
-        }
+        self.request(self.client.get(url), url)
     }
 
     /// Create a rate-limited POST request builder
     pub fn post(&self, url: &str) -> RateLimitedRequestBuilder {
+        self.request(self.client.post(url), url)
+    }
+
+    fn request(&self, inner: reqwest::RequestBuilder, url: &str) -> RateLimitedRequestBuilder {
         RateLimitedRequestBuilder {
-            inner: self.client.post(url),
+            inner,
             rate_limiter: self.rate_limiter.clone(),
+            host: url_host(url),
+            min_interval: self.min_interval,
+            no_cache: false,
         }
     }
 
@@ -474,13 +609,9 @@ impl HttpClient {
         request: &DownloadRequest,
         filename: &str,
     ) -> Result<DownloadResult, SourceError> {
-        if let Some(ref limiter) = self.rate_limiter {
-            limiter.until_ready().await;
-        }
-
         let response = self
-            .client
             .get(url)
+            .no_cache()
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Failed to download: {}", e)))?;
@@ -528,13 +659,8 @@ impl HttpClient {
 
     /// Check if a URL returns success status
     pub async fn head(&self, url: &str) -> Result<bool, SourceError> {
-        if let Some(ref limiter) = self.rate_limiter {
-            limiter.until_ready().await;
-        }
-
         let response = self
-            .client
-            .head(url)
+            .request(self.client.head(url), url)
             .send()
             .await
             .map_err(|e| SourceError::Network(format!("Head request failed: {}", e)))?;
@@ -542,6 +668,27 @@ impl HttpClient {
     }
 }
 
+/// Requests per second from the environment, or the default
+fn default_requests_per_second() -> u32 {
+    std::env::var(RATE_LIMIT_ENV_VAR)
+        .ok()
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_REQUESTS_PER_SECOND)
+}
+
+/// Interval between requests to one host at a rate (none when unlimited)
+fn min_interval(requests_per_second: u32) -> Option<Duration> {
+    (requests_per_second > 0).then(|| Duration::from_secs(1) / requests_per_second)
+}
+
+/// Lowercase host of a URL, which requests are paced by
+fn url_host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_lowercase())
+}
+
 impl Default for HttpClient {
     fn default() -> Self {
         Self::new().expect("Failed to create default HTTP client")
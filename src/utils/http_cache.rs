@@ -0,0 +1,728 @@
+//! Conditional requests and a persistent cache of HTTP responses.
+//!
+//! While the cache is enabled, every GET sent through an
+//! [`HttpClient`](super::HttpClient) goes through the process-wide
+//! [`HttpCache`], which:
+//!
+//! - answers from the cache while a response is fresh, as given by its
+//!   `Cache-Control: max-age` or the TTL configured for its source
+//! - revalidates a stale response with `If-None-Match` / `If-Modified-Since`
+//!   and reuses its body when the server answers `304 Not Modified`
+//! - never stores `no-store` responses and always revalidates `no-cache` ones
+//! - drops the least recently used responses once it is over its size limit
+//!
+//! Responses are kept in the `http` namespace of the [`CacheStore`], next to
+//! the hit, revalidation and miss counts.
+
+use std::sync::{Arc, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Method, Request, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use super::cache::CacheService;
+use super::cache_store::{CacheNamespace, CacheStore};
+use super::http_replay::scrub_url;
+use super::metrics::Metrics;
+use crate::config::{CacheConfig, HttpCacheSourceConfig};
+
+/// Prefix of the counters of the HTTP cache in the store
+const COUNTER_PREFIX: &str = "http_";
+
+/// The cache used by every [`HttpClient`](super::HttpClient)
+static GLOBAL: OnceLock<Option<HttpCache>> = OnceLock::new();
+
+/// Largest response body that is stored
+const MAX_ENTRY_SIZE: u64 = 10 * 1024 * 1024;
+
+/// API hosts of each source, so settings can name the source
+const SOURCE_HOSTS: &[(&str, &[&str])] = &[
+    ("acm", &["dl.acm.org"]),
+    ("arxiv", &["export.arxiv.org"]),
+    ("base", &["api.base-search.net"]),
+    ("biorxiv", &["api.biorxiv.org", "api.medrxiv.org"]),
+    ("connected_papers", &["api.connectedpapers.com"]),
+    ("core", &["api.core.ac.uk"]),
+    ("crossref", &["api.crossref.org"]),
+    ("dblp", &["dblp.org"]),
+    ("dimensions", &["api.dimensions.ai"]),
+    ("doaj", &["doaj.org"]),
+    ("europe_pmc", &["www.ebi.ac.uk"]),
+    ("google_scholar", &["scholar.google.com"]),
+    ("hal", &["api.archives-ouvertes.fr"]),
+    ("iacr", &["eprint.iacr.org"]),
+    ("ieee_xplore", &["ieeexploreapi.ieee.org"]),
+    ("jstor", &["api.jstor.org"]),
+    ("mdpi", &["api.mdpi.com"]),
+    ("openalex", &["api.openalex.org"]),
+    ("orcid", &["pub.orcid.org"]),
+    ("osf", &["api.osf.io"]),
+    ("pmc", &["eutils.ncbi.nlm.nih.gov"]),
+    ("pubmed", &["eutils.ncbi.nlm.nih.gov"]),
+    ("scispace", &["api.typeset.io"]),
+    ("semantic", &["api.semanticscholar.org"]),
+    ("springer", &["api.springernature.com"]),
+    ("ssrn", &["papers.ssrn.com"]),
+    ("unpaywall", &["api.unpaywall.org"]),
+    ("worldwidescience", &["worldwidescience.org"]),
+    ("zenodo", &["zenodo.org"]),
+];
+
+/// `Cache-Control` directives of a response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    /// `no-store`: the response must not be kept
+    pub no_store: bool,
+
+    /// `no-cache`: the response must be revalidated before each use
+    pub no_cache: bool,
+
+    /// `max-age`, in seconds
+    pub max_age: Option<u64>,
+}
+
+impl CacheControl {
+    /// Read the `Cache-Control` header of a response
+    pub fn parse(headers: &HeaderMap) -> Self {
+        let mut control = Self::default();
+        for value in headers.get_all(header::CACHE_CONTROL) {
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+            for directive in value.split(',') {
+                let (name, argument) = match directive.split_once('=') {
+                    Some((name, argument)) => (name, Some(argument.trim().trim_matches('"'))),
+                    None => (directive, None),
+                };
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "no-store" => control.no_store = true,
+                    "no-cache" => control.no_cache = true,
+                    "max-age" => control.max_age = argument.and_then(|a| a.parse().ok()),
+                    _ => {}
+                }
+            }
+        }
+        control
+    }
+}
+
+/// A stored response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// URL the response was fetched from
+    pub url: String,
+
+    /// `Content-Type` of the response
+    pub content_type: Option<String>,
+
+    /// `ETag` of the response
+    pub etag: Option<String>,
+
+    /// `Last-Modified` of the response
+    pub last_modified: Option<String>,
+
+    /// When the response was stored or last revalidated (Unix timestamp)
+    pub stored_at: u64,
+
+    /// Until when the response can be used without revalidation (Unix timestamp)
+    pub fresh_until: u64,
+
+    /// Response body
+    #[serde(skip)]
+    pub body: Vec<u8>,
+}
+
+impl CachedResponse {
+    /// Whether the response can be used without asking the server
+    pub fn is_fresh(&self) -> bool {
+        unix_seconds() < self.fresh_until
+    }
+
+    /// Add `If-None-Match` / `If-Modified-Since` for the stored validators
+    pub fn add_validators(&self, headers: &mut HeaderMap) {
+        let validators = [
+            (header::IF_NONE_MATCH, &self.etag),
+            (header::IF_MODIFIED_SINCE, &self.last_modified),
+        ];
+        for (name, value) in validators {
+            if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(name, value);
+            }
+        }
+    }
+
+    /// Turn the stored response into a `200 OK` response
+    pub fn to_response(&self) -> reqwest::Response {
+        let mut builder = http::Response::builder().status(StatusCode::OK);
+        let headers = [
+            (header::CONTENT_TYPE, &self.content_type),
+            (header::ETAG, &self.etag),
+            (header::LAST_MODIFIED, &self.last_modified),
+        ];
+        for (name, value) in headers {
+            if let Some(value) = value {
+                builder = builder.header(name, value.as_str());
+            }
+        }
+        let response = builder
+            .body(self.body.clone())
+            .unwrap_or_else(|_| http::Response::new(self.body.clone()));
+        reqwest::Response::from(response)
+    }
+}
+
+/// What the cache did for a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpCacheEvent {
+    /// Answered from the cache without asking the server
+    Hit,
+
+    /// Answered from the cache after a `304 Not Modified`
+    Revalidated,
+
+    /// Fetched from the server
+    Miss,
+}
+
+impl HttpCacheEvent {
+    /// Name of the event in metrics
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Hit => "hit",
+            Self::Revalidated => "revalidated",
+            Self::Miss => "miss",
+        }
+    }
+
+    /// Name of the counter of the event in the store
+    fn counter(&self) -> &'static str {
+        match self {
+            Self::Hit => "http_hits",
+            Self::Revalidated => "http_revalidated",
+            Self::Miss => "http_misses",
+        }
+    }
+}
+
+/// Statistics about the HTTP cache
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpCacheStats {
+    /// Number of stored responses
+    pub entries: usize,
+
+    /// Size of the stored responses in KB
+    pub size_kb: u64,
+
+    /// Size limit in KB
+    pub max_size_kb: u64,
+
+    /// Requests answered without asking the server
+    pub hits: u64,
+
+    /// Requests answered from the cache after a `304 Not Modified`
+    pub revalidated: u64,
+
+    /// Requests fetched from the server
+    pub misses: u64,
+}
+
+/// Persistent cache of HTTP responses
+#[derive(Debug)]
+pub struct HttpCache {
+    /// Store holding the responses
+    store: Arc<CacheStore>,
+
+    /// Size limit in bytes
+    max_size: u64,
+
+    /// Per-source settings
+    sources: Vec<HttpCacheSourceConfig>,
+}
+
+impl HttpCache {
+    /// Create a cache in `store` holding up to `max_size_mb` of responses
+    pub fn new(store: Arc<CacheStore>, max_size_mb: usize) -> Self {
+        Self {
+            store,
+            max_size: max_size_mb as u64 * 1024 * 1024,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Use per-source settings
+    pub fn with_sources(mut self, sources: Vec<HttpCacheSourceConfig>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// The cache used by every [`HttpClient`](super::HttpClient)
+    ///
+    /// Set up from the environment unless [`HttpCache::init_global`] was
+    /// called first. `None` unless caching and HTTP caching are enabled, and
+    /// always `None` in test mode.
+    pub fn global() -> Option<&'static HttpCache> {
+        GLOBAL
+            .get_or_init(|| Self::from_config(crate::config::get_config().cache))
+            .as_ref()
+    }
+
+    /// Set up the cache returned by [`HttpCache::global`] from a loaded
+    /// configuration; returns `false` if it was already set up
+    pub fn init_global(config: CacheConfig) -> bool {
+        let mut initialized = false;
+        GLOBAL.get_or_init(|| {
+            initialized = true;
+            Self::from_config(config)
+        });
+        initialized
+    }
+
+    fn from_config(config: CacheConfig) -> Option<Self> {
+        let test_mode = std::env::var("RESEARCH_MASTER_TEST_MODE")
+            .map(|value| value.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if test_mode || !config.http_enabled {
+            return None;
+        }
+        CacheService::from_config(config).http_cache()
+    }
+
+    /// Store holding the responses
+    pub fn store(&self) -> &CacheStore {
+        &self.store
+    }
+
+    /// Cache key of a request; `None` if it is not a GET or its source has
+    /// HTTP caching turned off
+    pub fn key(&self, request: &Request) -> Option<String> {
+        if request.method() != Method::GET {
+            return None;
+        }
+        let host = request.url().host_str()?;
+        if self
+            .source_config(host)
+            .is_some_and(|config| !config.enabled)
+        {
+            return None;
+        }
+        let accept = request
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let input = format!("GET|{}|{}", request.url(), accept);
+        Some(format!("{:x}", md5::compute(input.as_bytes())))
+    }
+
+    /// Stored response for a key, fresh or not
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        let entry = self.store.get(CacheNamespace::Http, key).ok()??;
+        decode(&entry.value)
+    }
+
+    /// Whether a response can be stored, judging by its status and headers
+    pub fn is_storable(&self, url: &Url, status: StatusCode, headers: &HeaderMap) -> bool {
+        let content_length = headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+        status == StatusCode::OK
+            && !CacheControl::parse(headers).no_store
+            && content_length.is_none_or(|length| length <= self.max_entry_size())
+            && (self.freshness(url, headers) > 0
+                || headers.contains_key(header::ETAG)
+                || headers.contains_key(header::LAST_MODIFIED))
+    }
+
+    /// Store a `200 OK` response; returns whether it was stored
+    pub fn store_response(&self, key: &str, url: &Url, headers: &HeaderMap, body: &[u8]) -> bool {
+        if !self.is_storable(url, StatusCode::OK, headers)
+            || body.len() as u64 > self.max_entry_size()
+        {
+            return false;
+        }
+        let header = |name: header::HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let now = unix_seconds();
+        // Keep API keys in the query out of the store, which `cache inspect`
+        // and `cache export` show
+        let entry = CachedResponse {
+            url: scrub_url(url),
+            content_type: header(header::CONTENT_TYPE),
+            etag: header(header::ETAG),
+            last_modified: header(header::LAST_MODIFIED),
+            stored_at: now,
+            fresh_until: now + self.freshness(url, headers),
+            body: body.to_vec(),
+        };
+        self.put(key, &entry)
+    }
+
+    /// Renew a stored response after a `304 Not Modified`
+    pub fn refresh(&self, key: &str, entry: &mut CachedResponse, url: &Url, headers: &HeaderMap) {
+        let now = unix_seconds();
+        entry.stored_at = now;
+        entry.fresh_until = now + self.freshness(url, headers);
+        if let Some(etag) = headers.get(header::ETAG).and_then(|v| v.to_str().ok()) {
+            entry.etag = Some(etag.to_string());
+        }
+        if let Some(modified) = headers
+            .get(header::LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+        {
+            entry.last_modified = Some(modified.to_string());
+        }
+        self.put(key, entry);
+    }
+
+    /// Count what the cache did for a request
+    pub fn record(&self, event: HttpCacheEvent) {
+        Metrics::global().record_cache(CacheNamespace::Http.as_str(), event.name());
+        if let Err(e) = self.store.increment(event.counter()) {
+            tracing::debug!("Failed to count HTTP cache event: {}", e);
+        }
+    }
+
+    /// Get cache statistics
+    pub fn stats(&self) -> HttpCacheStats {
+        let namespace = self
+            .store
+            .stats()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|stats| stats.namespace == CacheNamespace::Http);
+        let counter = |event: HttpCacheEvent| self.store.counter(event.counter()).unwrap_or(0);
+        HttpCacheStats {
+            entries: namespace.map(|stats| stats.entries).unwrap_or(0),
+            size_kb: namespace.map(|stats| stats.size).unwrap_or(0) / 1024,
+            max_size_kb: self.max_size / 1024,
+            hits: counter(HttpCacheEvent::Hit),
+            revalidated: counter(HttpCacheEvent::Revalidated),
+            misses: counter(HttpCacheEvent::Miss),
+        }
+    }
+
+    /// Remove every stored response and the counts
+    pub fn clear(&self) -> std::io::Result<()> {
+        self.store.clear(Some(CacheNamespace::Http))?;
+        self.store.reset_counters(COUNTER_PREFIX)?;
+        Ok(())
+    }
+
+    /// Write a response to the store, then hold the namespace to its limit
+    fn put(&self, key: &str, entry: &CachedResponse) -> bool {
+        let source = entry
+            .url
+            .parse::<Url>()
+            .ok()
+            .and_then(|url| Some(self.source_name(url.host_str()?)))
+            .unwrap_or_default();
+        let stored = encode(entry).and_then(|value| {
+            self.store
+                .put(CacheNamespace::Http, key, &value, &source, &entry.url, None)
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = stored {
+            tracing::debug!("Failed to cache response of {}: {}", entry.url, e);
+            return false;
+        }
+        if let Err(e) = self
+            .store
+            .evict_namespace(CacheNamespace::Http, self.max_size)
+        {
+            tracing::debug!("Failed to evict HTTP responses: {}", e);
+        }
+        true
+    }
+
+    /// Seconds a response stays fresh: its `max-age`, else the TTL of its
+    /// source, else none
+    fn freshness(&self, url: &Url, headers: &HeaderMap) -> u64 {
+        let control = CacheControl::parse(headers);
+        if control.no_cache || control.no_store {
+            return 0;
+        }
+        control
+            .max_age
+            .or_else(|| {
+                self.source_config(url.host_str()?)
+                    .and_then(|config| config.ttl_seconds)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Settings of the source a host belongs to
+    fn source_config(&self, host: &str) -> Option<&HttpCacheSourceConfig> {
+        self.sources.iter().find(|config| {
+            config.source.eq_ignore_ascii_case(host)
+                || SOURCE_HOSTS
+                    .iter()
+                    .filter(|(source, _)| config.source.eq_ignore_ascii_case(source))
+                    .any(|(_, hosts)| hosts.contains(&host))
+        })
+    }
+
+    /// Source ID of a host, or the host itself, recorded with its responses
+    fn source_name(&self, host: &str) -> String {
+        SOURCE_HOSTS
+            .iter()
+            .find(|(_, hosts)| hosts.contains(&host))
+            .map(|(source, _)| source.to_string())
+            .unwrap_or_else(|| host.to_string())
+    }
+
+    /// Largest body worth storing
+    fn max_entry_size(&self) -> u64 {
+        MAX_ENTRY_SIZE.min(self.max_size)
+    }
+}
+
+/// A stored response is its JSON description, a newline, then its body
+fn encode(entry: &CachedResponse) -> Result<Vec<u8>, String> {
+    let mut value = serde_json::to_vec(entry).map_err(|e| e.to_string())?;
+    value.push(b'\n');
+    value.extend_from_slice(&entry.body);
+    Ok(value)
+}
+
+fn decode(value: &[u8]) -> Option<CachedResponse> {
+    let split = value.iter().position(|&byte| byte == b'\n')?;
+    let mut entry: CachedResponse = serde_json::from_slice(&value[..split]).ok()?;
+    entry.body = value[split + 1..].to_vec();
+    Some(entry)
+}
+
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::cache_store::{CacheFilter, CACHE_DB_FILE};
+    use reqwest::header::HeaderValue;
+    use tempfile::TempDir;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn get(url: &str) -> Request {
+        Request::new(Method::GET, Url::parse(url).unwrap())
+    }
+
+    fn cache(dir: &TempDir) -> HttpCache {
+        let store = CacheStore::open(dir.path().join(CACHE_DB_FILE), 0).unwrap();
+        HttpCache::new(Arc::new(store), 10)
+    }
+
+    #[test]
+    fn test_parse_cache_control() {
+        let control = CacheControl::parse(&headers(&[(
+            "cache-control",
+            "public, max-age=\"600\", no-cache",
+        )]));
+        assert_eq!(
+            control,
+            CacheControl {
+                no_store: false,
+                no_cache: true,
+                max_age: Some(600),
+            }
+        );
+        assert!(CacheControl::parse(&headers(&[("cache-control", "no-store")])).no_store);
+        assert_eq!(
+            CacheControl::parse(&HeaderMap::new()),
+            CacheControl::default()
+        );
+    }
+
+    #[test]
+    fn test_store_and_revalidate() {
+        let dir = TempDir::new().unwrap();
+        let cache = cache(&dir);
+        let request = get("https://api.example.org/works?q=rust");
+        let url = request.url().clone();
+        let key = cache.key(&request).unwrap();
+        assert!(cache.get(&key).is_none());
+
+        // Fresh for a minute
+        let fresh = headers(&[
+            ("cache-control", "max-age=60"),
+            ("content-type", "text/plain"),
+        ]);
+        assert!(cache.store_response(&key, &url, &fresh, b"hello\nworld"));
+        let entry = cache.get(&key).unwrap();
+        assert!(entry.is_fresh());
+        assert_eq!(entry.body, b"hello\nworld");
+        assert_eq!(entry.content_type.as_deref(), Some("text/plain"));
+
+        // Only an ETag: stored, but revalidated before use
+        let validated = headers(&[("etag", "\"v1\"")]);
+        assert!(cache.store_response(&key, &url, &validated, b"hello"));
+        let mut entry = cache.get(&key).unwrap();
+        assert!(!entry.is_fresh());
+        let mut conditional = HeaderMap::new();
+        entry.add_validators(&mut conditional);
+        assert_eq!(conditional.get("if-none-match").unwrap(), "\"v1\"");
+        assert!(conditional.get("if-modified-since").is_none());
+
+        // A 304 that allows caching makes it fresh again
+        cache.refresh(&key, &mut entry, &url, &fresh);
+        assert!(cache.get(&key).unwrap().is_fresh());
+        assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+
+        // Neither fresh nor revalidatable, or forbidden: not stored
+        assert!(!cache.store_response(&key, &url, &HeaderMap::new(), b"x"));
+        assert!(!cache.store_response(
+            &key,
+            &url,
+            &headers(&[("cache-control", "no-store"), ("etag", "\"v2\"")]),
+            b"x"
+        ));
+        assert!(!cache.is_storable(&url, StatusCode::NOT_FOUND, &fresh));
+    }
+
+    #[test]
+    fn test_api_keys_not_stored() {
+        let dir = TempDir::new().unwrap();
+        let cache = cache(&dir);
+        let request = get(
+            "https://ieeexploreapi.ieee.org/api/v1/search/articles?querytext=rust&apikey=s3cr3t",
+        );
+        let key = cache.key(&request).unwrap();
+        let fresh = headers(&[("cache-control", "max-age=60")]);
+        assert!(cache.store_response(&key, request.url(), &fresh, b"{}"));
+
+        let url = cache.get(&key).unwrap().url;
+        assert!(url.contains("querytext=rust"));
+        assert!(!url.contains("s3cr3t"));
+        let entries = cache.store.entries(&CacheFilter::new()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(!entries[0].description.contains("s3cr3t"));
+
+        let export = dir.path().join("export.db");
+        cache.store.export(&export).unwrap();
+        let exported = std::fs::read(export).unwrap();
+        assert!(!exported.windows(6).any(|bytes| bytes == b"s3cr3t"));
+    }
+
+    #[test]
+    fn test_http_cache_settings() {
+        let dir = TempDir::new().unwrap();
+        let config = CacheConfig {
+            enabled: true,
+            directory: Some(dir.path().to_path_buf()),
+            http_enabled: false,
+            ..CacheConfig::default()
+        };
+        assert!(HttpCache::from_config(config).is_none());
+    }
+
+    #[test]
+    fn test_cached_response_to_response() {
+        let entry = CachedResponse {
+            url: "https://api.example.org/".to_string(),
+            content_type: Some("application/json".to_string()),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            stored_at: 0,
+            fresh_until: 0,
+            body: b"{}".to_vec(),
+        };
+        let response = entry.to_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(response.headers().get("etag").unwrap(), "\"v1\"");
+    }
+
+    #[test]
+    fn test_source_settings() {
+        let dir = TempDir::new().unwrap();
+        let cache = cache(&dir).with_sources(HttpCacheSourceConfig::parse_list(
+            "openalex=3600,google_scholar=off",
+        ));
+
+        assert!(cache
+            .key(&get("https://scholar.google.com/scholar?q=x"))
+            .is_none());
+        assert!(cache
+            .key(&Request::new(
+                Method::POST,
+                Url::parse("https://api.openalex.org/works").unwrap()
+            ))
+            .is_none());
+
+        // The source TTL applies when the response has no max-age
+        let request = get("https://api.openalex.org/works?search=x");
+        let url = request.url().clone();
+        let key = cache.key(&request).unwrap();
+        assert!(cache.store_response(&key, &url, &HeaderMap::new(), b"{}"));
+        let entry = cache.get(&key).unwrap();
+        assert_eq!(entry.fresh_until - entry.stored_at, 3600);
+
+        // Responses are recorded under their source
+        let stored = cache
+            .store()
+            .peek(CacheNamespace::Http, &key)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.info.source, "openalex");
+        assert_eq!(stored.info.description, url.as_str());
+    }
+
+    #[test]
+    fn test_eviction_and_stats() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = cache(&dir);
+        cache.max_size = 4096;
+        let fresh = headers(&[("cache-control", "max-age=60")]);
+        // Bytes that do not compress
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let body: Vec<u8> = (0..1500)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+
+        let mut keys = Vec::new();
+        for i in 0..4 {
+            let request = get(&format!("https://api.example.org/{}", i));
+            let key = cache.key(&request).unwrap();
+            assert!(cache.store_response(&key, request.url(), &fresh, &body));
+            keys.push(key);
+        }
+        // The third and fourth responses pushed out the least recently used
+        assert!(cache.get(&keys[0]).is_none());
+        assert!(cache.get(&keys[3]).is_some());
+
+        cache.record(HttpCacheEvent::Hit);
+        cache.record(HttpCacheEvent::Hit);
+        cache.record(HttpCacheEvent::Miss);
+        let stats = cache.stats();
+        assert!(stats.entries <= 2);
+        assert!(stats.size_kb <= 4);
+        assert_eq!((stats.hits, stats.revalidated, stats.misses), (2, 0, 1));
+
+        cache.clear().unwrap();
+        assert_eq!(cache.stats().entries, 0);
+        assert_eq!(cache.stats().hits, 0);
+    }
+}
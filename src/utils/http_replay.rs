@@ -0,0 +1,526 @@
+//! Recording HTTP exchanges to fixture files and replaying them.
+//!
+//! In record mode every request an [`HttpClient`](super::HttpClient) sends is
+//! saved with its response as a JSON file; in replay mode requests are
+//! answered from those files without touching the network. This makes source
+//! parsers testable end to end against real responses, and lets users attach
+//! a recording to a bug report.
+//!
+//! # Fixture Layout
+//!
+//! ```text
+//! <dir>/
+//!   export.arxiv.org/
+//!     get-1f3870be274f6c49.json   {"request": {...}, "response": {...}}
+//! ```
+//!
+//! Requests are matched by method, URL and body, not by file name, so
+//! recordings can be renamed or written by hand. API keys, tokens and e-mail
+//! addresses in the query string are replaced by `REDACTED` before a request
+//! is saved or matched, and request headers are never saved.
+
+use base64::Engine as _;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Environment variable naming the directory to record HTTP exchanges into
+pub const HTTP_RECORD_ENV_VAR: &str = "RESEARCH_MASTER_HTTP_RECORD";
+
+/// Environment variable naming the directory to replay HTTP exchanges from
+pub const HTTP_REPLAY_ENV_VAR: &str = "RESEARCH_MASTER_HTTP_REPLAY";
+
+/// Stand-in for scrubbed secrets
+pub const REDACTED: &str = "REDACTED";
+
+/// Query parameters whose values are secret or personal
+const SECRET_PARAMS: [&str; 9] = [
+    "api_key",
+    "apikey",
+    "api-key",
+    "key",
+    "token",
+    "access_token",
+    "email",
+    "mailto",
+    "client_secret",
+];
+
+/// Response headers left out of recordings: cookies, and framing that no
+/// longer applies to the decoded body
+const SKIPPED_HEADERS: [&str; 4] = [
+    "set-cookie",
+    "content-encoding",
+    "content-length",
+    "transfer-encoding",
+];
+
+/// Whether requests are recorded or replayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Send requests and save them with their responses
+    Record,
+    /// Answer requests from saved responses, without the network
+    Replay,
+}
+
+/// A request as saved in a recording
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// HTTP method
+    pub method: String,
+
+    /// URL with secrets scrubbed
+    pub url: String,
+
+    /// Request body, if any and if it is text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+impl RecordedRequest {
+    /// Describe a request, scrubbing secrets from its URL
+    pub fn new(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().to_string(),
+            url: scrub_url(request.url()),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+        }
+    }
+
+    /// Key requests are matched by
+    fn key(&self) -> String {
+        format!(
+            "{} {}\n{}",
+            self.method,
+            self.url,
+            self.body.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+/// A response as saved in a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// Status code
+    pub status: u16,
+
+    /// Response headers (lowercase names)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Body, as text or (when `base64` is set) base64
+    #[serde(default)]
+    pub body: String,
+
+    /// Whether the body is base64, because it is not UTF-8
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub base64: bool,
+}
+
+impl RecordedResponse {
+    /// Decoded body bytes
+    pub fn body_bytes(&self) -> Vec<u8> {
+        if self.base64 {
+            base64::engine::general_purpose::STANDARD
+                .decode(&self.body)
+                .unwrap_or_default()
+        } else {
+            self.body.clone().into_bytes()
+        }
+    }
+
+    fn to_response(&self, url: &str) -> reqwest::Response {
+        let mut response = http::Response::new(self.body_bytes());
+        *response.status_mut() = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                http::HeaderName::from_bytes(name.as_bytes()),
+                http::HeaderValue::from_str(value),
+            ) {
+                response.headers_mut().insert(name, value);
+            }
+        }
+        if let Ok(url) = url::Url::parse(url) {
+            response.extensions_mut().insert(url);
+        }
+        reqwest::Response::from(response)
+    }
+}
+
+/// One saved exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    /// The request
+    pub request: RecordedRequest,
+
+    /// The response it got
+    pub response: RecordedResponse,
+}
+
+/// Records HTTP exchanges to, or replays them from, a directory
+#[derive(Debug)]
+pub struct HttpRecorder {
+    /// Record or replay
+    mode: ReplayMode,
+
+    /// Directory holding the recordings
+    dir: PathBuf,
+
+    /// Recordings by request key, loaded on first replay
+    recordings: Mutex<Option<HashMap<String, Recording>>>,
+}
+
+impl HttpRecorder {
+    /// Record exchanges into `dir`
+    pub fn record(dir: impl Into<PathBuf>) -> Self {
+        Self::new(ReplayMode::Record, dir.into())
+    }
+
+    /// Replay exchanges from `dir`
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self::new(ReplayMode::Replay, dir.into())
+    }
+
+    fn new(mode: ReplayMode, dir: PathBuf) -> Self {
+        Self {
+            mode,
+            dir,
+            recordings: Mutex::new(None),
+        }
+    }
+
+    /// The recorder used by every [`HttpClient`](super::HttpClient)
+    ///
+    /// Set up from `RESEARCH_MASTER_HTTP_REPLAY` or, failing that,
+    /// `RESEARCH_MASTER_HTTP_RECORD`; `None` when neither is set.
+    pub fn global() -> Option<&'static HttpRecorder> {
+        static RECORDER: OnceLock<Option<HttpRecorder>> = OnceLock::new();
+        RECORDER
+            .get_or_init(|| {
+                let dir = |name| std::env::var(name).ok().filter(|dir| !dir.is_empty());
+                if let Some(dir) = dir(HTTP_REPLAY_ENV_VAR) {
+                    tracing::info!("Replaying HTTP responses from {}", dir);
+                    Some(Self::replay(dir))
+                } else if let Some(dir) = dir(HTTP_RECORD_ENV_VAR) {
+                    tracing::info!("Recording HTTP responses to {}", dir);
+                    Some(Self::record(dir))
+                } else {
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    /// Record or replay
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    /// Directory holding the recordings
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The recording matching a request, if any
+    pub fn find(&self, request: &RecordedRequest) -> Option<Recording> {
+        let mut recordings = self.recordings.lock().unwrap_or_else(|e| e.into_inner());
+        recordings
+            .get_or_insert_with(|| load_recordings(&self.dir))
+            .get(&request.key())
+            .cloned()
+    }
+
+    /// Answer a request from its recording
+    ///
+    /// A request without a recording gets a `404 Not Found` whose body names
+    /// the request, so that a missing fixture fails without being retried.
+    pub fn respond(&self, request: &RecordedRequest) -> reqwest::Response {
+        if let Some(recording) = self.find(request) {
+            return recording.response.to_response(&request.url);
+        }
+        tracing::warn!(
+            "No recorded response for {} {} in {}",
+            request.method,
+            request.url,
+            self.dir.display()
+        );
+        RecordedResponse {
+            status: StatusCode::NOT_FOUND.as_u16(),
+            headers: BTreeMap::from([("content-type".to_string(), "text/plain".to_string())]),
+            body: format!(
+                "No recorded response for {} {} in {}",
+                request.method,
+                request.url,
+                self.dir.display()
+            ),
+            base64: false,
+        }
+        .to_response(&request.url)
+    }
+
+    /// Save a response with its request, and return it for use
+    pub async fn save(
+        &self,
+        request: RecordedRequest,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+
+        let (text, base64) = match std::str::from_utf8(&body) {
+            Ok(text) => (text.to_string(), false),
+            Err(_) => (
+                base64::engine::general_purpose::STANDARD.encode(&body),
+                true,
+            ),
+        };
+        let recording = Recording {
+            response: RecordedResponse {
+                status: status.as_u16(),
+                headers: headers
+                    .iter()
+                    .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+                    .filter_map(|(name, value)| {
+                        Some((name.to_string(), value.to_str().ok()?.to_string()))
+                    })
+                    .collect(),
+                body: text,
+                base64,
+            },
+            request,
+        };
+        if let Err(e) = self.write(&recording) {
+            tracing::warn!("Failed to save HTTP recording: {}", e);
+        }
+
+        let mut rebuilt = http::Response::new(body);
+        *rebuilt.status_mut() = status;
+        *rebuilt.headers_mut() = headers;
+        Ok(reqwest::Response::from(rebuilt))
+    }
+
+    /// Write a recording to `<dir>/<host>/<method>-<hash>.json`
+    fn write(&self, recording: &Recording) -> io::Result<PathBuf> {
+        let request = &recording.request;
+        let host = url::Url::parse(&request.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string());
+        let digest = format!("{:x}", md5::compute(request.key()));
+        let dir = self.dir.join(host);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!(
+            "{}-{}.json",
+            request.method.to_lowercase(),
+            &digest[..16]
+        ));
+        let json = serde_json::to_string_pretty(recording)?;
+        fs::write(&path, json + "\n")?;
+
+        let mut recordings = self.recordings.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(recordings) = recordings.as_mut() {
+            recordings.insert(request.key(), recording.clone());
+        }
+        Ok(path)
+    }
+}
+
+/// URL with the values of secret query parameters replaced by `REDACTED`
+pub fn scrub_url(url: &url::Url) -> String {
+    if url.query().is_none() {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            if SECRET_PARAMS.contains(&name.to_lowercase().as_str()) {
+                (name.into_owned(), REDACTED.to_string())
+            } else {
+                (name.into_owned(), value.into_owned())
+            }
+        })
+        .collect();
+    let mut scrubbed = url.clone();
+    scrubbed.query_pairs_mut().clear().extend_pairs(pairs);
+    scrubbed.to_string()
+}
+
+/// Text with [`scrub_url`] applied to every URL in it, such as the URL in a
+/// request error
+pub fn scrub_urls(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = ["http://", "https://"]
+        .iter()
+        .filter_map(|scheme| rest.find(scheme))
+        .min()
+    {
+        out.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '(' | ')' | '<' | '>'))
+            .unwrap_or(candidate.len());
+        match url::Url::parse(&candidate[..end]) {
+            Ok(url) => out.push_str(&scrub_url(&url)),
+            Err(_) => out.push_str(&candidate[..end]),
+        }
+        rest = &candidate[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Every recording under `dir`, by request key
+fn load_recordings(dir: &Path) -> HashMap<String, Recording> {
+    let mut recordings = HashMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let recording = fs::read_to_string(&path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<Recording>(&json).ok());
+            match recording {
+                Some(mut recording) => {
+                    // Hand-written recordings may hold unscrubbed URLs
+                    if let Ok(url) = url::Url::parse(&recording.request.url) {
+                        recording.request.url = scrub_url(&url);
+                    }
+                    recordings.insert(recording.request.key(), recording);
+                }
+                None => tracing::warn!("Skipping invalid HTTP recording {}", path.display()),
+            }
+        }
+    }
+    tracing::debug!("Loaded {} HTTP recordings", recordings.len());
+    recordings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn request(url: &str) -> RecordedRequest {
+        let url = url::Url::parse(url).unwrap();
+        RecordedRequest {
+            method: "GET".to_string(),
+            url: scrub_url(&url),
+            body: None,
+        }
+    }
+
+    fn response(status: u16, body: &'static [u8]) -> reqwest::Response {
+        let mut response = http::Response::new(body);
+        *response.status_mut() = StatusCode::from_u16(status).unwrap();
+        response.headers_mut().insert(
+            "content-type",
+            http::HeaderValue::from_static("application/json"),
+        );
+        response
+            .headers_mut()
+            .insert("set-cookie", http::HeaderValue::from_static("session=1"));
+        reqwest::Response::from(response)
+    }
+
+    #[test]
+    fn test_scrub_url() {
+        let url = url::Url::parse(
+            "https://api.example.org/works?q=graph&api_key=secret&mailto=me%40example.org",
+        )
+        .unwrap();
+        assert_eq!(
+            scrub_url(&url),
+            "https://api.example.org/works?q=graph&api_key=REDACTED&mailto=REDACTED"
+        );
+        let url = url::Url::parse("https://api.example.org/works/W1").unwrap();
+        assert_eq!(scrub_url(&url), "https://api.example.org/works/W1");
+    }
+
+    #[test]
+    fn test_scrub_urls() {
+        assert_eq!(
+            scrub_urls(
+                "error sending request for url (https://ieeexploreapi.ieee.org/api/v1/search/articles?apikey=secret&querytext=x): timed out"
+            ),
+            "error sending request for url (https://ieeexploreapi.ieee.org/api/v1/search/articles?apikey=REDACTED&querytext=x): timed out"
+        );
+        assert_eq!(
+            scrub_urls("HTTP 503 from http://a.example"),
+            "HTTP 503 from http://a.example/"
+        );
+        assert_eq!(scrub_urls("no URL here"), "no URL here");
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = TempDir::new().unwrap();
+        let recorder = HttpRecorder::record(dir.path());
+        let saved = recorder
+            .save(
+                request("https://api.example.org/works?q=graph&api_key=secret"),
+                response(200, br#"{"results": []}"#),
+            )
+            .await
+            .unwrap();
+        assert_eq!(saved.text().await.unwrap(), r#"{"results": []}"#);
+        recorder
+            .save(
+                request("https://api.example.org/pdf/1"),
+                response(200, b"%PDF-1.4\xff\xfe"),
+            )
+            .await
+            .unwrap();
+
+        let files: Vec<_> = fs::read_dir(dir.path().join("api.example.org"))
+            .unwrap()
+            .collect();
+        assert_eq!(files.len(), 2);
+        let json: String = files
+            .into_iter()
+            .map(|file| fs::read_to_string(file.unwrap().path()).unwrap())
+            .collect();
+        assert!(!json.contains("secret"));
+        assert!(!json.contains("set-cookie"));
+
+        // Another key matches the same recording
+        let replayer = HttpRecorder::replay(dir.path());
+        let replayed = replayer.respond(&request(
+            "https://api.example.org/works?q=graph&api_key=other",
+        ));
+        assert_eq!(replayed.status(), StatusCode::OK);
+        assert_eq!(
+            replayed.headers()["content-type"],
+            "application/json".parse::<http::HeaderValue>().unwrap()
+        );
+        assert_eq!(replayed.text().await.unwrap(), r#"{"results": []}"#);
+        let pdf = replayer.respond(&request("https://api.example.org/pdf/1"));
+        assert_eq!(pdf.bytes().await.unwrap().as_ref(), b"%PDF-1.4\xff\xfe");
+
+        let missing = replayer.respond(&request("https://api.example.org/works?q=other"));
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        assert!(missing
+            .text()
+            .await
+            .unwrap()
+            .contains("No recorded response for GET"));
+    }
+}
@@ -0,0 +1,576 @@
+//! Process-wide counters for monitoring, in Prometheus text format.
+//!
+//! Sources, caches, the HTTP client and MCP tools report to
+//! [`Metrics::global`]; `research-master mcp --http --metrics-port <port>`
+//! serves the result at `/metrics`, next to a `/healthz` report built from
+//! [`Metrics::source_health`].
+//!
+//! ```rust
+//! use research_master::utils::Metrics;
+//! use std::time::Duration;
+//!
+//! let metrics = Metrics::new();
+//! metrics.record_source_call("arxiv", "search", Duration::from_millis(120), None);
+//! assert!(metrics.render().contains(r#"research_master_source_requests_total{source="arxiv",operation="search",result="ok"} 1"#));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, Instant, SystemTime};
+
+use super::circuit_breaker::{CircuitBreaker, CircuitState};
+use crate::sources::SourceError;
+
+/// Upper bounds (seconds) of the source latency histogram buckets
+const LATENCY_BUCKETS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// Consecutive failures after which a source is reported as failing
+const FAILING_AFTER: u32 = 3;
+
+/// Waits shorter than this are not counted as rate-limiter waits
+const MIN_COUNTED_WAIT: Duration = Duration::from_millis(1);
+
+/// Latency histogram with cumulative buckets
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Health of one source, as seen from the calls made to it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceHealth {
+    /// Number of calls
+    pub requests: u64,
+
+    /// Number of calls that failed because of the source
+    pub failures: u64,
+
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+
+    /// When the last call succeeded
+    pub last_success: Option<SystemTime>,
+
+    /// When the last failure happened, and its message
+    pub last_failure: Option<(SystemTime, String)>,
+
+    /// State of the source's circuit breaker, if it has one
+    pub circuit: Option<CircuitState>,
+}
+
+impl SourceHealth {
+    /// Whether the source keeps failing or its circuit is open
+    pub fn is_failing(&self) -> bool {
+        self.consecutive_failures >= FAILING_AFTER || self.circuit == Some(CircuitState::Open)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Calls by (source, operation, result)
+    source_requests: BTreeMap<(String, String, &'static str), u64>,
+    /// Latencies by (source, operation)
+    source_latency: BTreeMap<(String, String), Histogram>,
+    /// Errors by (source, `SourceError` variant)
+    source_errors: BTreeMap<(String, &'static str), u64>,
+    /// Health by source
+    source_health: BTreeMap<String, SourceHealth>,
+    /// Cache lookups by (namespace, result)
+    cache: BTreeMap<(&'static str, &'static str), u64>,
+    /// Rate-limiter waits and seconds waited by host
+    rate_limit_waits: BTreeMap<String, (u64, f64)>,
+    /// Tool calls by (tool, result)
+    tool_calls: BTreeMap<(String, &'static str), u64>,
+    /// Downloaded bytes by source
+    download_bytes: BTreeMap<String, u64>,
+}
+
+/// Counters, histograms and gauges describing what the process has done
+#[derive(Debug)]
+pub struct Metrics {
+    /// When counting started
+    started: Instant,
+
+    /// Everything counted so far
+    state: Mutex<State>,
+
+    /// Circuit breakers whose state is reported
+    breakers: Mutex<Vec<Weak<CircuitBreaker>>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Create an empty set of metrics
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            state: Mutex::new(State::default()),
+            breakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The metrics shared by the whole process
+    pub fn global() -> &'static Metrics {
+        static METRICS: OnceLock<Metrics> = OnceLock::new();
+        METRICS.get_or_init(Metrics::new)
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Count a call to a source and how long it took
+    pub fn record_source_call(
+        &self,
+        source: &str,
+        operation: &str,
+        elapsed: Duration,
+        error: Option<&SourceError>,
+    ) {
+        let mut state = self.state();
+        let result = if error.is_some() { "error" } else { "ok" };
+        *state
+            .source_requests
+            .entry((source.to_string(), operation.to_string(), result))
+            .or_default() += 1;
+        state
+            .source_latency
+            .entry((source.to_string(), operation.to_string()))
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+        if let Some(error) = error {
+            *state
+                .source_errors
+                .entry((source.to_string(), error.kind()))
+                .or_default() += 1;
+        }
+
+        let health = state.source_health.entry(source.to_string()).or_default();
+        health.requests += 1;
+        match error {
+            Some(error) if error.is_source_failure() => {
+                health.failures += 1;
+                health.consecutive_failures += 1;
+                health.last_failure = Some((SystemTime::now(), error.to_string()));
+            }
+            Some(_) => {}
+            None => {
+                health.consecutive_failures = 0;
+                health.last_success = Some(SystemTime::now());
+            }
+        }
+    }
+
+    /// Count a cache lookup (`result` is e.g. `hit`, `miss` or `expired`)
+    pub fn record_cache(&self, namespace: &'static str, result: &'static str) {
+        *self.state().cache.entry((namespace, result)).or_default() += 1;
+    }
+
+    /// Count time a request to `host` spent waiting for a rate limiter
+    pub fn record_rate_limit_wait(&self, host: &str, waited: Duration) {
+        if waited < MIN_COUNTED_WAIT {
+            return;
+        }
+        let mut state = self.state();
+        let (waits, seconds) = state.rate_limit_waits.entry(host.to_string()).or_default();
+        *waits += 1;
+        *seconds += waited.as_secs_f64();
+    }
+
+    /// Count an MCP tool call
+    pub fn record_tool_call(&self, tool: &str, ok: bool) {
+        let result = if ok { "ok" } else { "error" };
+        *self
+            .state()
+            .tool_calls
+            .entry((tool.to_string(), result))
+            .or_default() += 1;
+    }
+
+    /// Count bytes downloaded from a source
+    pub fn record_download(&self, source: &str, bytes: u64) {
+        *self
+            .state()
+            .download_bytes
+            .entry(source.to_string())
+            .or_default() += bytes;
+    }
+
+    /// Report the state of a circuit breaker for as long as it exists
+    pub fn watch_circuit_breaker(&self, breaker: &Arc<CircuitBreaker>) {
+        let mut breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        breakers.retain(|watched| watched.strong_count() > 0);
+        breakers.push(Arc::downgrade(breaker));
+    }
+
+    /// Name and state of every live circuit breaker
+    fn circuit_states(&self) -> BTreeMap<String, CircuitState> {
+        let breakers = self.breakers.lock().unwrap_or_else(|e| e.into_inner());
+        breakers
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|breaker| (breaker.name().to_string(), breaker.state()))
+            .collect()
+    }
+
+    /// Health of every source that has been called or has a circuit breaker
+    pub fn source_health(&self) -> BTreeMap<String, SourceHealth> {
+        let mut health = self.state().source_health.clone();
+        for (source, state) in self.circuit_states() {
+            health.entry(source).or_default().circuit = Some(state);
+        }
+        health
+    }
+
+    /// Time since counting started
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Everything counted so far, in Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let circuits = self.circuit_states();
+        let state = self.state();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "research_master_uptime_seconds",
+            "gauge",
+            "Seconds since the process started counting",
+        );
+        let _ = writeln!(
+            out,
+            "research_master_uptime_seconds {}",
+            self.uptime().as_secs_f64()
+        );
+
+        header(
+            &mut out,
+            "research_master_source_requests_total",
+            "counter",
+            "Calls to research sources",
+        );
+        for ((source, operation, result), count) in &state.source_requests {
+            sample(
+                &mut out,
+                "research_master_source_requests_total",
+                &[
+                    ("source", source),
+                    ("operation", operation),
+                    ("result", result),
+                ],
+                *count,
+            );
+        }
+
+        header(
+            &mut out,
+            "research_master_source_request_duration_seconds",
+            "histogram",
+            "Time taken by calls to research sources",
+        );
+        for ((source, operation), histogram) in &state.source_latency {
+            let labels = [
+                ("source", source.as_str()),
+                ("operation", operation.as_str()),
+            ];
+            for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                let le = bound.to_string();
+                sample(
+                    &mut out,
+                    "research_master_source_request_duration_seconds_bucket",
+                    &[labels[0], labels[1], ("le", &le)],
+                    count,
+                );
+            }
+            sample(
+                &mut out,
+                "research_master_source_request_duration_seconds_bucket",
+                &[labels[0], labels[1], ("le", "+Inf")],
+                histogram.count,
+            );
+            sample(
+                &mut out,
+                "research_master_source_request_duration_seconds_sum",
+                &labels,
+                histogram.sum,
+            );
+            sample(
+                &mut out,
+                "research_master_source_request_duration_seconds_count",
+                &labels,
+                histogram.count,
+            );
+        }
+
+        header(
+            &mut out,
+            "research_master_source_errors_total",
+            "counter",
+            "Failed calls to research sources, by error kind",
+        );
+        for ((source, kind), count) in &state.source_errors {
+            sample(
+                &mut out,
+                "research_master_source_errors_total",
+                &[("source", source), ("kind", kind)],
+                *count,
+            );
+        }
+
+        header(
+            &mut out,
+            "research_master_circuit_breaker_state",
+            "gauge",
+            "Circuit breaker state of a source (1 for the current state)",
+        );
+        for (source, current) in &circuits {
+            for (state, name) in [
+                (CircuitState::Closed, "closed"),
+                (CircuitState::Open, "open"),
+                (CircuitState::HalfOpen, "half_open"),
+            ] {
+                sample(
+                    &mut out,
+                    "research_master_circuit_breaker_state",
+                    &[("source", source), ("state", name)],
+                    u8::from(*current == state),
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "research_master_cache_requests_total",
+            "counter",
+            "Cache lookups, by namespace and result",
+        );
+        for ((namespace, result), count) in &state.cache {
+            sample(
+                &mut out,
+                "research_master_cache_requests_total",
+                &[("namespace", namespace), ("result", result)],
+                *count,
+            );
+        }
+
+        header(
+            &mut out,
+            "research_master_rate_limit_waits_total",
+            "counter",
+            "Requests that waited for a rate limiter, by host",
+        );
+        for (host, (waits, _)) in &state.rate_limit_waits {
+            sample(
+                &mut out,
+                "research_master_rate_limit_waits_total",
+                &[("host", host)],
+                *waits,
+            );
+        }
+        header(
+            &mut out,
+            "research_master_rate_limit_wait_seconds_total",
+            "counter",
+            "Seconds spent waiting for rate limiters, by host",
+        );
+        for (host, (_, seconds)) in &state.rate_limit_waits {
+            sample(
+                &mut out,
+                "research_master_rate_limit_wait_seconds_total",
+                &[("host", host)],
+                *seconds,
+            );
+        }
+
+        header(
+            &mut out,
+            "research_master_tool_calls_total",
+            "counter",
+            "MCP tool invocations, by tool and result",
+        );
+        for ((tool, result), count) in &state.tool_calls {
+            sample(
+                &mut out,
+                "research_master_tool_calls_total",
+                &[("tool", tool), ("result", result)],
+                *count,
+            );
+        }
+
+        header(
+            &mut out,
+            "research_master_download_bytes_total",
+            "counter",
+            "Bytes of papers downloaded, by source",
+        );
+        for (source, bytes) in &state.download_bytes {
+            sample(
+                &mut out,
+                "research_master_download_bytes_total",
+                &[("source", source)],
+                *bytes,
+            );
+        }
+
+        out
+    }
+}
+
+/// Write the `# HELP` and `# TYPE` lines of a metric
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Write one sample line
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+        .collect();
+    let _ = writeln!(out, "{}{{{}}} {}", name, labels.join(","), value);
+}
+
+/// Escape a label value as the exposition format requires
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_calls() {
+        let metrics = Metrics::new();
+        metrics.record_source_call("arxiv", "search", Duration::from_millis(80), None);
+        metrics.record_source_call(
+            "arxiv",
+            "search",
+            Duration::from_secs(3),
+            Some(&SourceError::Network("timed out".into())),
+        );
+
+        let text = metrics.render();
+        assert!(text.contains(
+            r#"research_master_source_requests_total{source="arxiv",operation="search",result="ok"} 1"#
+        ));
+        assert!(text.contains(
+            r#"research_master_source_requests_total{source="arxiv",operation="search",result="error"} 1"#
+        ));
+        assert!(text.contains(
+            r#"research_master_source_request_duration_seconds_bucket{source="arxiv",operation="search",le="0.1"} 1"#
+        ));
+        assert!(text.contains(
+            r#"research_master_source_request_duration_seconds_bucket{source="arxiv",operation="search",le="5"} 2"#
+        ));
+        assert!(text.contains(
+            r#"research_master_source_request_duration_seconds_count{source="arxiv",operation="search"} 2"#
+        ));
+        assert!(text
+            .contains(r#"research_master_source_errors_total{source="arxiv",kind="network"} 1"#));
+
+        let health = &metrics.source_health()["arxiv"];
+        assert_eq!(health.requests, 2);
+        assert_eq!(health.consecutive_failures, 1);
+        assert!(!health.is_failing());
+    }
+
+    #[test]
+    fn test_failing_source() {
+        let metrics = Metrics::new();
+        let failure = SourceError::Api("HTTP 500".into());
+        for _ in 0..FAILING_AFTER {
+            metrics.record_source_call("crossref", "get_by_doi", Duration::ZERO, Some(&failure));
+        }
+        assert!(metrics.source_health()["crossref"].is_failing());
+
+        // Not finding a paper says nothing about the source
+        let not_found = SourceError::NotFound("10.1/x".into());
+        metrics.record_source_call("crossref", "get_by_doi", Duration::ZERO, Some(&not_found));
+        assert!(metrics.source_health()["crossref"].is_failing());
+
+        metrics.record_source_call("crossref", "get_by_doi", Duration::ZERO, None);
+        assert!(!metrics.source_health()["crossref"].is_failing());
+    }
+
+    #[test]
+    fn test_circuit_breaker_state() {
+        let metrics = Metrics::new();
+        let breaker = Arc::new(CircuitBreaker::new("semantic", 1, Duration::from_secs(60)));
+        metrics.watch_circuit_breaker(&breaker);
+        breaker.record_failure();
+
+        let text = metrics.render();
+        assert!(text.contains(
+            r#"research_master_circuit_breaker_state{source="semantic",state="open"} 1"#
+        ));
+        assert!(text.contains(
+            r#"research_master_circuit_breaker_state{source="semantic",state="closed"} 0"#
+        ));
+        assert!(metrics.source_health()["semantic"].is_failing());
+
+        drop(breaker);
+        assert!(metrics.source_health().is_empty());
+    }
+
+    #[test]
+    fn test_counters() {
+        let metrics = Metrics::new();
+        metrics.record_cache("http", "hit");
+        metrics.record_cache("http", "hit");
+        metrics.record_cache("searches", "miss");
+        metrics.record_rate_limit_wait("api.openalex.org", Duration::from_millis(250));
+        metrics.record_rate_limit_wait("api.openalex.org", Duration::ZERO);
+        metrics.record_tool_call("search_papers", true);
+        metrics.record_download("arxiv", 1024);
+        metrics.record_download("arxiv", 1024);
+
+        let text = metrics.render();
+        assert!(text
+            .contains(r#"research_master_cache_requests_total{namespace="http",result="hit"} 2"#));
+        assert!(text.contains(
+            r#"research_master_cache_requests_total{namespace="searches",result="miss"} 1"#
+        ));
+        assert!(
+            text.contains(r#"research_master_rate_limit_waits_total{host="api.openalex.org"} 1"#)
+        );
+        assert!(text.contains(
+            r#"research_master_rate_limit_wait_seconds_total{host="api.openalex.org"} 0.25"#
+        ));
+        assert!(text
+            .contains(r#"research_master_tool_calls_total{tool="search_papers",result="ok"} 1"#));
+        assert!(text.contains(r#"research_master_download_bytes_total{source="arxiv"} 2048"#));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a "b" \c"#), r#"a \"b\" \\c"#);
+        assert_eq!(escape_label("line\nbreak"), r"line\nbreak");
+    }
+}
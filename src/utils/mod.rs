@@ -7,11 +7,17 @@
 //! - [`DuplicateStrategy`]: Strategy for handling duplicates (KeepFirst, KeepLast, Mark)
 //! - [`HttpClient`]: HTTP client with built-in rate limiting
 //! - [`RateLimitedRequestBuilder`]: Builder for rate-limited HTTP requests
+//! - [`HostLimiter`]: Per-host pacing from rate-limit headers, shared across processes
+//! - [`CacheStore`]: Single-file cache database with namespaces, TTLs and LRU eviction
+//! - [`HttpCache`]: Persistent HTTP response cache with conditional revalidation
+//! - [`HttpRecorder`]: Record HTTP exchanges to fixture files and replay them offline
+//! - [`Metrics`]: Process-wide request, cache and tool counters in Prometheus format
 //! - [`extract_text`]: Extract text content from PDF files
 //! - [`is_available`]: Check if PDF extraction is available (requires poppler)
 //! - [`PdfExtractError`]: Errors that can occur during PDF extraction
 //! - [`RetryConfig`]: Configuration for retry logic with exponential backoff
 //! - [`with_retry`]: Execute an operation with automatic retry on transient errors
+//! - [`xml_escape`]: Escape text for XML content and attributes
 //!
 //! # Deduplication
 //!
@@ -28,6 +34,15 @@
 //! The HTTP client provides built-in rate limiting using the governor crate.
 //! Each source can be configured with different rate limits via environment
 //! variables (e.g., `SEMANTIC_SCHOLAR_RPM` for requests per minute).
+//! On top of that, requests to each host are paced by [`HostLimiter`], which
+//! follows `Retry-After` and `X-RateLimit-*` headers and shares each host's
+//! schedule with other research-master processes through the cache directory.
+//! While the cache is enabled, GET responses are kept in [`HttpCache`] as
+//! `Cache-Control` allows and revalidated with `If-None-Match` /
+//! `If-Modified-Since` once stale.
+//! With `RESEARCH_MASTER_HTTP_RECORD` or `RESEARCH_MASTER_HTTP_REPLAY` set,
+//! [`HttpRecorder`] saves every exchange to fixture files, or answers requests
+//! from them without the network.
 //!
 //! # Retry with Backoff
 //!
@@ -39,18 +54,24 @@
 //! ```
 
 mod cache;
+mod cache_store;
 mod circuit_breaker;
 mod dedup;
 mod display;
 mod history;
 mod http;
+mod http_cache;
+mod http_replay;
+mod metrics;
 mod cite;
 mod pdf;
 mod progress;
+mod rate_limit;
 mod retry;
 mod streaming;
 mod update;
 mod validate;
+mod xml;
 
 pub use streaming::{
     collect_papers, filter_by_year, paper_stream, ConcurrentPaperStream, FilterByYearStream,
@@ -58,13 +79,17 @@ pub use streaming::{
 };
 
 pub use cache::{CacheResult, CacheService, CacheStats};
+pub use cache_store::{
+    CacheEntry, CacheFilter, CacheNamespace, CacheStore, CacheStoreError, EntryInfo,
+    NamespaceStats, CACHE_DB_FILE, SCHEMA_VERSION,
+};
 pub use circuit_breaker::{CircuitBreaker, CircuitBreakerManager, CircuitResult, CircuitState};
 pub use dedup::{deduplicate_papers, fast_deduplicate_papers, find_duplicates, DuplicateStrategy};
 pub use display::{
-    calculate_column_widths, calculate_dynamic_column_widths, format_authors, format_source,
-    format_title, format_year, get_paper_table_columns, is_terminal, terminal_height,
-    terminal_info, terminal_width, truncate_at_word, truncate_with_ellipsis, ColumnConfig,
-    Terminal,
+    calculate_column_widths, calculate_dynamic_column_widths, format_age, format_authors,
+    format_source, format_title, format_year, get_paper_table_columns, is_terminal,
+    terminal_height, terminal_info, terminal_width, truncate_at_word, truncate_with_ellipsis,
+    ColumnConfig, Terminal,
 };
 pub use history::{HistoryEntry, HistoryEntryType, HistoryService};
 pub use http::{apply_cli_proxy_args, create_proxy_config_from_cli, get_user_agent, HttpClient, RateLimitedRequestBuilder, ProxyConfig, USER_AGENT_ENV_VAR};
@@ -73,6 +98,13 @@ pub use pdf::{
     ExtractionInfo, ExtractionMethod, PdfExtractError,
 };
 pub use progress::{ProgressReporter, SharedProgress};
+pub use http_cache::{CacheControl, CachedResponse, HttpCache, HttpCacheEvent, HttpCacheStats};
+pub use metrics::{Metrics, SourceHealth};
+pub use http_replay::{
+    scrub_urls, HttpRecorder, RecordedRequest, RecordedResponse, Recording, ReplayMode,
+    HTTP_RECORD_ENV_VAR, HTTP_REPLAY_ENV_VAR,
+};
+pub use rate_limit::{HostLimiter, HostState, RateLimitHeaders, SHARED_STATE_ENV_VAR};
 pub use retry::{
     api_retry_config, strict_rate_limit_retry_config, with_retry, with_retry_detailed, RetryConfig,
     RetryResult, TransientError,
@@ -86,4 +118,5 @@ pub use update::{
 pub use validate::{
     sanitize_filename, sanitize_paper_id, validate_doi, validate_url, ValidationError,
 };
+pub use xml::xml_escape;
 pub use cite::{format_citation, get_structured_citation, CitationStyle, StructuredCitation};
@@ -0,0 +1,639 @@
+//! Request pacing driven by the rate-limit headers servers send, shared
+//! across processes.
+//!
+//! Every [`HttpClient`](super::HttpClient) request waits for its host's turn
+//! in the process-wide [`HostLimiter`], which:
+//!
+//! - spaces requests by the client's configured rate (and by the published
+//!   limit of hosts such as NCBI E-utilities)
+//! - holds a host back until `Retry-After` (or `X-RateLimit-Reset`) has passed
+//!   after a 429 or 503, backing off further on repeated 429s without one
+//! - spreads the requests left in a window (`X-RateLimit-Remaining`) over the
+//!   time until it resets, so the quota is not used up before the reset
+//!
+//! The schedule of each host is kept in `rate_limits.json` in the cache
+//! directory, so concurrent CLI invocations and the MCP server share one quota
+//! per source instead of each using the full quota. The file is read and
+//! written on the blocking thread pool under an OS lock on
+//! `rate_limits.json.lock`, which is released even if the process dies, and
+//! rewritten only when a schedule changes. Set `shared_state = false` under
+//! `[rate_limits]` (or `RESEARCH_MASTER_RATE_LIMITS_SHARED_STATE=false`) to
+//! keep the schedule in memory only.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::config::RateLimitConfig;
+
+/// Environment variable to turn sharing the schedule across processes off
+pub const SHARED_STATE_ENV_VAR: &str = "RESEARCH_MASTER_RATE_LIMITS_SHARED_STATE";
+
+/// File holding the shared schedule, in the cache directory
+const STATE_FILE: &str = "rate_limits.json";
+
+/// Published request limits (per second) of hosts without a source setting
+const HOST_LIMITS: &[(&str, f64)] = &[("eutils.ncbi.nlm.nih.gov", 3.0)];
+
+/// Wait after a 429 without `Retry-After`, doubled on each one in a row
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Longest wait before a request is sent anyway (and answered by the server)
+const MAX_WAIT: Duration = Duration::from_secs(120);
+
+/// The limiter used by every [`HttpClient`](super::HttpClient)
+static GLOBAL: OnceLock<HostLimiter> = OnceLock::new();
+
+/// Rate-limit headers of one response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitHeaders {
+    /// `Retry-After`, in seconds or as an HTTP date
+    pub retry_after: Option<Duration>,
+
+    /// `X-RateLimit-Remaining` (or `RateLimit-Remaining`)
+    pub remaining: Option<u64>,
+
+    /// `X-RateLimit-Reset` (or `RateLimit-Reset`), in seconds or as a Unix time
+    pub reset: Option<Duration>,
+}
+
+impl RateLimitHeaders {
+    /// Read the rate-limit headers of a response
+    pub fn parse(headers: &HeaderMap) -> Self {
+        let header = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| headers.get(*name)?.to_str().ok())
+                .map(str::trim)
+        };
+        let now = unix_seconds();
+
+        let retry_after = header(&["retry-after"]).and_then(|value| {
+            match value.parse::<f64>() {
+                Ok(seconds) => Some(seconds),
+                Err(_) => chrono::DateTime::parse_from_rfc2822(value)
+                    .ok()
+                    .map(|date| date.timestamp() as f64 - now),
+            }
+            .map(|seconds| Duration::from_secs_f64(seconds.max(0.0)))
+        });
+        let remaining = header(&["x-ratelimit-remaining", "ratelimit-remaining"])
+            .and_then(|value| value.parse::<f64>().ok())
+            .map(|n| n.max(0.0) as u64);
+        let reset = header(&["x-ratelimit-reset", "ratelimit-reset"])
+            .and_then(|value| value.parse::<f64>().ok())
+            .map(|value| {
+                // Large values are Unix times rather than seconds to wait
+                let seconds = if value > 1_000_000_000.0 {
+                    value - now
+                } else {
+                    value
+                };
+                Duration::from_secs_f64(seconds.max(0.0))
+            });
+
+        Self {
+            retry_after,
+            remaining,
+            reset,
+        }
+    }
+}
+
+/// Schedule of one host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostState {
+    /// Earliest time the next request may be sent (Unix milliseconds)
+    #[serde(default)]
+    pub next_at: u64,
+
+    /// Time until which the server asked for no requests (Unix milliseconds)
+    #[serde(default)]
+    pub blocked_until: u64,
+
+    /// 429 responses in a row without `Retry-After`
+    #[serde(default)]
+    pub strikes: u32,
+}
+
+impl HostState {
+    fn is_current(&self, now: u64) -> bool {
+        self.next_at > now || self.blocked_until > now || self.strikes > 0
+    }
+}
+
+/// Per-host request schedule, shared by every client of the process and,
+/// through a lock-protected file, by other processes
+#[derive(Debug, Default)]
+pub struct HostLimiter {
+    schedule: Arc<Schedule>,
+}
+
+#[derive(Debug, Default)]
+struct Schedule {
+    state_file: Option<PathBuf>,
+    hosts: Mutex<HashMap<String, HostState>>,
+}
+
+impl HostLimiter {
+    /// A limiter keeping the schedule in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A limiter sharing the schedule through `path` with other processes
+    pub fn with_state_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            schedule: Arc::new(Schedule {
+                state_file: Some(path.into()),
+                hosts: Mutex::default(),
+            }),
+        }
+    }
+
+    /// The limiter used by every [`HttpClient`](super::HttpClient)
+    ///
+    /// Set up from the environment unless [`HostLimiter::init_global`] was
+    /// called first. It shares the schedule through the cache directory unless
+    /// `shared_state` is off or the test mode is on.
+    pub fn global() -> &'static HostLimiter {
+        GLOBAL.get_or_init(|| Self::from_config(&crate::config::get_config().rate_limits))
+    }
+
+    /// Set up the limiter returned by [`HostLimiter::global`] from a loaded
+    /// configuration; returns `false` if it was already set up
+    pub fn init_global(config: &RateLimitConfig) -> bool {
+        let mut initialized = false;
+        GLOBAL.get_or_init(|| {
+            initialized = true;
+            Self::from_config(config)
+        });
+        initialized
+    }
+
+    fn from_config(config: &RateLimitConfig) -> Self {
+        let test_mode = std::env::var("RESEARCH_MASTER_TEST_MODE")
+            .map(|value| value.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if config.shared_state && !test_mode {
+            Self::with_state_file(crate::config::default_cache_dir().join(STATE_FILE))
+        } else {
+            Self::new()
+        }
+    }
+
+    /// The file the schedule is shared through, if any
+    pub fn state_file(&self) -> Option<&Path> {
+        self.schedule.state_file.as_deref()
+    }
+
+    /// Wait for the host's next turn, `min_interval` after the previous one
+    pub async fn wait(&self, host: &str, min_interval: Option<Duration>) {
+        let owned = host.to_string();
+        let wait = self
+            .run(host, move |schedule| schedule.reserve(&owned, min_interval))
+            .await;
+        if !wait.is_zero() {
+            tracing::debug!("Waiting {:?} for the rate limit of {}", wait, host);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Adjust the host's schedule to the status and headers of a response
+    pub async fn observe(&self, host: &str, status: StatusCode, headers: &HeaderMap) {
+        let limits = RateLimitHeaders::parse(headers);
+        if !is_limited(status) && limits == RateLimitHeaders::default() {
+            // Nothing to learn, and no need to touch the shared file
+            let strikes = self
+                .schedule
+                .hosts
+                .lock()
+                .ok()
+                .and_then(|hosts| hosts.get(host).map(|state| state.strikes));
+            if strikes.unwrap_or(0) == 0 {
+                return;
+            }
+        }
+        let owned = host.to_string();
+        self.run(host, move |schedule| {
+            schedule.observe(&owned, status, limits)
+        })
+        .await;
+    }
+
+    /// Take the host's next turn and return how long to wait for it
+    ///
+    /// Blocks while the shared file is read and written; async code uses
+    /// [`wait`](Self::wait).
+    pub fn reserve(&self, host: &str, min_interval: Option<Duration>) -> Duration {
+        self.schedule.reserve(host, min_interval)
+    }
+
+    /// Current schedule of a host
+    pub fn host_state(&self, host: &str) -> HostState {
+        self.schedule
+            .update(host, |state, _| *state)
+            .unwrap_or_default()
+    }
+
+    /// Run `f` on the schedule, on the blocking thread pool when it reads and
+    /// writes the shared file
+    async fn run<T>(&self, host: &str, f: impl FnOnce(&Schedule) -> T + Send + 'static) -> T
+    where
+        T: Default + Send + 'static,
+    {
+        if self.schedule.shared_file(host).is_none() {
+            return f(&self.schedule);
+        }
+        let schedule = Arc::clone(&self.schedule);
+        tokio::task::spawn_blocking(move || f(&schedule))
+            .await
+            .unwrap_or_else(|e| {
+                tracing::debug!("Rate-limit state update failed: {}", e);
+                T::default()
+            })
+    }
+}
+
+impl Schedule {
+    fn reserve(&self, host: &str, min_interval: Option<Duration>) -> Duration {
+        let interval = min_interval
+            .into_iter()
+            .chain(host_limit(host))
+            .max()
+            .unwrap_or_default();
+        let mut slot = 0;
+        self.update(host, |state, now| {
+            slot = now.max(state.next_at).max(state.blocked_until);
+            // Never wait longer than MAX_WAIT: the server answers again after that
+            slot = slot.min(now + MAX_WAIT.as_millis() as u64);
+            state.next_at = slot + interval.as_millis() as u64;
+            now
+        })
+        .map(|now| Duration::from_millis(slot.saturating_sub(now)))
+        .unwrap_or_default()
+    }
+
+    fn observe(&self, host: &str, status: StatusCode, limits: RateLimitHeaders) {
+        let limited = is_limited(status);
+        self.update(host, |state, now| {
+            let at = |wait: Duration| now + wait.min(MAX_WAIT).as_millis() as u64;
+            if limited {
+                let wait = match limits.retry_after.or(limits.reset) {
+                    Some(wait) => {
+                        state.strikes = 0;
+                        wait
+                    }
+                    None if status == StatusCode::TOO_MANY_REQUESTS => {
+                        let wait = DEFAULT_BACKOFF * 2u32.pow(state.strikes.min(4));
+                        state.strikes += 1;
+                        wait
+                    }
+                    None => Duration::ZERO,
+                };
+                state.blocked_until = state.blocked_until.max(at(wait));
+                return;
+            }
+
+            state.strikes = 0;
+            match (limits.remaining, limits.reset) {
+                (Some(0), reset) => {
+                    let wait = reset.unwrap_or(Duration::from_secs(1));
+                    state.blocked_until = state.blocked_until.max(at(wait));
+                }
+                // Spread what is left of the window over the time until it resets
+                (Some(remaining), Some(reset)) => {
+                    let spacing = reset / (remaining + 1).min(u32::MAX as u64) as u32;
+                    state.next_at = state.next_at.max(at(spacing));
+                }
+                _ => {}
+            }
+        });
+    }
+
+    /// The file the host's schedule is shared through, if any (local hosts
+    /// are not shared)
+    fn shared_file(&self, host: &str) -> Option<&Path> {
+        self.state_file.as_deref().filter(|_| !is_local(host))
+    }
+
+    /// Apply `f` to the host's state, reading the shared file when there is
+    /// one and writing it back if a schedule changed
+    fn update<T>(&self, host: &str, f: impl FnOnce(&mut HostState, u64) -> T) -> Option<T> {
+        let mut hosts = self.hosts.lock().ok()?;
+        let now = unix_millis();
+
+        let shared = self.shared_file(host);
+        let lock = shared.and_then(FileLock::acquire);
+        let mut saved = None;
+        if let (Some(path), Some(_)) = (shared, &lock) {
+            match read_state(path) {
+                Ok(state) => {
+                    hosts.retain(|host, _| is_local(host));
+                    hosts.extend(state.iter().map(|(host, state)| (host.clone(), *state)));
+                    saved = Some(state);
+                }
+                Err(e) => tracing::debug!("Ignoring rate-limit state {}: {}", path.display(), e),
+            }
+        }
+
+        let result = f(hosts.entry(host.to_string()).or_default(), now);
+        hosts.retain(|_, state| state.is_current(now));
+
+        if let (Some(path), Some(_)) = (shared, &lock) {
+            let state: HashMap<_, _> = hosts
+                .iter()
+                .filter(|(host, _)| !is_local(host))
+                .map(|(host, state)| (host.clone(), *state))
+                .collect();
+            if saved.as_ref() != Some(&state) {
+                if let Err(e) = write_state(path, &state) {
+                    tracing::debug!("Cannot save rate-limit state {}: {}", path.display(), e);
+                }
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Whether a response asks for fewer requests
+fn is_limited(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+/// Minimum interval between requests to a host with a published limit
+fn host_limit(host: &str) -> Option<Duration> {
+    HOST_LIMITS
+        .iter()
+        .find(|(name, _)| host.eq_ignore_ascii_case(name))
+        .map(|(_, per_second)| Duration::from_secs_f64(1.0 / per_second))
+}
+
+/// Local servers (tests, mocks) are not sources with quotas to share
+fn is_local(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+fn read_state(path: &Path) -> io::Result<HashMap<String, HostState>> {
+    match fs::read_to_string(path) {
+        Ok(content) if content.trim().is_empty() => Ok(HashMap::new()),
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn write_state(path: &Path, hosts: &HashMap<String, HostState>) -> io::Result<()> {
+    let content = serde_json::to_string(hosts).map_err(io::Error::other)?;
+    fs::write(path, content)
+}
+
+/// An exclusive OS lock on `<state file>.lock`, held while the shared state
+/// is read and written and released when dropped or when the process exits
+struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Lock `<state file>.lock`, waiting for other processes to release it;
+    /// `None` if the lock file cannot be opened or locked
+    fn acquire(state_file: &Path) -> Option<Self> {
+        let path = state_file.with_extension("json.lock");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).ok()?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .ok()?;
+        match file.lock() {
+            Ok(()) => Some(Self { _file: file }),
+            Err(e) => {
+                tracing::debug!("Cannot lock rate-limit state {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn unix_seconds() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn secs(d: Duration) -> u64 {
+        (d.as_secs_f64()).round() as u64
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        let limits = RateLimitHeaders::parse(&headers(&[
+            ("retry-after", "30"),
+            ("x-ratelimit-remaining", "7"),
+            ("x-ratelimit-reset", "60"),
+        ]));
+        assert_eq!(limits.retry_after, Some(Duration::from_secs(30)));
+        assert_eq!(limits.remaining, Some(7));
+        assert_eq!(limits.reset, Some(Duration::from_secs(60)));
+
+        let in_a_minute = chrono::Utc::now() + chrono::Duration::seconds(60);
+        let limits = RateLimitHeaders::parse(&headers(&[
+            ("retry-after", &in_a_minute.to_rfc2822()),
+            (
+                "x-ratelimit-reset",
+                &(in_a_minute.timestamp() + 60).to_string(),
+            ),
+        ]));
+        assert!((58..=60).contains(&secs(limits.retry_after.unwrap())));
+        assert!((118..=120).contains(&secs(limits.reset.unwrap())));
+
+        assert_eq!(
+            RateLimitHeaders::parse(&headers(&[("retry-after", "soon")])),
+            RateLimitHeaders::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_holds_host_back() {
+        let limiter = HostLimiter::new();
+        assert_eq!(limiter.reserve("api.example.org", None), Duration::ZERO);
+
+        limiter
+            .observe(
+                "api.example.org",
+                StatusCode::TOO_MANY_REQUESTS,
+                &headers(&[("retry-after", "20")]),
+            )
+            .await;
+        assert!((19..=20).contains(&secs(limiter.reserve("api.example.org", None))));
+        // Other hosts are not affected
+        assert_eq!(limiter.reserve("api.other.org", None), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_without_retry_after() {
+        let limiter = HostLimiter::new();
+        limiter
+            .observe("h", StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new())
+            .await;
+        assert_eq!(limiter.host_state("h").strikes, 1);
+        assert!((4..=5).contains(&secs(limiter.reserve("h", None))));
+
+        limiter
+            .observe("h", StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new())
+            .await;
+        assert!((9..=10).contains(&secs(limiter.reserve("h", None))));
+
+        limiter
+            .observe("h", StatusCode::OK, &HeaderMap::new())
+            .await;
+        assert_eq!(limiter.host_state("h").strikes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_slows_down_before_quota_runs_out() {
+        let limiter = HostLimiter::new();
+        limiter
+            .observe(
+                "h",
+                StatusCode::OK,
+                &headers(&[("x-ratelimit-remaining", "3"), ("x-ratelimit-reset", "8")]),
+            )
+            .await;
+        // 8 seconds left for 3 requests: the next one about 2 seconds later
+        assert_eq!(secs(limiter.reserve("h", None)), 2);
+
+        limiter
+            .observe(
+                "h",
+                StatusCode::OK,
+                &headers(&[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", "30")]),
+            )
+            .await;
+        assert!((29..=30).contains(&secs(limiter.reserve("h", None))));
+    }
+
+    #[test]
+    fn test_shared_state_setting() {
+        let config = RateLimitConfig {
+            shared_state: false,
+            ..RateLimitConfig::default()
+        };
+        assert!(HostLimiter::from_config(&config).state_file().is_none());
+    }
+
+    #[test]
+    fn test_min_interval_and_host_limits() {
+        let limiter = HostLimiter::new();
+        let interval = Some(Duration::from_millis(500));
+        assert_eq!(limiter.reserve("h", interval), Duration::ZERO);
+        let wait = limiter.reserve("h", interval);
+        assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+
+        assert_eq!(
+            host_limit("EUTILS.ncbi.nlm.nih.gov"),
+            Some(Duration::from_secs_f64(1.0 / 3.0))
+        );
+        assert!(host_limit("api.example.org").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_state_is_shared_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE);
+        let first = HostLimiter::with_state_file(&path);
+        let second = HostLimiter::with_state_file(&path);
+
+        first
+            .observe(
+                "api.example.org",
+                StatusCode::TOO_MANY_REQUESTS,
+                &headers(&[("retry-after", "15")]),
+            )
+            .await;
+        assert!((14..=15).contains(&secs(second.reserve("api.example.org", None))));
+
+        // Turns are taken from one schedule
+        let interval = Some(Duration::from_secs(1));
+        first.reserve("api.shared.org", interval);
+        assert!(second.reserve("api.shared.org", interval) > Duration::from_millis(900));
+
+        // Requests that change no schedule leave the file alone
+        let modified = || fs::metadata(&path).unwrap().modified().unwrap();
+        let before = modified();
+        second.wait("api.quiet.org", None).await;
+        second
+            .observe("api.quiet.org", StatusCode::OK, &HeaderMap::new())
+            .await;
+        assert_eq!(modified(), before);
+
+        // Local hosts stay in memory
+        first
+            .observe(
+                "127.0.0.1",
+                StatusCode::TOO_MANY_REQUESTS,
+                &HeaderMap::new(),
+            )
+            .await;
+        assert!(!fs::read_to_string(&path).unwrap().contains("127.0.0.1"));
+    }
+
+    #[test]
+    fn test_file_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE);
+
+        // A lock file left behind does not hold anyone up
+        fs::write(path.with_extension("json.lock"), "").unwrap();
+        let held = FileLock::acquire(&path).unwrap();
+
+        // A held lock does, until it is released
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let waiter = std::thread::spawn({
+            let path = path.clone();
+            move || {
+                let _lock = FileLock::acquire(&path).unwrap();
+                sender.send(()).unwrap();
+            }
+        });
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(held);
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        waiter.join().unwrap();
+    }
+}
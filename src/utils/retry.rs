@@ -117,6 +117,19 @@ impl TransientError {
             TransientError::ServerError => Duration::from_secs(2),
         }
     }
+
+    /// Delay before retrying, at least `backoff`
+    ///
+    /// Rate limits are waited out by the HTTP client before the retried
+    /// request is sent, for as long as the server's `Retry-After` or
+    /// `X-RateLimit-Reset` asks (see [`HostLimiter`](super::HostLimiter)), so
+    /// they only add the backoff here instead of a guessed delay.
+    pub fn retry_delay(&self, backoff: Duration) -> Duration {
+        match self {
+            TransientError::RateLimit(None) | TransientError::TooManyRequests => backoff,
+            _ => std::cmp::max(backoff, self.recommended_delay()),
+        }
+    }
 }
 
 /// Result of a retry operation
@@ -177,7 +190,7 @@ where
                     };
 
                     // Also consider error-specific recommended delay
-                    let delay = std::cmp::max(delay, transient.recommended_delay());
+                    let delay = transient.retry_delay(delay);
 
                     total_elapsed += delay;
 
@@ -255,7 +268,7 @@ where
                         Duration::from_secs_f64(exp_delay.min(config.max_delay.as_secs_f64()))
                     };
 
-                    let delay = std::cmp::max(delay, transient.recommended_delay());
+                    let delay = transient.retry_delay(delay);
                     total_elapsed += delay;
 
                     if attempts >= config.max_attempts || total_elapsed >= config.max_total_time {
@@ -414,6 +427,23 @@ mod tests {
         assert!(TransientError::from_source_error(&parse_error).is_none());
     }
 
+    #[test]
+    fn test_retry_delay_leaves_rate_limits_to_client() {
+        let backoff = Duration::from_secs(2);
+        assert_eq!(
+            TransientError::RateLimit(None).retry_delay(backoff),
+            backoff
+        );
+        assert_eq!(
+            TransientError::RateLimit(Some(30)).retry_delay(backoff),
+            Duration::from_secs(31)
+        );
+        assert_eq!(
+            TransientError::ServiceUnavailable.retry_delay(backoff),
+            Duration::from_secs(10)
+        );
+    }
+
     #[test]
     fn test_recommended_delay() {
         assert_eq!(
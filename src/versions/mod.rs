@@ -0,0 +1,424 @@
+//! Preprint and published versions of the same work.
+//!
+//! - [`version_kind`]: Whether a paper is a preprint or a published version
+//! - [`link`]: Why a preprint and a published paper are versions of one work:
+//!   a DOI relation reported by the sources, a shared arXiv ID, the journal
+//!   named in an arXiv `journal_ref`, or a close title by the same authors
+//! - [`group_versions`]: Folds the versions of each work into one paper, with
+//!   the others kept in [`Paper::versions`]
+//! - [`find_version`]: The version of a given kind among a paper and its
+//!   grouped versions
+//!
+//! A preprint and its published version have different DOIs, years and
+//! sometimes titles, so deduplication keeps both and they are linked here
+//! instead.
+//!
+//! # Example
+//!
+//! ```rust
+//! use research_master::models::{PaperBuilder, SourceType, VersionKind};
+//! use research_master::versions::{group_versions, VersionPreference};
+//!
+//! let preprint = PaperBuilder::new("1706.03762", "Attention Is All You Need", "", SourceType::Arxiv)
+//!     .authors("Ashish Vaswani; Noam Shazeer")
+//!     .published_date("2017-06-12")
+//!     .build();
+//! let published = PaperBuilder::new("W1", "Attention is all you need", "", SourceType::OpenAlex)
+//!     .authors("A. Vaswani; N. Shazeer")
+//!     .doi("10.5555/3295222.3295349")
+//!     .published_date("2017-12-04")
+//!     .build();
+//!
+//! let papers = group_versions(vec![preprint, published], VersionPreference::Published);
+//! assert_eq!(papers.len(), 1);
+//! assert_eq!(papers[0].source, SourceType::OpenAlex);
+//! assert_eq!(papers[0].versions[0].source, SourceType::Arxiv);
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strsim::jaro_winkler;
+
+use crate::enrich::title_key;
+use crate::formats::{extra_text, split_name};
+use crate::graph::normalize_doi;
+use crate::models::{Paper, SourceType, VersionKind};
+
+/// DOI prefixes of preprint servers (arXiv, bioRxiv/medRxiv, OSF, SSRN,
+/// Preprints.org, Research Square, PsyArXiv, ChemRxiv)
+pub(crate) const PREPRINT_DOI_PREFIXES: &[&str] = &[
+    "10.48550/",
+    "10.1101/",
+    "10.31219/",
+    "10.2139/ssrn",
+    "10.20944/preprints",
+    "10.21203/rs.",
+    "10.31234/",
+    "10.26434/chemrxiv",
+];
+
+/// Minimum Jaro-Winkler similarity of title words for a title link; lower
+/// than for duplicates, as titles are often reworded on publication
+const TITLE_SIMILARITY: f64 = 0.9;
+
+/// Which version of a work is shown when versions are grouped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionPreference {
+    /// The published version, with the preprint listed under it
+    #[default]
+    Published,
+    /// The preprint, with the published version listed under it
+    Preprint,
+}
+
+impl VersionPreference {
+    fn kind(self) -> VersionKind {
+        match self {
+            VersionPreference::Published => VersionKind::Published,
+            VersionPreference::Preprint => VersionKind::Preprint,
+        }
+    }
+}
+
+/// Whether a paper is a preprint or a published version: `None` when the
+/// record has neither a DOI nor a venue to tell
+pub fn version_kind(paper: &Paper) -> Option<VersionKind> {
+    if is_preprint(paper) {
+        Some(VersionKind::Preprint)
+    } else if paper.doi.as_deref().and_then(normalize_doi).is_some()
+        || paper.venue().is_some_and(|v| !v.trim().is_empty())
+    {
+        Some(VersionKind::Published)
+    } else {
+        None
+    }
+}
+
+/// Whether two papers are known to be different versions of a work, which
+/// deduplication must not merge
+pub(crate) fn distinct_versions(a: &Paper, b: &Paper) -> bool {
+    matches!(
+        (version_kind(a), version_kind(b)),
+        (Some(x), Some(y)) if x != y
+    )
+}
+
+/// How a preprint and a published paper were found to be versions of the
+/// same work (`doi`, `arxiv_id`, `journal_ref` or `title`), or `None` if they
+/// are not
+pub fn link(a: &Paper, b: &Paper) -> Option<&'static str> {
+    let (preprint, published) = match (version_kind(a)?, version_kind(b)?) {
+        (VersionKind::Preprint, VersionKind::Published) => (a, b),
+        (VersionKind::Published, VersionKind::Preprint) => (b, a),
+        _ => return None,
+    };
+
+    let doi = |paper: &Paper| paper.doi.as_deref().and_then(normalize_doi);
+    let related =
+        |paper: &Paper, key: &str| extra_text(paper, key).and_then(|doi| normalize_doi(&doi));
+    let published_doi = doi(published);
+    let preprint_doi = doi(preprint);
+    if (published_doi.is_some() && related(preprint, "published_doi") == published_doi)
+        || (preprint_doi.is_some() && related(published, "preprint_doi") == preprint_doi)
+    {
+        return Some("doi");
+    }
+
+    if let (Some(x), Some(y)) = (arxiv_id(preprint), arxiv_id(published)) {
+        if x == y {
+            return Some("arxiv_id");
+        }
+    }
+
+    // Titles often change on publication, so a preprint naming the journal
+    // only needs the same authors and year range
+    let journal_ref = extra_text(preprint, "journal_ref").map(|r| r.to_lowercase());
+    let venue = published.venue().map(|v| v.trim().to_lowercase());
+    if let (Some(journal_ref), Some(venue)) = (journal_ref, venue) {
+        if !venue.is_empty()
+            && journal_ref.contains(&venue)
+            && years_agree(preprint, published)
+            && authors_overlap(preprint, published)
+        {
+            return Some("journal_ref");
+        }
+    }
+
+    titles_match(preprint, published).then_some("title")
+}
+
+/// Fold the versions of each work into one paper
+///
+/// The paper shown is the first of the preferred kind in each group, placed
+/// where the group's first paper was; the other versions are moved into its
+/// [`versions`](Paper::versions). Papers without other versions are kept as
+/// they are.
+pub fn group_versions(papers: Vec<Paper>, prefer: VersionPreference) -> Vec<Paper> {
+    // Group of each paper, linked transitively
+    let mut group: Vec<usize> = (0..papers.len()).collect();
+    for i in 0..papers.len() {
+        for j in (i + 1)..papers.len() {
+            if group[j] == j && link(&papers[i], &papers[j]).is_some() {
+                group[j] = group[i];
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<Paper>> = Vec::new();
+    let mut slot: Vec<Option<usize>> = vec![None; papers.len()];
+    for (i, paper) in papers.into_iter().enumerate() {
+        let root = group[i];
+        let index = *slot[root].get_or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(paper);
+    }
+
+    groups
+        .into_iter()
+        .map(|mut members| {
+            let shown = members
+                .iter()
+                .position(|p| version_kind(p) == Some(prefer.kind()))
+                .unwrap_or(0);
+            let mut paper = members.remove(shown);
+            for mut other in members {
+                paper.versions.append(&mut other.versions);
+                paper.versions.push(other);
+            }
+            paper
+        })
+        .collect()
+}
+
+/// The paper or grouped version of the given kind
+pub fn find_version(paper: &Paper, kind: VersionKind) -> Option<&Paper> {
+    std::iter::once(paper)
+        .chain(&paper.versions)
+        .find(|p| version_kind(p) == Some(kind))
+}
+
+/// Whether a paper is a preprint: an arXiv paper, an entry with an arXiv
+/// eprint, or a DOI or venue of a preprint server
+pub(crate) fn is_preprint(paper: &Paper) -> bool {
+    if paper.source == SourceType::Arxiv {
+        return true;
+    }
+    if paper
+        .doi
+        .as_deref()
+        .and_then(normalize_doi)
+        .is_some_and(|doi| is_preprint_doi(&doi))
+    {
+        return true;
+    }
+    let venue = paper.venue().unwrap_or_default().to_lowercase();
+    arxiv_eprint(paper)
+        || ["arxiv", "biorxiv", "medrxiv", "ssrn"]
+            .iter()
+            .any(|v| venue.contains(v))
+}
+
+pub(crate) fn is_preprint_doi(doi: &str) -> bool {
+    PREPRINT_DOI_PREFIXES.iter().any(|p| doi.starts_with(p))
+}
+
+/// Whether a BibTeX entry marks its eprint as an arXiv ID
+fn arxiv_eprint(paper: &Paper) -> bool {
+    ["archiveprefix", "eprinttype"]
+        .iter()
+        .filter_map(|field| extra_text(paper, field))
+        .any(|v| v.eq_ignore_ascii_case("arxiv"))
+}
+
+/// The arXiv ID of a paper, without version: its own ID for arXiv papers,
+/// the ID a source linked it to, an arXiv eprint, or an arXiv DOI
+fn arxiv_id(paper: &Paper) -> Option<String> {
+    let id = if paper.source == SourceType::Arxiv {
+        Some(paper.paper_id.clone())
+    } else {
+        extra_text(paper, "arxiv_id")
+            .or_else(|| extra_text(paper, "eprint").filter(|_| arxiv_eprint(paper)))
+            .or_else(|| {
+                let doi = paper.doi.as_deref().and_then(normalize_doi)?;
+                doi.strip_prefix("10.48550/arxiv.").map(str::to_string)
+            })
+    }?;
+
+    let id = id.trim().to_lowercase();
+    let id = id.strip_prefix("arxiv:").unwrap_or(&id);
+    let id = match id.rsplit_once('v') {
+        Some((base, version))
+            if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => id,
+    };
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// Close titles by overlapping authors, in compatible years
+fn titles_match(preprint: &Paper, published: &Paper) -> bool {
+    years_agree(preprint, published)
+        && authors_overlap(preprint, published)
+        && jaro_winkler(&title_key(&preprint.title), &title_key(&published.title))
+            >= TITLE_SIMILARITY
+}
+
+/// Published no earlier than a year before the preprint and at most three
+/// years after
+fn years_agree(preprint: &Paper, published: &Paper) -> bool {
+    match (preprint.year(), published.year()) {
+        (Some(pre), Some(publ)) => (-1..=3).contains(&(publ - pre)),
+        _ => true,
+    }
+}
+
+/// Whether the papers share an author surname (never when one lists none)
+fn authors_overlap(a: &Paper, b: &Paper) -> bool {
+    let surnames = |paper: &Paper| -> Vec<String> {
+        paper
+            .author_list()
+            .iter()
+            .map(|name| split_name(name).0.to_lowercase())
+            .collect()
+    };
+    let (a, b) = (surnames(a), surnames(b));
+    a.iter().any(|name| b.contains(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PaperBuilder;
+
+    fn preprint() -> Paper {
+        PaperBuilder::new(
+            "2401.00001v2",
+            "Sparse Attention for Long Documents",
+            "https://arxiv.org/abs/2401.00001v2",
+            SourceType::Arxiv,
+        )
+        .authors("Jane Doe; Wei Zhang")
+        .published_date("2024-01-02")
+        .build()
+    }
+
+    fn published() -> Paper {
+        PaperBuilder::new(
+            "W42",
+            "Sparse attention for long documents",
+            "https://doi.org/10.1234/acl.42",
+            SourceType::OpenAlex,
+        )
+        .authors("J. Doe; W. Zhang")
+        .doi("10.1234/acl.42")
+        .published_date("2024-08-10")
+        .build()
+    }
+
+    #[test]
+    fn test_version_kind() {
+        assert_eq!(version_kind(&preprint()), Some(VersionKind::Preprint));
+        assert_eq!(version_kind(&published()), Some(VersionKind::Published));
+
+        let biorxiv = PaperBuilder::new("1", "T", "", SourceType::CrossRef)
+            .doi("10.1101/2024.01.01.123456")
+            .build();
+        assert_eq!(version_kind(&biorxiv), Some(VersionKind::Preprint));
+
+        let unknown = PaperBuilder::new("2", "T", "", SourceType::SemanticScholar).build();
+        assert_eq!(version_kind(&unknown), None);
+    }
+
+    #[test]
+    fn test_link_by_doi_arxiv_id_and_title() {
+        let mut pre = preprint();
+        pre.extra = Some(
+            [(
+                "published_doi".to_string(),
+                serde_json::json!("https://doi.org/10.1234/ACL.42"),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(link(&pre, &published()), Some("doi"));
+
+        let mut publ = published();
+        publ.title = "A different title entirely".to_string();
+        publ.extra = Some(
+            [("arxiv_id".to_string(), serde_json::json!("2401.00001"))]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(link(&publ, &preprint()), Some("arxiv_id"));
+
+        assert_eq!(link(&preprint(), &published()), Some("title"));
+
+        let mut pre = preprint();
+        pre.extra = Some(
+            [(
+                "journal_ref".to_string(),
+                serde_json::json!("Transactions of the ACL 12 (2024) 1-20"),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        let mut publ = published();
+        publ.title = "Efficient long-document attention".to_string();
+        publ.extra = Some(
+            [(
+                "venue".to_string(),
+                serde_json::json!("Transactions of the ACL"),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(link(&pre, &publ), Some("journal_ref"));
+
+        // Two preprints are duplicates or distinct works, not versions
+        assert_eq!(link(&preprint(), &preprint()), None);
+
+        let mut other_authors = published();
+        other_authors.authors = "Alice Smith".to_string();
+        assert_eq!(link(&preprint(), &other_authors), None);
+
+        let mut much_later = published();
+        much_later.published_date = Some("2030-01-01".to_string());
+        assert_eq!(link(&preprint(), &much_later), None);
+    }
+
+    #[test]
+    fn test_group_versions() {
+        let unrelated = PaperBuilder::new("3", "Unrelated", "", SourceType::CrossRef)
+            .doi("10.1234/other")
+            .build();
+        let papers = vec![preprint(), unrelated.clone(), published()];
+
+        let grouped = group_versions(papers.clone(), VersionPreference::Published);
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[0].paper_id, "W42");
+        assert_eq!(grouped[0].versions.len(), 1);
+        assert_eq!(grouped[0].versions[0].paper_id, "2401.00001v2");
+        assert_eq!(grouped[1].paper_id, "3");
+        assert!(grouped[1].versions.is_empty());
+
+        let grouped = group_versions(papers, VersionPreference::Preprint);
+        assert_eq!(grouped[0].paper_id, "2401.00001v2");
+        assert_eq!(grouped[0].versions[0].paper_id, "W42");
+
+        let shown = &grouped[0];
+        assert_eq!(
+            find_version(shown, VersionKind::Published).map(|p| p.paper_id.as_str()),
+            Some("W42")
+        );
+        assert_eq!(
+            find_version(shown, VersionKind::Preprint).map(|p| p.paper_id.as_str()),
+            Some("2401.00001v2")
+        );
+        assert!(find_version(&unrelated, VersionKind::Preprint).is_none());
+    }
+}
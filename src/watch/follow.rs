@@ -0,0 +1,295 @@
+//! Followed authors and papers.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::authors::{AuthorOptions, AuthorResolver};
+use crate::graph::{resolve_seed, CitationFetcher, DEFAULT_CITATION_SOURCES};
+use crate::models::{AuthorLookup, Paper};
+use crate::sources::{Source, SourceError};
+use crate::utils::{deduplicate_papers, DuplicateStrategy};
+
+fn default_follow_results() -> usize {
+    50
+}
+
+/// What a follow tracks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FollowTarget {
+    /// New publications by an author
+    Author {
+        /// Identifier the follow was created with (ORCID iD or `<source>:<id>`)
+        id: String,
+        /// ORCID iD, if known
+        orcid: Option<String>,
+        /// Source records of the author (`<source>:<author id>`)
+        records: Vec<String>,
+    },
+    /// New papers citing a paper
+    Paper {
+        /// Identifier the follow was created with (DOI, arXiv ID, ...)
+        id: String,
+        /// The paper, as resolved when the follow was created
+        paper: Box<Paper>,
+    },
+}
+
+/// A followed author or paper, re-checked by `watch run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Follow {
+    /// Unique name, shared with saved searches, also used for the snapshot file
+    pub name: String,
+
+    /// Author name or paper title
+    pub label: String,
+
+    /// The followed author or paper
+    #[serde(flatten)]
+    pub target: FollowTarget,
+
+    /// Maximum publications or citing papers fetched per run (and per author record)
+    #[serde(default = "default_follow_results")]
+    pub max_results: usize,
+
+    /// When the follow was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl Follow {
+    /// Follow an author given an ORCID iD or `<source>:<id>` keys
+    ///
+    /// The author's source records are resolved once, here, so later runs do not
+    /// depend on name matching.
+    pub async fn author(
+        sources: &[Arc<dyn Source>],
+        name: impl Into<String>,
+        id: &str,
+    ) -> Result<Self, SourceError> {
+        let resolver = AuthorResolver::new(sources.to_vec(), AuthorOptions::new().max_papers(1));
+        let profile = match resolver.resolve(id).await? {
+            AuthorLookup::Found { profile } => profile,
+            AuthorLookup::Ambiguous { candidates, .. } => {
+                let ids: Vec<&str> = candidates.iter().map(|c| c.id.as_str()).collect();
+                return Err(SourceError::InvalidRequest(format!(
+                    "'{}' matches several authors; follow one of: {}",
+                    id,
+                    ids.join(", ")
+                )));
+            }
+        };
+
+        Ok(Self {
+            name: name.into(),
+            label: profile.name.clone(),
+            target: FollowTarget::Author {
+                id: id.trim().to_string(),
+                orcid: profile.orcid.clone(),
+                records: profile.records.iter().map(|r| r.key()).collect(),
+            },
+            max_results: default_follow_results(),
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Follow the papers citing a paper (DOI, arXiv ID, OpenAlex or Semantic Scholar ID)
+    pub async fn paper(sources: &[Arc<dyn Source>], name: impl Into<String>, id: &str) -> Self {
+        let paper = resolve_seed(sources, id).await;
+        let label = if paper.title.is_empty() {
+            id.trim().to_string()
+        } else {
+            paper.title.clone()
+        };
+
+        Self {
+            name: name.into(),
+            label,
+            target: FollowTarget::Paper {
+                id: id.trim().to_string(),
+                paper: Box::new(paper),
+            },
+            max_results: default_follow_results(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Set the maximum publications or citing papers fetched per run
+    pub fn max_results(mut self, max: usize) -> Self {
+        self.max_results = max.max(1);
+        self
+    }
+
+    /// Fetch the author's recent publications or the paper's citing papers
+    ///
+    /// Returns the papers, the failures as `<source>: <error>`, and whether any
+    /// lookup succeeded.
+    pub(crate) async fn fetch(
+        &self,
+        sources: &[Arc<dyn Source>],
+    ) -> (Vec<Paper>, Vec<String>, bool) {
+        match &self.target {
+            FollowTarget::Author { records, .. } => {
+                let lookups = records.iter().map(|key| async move {
+                    let (source_id, author_id) = key.split_once(':').unwrap_or((key, ""));
+                    let source = sources
+                        .iter()
+                        .find(|s| s.id() == source_id && s.supports_author_profiles())
+                        .ok_or_else(|| format!("{}: source not available", source_id))?;
+                    source
+                        .get_author_papers(author_id, self.max_results)
+                        .await
+                        .map_err(|e| format!("{}: {}", key, e))
+                });
+
+                let mut papers = Vec::new();
+                let mut errors = Vec::new();
+                let mut succeeded = false;
+                for result in join_all(lookups).await {
+                    match result {
+                        Ok(found) => {
+                            succeeded = true;
+                            papers.extend(found);
+                        }
+                        Err(e) => errors.push(e),
+                    }
+                }
+                (
+                    deduplicate_papers(papers, DuplicateStrategy::First),
+                    errors,
+                    succeeded,
+                )
+            }
+            FollowTarget::Paper { paper, .. } => {
+                let preferred: Vec<String> = DEFAULT_CITATION_SOURCES
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect();
+                let fetcher = CitationFetcher::new(sources.to_vec(), &preferred);
+                match fetcher.citations(paper, self.max_results).await {
+                    Ok(papers) => (papers, Vec::new(), true),
+                    Err(e) => (Vec::new(), vec![e], false),
+                }
+            }
+        }
+    }
+
+    /// What the follow tracks, for reports (`author ...` or `citations of ...`)
+    pub fn describe(&self) -> String {
+        match &self.target {
+            FollowTarget::Author { id, .. } => format!("author {} ({})", self.label, id),
+            FollowTarget::Paper { id, .. } => format!("citations of {} ({})", self.label, id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use crate::models::{Author, CitationRequest, PaperBuilder, SearchResponse, SourceType};
+    use crate::sources::SourceCapabilities;
+    use crate::watch::{WatchKind, WatchStore, Watcher};
+
+    /// An author and citation source whose papers can change between runs
+    #[derive(Debug, Default)]
+    struct FollowSource {
+        papers: Mutex<Vec<Paper>>,
+    }
+
+    impl FollowSource {
+        fn publish(&self, id: &str, title: &str, date: &str) {
+            let paper = PaperBuilder::new(id, title, "", SourceType::SemanticScholar)
+                .published_date(date)
+                .build();
+            self.papers.lock().unwrap().push(paper);
+        }
+    }
+
+    #[async_trait]
+    impl Source for FollowSource {
+        fn id(&self) -> &str {
+            "semantic"
+        }
+
+        fn name(&self) -> &str {
+            "Semantic Scholar"
+        }
+
+        fn capabilities(&self) -> SourceCapabilities {
+            SourceCapabilities::AUTHOR_PROFILES | SourceCapabilities::CITATIONS
+        }
+
+        async fn get_author(
+            &self,
+            author_id: &str,
+            _max_papers: usize,
+        ) -> Result<Author, SourceError> {
+            let mut author = Author::new(author_id, "Jane Smith", SourceType::SemanticScholar);
+            author.papers = self.papers.lock().unwrap().clone();
+            Ok(author)
+        }
+
+        async fn get_citations(
+            &self,
+            request: &CitationRequest,
+        ) -> Result<SearchResponse, SourceError> {
+            let papers = self.papers.lock().unwrap().clone();
+            Ok(SearchResponse::new(
+                papers,
+                "Semantic Scholar",
+                &request.paper_id,
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_follows_report_new_papers_and_citations() {
+        let source = Arc::new(FollowSource::default());
+        source.publish("P1", "An early paper on language agents", "2021-03-01");
+        let sources: Vec<Arc<dyn Source>> = vec![source.clone()];
+
+        let author = Follow::author(&sources, "jane", "semantic:1741101")
+            .await
+            .unwrap();
+        assert_eq!(author.label, "Jane Smith");
+        match author.target {
+            FollowTarget::Author { ref records, .. } => {
+                assert_eq!(records, &["semantic:1741101"]);
+            }
+            _ => panic!("Expected an author follow"),
+        }
+        let paper = Follow::paper(&sources, "seed", "S2-SEED").await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = WatchStore::with_dir(dir.path());
+        store.add_follow(author, false).unwrap();
+        store.add_follow(paper, false).unwrap();
+        let watcher = Watcher::new(sources, store);
+
+        let first = watcher.run(&[], true).await.unwrap();
+        assert_eq!(first.searches.len(), 2);
+        assert!(first.searches.iter().all(|d| d.first_run));
+
+        source.publish(
+            "P2",
+            "Tool use by language agents in practice",
+            "2024-06-01",
+        );
+        let second = watcher.run(&[], true).await.unwrap();
+        let kinds: Vec<WatchKind> = second.searches.iter().map(|d| d.kind).collect();
+        assert_eq!(kinds, vec![WatchKind::Author, WatchKind::Paper]);
+        for digest in &second.searches {
+            assert!(!digest.first_run);
+            assert_eq!(digest.total, 2);
+            assert_eq!(digest.new_papers.len(), 1);
+            assert_eq!(digest.new_papers[0].paper_id, "P2");
+        }
+        assert!(second.to_markdown().contains("across 2 follows"));
+    }
+}
@@ -0,0 +1,717 @@
+//! Saved searches and follows with "what's new since last run" digests.
+//!
+//! - [`SavedSearch`]: A named query with its sources and filters
+//! - [`Follow`]: A followed author (new publications) or paper (new citing papers)
+//! - [`WatchStore`]: Saved searches, follows and their snapshots in the config directory
+//! - [`Watcher`]: Re-runs saved searches and follows and reports papers not seen before
+//! - [`WatchReport`]: The new papers of a run, as Markdown, JSON or an Atom feed
+//!
+//! A paper counts as seen when it shares an identifier (DOI or source ID) with a
+//! paper from an earlier run, or when its title is nearly identical to one.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use research_master::sources::SourceRegistry;
+//! use research_master::watch::{SavedSearch, WatchStore, Watcher};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! let store = WatchStore::new();
+//! store.add(SavedSearch::new("llm-agents", "large language model agents"), false)?;
+//!
+//! let registry = SourceRegistry::new();
+//! let watcher = Watcher::new(registry.all().cloned().collect(), store);
+//! let report = watcher.run(&[], true).await?;
+//! println!("{}", report.to_markdown());
+//! # Ok(())
+//! # }
+//! ```
+
+mod follow;
+mod store;
+
+pub use follow::{Follow, FollowTarget};
+pub use store::{SeenPaper, Snapshot, WatchStore};
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strsim::jaro_winkler;
+
+use crate::graph::normalize_doi;
+use crate::models::{Paper, SearchQuery, SortBy};
+use crate::sources::Source;
+use crate::utils::{deduplicate_papers, xml_escape, DuplicateStrategy};
+
+/// Titles at least this similar (Jaro-Winkler) are treated as the same paper
+const TITLE_SIMILARITY: f64 = 0.95;
+
+/// Titles shorter than this must match exactly
+const MIN_FUZZY_TITLE_LEN: usize = 20;
+
+/// A named search re-run by `watch run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    /// Unique name, also used for the snapshot file
+    pub name: String,
+
+    /// Search query
+    pub query: String,
+
+    /// Source IDs to search (all searchable sources if empty)
+    #[serde(default)]
+    pub sources: Vec<String>,
+
+    /// Maximum results per source
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+
+    /// Year filter (e.g. 2024, 2018-2022, 2010-)
+    #[serde(default)]
+    pub year: Option<String>,
+
+    /// Category/subject filter
+    #[serde(default)]
+    pub category: Option<String>,
+
+    /// Author filter
+    #[serde(default)]
+    pub author: Option<String>,
+
+    /// Sort order requested from the sources
+    #[serde(default)]
+    pub sort_by: Option<SortBy>,
+
+    /// When the search was saved
+    pub created_at: DateTime<Utc>,
+}
+
+fn default_max_results() -> usize {
+    20
+}
+
+impl SavedSearch {
+    /// Create a saved search over all sources with default filters
+    pub fn new(name: impl Into<String>, query: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            query: query.into(),
+            sources: Vec::new(),
+            max_results: default_max_results(),
+            year: None,
+            category: None,
+            author: None,
+            sort_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Set the sources to search
+    pub fn sources(mut self, sources: Vec<String>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Set the maximum results per source
+    pub fn max_results(mut self, max: usize) -> Self {
+        self.max_results = max;
+        self
+    }
+
+    /// Set the year filter
+    pub fn year(mut self, year: Option<String>) -> Self {
+        self.year = year;
+        self
+    }
+
+    /// Set the category filter
+    pub fn category(mut self, category: Option<String>) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Set the author filter
+    pub fn author(mut self, author: Option<String>) -> Self {
+        self.author = author;
+        self
+    }
+
+    /// Set the sort order
+    pub fn sort_by(mut self, sort_by: Option<SortBy>) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
+    /// The query sent to each source
+    pub fn to_query(&self) -> SearchQuery {
+        let mut query = SearchQuery::new(&self.query).max_results(self.max_results);
+        query.year = self.year.clone();
+        query.category = self.category.clone();
+        query.author = self.author.clone();
+        query.sort_by = self.sort_by;
+        query
+    }
+}
+
+/// What a digest was produced from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchKind {
+    /// A saved search
+    Search,
+    /// A followed author's publications
+    Author,
+    /// Papers citing a followed paper
+    Paper,
+}
+
+/// New papers found by one saved search or follow
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchDigest {
+    /// Saved search or follow name
+    pub name: String,
+
+    /// Whether this is a saved search or a follow
+    pub kind: WatchKind,
+
+    /// Search query, or the followed author or paper
+    pub query: String,
+
+    /// Whether this was the first run (every paper is new)
+    pub first_run: bool,
+
+    /// Papers returned by this run after deduplication
+    pub total: usize,
+
+    /// Papers not returned by any earlier run
+    pub new_papers: Vec<Paper>,
+
+    /// Sources that failed, as `<source>: <error>`
+    pub errors: Vec<String>,
+}
+
+/// Result of running saved searches and follows
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchReport {
+    /// When the searches were run
+    pub run_at: DateTime<Utc>,
+
+    /// One digest per saved search, then one per follow, in saved order
+    pub searches: Vec<SearchDigest>,
+}
+
+impl WatchReport {
+    /// Number of new papers across all searches
+    pub fn new_count(&self) -> usize {
+        self.searches.iter().map(|s| s.new_papers.len()).sum()
+    }
+
+    /// New papers across all searches, each listed once, for exporting with
+    /// [`ExportFormat`](crate::formats::ExportFormat)
+    pub fn new_papers(&self) -> Vec<Paper> {
+        let mut ids = HashSet::new();
+        self.searches
+            .iter()
+            .flat_map(|s| &s.new_papers)
+            .filter(|paper| ids.insert(entry_id(paper)))
+            .cloned()
+            .collect()
+    }
+
+    /// Render the report as a Markdown digest
+    pub fn to_markdown(&self) -> String {
+        let searches = self
+            .searches
+            .iter()
+            .filter(|s| s.kind == WatchKind::Search)
+            .count();
+        let follows = self.searches.len() - searches;
+        let mut watched = Vec::new();
+        if searches > 0 || follows == 0 {
+            watched.push(format!(
+                "{} saved search{}",
+                searches,
+                if searches == 1 { "" } else { "es" }
+            ));
+        }
+        if follows > 0 {
+            watched.push(format!(
+                "{} follow{}",
+                follows,
+                if follows == 1 { "" } else { "s" }
+            ));
+        }
+        let mut out = format!(
+            "# New papers — {}\n\n{} new paper{} across {}.\n",
+            self.run_at.format("%Y-%m-%d %H:%M UTC"),
+            self.new_count(),
+            if self.new_count() == 1 { "" } else { "s" },
+            watched.join(" and ")
+        );
+
+        for search in &self.searches {
+            let what = match search.kind {
+                WatchKind::Search => format!("Query: `{}`", search.query),
+                WatchKind::Author => format!("Publications by {}", search.query),
+                WatchKind::Paper => format!("Papers citing {}", search.query),
+            };
+            let _ = write!(
+                out,
+                "\n## {} ({} new)\n\n{}",
+                search.name,
+                search.new_papers.len(),
+                what
+            );
+            if search.first_run {
+                out.push_str(" · first run");
+            }
+            out.push('\n');
+            for error in &search.errors {
+                let _ = writeln!(out, "\n> Failed: {}", error);
+            }
+            if !search.new_papers.is_empty() {
+                out.push('\n');
+            }
+            for (i, paper) in search.new_papers.iter().enumerate() {
+                let _ = write!(out, "{}. **{}**", i + 1, paper.title.trim());
+                if !paper.authors.is_empty() {
+                    let _ = write!(out, " — {}", paper.authors);
+                }
+                if let Some(year) = paper.year() {
+                    let _ = write!(out, " ({})", year);
+                }
+                if let Some(venue) = paper.venue() {
+                    let _ = write!(out, " *{}*", venue);
+                }
+                let _ = writeln!(out, " <{}>", paper_link(paper));
+            }
+        }
+        out
+    }
+
+    /// Render the new papers as an Atom feed
+    pub fn to_atom(&self) -> String {
+        let updated = self.run_at.to_rfc3339();
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        out.push_str("  <title>Research Master saved searches</title>\n");
+        out.push_str("  <id>urn:research-master:watch</id>\n");
+        let _ = writeln!(out, "  <updated>{}</updated>", updated);
+        out.push_str("  <author><name>research-master</name></author>\n");
+        out.push_str("  <generator>research-master</generator>\n");
+
+        for search in &self.searches {
+            for paper in &search.new_papers {
+                out.push_str("  <entry>\n");
+                let _ = writeln!(out, "    <title>{}</title>", xml_escape(paper.title.trim()));
+                let _ = writeln!(out, "    <id>{}</id>", xml_escape(&entry_id(paper)));
+                let _ = writeln!(
+                    out,
+                    "    <link href=\"{}\"/>",
+                    xml_escape(&paper_link(paper))
+                );
+                let _ = writeln!(out, "    <updated>{}</updated>", updated);
+                for author in paper
+                    .authors
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|a| !a.is_empty())
+                {
+                    let _ = writeln!(
+                        out,
+                        "    <author><name>{}</name></author>",
+                        xml_escape(author)
+                    );
+                }
+                let _ = writeln!(out, "    <category term=\"{}\"/>", xml_escape(&search.name));
+                if !paper.r#abstract.is_empty() {
+                    let _ = writeln!(
+                        out,
+                        "    <summary>{}</summary>",
+                        xml_escape(paper.r#abstract.trim())
+                    );
+                }
+                out.push_str("  </entry>\n");
+            }
+        }
+        out.push_str("</feed>\n");
+        out
+    }
+}
+
+/// Re-runs saved searches against the registered sources
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    sources: Vec<Arc<dyn Source>>,
+    store: WatchStore,
+}
+
+impl Watcher {
+    /// Create a watcher over the given sources and store
+    pub fn new(sources: Vec<Arc<dyn Source>>, store: WatchStore) -> Self {
+        Self { sources, store }
+    }
+
+    /// The store searches and snapshots are read from
+    pub fn store(&self) -> &WatchStore {
+        &self.store
+    }
+
+    /// Run the named saved searches and follows (all if `names` is empty)
+    ///
+    /// With `update`, each snapshot is extended with the papers returned, so
+    /// they are not reported again.
+    pub async fn run(&self, names: &[String], update: bool) -> io::Result<WatchReport> {
+        let mut searches = self.store.list()?;
+        let mut follows = self.store.list_follows()?;
+        if let Some(missing) = names.iter().find(|n| {
+            !searches.iter().any(|s| &s.name == *n) && !follows.iter().any(|f| &f.name == *n)
+        }) {
+            return Err(store::not_found(missing));
+        }
+        if !names.is_empty() {
+            searches.retain(|s| names.contains(&s.name));
+            follows.retain(|f| names.contains(&f.name));
+        }
+
+        let run_at = Utc::now();
+        let mut digests = Vec::with_capacity(searches.len() + follows.len());
+        for search in &searches {
+            let (papers, errors, succeeded) = self.fetch(search).await;
+            let digest = SearchDigest {
+                name: search.name.clone(),
+                kind: WatchKind::Search,
+                query: search.query.clone(),
+                first_run: false,
+                total: papers.len(),
+                new_papers: papers,
+                errors,
+            };
+            digests.push(self.compare(digest, succeeded, run_at, update)?);
+        }
+        for follow in &follows {
+            let (papers, errors, succeeded) = follow.fetch(&self.sources).await;
+            let (kind, id) = match &follow.target {
+                FollowTarget::Author { id, .. } => (WatchKind::Author, id),
+                FollowTarget::Paper { id, .. } => (WatchKind::Paper, id),
+            };
+            let digest = SearchDigest {
+                name: follow.name.clone(),
+                kind,
+                query: format!("{} ({})", follow.label, id),
+                first_run: false,
+                total: papers.len(),
+                new_papers: papers,
+                errors,
+            };
+            digests.push(self.compare(digest, succeeded, run_at, update)?);
+        }
+
+        Ok(WatchReport {
+            run_at,
+            searches: digests,
+        })
+    }
+
+    /// Reduce a digest's papers to those missing from its snapshot
+    fn compare(
+        &self,
+        mut digest: SearchDigest,
+        succeeded: bool,
+        run_at: DateTime<Utc>,
+        update: bool,
+    ) -> io::Result<SearchDigest> {
+        let snapshot = self.store.load_snapshot(&digest.name)?;
+        let first_run = snapshot.is_none();
+        let mut snapshot = snapshot.unwrap_or_default();
+
+        let new_papers = unseen_papers(std::mem::take(&mut digest.new_papers), &snapshot.papers);
+
+        // A run where every source failed says nothing about what is new
+        if update && succeeded {
+            let seen = new_papers
+                .iter()
+                .map(|paper| SeenPaper {
+                    ids: paper_ids(paper),
+                    title: paper.title.clone(),
+                    first_seen: run_at,
+                })
+                .collect();
+            snapshot.record(seen, run_at);
+            self.store.save_snapshot(&digest.name, &snapshot)?;
+        }
+
+        digest.first_run = first_run;
+        digest.new_papers = new_papers;
+        Ok(digest)
+    }
+
+    /// Search every source of a saved search concurrently
+    async fn fetch(&self, search: &SavedSearch) -> (Vec<Paper>, Vec<String>, bool) {
+        let mut errors = Vec::new();
+        let sources: Vec<&Arc<dyn Source>> = if search.sources.is_empty() {
+            self.sources
+                .iter()
+                .filter(|s| s.supports_search())
+                .collect()
+        } else {
+            search
+                .sources
+                .iter()
+                .filter_map(|id| {
+                    let source = self
+                        .sources
+                        .iter()
+                        .find(|s| s.id() == id && s.supports_search());
+                    if source.is_none() {
+                        errors.push(format!("{}: source not available for search", id));
+                    }
+                    source
+                })
+                .collect()
+        };
+
+        let query = search.to_query();
+        let results = join_all(sources.iter().map(|source| source.search(&query))).await;
+
+        let mut papers = Vec::new();
+        let mut succeeded = false;
+        for (source, result) in sources.iter().zip(results) {
+            match result {
+                Ok(response) => {
+                    succeeded = true;
+                    papers.extend(response.papers);
+                }
+                Err(e) => errors.push(format!("{}: {}", source.id(), e)),
+            }
+        }
+
+        (
+            deduplicate_papers(papers, DuplicateStrategy::First),
+            errors,
+            succeeded,
+        )
+    }
+}
+
+/// Papers that match no earlier paper by identifier or title
+///
+/// Papers repeated within `papers` are reported once.
+pub fn unseen_papers(papers: Vec<Paper>, seen: &[SeenPaper]) -> Vec<Paper> {
+    let mut known: Vec<(Vec<String>, String)> = seen
+        .iter()
+        .map(|s| (s.ids.clone(), title_key(&s.title)))
+        .collect();
+
+    let mut unseen = Vec::new();
+    for paper in papers {
+        let ids = paper_ids(&paper);
+        let title = title_key(&paper.title);
+        let matched = known.iter().any(|(known_ids, known_title)| {
+            ids.iter().any(|id| known_ids.contains(id)) || titles_match(&title, known_title)
+        });
+        if !matched {
+            known.push((ids, title));
+            unseen.push(paper);
+        }
+    }
+    unseen
+}
+
+/// Identifiers a paper can be recognized by across runs
+fn paper_ids(paper: &Paper) -> Vec<String> {
+    let mut ids = vec![format!("{}:{}", paper.source.id(), paper.paper_id)];
+    if let Some(doi) = paper.doi.as_deref().and_then(normalize_doi) {
+        ids.push(format!("doi:{}", doi));
+    }
+    ids
+}
+
+fn title_key(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn titles_match(a: &str, b: &str) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    if a.len() < MIN_FUZZY_TITLE_LEN || b.len() < MIN_FUZZY_TITLE_LEN {
+        return a == b;
+    }
+    jaro_winkler(a, b) >= TITLE_SIMILARITY
+}
+
+/// Link for a paper: its DOI if known, otherwise its landing page
+fn paper_link(paper: &Paper) -> String {
+    match paper.doi.as_deref().and_then(normalize_doi) {
+        Some(doi) => format!("https://doi.org/{}", doi),
+        None => paper.url.clone(),
+    }
+}
+
+/// Stable Atom entry ID for a paper
+fn entry_id(paper: &Paper) -> String {
+    match paper.doi.as_deref().and_then(normalize_doi) {
+        Some(doi) => format!("https://doi.org/{}", doi),
+        None => format!(
+            "urn:research-master:{}:{}",
+            paper.source.id(),
+            paper.paper_id
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PaperBuilder, SourceType};
+
+    fn paper(id: &str, title: &str, source: SourceType) -> Paper {
+        PaperBuilder::new(id, title, format!("https://example.org/{}", id), source).build()
+    }
+
+    fn seen(paper: &Paper) -> SeenPaper {
+        SeenPaper {
+            ids: paper_ids(paper),
+            title: paper.title.clone(),
+            first_seen: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_unseen_papers_by_id_and_title() {
+        let old = PaperBuilder::new(
+            "2401.00001",
+            "Scaling Laws for Neural Language Models",
+            "https://arxiv.org/abs/2401.00001",
+            SourceType::Arxiv,
+        )
+        .doi("10.48550/arXiv.2401.00001")
+        .build();
+        let seen = vec![seen(&old)];
+
+        // Same DOI from another source, near-identical title, and a new paper
+        let by_doi = PaperBuilder::new("S2-1", "Different", "", SourceType::SemanticScholar)
+            .doi("https://doi.org/10.48550/ARXIV.2401.00001")
+            .build();
+        let by_title = paper(
+            "W1",
+            "Scaling laws for neural language models.",
+            SourceType::OpenAlex,
+        );
+        let fresh = paper(
+            "2401.00002",
+            "Emergent Abilities of Agents",
+            SourceType::Arxiv,
+        );
+
+        let unseen = unseen_papers(vec![by_doi, by_title, fresh.clone(), fresh], &seen);
+        assert_eq!(unseen.len(), 1);
+        assert_eq!(unseen[0].paper_id, "2401.00002");
+    }
+
+    #[test]
+    fn test_short_titles_need_exact_match() {
+        let seen = vec![seen(&paper("1", "Attention", SourceType::Arxiv))];
+        let unseen = unseen_papers(vec![paper("2", "Retention", SourceType::Arxiv)], &seen);
+        assert_eq!(unseen.len(), 1);
+    }
+
+    #[test]
+    fn test_report_markdown_and_atom() {
+        let mut new = paper(
+            "2401.00002",
+            "Agents & Tools <in> practice",
+            SourceType::Arxiv,
+        );
+        new.authors = "Jane Smith; John Doe".to_string();
+        let report = WatchReport {
+            run_at: Utc::now(),
+            searches: vec![SearchDigest {
+                name: "agents".to_string(),
+                kind: WatchKind::Search,
+                query: "llm agents".to_string(),
+                first_run: false,
+                total: 5,
+                new_papers: vec![new],
+                errors: vec!["semantic: rate limited".to_string()],
+            }],
+        };
+
+        let mut repeated = report.searches[0].clone();
+        repeated.errors.clear();
+        let mut twice = report.clone();
+        twice.searches.push(repeated);
+        assert_eq!(twice.new_count(), 2);
+        assert_eq!(twice.new_papers().len(), 1);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## agents (1 new)"));
+        assert!(markdown.contains("> Failed: semantic: rate limited"));
+        assert!(markdown.contains("<https://example.org/2401.00002>"));
+
+        let atom = report.to_atom();
+        assert!(atom.contains("<title>Agents &amp; Tools &lt;in&gt; practice</title>"));
+        assert!(atom.contains("<id>urn:research-master:arxiv:2401.00002</id>"));
+        assert!(atom.contains("<author><name>John Doe</name></author>"));
+        assert!(atom.contains("<category term=\"agents\"/>"));
+    }
+
+    #[tokio::test]
+    async fn test_watcher_reports_only_new_papers() {
+        use crate::models::SearchResponse;
+        use crate::sources::mock::MockSource;
+
+        let respond = |source: &MockSource, papers: Vec<Paper>| {
+            source.set_search_response(SearchResponse::new(papers, "Mock Source", "agents"))
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = WatchStore::with_dir(dir.path());
+        store
+            .add(
+                SavedSearch::new("agents", "agents").sources(vec!["mock".into()]),
+                false,
+            )
+            .unwrap();
+
+        let source = Arc::new(MockSource::new());
+        respond(
+            &source,
+            vec![paper("1", "A study of language agents", SourceType::Arxiv)],
+        );
+        let watcher = Watcher::new(vec![source.clone()], store);
+
+        let first = watcher.run(&[], true).await.unwrap();
+        assert!(first.searches[0].first_run);
+        assert_eq!(first.new_count(), 1);
+
+        respond(
+            &source,
+            vec![
+                paper("1", "A study of language agents", SourceType::Arxiv),
+                paper("2", "Tool use by language agents", SourceType::Arxiv),
+            ],
+        );
+        let second = watcher.run(&["agents".to_string()], true).await.unwrap();
+        assert!(!second.searches[0].first_run);
+        assert_eq!(second.searches[0].total, 2);
+        assert_eq!(second.new_count(), 1);
+        assert_eq!(second.searches[0].new_papers[0].paper_id, "2");
+
+        let missing = watcher.run(&["unknown".to_string()], true).await;
+        assert_eq!(missing.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
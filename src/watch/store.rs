@@ -0,0 +1,326 @@
+//! Saved searches, follows and their snapshots, stored in the config directory.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Follow, SavedSearch};
+
+/// Most papers remembered per saved search; the oldest are forgotten first
+const MAX_SNAPSHOT_PAPERS: usize = 5000;
+
+/// A paper returned by an earlier run of a saved search or follow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeenPaper {
+    /// Identifiers the paper was recognized by (`doi:...`, `<source>:<id>`)
+    pub ids: Vec<String>,
+
+    /// Title, for fuzzy matching when identifiers differ between sources
+    pub title: String,
+
+    /// When the paper was first returned
+    pub first_seen: DateTime<Utc>,
+}
+
+/// Papers a saved search or follow has returned so far
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Time of the last run
+    pub updated_at: Option<DateTime<Utc>>,
+
+    /// Papers seen in any earlier run, oldest first
+    pub papers: Vec<SeenPaper>,
+}
+
+impl Snapshot {
+    /// Record papers seen in a run, keeping the snapshot bounded
+    pub fn record(&mut self, papers: Vec<SeenPaper>, at: DateTime<Utc>) {
+        self.updated_at = Some(at);
+        self.papers.extend(papers);
+        if self.papers.len() > MAX_SNAPSHOT_PAPERS {
+            let excess = self.papers.len() - MAX_SNAPSHOT_PAPERS;
+            self.papers.drain(..excess);
+        }
+    }
+}
+
+/// Saved search and follow definitions and their snapshots
+///
+/// Searches are kept in `watch/searches.json`, follows in `watch/follows.json`,
+/// and the snapshot of each in `watch/snapshots/<name>.json` under the
+/// research-master config directory. Names are unique across searches and follows.
+#[derive(Debug, Clone)]
+pub struct WatchStore {
+    /// Directory holding the searches file and snapshots
+    dir: PathBuf,
+}
+
+impl WatchStore {
+    /// Create a store in the default config directory
+    pub fn new() -> Self {
+        let config_dir = dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("~/.config"))
+            .join("research-master");
+        Self::with_dir(config_dir.join("watch"))
+    }
+
+    /// Create a store in the given directory
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory holding the searches file and snapshots
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// All saved searches, in the order they were added
+    pub fn list(&self) -> io::Result<Vec<SavedSearch>> {
+        let path = self.searches_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// A saved search by name
+    pub fn get(&self, name: &str) -> io::Result<Option<SavedSearch>> {
+        Ok(self.list()?.into_iter().find(|s| s.name == name))
+    }
+
+    /// Save a search, replacing one with the same name only if `replace` is set
+    pub fn add(&self, search: SavedSearch, replace: bool) -> io::Result<()> {
+        validate_name(&search.name)?;
+        if self.list_follows()?.iter().any(|f| f.name == search.name) {
+            return Err(already_exists(&search.name));
+        }
+        let mut searches = self.list()?;
+        match searches.iter_mut().find(|s| s.name == search.name) {
+            Some(_) if !replace => return Err(already_exists(&search.name)),
+            Some(existing) => {
+                *existing = search;
+            }
+            None => searches.push(search),
+        }
+        self.write_json(&self.searches_path(), &searches)
+    }
+
+    /// All follows, in the order they were added
+    pub fn list_follows(&self) -> io::Result<Vec<Follow>> {
+        let path = self.follows_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Save a follow, replacing one with the same name only if `replace` is set
+    pub fn add_follow(&self, follow: Follow, replace: bool) -> io::Result<()> {
+        validate_name(&follow.name)?;
+        if self.list()?.iter().any(|s| s.name == follow.name) {
+            return Err(already_exists(&follow.name));
+        }
+        let mut follows = self.list_follows()?;
+        match follows.iter_mut().find(|f| f.name == follow.name) {
+            Some(_) if !replace => return Err(already_exists(&follow.name)),
+            Some(existing) => {
+                *existing = follow;
+            }
+            None => follows.push(follow),
+        }
+        self.write_json(&self.follows_path(), &follows)
+    }
+
+    /// Remove a saved search or follow and its snapshot
+    pub fn remove(&self, name: &str) -> io::Result<()> {
+        let mut searches = self.list()?;
+        let mut follows = self.list_follows()?;
+        let before = searches.len() + follows.len();
+        searches.retain(|s| s.name != name);
+        follows.retain(|f| f.name != name);
+        if searches.len() + follows.len() == before {
+            return Err(not_found(name));
+        }
+        self.write_json(&self.searches_path(), &searches)?;
+        self.write_json(&self.follows_path(), &follows)?;
+
+        let snapshot = self.snapshot_path(name);
+        if snapshot.exists() {
+            fs::remove_file(snapshot)?;
+        }
+        Ok(())
+    }
+
+    /// The snapshot of a saved search, or `None` if it has never run
+    pub fn load_snapshot(&self, name: &str) -> io::Result<Option<Snapshot>> {
+        let path = self.snapshot_path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Store the snapshot of a saved search
+    pub fn save_snapshot(&self, name: &str, snapshot: &Snapshot) -> io::Result<()> {
+        let path = self.snapshot_path(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(snapshot)?)
+    }
+
+    fn write_json<T: Serialize>(&self, path: &Path, items: &[T]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(path, serde_json::to_string_pretty(items)?)
+    }
+
+    fn searches_path(&self) -> PathBuf {
+        self.dir.join("searches.json")
+    }
+
+    fn follows_path(&self) -> PathBuf {
+        self.dir.join("follows.json")
+    }
+
+    fn snapshot_path(&self, name: &str) -> PathBuf {
+        self.dir.join("snapshots").join(format!("{}.json", name))
+    }
+}
+
+impl Default for WatchStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Names become snapshot file names, so keep them to a safe character set
+fn validate_name(name: &str) -> io::Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid saved search name '{}': use up to 64 letters, digits, '-', '_' or '.'",
+                name
+            ),
+        ))
+    }
+}
+
+pub(crate) fn not_found(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No saved search or follow named '{}'", name),
+    )
+}
+
+fn already_exists(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("A saved search or follow named '{}' already exists", name),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_add_list_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = WatchStore::with_dir(dir.path());
+        assert!(store.list().unwrap().is_empty());
+
+        store
+            .add(SavedSearch::new("llm-agents", "llm agents"), false)
+            .unwrap();
+        let err = store
+            .add(SavedSearch::new("llm-agents", "other"), false)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        store
+            .add(SavedSearch::new("llm-agents", "tool use"), true)
+            .unwrap();
+        assert_eq!(store.get("llm-agents").unwrap().unwrap().query, "tool use");
+
+        let mut snapshot = Snapshot::default();
+        snapshot.record(Vec::new(), Utc::now());
+        store.save_snapshot("llm-agents", &snapshot).unwrap();
+        assert!(store.load_snapshot("llm-agents").unwrap().is_some());
+
+        store.remove("llm-agents").unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.load_snapshot("llm-agents").unwrap().is_none());
+        assert_eq!(
+            store.remove("llm-agents").unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn test_store_rejects_unsafe_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = WatchStore::with_dir(dir.path());
+        for name in ["", "../x", "a/b", ".hidden", "with space"] {
+            assert!(store.add(SavedSearch::new(name, "q"), false).is_err());
+        }
+    }
+
+    #[test]
+    fn test_follow_names_shared_with_searches() {
+        use crate::models::{PaperBuilder, SourceType};
+        use crate::watch::FollowTarget;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = WatchStore::with_dir(dir.path());
+        store
+            .add(SavedSearch::new("attention", "attention"), false)
+            .unwrap();
+
+        let paper = PaperBuilder::new(
+            "1706.03762",
+            "Attention Is All You Need",
+            "",
+            SourceType::Arxiv,
+        )
+        .build();
+        let follow = Follow {
+            name: "attention".to_string(),
+            label: paper.title.clone(),
+            target: FollowTarget::Paper {
+                id: "1706.03762".to_string(),
+                paper: Box::new(paper),
+            },
+            max_results: 50,
+            created_at: Utc::now(),
+        };
+        let err = store.add_follow(follow.clone(), true).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+        let follow = Follow {
+            name: "transformer".to_string(),
+            ..follow
+        };
+        store.add_follow(follow, false).unwrap();
+        assert_eq!(store.list_follows().unwrap().len(), 1);
+
+        store.remove("transformer").unwrap();
+        assert!(store.list_follows().unwrap().is_empty());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+}
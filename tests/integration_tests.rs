@@ -124,6 +124,9 @@ fn expected_source_count() -> usize {
     if cfg!(feature = "source-springer") {
         count += 1;
     }
+    if cfg!(feature = "source-orcid") {
+        count += 1;
+    }
     if cfg!(feature = "source-google_scholar") {
         count += 1;
     }
@@ -184,6 +187,7 @@ async fn test_all_sources_registered() {
         assert!(source_ids.contains(&"osf"));
         assert!(source_ids.contains(&"base"));
         assert!(source_ids.contains(&"springer"));
+        assert!(source_ids.contains(&"orcid"));
         if cfg!(feature = "source-google_scholar") {
             assert!(source_ids.contains(&"google_scholar"));
         } else {
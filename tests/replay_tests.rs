@@ -0,0 +1,54 @@
+//! End-to-end source tests against recorded HTTP responses
+//!
+//! Requests are answered from `tests/fixtures/http`, so these tests run
+//! without the network. To add a recording, run a command with
+//! `--http-record tests/fixtures/http` and commit the new files.
+
+use research_master::models::SearchQuery;
+use research_master::sources::SourceRegistry;
+use research_master::utils::HTTP_REPLAY_ENV_VAR;
+
+/// Registry whose sources replay the recorded fixtures
+fn replay_registry() -> SourceRegistry {
+    std::env::set_var(
+        HTTP_REPLAY_ENV_VAR,
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/http"),
+    );
+    SourceRegistry::new()
+}
+
+#[tokio::test]
+async fn test_arxiv_search_replayed() {
+    let registry = replay_registry();
+    let arxiv = registry.get("arxiv").expect("arxiv source");
+
+    let response = arxiv
+        .search(&SearchQuery::new("graph neural networks").max_results(2))
+        .await
+        .unwrap();
+
+    assert_eq!(response.papers.len(), 2);
+    let paper = &response.papers[0];
+    assert_eq!(
+        paper.title,
+        "Graph Neural Networks: A Review of Methods and Applications"
+    );
+    assert_eq!(paper.authors, "Jie Zhou; Ganqu Cui");
+    assert_eq!(paper.paper_id, "1812.08434");
+    assert_eq!(
+        response.papers[1].title,
+        "A Comprehensive Survey on Graph Neural Networks"
+    );
+}
+
+#[tokio::test]
+async fn test_unrecorded_request_fails_without_network() {
+    let registry = replay_registry();
+    let arxiv = registry.get("arxiv").expect("arxiv source");
+
+    let result = arxiv
+        .search(&SearchQuery::new("no recording for this query").max_results(2))
+        .await;
+
+    assert!(result.is_err());
+}